@@ -1,10 +1,17 @@
 use serde_json::Value;
 use std::path::PathBuf;
 use std::sync::{OnceLock, RwLock};
+#[cfg(feature = "gui")]
 use tauri_plugin_store::StoreExt;
 
 use crate::error::AppError;
 
+/// Store 文件名（位于 Tauri 的应用数据目录）
+const STORE_FILE: &str = "app_paths.json";
+
+/// Tauri 应用标识，决定应用数据目录名（与 tauri.conf.json 的 `identifier` 一致）
+const APP_IDENTIFIER: &str = "com.ccswitch.desktop";
+
 /// Store 中的键名
 const STORE_KEY_APP_CONFIG_DIR: &str = "app_config_dir_override";
 
@@ -26,8 +33,9 @@ pub fn get_app_config_dir_override() -> Option<PathBuf> {
     override_cache().read().ok()?.clone()
 }
 
+#[cfg(feature = "gui")]
 fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
-    let store = match app.store_builder("app_paths.json").build() {
+    let store = match app.store_builder(STORE_FILE).build() {
         Ok(store) => store,
         Err(e) => {
             log::warn!("无法创建 Store: {e}");
@@ -35,7 +43,11 @@ fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
         }
     };
 
-    match store.get(STORE_KEY_APP_CONFIG_DIR) {
+    parse_override(store.get(STORE_KEY_APP_CONFIG_DIR))
+}
+
+fn parse_override(value: Option<Value>) -> Option<PathBuf> {
+    match value {
         Some(Value::String(path_str)) => {
             let path_str = path_str.trim();
             if path_str.is_empty() {
@@ -64,19 +76,33 @@ fn read_override_from_store(app: &tauri::AppHandle) -> Option<PathBuf> {
 }
 
 /// 从 Store 刷新 app_config_dir 覆盖值并更新缓存
+#[cfg(feature = "gui")]
 pub fn refresh_app_config_dir_override(app: &tauri::AppHandle) -> Option<PathBuf> {
     let value = read_override_from_store(app);
     update_cached_override(value.clone());
     value
 }
 
+/// 不依赖 AppHandle（如命令行工具）时，直接读取 Store 文件刷新覆盖路径
+pub fn refresh_app_config_dir_override_from_disk() -> Option<PathBuf> {
+    let store_path = dirs::data_dir()?.join(APP_IDENTIFIER).join(STORE_FILE);
+    let value = std::fs::read_to_string(&store_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|store| store.get(STORE_KEY_APP_CONFIG_DIR).cloned());
+    let value = parse_override(value);
+    update_cached_override(value.clone());
+    value
+}
+
 /// 写入 app_config_dir 到 Tauri Store
+#[cfg(feature = "gui")]
 pub fn set_app_config_dir_to_store(
     app: &tauri::AppHandle,
     path: Option<&str>,
 ) -> Result<(), AppError> {
     let store = app
-        .store_builder("app_paths.json")
+        .store_builder(STORE_FILE)
         .build()
         .map_err(|e| AppError::Message(format!("创建 Store 失败: {e}")))?;
 
@@ -125,6 +151,7 @@ fn resolve_path(raw: &str) -> PathBuf {
 }
 
 /// 从旧的 settings.json 迁移 app_config_dir 到 Store
+#[cfg(feature = "gui")]
 pub fn migrate_app_config_dir_from_settings(app: &tauri::AppHandle) -> Result<(), AppError> {
     // app_config_dir 已从 settings.json 移除，此函数保留但不再执行迁移
     // 如果用户在旧版本设置过 app_config_dir，需要在 Store 中手动配置
@@ -0,0 +1,344 @@
+//! live 配置写入审计日志
+//!
+//! 每次写入或删除各应用的 live 配置文件（`~/.claude`、`~/.codex`、`~/.gemini`、OpenCode
+//! 配置目录与 `~/.claude.json`，以及软链接模式下的 `~/.cc-switch/live/`）都会向
+//! `~/.cc-switch/audit.jsonl` 追加一条记录：路径、写入前后的 SHA-256、触发写入的供应商与时间。
+//!
+//! 每条记录包含上一条记录的哈希（`prevHash`）与自身哈希（`entryHash`），构成哈希链；
+//! 任何中间记录被修改或删除都会在 [`read_audit_log`] 校验时暴露出来。
+
+use std::cell::RefCell;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::get_app_config_dir;
+use crate::crypto::sha256_hex;
+use crate::error::AppError;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// 哈希链起点
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 审计动作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Write,
+    Delete,
+    /// 供应商 API Key 轮换（`path` 为 `provider://<app>/<id>`，哈希为新旧密钥的 SHA-256）
+    Rotate,
+}
+
+/// 单条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    pub action: AuditAction,
+    pub path: String,
+    pub hash_before: Option<String>,
+    pub hash_after: Option<String>,
+    pub app: Option<String>,
+    pub provider_id: Option<String>,
+    pub prev_hash: String,
+    pub entry_hash: String,
+}
+
+/// 审计日志查询结果
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLog {
+    pub path: String,
+    /// 最新的记录在前
+    pub entries: Vec<AuditEntry>,
+    pub total: usize,
+    /// 哈希链是否完整
+    pub chain_valid: bool,
+    /// 第一条校验失败的记录序号（行号从 1 开始）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broken_at: Option<u64>,
+}
+
+/// 最近一条记录的 (日志路径, seq, entry_hash)，首次写入时从文件末尾加载
+static CHAIN_HEAD: Mutex<Option<(PathBuf, u64, String)>> = Mutex::new(None);
+
+thread_local! {
+    static CONTEXT: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
+}
+
+/// 审计上下文守卫：在作用域内发生的写入会记录触发它的供应商
+pub struct AuditScope {
+    previous: Option<(String, String)>,
+}
+
+impl Drop for AuditScope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CONTEXT.with(|ctx| *ctx.borrow_mut() = previous);
+    }
+}
+
+/// 标记当前线程后续的写入由指定供应商触发
+pub fn scope(app: &str, provider_id: &str) -> AuditScope {
+    let previous = CONTEXT.with(|ctx| {
+        ctx.borrow_mut()
+            .replace((app.to_string(), provider_id.to_string()))
+    });
+    AuditScope { previous }
+}
+
+pub fn audit_log_path() -> PathBuf {
+    get_app_config_dir().join(AUDIT_FILE)
+}
+
+/// 仅审计各应用的 live 配置文件（以及软链接模式下的 live 存储目录）
+fn is_audited(path: &Path) -> bool {
+    if path == crate::config::get_claude_mcp_path() {
+        return true;
+    }
+    [
+        crate::config::get_claude_config_dir(),
+        crate::codex_config::get_codex_config_dir(),
+        crate::gemini_config::get_gemini_dir(),
+        crate::opencode_config::get_opencode_dir(),
+        get_app_config_dir().join("live"),
+    ]
+    .iter()
+    .any(|dir| path.starts_with(dir))
+}
+
+/// 读取文件当前内容的哈希（不存在时为 None）
+pub fn file_hash(path: &Path) -> Option<String> {
+    if !is_audited(path) {
+        return None;
+    }
+    fs::read(path).ok().map(|data| sha256_hex(&data))
+}
+
+fn compute_entry_hash(entry: &AuditEntry) -> String {
+    let payload = serde_json::json!({
+        "seq": entry.seq,
+        "timestamp": entry.timestamp,
+        "action": entry.action,
+        "path": entry.path,
+        "hashBefore": entry.hash_before,
+        "hashAfter": entry.hash_after,
+        "app": entry.app,
+        "providerId": entry.provider_id,
+        "prevHash": entry.prev_hash,
+    });
+    sha256_hex(payload.to_string().as_bytes())
+}
+
+fn load_chain_head(path: &Path) -> (u64, String) {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| {
+            content
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty())
+                .and_then(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        })
+        .map(|entry| (entry.seq, entry.entry_hash))
+        .unwrap_or((0, GENESIS_HASH.to_string()))
+}
+
+fn append(
+    log_path: &Path,
+    action: AuditAction,
+    path: &Path,
+    hash_before: Option<String>,
+    hash_after: Option<String>,
+) -> Result<(), AppError> {
+    let mut head = CHAIN_HEAD.lock()?;
+    let (last_seq, prev_hash) = match head.as_ref() {
+        Some((cached, seq, hash)) if cached == log_path => (*seq, hash.clone()),
+        _ => load_chain_head(log_path),
+    };
+    let (app, provider_id) = CONTEXT
+        .with(|ctx| ctx.borrow().clone())
+        .map_or((None, None), |(app, id)| (Some(app), Some(id)));
+
+    let mut entry = AuditEntry {
+        seq: last_seq + 1,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        action,
+        path: path.display().to_string(),
+        hash_before,
+        hash_after,
+        app,
+        provider_id,
+        prev_hash,
+        entry_hash: String::new(),
+    };
+    entry.entry_hash = compute_entry_hash(&entry);
+
+    if let Some(parent) = log_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    let line = serde_json::to_string(&entry).map_err(|e| AppError::JsonSerialize { source: e })?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path)
+        .map_err(|e| AppError::io(log_path, e))?;
+    writeln!(file, "{line}").map_err(|e| AppError::io(log_path, e))?;
+
+    *head = Some((log_path.to_path_buf(), entry.seq, entry.entry_hash));
+    Ok(())
+}
+
+/// 记录一次文件写入（`hash_before` 由调用方在写入前通过 [`file_hash`] 取得）
+///
+/// 审计失败只记录日志，不影响配置写入本身。
+pub fn record_write(path: &Path, hash_before: Option<String>, data: &[u8]) {
+    if !is_audited(path) {
+        return;
+    }
+    if let Err(e) = append(
+        &audit_log_path(),
+        AuditAction::Write,
+        path,
+        hash_before,
+        Some(sha256_hex(data)),
+    ) {
+        log::warn!("写入审计日志失败: {e}");
+    }
+}
+
+/// 记录一次文件删除
+pub fn record_delete(path: &Path, hash_before: Option<String>) {
+    if !is_audited(path) {
+        return;
+    }
+    if let Err(e) = append(
+        &audit_log_path(),
+        AuditAction::Delete,
+        path,
+        hash_before,
+        None,
+    ) {
+        log::warn!("写入审计日志失败: {e}");
+    }
+}
+
+/// 记录一次 API Key 轮换（只记录新旧密钥的哈希，不记录原文）
+#[cfg(feature = "gui")]
+pub fn record_key_rotation(app: &str, provider_id: &str, old_key: Option<&str>, new_key: &str) {
+    let _scope = scope(app, provider_id);
+    let subject = PathBuf::from(format!("provider://{app}/{provider_id}"));
+    if let Err(e) = append(
+        &audit_log_path(),
+        AuditAction::Rotate,
+        &subject,
+        old_key.map(|key| sha256_hex(key.as_bytes())),
+        Some(sha256_hex(new_key.as_bytes())),
+    ) {
+        log::warn!("写入审计日志失败: {e}");
+    }
+}
+
+/// 读取并校验审计日志，返回最新的 `limit` 条记录
+#[cfg(feature = "gui")]
+pub fn read_audit_log(limit: usize) -> Result<AuditLog, AppError> {
+    let log_path = audit_log_path();
+    let content = match fs::read_to_string(&log_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::io(&log_path, e)),
+    };
+    let (entries, broken_at) = verify_chain(&content);
+
+    let total = entries.len();
+    let entries = entries.into_iter().rev().take(limit).collect();
+    Ok(AuditLog {
+        path: log_path.display().to_string(),
+        entries,
+        total,
+        chain_valid: broken_at.is_none(),
+        broken_at,
+    })
+}
+
+/// 逐条校验哈希链，返回解析出的记录与第一处断裂的行号
+#[cfg(any(feature = "gui", test))]
+fn verify_chain(content: &str) -> (Vec<AuditEntry>, Option<u64>) {
+    let mut entries = Vec::new();
+    let mut broken_at = None;
+    let mut prev_hash = GENESIS_HASH.to_string();
+
+    for (index, line) in content.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+        let line_no = index as u64 + 1;
+        match serde_json::from_str::<AuditEntry>(line) {
+            Ok(entry) => {
+                let intact = entry.prev_hash == prev_hash
+                    && entry.seq == line_no
+                    && compute_entry_hash(&entry) == entry.entry_hash;
+                if !intact && broken_at.is_none() {
+                    broken_at = Some(line_no);
+                }
+                prev_hash = entry.entry_hash.clone();
+                entries.push(entry);
+            }
+            Err(_) => {
+                broken_at.get_or_insert(line_no);
+            }
+        }
+    }
+
+    (entries, broken_at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_chain_detects_tampering() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let log_path = dir.path().join(AUDIT_FILE);
+        let target = dir.path().join("settings.json");
+
+        {
+            let _scope = scope("claude", "p1");
+            append(
+                &log_path,
+                AuditAction::Write,
+                &target,
+                None,
+                Some(sha256_hex(b"a")),
+            )
+            .unwrap();
+        }
+        append(
+            &log_path,
+            AuditAction::Delete,
+            &target,
+            Some(sha256_hex(b"a")),
+            None,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        let (entries, broken_at) = verify_chain(&content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(broken_at, None);
+        assert_eq!(entries[0].provider_id.as_deref(), Some("p1"));
+        assert_eq!(entries[1].provider_id, None);
+
+        let tampered = content.replacen("\"p1\"", "\"p2\"", 1);
+        assert_eq!(verify_chain(&tampered).1, Some(1));
+
+        let truncated: String = content.lines().skip(1).map(|l| format!("{l}\n")).collect();
+        assert_eq!(verify_chain(&truncated).1, Some(1));
+    }
+}
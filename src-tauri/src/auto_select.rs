@@ -0,0 +1,127 @@
+//! 按速度自动选择供应商
+//!
+//! 对设置 `autoSelect` 中启用的应用，后台按配置的间隔测试候选供应商的速度（见
+//! [`crate::services::benchmark`]），更快且健康的供应商超过阈值时自动切换并发送通知。
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::app_config::AppType;
+use crate::notifications::Notice;
+use crate::services::benchmark;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动后首次检查的延迟，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(2 * 60);
+
+/// 最短测速间隔（分钟），避免频繁请求供应商
+const MIN_INTERVAL_MINUTES: u32 = 5;
+
+/// 启动后台调度任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + STARTUP_DELAY;
+        let mut ticker = tokio::time::interval_at(start, CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // 应用 -> 上次测速时间
+        let mut last_runs: HashMap<String, tokio::time::Instant> = HashMap::new();
+
+        loop {
+            ticker.tick().await;
+
+            let configs = crate::settings::get_settings().auto_select;
+            for (app_name, config) in configs {
+                if !config.enabled {
+                    continue;
+                }
+                let Ok(app_type) = AppType::from_str(&app_name) else {
+                    continue;
+                };
+                if app_type == AppType::OpenCode {
+                    continue;
+                }
+
+                let interval = Duration::from_secs(
+                    u64::from(config.interval_minutes.max(MIN_INTERVAL_MINUTES)) * 60,
+                );
+                let now = tokio::time::Instant::now();
+                if last_runs
+                    .get(&app_name)
+                    .is_some_and(|last| now.duration_since(*last) < interval)
+                {
+                    continue;
+                }
+                last_runs.insert(app_name, now);
+
+                run_once(&app, app_type, &config).await;
+            }
+        }
+    });
+}
+
+/// 测试一个应用的候选供应商，必要时切换
+async fn run_once(app: &AppHandle, app_type: AppType, config: &crate::settings::AutoSelectConfig) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if state.db.is_locked() {
+        return;
+    }
+    let db = state.db.clone();
+
+    let ranked = match benchmark::benchmark_candidates(
+        &db,
+        &app_type,
+        &config.candidates,
+        benchmark::DEFAULT_SAMPLES,
+    )
+    .await
+    {
+        Ok(ranked) => ranked,
+        Err(e) => {
+            log::warn!("自动选择测速失败 ({}): {e}", app_type.as_str());
+            return;
+        }
+    };
+
+    let current = match crate::settings::get_effective_current_provider(&db, &app_type) {
+        Ok(current) => current.unwrap_or_default(),
+        Err(e) => {
+            log::warn!("自动选择读取当前供应商失败 ({}): {e}", app_type.as_str());
+            return;
+        }
+    };
+    let Some(target) = benchmark::pick_faster(&ranked, &current, config.margin_percent) else {
+        log::debug!("自动选择: {} 保持当前供应商 {current}", app_type.as_str());
+        return;
+    };
+
+    let provider_id = target.provider_id.clone();
+    let ttfb_ms = target.ttfb_ms.unwrap_or_default();
+    log::info!(
+        "自动选择: {} 从 {current} 切换到 {provider_id}（首字节 {ttfb_ms}ms）",
+        app_type.as_str()
+    );
+    let app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        crate::tray::switch_provider_notifying(&app, app_type, provider_id, |app_type, provider| {
+            Notice::AutoSelected {
+                app_type,
+                provider,
+                ttfb_ms,
+            }
+        })
+    })
+    .await;
+    match result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::error!("自动选择切换失败: {e}"),
+        Err(e) => log::error!("自动选择切换任务异常: {e}"),
+    }
+}
@@ -0,0 +1,150 @@
+//! 辅助窗口
+//!
+//! MCP 管理、请求日志与用量统计可以在独立窗口中打开，与主窗口并排使用。每种窗口只
+//! 保留一个实例，再次打开时显示并聚焦已有窗口。窗口关闭时把位置与大小保存到
+//! `~/.cc-switch/window-state.json`，下次打开时恢复。
+//!
+//! 辅助窗口加载与主窗口相同的页面，前端根据 URL 参数 `?window=<label>` 渲染对应面板。
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Window};
+
+use crate::error::AppError;
+use crate::i18n::{self, Language};
+
+/// 窗口状态文件名
+const STATE_FILE: &str = "window-state.json";
+
+/// 辅助窗口的最小尺寸
+const MIN_SIZE: (f64, f64) = (600.0, 400.0);
+
+/// 辅助窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuxWindow {
+    Mcp,
+    Logs,
+    Usage,
+}
+
+impl AuxWindow {
+    const ALL: [AuxWindow; 3] = [AuxWindow::Mcp, AuxWindow::Logs, AuxWindow::Usage];
+
+    /// 窗口标签，同时作为前端的 `window` 参数
+    pub fn label(self) -> &'static str {
+        match self {
+            AuxWindow::Mcp => "mcp",
+            AuxWindow::Logs => "logs",
+            AuxWindow::Usage => "usage",
+        }
+    }
+
+    pub fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|window| window.label() == label)
+    }
+
+    fn title(self, language: Language) -> &'static str {
+        let key = match self {
+            AuxWindow::Mcp => "window.mcp",
+            AuxWindow::Logs => "window.logs",
+            AuxWindow::Usage => "window.usage",
+        };
+        i18n::text(language, key)
+    }
+
+    fn default_size(self) -> (f64, f64) {
+        match self {
+            AuxWindow::Mcp => (900.0, 650.0),
+            AuxWindow::Logs => (1000.0, 600.0),
+            AuxWindow::Usage => (1000.0, 700.0),
+        }
+    }
+}
+
+/// 窗口位置与大小（逻辑像素）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+fn state_path() -> PathBuf {
+    crate::config::get_app_config_dir().join(STATE_FILE)
+}
+
+/// 各窗口保存的位置与大小（按窗口标签）
+fn load_states() -> BTreeMap<String, WindowGeometry> {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(label: &str, geometry: WindowGeometry) -> Result<(), AppError> {
+    let mut states = load_states();
+    states.insert(label.to_string(), geometry);
+    let json =
+        serde_json::to_vec_pretty(&states).map_err(|source| AppError::JsonSerialize { source })?;
+    crate::config::atomic_write(&state_path(), &json)
+}
+
+fn geometry(window: &Window) -> Option<WindowGeometry> {
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// 保存辅助窗口的位置与大小（窗口关闭时调用；最小化时的尺寸没有意义，跳过）
+pub fn remember_geometry(window: &Window) {
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let Some(geometry) = geometry(window) else {
+        return;
+    };
+    if let Err(e) = save_state(window.label(), geometry) {
+        log::warn!("保存窗口状态失败: {e}");
+    }
+}
+
+/// 打开辅助窗口；已打开时显示并聚焦
+pub fn open(app: &AppHandle, window: AuxWindow) -> Result<(), AppError> {
+    let label = window.label();
+    if let Some(existing) = app.get_webview_window(label) {
+        let _ = existing.unminimize();
+        let _ = existing.show();
+        let _ = existing.set_focus();
+        return Ok(());
+    }
+
+    let language = Language::current();
+    let url = WebviewUrl::App(format!("index.html?window={label}").into());
+    let mut builder = WebviewWindowBuilder::new(app, label, url)
+        .title(window.title(language))
+        .min_inner_size(MIN_SIZE.0, MIN_SIZE.1);
+    builder = match load_states().get(label) {
+        Some(state) => builder
+            .position(state.x, state.y)
+            .inner_size(state.width, state.height),
+        None => {
+            let (width, height) = window.default_size();
+            builder.inner_size(width, height).center()
+        }
+    };
+    builder
+        .build()
+        .map_err(|e| AppError::Message(format!("打开窗口 {label} 失败: {e}")))?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! 定时自动备份调度
+//!
+//! 后台定期检查距上次备份是否已超过配置的间隔，超过则创建备份并按保留策略清理。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::notifications::{self, Notice};
+use crate::services::backup::BackupService;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 启动后首次检查的延迟，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+
+/// 启动后台调度任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + STARTUP_DELAY;
+        let mut ticker = tokio::time::interval_at(start, CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            let config = crate::settings::get_settings().auto_backup;
+            if !config.enabled {
+                continue;
+            }
+
+            let interval_ms = i64::from(config.interval_hours.max(1)) * 3600 * 1000;
+            let now = chrono::Utc::now().timestamp_millis();
+            if let Some(last) = BackupService::latest_backup_time() {
+                if now - last < interval_ms {
+                    continue;
+                }
+            }
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            // 主密码锁定期间不创建备份，解锁后再补上
+            if state.db.is_locked() {
+                continue;
+            }
+            let db = state.db.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                let entry = BackupService::create_backup(&db)?;
+                let removed = BackupService::prune_backups(config.keep_daily, config.keep_weekly)?;
+                Ok::<_, crate::error::AppError>((entry, removed))
+            })
+            .await;
+
+            match result {
+                Ok(Ok((entry, removed))) => {
+                    log::info!("自动备份完成: {}（清理 {removed} 个旧备份）", entry.id);
+                    notifications::notify(&app, Notice::BackupCreated { id: &entry.id });
+                }
+                Ok(Err(e)) => {
+                    log::error!("自动备份失败: {e}");
+                    notifications::notify(
+                        &app,
+                        Notice::BackupFailed {
+                            error: &e.to_string(),
+                        },
+                    );
+                }
+                Err(e) => log::error!("自动备份任务异常: {e}"),
+            }
+        }
+    });
+}
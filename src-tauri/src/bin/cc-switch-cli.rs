@@ -0,0 +1,5 @@
+//! `cc-switch-cli`：无需 GUI 即可查看与切换供应商（见 `cc_switch_lib::run_cli`）
+
+fn main() {
+    std::process::exit(cc_switch_lib::run_cli(std::env::args().skip(1)));
+}
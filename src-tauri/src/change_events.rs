@@ -0,0 +1,116 @@
+//! 数据变更事件
+//!
+//! 数据库与设置层在写入成功（事务提交）后发出细粒度的 Tauri 事件，前端与托盘可以只刷新
+//! 受影响的部分，而不必在每次操作后重新获取全部数据；同步合并、自动化规则等后台写入也能
+//! 及时反映到界面上。
+//!
+//! | 事件               | 负载                      |
+//! | ------------------ | ------------------------- |
+//! | `provider:added`   | `{ appType, providerId }` |
+//! | `provider:updated` | `{ appType, providerId }` |
+//! | `provider:deleted` | `{ appType, providerId }` |
+//! | `provider:switched`| `{ appType, providerId }` |
+//! | `mcp:updated`      | `{ id }`                  |
+//! | `mcp:deleted`      | `{ id }`                  |
+//! | `settings:changed` | `null`                    |
+//!
+//! 事件只在界面启动并注册了 AppHandle 后发出；命令行与测试中直接丢弃。
+
+use serde::Serialize;
+
+#[cfg(feature = "gui")]
+static APP_HANDLE: std::sync::RwLock<Option<tauri::AppHandle>> = std::sync::RwLock::new(None);
+
+/// 单个变更事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged, rename_all_fields = "camelCase")]
+pub enum ChangeEvent {
+    ProviderAdded {
+        app_type: String,
+        provider_id: String,
+    },
+    ProviderUpdated {
+        app_type: String,
+        provider_id: String,
+    },
+    ProviderDeleted {
+        app_type: String,
+        provider_id: String,
+    },
+    ProviderSwitched {
+        app_type: String,
+        provider_id: String,
+    },
+    McpUpdated {
+        id: String,
+    },
+    McpDeleted {
+        id: String,
+    },
+    SettingsChanged,
+}
+
+impl ChangeEvent {
+    /// Tauri 事件名
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ProviderAdded { .. } => "provider:added",
+            Self::ProviderUpdated { .. } => "provider:updated",
+            Self::ProviderDeleted { .. } => "provider:deleted",
+            Self::ProviderSwitched { .. } => "provider:switched",
+            Self::McpUpdated { .. } => "mcp:updated",
+            Self::McpDeleted { .. } => "mcp:deleted",
+            Self::SettingsChanged => "settings:changed",
+        }
+    }
+}
+
+/// 注册发出事件使用的 AppHandle（界面启动时调用一次）
+#[cfg(feature = "gui")]
+pub fn set_app_handle(app: tauri::AppHandle) {
+    match APP_HANDLE.write() {
+        Ok(mut guard) => *guard = Some(app),
+        Err(e) => log::error!("注册变更事件 AppHandle 失败: {e}"),
+    }
+}
+
+/// 发出变更事件（未注册 AppHandle 时忽略）
+pub fn emit(event: ChangeEvent) {
+    #[cfg(not(feature = "gui"))]
+    let _ = event;
+    #[cfg(feature = "gui")]
+    {
+        use tauri::Emitter;
+
+        let Ok(guard) = APP_HANDLE.read() else {
+            return;
+        };
+        if let Some(app) = guard.as_ref() {
+            if let Err(e) = app.emit(event.name(), &event) {
+                log::error!("发射变更事件 {} 失败: {e}", event.name());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payloads_use_camel_case_and_settings_carry_no_data() {
+        let event = ChangeEvent::ProviderSwitched {
+            app_type: "claude".to_string(),
+            provider_id: "p1".to_string(),
+        };
+        assert_eq!(event.name(), "provider:switched");
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "appType": "claude", "providerId": "p1" })
+        );
+        assert_eq!(
+            serde_json::to_value(ChangeEvent::SettingsChanged).unwrap(),
+            serde_json::Value::Null
+        );
+    }
+}
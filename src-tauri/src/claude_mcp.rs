@@ -1,5 +1,7 @@
+#[cfg(feature = "gui")]
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+#[cfg(feature = "gui")]
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -89,6 +91,7 @@ fn is_wsl_path(_path: &Path) -> bool {
     false
 }
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpStatus {
@@ -161,6 +164,7 @@ fn write_json_value(path: &Path, value: &Value) -> Result<(), AppError> {
     atomic_write(path, json.as_bytes())
 }
 
+#[cfg(feature = "gui")]
 pub fn get_mcp_status() -> Result<McpStatus, AppError> {
     let path = user_config_path();
     let (exists, count) = if path.exists() {
@@ -189,6 +193,7 @@ pub fn read_mcp_json() -> Result<Option<String>, AppError> {
 
 /// 在 ~/.claude.json 根对象写入 hasCompletedOnboarding=true（用于跳过 Claude Code 初次安装确认）
 /// 仅增量写入该字段，其他字段保持不变
+#[cfg(feature = "gui")]
 pub fn set_has_completed_onboarding() -> Result<bool, AppError> {
     let path = user_config_path();
     let mut root = if path.exists() {
@@ -216,6 +221,7 @@ pub fn set_has_completed_onboarding() -> Result<bool, AppError> {
 
 /// 删除 ~/.claude.json 根对象的 hasCompletedOnboarding 字段（恢复 Claude Code 初次安装确认）
 /// 仅增量删除该字段，其他字段保持不变
+#[cfg(feature = "gui")]
 pub fn clear_has_completed_onboarding() -> Result<bool, AppError> {
     let path = user_config_path();
     if !path.exists() {
@@ -236,6 +242,7 @@ pub fn clear_has_completed_onboarding() -> Result<bool, AppError> {
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub fn upsert_mcp_server(id: &str, spec: Value) -> Result<bool, AppError> {
     if id.trim().is_empty() {
         return Err(AppError::InvalidInput("MCP 服务器 ID 不能为空".into()));
@@ -328,6 +335,7 @@ pub fn delete_mcp_server(id: &str) -> Result<bool, AppError> {
     Ok(true)
 }
 
+#[cfg(feature = "gui")]
 pub fn validate_command_in_path(cmd: &str) -> Result<bool, AppError> {
     if cmd.trim().is_empty() {
         return Ok(false);
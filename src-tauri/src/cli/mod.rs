@@ -0,0 +1,916 @@
+//! 命令行工具 `cc-switch-cli`
+//!
+//! 与 GUI 共用同一数据库与设置，便于在脚本或 SSH 会话中查看和切换供应商：
+//! - `cc-switch-cli list claude`
+//! - `cc-switch-cli use codex my-relay`
+//! - `cc-switch-cli current --json`
+//! - `cc-switch-cli status --json`：供脚本使用的完整状态（字段见 `services::status`）
+//! - `eval "$(cc-switch-cli env claude)"`：导出当前供应商的密钥环境变量
+//! - `cc-switch-cli launch claude my-relay -- --resume`：以指定供应商启动 CLI，仅该进程生效
+//! - `eval "$(cc-switch-cli env claude my-relay --session)"`：仅在当前终端会话使用该供应商
+//! - `cat provider.json | cc-switch-cli add claude -`、`cc-switch-cli add codex --from-env`：
+//!   在部署脚本中非交互地添加供应商，与 GUI 添加走同一校验
+//!
+//! Shell 补全与 `ccs` 快捷函数见 [`shell`]。
+//!
+//! 退出码是稳定的约定（见 `exit_code` 与用法说明中的 Exit codes），脚本可以据此分支；
+//! `--quiet` 只关闭正常输出，错误仍写到 stderr。
+//!
+//! GUI 运行中时不会立即感知命令行的切换，界面与托盘菜单在下次刷新时更新。
+//! 代理接管模式下的切换依赖运行中的代理热切换，命令行会直接拒绝。
+
+mod shell;
+
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Read};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::provider::EnvShell;
+use crate::services::status::{self as status_service, DriftState};
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+use self::shell::Shell;
+
+const USAGE: &str = "\
+Usage: cc-switch-cli <command> [options]
+
+Commands:
+  list <app> [--json|--ids]     List the providers of an app (claude, codex, gemini, opencode)
+  use <app> <provider>          Switch to a provider, given its ID or name
+  add <app> -                   Add a provider read as JSON from stdin, either a full provider
+                                ({\"name\", \"settingsConfig\", ...}) or its settings config alone
+  add <app> --from-env          Add a provider from the credential variables of the current
+                                environment (e.g. ANTHROPIC_AUTH_TOKEN, OPENAI_API_KEY)
+  current [app] [--json]        Show the current provider (of every app when omitted)
+  status [--json]               Show providers, config dirs, drift and health of every app
+  env <app> [provider]          Print export statements for a provider's credentials
+                                (the current provider when omitted)
+  launch <app> [provider] [-- args...]
+                                Run the app's CLI with a provider's credentials set in its
+                                environment only (the current provider when omitted)
+  completions <shell>           Print a completion script (bash, zsh, fish, powershell)
+  shell-init <shell>            Print the `ccs <app> <provider>` shell function, which
+                                switches and exports the credentials into the current shell
+  help                          Show this message
+
+Options:
+  --json                        Print machine-readable JSON
+  --ids                         Print provider IDs only, one per line
+  --name <name>                 Name of the provider created by `add`
+  --shell <shell>               Output format of `env` (default: posix)
+  --session                     Make `env` print the provider's full environment (endpoint,
+                                model and credentials) for use in a single terminal session
+  -q, --quiet                   Print nothing on success; errors still go to stderr
+  --version                     Print the version
+
+Exit codes:
+  0    Success
+  1    Other errors
+  2    Provider not found, or no current provider
+  3    Validation failed (invalid provider or config)
+  4    Failed to read or write a config file or the database
+  5    Refused (read-only mode, proxy takeover, OS authentication)
+  64   Invalid command line
+  127  The app's CLI was not found (`launch`; otherwise `launch` exits with the CLI's code)
+
+Setup:
+  bash/zsh:    eval \"$(cc-switch-cli completions bash)\"; eval \"$(cc-switch-cli shell-init bash)\"
+  fish:        cc-switch-cli completions fish | source; cc-switch-cli shell-init fish | source
+  PowerShell:  cc-switch-cli completions powershell | Out-String | Invoke-Expression
+               cc-switch-cli shell-init powershell | Out-String | Invoke-Expression
+";
+
+/// 已知子命令（参数个数不对时提示用法，而不是「未知命令」）
+const COMMANDS: [&str; 9] = [
+    "list",
+    "use",
+    "add",
+    "current",
+    "status",
+    "env",
+    "launch",
+    "completions",
+    "shell-init",
+];
+
+// 退出码：对脚本公开的稳定约定，修改需同步用法说明
+const EXIT_OK: i32 = 0;
+const EXIT_ERROR: i32 = 1;
+const EXIT_NOT_FOUND: i32 = 2;
+const EXIT_INVALID: i32 = 3;
+const EXIT_WRITE_FAILED: i32 = 4;
+const EXIT_REFUSED: i32 = 5;
+/// 与 sysexits 的 `EX_USAGE` 相同
+const EXIT_USAGE: i32 = 64;
+/// 与 shell 的「命令未找到」相同
+const EXIT_COMMAND_NOT_FOUND: i32 = 127;
+
+/// 存在「当前供应商」的应用（OpenCode 为累加模式）
+const CURRENT_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+#[derive(Debug)]
+enum CliError {
+    /// 参数错误（退出码 64）
+    Usage(String),
+    App(AppError),
+    /// 子进程的退出码（`launch`）
+    Exit(i32),
+}
+
+impl From<AppError> for CliError {
+    fn from(e: AppError) -> Self {
+        CliError::App(e)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderEntry<'a> {
+    id: &'a str,
+    name: &'a str,
+    current: bool,
+}
+
+/// 错误对应的退出码
+fn exit_code(e: &AppError) -> i32 {
+    match e {
+        AppError::InvalidInput(_)
+        | AppError::Config(_)
+        | AppError::Json { .. }
+        | AppError::Toml { .. }
+        | AppError::McpValidation(_) => EXIT_INVALID,
+        AppError::Io { .. }
+        | AppError::IoContext { .. }
+        | AppError::NetworkPath { .. }
+        | AppError::Database(_)
+        | AppError::FileLocked { .. }
+        | AppError::Lock(_) => EXIT_WRITE_FAILED,
+        AppError::Localized { key, .. } => match *key {
+            "cli.provider_not_found" | "cli.no_current_provider" => EXIT_NOT_FOUND,
+            "cli.provider_exists" => EXIT_INVALID,
+            "read_only.locked"
+            | "cli.proxy_takeover"
+            | "os_auth.denied"
+            | "os_auth.unavailable" => EXIT_REFUSED,
+            "launch.not_found" => EXIT_COMMAND_NOT_FOUND,
+            key if key.starts_with("provider.") => EXIT_INVALID,
+            _ => EXIT_ERROR,
+        },
+        _ => EXIT_ERROR,
+    }
+}
+
+/// 执行命令行，返回进程退出码
+pub fn run<I: IntoIterator<Item = String>>(args: I) -> i32 {
+    let args = args.into_iter().collect::<Vec<_>>();
+    let quiet = args
+        .iter()
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "-q" || arg == "--quiet");
+    match dispatch(&args) {
+        Ok(output) => {
+            if !quiet {
+                print!("{output}");
+            }
+            EXIT_OK
+        }
+        Err(CliError::Usage(message)) => {
+            if quiet {
+                eprintln!("{message}");
+            } else {
+                eprintln!("{message}\n\n{USAGE}");
+            }
+            EXIT_USAGE
+        }
+        Err(CliError::App(e)) => {
+            eprintln!("error: {e}");
+            exit_code(&e)
+        }
+        Err(CliError::Exit(code)) => code,
+    }
+}
+
+fn dispatch(args: &[String]) -> Result<String, CliError> {
+    let mut json = false;
+    let mut ids = false;
+    let mut session = false;
+    let mut from_env = false;
+    let mut name = None;
+    let mut shell = None;
+    let mut positional = Vec::new();
+    let mut passthrough = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                passthrough = args.by_ref().cloned().collect();
+                break;
+            }
+            "--json" => json = true,
+            "--ids" => ids = true,
+            "--session" => session = true,
+            // 由 `run` 处理
+            "-q" | "--quiet" => {}
+            "--from-env" => from_env = true,
+            "--name" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::Usage("missing value for --name".to_string()))?;
+                name = Some(value.as_str());
+            }
+            flag if flag.starts_with("--name=") => name = Some(&flag["--name=".len()..]),
+            "--shell" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError::Usage("missing value for --shell".to_string()))?;
+                shell = Some(parse_shell(value)?);
+            }
+            flag if flag.starts_with("--shell=") => {
+                shell = Some(parse_shell(&flag["--shell=".len()..])?);
+            }
+            "-h" | "--help" => return Ok(USAGE.to_string()),
+            "-V" | "--version" => return Ok(format!("{}\n", env!("CARGO_PKG_VERSION"))),
+            // `add <app> -`：从标准输入读取
+            "-" => positional.push("-"),
+            flag if flag.starts_with('-') => {
+                return Err(CliError::Usage(format!("unknown option: {flag}")))
+            }
+            value => positional.push(value),
+        }
+    }
+
+    match positional.as_slice() {
+        [] | ["help"] => Ok(USAGE.to_string()),
+        ["list", app] => {
+            let app_type = parse_app(app)?;
+            with_state(false, |state| list(state, app_type, json, ids))
+        }
+        ["use", app, provider] => {
+            let app_type = parse_app(app)?;
+            with_state(true, |state| switch(state, app_type, provider))
+        }
+        ["add", app, "-"] if !from_env => {
+            let app_type = parse_app(app)?;
+            let provider = provider_from_json(&read_stdin()?, name)?;
+            with_state(true, |state| add(state, app_type, provider, json))
+        }
+        ["add", app] if from_env => {
+            let app_type = parse_app(app)?;
+            let vars = std::env::vars().collect::<BTreeMap<_, _>>();
+            let provider = provider_from_env(&app_type, name, &vars)?;
+            with_state(true, |state| add(state, app_type, provider, json))
+        }
+        ["add", _] | ["add", _, _] => Err(CliError::Usage(
+            "add expects '-' to read the provider from stdin, or --from-env".to_string(),
+        )),
+        ["current"] => with_state(false, |state| current(state, &CURRENT_APPS, json)),
+        ["current", app] => {
+            let app_type = parse_app(app)?;
+            with_state(false, |state| {
+                current(state, std::slice::from_ref(&app_type), json)
+            })
+        }
+        ["status"] => with_state(false, |state| status(state, json)),
+        ["env", app] | ["env", app, _] => {
+            let app_type = parse_app(app)?;
+            let env_shell = shell.map_or(EnvShell::Posix, Shell::env_shell);
+            with_state(false, |state| {
+                env(
+                    state,
+                    app_type,
+                    positional.get(2).copied(),
+                    env_shell,
+                    session,
+                )
+            })
+        }
+        ["launch", app] | ["launch", app, _] => {
+            let app_type = parse_app(app)?;
+            with_state(false, |state| {
+                launch(state, app_type, positional.get(2).copied(), &passthrough)
+            })
+        }
+        ["completions", name] => Ok(parse_shell(name)?.completion_script().to_string()),
+        ["shell-init", name] => Ok(parse_shell(name)?.init_script().to_string()),
+        [command, ..] if COMMANDS.contains(command) => Err(CliError::Usage(format!(
+            "wrong number of arguments for '{command}'"
+        ))),
+        [command, ..] => Err(CliError::Usage(format!("unknown command: {command}"))),
+    }
+}
+
+fn parse_app(app: &str) -> Result<AppType, CliError> {
+    AppType::from_str(app).map_err(|_| CliError::Usage(format!("unknown app: {app}")))
+}
+
+fn parse_shell(name: &str) -> Result<Shell, CliError> {
+    Shell::from_str(name).map_err(CliError::Usage)
+}
+
+fn open_state() -> Result<AppState, CliError> {
+    // 与 GUI 一致：优先使用在设置中覆盖过的配置目录
+    if crate::app_store::refresh_app_config_dir_override_from_disk().is_some() {
+        crate::settings::reload_settings()?;
+    }
+    let db = Database::init()?;
+    Ok(AppState::new(Arc::new(db)))
+}
+
+/// 打开数据库执行命令，返回前写回加密数据库
+///
+/// 启用静态加密时数据库只在内存中修改，进程退出前必须写回 `cc-switch.db.enc`，
+/// 否则命令报告成功但修改丢失。写命令（`write`）强制写回；命令失败时也写回，
+/// 已完成的部分修改不会丢失，但仍返回命令本身的错误。
+fn with_state(
+    write: bool,
+    command: impl FnOnce(&AppState) -> Result<String, CliError>,
+) -> Result<String, CliError> {
+    let state = open_state()?;
+    let result = command(&state);
+    let flushed = state.db.flush_encrypted(write);
+    let output = result?;
+    flushed?;
+    Ok(output)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CliError> {
+    serde_json::to_string_pretty(value)
+        .map(|out| format!("{out}\n"))
+        .map_err(|e| AppError::JsonSerialize { source: e }.into())
+}
+
+/// 按 ID 或名称（忽略大小写）查找供应商，名称重复时要求改用 ID
+fn resolve_provider<'a>(
+    providers: &'a IndexMap<String, Provider>,
+    query: &str,
+) -> Result<&'a Provider, AppError> {
+    if let Some(provider) = providers.get(query) {
+        return Ok(provider);
+    }
+    let matches = providers
+        .values()
+        .filter(|provider| provider.name.eq_ignore_ascii_case(query))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [provider] => Ok(provider),
+        [] => Err(AppError::localized(
+            "cli.provider_not_found",
+            format!("供应商 {query} 不存在"),
+            format!("Provider {query} not found"),
+        )),
+        _ => Err(AppError::InvalidInput(format!(
+            "名称 {query} 对应多个供应商，请改用 ID：{}",
+            matches
+                .iter()
+                .map(|provider| provider.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+fn read_stdin() -> Result<String, CliError> {
+    let mut stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(CliError::Usage(
+            "expected the provider JSON on stdin, e.g. `cat provider.json | cc-switch-cli add claude -`"
+                .to_string(),
+        ));
+    }
+    let mut content = String::new();
+    stdin
+        .read_to_string(&mut content)
+        .map_err(|e| AppError::IoContext {
+            context: "读取标准输入失败".to_string(),
+            source: e,
+        })?;
+    Ok(content)
+}
+
+/// 解析标准输入中的供应商：完整的供应商对象（含 `settingsConfig`，与导出格式相同），
+/// 或只有配置本身（此时需要 `--name`）
+fn provider_from_json(content: &str, name: Option<&str>) -> Result<Provider, AppError> {
+    let mut value: Value =
+        serde_json::from_str(content).map_err(|e| AppError::json("<stdin>", e))?;
+    if let Some(object) = value
+        .as_object_mut()
+        .filter(|object| object.contains_key("settingsConfig"))
+    {
+        object.entry("id").or_insert_with(|| Value::from(""));
+        if let Some(name) = name {
+            object.insert("name".to_string(), Value::from(name));
+        }
+        return serde_json::from_value(value).map_err(|e| AppError::json("<stdin>", e));
+    }
+
+    let name = name.ok_or_else(|| {
+        AppError::InvalidInput(
+            "缺少供应商名称：请使用 --name，或提供包含 name 与 settingsConfig 的完整供应商"
+                .to_string(),
+        )
+    })?;
+    Ok(Provider::with_id(
+        String::new(),
+        name.to_string(),
+        value,
+        None,
+    ))
+}
+
+/// 从环境变量构建供应商，变量与迁移导入的 shell 脚本相同
+fn provider_from_env(
+    app_type: &AppType,
+    name: Option<&str>,
+    vars: &BTreeMap<String, String>,
+) -> Result<Provider, AppError> {
+    if matches!(app_type, AppType::OpenCode) {
+        return Err(AppError::InvalidInput(
+            "OpenCode 不支持从环境变量添加，请通过标准输入提供配置".to_string(),
+        ));
+    }
+    let default_name = format!("{} (env)", app_type.as_str());
+    let entry = crate::services::external_import::provider_from_env_vars(
+        app_type,
+        name.unwrap_or(&default_name),
+        vars,
+    )
+    .ok_or_else(|| {
+        let keys = match app_type {
+            AppType::Claude => "ANTHROPIC_AUTH_TOKEN / ANTHROPIC_API_KEY",
+            AppType::Codex => "OPENAI_API_KEY",
+            _ => "GEMINI_API_KEY / GOOGLE_API_KEY",
+        };
+        AppError::InvalidInput(format!(
+            "环境变量中没有 {} 的密钥（{keys}）",
+            app_type.as_str()
+        ))
+    })?;
+    if entry.base_url.is_empty() && !matches!(app_type, AppType::Claude) {
+        return Err(AppError::InvalidInput(format!(
+            "环境变量中没有 {} 的端点地址",
+            app_type.as_str()
+        )));
+    }
+    entry.to_provider()
+}
+
+/// 添加供应商；ID 为空时自动生成，已存在的 ID 不会被覆盖
+fn add(
+    state: &AppState,
+    app_type: AppType,
+    mut provider: Provider,
+    json: bool,
+) -> Result<String, CliError> {
+    if provider.name.trim().is_empty() {
+        return Err(AppError::InvalidInput("供应商名称不能为空".to_string()).into());
+    }
+    if provider.id.is_empty() {
+        provider.id = uuid::Uuid::new_v4().to_string();
+    } else if state
+        .db
+        .get_provider_by_id(&provider.id, app_type.as_str())?
+        .is_some()
+    {
+        return Err(AppError::localized(
+            "cli.provider_exists",
+            format!("供应商 {} 已存在", provider.id),
+            format!("Provider {} already exists", provider.id),
+        )
+        .into());
+    }
+    provider
+        .created_at
+        .get_or_insert_with(|| chrono::Utc::now().timestamp_millis());
+
+    let (id, name) = (provider.id.clone(), provider.name.clone());
+    ProviderService::add(state, app_type.clone(), provider)?;
+    let current = ProviderService::current(state, app_type.clone())? == id;
+
+    if json {
+        return to_json(&ProviderEntry {
+            id: &id,
+            name: &name,
+            current,
+        });
+    }
+    Ok(format!(
+        "Added {} provider {name} ({id}){}\n",
+        app_type.as_str(),
+        if current { ", now current" } else { "" }
+    ))
+}
+
+/// 按 ID 或名称查找供应商，未指定时使用当前供应商
+fn provider_id(
+    state: &AppState,
+    app_type: &AppType,
+    query: Option<&str>,
+) -> Result<String, AppError> {
+    if let Some(query) = query {
+        let providers = ProviderService::list(state, app_type.clone())?;
+        return Ok(resolve_provider(&providers, query)?.id.clone());
+    }
+    let id = ProviderService::current(state, app_type.clone())?;
+    if id.is_empty() {
+        return Err(AppError::localized(
+            "cli.no_current_provider",
+            format!("{} 尚未选择供应商", app_type.as_str()),
+            format!("No current provider for {}", app_type.as_str()),
+        ));
+    }
+    Ok(id)
+}
+
+fn list(state: &AppState, app_type: AppType, json: bool, ids: bool) -> Result<String, CliError> {
+    let providers = ProviderService::list(state, app_type.clone())?;
+    if ids {
+        return Ok(providers.keys().map(|id| format!("{id}\n")).collect());
+    }
+    let current = ProviderService::current(state, app_type)?;
+    let entries = providers
+        .values()
+        .map(|provider| ProviderEntry {
+            id: &provider.id,
+            name: &provider.name,
+            current: provider.id == current,
+        })
+        .collect::<Vec<_>>();
+
+    if json {
+        return to_json(&entries);
+    }
+    Ok(entries
+        .iter()
+        .map(|entry| {
+            let marker = if entry.current { '*' } else { ' ' };
+            format!("{marker} {}\t{}\n", entry.id, entry.name)
+        })
+        .collect())
+}
+
+fn switch(state: &AppState, app_type: AppType, query: &str) -> Result<String, CliError> {
+    let providers = ProviderService::list(state, app_type.clone())?;
+    let provider = resolve_provider(&providers, query)?;
+
+    let taken_over =
+        futures::executor::block_on(state.db.get_live_backup(app_type.as_str()))?.is_some();
+    if taken_over {
+        return Err(AppError::localized(
+            "cli.proxy_takeover",
+            format!(
+                "{} 处于代理接管模式，请在 CC Switch 中切换",
+                app_type.as_str()
+            ),
+            format!(
+                "{} is taken over by the proxy; switch providers from CC Switch instead",
+                app_type.as_str()
+            ),
+        )
+        .into());
+    }
+
+    ProviderService::switch(state, app_type.clone(), &provider.id)?;
+    crate::services::webhook::flush();
+    Ok(format!(
+        "Switched {} to {} ({})\n",
+        app_type.as_str(),
+        provider.name,
+        provider.id
+    ))
+}
+
+/// 以供应商启动 CLI 并等待退出，退出码原样返回
+fn launch(
+    state: &AppState,
+    app_type: AppType,
+    query: Option<&str>,
+    args: &[String],
+) -> Result<String, CliError> {
+    let id = provider_id(state, &app_type, query)?;
+    let (_, mut cmd) = crate::services::launch::command(state, app_type.clone(), Some(&id), args)?;
+    let status = cmd
+        .status()
+        .map_err(|e| crate::services::launch::spawn_error(&app_type, e))?;
+    match status.code() {
+        Some(0) => Ok(String::new()),
+        Some(code) => Err(CliError::Exit(code)),
+        // 被信号终止
+        None => Err(CliError::Exit(1)),
+    }
+}
+
+/// 输出供应商密钥的环境变量脚本；与 GUI 一致，开启系统身份验证时先验证
+fn env(
+    state: &AppState,
+    app_type: AppType,
+    query: Option<&str>,
+    shell: EnvShell,
+    session: bool,
+) -> Result<String, CliError> {
+    let id = provider_id(state, &app_type, query)?;
+    if crate::settings::get_settings().require_os_auth_for_secrets {
+        crate::os_auth::authenticate("导出 API Key 环境变量")?;
+    }
+    if session {
+        let provider = crate::services::launch::resolve(state, &app_type, Some(&id))?;
+        return Ok(crate::services::launch::session_script(
+            &app_type, &provider, shell,
+        ));
+    }
+    Ok(ProviderService::env_script(state, app_type, &id, shell)?)
+}
+
+fn current(state: &AppState, apps: &[AppType], json: bool) -> Result<String, CliError> {
+    let mut result = Map::new();
+    let mut text = String::new();
+    for app_type in apps {
+        let id = ProviderService::current(state, app_type.clone())?;
+        let provider = match id.as_str() {
+            "" => None,
+            id => state.db.get_provider_by_id(id, app_type.as_str())?,
+        };
+
+        let entry = match &provider {
+            Some(provider) => serde_json::json!({ "id": provider.id, "name": provider.name }),
+            None => Value::Null,
+        };
+        result.insert(app_type.as_str().to_string(), entry);
+        text.push_str(&match &provider {
+            Some(provider) => format!(
+                "{}: {} ({})\n",
+                app_type.as_str(),
+                provider.name,
+                provider.id
+            ),
+            None => format!("{}: -\n", app_type.as_str()),
+        });
+    }
+
+    match (json, apps) {
+        (true, [app_type]) => to_json(&result[app_type.as_str()]),
+        (true, _) => to_json(&result),
+        (false, _) => Ok(text),
+    }
+}
+
+fn status(state: &AppState, json: bool) -> Result<String, CliError> {
+    let report = futures::executor::block_on(status_service::collect(state))?;
+    if json {
+        return to_json(&report);
+    }
+
+    let mut text = format!("version: {}\n", report.version);
+    let overridden = |flag: bool| if flag { " (override)" } else { "" };
+    let data_dir_note = match report.app_config_dir_source {
+        crate::paths::DataDirSource::Env => format!(" ({})", crate::paths::DATA_DIR_ENV),
+        _ => overridden(report.app_config_dir_overridden).to_string(),
+    };
+    text.push_str(&format!(
+        "data dir: {}{data_dir_note}\n",
+        report.app_config_dir
+    ));
+    if report.read_only {
+        text.push_str("read-only mode: on\n");
+    }
+    for app in &report.apps {
+        let current = match &app.current {
+            Some(provider) => format!("{} ({})", provider.name, provider.id),
+            None => "-".to_string(),
+        };
+        text.push_str(&format!("\n{}: {current}\n", app.app));
+        text.push_str(&format!(
+            "  config dir: {}{}\n",
+            app.config_dir,
+            overridden(app.config_dir_overridden)
+        ));
+        if app.proxy_takeover {
+            text.push_str("  proxy takeover: on\n");
+        }
+        let drift = match app.drift.state {
+            DriftState::InSync => "in sync".to_string(),
+            DriftState::Drifted => format!("drifted ({})", app.drift.fields.join(", ")),
+            DriftState::LiveUnreadable => format!(
+                "live config unreadable: {}",
+                app.drift.detail.as_deref().unwrap_or_default()
+            ),
+            DriftState::Skipped => "-".to_string(),
+        };
+        text.push_str(&format!("  drift: {drift}\n"));
+        if let Some(health) = &app.health {
+            text.push_str(&match &health.last_error {
+                Some(error) if !health.healthy => format!("  health: unhealthy ({error})\n"),
+                _ if !health.healthy => "  health: unhealthy\n".to_string(),
+                _ => "  health: ok\n".to_string(),
+            });
+        }
+    }
+    for finding in &report.findings {
+        text.push_str(&format!("\nwarning: {}\n", finding.message));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_bad_arguments_without_opening_the_database() {
+        assert!(matches!(dispatch(&args(&["help"])), Ok(out) if out == USAGE));
+        assert!(matches!(
+            dispatch(&args(&["list"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["use", "nope", "x"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["current", "--verbose"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["env", "claude", "--shell"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["env", "claude", "--shell=tcsh"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["completions"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["status", "claude"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["launch", "claude", "a", "b"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["add", "claude"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["add", "claude", "-", "--from-env"])),
+            Err(CliError::Usage(_))
+        ));
+        assert!(matches!(
+            dispatch(&args(&["add", "claude", "--name"])),
+            Err(CliError::Usage(_))
+        ));
+        // `--` 之后的参数原样交给 CLI，不按选项解析
+        assert!(matches!(
+            dispatch(&args(&["launch", "nope", "--", "--resume"])),
+            Err(CliError::Usage(message)) if message == "unknown app: nope"
+        ));
+    }
+
+    #[test]
+    fn prints_shell_integration_scripts() {
+        for name in ["bash", "zsh", "fish", "powershell"] {
+            let completions = dispatch(&args(&["completions", name])).unwrap();
+            assert!(completions.contains("cc-switch-cli list"));
+            assert!(completions.contains("ccs"));
+
+            let init = dispatch(&args(&["shell-init", name])).unwrap();
+            assert!(init.contains("cc-switch-cli use"));
+            assert!(init.contains("cc-switch-cli env"));
+        }
+        assert!(dispatch(&args(&["shell-init", "zsh"]))
+            .unwrap()
+            .contains("eval \"$script\""));
+        assert!(dispatch(&args(&["shell-init", "pwsh"]))
+            .unwrap()
+            .contains("--shell powershell"));
+        assert!(matches!(
+            dispatch(&args(&["shell-init", "cmd"])),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn parses_providers_from_stdin_json() {
+        let full = provider_from_json(
+            r#"{"name": "Relay", "settingsConfig": {"env": {"ANTHROPIC_AUTH_TOKEN": "sk"}}}"#,
+            None,
+        )
+        .unwrap();
+        assert_eq!(full.id, "");
+        assert_eq!(full.name, "Relay");
+        assert_eq!(full.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"], "sk");
+
+        let renamed = provider_from_json(
+            r#"{"id": "relay", "name": "Relay", "settingsConfig": {}}"#,
+            Some("Team relay"),
+        )
+        .unwrap();
+        assert_eq!(
+            (renamed.id.as_str(), renamed.name.as_str()),
+            ("relay", "Team relay")
+        );
+
+        let bare =
+            provider_from_json(r#"{"auth": {"OPENAI_API_KEY": "sk"}}"#, Some("Relay")).unwrap();
+        assert_eq!(bare.settings_config["auth"]["OPENAI_API_KEY"], "sk");
+        assert!(provider_from_json(r#"{"env": {}}"#, None).is_err());
+        assert!(provider_from_json("not json", Some("Relay")).is_err());
+    }
+
+    #[test]
+    fn builds_providers_from_environment() {
+        let vars = [
+            ("OPENAI_API_KEY", "sk-codex"),
+            ("OPENAI_BASE_URL", "https://relay.example.com/v1"),
+            ("ANTHROPIC_AUTH_TOKEN", "sk-claude"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect::<BTreeMap<_, _>>();
+
+        let codex = provider_from_env(&AppType::Codex, Some("Relay"), &vars).unwrap();
+        assert_eq!(codex.name, "Relay");
+        assert_eq!(codex.settings_config["auth"]["OPENAI_API_KEY"], "sk-codex");
+        assert!(codex.settings_config["config"]
+            .as_str()
+            .unwrap()
+            .contains("https://relay.example.com/v1"));
+
+        // 官方 Claude 账号不需要端点
+        let claude = provider_from_env(&AppType::Claude, None, &vars).unwrap();
+        assert_eq!(claude.name, "claude (env)");
+        assert!(claude.settings_config["env"]
+            .get("ANTHROPIC_BASE_URL")
+            .is_none());
+
+        assert!(provider_from_env(&AppType::Gemini, None, &vars).is_err());
+        assert!(provider_from_env(&AppType::OpenCode, None, &vars).is_err());
+    }
+
+    #[test]
+    fn maps_errors_to_stable_exit_codes() {
+        let localized = |key| AppError::localized(key, "", "");
+        assert_eq!(
+            exit_code(&localized("cli.provider_not_found")),
+            EXIT_NOT_FOUND
+        );
+        assert_eq!(
+            exit_code(&AppError::InvalidInput("x".to_string())),
+            EXIT_INVALID
+        );
+        assert_eq!(
+            exit_code(&localized("provider.codex.auth.missing")),
+            EXIT_INVALID
+        );
+        assert_eq!(
+            exit_code(&AppError::io(
+                "settings.json",
+                std::io::Error::from(std::io::ErrorKind::PermissionDenied)
+            )),
+            EXIT_WRITE_FAILED
+        );
+        assert_eq!(exit_code(&localized("read_only.locked")), EXIT_REFUSED);
+        assert_eq!(
+            exit_code(&localized("launch.not_found")),
+            EXIT_COMMAND_NOT_FOUND
+        );
+        assert_eq!(exit_code(&AppError::Message("x".to_string())), EXIT_ERROR);
+
+        // 参数错误即使在静默模式下也有独立的退出码
+        assert_eq!(run(args(&["list", "--quiet"])), EXIT_USAGE);
+        assert_eq!(run(args(&["help", "-q"])), EXIT_OK);
+    }
+
+    #[test]
+    fn resolves_providers_by_id_or_unique_name() {
+        let mut providers = IndexMap::new();
+        for (id, name) in [("a", "Relay"), ("b", "Official"), ("c", "official")] {
+            providers.insert(
+                id.to_string(),
+                Provider::with_id(id.to_string(), name.to_string(), json!({}), None),
+            );
+        }
+
+        assert_eq!(resolve_provider(&providers, "b").unwrap().id, "b");
+        assert_eq!(resolve_provider(&providers, "relay").unwrap().id, "a");
+        assert!(resolve_provider(&providers, "Official").is_err());
+        assert!(resolve_provider(&providers, "missing").is_err());
+    }
+}
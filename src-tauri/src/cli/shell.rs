@@ -0,0 +1,186 @@
+//! Shell 集成
+//!
+//! - `completions <shell>`：`cc-switch-cli` 与 `ccs` 的补全脚本，供应商 ID 通过
+//!   `cc-switch-cli list <app> --ids` 动态获取；
+//! - `shell-init <shell>`：定义 `ccs <app> <provider>` 函数，切换供应商后把该供应商的
+//!   密钥环境变量导出到当前 shell 会话（`cc-switch-cli env <app>`）。
+//!
+//! 用法示例：`eval "$(cc-switch-cli shell-init bash)"`、
+//! `cc-switch-cli shell-init fish | source`、
+//! `cc-switch-cli shell-init powershell | Out-String | Invoke-Expression`。
+
+use std::str::FromStr;
+
+use crate::services::provider::EnvShell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Pwsh,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" | "pwsh" => Ok(Shell::Pwsh),
+            other => Err(format!("unsupported shell: {other}")),
+        }
+    }
+}
+
+impl Shell {
+    /// `env` 子命令应输出的脚本格式（fish 自带兼容 POSIX 写法的 `export`）
+    pub(super) fn env_shell(self) -> EnvShell {
+        match self {
+            Shell::Pwsh => EnvShell::PowerShell,
+            Shell::Bash | Shell::Zsh | Shell::Fish => EnvShell::Posix,
+        }
+    }
+
+    pub(super) fn completion_script(self) -> &'static str {
+        match self {
+            Shell::Bash => BASH_COMPLETION,
+            Shell::Zsh => ZSH_COMPLETION,
+            Shell::Fish => FISH_COMPLETION,
+            Shell::Pwsh => POWERSHELL_COMPLETION,
+        }
+    }
+
+    pub(super) fn init_script(self) -> &'static str {
+        match self {
+            Shell::Bash | Shell::Zsh => POSIX_INIT,
+            Shell::Fish => FISH_INIT,
+            Shell::Pwsh => POWERSHELL_INIT,
+        }
+    }
+}
+
+const BASH_COMPLETION: &str = r#"_cc_switch_cli() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    case "$COMP_CWORD" in
+        1) COMPREPLY=($(compgen -W "list use add current env launch completions shell-init help" -- "$cur")) ;;
+        2) case "${COMP_WORDS[1]}" in
+               list|use|add|current|env|launch) COMPREPLY=($(compgen -W "claude codex gemini opencode" -- "$cur")) ;;
+               completions|shell-init) COMPREPLY=($(compgen -W "bash zsh fish powershell" -- "$cur")) ;;
+           esac ;;
+        3) case "${COMP_WORDS[1]}" in
+               use|env|launch) COMPREPLY=($(compgen -W "$(cc-switch-cli list "${COMP_WORDS[2]}" --ids 2>/dev/null)" -- "$cur")) ;;
+           esac ;;
+    esac
+}
+complete -F _cc_switch_cli cc-switch-cli
+
+_ccs() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    case "$COMP_CWORD" in
+        1) COMPREPLY=($(compgen -W "claude codex gemini opencode" -- "$cur")) ;;
+        2) COMPREPLY=($(compgen -W "$(cc-switch-cli list "${COMP_WORDS[1]}" --ids 2>/dev/null)" -- "$cur")) ;;
+    esac
+}
+complete -F _ccs ccs
+"#;
+
+const ZSH_COMPLETION: &str = r#"_cc_switch_cli() {
+    local -a apps shells ids
+    apps=(claude codex gemini opencode)
+    shells=(bash zsh fish powershell)
+    if [[ $service == ccs ]]; then
+        case $CURRENT in
+            2) _describe 'app' apps ;;
+            3) ids=(${(f)"$(cc-switch-cli list $words[2] --ids 2>/dev/null)"}); _describe 'provider' ids ;;
+        esac
+        return
+    fi
+    case $CURRENT in
+        2) local -a commands; commands=(list use add current env launch completions shell-init help); _describe 'command' commands ;;
+        3) case $words[2] in
+               list|use|add|current|env|launch) _describe 'app' apps ;;
+               completions|shell-init) _describe 'shell' shells ;;
+           esac ;;
+        4) case $words[2] in
+               use|env|launch) ids=(${(f)"$(cc-switch-cli list $words[3] --ids 2>/dev/null)"}); _describe 'provider' ids ;;
+           esac ;;
+    esac
+}
+compdef _cc_switch_cli cc-switch-cli ccs
+"#;
+
+const FISH_COMPLETION: &str = r#"complete -c cc-switch-cli -f
+complete -c cc-switch-cli -n '__fish_use_subcommand' -a 'list use add current env launch completions shell-init help'
+complete -c cc-switch-cli -n '__fish_seen_subcommand_from list use add current env launch; and test (count (commandline -opc)) -eq 2' -a 'claude codex gemini opencode'
+complete -c cc-switch-cli -n '__fish_seen_subcommand_from completions shell-init' -a 'bash zsh fish powershell'
+complete -c cc-switch-cli -n '__fish_seen_subcommand_from use env launch; and test (count (commandline -opc)) -eq 3' -a '(cc-switch-cli list (commandline -opc)[3] --ids 2>/dev/null)'
+complete -c cc-switch-cli -l json -d 'Print machine-readable JSON'
+complete -c cc-switch-cli -s q -l quiet -d 'Print nothing on success'
+complete -c cc-switch-cli -l session -d 'Print the full provider environment for this session'
+complete -c cc-switch-cli -n '__fish_seen_subcommand_from add' -l from-env -d 'Add a provider from environment variables'
+complete -c cc-switch-cli -n '__fish_seen_subcommand_from add' -l name -r -d 'Name of the new provider'
+complete -c ccs -f
+complete -c ccs -n 'test (count (commandline -opc)) -eq 1' -a 'claude codex gemini opencode'
+complete -c ccs -n 'test (count (commandline -opc)) -eq 2' -a '(cc-switch-cli list (commandline -opc)[2] --ids 2>/dev/null)'
+"#;
+
+const POWERSHELL_COMPLETION: &str = r#"Register-ArgumentCompleter -Native -CommandName cc-switch-cli -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements | ForEach-Object { $_.ToString() })
+    if ($wordToComplete) { $words = $words[0..($words.Count - 2)] }
+    $candidates = switch ($words.Count) {
+        1 { 'list', 'use', 'add', 'current', 'env', 'launch', 'completions', 'shell-init', 'help' }
+        2 {
+            if ($words[1] -in 'completions', 'shell-init') { 'bash', 'zsh', 'fish', 'powershell' }
+            else { 'claude', 'codex', 'gemini', 'opencode' }
+        }
+        3 { if ($words[1] -in 'use', 'env', 'launch') { cc-switch-cli list $words[2] --ids 2>$null } }
+    }
+    $candidates | Where-Object { $_ -like "$wordToComplete*" } | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_)
+    }
+}
+Register-ArgumentCompleter -CommandName ccs -ParameterName Provider -ScriptBlock {
+    param($commandName, $parameterName, $wordToComplete, $commandAst, $fakeBoundParameters)
+    cc-switch-cli list $fakeBoundParameters['App'] --ids 2>$null | Where-Object { $_ -like "$wordToComplete*" }
+}
+"#;
+
+const POSIX_INIT: &str = r#"ccs() {
+    if [ "$#" -ne 2 ]; then
+        echo "usage: ccs <app> <provider>" >&2
+        return 64
+    fi
+    cc-switch-cli use "$1" "$2" || return
+    local script
+    script="$(cc-switch-cli env "$1")" || return
+    eval "$script"
+}
+"#;
+
+const FISH_INIT: &str = r#"function ccs --description 'Switch provider and export its credentials'
+    if test (count $argv) -ne 2
+        echo "usage: ccs <app> <provider>" >&2
+        return 64
+    end
+    cc-switch-cli use $argv[1] $argv[2]; or return
+    set -l script (cc-switch-cli env $argv[1]); or return
+    printf '%s\n' $script | source
+end
+"#;
+
+const POWERSHELL_INIT: &str = r#"function ccs {
+    param(
+        [Parameter(Mandatory = $true)][ValidateSet('claude', 'codex', 'gemini', 'opencode')][string]$App,
+        [Parameter(Mandatory = $true)][string]$Provider
+    )
+    cc-switch-cli use $App $Provider
+    if ($LASTEXITCODE -ne 0) { return }
+    $script = cc-switch-cli env $App --shell powershell | Out-String
+    if ($LASTEXITCODE -ne 0) { return }
+    Invoke-Expression $script
+}
+"#;
@@ -0,0 +1,199 @@
+//! 网盘同步目录检测
+//!
+//! `~/.cc-switch` 或目录覆盖位于 Dropbox / iCloud Drive / OneDrive / Google Drive
+//! 中时，网盘客户端可能在写入过程中上传临时文件，或在多端同时修改时生成
+//! “冲突副本”覆盖 live 配置。检测到这类目录后，写入使用网盘会忽略的唯一临时文件名，
+//! 并通过诊断命令提示用户。
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+
+/// 网盘类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloudDrive {
+    Dropbox,
+    #[serde(rename = "icloud")]
+    ICloud,
+    #[serde(rename = "onedrive")]
+    OneDrive,
+    #[serde(rename = "googledrive")]
+    GoogleDrive,
+}
+
+impl CloudDrive {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            CloudDrive::Dropbox => "Dropbox",
+            CloudDrive::ICloud => "iCloud Drive",
+            CloudDrive::OneDrive => "OneDrive",
+            CloudDrive::GoogleDrive => "Google Drive",
+        }
+    }
+}
+
+/// 通过环境变量声明的网盘根目录（Windows OneDrive 客户端会设置）
+fn env_roots() -> Vec<(CloudDrive, PathBuf)> {
+    ["OneDrive", "OneDriveConsumer", "OneDriveCommercial"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .filter(|v| !v.is_empty())
+        .map(|v| (CloudDrive::OneDrive, PathBuf::from(v)))
+        .collect()
+}
+
+/// 按路径组件名判断网盘类型
+fn classify_component(name: &str) -> Option<CloudDrive> {
+    let lower = name.to_ascii_lowercase();
+    if lower == "dropbox" || lower.starts_with("dropbox (") || lower.starts_with("dropbox-") {
+        return Some(CloudDrive::Dropbox);
+    }
+    if lower == "mobile documents"
+        || lower == "icloud drive"
+        || lower == "icloud~com~apple~clouddocs"
+    {
+        return Some(CloudDrive::ICloud);
+    }
+    if lower == "onedrive" || lower.starts_with("onedrive - ") || lower.starts_with("onedrive-") {
+        return Some(CloudDrive::OneDrive);
+    }
+    if lower == "google drive" || lower.starts_with("googledrive-") || lower == "my drive" {
+        return Some(CloudDrive::GoogleDrive);
+    }
+    None
+}
+
+fn detect_with_roots(path: &Path, roots: &[(CloudDrive, PathBuf)]) -> Option<CloudDrive> {
+    for (drive, root) in roots {
+        if path.starts_with(root) {
+            return Some(*drive);
+        }
+    }
+
+    path.components().find_map(|component| match component {
+        Component::Normal(name) => classify_component(&name.to_string_lossy()),
+        _ => None,
+    })
+}
+
+/// 检测路径是否位于网盘同步目录中
+pub fn detect_cloud_drive(path: &Path) -> Option<CloudDrive> {
+    detect_with_roots(path, &env_roots())
+}
+
+/// 判断文件名是否像网盘生成的冲突副本
+///
+/// 例如 `settings (conflicted copy 2024-01-01).json`（Dropbox）、
+/// `settings-DESKTOP-ABC.json`（OneDrive）、`settings 2.json`（iCloud）。
+pub fn is_conflict_copy_of(candidate: &str, original: &str) -> bool {
+    if candidate == original {
+        return false;
+    }
+    let (stem, ext) = match original.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (original, String::new()),
+    };
+    let Some(rest) = candidate
+        .strip_prefix(stem)
+        .and_then(|r| r.strip_suffix(ext.as_str()))
+    else {
+        return false;
+    };
+
+    let lower = rest.to_ascii_lowercase();
+    if lower.contains("conflicted copy") || lower.contains("conflict") {
+        return true;
+    }
+    // iCloud: "settings 2.json"
+    if let Some(num) = rest.strip_prefix(' ') {
+        if !num.is_empty() && num.chars().all(|c| c.is_ascii_digit()) {
+            return true;
+        }
+    }
+    // OneDrive: "settings-HOSTNAME.json"
+    if let Some(host) = rest.strip_prefix('-') {
+        return !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-');
+    }
+    false
+}
+
+/// 列出目录中指定文件的网盘冲突副本
+pub fn find_conflict_copies(dir: &Path, file_name: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter(|e| is_conflict_copy_of(&e.file_name().to_string_lossy(), file_name))
+        .map(|e| e.path())
+        .collect()
+}
+
+/// 网盘目录中写入使用的临时文件名
+///
+/// 以 `.~` 开头、`.tmp` 结尾，Dropbox / OneDrive 不会上传这类文件；
+/// 包含进程号与时间戳，避免多端同时写入时互相覆盖临时文件。
+pub fn temp_file_name(file_name: &str) -> String {
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    format!(".~{file_name}.{}-{ts}.tmp", std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_drives_by_path_components() {
+        assert_eq!(
+            detect_with_roots(Path::new("/Users/me/Dropbox/dotfiles/.claude"), &[]),
+            Some(CloudDrive::Dropbox)
+        );
+        assert_eq!(
+            detect_with_roots(
+                Path::new("/Users/me/Library/Mobile Documents/com~apple~CloudDocs/cc"),
+                &[]
+            ),
+            Some(CloudDrive::ICloud)
+        );
+        assert_eq!(
+            detect_with_roots(
+                Path::new("/Users/me/Library/CloudStorage/GoogleDrive-me@x.com/My Drive"),
+                &[]
+            ),
+            Some(CloudDrive::GoogleDrive)
+        );
+        assert_eq!(detect_with_roots(Path::new("/home/me/.claude"), &[]), None);
+    }
+
+    #[test]
+    fn detects_drives_by_env_root() {
+        let roots = vec![(CloudDrive::OneDrive, PathBuf::from("/mnt/c/Users/me/Work"))];
+        assert_eq!(
+            detect_with_roots(Path::new("/mnt/c/Users/me/Work/.cc-switch"), &roots),
+            Some(CloudDrive::OneDrive)
+        );
+    }
+
+    #[test]
+    fn recognizes_conflict_copies() {
+        assert!(is_conflict_copy_of(
+            "settings (conflicted copy 2024-01-01).json",
+            "settings.json"
+        ));
+        assert!(is_conflict_copy_of("settings 2.json", "settings.json"));
+        assert!(is_conflict_copy_of(
+            "settings-DESKTOP-AB12.json",
+            "settings.json"
+        ));
+        assert!(!is_conflict_copy_of("settings.json", "settings.json"));
+        assert!(!is_conflict_copy_of("settings-local.json", "settings.json"));
+        assert!(!is_conflict_copy_of("config.toml", "settings.json"));
+    }
+}
@@ -5,6 +5,9 @@ use crate::config::{
     atomic_write, delete_file, sanitize_provider_name, write_json_file, write_text_file,
 };
 use crate::error::AppError;
+use crate::network_fs::with_retry;
+use crate::settings::LiveFile;
+use base64::prelude::*;
 use serde_json::Value;
 use std::fs;
 use std::path::Path;
@@ -26,14 +29,18 @@ pub fn get_codex_config_dir() -> PathBuf {
     get_home_dir().join(".codex")
 }
 
-/// 获取 Codex auth.json 路径
+/// 获取 Codex auth.json 路径（支持设置中的文件路径覆盖）
 pub fn get_codex_auth_path() -> PathBuf {
-    get_codex_config_dir().join("auth.json")
+    crate::settings::resolve_live_file(LiveFile::CodexAuth, &get_codex_config_dir(), "auth.json")
 }
 
-/// 获取 Codex config.toml 路径
+/// 获取 Codex config.toml 路径（支持设置中的文件路径覆盖）
 pub fn get_codex_config_path() -> PathBuf {
-    get_codex_config_dir().join("config.toml")
+    crate::settings::resolve_live_file(
+        LiveFile::CodexConfig,
+        &get_codex_config_dir(),
+        "config.toml",
+    )
 }
 
 /// 获取 Codex 供应商配置文件路径
@@ -67,37 +74,46 @@ pub fn delete_codex_provider_config(
 }
 
 /// 原子写 Codex 的 `auth.json` 与 `config.toml`，在第二步失败时回滚第一步
+///
+/// `config_text_opt` 是供应商的 config.toml 片段，按 [`merge_codex_config`] 合并到现有
+/// 文件中，保留用户维护的配置；返回被供应商配置覆盖的用户配置路径。
 pub fn write_codex_live_atomic(
     auth: &Value,
     config_text_opt: Option<&str>,
-) -> Result<(), AppError> {
+) -> Result<Vec<String>, AppError> {
     let auth_path = get_codex_auth_path();
     let config_path = get_codex_config_path();
 
     if let Some(parent) = auth_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        with_retry(parent, || std::fs::create_dir_all(parent))?;
     }
 
-    // 读取旧内容用于回滚
+    // 读取旧内容用于回滚（网络路径上的瞬时错误会自动重试）
     let old_auth = if auth_path.exists() {
-        Some(fs::read(&auth_path).map_err(|e| AppError::io(&auth_path, e))?)
+        Some(with_retry(&auth_path, || fs::read(&auth_path))?)
     } else {
         None
     };
-    let _old_config = if config_path.exists() {
-        Some(fs::read(&config_path).map_err(|e| AppError::io(&config_path, e))?)
+    let old_config = if config_path.exists() {
+        Some(with_retry(&config_path, || fs::read(&config_path))?)
     } else {
         None
     };
 
-    // 准备写入内容
-    let cfg_text = match config_text_opt {
-        Some(s) => s.to_string(),
-        None => String::new(),
-    };
-    if !cfg_text.trim().is_empty() {
-        toml::from_str::<toml::Table>(&cfg_text).map_err(|e| AppError::toml(&config_path, e))?;
+    // 准备写入内容：供应商片段合并到现有配置
+    let fragment = config_text_opt.unwrap_or_default();
+    if !fragment.trim().is_empty() {
+        toml::from_str::<toml::Table>(fragment).map_err(|e| AppError::toml(&config_path, e))?;
     }
+    let existing = old_config
+        .as_deref()
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    let CodexConfigMerge {
+        text: cfg_text,
+        conflicts,
+    } = merge_codex_config(&existing, fragment)?;
+    log_conflicts(&conflicts);
 
     // 第一步：写 auth.json
     write_json_file(&auth_path, auth)?;
@@ -113,14 +129,14 @@ pub fn write_codex_live_atomic(
         return Err(e);
     }
 
-    Ok(())
+    Ok(conflicts)
 }
 
 /// 读取 `~/.codex/config.toml`，若不存在返回空字符串
 pub fn read_codex_config_text() -> Result<String, AppError> {
     let path = get_codex_config_path();
     if path.exists() {
-        std::fs::read_to_string(&path).map_err(|e| AppError::io(&path, e))
+        with_retry(&path, || std::fs::read_to_string(&path))
     } else {
         Ok(String::new())
     }
@@ -142,3 +158,488 @@ pub fn read_and_validate_codex_config_text() -> Result<String, AppError> {
     validate_config_toml(&s)?;
     Ok(s)
 }
+
+/// config.toml 中托管区域的说明注释
+const MANAGED_HEADER: &str = "# Managed by CC Switch: the keys listed below follow the active provider and are replaced when switching. Other settings are preserved.";
+
+/// config.toml 中记录托管键（点分路径的 TOML 数组）的注释前缀
+///
+/// 旧版本写入的是逗号分隔的顶层键，读取时仍按顶层键处理。
+const MANAGED_KEYS_PREFIX: &str = "# cc-switch-managed:";
+
+/// config.toml 中保存被供应商配置覆盖的用户值（Base64 编码的 TOML）的注释前缀
+const USER_VALUES_PREFIX: &str = "# cc-switch-user-values:";
+
+/// 没有托管标记的旧文件（由旧版本整体写入）中视为供应商所有的顶层键
+///
+/// `[model_providers]` 下可能有用户自己的条目，只移除 `model_provider` 指向的那一项。
+const LEGACY_MANAGED_KEYS: [&str; 6] = [
+    "model",
+    "model_provider",
+    "model_reasoning_effort",
+    "model_verbosity",
+    "disable_response_storage",
+    "preferred_auth_method",
+];
+
+/// MCP 服务器由 MCP 同步单独管理，不随供应商整体替换
+const MCP_SERVERS_KEY: &str = "mcp_servers";
+
+/// 供应商配置合并到 live config.toml 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodexConfigMerge {
+    pub text: String,
+    /// 被供应商配置覆盖的用户配置（点分路径）
+    pub conflicts: Vec<String>,
+}
+
+/// 键路径（逐段的键名）
+type KeyPath = Vec<String>;
+
+/// 托管标记中记录的状态
+#[derive(Default)]
+struct ManagedState {
+    /// 供应商写入的叶子路径，下次切换时移除
+    paths: Vec<KeyPath>,
+    /// 被供应商配置覆盖的用户值，下次切换时恢复
+    user_values: Vec<(KeyPath, toml_edit::Item)>,
+}
+
+fn format_path(path: &[String]) -> String {
+    path.iter()
+        .map(|key| {
+            toml_edit::Key::new(key.as_str())
+                .display_repr()
+                .into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn parse_path(text: &str) -> Option<KeyPath> {
+    let keys = toml_edit::Key::parse(text).ok()?;
+    Some(keys.iter().map(|key| key.get().to_string()).collect())
+}
+
+fn parse_managed_paths(list: &str) -> Vec<KeyPath> {
+    if !list.starts_with('[') {
+        return list
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| vec![key.to_string()])
+            .collect();
+    }
+    let Ok(doc) = format!("paths = {list}").parse::<toml_edit::DocumentMut>() else {
+        log::warn!("Codex config.toml 中的托管标记无法解析，已忽略");
+        return Vec::new();
+    };
+    doc["paths"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str().and_then(parse_path))
+        .collect()
+}
+
+fn parse_user_values(encoded: &str) -> Vec<(KeyPath, toml_edit::Item)> {
+    let doc = BASE64_STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok());
+    let Some(doc) = doc else {
+        log::warn!("Codex config.toml 中保存的用户配置无法解析，已忽略");
+        return Vec::new();
+    };
+    doc.iter()
+        .filter_map(|(key, item)| Some((parse_path(key)?, item.clone())))
+        .collect()
+}
+
+/// 去掉托管标记注释，返回正文与标记中记录的状态（没有托管键标记时为 None）
+fn strip_managed_marker(text: &str) -> (String, Option<ManagedState>) {
+    let mut state: Option<ManagedState> = None;
+    let mut user_values = Vec::new();
+    let mut body = String::with_capacity(text.len());
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(list) = trimmed.strip_prefix(MANAGED_KEYS_PREFIX) {
+            state.get_or_insert_with(Default::default).paths = parse_managed_paths(list.trim());
+            continue;
+        }
+        if let Some(encoded) = trimmed.strip_prefix(USER_VALUES_PREFIX) {
+            user_values = parse_user_values(encoded.trim());
+            continue;
+        }
+        if trimmed == MANAGED_HEADER {
+            continue;
+        }
+        body.push_str(line);
+        body.push('\n');
+    }
+    if let Some(state) = state.as_mut() {
+        state.user_values = user_values;
+    }
+    (body, state)
+}
+
+fn parse_document(text: &str) -> Result<toml_edit::DocumentMut, AppError> {
+    text.parse::<toml_edit::DocumentMut>()
+        .map_err(|e| AppError::Config(format!("解析 Codex config.toml 失败: {e}")))
+}
+
+fn get_path<'a>(doc: &'a toml_edit::DocumentMut, path: &[String]) -> Option<&'a toml_edit::Item> {
+    path.iter()
+        .try_fold(doc.as_item(), |item, key| item.as_table_like()?.get(key))
+}
+
+/// 移除路径上的值，并清理因此变空的父表；返回是否移除了内容
+fn remove_path(table: &mut dyn toml_edit::TableLike, path: &[String]) -> bool {
+    let Some((key, rest)) = path.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return table.remove(key).is_some();
+    }
+    let Some(child) = table
+        .get_mut(key)
+        .and_then(toml_edit::Item::as_table_like_mut)
+    else {
+        return false;
+    };
+    let removed = remove_path(child, rest);
+    if removed && child.is_empty() {
+        table.remove(key);
+    }
+    removed
+}
+
+/// 在路径上写入值，缺少的父表按需创建
+fn set_path(table: &mut dyn toml_edit::TableLike, path: &[String], item: toml_edit::Item) {
+    let Some((key, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        table.insert(key, item);
+        return;
+    }
+    if !table.get(key).is_some_and(toml_edit::Item::is_table_like) {
+        let mut child = toml_edit::Table::new();
+        child.set_implicit(true);
+        table.insert(key, toml_edit::Item::Table(child));
+    }
+    if let Some(child) = table
+        .get_mut(key)
+        .and_then(toml_edit::Item::as_table_like_mut)
+    {
+        set_path(child, rest, item);
+    }
+}
+
+/// 收集片段中的叶子路径（表逐键展开，其余值视为叶子）
+fn leaf_paths(item: &toml_edit::Item, prefix: &mut KeyPath, out: &mut Vec<KeyPath>) {
+    match item.as_table_like() {
+        Some(table) if !table.is_empty() => {
+            for (key, child) in table.iter() {
+                prefix.push(key.to_string());
+                leaf_paths(child, prefix, out);
+                prefix.pop();
+            }
+        }
+        _ => out.push(prefix.clone()),
+    }
+}
+
+/// 去掉修饰（空白与注释）后的值文本，用于比较
+fn plain_value(item: &toml_edit::Item) -> String {
+    let mut item = item.clone();
+    if let Some(value) = item.as_value_mut() {
+        value.decor_mut().clear();
+    }
+    item.to_string().trim().to_string()
+}
+
+/// 把 `patch` 合并到 `base`：表逐键合并，其余以 `patch` 为准，并记录被替换的原值
+fn merge_item(
+    base: &mut toml_edit::Item,
+    patch: &toml_edit::Item,
+    path: &mut KeyPath,
+    conflicts: &mut Vec<String>,
+    replaced: &mut Vec<(KeyPath, toml_edit::Item)>,
+) {
+    if let (Some(base_table), Some(patch_table)) = (base.as_table_like_mut(), patch.as_table_like())
+    {
+        for (key, patch_item) in patch_table.iter() {
+            path.push(key.to_string());
+            match base_table.get_mut(key) {
+                Some(base_item) => merge_item(base_item, patch_item, path, conflicts, replaced),
+                None => {
+                    base_table.insert(key, patch_item.clone());
+                }
+            }
+            path.pop();
+        }
+        return;
+    }
+    if plain_value(base) != plain_value(patch) {
+        conflicts.push(path.join("."));
+    }
+    // 值相同也要记录：该路径随后归供应商托管，下次切换移除时需要恢复用户的值
+    replaced.push((path.clone(), base.clone()));
+    *base = patch.clone();
+}
+
+/// 两条路径是否互为前缀（位于同一分支上）
+fn overlaps(a: &[String], b: &[String]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x == y)
+}
+
+fn is_mcp_path(path: &[String]) -> bool {
+    path.first().is_some_and(|key| key == MCP_SERVERS_KEY)
+}
+
+fn encode_user_values(values: &[(KeyPath, toml_edit::Item)]) -> String {
+    let mut doc = toml_edit::DocumentMut::new();
+    for (path, item) in values {
+        doc.insert(&format_path(path), item.clone());
+    }
+    BASE64_STANDARD.encode(doc.to_string())
+}
+
+/// 把供应商的 config.toml 片段合并到现有 live 配置
+///
+/// 上次写入时托管的叶子路径（见文件中的 `# cc-switch-managed:` 标记）先被移除，被覆盖
+/// 的用户值从 `# cc-switch-user-values:` 中恢复，其余用户维护的配置（如 `[tui]`、
+/// `[projects."..."]`）原样保留；供应商片段与用户配置冲突时以片段为准，冲突的路径
+/// 记录在结果中，原值保存下来供下次切换时恢复。片段本身带有托管标记时（如完整的
+/// live 文件备份）沿用其中的托管路径。现有文件无法解析时返回错误，不会整体覆盖。
+pub fn merge_codex_config(existing: &str, fragment: &str) -> Result<CodexConfigMerge, AppError> {
+    let (fragment_body, fragment_state) = strip_managed_marker(fragment);
+    let patch = parse_document(&fragment_body)?;
+    let (existing_body, previous) = strip_managed_marker(existing);
+    let mut doc = parse_document(&existing_body)?;
+
+    match previous {
+        Some(previous) => {
+            for path in previous.paths.iter().filter(|path| !is_mcp_path(path)) {
+                remove_path(doc.as_table_mut(), path);
+            }
+            for (path, item) in previous.user_values {
+                set_path(doc.as_table_mut(), &path, item);
+            }
+        }
+        None => {
+            let legacy_provider = doc
+                .get("model_provider")
+                .and_then(toml_edit::Item::as_str)
+                .map(str::to_string);
+            for key in LEGACY_MANAGED_KEYS {
+                doc.remove(key);
+            }
+            if let Some(id) = legacy_provider {
+                remove_path(doc.as_table_mut(), &["model_providers".to_string(), id]);
+            }
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    let mut replaced = Vec::new();
+    merge_item(
+        doc.as_item_mut(),
+        patch.as_item(),
+        &mut Vec::new(),
+        &mut conflicts,
+        &mut replaced,
+    );
+
+    let (managed_paths, mut user_values) = match fragment_state {
+        Some(state) => (state.paths, state.user_values),
+        None => {
+            let mut paths = Vec::new();
+            leaf_paths(patch.as_item(), &mut Vec::new(), &mut paths);
+            (paths, Vec::new())
+        }
+    };
+    let managed_paths = managed_paths
+        .into_iter()
+        .filter(|path| !path.is_empty() && !is_mcp_path(path))
+        .collect::<Vec<_>>();
+    // 只保存托管路径上被覆盖的值；完整备份中的用户配置不需要恢复
+    for (path, item) in replaced {
+        if managed_paths.iter().any(|managed| overlaps(managed, &path))
+            && !user_values.iter().any(|(saved, _)| *saved == path)
+        {
+            user_values.push((path, item));
+        }
+    }
+    user_values.retain(|(path, _)| !is_mcp_path(path));
+
+    let body = doc.to_string();
+    let text = if managed_paths.is_empty() {
+        body
+    } else {
+        let mut paths = toml_edit::Array::new();
+        for path in &managed_paths {
+            paths.push(format_path(path));
+        }
+        let mut header = format!("{MANAGED_HEADER}\n{MANAGED_KEYS_PREFIX} {paths}\n");
+        if !user_values.is_empty() {
+            header.push_str(&format!(
+                "{USER_VALUES_PREFIX} {}\n",
+                encode_user_values(&user_values)
+            ));
+        }
+        format!("{header}{}", body.trim_start_matches('\n'))
+    };
+    Ok(CodexConfigMerge { text, conflicts })
+}
+
+/// 只保留 live config.toml 中由供应商托管的部分（没有托管标记时原样返回）
+///
+/// 切换前回填供应商配置时使用，避免把用户维护的配置复制进供应商。
+pub fn managed_fragment(text: &str) -> String {
+    let (body, Some(state)) = strip_managed_marker(text) else {
+        return text.to_string();
+    };
+    let Ok(doc) = parse_document(&body) else {
+        return text.to_string();
+    };
+    let mut fragment = toml_edit::DocumentMut::new();
+    let mcp_path = vec![MCP_SERVERS_KEY.to_string()];
+    for path in state.paths.iter().chain([&mcp_path]) {
+        if let Some(item) = get_path(&doc, path) {
+            set_path(fragment.as_table_mut(), path, item.clone());
+        }
+    }
+    fragment.to_string()
+}
+
+/// 把供应商片段合并到当前的 live config.toml，冲突时记录日志
+pub fn merge_into_live_config(fragment: &str) -> Result<String, AppError> {
+    let existing = read_codex_config_text()?;
+    let merged = merge_codex_config(&existing, fragment)?;
+    log_conflicts(&merged.conflicts);
+    Ok(merged.text)
+}
+
+fn log_conflicts(conflicts: &[String]) {
+    if !conflicts.is_empty() {
+        log::warn!(
+            "Codex config.toml 中的用户配置被供应商配置覆盖: {}",
+            conflicts.join(", ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_keeps_user_sections_and_reports_conflicts() {
+        let existing = r#"model = "old-model"
+model_provider = "old"
+
+[model_providers.old]
+base_url = "https://old.example"
+
+[tui]
+notifications = true
+
+[projects."/repo"]
+trust_level = "trusted"
+"#;
+        let fragment = r#"model = "gpt-5"
+model_provider = "relay"
+
+[model_providers.relay]
+base_url = "https://relay.example"
+
+[tui]
+notifications = false
+"#;
+
+        let merged = merge_codex_config(existing, fragment).expect("merge");
+        let doc = merged
+            .text
+            .parse::<toml_edit::DocumentMut>()
+            .expect("valid toml");
+        assert_eq!(doc["model"].as_str(), Some("gpt-5"));
+        assert!(doc["model_providers"].get("old").is_none());
+        assert_eq!(
+            doc["projects"]["/repo"]["trust_level"].as_str(),
+            Some("trusted")
+        );
+        assert_eq!(doc["tui"]["notifications"].as_bool(), Some(false));
+        assert_eq!(merged.conflicts, vec!["tui.notifications"]);
+        assert!(merged.text.contains(
+            r#"# cc-switch-managed: ["model", "model_provider", "model_providers.relay.base_url", "tui.notifications"]"#
+        ));
+
+        // 再次切换时只移除托管的叶子路径并恢复被覆盖的用户值，[projects] 与 [tui] 都保留
+        let next = merge_codex_config(&merged.text, "model = \"o3\"\n").expect("merge again");
+        let doc = next
+            .text
+            .parse::<toml_edit::DocumentMut>()
+            .expect("valid toml");
+        assert_eq!(doc["model"].as_str(), Some("o3"));
+        assert!(doc.get("model_providers").is_none());
+        assert_eq!(doc["tui"]["notifications"].as_bool(), Some(true));
+        assert!(doc.get("projects").is_some());
+        assert!(next.conflicts.is_empty());
+        assert!(!next.text.contains(USER_VALUES_PREFIX));
+
+        let fragment_only = managed_fragment(&next.text);
+        assert_eq!(fragment_only.trim(), "model = \"o3\"");
+    }
+
+    #[test]
+    fn merge_keeps_user_values_inside_provider_tables() {
+        let existing = r#"[tui]
+notifications = true
+theme = "dark"
+"#;
+        let merged = merge_codex_config(existing, "[tui]\ntheme = \"dark\"\n").expect("merge");
+        assert!(merged.conflicts.is_empty());
+
+        // 供应商片段不再包含 [tui] 时，用户原有的同名值与同表的其他键都不会丢失
+        let next = merge_codex_config(&merged.text, "model = \"o3\"\n").expect("merge again");
+        let doc = next
+            .text
+            .parse::<toml_edit::DocumentMut>()
+            .expect("valid toml");
+        assert_eq!(doc["tui"]["notifications"].as_bool(), Some(true));
+        assert_eq!(doc["tui"]["theme"].as_str(), Some("dark"));
+    }
+
+    #[test]
+    fn legacy_migration_removes_only_the_active_model_provider() {
+        let existing = r#"model_provider = "relay"
+
+[model_providers.relay]
+base_url = "https://relay.example"
+
+[model_providers.local]
+base_url = "http://localhost:8080"
+"#;
+        let merged = merge_codex_config(existing, "model = \"o3\"\n").expect("merge");
+        let doc = merged
+            .text
+            .parse::<toml_edit::DocumentMut>()
+            .expect("valid toml");
+        assert!(doc.get("model_provider").is_none());
+        assert!(doc["model_providers"].get("relay").is_none());
+        assert_eq!(
+            doc["model_providers"]["local"]["base_url"].as_str(),
+            Some("http://localhost:8080")
+        );
+    }
+
+    #[test]
+    fn merge_refuses_to_replace_an_unparseable_file() {
+        let err = merge_codex_config("[tui\nnotifications = true\n", "model = \"o3\"\n")
+            .expect_err("broken config should not be overwritten");
+        assert!(matches!(err, AppError::Config(_)));
+    }
+}
@@ -0,0 +1,52 @@
+//! 自动化规则命令
+
+use tauri::{AppHandle, State};
+
+use crate::services::automation_rules::AutomationRule;
+use crate::store::AppState;
+
+/// 获取全部自动化规则
+#[tauri::command]
+pub fn get_automation_rules(state: State<'_, AppState>) -> Result<Vec<AutomationRule>, String> {
+    state.db.get_automation_rules().map_err(|e| e.to_string())
+}
+
+/// 新增或更新自动化规则（`id` 为空时新增）
+#[tauri::command]
+pub fn save_automation_rule(
+    state: State<'_, AppState>,
+    rule: AutomationRule,
+) -> Result<AutomationRule, String> {
+    state
+        .db
+        .save_automation_rule(&rule)
+        .map_err(|e| e.to_string())
+}
+
+/// 删除自动化规则
+#[tauri::command]
+pub fn delete_automation_rule(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+    state
+        .db
+        .delete_automation_rule(&id)
+        .map_err(|e| e.to_string())
+}
+
+/// 立即执行一条规则的动作（不检查触发条件），返回执行结果
+#[tauri::command]
+pub async fn run_automation_rule(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<String, String> {
+    let rule = state
+        .db
+        .get_automation_rules()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|rule| rule.id == id)
+        .ok_or_else(|| format!("自动化规则不存在: {id}"))?;
+    crate::rule_engine::run_rule(&app, &rule)
+        .await
+        .map_err(|e| e.to_string())
+}
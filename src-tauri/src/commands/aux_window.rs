@@ -0,0 +1,13 @@
+//! 辅助窗口命令
+
+use tauri::AppHandle;
+
+use crate::aux_windows::{self, AuxWindow};
+
+/// 打开辅助窗口（MCP 管理、请求日志、用量统计）；已打开时聚焦
+#[tauri::command]
+pub fn open_aux_window(app: AppHandle, window: AuxWindow) -> Result<bool, String> {
+    aux_windows::open(&app, window)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,254 @@
+//! 本地备份、快照与撤销相关命令
+
+use tauri::State;
+
+use crate::database::{DatabaseEncryptionStatus, DatabaseProfile, MaintenanceReport};
+use crate::error::AppError;
+use crate::services::backup::{
+    BackupEntry, BackupRestoreResult, BackupService, BackupVerification,
+};
+use crate::services::snapshot::{SnapshotInfo, SnapshotRestoreResult, SnapshotService};
+use crate::services::undo::{self, UndoSummary};
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 列出本地自动备份
+#[tauri::command]
+pub async fn list_backups() -> Result<Vec<BackupEntry>, String> {
+    tauri::async_runtime::spawn_blocking(BackupService::list_backups)
+        .await
+        .map_err(|e| format!("读取备份列表失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// 立即创建一份备份，并按保留策略清理旧备份
+#[tauri::command]
+pub async fn create_backup_now(state: State<'_, AppState>) -> Result<BackupEntry, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let entry = BackupService::create_backup(&db)?;
+        let config = crate::settings::get_settings().auto_backup;
+        BackupService::prune_backups(config.keep_daily, config.keep_weekly)?;
+        Ok::<_, AppError>(entry)
+    })
+    .await
+    .map_err(|e| format!("创建备份失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 恢复指定备份（数据库 + live 配置）
+#[tauri::command]
+pub async fn restore_backup(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<BackupRestoreResult, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || BackupService::restore_backup(&db, &id))
+        .await
+        .map_err(|e| format!("恢复备份失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// 校验备份或快照的完整性（SHA-256），不做任何恢复
+#[tauri::command]
+pub async fn verify_backup(id: String) -> Result<BackupVerification, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        if SnapshotService::exists(&id) {
+            SnapshotService::verify_snapshot(&id)
+        } else {
+            BackupService::verify_backup(&id)
+        }
+    })
+    .await
+    .map_err(|e| format!("校验备份失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 创建完整应用状态快照（供应商、MCP、设置与 live 配置）
+#[tauri::command]
+pub async fn create_snapshot(
+    label: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<SnapshotInfo, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        SnapshotService::create_snapshot(&db, label.as_deref().unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("创建快照失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 列出应用状态快照
+#[tauri::command]
+pub async fn list_snapshots() -> Result<Vec<SnapshotInfo>, String> {
+    tauri::async_runtime::spawn_blocking(SnapshotService::list_snapshots)
+        .await
+        .map_err(|e| format!("读取快照列表失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// 恢复应用状态快照
+#[tauri::command]
+pub async fn restore_snapshot(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<SnapshotRestoreResult, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || SnapshotService::restore_snapshot(&db, &id))
+        .await
+        .map_err(|e| format!("恢复快照失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// 删除应用状态快照
+#[tauri::command]
+pub async fn delete_snapshot(id: String) -> Result<bool, String> {
+    SnapshotService::delete_snapshot(&id)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 撤销本次运行中最近一次删除供应商、删除 MCP 服务器或修改设置的操作
+///
+/// 没有可撤销的操作时返回 `null`。
+#[tauri::command]
+pub async fn undo_last_operation(
+    state: State<'_, AppState>,
+) -> Result<Option<UndoSummary>, String> {
+    undo::undo_last_operation(&state).map_err(|e| e.to_string())
+}
+
+/// 获取数据库静态加密状态
+#[tauri::command]
+pub async fn get_database_encryption_status(
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    Ok(state.db.encryption_status())
+}
+
+/// 启用数据库静态加密（口令保存在系统钥匙串）
+#[tauri::command]
+pub async fn enable_database_encryption(
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.enable_encryption(&passphrase)?;
+        Ok::<_, AppError>(db.encryption_status())
+    })
+    .await
+    .map_err(|e| format!("启用数据库加密失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 使用主密码启用数据库静态加密（密码不保存，每次启动需解锁）
+#[tauri::command]
+pub async fn enable_database_master_password(
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.enable_master_password(&password)?;
+        Ok::<_, AppError>(db.encryption_status())
+    })
+    .await
+    .map_err(|e| format!("设置主密码失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 输入主密码解锁数据库，并执行锁定期间推迟的启动导入
+#[tauri::command]
+pub async fn unlock_database(
+    password: String,
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.unlock(&password)?;
+        let status = db.encryption_status();
+        crate::gui::run_startup_imports(&AppState::new(db));
+        Ok::<_, AppError>(status)
+    })
+    .await
+    .map_err(|e| format!("解锁数据库失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 锁定数据库：写回加密文件并清除内存中的数据与主密钥
+#[tauri::command]
+pub async fn lock_database(state: State<'_, AppState>) -> Result<DatabaseEncryptionStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.lock()?;
+        Ok::<_, AppError>(db.encryption_status())
+    })
+    .await
+    .map_err(|e| format!("锁定数据库失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 关闭数据库静态加密，恢复为明文数据库文件
+#[tauri::command]
+pub async fn disable_database_encryption(
+    state: State<'_, AppState>,
+) -> Result<DatabaseEncryptionStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.disable_encryption()?;
+        Ok::<_, AppError>(db.encryption_status())
+    })
+    .await
+    .map_err(|e| format!("关闭数据库加密失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 数据库维护：校验完整性、清理孤立记录与超出保留策略的备份，压缩数据库并报告回收的空间
+#[tauri::command]
+pub async fn maintain_database(state: State<'_, AppState>) -> Result<MaintenanceReport, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = db.maintain()?;
+        if report.integrity_ok {
+            let size = |entries: Vec<BackupEntry>| entries.iter().map(|e| e.size).sum::<u64>();
+            let before = size(BackupService::list_backups()?);
+            let config = crate::settings::get_settings().auto_backup;
+            let removed = BackupService::prune_backups(config.keep_daily, config.keep_weekly)?;
+            let after = size(BackupService::list_backups()?);
+            report.add_removed_backups(removed, before.saturating_sub(after));
+        }
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("数据库维护失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 列出数据库配置档（默认配置档与 `~/.cc-switch/profiles/` 下的配置档）
+#[tauri::command]
+pub async fn list_database_profiles() -> Result<Vec<DatabaseProfile>, String> {
+    Ok(crate::database::list_profiles())
+}
+
+/// 切换数据库配置档（不存在时创建），并把新配置档的当前供应商写入 live 配置
+#[tauri::command]
+pub async fn switch_profile(
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<DatabaseProfile>, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        db.switch_profile(&name)?;
+        if db.is_locked() {
+            log::info!("配置档 {name} 使用主密码加密，解锁后再同步 live 配置");
+        } else if let Err(e) = ProviderService::sync_current_to_live(&AppState::new(db)) {
+            log::warn!("切换配置档后同步 live 配置失败: {e}");
+        }
+        Ok::<_, AppError>(crate::database::list_profiles())
+    })
+    .await
+    .map_err(|e| format!("切换配置档失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
@@ -1,13 +1,17 @@
 #![allow(non_snake_case)]
 
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_opener::OpenerExt;
 
 use crate::app_config::AppType;
+use crate::audit_log::{self, AuditLog};
 use crate::codex_config;
 use crate::config::{self, get_claude_settings_path, ConfigStatus};
-use crate::settings;
+use crate::i18n::{self, Language};
+use crate::services::config_search::{self, ConfigSearchMatch};
+use crate::services::wsl::{self, WslConfigDirCandidate};
+use crate::store::AppState;
 
 /// 获取 Claude Code 配置状态
 #[tauri::command]
@@ -18,15 +22,11 @@ pub async fn get_claude_config_status() -> Result<ConfigStatus, String> {
 use std::str::FromStr;
 
 fn invalid_json_format_error(error: serde_json::Error) -> String {
-    let lang = settings::get_settings()
-        .language
-        .unwrap_or_else(|| "zh".to_string());
-
-    match lang.as_str() {
-        "en" => format!("Invalid JSON format: {error}"),
-        "ja" => format!("JSON形式が無効です: {error}"),
-        _ => format!("无效的 JSON 格式: {error}"),
-    }
+    i18n::format(
+        Language::current(),
+        "error.invalidJson",
+        &[("error", &error.to_string())],
+    )
 }
 
 #[tauri::command]
@@ -63,6 +63,29 @@ pub async fn get_config_status(app: String) -> Result<ConfigStatus, String> {
     }
 }
 
+/// 探测 WSL 发行版中的配置目录候选项（仅 Windows 有结果）
+#[tauri::command]
+pub async fn detect_wsl_config_dirs() -> Result<Vec<WslConfigDirCandidate>, String> {
+    tauri::async_runtime::spawn_blocking(wsl::detect_wsl_config_dirs)
+        .await
+        .map_err(|e| format!("探测 WSL 配置目录失败: {e}"))
+}
+
+/// 切换前预检配置目录是否可访问（网络/WSL 路径可能暂时不可用）
+#[tauri::command]
+pub async fn check_config_dir_reachable(app: String) -> Result<bool, String> {
+    let dir = match AppType::from_str(&app).map_err(|e| e.to_string())? {
+        AppType::Claude => config::get_claude_config_dir(),
+        AppType::Codex => codex_config::get_codex_config_dir(),
+        AppType::Gemini => crate::gemini_config::get_gemini_dir(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_dir(),
+    };
+    crate::network_fs::check_reachable(&dir, std::time::Duration::from_secs(3))
+        .await
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
 /// 获取 Claude Code 配置文件路径
 #[tauri::command]
 pub async fn get_claude_code_config_path() -> Result<String, String> {
@@ -160,6 +183,26 @@ pub async fn open_app_config_folder(handle: AppHandle) -> Result<bool, String> {
     Ok(true)
 }
 
+/// 读取 live 配置写入审计日志（最新的在前，默认 200 条），并校验哈希链
+#[tauri::command]
+pub async fn get_audit_log(limit: Option<usize>) -> Result<AuditLog, String> {
+    audit_log::read_audit_log(limit.unwrap_or(200)).map_err(|e| e.to_string())
+}
+
+/// 在全部供应商与 MCP 服务器配置中搜索文本，返回每处匹配的位置（默认最多 200 条）
+#[tauri::command]
+pub async fn search_configs(
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ConfigSearchMatch>, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || config_search::search_configs(&db, &query, limit))
+        .await
+        .map_err(|e| format!("搜索配置失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
 /// 获取 Claude 通用配置片段（已废弃，使用 get_common_config_snippet）
 #[tauri::command]
 pub async fn get_claude_common_config_snippet(
@@ -1,7 +1,8 @@
 use crate::deeplink::{
     import_mcp_from_deeplink, import_prompt_from_deeplink, import_provider_from_deeplink,
-    import_skill_from_deeplink, parse_deeplink_url, DeepLinkImportRequest,
+    import_skill_from_deeplink, lint_deeplink_keys, parse_deeplink_url, DeepLinkImportRequest,
 };
+use crate::services::secret_lint::KeyWarning;
 use crate::store::AppState;
 use tauri::State;
 
@@ -22,6 +23,12 @@ pub fn merge_deeplink_config(
     crate::deeplink::parse_and_merge_config(&request).map_err(|e| e.to_string())
 }
 
+/// Check the API keys in a deep link request (malformed, placeholder, wrong vendor, ...)
+#[tauri::command]
+pub fn lint_deeplink_request(request: DeepLinkImportRequest) -> Result<Vec<KeyWarning>, String> {
+    lint_deeplink_keys(&request).map_err(|e| e.to_string())
+}
+
 /// Import a provider from a deep link request (legacy, kept for compatibility)
 #[tauri::command]
 pub fn import_from_deeplink(
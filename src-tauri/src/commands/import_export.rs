@@ -2,11 +2,21 @@
 
 use serde_json::{json, Value};
 use std::path::PathBuf;
+use std::str::FromStr;
 use tauri::State;
 use tauri_plugin_dialog::DialogExt;
 
+use crate::app_config::AppType;
+use crate::database::BatchOp;
 use crate::error::AppError;
+use crate::services::data_bundle::{self, BundleImportResult, BundleManifest};
+use crate::services::database_export::{self, DumpFormat};
+use crate::services::export_rules::ExcludeRules;
+use crate::services::external_import::{self, ExternalImportReport};
+use crate::services::import_merge::{self, ImportMergeReport, MergeStrategy};
+use crate::services::legacy_migration::{self, LegacyMigrationReport};
 use crate::services::provider::ProviderService;
+use crate::services::ConfigService;
 use crate::store::AppState;
 
 /// 导出数据库为 SQL 备份
@@ -15,6 +25,9 @@ pub async fn export_config_to_file(
     #[allow(non_snake_case)] filePath: String,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
+    crate::os_auth::ensure_secret_access("导出包含 API Key 的配置")
+        .await
+        .map_err(|e| e.to_string())?;
     let db = state.db.clone();
     tauri::async_runtime::spawn_blocking(move || {
         let target_path = PathBuf::from(&filePath);
@@ -42,25 +55,215 @@ pub async fn import_config_from_file(
         let path_buf = PathBuf::from(&filePath);
         let backup_id = db.import_sql(&path_buf)?;
 
-        // 导入后同步当前供应商到各自的 live 配置
+        // 导入后同步当前供应商到 live 配置，并重新加载设置缓存
         let app_state = AppState::new(db_for_state);
-        if let Err(err) = ProviderService::sync_current_to_live(&app_state) {
-            log::warn!("导入后同步 live 配置失败: {err}");
+        ConfigService::refresh_after_database_restore(&app_state);
+
+        Ok::<_, AppError>(json!({
+            "success": true,
+            "message": "SQL imported successfully",
+            "backupId": backup_id
+        }))
+    })
+    .await
+    .map_err(|e| format!("导入配置失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 转储数据库为规范化 JSON 或 SQL；提供 `filePath` 时同时写入该文件。
+/// JSON 转储会应用设置中的导出排除规则
+#[tauri::command]
+pub async fn export_database(
+    format: DumpFormat,
+    filePath: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    crate::os_auth::ensure_secret_access("导出包含 API Key 的数据库")
+        .await
+        .map_err(|e| e.to_string())?;
+    let db = state.db.clone();
+    let exclude = ExcludeRules::parse(&crate::settings::get_settings().export_exclude_rules);
+    tauri::async_runtime::spawn_blocking(move || {
+        let content = database_export::export_database(&db, format, &exclude)?;
+        if let Some(path) = filePath.filter(|p| !p.trim().is_empty()) {
+            crate::config::write_text_file(&PathBuf::from(path), &content)?;
         }
+        Ok::<_, AppError>(content)
+    })
+    .await
+    .map_err(|e| format!("导出数据库失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
 
-        // 重新加载设置到内存缓存，确保导入的设置生效
-        if let Err(err) = crate::settings::reload_settings() {
-            log::warn!("导入后重载设置失败: {err}");
+/// 导出便携数据包（数据库 + 设置），用于迁移到新设备
+#[tauri::command]
+pub async fn export_bundle(
+    filePath: String,
+    state: State<'_, AppState>,
+) -> Result<BundleManifest, String> {
+    crate::os_auth::ensure_secret_access("导出包含 API Key 的数据包")
+        .await
+        .map_err(|e| e.to_string())?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        data_bundle::export_bundle(&db, &PathBuf::from(&filePath))
+    })
+    .await
+    .map_err(|e| format!("导出数据包失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 导入便携数据包，替换数据库与设置（导入前检查版本并自动备份）
+#[tauri::command]
+pub async fn import_bundle(
+    filePath: String,
+    state: State<'_, AppState>,
+) -> Result<BundleImportResult, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = data_bundle::import_bundle(&db, &PathBuf::from(&filePath))?;
+
+        let app_state = AppState::new(db);
+        ConfigService::refresh_after_database_restore(&app_state);
+        Ok::<_, AppError>(result)
+    })
+    .await
+    .map_err(|e| format!("导入数据包失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 迁移旧版 `~/.cc-switch/config.json` 中的供应商、自定义端点与当前供应商
+///
+/// `dryRun` 为 true 时只返回报告，不做任何修改。
+#[tauri::command]
+pub async fn migrate_legacy_config(
+    dryRun: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<LegacyMigrationReport, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let dry_run = dryRun.unwrap_or(false);
+        let report = legacy_migration::migrate_legacy_config(&db, dry_run)?;
+        if !dry_run && report.found {
+            let app_state = AppState::new(db);
+            ConfigService::refresh_after_database_restore(&app_state);
         }
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("迁移旧版配置失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 从其他切换工具的配置文件（JSON/YAML/shell 脚本）迁移供应商
+#[tauri::command]
+pub fn import_external_config(
+    filePath: String,
+    app: Option<String>,
+    dryRun: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ExternalImportReport, String> {
+    let app_type = app
+        .filter(|a| !a.trim().is_empty())
+        .map(|a| AppType::from_str(&a))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    external_import::import_external_config(
+        state.inner(),
+        &PathBuf::from(&filePath),
+        app_type,
+        dryRun.unwrap_or(false),
+    )
+    .map_err(|e| e.to_string())
+}
 
+/// 按合并策略从 SQL 备份导入供应商（不整库替换），返回逐项报告
+#[tauri::command]
+pub async fn import_config_with_strategy(
+    #[allow(non_snake_case)] filePath: String,
+    strategy: MergeStrategy,
+    state: State<'_, AppState>,
+) -> Result<ImportMergeReport, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path_buf = PathBuf::from(&filePath);
+        let report = import_merge::import_with_strategy(&db, &path_buf, strategy)?;
+
+        let app_state = AppState::new(db);
+        ConfigService::refresh_after_database_restore(&app_state);
+        Ok::<_, AppError>(report)
+    })
+    .await
+    .map_err(|e| format!("合并导入失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 在一个事务中批量新增/更新/删除供应商与 MCP 服务器，任何一项失败时全部回滚
+///
+/// 每项操作与单独的新增/编辑/删除一样受只读模式、锁定供应商等检查约束。
+#[tauri::command]
+pub async fn apply_batch(ops: Vec<BatchOp>, state: State<'_, AppState>) -> Result<usize, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let app_state = AppState::new(db);
+        let applied = ProviderService::apply_batch(&app_state, ops)?;
+        ConfigService::refresh_after_database_restore(&app_state);
+        Ok::<_, AppError>(applied)
+    })
+    .await
+    .map_err(|e| format!("批量操作失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 导出为口令加密的备份文件
+#[tauri::command]
+pub async fn export_encrypted_config_to_file(
+    #[allow(non_snake_case)] filePath: String,
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    crate::os_auth::ensure_secret_access("导出包含 API Key 的加密备份")
+        .await
+        .map_err(|e| e.to_string())?;
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let target_path = PathBuf::from(&filePath);
+        db.export_encrypted(&target_path, &passphrase)?;
         Ok::<_, AppError>(json!({
             "success": true,
-            "message": "SQL imported successfully",
+            "message": "Encrypted export created successfully",
+            "filePath": filePath
+        }))
+    })
+    .await
+    .map_err(|e| format!("导出加密备份失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 从口令加密的备份文件导入数据库
+#[tauri::command]
+pub async fn import_encrypted_config_from_file(
+    #[allow(non_snake_case)] filePath: String,
+    passphrase: String,
+    state: State<'_, AppState>,
+) -> Result<Value, String> {
+    let db = state.db.clone();
+    let db_for_state = db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let path_buf = PathBuf::from(&filePath);
+        let backup_id = db.import_encrypted(&path_buf, &passphrase)?;
+
+        let app_state = AppState::new(db_for_state);
+        ConfigService::refresh_after_database_restore(&app_state);
+
+        Ok::<_, AppError>(json!({
+            "success": true,
+            "message": "Encrypted backup imported successfully",
             "backupId": backup_id
         }))
     })
     .await
-    .map_err(|e| format!("导入配置失败: {e}"))?
+    .map_err(|e| format!("导入加密备份失败: {e}"))?
     .map_err(|e: AppError| e.to_string())
 }
 
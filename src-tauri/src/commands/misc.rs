@@ -1,7 +1,7 @@
 #![allow(non_snake_case)]
 
 use crate::app_config::AppType;
-use crate::init_status::{InitErrorPayload, SkillsMigrationPayload};
+use crate::init_status::{InitErrorPayload, RecoveryPayload, SkillsMigrationPayload};
 use crate::services::ProviderService;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -79,6 +79,13 @@ pub async fn get_skills_migration_result() -> Result<Option<SkillsMigrationPaylo
     Ok(crate::init_status::take_skills_migration_result())
 }
 
+/// 获取启动时损坏文件（数据库、设置）的隔离与恢复记录。
+/// 只返回一次，之后返回空列表，用于前端提示数据已从备份恢复。
+#[tauri::command]
+pub async fn get_recovery_events() -> Result<Vec<RecoveryPayload>, String> {
+    Ok(crate::init_status::take_recoveries())
+}
+
 #[derive(serde::Serialize)]
 pub struct ToolVersion {
     name: String,
@@ -1,5 +1,8 @@
 #![allow(non_snake_case)]
 
+mod automation_rules;
+mod aux_window;
+mod backup;
 mod config;
 mod deeplink;
 mod env;
@@ -12,11 +15,17 @@ mod plugin;
 mod prompt;
 mod provider;
 mod proxy;
+mod quick_switch;
+mod runtime;
 mod settings;
 pub mod skill;
 mod stream_check;
+mod sync;
 mod usage;
 
+pub use automation_rules::*;
+pub use aux_window::*;
+pub use backup::*;
 pub use config::*;
 pub use deeplink::*;
 pub use env::*;
@@ -29,7 +38,10 @@ pub use plugin::*;
 pub use prompt::*;
 pub use provider::*;
 pub use proxy::*;
+pub use quick_switch::*;
+pub use runtime::*;
 pub use settings::*;
 pub use skill::*;
 pub use stream_check::*;
+pub use sync::*;
 pub use usage::*;
@@ -19,9 +19,12 @@ pub async fn read_claude_plugin_config() -> Result<Option<String>, String> {
     crate::claude_plugin::read_claude_config().map_err(|e| e.to_string())
 }
 
-/// Claude 插件：写入/清除固定配置
+/// Claude 插件：写入/清除固定配置（暂停期间不写入）
 #[tauri::command]
 pub async fn apply_claude_plugin_config(official: bool) -> Result<bool, String> {
+    if crate::runtime_state::is_paused() {
+        return Ok(false);
+    }
     if official {
         crate::claude_plugin::clear_claude_config().map_err(|e| e.to_string())
     } else {
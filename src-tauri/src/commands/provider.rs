@@ -2,8 +2,15 @@ use indexmap::IndexMap;
 use tauri::State;
 
 use crate::app_config::AppType;
+use crate::database::{ProviderPage, ProviderSort};
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::services::balance::{self, ProviderBalance};
+use crate::services::key_rotation::{KeyRotationResult, KeyRotationService};
+use crate::services::live_secrets::{self, LiveSecretFinding, VaultLiveSecretsResult};
+use crate::services::provider::{AdoptLiveResult, EnvShell, ProviderHistoryEntry};
+use crate::services::secret_access;
+use crate::services::switch_backup::{SwitchBackupService, UndoSwitchResult};
 use crate::services::{EndpointLatency, ProviderService, ProviderSortUpdate, SpeedtestService};
 use crate::store::AppState;
 use std::str::FromStr;
@@ -18,6 +25,26 @@ pub fn get_providers(
     ProviderService::list(state.inner(), app_type).map_err(|e| e.to_string())
 }
 
+/// 分页获取供应商（默认每页 50 条，按自定义顺序）
+#[tauri::command]
+pub fn list_providers(
+    state: State<'_, AppState>,
+    app: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    sort: Option<ProviderSort>,
+) -> Result<ProviderPage, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::list_page(
+        state.inner(),
+        app_type,
+        offset.unwrap_or(0),
+        limit.unwrap_or(50),
+        sort.unwrap_or_default(),
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// 获取当前供应商ID
 #[tauri::command]
 pub fn get_current_provider(state: State<'_, AppState>, app: String) -> Result<String, String> {
@@ -60,6 +87,29 @@ pub fn delete_provider(
         .map_err(|e| e.to_string())
 }
 
+/// 获取供应商配置历史版本（含相对当前配置的差异）
+#[tauri::command]
+pub fn get_provider_history(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<Vec<ProviderHistoryEntry>, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::get_history(state.inner(), app_type, &id).map_err(|e| e.to_string())
+}
+
+/// 将供应商配置恢复到指定历史版本
+#[tauri::command]
+pub fn restore_provider_revision(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    rev: i64,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::restore_revision(state.inner(), app_type, &id, rev).map_err(|e| e.to_string())
+}
+
 /// Remove provider from live config only (for additive mode apps like OpenCode)
 /// Does NOT delete from database - provider remains in the list
 #[tauri::command]
@@ -96,6 +146,95 @@ pub fn switch_provider(
         .map_err(|e| e.to_string())
 }
 
+/// 撤销最近一次切换：按字节恢复切换前的 live 配置文件
+#[tauri::command]
+pub fn undo_last_switch(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<UndoSwitchResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    SwitchBackupService::undo_last_switch(&state.db, &app_type).map_err(|e| e.to_string())
+}
+
+/// 重新应用当前供应商（live 配置被外部改写后使用），返回供应商 ID
+#[tauri::command]
+pub fn reapply_live_config(state: State<'_, AppState>, app: String) -> Result<String, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    crate::live_watcher::reapply_current(&state, app_type).map_err(|e| e.to_string())
+}
+
+/// 以指定供应商启动应用的 CLI（密钥仅注入该进程的环境变量），返回进程 ID
+#[tauri::command]
+pub fn launch_app(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+    args: Option<Vec<String>>,
+) -> Result<u32, String> {
+    let (provider, mut cmd) = crate::services::launch::command(
+        &state,
+        app_type.clone(),
+        Some(&provider_id),
+        &args.unwrap_or_default(),
+    )
+    .map_err(|e| e.to_string())?;
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| crate::services::launch::spawn_error(&app_type, e).to_string())?;
+    let pid = child.id();
+    log::info!(
+        "已以供应商 {} 启动 {}（pid {pid}）",
+        provider.name,
+        crate::services::launch::program(&app_type)
+    );
+    // 回收子进程，避免留下僵尸进程
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(pid)
+}
+
+/// 生成供应商的会话级环境变量脚本（端点、模型与密钥），供 tmux 窗格或单个终端 `source`
+#[tauri::command]
+pub async fn print_env(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+    shell: Option<String>,
+) -> Result<String, String> {
+    let shell = match shell.as_deref() {
+        Some(shell) => EnvShell::from_str(shell).map_err(|e| e.to_string())?,
+        None => EnvShell::Posix,
+    };
+    let provider = crate::services::launch::resolve(&state, &app_type, Some(&provider_id))
+        .map_err(|e| e.to_string())?;
+    crate::os_auth::ensure_secret_access("导出 API Key 环境变量")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(crate::services::launch::session_script(
+        &app_type, &provider, shell,
+    ))
+}
+
+/// 在项目目录生成 direnv `.envrc`（不含密钥），返回文件路径
+#[tauri::command]
+pub fn write_envrc(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+    dir: String,
+) -> Result<String, String> {
+    let provider = crate::services::launch::resolve(&state, &app_type, Some(&provider_id))
+        .map_err(|e| e.to_string())?;
+    let path = crate::services::launch::write_envrc(
+        &crate::settings::resolve_override_path(&dir),
+        &app_type,
+        &provider,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
 fn import_default_config_internal(state: &AppState, app_type: AppType) -> Result<bool, AppError> {
     ProviderService::import_default_config(state, app_type)
 }
@@ -108,6 +247,126 @@ pub fn import_default_config_test_hook(
     import_default_config_internal(state, app_type)
 }
 
+/// 将当前 live 配置收录为供应商（已有相同配置的供应商时不重复创建）
+#[tauri::command]
+pub fn adopt_live_config(
+    state: State<'_, AppState>,
+    app: String,
+    name: Option<String>,
+) -> Result<AdoptLiveResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    ProviderService::adopt_live_config(state.inner(), app_type, name).map_err(|e| e.to_string())
+}
+
+/// 复制供应商密钥到剪贴板，按设置在若干秒后自动清除；返回清除倒计时秒数（0 表示不清除）
+#[tauri::command]
+pub async fn copy_secret_to_clipboard(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    field: Option<String>,
+) -> Result<u32, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let field = field.unwrap_or_else(|| secret_access::API_KEY_FIELD.to_string());
+    let secret = secret_access::provider_secret(state.inner(), &app_type, &provider_id, &field)
+        .map_err(|e| e.to_string())?;
+    crate::os_auth::ensure_secret_access("复制 API Key")
+        .await
+        .map_err(|e| e.to_string())?;
+    let clear_after = crate::settings::get_settings().clipboard_clear_secs;
+    secret_access::copy_with_auto_clear(&handle, secret, clear_after).map_err(|e| e.to_string())?;
+    Ok(clear_after)
+}
+
+/// 读取供应商密钥原文（开启系统身份验证时需先通过验证）
+#[tauri::command]
+pub async fn reveal_provider_secret(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    field: Option<String>,
+) -> Result<String, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let field = field.unwrap_or_else(|| secret_access::API_KEY_FIELD.to_string());
+    let secret = secret_access::provider_secret(state.inner(), &app_type, &provider_id, &field)
+        .map_err(|e| e.to_string())?;
+    crate::os_auth::ensure_secret_access("查看 API Key")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(secret)
+}
+
+/// 轮换供应商 API Key：校验新 Key 后更新供应商（当前供应商同时更新 live 配置），旧配置保留为历史版本
+#[tauri::command]
+pub async fn rotate_key(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    new_key: String,
+) -> Result<KeyRotationResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    KeyRotationService::rotate(state.inner(), app_type, &provider_id, &new_key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 生成导出供应商密钥的环境变量脚本（`shell`: posix / powershell，默认 posix）
+#[tauri::command]
+pub async fn get_provider_env_script(
+    state: State<'_, AppState>,
+    app: String,
+    provider_id: String,
+    shell: Option<String>,
+) -> Result<String, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let shell = match shell.as_deref() {
+        Some(shell) => EnvShell::from_str(shell).map_err(|e| e.to_string())?,
+        None => EnvShell::Posix,
+    };
+    let script = ProviderService::env_script(state.inner(), app_type, &provider_id, shell)
+        .map_err(|e| e.to_string())?;
+    crate::os_auth::ensure_secret_access("导出 API Key 环境变量")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(script)
+}
+
+/// 扫描各应用 live 配置中的明文密钥，标记不属于任何供应商的未托管密钥
+#[tauri::command]
+pub fn scan_live_secrets(state: State<'_, AppState>) -> Result<Vec<LiveSecretFinding>, String> {
+    live_secrets::scan(state.inner()).map_err(|e| e.to_string())
+}
+
+/// 托管指定应用 live 配置中的未知密钥（收编为供应商并保存到系统钥匙串）
+#[tauri::command]
+pub fn vault_live_secrets(
+    state: State<'_, AppState>,
+    app: String,
+) -> Result<VaultLiveSecretsResult, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    live_secrets::vault(state.inner(), app_type).map_err(|e| e.to_string())
+}
+
+/// 锁定或解锁供应商（锁定后不能删除、不能修改密钥；开启系统身份验证时解锁需先通过验证）
+#[tauri::command]
+pub async fn set_provider_locked(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    locked: bool,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    if !locked {
+        crate::os_auth::ensure_secret_access("解锁受保护的供应商")
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    ProviderService::set_locked(state.inner(), app_type, &id, locked)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
 /// 导入当前配置为默认供应商
 #[tauri::command]
 pub fn import_default_config(state: State<'_, AppState>, app: String) -> Result<bool, String> {
@@ -129,6 +388,24 @@ pub async fn queryProviderUsage(
         .map_err(|e| e.to_string())
 }
 
+/// 查询中转站余额（使用供应商的余额查询配置）
+#[tauri::command]
+pub async fn get_provider_balance(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+) -> Result<ProviderBalance, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    let provider = state
+        .db
+        .get_provider_by_id(&id, app_type.as_str())
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("供应商 {id} 不存在"))?;
+    balance::query_balance(&app_type, &provider)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 测试用量脚本（使用当前编辑器中的脚本，不保存）
 #[allow(non_snake_case)]
 #[allow(clippy::too_many_arguments)]
@@ -0,0 +1,52 @@
+//! 快速切换（命令面板）命令
+
+use std::str::FromStr;
+
+use tauri::{AppHandle, State};
+
+use crate::app_config::AppType;
+use crate::services::quick_switch::{self, QuickSwitchCandidate};
+use crate::store::AppState;
+
+/// 按查询模糊匹配所有应用的供应商（查询为空时按置顶与最近使用排序）
+#[tauri::command]
+pub fn quick_switch_candidates(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<QuickSwitchCandidate>, String> {
+    quick_switch::candidates(
+        state.inner(),
+        &query,
+        limit.unwrap_or(quick_switch::DEFAULT_LIMIT),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 切换到候选项对应的供应商
+#[tauri::command]
+pub async fn execute_quick_switch(app: AppHandle, candidate_id: String) -> Result<bool, String> {
+    let (app_type, provider_id) =
+        quick_switch::parse_candidate_id(&candidate_id).map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::tray::switch_provider_internal(&app, app_type, provider_id)
+    })
+    .await
+    .map_err(|e| format!("切换任务异常: {e}"))?
+    .map(|_| true)
+    .map_err(|e| e.to_string())
+}
+
+/// 置顶或取消置顶供应商
+#[tauri::command]
+pub fn set_provider_pinned(
+    state: State<'_, AppState>,
+    app: String,
+    id: String,
+    pinned: bool,
+) -> Result<bool, String> {
+    let app_type = AppType::from_str(&app).map_err(|e| e.to_string())?;
+    quick_switch::set_pinned(state.inner(), &app_type, &id, pinned)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,17 @@
+//! 运行时状态命令
+
+use tauri::AppHandle;
+
+use crate::runtime_state::RuntimeState;
+
+/// 获取运行时状态（是否已暂停后台集成）
+#[tauri::command]
+pub fn get_runtime_state() -> Result<RuntimeState, String> {
+    Ok(crate::runtime_state::get())
+}
+
+/// 暂停或恢复后台集成
+#[tauri::command]
+pub fn set_runtime_paused(app: AppHandle, paused: bool) -> Result<RuntimeState, String> {
+    Ok(crate::tray::set_paused(&app, paused))
+}
@@ -1,18 +1,166 @@
 #![allow(non_snake_case)]
 
+use serde::Serialize;
 use tauri::AppHandle;
 
+use crate::error::AppError;
+use crate::read_only::{self, ReadOnlyStatus};
+use crate::services::automation_api::{self, AutomationApiStatus};
+use crate::services::settings_diagnostics::{self, SettingsFinding};
+use crate::services::status::{self, StatusReport};
+use crate::services::undo::{self, UndoEntry};
+
 /// 获取设置
 #[tauri::command]
 pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
     Ok(crate::settings::get_settings())
 }
 
+/// 保存设置的结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveSettingsResult {
+    pub success: bool,
+    /// 新修改的目录覆盖的校验结果（不阻止保存）
+    pub warnings: Vec<SettingsFinding>,
+}
+
 /// 保存设置
 #[tauri::command]
-pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
+pub async fn save_settings(
+    mut settings: crate::settings::AppSettings,
+) -> Result<SaveSettingsResult, String> {
+    settings.normalize_paths();
+    let previous = crate::settings::get_settings();
+    // 关闭密钥访问保护本身需要通过系统身份验证
+    let disable_secret_auth =
+        previous.require_os_auth_for_secrets && !settings.require_os_auth_for_secrets;
+    if disable_secret_auth {
+        crate::os_auth::ensure_secret_access("关闭 API Key 访问保护")
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    settings.keep_command_managed_state(&previous);
+    if disable_secret_auth {
+        settings.require_os_auth_for_secrets = false;
+    }
+    let warnings = settings_diagnostics::validate_changed_overrides(&previous, &settings);
     crate::settings::update_settings(settings).map_err(|e| e.to_string())?;
-    Ok(true)
+    undo::record(UndoEntry::SettingsChanged {
+        previous: Box::new(previous),
+    });
+    Ok(SaveSettingsResult {
+        success: true,
+        warnings,
+    })
+}
+
+/// 导出设置为 JSON（默认不包含设备级的当前供应商 ID）
+#[tauri::command]
+pub async fn export_settings(include_current_providers: Option<bool>) -> Result<String, String> {
+    crate::os_auth::ensure_secret_access("导出设置")
+        .await
+        .map_err(|e| e.to_string())?;
+    crate::settings::export_settings(include_current_providers.unwrap_or(false))
+        .map_err(|e| e.to_string())
+}
+
+/// 从 JSON 导入设置，返回导入后的设置
+#[tauri::command]
+pub async fn import_settings(json: String) -> Result<crate::settings::AppSettings, String> {
+    let previous = crate::settings::get_settings();
+    let imported = crate::settings::import_settings(&json).map_err(|e| e.to_string())?;
+    undo::record(UndoEntry::SettingsChanged {
+        previous: Box::new(previous),
+    });
+    Ok(imported)
+}
+
+/// 诊断当前设置（目录覆盖是否存在、可写、是否为目标应用目录等）
+#[tauri::command]
+pub async fn diagnose_settings() -> Result<Vec<SettingsFinding>, String> {
+    let settings = crate::settings::get_settings();
+    Ok(settings_diagnostics::diagnose_settings(&settings))
+}
+
+/// 获取机器可读的运行状态（当前供应商、配置目录、漂移检测与健康状态）
+#[tauri::command]
+pub async fn get_status(state: tauri::State<'_, crate::AppState>) -> Result<StatusReport, String> {
+    status::collect(&state).await.map_err(|e| e.to_string())
+}
+
+/// 向 Webhook 发送一条测试消息
+#[tauri::command]
+pub async fn test_webhook(webhook: crate::settings::WebhookConfig) -> Result<(), String> {
+    crate::services::webhook::send_test(&webhook)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取本地自动化 API 状态
+#[tauri::command]
+pub async fn get_automation_api_status() -> Result<AutomationApiStatus, String> {
+    Ok(automation_api::status())
+}
+
+/// 开启或关闭本地自动化 API（`port` 为空时沿用已保存的端口）
+#[tauri::command]
+pub async fn set_automation_api_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<AutomationApiStatus, String> {
+    automation_api::set_enabled(app, enabled, port)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取自动化 API 的访问 token（需要系统身份验证时先验证）
+#[tauri::command]
+pub async fn get_automation_api_token() -> Result<String, String> {
+    crate::os_auth::ensure_secret_access("查看自动化 API token")
+        .await
+        .map_err(|e| e.to_string())?;
+    automation_api::token().map_err(|e| e.to_string())
+}
+
+/// 重新生成自动化 API 的访问 token，旧 token 立即失效
+#[tauri::command]
+pub async fn regenerate_automation_api_token() -> Result<String, String> {
+    crate::os_auth::ensure_secret_access("重新生成自动化 API token")
+        .await
+        .map_err(|e| e.to_string())?;
+    automation_api::regenerate_token().map_err(|e| e.to_string())
+}
+
+/// 获取只读模式状态
+#[tauri::command]
+pub async fn get_read_only_status() -> Result<ReadOnlyStatus, String> {
+    Ok(read_only::status())
+}
+
+/// 开启只读模式（`passphrase` 为空时解锁需要系统身份验证）
+#[tauri::command]
+pub async fn lock_read_only_mode(passphrase: Option<String>) -> Result<ReadOnlyStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        read_only::lock(passphrase.as_deref())?;
+        Ok::<_, AppError>(read_only::status())
+    })
+    .await
+    .map_err(|e| format!("开启只读模式失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 解除只读模式（校验口令或弹出系统身份验证）
+#[tauri::command]
+pub async fn unlock_read_only_mode(passphrase: Option<String>) -> Result<ReadOnlyStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        read_only::unlock(passphrase.as_deref())?;
+        Ok::<_, AppError>(read_only::status())
+    })
+    .await
+    .map_err(|e| format!("解除只读模式失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
 }
 
 /// 重启应用程序（当 app_config_dir 变更后使用）
@@ -2,9 +2,15 @@
 
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::services::benchmark::{self, ProviderBenchmark};
+use crate::services::key_check::{KeyCheckResult, KeyCheckService};
+use crate::services::model_list::{self, ProviderModels};
+use crate::services::provider_compare::{self, ProviderComparisonReport};
+use crate::services::session_usage::UsageRange;
 use crate::services::stream_check::{
     HealthStatus, StreamCheckConfig, StreamCheckResult, StreamCheckService,
 };
+use crate::services::uptime::{self, ProviderUptime};
 use crate::store::AppState;
 use std::collections::HashSet;
 use tauri::State;
@@ -90,6 +96,105 @@ pub async fn stream_check_all_providers(
     Ok(results)
 }
 
+/// 端点测速：对应用的每个供应商发送多次请求，保存并返回按速度排序的结果
+#[tauri::command]
+pub async fn benchmark_providers(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    samples: Option<u32>,
+) -> Result<Vec<ProviderBenchmark>, AppError> {
+    benchmark::benchmark_providers(
+        &state.db,
+        &app_type,
+        samples.unwrap_or(benchmark::DEFAULT_SAMPLES),
+    )
+    .await
+}
+
+/// 获取保存的测速结果（按速度排序）
+#[tauri::command]
+pub fn get_provider_benchmarks(
+    state: State<'_, AppState>,
+    app_type: AppType,
+) -> Result<Vec<ProviderBenchmark>, AppError> {
+    state.db.get_provider_benchmarks(app_type.as_str())
+}
+
+/// 检查供应商 API Key 是否有效（按供应商类型请求模型列表，返回具体原因）
+#[tauri::command]
+pub async fn validate_key(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+) -> Result<KeyCheckResult, AppError> {
+    let provider = state
+        .db
+        .get_provider_by_id(&provider_id, app_type.as_str())?
+        .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+
+    KeyCheckService::validate(&app_type, &provider).await
+}
+
+/// 查询供应商提供的模型，并标记配置中填写但接口未返回的模型
+#[tauri::command]
+pub async fn list_models(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    provider_id: String,
+    refresh: Option<bool>,
+) -> Result<ProviderModels, AppError> {
+    let provider = state
+        .db
+        .get_provider_by_id(&provider_id, app_type.as_str())?
+        .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+
+    model_list::list_models(&app_type, &provider, refresh.unwrap_or(false)).await
+}
+
+/// 获取应用下已缓存的模型列表结果
+#[tauri::command]
+pub fn get_cached_models(app_type: AppType) -> Vec<ProviderModels> {
+    model_list::cached_models(&app_type)
+}
+
+/// 最近若干小时内各供应商的可用率与故障记录（默认 24 小时）
+#[tauri::command]
+pub fn get_provider_uptime(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    hours: Option<u32>,
+) -> Result<Vec<ProviderUptime>, AppError> {
+    uptime::get_uptime(&state.db, app_type.as_ref(), hours.unwrap_or(24))
+}
+
+/// 对比同一应用下的多个供应商（测速、可用性、成本与模型列表）
+#[tauri::command]
+pub async fn compare_providers(
+    state: State<'_, AppState>,
+    app_type: AppType,
+    ids: Vec<String>,
+    range: Option<UsageRange>,
+) -> Result<ProviderComparisonReport, String> {
+    let db = state.db.clone();
+    let blocking_app_type = app_type.clone();
+    let (providers, mut report) = tauri::async_runtime::spawn_blocking(move || {
+        let providers = provider_compare::load_providers(&db, &blocking_app_type, &ids)?;
+        let report = provider_compare::build_report(
+            &db,
+            &blocking_app_type,
+            &providers,
+            range.unwrap_or_default(),
+        )?;
+        Ok::<_, AppError>((providers, report))
+    })
+    .await
+    .map_err(|e| format!("生成供应商对比失败: {e}"))?
+    .map_err(|e| e.to_string())?;
+
+    provider_compare::attach_models(&app_type, &providers, &mut report).await;
+    Ok(report)
+}
+
 /// 获取流式检查配置
 #[tauri::command]
 pub fn get_stream_check_config(state: State<'_, AppState>) -> Result<StreamCheckConfig, AppError> {
@@ -0,0 +1,238 @@
+//! 远程同步相关命令
+
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::import_merge::MergeStrategy;
+use crate::services::lan_sync::{LanPeer, LanPullResult, LanShareSession, LanSyncService};
+use crate::services::s3_backup::{RemoteBackupEntry, S3BackupService, S3CredentialStatus};
+use crate::services::sync_merge::{
+    self, ConflictResolution, ProviderConflict, SyncMergeResult, SYNC_CONFLICTS_EVENT,
+};
+use crate::services::sync_status::{self, SyncOperation, SyncStatus};
+use crate::services::webdav_sync::{WebDavSyncResult, WebDavSyncService};
+use crate::services::ConfigService;
+use crate::settings::WebDavSyncConfig;
+use crate::store::AppState;
+
+/// 测试 WebDAV 连接（使用传入的配置，便于保存前验证）
+#[tauri::command]
+pub async fn webdav_test_connection(config: WebDavSyncConfig) -> Result<bool, String> {
+    WebDavSyncService::test_connection(&config)
+        .await
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 上传本地数据库到 WebDAV
+#[tauri::command]
+pub async fn webdav_sync_upload(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WebDavSyncResult, String> {
+    sync_status::begin(&app, SyncOperation::Upload);
+    let result = async {
+        let config = WebDavSyncService::config_from_settings()?;
+        WebDavSyncService::upload(state.db.clone(), &config).await
+    }
+    .await
+    .map_err(|e| e.to_string());
+    sync_status::finish(&app, SyncOperation::Upload, &result);
+    result
+}
+
+/// 从 WebDAV 下载并导入数据库
+#[tauri::command]
+pub async fn webdav_sync_download(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<WebDavSyncResult, String> {
+    sync_status::begin(&app, SyncOperation::Download);
+    let result = async {
+        let config = WebDavSyncService::config_from_settings().map_err(|e| e.to_string())?;
+        let result = WebDavSyncService::download(state.db.clone(), &config)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let db = state.db.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            ConfigService::refresh_after_database_restore(&AppState::new(db));
+        })
+        .await
+        .map_err(|e| format!("同步 live 配置失败: {e}"))?;
+
+        Ok::<_, String>(result)
+    }
+    .await;
+    sync_status::finish(&app, SyncOperation::Download, &result);
+    result
+}
+
+/// 从 WebDAV 下载并与本地三方合并，存在冲突时发出 `sync-conflicts` 事件
+#[tauri::command]
+pub async fn webdav_sync_merge(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SyncMergeResult, String> {
+    sync_status::begin(&app, SyncOperation::Merge);
+    let result = async {
+        let config = WebDavSyncService::config_from_settings().map_err(|e| e.to_string())?;
+        let result = WebDavSyncService::merge_download(state.db.clone(), &config)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !result.applied_remote.is_empty() || !result.auto_merged.is_empty() {
+            let db = state.db.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                ConfigService::refresh_after_database_restore(&AppState::new(db));
+            })
+            .await
+            .map_err(|e| format!("同步 live 配置失败: {e}"))?;
+        }
+
+        Ok::<_, String>(result)
+    }
+    .await;
+    sync_status::finish(&app, SyncOperation::Merge, &result);
+
+    if let Ok(result) = &result {
+        if !result.conflicts.is_empty() {
+            if let Err(e) = app.emit(SYNC_CONFLICTS_EVENT, &result.conflicts) {
+                log::error!("发射同步冲突事件失败: {e}");
+            }
+        }
+    }
+
+    result
+}
+
+/// 获取同步状态（上次同步时间、待同步改动、冲突与错误）
+#[tauri::command]
+pub async fn get_sync_status(state: State<'_, AppState>) -> Result<SyncStatus, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || sync_status::get_status(&db))
+        .await
+        .map_err(|e| format!("获取同步状态失败: {e}"))?
+        .map_err(|e| e.to_string())
+}
+
+/// 获取待解决的同步冲突
+#[tauri::command]
+pub async fn get_sync_conflicts() -> Result<Vec<ProviderConflict>, String> {
+    Ok(sync_merge::load_conflicts())
+}
+
+/// 解决同步冲突（local / remote / custom），返回剩余冲突
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    app: String,
+    id: String,
+    resolution: ConflictResolution,
+    provider: Option<Provider>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ProviderConflict>, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let remaining = sync_merge::resolve_conflict(&db, &app, &id, resolution, provider)?;
+        ConfigService::refresh_after_database_restore(&AppState::new(db));
+        Ok::<_, AppError>(remaining)
+    })
+    .await
+    .map_err(|e| format!("解决同步冲突失败: {e}"))?
+    .map_err(|e: AppError| e.to_string())
+}
+
+/// 保存 S3 访问密钥与备份口令到系统钥匙串
+#[tauri::command]
+pub async fn set_s3_backup_credentials(
+    access_key_id: String,
+    secret_access_key: String,
+    passphrase: Option<String>,
+) -> Result<bool, String> {
+    S3BackupService::set_credentials(&access_key_id, &secret_access_key, passphrase.as_deref())
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 查询钥匙串中是否已保存 S3 凭据
+#[tauri::command]
+pub async fn get_s3_backup_credential_status() -> Result<S3CredentialStatus, String> {
+    S3BackupService::credential_status().map_err(|e| e.to_string())
+}
+
+/// 加密并上传备份到 S3 兼容存储
+#[tauri::command]
+pub async fn upload_backup(state: State<'_, AppState>) -> Result<RemoteBackupEntry, String> {
+    S3BackupService::upload_backup(state.db.clone())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 列出 S3 兼容存储中的备份
+#[tauri::command]
+pub async fn list_remote_backups() -> Result<Vec<RemoteBackupEntry>, String> {
+    S3BackupService::list_backups()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 从 S3 兼容存储恢复指定备份，返回本地数据库备份 ID
+#[tauri::command]
+pub async fn restore_remote_backup(
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    S3BackupService::restore_backup(state.db.clone(), &key)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 开始局域网分享，返回需要在对端输入的配对码
+#[tauri::command]
+pub async fn start_lan_share(state: State<'_, AppState>) -> Result<LanShareSession, String> {
+    LanSyncService::start_share(state.db.clone())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 停止局域网分享
+#[tauri::command]
+pub async fn stop_lan_share() -> Result<bool, String> {
+    LanSyncService::stop_share();
+    Ok(true)
+}
+
+/// 发现局域网内正在分享的 CC Switch（默认等待 3 秒）
+#[tauri::command]
+pub async fn discover_lan_peers(
+    #[allow(non_snake_case)] timeoutMs: Option<u64>,
+) -> Result<Vec<LanPeer>, String> {
+    let timeout = std::time::Duration::from_millis(timeoutMs.unwrap_or(3000).clamp(500, 30_000));
+    LanSyncService::discover_peers(timeout)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 使用配对码从局域网对端拉取供应商与 MCP 数据并合并到本地
+#[tauri::command]
+pub async fn pull_from_lan_peer(
+    address: String,
+    port: u16,
+    code: String,
+    strategy: MergeStrategy,
+    state: State<'_, AppState>,
+) -> Result<LanPullResult, String> {
+    let result = LanSyncService::pull_from_peer(state.db.clone(), &address, port, &code, strategy)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        ConfigService::refresh_after_database_restore(&AppState::new(db));
+    })
+    .await
+    .map_err(|e| format!("同步 live 配置失败: {e}"))?;
+
+    Ok(result)
+}
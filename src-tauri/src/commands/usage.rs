@@ -1,6 +1,13 @@
 //! 使用统计相关命令
 
+use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::services::cost_report::{self, CostPeriod, CostReport};
+use crate::services::session_browser::{self, SessionSummary};
+use crate::services::session_usage::{self, ProviderUsage, UsageRange};
+use crate::services::switch_stats::{self, SwitchStats};
+use crate::services::usage_dashboard::{self, UsageDashboard};
+use crate::services::usage_report::{self, ReportFormat};
 use crate::services::usage_stats::*;
 use crate::store::AppState;
 use tauri::State;
@@ -166,6 +173,110 @@ pub fn delete_model_pricing(state: State<'_, AppState>, model_id: String) -> Res
     Ok(())
 }
 
+/// 获取供应商的会话用量（先统计 Claude Code / Codex 新增的本地会话日志）
+#[tauri::command]
+pub async fn get_usage(
+    state: State<'_, AppState>,
+    provider_id: String,
+    range: Option<UsageRange>,
+) -> Result<ProviderUsage, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        session_usage::get_usage(&db, &provider_id, range.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("统计会话用量失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 获取成本报告（按模型定价估算各供应商的会话用量成本）
+#[tauri::command]
+pub async fn get_cost_report(
+    state: State<'_, AppState>,
+    range: Option<UsageRange>,
+    period: Option<CostPeriod>,
+) -> Result<CostReport, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        cost_report::get_cost_report(&db, range.unwrap_or_default(), period.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("生成成本报告失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 列出最近的 Claude Code / Codex 本地会话（标题、时间、token 用量与会话期间使用的供应商）
+#[tauri::command]
+pub async fn list_sessions(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    limit: Option<usize>,
+) -> Result<Vec<SessionSummary>, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        session_browser::list_sessions(
+            &db,
+            app_type,
+            limit.unwrap_or(session_browser::DEFAULT_LIMIT),
+        )
+    })
+    .await
+    .map_err(|e| format!("读取会话列表失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 获取用量面板数据（每日 token、供应商与模型用量、切换频率；一分钟内使用缓存）
+#[tauri::command]
+pub async fn get_usage_dashboard(
+    state: State<'_, AppState>,
+    range: Option<UsageRange>,
+    refresh: Option<bool>,
+) -> Result<UsageDashboard, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        usage_dashboard::get_dashboard(&db, range.unwrap_or_default(), refresh.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("统计用量面板数据失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 导出用量报告（每月各供应商的用量、成本与切换次数，CSV 或 JSON）；提供 `file_path`
+/// 时同时写入该文件
+#[tauri::command]
+pub async fn export_usage_report(
+    state: State<'_, AppState>,
+    range: Option<UsageRange>,
+    format: Option<ReportFormat>,
+    file_path: Option<String>,
+) -> Result<String, String> {
+    let db = state.db.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let content = usage_report::export_usage_report(
+            &db,
+            range.unwrap_or_default(),
+            format.unwrap_or_default(),
+        )?;
+        if let Some(path) = file_path.filter(|p| !p.trim().is_empty()) {
+            crate::config::write_text_file(&std::path::PathBuf::from(path), &content)?;
+        }
+        Ok::<_, AppError>(content)
+    })
+    .await
+    .map_err(|e| format!("导出用量报告失败: {e}"))?
+    .map_err(|e| e.to_string())
+}
+
+/// 获取本地切换习惯统计（切换频率、时段与常见切换路径；`days` 为空时统计全部记录）
+#[tauri::command]
+pub fn get_switch_stats(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    days: Option<u32>,
+) -> Result<SwitchStats, AppError> {
+    switch_stats::get_switch_stats(&state.db, app_type.as_ref(), days)
+}
+
 /// 模型定价信息
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -3,7 +3,10 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::network_fs::with_retry;
+use crate::settings::LiveFile;
 
 /// 获取用户主目录，带回退和日志
 fn get_home_dir() -> PathBuf {
@@ -50,9 +53,12 @@ pub fn get_claude_mcp_path() -> PathBuf {
     get_default_claude_mcp_path()
 }
 
-/// 获取 Claude Code 主配置文件路径
+/// 获取 Claude Code 主配置文件路径（支持设置中的文件路径覆盖）
 pub fn get_claude_settings_path() -> PathBuf {
     let dir = get_claude_config_dir();
+    if crate::settings::has_live_file_override(LiveFile::ClaudeSettings) {
+        return crate::settings::resolve_live_file(LiveFile::ClaudeSettings, &dir, "settings.json");
+    }
     let settings = dir.join("settings.json");
     if settings.exists() {
         return settings;
@@ -66,15 +72,9 @@ pub fn get_claude_settings_path() -> PathBuf {
     settings
 }
 
-/// 获取应用配置目录路径 (~/.cc-switch)
+/// 获取应用配置目录路径（默认 ~/.cc-switch，解析顺序见 [`crate::paths`]）
 pub fn get_app_config_dir() -> PathBuf {
-    if let Some(custom) = crate::app_store::get_app_config_dir_override() {
-        return custom;
-    }
-
-    dirs::home_dir()
-        .expect("无法获取用户主目录")
-        .join(".cc-switch")
+    crate::paths::data_dir()
 }
 
 /// 获取应用配置文件路径
@@ -83,7 +83,6 @@ pub fn get_app_config_path() -> PathBuf {
 }
 
 /// 清理供应商名称，确保文件名安全
-#[allow(dead_code)]
 pub fn sanitize_provider_name(name: &str) -> String {
     name.chars()
         .map(|c| match c {
@@ -137,9 +136,16 @@ pub fn write_text_file(path: &Path, data: &str) -> Result<(), AppError> {
 }
 
 /// 原子写入：写入临时文件后 rename 替换，避免半写状态
+///
+/// 若目标是软链接（软链接模式或用户自行管理的 dotfiles），写入链接指向的实际文件并保留链接。
 pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    let audited_path = path;
+    let hash_before = crate::audit_log::file_hash(audited_path);
+    let resolved = resolve_symlink_target(path);
+    let path = resolved.as_deref().unwrap_or(path);
+
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        with_retry(parent, || fs::create_dir_all(parent))?;
     }
 
     let parent = path
@@ -151,18 +157,23 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
         .ok_or_else(|| AppError::Config("无效的文件名".to_string()))?
         .to_string_lossy()
         .to_string();
-    let ts = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_nanos();
-    tmp.push(format!("{file_name}.tmp.{ts}"));
-
-    {
-        let mut f = fs::File::create(&tmp).map_err(|e| AppError::io(&tmp, e))?;
-        f.write_all(data).map_err(|e| AppError::io(&tmp, e))?;
-        f.flush().map_err(|e| AppError::io(&tmp, e))?;
+    if crate::cloud_drive::detect_cloud_drive(parent).is_some() {
+        // 网盘目录：使用网盘客户端会忽略的唯一临时文件名，避免上传半成品或产生冲突副本
+        tmp.push(crate::cloud_drive::temp_file_name(&file_name));
+    } else {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        tmp.push(format!("{file_name}.tmp.{ts}"));
     }
 
+    with_retry(&tmp, || {
+        let mut f = fs::File::create(&tmp)?;
+        f.write_all(data)?;
+        f.flush()
+    })?;
+
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -172,28 +183,38 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
         }
     }
 
-    #[cfg(windows)]
-    {
-        // Windows 上 rename 目标存在会失败，先移除再重命名（尽量接近原子性）
-        if path.exists() {
-            let _ = fs::remove_file(path);
+    let replace = || {
+        #[cfg(windows)]
+        {
+            // Windows 上 rename 目标存在会失败，先移除再重命名（尽量接近原子性）
+            if path.exists() {
+                let _ = fs::remove_file(path);
+            }
         }
-        fs::rename(&tmp, path).map_err(|e| AppError::IoContext {
-            context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
-            source: e,
-        })?;
-    }
+        fs::rename(&tmp, path)
+    };
 
-    #[cfg(not(windows))]
-    {
-        fs::rename(&tmp, path).map_err(|e| AppError::IoContext {
+    with_retry(path, replace).map_err(|e| match e {
+        AppError::Io { source, .. } => AppError::IoContext {
             context: format!("原子替换失败: {} -> {}", tmp.display(), path.display()),
-            source: e,
-        })?;
-    }
+            source,
+        },
+        other => other,
+    })?;
+
+    crate::audit_log::record_write(audited_path, hash_before, data);
     Ok(())
 }
 
+/// 若路径是有效的软链接，返回其最终指向的文件
+fn resolve_symlink_target(path: &Path) -> Option<PathBuf> {
+    let meta = fs::symlink_metadata(path).ok()?;
+    if !meta.file_type().is_symlink() {
+        return None;
+    }
+    fs::canonicalize(path).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +248,24 @@ mod tests {
         let override_dir = PathBuf::from("/");
         assert!(derive_mcp_path_from_override(&override_dir).is_none());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_follows_symlink_and_keeps_link() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let target = tmp.path().join("store.json");
+        let link = tmp.path().join("live.json");
+        fs::write(&target, "old").expect("write target");
+        std::os::unix::fs::symlink(&target, &link).expect("create symlink");
+
+        atomic_write(&link, b"new").expect("write through link");
+
+        assert!(fs::symlink_metadata(&link)
+            .expect("link metadata")
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(&target).expect("read target"), "new");
+    }
 }
 
 /// 复制文件
@@ -241,12 +280,61 @@ pub fn copy_file(from: &Path, to: &Path) -> Result<(), AppError> {
 /// 删除文件
 pub fn delete_file(path: &Path) -> Result<(), AppError> {
     if path.exists() {
+        let hash_before = crate::audit_log::file_hash(path);
         fs::remove_file(path).map_err(|e| AppError::io(path, e))?;
+        crate::audit_log::record_delete(path, hash_before);
     }
     Ok(())
 }
 
+/// 隔离损坏的文件：改名为 `<文件名>.corrupt-<时间>`，返回新路径
+pub fn quarantine_file(path: &Path) -> Result<PathBuf, AppError> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = path.with_file_name(format!(
+        "{name}.corrupt-{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    fs::rename(path, &target).map_err(|e| AppError::io(path, e))?;
+    Ok(target)
+}
+
+/// 当前所有 live 配置文件（应用、稳定的存储名、实际路径）
+pub(crate) fn live_config_files() -> Vec<(AppType, &'static str, PathBuf)> {
+    vec![
+        (AppType::Claude, "settings.json", get_claude_settings_path()),
+        (
+            AppType::Codex,
+            "auth.json",
+            crate::codex_config::get_codex_auth_path(),
+        ),
+        (
+            AppType::Codex,
+            "config.toml",
+            crate::codex_config::get_codex_config_path(),
+        ),
+        (
+            AppType::Gemini,
+            ".env",
+            crate::gemini_config::get_gemini_env_path(),
+        ),
+        (
+            AppType::Gemini,
+            "settings.json",
+            crate::gemini_config::get_gemini_settings_path(),
+        ),
+        (
+            AppType::OpenCode,
+            "opencode.json",
+            crate::opencode_config::get_opencode_config_path(),
+        ),
+    ]
+}
+
 /// 检查 Claude Code 配置状态
+#[cfg(feature = "gui")]
 #[derive(Serialize, Deserialize)]
 pub struct ConfigStatus {
     pub exists: bool,
@@ -254,6 +342,7 @@ pub struct ConfigStatus {
 }
 
 /// 获取 Claude Code 配置状态
+#[cfg(feature = "gui")]
 pub fn get_claude_config_status() -> ConfigStatus {
     let path = get_claude_settings_path();
     ConfigStatus {
@@ -0,0 +1,318 @@
+//! 基于口令的对称加密
+//!
+//! 使用 PBKDF2-HMAC-SHA256 从口令派生 256 位密钥，再以 AES-256-GCM 加密。
+//! 密文格式：`MAGIC(5) | salt(16) | nonce(12) | ciphertext+tag`
+//!
+//! 主密码（[`MasterKey`]）使用 Argon2id 派生密钥，格式相同但 MAGIC 为 `CCSE2`；
+//! 派生出的密钥可缓存在内存中重复加密，避免每次写入都重新计算 Argon2。
+//!
+//! 由密钥交换得到的会话密钥（局域网同步）无需派生，直接用 [`encrypt_with_key`] 加密，
+//! 格式为 `nonce(12) | ciphertext+tag`。
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use sha2::{Digest, Sha256};
+
+use crate::error::AppError;
+
+const MAGIC: &[u8; 5] = b"CCSE1";
+const MASTER_MAGIC: &[u8; 5] = b"CCSE2";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + SALT_LEN + NONCE_LEN;
+const PBKDF2_ROUNDS: u32 = 210_000;
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Key<Aes256Gcm> {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    key.into()
+}
+
+fn seal(
+    magic: &[u8],
+    key: &Key<Aes256Gcm>,
+    salt: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, AppError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(key);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| AppError::Message("加密数据失败".to_string()))?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    out.extend_from_slice(magic);
+    out.extend_from_slice(salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn unseal(key: &Key<Aes256Gcm>, data: &[u8]) -> Result<Vec<u8>, AppError> {
+    let nonce = &data[MAGIC.len() + SALT_LEN..HEADER_LEN];
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), &data[HEADER_LEN..])
+        .map_err(|_| {
+            AppError::localized(
+                "crypto.decrypt_failed",
+                "解密失败：口令错误或数据已损坏",
+                "Decryption failed: wrong passphrase or corrupted data",
+            )
+        })
+}
+
+/// 由主密码经 Argon2id 派生的密钥（连同盐值缓存在内存中）
+#[derive(Clone)]
+pub struct MasterKey {
+    key: Key<Aes256Gcm>,
+    salt: [u8; SALT_LEN],
+}
+
+impl MasterKey {
+    fn derive(password: &str, salt: [u8; SALT_LEN]) -> Result<Self, AppError> {
+        if password.is_empty() {
+            return Err(AppError::InvalidInput("主密码不能为空".to_string()));
+        }
+        let mut key = [0u8; 32];
+        argon2::Argon2::default()
+            .hash_password_into(password.as_bytes(), &salt, &mut key)
+            .map_err(|e| AppError::Message(format!("派生主密钥失败: {e}")))?;
+        Ok(Self {
+            key: key.into(),
+            salt,
+        })
+    }
+
+    /// 使用新的随机盐值派生主密钥（设置主密码时使用）
+    pub fn generate(password: &str) -> Result<Self, AppError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(password, salt)
+    }
+
+    /// 按密文中记录的盐值派生主密钥，并校验密码能否解密该密文
+    pub fn unlock(data: &[u8], password: &str) -> Result<(Self, Vec<u8>), AppError> {
+        if !is_master_encrypted(data) {
+            return Err(AppError::InvalidInput("数据不是主密码加密格式".to_string()));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&data[MASTER_MAGIC.len()..MASTER_MAGIC.len() + SALT_LEN]);
+        let key = Self::derive(password, salt)?;
+        let plaintext = unseal(&key.key, data)?;
+        Ok((key, plaintext))
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        seal(MASTER_MAGIC, &self.key, &self.salt, plaintext)
+    }
+
+    /// 解密由本密钥加密的数据（盐值不同说明来自其他主密码，直接拒绝）
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, AppError> {
+        if !is_master_encrypted(data)
+            || data[MASTER_MAGIC.len()..MASTER_MAGIC.len() + SALT_LEN] != self.salt
+        {
+            return Err(AppError::InvalidInput(
+                "数据不是由当前主密码加密的".to_string(),
+            ));
+        }
+        unseal(&self.key, data)
+    }
+}
+
+impl std::fmt::Debug for MasterKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MasterKey(..)")
+    }
+}
+
+/// 生成口令的 Argon2id 校验哈希（PHC 字符串格式，含随机盐值）
+pub fn hash_passphrase(passphrase: &str) -> Result<String, AppError> {
+    use argon2::password_hash::{PasswordHasher, SaltString};
+
+    if passphrase.is_empty() {
+        return Err(AppError::InvalidInput("口令不能为空".to_string()));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt)
+        .map_err(|e| AppError::Message(format!("生成盐值失败: {e}")))?;
+    argon2::Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AppError::Message(format!("计算口令哈希失败: {e}")))
+}
+
+/// 校验口令是否与 [`hash_passphrase`] 生成的哈希匹配
+pub fn verify_passphrase(passphrase: &str, hash: &str) -> bool {
+    use argon2::password_hash::{PasswordHash, PasswordVerifier};
+
+    PasswordHash::new(hash).is_ok_and(|parsed| {
+        argon2::Argon2::default()
+            .verify_password(passphrase.as_bytes(), &parsed)
+            .is_ok()
+    })
+}
+
+/// 计算 SHA-256 摘要（小写十六进制）
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// 判断数据是否为本模块生成的密文
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.len() > HEADER_LEN && (data.starts_with(MAGIC) || data.starts_with(MASTER_MAGIC))
+}
+
+/// 判断密文是否由主密码（Argon2id）加密
+pub fn is_master_encrypted(data: &[u8]) -> bool {
+    data.len() > HEADER_LEN && data.starts_with(MASTER_MAGIC)
+}
+
+/// 使用口令加密数据
+pub fn encrypt_with_passphrase(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if passphrase.is_empty() {
+        return Err(AppError::InvalidInput("加密口令不能为空".to_string()));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    seal(MAGIC, &derive_key(passphrase, &salt), &salt, plaintext)
+}
+
+/// 使用口令解密数据（口令错误或数据被篡改时返回错误）
+pub fn decrypt_with_passphrase(data: &[u8], passphrase: &str) -> Result<Vec<u8>, AppError> {
+    if !is_encrypted(data) {
+        return Err(AppError::InvalidInput(
+            "数据不是有效的 CC Switch 加密格式".to_string(),
+        ));
+    }
+
+    if is_master_encrypted(data) {
+        return MasterKey::unlock(data, passphrase).map(|(_, plaintext)| plaintext);
+    }
+
+    let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    unseal(&derive_key(passphrase, salt), data)
+}
+
+/// 使用 256 位会话密钥加密数据
+#[cfg(any(feature = "gui", test))]
+pub fn encrypt_with_key(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, AppError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| AppError::Message("加密数据失败".to_string()))?;
+    let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// 使用 256 位会话密钥解密 [`encrypt_with_key`] 的输出
+#[cfg(any(feature = "gui", test))]
+pub fn decrypt_with_key(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>, AppError> {
+    if data.len() <= NONCE_LEN {
+        return Err(AppError::InvalidInput("密文长度无效".to_string()));
+    }
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(&data[..NONCE_LEN]), &data[NONCE_LEN..])
+        .map_err(|_| {
+            AppError::localized(
+                "crypto.decrypt_failed",
+                "解密失败：密钥错误或数据已损坏",
+                "Decryption failed: wrong key or corrupted data",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_key_roundtrip_rejects_other_keys() {
+        let key = [7u8; 32];
+        let encrypted = encrypt_with_key(b"hello", &key).unwrap();
+        assert_eq!(decrypt_with_key(&encrypted, &key).unwrap(), b"hello");
+        assert!(decrypt_with_key(&encrypted, &[8u8; 32]).is_err());
+        assert!(decrypt_with_key(&encrypted[..NONCE_LEN], &key).is_err());
+    }
+
+    #[test]
+    fn roundtrip_with_correct_passphrase() {
+        let encrypted = encrypt_with_passphrase(b"hello", "secret").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(
+            decrypt_with_passphrase(&encrypted, "secret").unwrap(),
+            b"hello"
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_or_tampering_is_rejected() {
+        let mut encrypted = encrypt_with_passphrase(b"hello", "secret").unwrap();
+        assert!(decrypt_with_passphrase(&encrypted, "other").is_err());
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0xFF;
+        assert!(decrypt_with_passphrase(&encrypted, "secret").is_err());
+    }
+
+    #[test]
+    fn master_key_roundtrip_and_reuse() {
+        let key = MasterKey::generate("master").unwrap();
+        let first = key.encrypt(b"one").unwrap();
+        let second = key.encrypt(b"two").unwrap();
+        assert!(is_master_encrypted(&first));
+        assert!(is_encrypted(&first));
+
+        let (unlocked, plaintext) = MasterKey::unlock(&second, "master").unwrap();
+        assert_eq!(plaintext, b"two");
+        assert_eq!(
+            decrypt_with_passphrase(&unlocked.encrypt(b"three").unwrap(), "master").unwrap(),
+            b"three"
+        );
+        assert!(MasterKey::unlock(&first, "wrong").is_err());
+        assert!(!is_master_encrypted(
+            &encrypt_with_passphrase(b"x", "master").unwrap()
+        ));
+    }
+
+    #[test]
+    fn master_key_decrypts_only_its_own_data() {
+        let key = MasterKey::generate("master").unwrap();
+        let sealed = key.encrypt(b"base snapshot").unwrap();
+        assert_eq!(key.decrypt(&sealed).unwrap(), b"base snapshot");
+
+        // 同一主密码、不同盐值派生的密钥同样拒绝
+        let other = MasterKey::generate("master").unwrap();
+        assert!(other.decrypt(&sealed).is_err());
+        assert!(key
+            .decrypt(&encrypt_with_passphrase(b"x", "master").unwrap())
+            .is_err());
+    }
+
+    #[test]
+    fn passphrase_hash_verifies_only_matching_passphrase() {
+        let hash = hash_passphrase("kiosk").unwrap();
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(verify_passphrase("kiosk", &hash));
+        assert!(!verify_passphrase("other", &hash));
+        assert!(!verify_passphrase("kiosk", "not-a-hash"));
+        assert!(hash_passphrase("").is_err());
+    }
+
+    #[test]
+    fn plain_data_is_not_treated_as_encrypted() {
+        assert!(!is_encrypted("-- CC Switch SQLite 导出".as_bytes()));
+        assert!(decrypt_with_passphrase(b"plain", "secret").is_err());
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! 提供 SQL 导出/导入和二进制快照备份功能。
 
+use super::profiles::database_dir;
 use super::{lock_conn, Database, DB_BACKUP_RETAIN};
-use crate::config::get_app_config_dir;
 use crate::error::AppError;
 use chrono::Utc;
 use rusqlite::backup::Backup;
@@ -18,8 +18,7 @@ const CC_SWITCH_SQL_EXPORT_HEADER: &str = "-- CC Switch SQLite 导出";
 impl Database {
     /// 导出为 SQLite 兼容的 SQL 文本
     pub fn export_sql(&self, target_path: &Path) -> Result<(), AppError> {
-        let snapshot = self.snapshot_to_memory()?;
-        let dump = Self::dump_sql(&snapshot)?;
+        let dump = self.export_sql_string()?;
 
         if let Some(parent) = target_path.parent() {
             fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
@@ -28,6 +27,12 @@ impl Database {
         crate::config::atomic_write(target_path, dump.as_bytes())
     }
 
+    /// 导出为 SQL 文本（内存中，不落盘）
+    pub fn export_sql_string(&self) -> Result<String, AppError> {
+        let snapshot = self.snapshot_to_memory()?;
+        Self::dump_sql(&snapshot)
+    }
+
     /// 从 SQL 文件导入，返回生成的备份 ID（若无备份则为空字符串）
     pub fn import_sql(&self, source_path: &Path) -> Result<String, AppError> {
         if !source_path.exists() {
@@ -37,7 +42,55 @@ impl Database {
             )));
         }
 
-        let sql_raw = fs::read_to_string(source_path).map_err(|e| AppError::io(source_path, e))?;
+        let bytes = fs::read(source_path).map_err(|e| AppError::io(source_path, e))?;
+        if crate::crypto::is_encrypted(&bytes) {
+            return Err(AppError::localized(
+                "backup.sql.encrypted",
+                "该文件是加密导出文件，请使用“导入加密备份”并输入口令。",
+                "This file is an encrypted export; import it as an encrypted backup with its passphrase.",
+            ));
+        }
+        let sql_raw = String::from_utf8(bytes)
+            .map_err(|e| AppError::InvalidInput(format!("SQL 文件不是有效的 UTF-8 文本: {e}")))?;
+        self.import_sql_string(&sql_raw)
+    }
+
+    /// 导出为口令加密的 SQL 归档（AES-256-GCM），包含供应商密钥等全部数据
+    pub fn export_encrypted(&self, target_path: &Path, passphrase: &str) -> Result<(), AppError> {
+        let dump = self.export_sql_string()?;
+        let encrypted = crate::crypto::encrypt_with_passphrase(dump.as_bytes(), passphrase)?;
+
+        if let Some(parent) = target_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+
+        crate::config::atomic_write(target_path, &encrypted)
+    }
+
+    /// 从口令加密的归档导入，返回生成的备份 ID（若无备份则为空字符串）
+    pub fn import_encrypted(
+        &self,
+        source_path: &Path,
+        passphrase: &str,
+    ) -> Result<String, AppError> {
+        if !source_path.exists() {
+            return Err(AppError::InvalidInput(format!(
+                "加密备份文件不存在: {}",
+                source_path.display()
+            )));
+        }
+
+        let bytes = fs::read(source_path).map_err(|e| AppError::io(source_path, e))?;
+        let plain = crate::crypto::decrypt_with_passphrase(&bytes, passphrase)?;
+        let sql_raw = String::from_utf8(plain)
+            .map_err(|e| AppError::InvalidInput(format!("解密后的内容不是有效的 SQL 文本: {e}")))?;
+        self.import_sql_string(&sql_raw)
+    }
+
+    /// 从 SQL 文本导入，返回生成的备份 ID（若无备份则为空字符串）
+    pub fn import_sql_string(&self, sql_raw: &str) -> Result<String, AppError> {
+        // 导入会整体替换数据库（备份恢复、快照回滚、云端恢复都经过这里）
+        crate::read_only::ensure_writable()?;
         let sql_content = sql_raw.trim_start_matches('\u{feff}');
         Self::validate_cc_switch_sql_export(sql_content)?;
 
@@ -72,6 +125,8 @@ impl Database {
                 .map_err(|e| AppError::Database(e.to_string()))?;
         }
 
+        self.flush_encrypted(true)?;
+
         let backup_id = backup_path
             .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
             .unwrap_or_default();
@@ -79,6 +134,27 @@ impl Database {
         Ok(backup_id)
     }
 
+    /// 将 CC Switch SQL 导出加载为独立的内存数据库（不影响主库），用于合并导入等场景
+    #[cfg(feature = "gui")]
+    pub(crate) fn open_sql_export(sql_raw: &str) -> Result<Database, AppError> {
+        let conn = Self::sql_export_to_memory(sql_raw)?;
+        Self::create_tables_on_conn(&conn)?;
+        Self::apply_schema_migrations_on_conn(&conn)?;
+
+        Ok(Database::from_connection(conn))
+    }
+
+    /// 将 CC Switch SQL 导出执行到新的内存连接
+    pub(crate) fn sql_export_to_memory(sql_raw: &str) -> Result<Connection, AppError> {
+        let sql_content = sql_raw.trim_start_matches('\u{feff}');
+        Self::validate_cc_switch_sql_export(sql_content)?;
+
+        let conn = Connection::open_in_memory().map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute_batch(sql_content)
+            .map_err(|e| AppError::Database(format!("执行 SQL 导入失败: {e}")))?;
+        Ok(conn)
+    }
+
     /// 创建内存快照以避免长时间持有数据库锁
     pub(crate) fn snapshot_to_memory(&self) -> Result<Connection, AppError> {
         let conn = lock_conn!(self.conn);
@@ -110,8 +186,8 @@ impl Database {
     }
 
     /// 生成一致性快照备份，返回备份文件路径（不存在主库时返回 None）
-    fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
-        let db_path = get_app_config_dir().join("cc-switch.db");
+    pub(crate) fn backup_database_file(&self) -> Result<Option<PathBuf>, AppError> {
+        let db_path = database_dir().join("cc-switch.db");
         if !db_path.exists() {
             return Ok(None);
         }
@@ -133,23 +209,71 @@ impl Database {
             counter += 1;
         }
 
+        self.backup_to_file(&backup_path)?;
+
+        Self::cleanup_db_backups(&backup_dir)?;
+        Ok(Some(backup_path))
+    }
+
+    /// 将当前数据库一致性复制到指定文件
+    pub(crate) fn backup_to_file(&self, dest: &Path) -> Result<(), AppError> {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+
+        let conn = lock_conn!(self.conn);
+        let mut dest_conn =
+            Connection::open(dest).map_err(|e| AppError::Database(e.to_string()))?;
+        let backup =
+            Backup::new(&conn, &mut dest_conn).map_err(|e| AppError::Database(e.to_string()))?;
+        backup
+            .step(-1)
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        drop(backup);
+        // 快照会沿用主库的 WAL 模式，改回回滚日志，保证快照是可单独复制的单个文件
+        dest_conn
+            .pragma_update_and_check(None, "journal_mode", "DELETE", |row| {
+                row.get::<_, String>(0)
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 用数据库快照文件整体替换当前数据库，返回替换前生成的备份 ID
+    #[cfg(feature = "gui")]
+    pub(crate) fn restore_from_file(&self, source: &Path) -> Result<String, AppError> {
+        if !source.exists() {
+            return Err(AppError::InvalidInput(format!(
+                "数据库快照不存在: {}",
+                source.display()
+            )));
+        }
+
+        let source_conn =
+            Connection::open(source).map_err(|e| AppError::Database(e.to_string()))?;
+        Self::validate_basic_state(&source_conn)?;
+
+        let backup_path = self.backup_database_file()?;
         {
-            let conn = lock_conn!(self.conn);
-            let mut dest_conn =
-                Connection::open(&backup_path).map_err(|e| AppError::Database(e.to_string()))?;
-            let backup = Backup::new(&conn, &mut dest_conn)
+            let mut main_conn = lock_conn!(self.conn);
+            let backup = Backup::new(&source_conn, &mut main_conn)
                 .map_err(|e| AppError::Database(e.to_string()))?;
             backup
                 .step(-1)
                 .map_err(|e| AppError::Database(e.to_string()))?;
         }
+        // 旧版本快照需要补齐表结构
+        self.create_tables()?;
+        self.apply_schema_migrations()?;
+        self.flush_encrypted(true)?;
 
-        Self::cleanup_db_backups(&backup_dir)?;
-        Ok(Some(backup_path))
+        Ok(backup_path
+            .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+            .unwrap_or_default())
     }
 
-    /// 清理旧的数据库备份，保留最新的 N 个
-    fn cleanup_db_backups(dir: &Path) -> Result<(), AppError> {
+    /// 清理旧的数据库备份，保留最新的 N 个，返回删除的数量与字节数
+    pub(super) fn cleanup_db_backups(dir: &Path) -> Result<(usize, u64), AppError> {
         let entries = match fs::read_dir(dir) {
             Ok(iter) => iter
                 .filter_map(|entry| entry.ok())
@@ -161,23 +285,32 @@ impl Database {
                         .unwrap_or(false)
                 })
                 .collect::<Vec<_>>(),
-            Err(_) => return Ok(()),
+            Err(_) => return Ok((0, 0)),
         };
 
         if entries.len() <= DB_BACKUP_RETAIN {
-            return Ok(());
+            return Ok((0, 0));
         }
 
         let remove_count = entries.len().saturating_sub(DB_BACKUP_RETAIN);
         let mut sorted = entries;
         sorted.sort_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
 
+        let mut removed = 0;
+        let mut bytes = 0;
         for entry in sorted.into_iter().take(remove_count) {
-            if let Err(err) = fs::remove_file(entry.path()) {
-                log::warn!("删除旧数据库备份失败 {}: {}", entry.path().display(), err);
+            let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+            match fs::remove_file(entry.path()) {
+                Ok(()) => {
+                    removed += 1;
+                    bytes += size;
+                }
+                Err(err) => {
+                    log::warn!("删除旧数据库备份失败 {}: {}", entry.path().display(), err)
+                }
             }
         }
-        Ok(())
+        Ok((removed, bytes))
     }
 
     /// 基础状态校验
@@ -198,7 +331,7 @@ impl Database {
     }
 
     /// 导出数据库为 SQL 文本
-    fn dump_sql(conn: &Connection) -> Result<String, AppError> {
+    pub(crate) fn dump_sql(conn: &Connection) -> Result<String, AppError> {
         let mut output = String::new();
         let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let user_version: i64 = conn
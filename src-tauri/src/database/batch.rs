@@ -0,0 +1,168 @@
+//! 批量写入
+//!
+//! 批量导入与同步合并会一次写入大量供应商与 MCP 服务器。逐条保存时中途失败会留下
+//! 一半新、一半旧的数据；这里把一组新增/更新/删除操作放在同一个事务中执行，任何一项
+//! 失败都整体回滚，数据库保持执行前的状态。
+
+use super::{lock_conn, Database};
+use crate::app_config::McpServer;
+use crate::change_events::ChangeEvent;
+use crate::error::AppError;
+use crate::provider::Provider;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 单个批量操作
+///
+/// 新增要求对象不存在，更新与删除要求对象已存在，避免并发修改时静默覆盖或漏删。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum BatchOp {
+    AddProvider {
+        app_type: String,
+        provider: Provider,
+    },
+    UpdateProvider {
+        app_type: String,
+        provider: Provider,
+    },
+    DeleteProvider {
+        app_type: String,
+        id: String,
+    },
+    AddMcpServer {
+        server: McpServer,
+    },
+    UpdateMcpServer {
+        server: McpServer,
+    },
+    DeleteMcpServer {
+        id: String,
+    },
+}
+
+impl BatchOp {
+    /// 操作对象的描述，用于错误信息
+    pub(crate) fn target(&self) -> String {
+        match self {
+            Self::AddProvider { app_type, provider }
+            | Self::UpdateProvider { app_type, provider } => {
+                format!("{app_type}/{}", provider.id)
+            }
+            Self::DeleteProvider { app_type, id } => format!("{app_type}/{id}"),
+            Self::AddMcpServer { server } | Self::UpdateMcpServer { server } => {
+                format!("mcp/{}", server.id)
+            }
+            Self::DeleteMcpServer { id } => format!("mcp/{id}"),
+        }
+    }
+
+    /// 提交后发出的变更事件
+    fn change_event(&self) -> ChangeEvent {
+        match self {
+            Self::AddProvider { app_type, provider } => ChangeEvent::ProviderAdded {
+                app_type: app_type.clone(),
+                provider_id: provider.id.clone(),
+            },
+            Self::UpdateProvider { app_type, provider } => ChangeEvent::ProviderUpdated {
+                app_type: app_type.clone(),
+                provider_id: provider.id.clone(),
+            },
+            Self::DeleteProvider { app_type, id } => ChangeEvent::ProviderDeleted {
+                app_type: app_type.clone(),
+                provider_id: id.clone(),
+            },
+            Self::AddMcpServer { server } | Self::UpdateMcpServer { server } => {
+                ChangeEvent::McpUpdated {
+                    id: server.id.clone(),
+                }
+            }
+            Self::DeleteMcpServer { id } => ChangeEvent::McpDeleted { id: id.clone() },
+        }
+    }
+}
+
+fn provider_exists(conn: &Connection, app_type: &str, id: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM providers WHERE id = ?1 AND app_type = ?2)",
+        params![id, app_type],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+fn mcp_server_exists(conn: &Connection, id: &str) -> Result<bool, AppError> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM mcp_servers WHERE id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+    .map_err(|e| AppError::Database(e.to_string()))
+}
+
+fn expect_exists(exists: bool, expected: bool) -> Result<(), AppError> {
+    match (exists, expected) {
+        (true, false) => Err(AppError::InvalidInput("已存在".to_string())),
+        (false, true) => Err(AppError::InvalidInput("不存在".to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn apply_op(conn: &Connection, op: &BatchOp) -> Result<(), AppError> {
+    match op {
+        BatchOp::AddProvider { app_type, provider } => {
+            expect_exists(provider_exists(conn, app_type, &provider.id)?, false)?;
+            Database::save_provider_on_conn(conn, app_type, provider).map(|_| ())
+        }
+        BatchOp::UpdateProvider { app_type, provider } => {
+            expect_exists(provider_exists(conn, app_type, &provider.id)?, true)?;
+            Database::save_provider_on_conn(conn, app_type, provider).map(|_| ())
+        }
+        BatchOp::DeleteProvider { app_type, id } => {
+            expect_exists(provider_exists(conn, app_type, id)?, true)?;
+            Database::delete_provider_on_conn(conn, app_type, id)
+        }
+        BatchOp::AddMcpServer { server } => {
+            expect_exists(mcp_server_exists(conn, &server.id)?, false)?;
+            Database::save_mcp_server_on_conn(conn, server)
+        }
+        BatchOp::UpdateMcpServer { server } => {
+            expect_exists(mcp_server_exists(conn, &server.id)?, true)?;
+            Database::save_mcp_server_on_conn(conn, server)
+        }
+        BatchOp::DeleteMcpServer { id } => {
+            expect_exists(mcp_server_exists(conn, id)?, true)?;
+            Database::delete_mcp_server_on_conn(conn, id)
+        }
+    }
+}
+
+impl Database {
+    /// 在一个事务中依次执行全部操作，返回执行的操作数
+    ///
+    /// 任何一项失败时回滚整个事务，错误信息中标明失败的操作序号与对象；提交后才发出
+    /// 各项的变更事件。
+    pub fn transaction(&self, ops: &[BatchOp]) -> Result<usize, AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        for (index, op) in ops.iter().enumerate() {
+            if let Err(e) = apply_op(&tx, op) {
+                // tx 在返回时 drop，自动回滚
+                return Err(AppError::Message(format!(
+                    "批量操作第 {} 项（{}）失败，已全部回滚: {e}",
+                    index + 1,
+                    op.target()
+                )));
+            }
+        }
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        drop(conn);
+
+        for op in ops {
+            self.notify(op.change_event());
+        }
+        Ok(ops.len())
+    }
+}
@@ -0,0 +1,127 @@
+//! 自动化规则 DAO
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::automation_rules::AutomationRule;
+use rusqlite::params;
+
+impl Database {
+    /// 获取全部规则（按创建时间排序）
+    pub fn get_automation_rules(&self) -> Result<Vec<AutomationRule>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, name, enabled, trigger_config, action_config, last_run_at, last_result, created_at
+                 FROM automation_rules ORDER BY created_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, i64>(7)?,
+                ))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let mut rules = Vec::new();
+        for row in rows {
+            let (id, name, enabled, trigger, action, last_run_at, last_result, created_at) =
+                row.map_err(|e| AppError::Database(e.to_string()))?;
+            // 无法解析的规则（如来自更新版本的动作类型）跳过，不影响其它规则
+            let (trigger, action) = match (
+                serde_json::from_str(&trigger),
+                serde_json::from_str(&action),
+            ) {
+                (Ok(trigger), Ok(action)) => (trigger, action),
+                _ => {
+                    log::warn!("跳过无法解析的自动化规则: {id}");
+                    continue;
+                }
+            };
+            rules.push(AutomationRule {
+                id,
+                name,
+                enabled,
+                trigger,
+                action,
+                last_run_at,
+                last_result,
+                created_at,
+            });
+        }
+        Ok(rules)
+    }
+
+    /// 新增或更新规则（保留执行记录），返回保存后的规则
+    pub fn save_automation_rule(&self, rule: &AutomationRule) -> Result<AutomationRule, AppError> {
+        rule.validate()?;
+        let mut rule = rule.clone();
+        if rule.id.is_empty() {
+            rule.id = uuid::Uuid::new_v4().to_string();
+        }
+        if rule.created_at == 0 {
+            rule.created_at = chrono::Utc::now().timestamp_millis();
+        }
+        let trigger = serde_json::to_string(&rule.trigger)
+            .map_err(|source| AppError::JsonSerialize { source })?;
+        let action = serde_json::to_string(&rule.action)
+            .map_err(|source| AppError::JsonSerialize { source })?;
+
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO automation_rules (id, name, enabled, trigger_config, action_config, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name, enabled = excluded.enabled,
+                trigger_config = excluded.trigger_config, action_config = excluded.action_config",
+            params![rule.id, rule.name, rule.enabled, trigger, action, rule.created_at],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.query_row(
+            "SELECT last_run_at, last_result, created_at FROM automation_rules WHERE id = ?1",
+            params![rule.id],
+            |row| {
+                rule.last_run_at = row.get(0)?;
+                rule.last_result = row.get(1)?;
+                rule.created_at = row.get(2)?;
+                Ok(())
+            },
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rule)
+    }
+
+    /// 删除规则
+    pub fn delete_automation_rule(&self, id: &str) -> Result<bool, AppError> {
+        let conn = lock_conn!(self.conn);
+        let affected = conn
+            .execute("DELETE FROM automation_rules WHERE id = ?1", params![id])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(affected > 0)
+    }
+
+    /// 记录一次执行结果
+    pub fn record_automation_rule_run(
+        &self,
+        id: &str,
+        ran_at: i64,
+        result: &str,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE automation_rules SET last_run_at = ?2, last_result = ?3 WHERE id = ?1",
+            params![id, ran_at, result],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! 端点测速结果 DAO
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::benchmark::{self, ProviderBenchmark};
+use rusqlite::params;
+
+impl Database {
+    /// 保存供应商的测速结果（覆盖上一次）
+    pub fn save_provider_benchmark(&self, result: &ProviderBenchmark) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT OR REPLACE INTO provider_benchmarks
+             (app_type, provider_id, provider_name, samples, successes, ttfb_ms, total_ms,
+              error, tested_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                result.app_type,
+                result.provider_id,
+                result.provider_name,
+                result.samples,
+                result.successes,
+                result.ttfb_ms.map(|ms| ms as i64),
+                result.total_ms.map(|ms| ms as i64),
+                result.error,
+                result.tested_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 应用的测速结果（按速度排序，已删除的供应商不返回）
+    pub fn get_provider_benchmarks(
+        &self,
+        app_type: &str,
+    ) -> Result<Vec<ProviderBenchmark>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT b.app_type, b.provider_id, b.provider_name, b.samples, b.successes,
+                 b.ttfb_ms, b.total_ms, b.error, b.tested_at
+                 FROM provider_benchmarks b
+                 JOIN providers p ON p.id = b.provider_id AND p.app_type = b.app_type
+                 WHERE b.app_type = ?1",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let mut results = stmt
+            .query_map(params![app_type], |row| {
+                Ok(ProviderBenchmark {
+                    app_type: row.get(0)?,
+                    provider_id: row.get(1)?,
+                    provider_name: row.get(2)?,
+                    samples: row.get(3)?,
+                    successes: row.get(4)?,
+                    ttfb_ms: row.get::<_, Option<i64>>(5)?.map(|ms| ms as u64),
+                    total_ms: row.get::<_, Option<i64>>(6)?.map(|ms| ms as u64),
+                    error: row.get(7)?,
+                    tested_at: row.get(8)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        benchmark::rank(&mut results);
+        Ok(results)
+    }
+}
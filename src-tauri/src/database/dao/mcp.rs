@@ -3,10 +3,11 @@
 //! 提供 MCP 服务器的 CRUD 操作。
 
 use crate::app_config::{McpApps, McpServer};
+use crate::change_events::ChangeEvent;
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection};
 
 impl Database {
     /// 获取所有 MCP 服务器
@@ -67,6 +68,20 @@ impl Database {
     /// 保存 MCP 服务器
     pub fn save_mcp_server(&self, server: &McpServer) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::save_mcp_server_on_conn(&conn, server)?;
+        drop(conn);
+
+        self.notify(ChangeEvent::McpUpdated {
+            id: server.id.clone(),
+        });
+        Ok(())
+    }
+
+    /// 在给定连接上保存 MCP 服务器
+    pub(crate) fn save_mcp_server_on_conn(
+        conn: &Connection,
+        server: &McpServer,
+    ) -> Result<(), AppError> {
         conn.execute(
             "INSERT OR REPLACE INTO mcp_servers (
                 id, name, server_config, description, homepage, docs, tags,
@@ -96,6 +111,15 @@ impl Database {
     /// 删除 MCP 服务器
     pub fn delete_mcp_server(&self, id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::delete_mcp_server_on_conn(&conn, id)?;
+        drop(conn);
+
+        self.notify(ChangeEvent::McpDeleted { id: id.to_string() });
+        Ok(())
+    }
+
+    /// 在给定连接上删除 MCP 服务器
+    pub(crate) fn delete_mcp_server_on_conn(conn: &Connection, id: &str) -> Result<(), AppError> {
         conn.execute("DELETE FROM mcp_servers WHERE id = ?1", params![id])
             .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
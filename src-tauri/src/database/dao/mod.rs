@@ -2,16 +2,31 @@
 //!
 //! Database access operations for each domain
 
+#[cfg(feature = "gui")]
+pub mod automation_rules;
+#[cfg(feature = "gui")]
+pub mod benchmarks;
 pub mod failover;
 pub mod mcp;
 pub mod prompts;
+pub mod provider_revisions;
 pub mod providers;
 pub mod proxy;
+#[cfg(feature = "gui")]
+pub mod session_usage;
 pub mod settings;
 pub mod skills;
+#[cfg(feature = "gui")]
 pub mod stream_check;
+pub mod switch_history;
 pub mod universal_providers;
+#[cfg(feature = "gui")]
+pub mod uptime;
 
 // 所有 DAO 方法都通过 Database impl 提供，无需单独导出
 // 导出 FailoverQueueItem 供外部使用
 pub use failover::FailoverQueueItem;
+pub use provider_revisions::ProviderRevision;
+pub use providers::{ProviderPage, ProviderSort};
+#[cfg(feature = "gui")]
+pub use session_usage::SessionUsageCursor;
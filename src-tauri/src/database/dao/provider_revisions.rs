@@ -0,0 +1,113 @@
+//! 供应商配置历史版本 DAO
+//!
+//! 每次更新供应商前保存旧的 `settings_config`，用于误改后的恢复。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::Value;
+
+/// 每个供应商保留的最大历史版本数
+const MAX_REVISIONS_PER_PROVIDER: i64 = 50;
+
+/// 供应商历史版本
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRevision {
+    pub revision: i64,
+    pub name: String,
+    pub settings_config: Value,
+    pub created_at: i64,
+}
+
+impl Database {
+    /// 记录一个历史版本，返回版本号
+    pub fn record_provider_revision(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        name: &str,
+        settings_config: &Value,
+    ) -> Result<i64, AppError> {
+        let conn = lock_conn!(self.conn);
+        let next: i64 = conn
+            .query_row(
+                "SELECT COALESCE(MAX(revision), 0) + 1 FROM provider_revisions
+                 WHERE provider_id = ?1 AND app_type = ?2",
+                params![provider_id, app_type],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO provider_revisions (provider_id, app_type, revision, name, settings_config, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                provider_id,
+                app_type,
+                next,
+                name,
+                serde_json::to_string(settings_config).map_err(|e| AppError::Database(format!(
+                    "Failed to serialize settings_config: {e}"
+                )))?,
+                chrono::Utc::now().timestamp_millis(),
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "DELETE FROM provider_revisions
+             WHERE provider_id = ?1 AND app_type = ?2 AND revision <= ?3",
+            params![provider_id, app_type, next - MAX_REVISIONS_PER_PROVIDER],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        Ok(next)
+    }
+
+    /// 获取供应商的历史版本（新版本在前）
+    pub fn get_provider_revisions(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+    ) -> Result<Vec<ProviderRevision>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT revision, name, settings_config, created_at FROM provider_revisions
+                 WHERE provider_id = ?1 AND app_type = ?2
+                 ORDER BY revision DESC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let rows = stmt
+            .query_map(params![provider_id, app_type], |row| {
+                let settings_config_str: String = row.get(2)?;
+                Ok(ProviderRevision {
+                    revision: row.get(0)?,
+                    name: row.get(1)?,
+                    settings_config: serde_json::from_str(&settings_config_str)
+                        .unwrap_or(Value::Null),
+                    created_at: row.get(3)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 获取指定历史版本
+    pub fn get_provider_revision(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        revision: i64,
+    ) -> Result<Option<ProviderRevision>, AppError> {
+        Ok(self
+            .get_provider_revisions(app_type, provider_id)?
+            .into_iter()
+            .find(|r| r.revision == revision))
+    }
+}
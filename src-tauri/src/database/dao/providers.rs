@@ -2,13 +2,54 @@
 //!
 //! 提供供应商（Provider）的 CRUD 操作。
 
+use crate::change_events::ChangeEvent;
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta};
 use indexmap::IndexMap;
-use rusqlite::params;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// 分页查询单页的最大条数
+pub const MAX_PROVIDER_PAGE_SIZE: usize = 500;
+
+/// 供应商列表的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderSort {
+    /// 用户拖拽排序（sort_index），与完整列表一致
+    #[default]
+    Custom,
+    Name,
+    NameDesc,
+    CreatedAt,
+    CreatedAtDesc,
+}
+
+impl ProviderSort {
+    fn order_by(self) -> &'static str {
+        match self {
+            ProviderSort::Custom => "COALESCE(sort_index, 999999), created_at ASC, id ASC",
+            ProviderSort::Name => "name COLLATE NOCASE ASC, id ASC",
+            ProviderSort::NameDesc => "name COLLATE NOCASE DESC, id ASC",
+            ProviderSort::CreatedAt => "COALESCE(created_at, 0) ASC, id ASC",
+            ProviderSort::CreatedAtDesc => "COALESCE(created_at, 0) DESC, id ASC",
+        }
+    }
+}
+
+/// 一页供应商
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPage {
+    pub items: Vec<Provider>,
+    /// 该应用的供应商总数
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
 impl Database {
     /// 获取指定应用类型的所有供应商
     pub fn get_all_providers(
@@ -16,14 +57,56 @@ impl Database {
         app_type: &str,
     ) -> Result<IndexMap<String, Provider>, AppError> {
         let conn = lock_conn!(self.conn);
-        let mut stmt = conn.prepare(
-            "SELECT id, name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue
+        // LIMIT -1 表示不限制条数
+        Self::query_providers(&conn, app_type, ProviderSort::Custom, -1, 0)
+    }
+
+    /// 分页获取指定应用类型的供应商，供应商很多时避免每次刷新都序列化全部数据
+    pub fn list_providers_page(
+        &self,
+        app_type: &str,
+        offset: usize,
+        limit: usize,
+        sort: ProviderSort,
+    ) -> Result<ProviderPage, AppError> {
+        let limit = limit.clamp(1, MAX_PROVIDER_PAGE_SIZE);
+        let conn = lock_conn!(self.conn);
+        let total: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM providers WHERE app_type = ?1",
+                params![app_type],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let items = Self::query_providers(&conn, app_type, sort, limit as i64, offset as i64)?
+            .into_values()
+            .collect();
+        Ok(ProviderPage {
+            items,
+            total: total as usize,
+            offset,
+            limit,
+        })
+    }
+
+    /// 按排序与分页读取供应商（含自定义端点）
+    fn query_providers(
+        conn: &Connection,
+        app_type: &str,
+        sort: ProviderSort,
+        limit: i64,
+        offset: i64,
+    ) -> Result<IndexMap<String, Provider>, AppError> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue, locked
              FROM providers WHERE app_type = ?1
-             ORDER BY COALESCE(sort_index, 999999), created_at ASC, id ASC"
-        ).map_err(|e| AppError::Database(e.to_string()))?;
+             ORDER BY {}
+             LIMIT ?2 OFFSET ?3",
+            sort.order_by()
+        )).map_err(|e| AppError::Database(e.to_string()))?;
 
         let provider_iter = stmt
-            .query_map(params![app_type], |row| {
+            .query_map(params![app_type, limit, offset], |row| {
                 let id: String = row.get(0)?;
                 let name: String = row.get(1)?;
                 let settings_config_str: String = row.get(2)?;
@@ -36,6 +119,7 @@ impl Database {
                 let icon_color: Option<String> = row.get(9)?;
                 let meta_str: String = row.get(10)?;
                 let in_failover_queue: bool = row.get(11)?;
+                let locked: bool = row.get(12)?;
 
                 let settings_config =
                     serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
@@ -56,6 +140,7 @@ impl Database {
                         icon,
                         icon_color,
                         in_failover_queue,
+                        locked,
                     },
                 ))
             })
@@ -131,7 +216,7 @@ impl Database {
     ) -> Result<Option<Provider>, AppError> {
         let conn = lock_conn!(self.conn);
         let result = conn.query_row(
-            "SELECT name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue
+            "SELECT name, settings_config, website_url, category, created_at, sort_index, notes, icon, icon_color, meta, in_failover_queue, locked
              FROM providers WHERE id = ?1 AND app_type = ?2",
             params![id, app_type],
             |row| {
@@ -146,6 +231,7 @@ impl Database {
                 let icon_color: Option<String> = row.get(8)?;
                 let meta_str: String = row.get(9)?;
                 let in_failover_queue: bool = row.get(10)?;
+                let locked: bool = row.get(11)?;
 
                 let settings_config = serde_json::from_str(&settings_config_str).unwrap_or(serde_json::Value::Null);
                 let meta: ProviderMeta = serde_json::from_str(&meta_str).unwrap_or_default();
@@ -163,6 +249,7 @@ impl Database {
                     icon,
                     icon_color,
                     in_failover_queue,
+                    locked,
                 })
             },
         );
@@ -183,23 +270,49 @@ impl Database {
         let tx = conn
             .transaction()
             .map_err(|e| AppError::Database(e.to_string()))?;
+        let added = Self::save_provider_on_conn(&tx, app_type, provider)?;
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        drop(conn);
+
+        let (app_type, provider_id) = (app_type.to_string(), provider.id.clone());
+        self.notify(if added {
+            ChangeEvent::ProviderAdded {
+                app_type,
+                provider_id,
+            }
+        } else {
+            ChangeEvent::ProviderUpdated {
+                app_type,
+                provider_id,
+            }
+        });
+        Ok(())
+    }
 
+    /// 在给定连接（通常是调用方的事务）上保存供应商，语义同 [`Database::save_provider`]
+    ///
+    /// 返回是否为新增。
+    pub(crate) fn save_provider_on_conn(
+        tx: &Connection,
+        app_type: &str,
+        provider: &Provider,
+    ) -> Result<bool, AppError> {
         // 处理 meta：取出 endpoints 以便单独处理
         let mut meta_clone = provider.meta.clone().unwrap_or_default();
         let endpoints = std::mem::take(&mut meta_clone.custom_endpoints);
 
-        // 检查是否存在（用于判断新增/更新，以及保留 is_current 和 in_failover_queue）
-        let existing: Option<(bool, bool)> = tx
+        // 检查是否存在（用于判断新增/更新，以及保留 is_current、in_failover_queue 和 locked）
+        let existing: Option<(bool, bool, bool)> = tx
             .query_row(
-                "SELECT is_current, in_failover_queue FROM providers WHERE id = ?1 AND app_type = ?2",
+                "SELECT is_current, in_failover_queue, locked FROM providers WHERE id = ?1 AND app_type = ?2",
                 params![provider.id, app_type],
-                |row| Ok((row.get(0)?, row.get(1)?)),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
             )
             .ok();
 
         let is_update = existing.is_some();
-        let (is_current, in_failover_queue) =
-            existing.unwrap_or((false, provider.in_failover_queue));
+        let (is_current, in_failover_queue, locked) =
+            existing.unwrap_or((false, provider.in_failover_queue, provider.locked));
 
         if is_update {
             // 更新模式：使用 UPDATE 避免触发 ON DELETE CASCADE
@@ -216,8 +329,9 @@ impl Database {
                     icon_color = ?9,
                     meta = ?10,
                     is_current = ?11,
-                    in_failover_queue = ?12
-                WHERE id = ?13 AND app_type = ?14",
+                    in_failover_queue = ?12,
+                    locked = ?13
+                WHERE id = ?14 AND app_type = ?15",
                 params![
                     provider.name,
                     serde_json::to_string(&provider.settings_config).map_err(|e| {
@@ -235,6 +349,7 @@ impl Database {
                     )))?,
                     is_current,
                     in_failover_queue,
+                    locked,
                     provider.id,
                     app_type,
                 ],
@@ -245,8 +360,8 @@ impl Database {
             tx.execute(
                 "INSERT INTO providers (
                     id, app_type, name, settings_config, website_url, category,
-                    created_at, sort_index, notes, icon, icon_color, meta, is_current, in_failover_queue
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+                    created_at, sort_index, notes, icon, icon_color, meta, is_current, in_failover_queue, locked
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
                 params![
                     provider.id,
                     app_type,
@@ -264,6 +379,7 @@ impl Database {
                         .map_err(|e| AppError::Database(format!("Failed to serialize meta: {e}")))?,
                     is_current,
                     in_failover_queue,
+                    locked,
                 ],
             )
             .map_err(|e| AppError::Database(e.to_string()))?;
@@ -278,19 +394,54 @@ impl Database {
                 .map_err(|e| AppError::Database(e.to_string()))?;
             }
         }
-
-        tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
-        Ok(())
+        Ok(!is_update)
     }
 
-    /// 删除供应商
+    /// 删除供应商（同时清理其历史版本）
     pub fn delete_provider(&self, app_type: &str, id: &str) -> Result<(), AppError> {
         let conn = lock_conn!(self.conn);
+        Self::delete_provider_on_conn(&conn, app_type, id)?;
+        drop(conn);
+
+        self.notify(ChangeEvent::ProviderDeleted {
+            app_type: app_type.to_string(),
+            provider_id: id.to_string(),
+        });
+        Ok(())
+    }
+
+    /// 在给定连接上删除供应商及其历史版本
+    pub(crate) fn delete_provider_on_conn(
+        conn: &Connection,
+        app_type: &str,
+        id: &str,
+    ) -> Result<(), AppError> {
         conn.execute(
             "DELETE FROM providers WHERE id = ?1 AND app_type = ?2",
             params![id, app_type],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM provider_revisions WHERE provider_id = ?1 AND app_type = ?2",
+            params![id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 设置供应商的保护（锁定）状态
+    pub fn set_provider_locked(
+        &self,
+        app_type: &str,
+        id: &str,
+        locked: bool,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "UPDATE providers SET locked = ?1 WHERE id = ?2 AND app_type = ?3",
+            params![locked, id, app_type],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
         Ok(())
     }
 
@@ -316,6 +467,12 @@ impl Database {
         .map_err(|e| AppError::Database(e.to_string()))?;
 
         tx.commit().map_err(|e| AppError::Database(e.to_string()))?;
+        drop(conn);
+
+        self.notify(ChangeEvent::ProviderSwitched {
+            app_type: app_type.to_string(),
+            provider_id: id.to_string(),
+        });
         Ok(())
     }
 
@@ -338,6 +495,12 @@ impl Database {
             ],
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
+        drop(conn);
+
+        self.notify(ChangeEvent::ProviderUpdated {
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+        });
         Ok(())
     }
 
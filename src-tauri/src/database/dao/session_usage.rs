@@ -0,0 +1,133 @@
+//! 会话用量 DAO
+//!
+//! 保存从 Claude Code / Codex 会话日志统计出的用量聚合，以及每个日志文件已读取的位置。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::session_usage::SessionUsageRow;
+use rusqlite::{params, OptionalExtension};
+
+/// 会话日志的读取位置
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionUsageCursor {
+    /// 已统计到的字节位置
+    pub offset: u64,
+    /// 最近一次出现的模型（Codex 日志只在每轮开头记录模型）
+    pub model: Option<String>,
+    /// 最近一次的累计 token 数（Codex 会重复上报相同的用量）
+    pub last_total: Option<u64>,
+}
+
+impl Database {
+    /// 获取日志文件的读取位置
+    pub fn get_session_usage_cursor(
+        &self,
+        path: &str,
+    ) -> Result<Option<SessionUsageCursor>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT offset, model, last_total FROM session_usage_files WHERE path = ?1",
+            params![path],
+            |row| {
+                Ok(SessionUsageCursor {
+                    offset: row.get::<_, i64>(0)? as u64,
+                    model: row.get(1)?,
+                    last_total: row.get::<_, Option<i64>>(2)?.map(|total| total as u64),
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 累加一个日志文件新增的用量并更新读取位置（同一事务，避免重复统计）
+    pub fn add_session_usage(
+        &self,
+        path: &str,
+        cursor: &SessionUsageCursor,
+        rows: &[SessionUsageRow],
+    ) -> Result<(), AppError> {
+        let mut conn = lock_conn!(self.conn);
+        let tx = conn
+            .transaction()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+
+        for row in rows {
+            tx.execute(
+                "INSERT INTO session_usage (app_type, provider_id, date, model, input_tokens,
+                 output_tokens, cache_read_tokens, cache_creation_tokens, message_count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                 ON CONFLICT(app_type, provider_id, date, model) DO UPDATE SET
+                 input_tokens = input_tokens + excluded.input_tokens,
+                 output_tokens = output_tokens + excluded.output_tokens,
+                 cache_read_tokens = cache_read_tokens + excluded.cache_read_tokens,
+                 cache_creation_tokens = cache_creation_tokens + excluded.cache_creation_tokens,
+                 message_count = message_count + excluded.message_count",
+                params![
+                    row.app_type,
+                    row.provider_id,
+                    row.date,
+                    row.model,
+                    row.input_tokens as i64,
+                    row.output_tokens as i64,
+                    row.cache_read_tokens as i64,
+                    row.cache_creation_tokens as i64,
+                    row.message_count as i64,
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO session_usage_files (path, offset, model, last_total)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                path,
+                cursor.offset as i64,
+                cursor.model,
+                cursor.last_total.map(|total| total as i64)
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        tx.commit().map_err(|e| AppError::Database(e.to_string()))
+    }
+
+    /// 查询用量聚合（按日期、模型排序）
+    ///
+    /// `provider_id` 为空时返回所有供应商；`since` 为起始日期（`YYYY-MM-DD`，含当天）。
+    pub fn get_session_usage(
+        &self,
+        provider_id: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<Vec<SessionUsageRow>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_type, provider_id, date, model, input_tokens, output_tokens,
+                 cache_read_tokens, cache_creation_tokens, message_count
+                 FROM session_usage
+                 WHERE (?1 IS NULL OR provider_id = ?1) AND (?2 IS NULL OR date >= ?2)
+                 ORDER BY date ASC, app_type ASC, provider_id ASC, model ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let rows = stmt
+            .query_map(params![provider_id, since], |row| {
+                Ok(SessionUsageRow {
+                    app_type: row.get(0)?,
+                    provider_id: row.get(1)?,
+                    date: row.get(2)?,
+                    model: row.get(3)?,
+                    input_tokens: row.get::<_, i64>(4)? as u64,
+                    output_tokens: row.get::<_, i64>(5)? as u64,
+                    cache_read_tokens: row.get::<_, i64>(6)? as u64,
+                    cache_creation_tokens: row.get::<_, i64>(7)? as u64,
+                    message_count: row.get::<_, i64>(8)? as u64,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(rows)
+    }
+}
@@ -2,7 +2,8 @@
 
 use crate::database::{lock_conn, Database};
 use crate::error::AppError;
-use crate::services::stream_check::{StreamCheckConfig, StreamCheckResult};
+use crate::services::stream_check::{StreamCheckConfig, StreamCheckLogSummary, StreamCheckResult};
+use rusqlite::OptionalExtension;
 
 impl Database {
     /// 保存流式检查日志
@@ -39,6 +40,27 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// 获取最近一次检查（所有应用）
+    pub fn get_latest_stream_check_log(&self) -> Result<Option<StreamCheckLogSummary>, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.query_row(
+            "SELECT app_type, provider_name, status, response_time_ms, tested_at
+             FROM stream_check_logs ORDER BY tested_at DESC, id DESC LIMIT 1",
+            [],
+            |row| {
+                Ok(StreamCheckLogSummary {
+                    app_type: row.get(0)?,
+                    provider_name: row.get(1)?,
+                    status: row.get(2)?,
+                    response_time_ms: row.get::<_, Option<i64>>(3)?.map(|ms| ms as u64),
+                    tested_at: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+
     /// 获取流式检查配置
     pub fn get_stream_check_config(&self) -> Result<StreamCheckConfig, AppError> {
         match self.get_setting("stream_check_config")? {
@@ -0,0 +1,108 @@
+//! 供应商切换历史 DAO
+//!
+//! 记录每次切换的目标供应商，用于托盘菜单的「最近使用」、用量面板的切换频率，以及把
+//! 会话日志中的用量归属到当时使用的供应商。
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::params;
+
+/// 每个应用保留的最大切换记录数
+const MAX_SWITCHES_PER_APP: i64 = 200;
+
+impl Database {
+    /// 记录一次切换
+    pub fn record_provider_switch(
+        &self,
+        app_type: &str,
+        provider_id: &str,
+        switched_at: i64,
+    ) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_switch_history (app_type, provider_id, switched_at)
+             VALUES (?1, ?2, ?3)",
+            params![app_type, provider_id, switched_at],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "DELETE FROM provider_switch_history
+             WHERE app_type = ?1 AND id NOT IN (
+                 SELECT id FROM provider_switch_history
+                 WHERE app_type = ?1 ORDER BY id DESC LIMIT ?2
+             )",
+            params![app_type, MAX_SWITCHES_PER_APP],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 最近切换过的不同供应商（最近的在前，已删除的供应商不计入）
+    pub fn get_recent_provider_ids(
+        &self,
+        app_type: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT h.provider_id FROM provider_switch_history h
+                 JOIN providers p ON p.id = h.provider_id AND p.app_type = h.app_type
+                 WHERE h.app_type = ?1
+                 GROUP BY h.provider_id
+                 ORDER BY MAX(h.id) DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let ids = stmt
+            .query_map(params![app_type, limit as i64], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(ids)
+    }
+
+    /// 切换时间线（按时间先后，`(switched_at 毫秒, provider_id)`）
+    pub fn get_provider_switch_timeline(
+        &self,
+        app_type: &str,
+    ) -> Result<Vec<(i64, String)>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT switched_at, provider_id FROM provider_switch_history
+                 WHERE app_type = ?1 ORDER BY switched_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let timeline = stmt
+            .query_map(params![app_type], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(timeline)
+    }
+
+    /// 各供应商的切换次数（`(app_type, provider_id, 次数)`），`since` 为起始毫秒时间戳
+    pub fn get_provider_switch_counts(
+        &self,
+        since: Option<i64>,
+    ) -> Result<Vec<(String, String, u64)>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_type, provider_id, COUNT(*) FROM provider_switch_history
+                 WHERE ?1 IS NULL OR switched_at >= ?1
+                 GROUP BY app_type, provider_id",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let counts = stmt
+            .query_map(params![since], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, i64>(2)? as u64))
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(counts)
+    }
+}
@@ -0,0 +1,72 @@
+//! 供应商可用性检查记录 DAO
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::services::uptime::UptimeCheck;
+use rusqlite::params;
+
+impl Database {
+    /// 记录一次可用性检查
+    pub fn record_uptime_check(&self, check: &UptimeCheck) -> Result<(), AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "INSERT INTO provider_uptime_checks
+             (app_type, provider_id, up, http_status, latency_ms, reason, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                check.app_type,
+                check.provider_id,
+                check.up,
+                check.http_status,
+                check.latency_ms.map(|ms| ms as i64),
+                check.reason,
+                check.checked_at,
+            ],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// 某时间之后的检查记录（为空时查询全部应用，按时间先后）
+    pub fn get_uptime_checks(
+        &self,
+        app_type: Option<&str>,
+        since: i64,
+    ) -> Result<Vec<UptimeCheck>, AppError> {
+        let conn = lock_conn!(self.conn);
+        let mut stmt = conn
+            .prepare(
+                "SELECT app_type, provider_id, up, http_status, latency_ms, reason, checked_at
+                 FROM provider_uptime_checks
+                 WHERE (?1 IS NULL OR app_type = ?1) AND checked_at >= ?2
+                 ORDER BY checked_at ASC, id ASC",
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let checks = stmt
+            .query_map(params![app_type, since], |row| {
+                Ok(UptimeCheck {
+                    app_type: row.get(0)?,
+                    provider_id: row.get(1)?,
+                    up: row.get(2)?,
+                    http_status: row.get(3)?,
+                    latency_ms: row.get::<_, Option<i64>>(4)?.map(|ms| ms as u64),
+                    reason: row.get(5)?,
+                    checked_at: row.get(6)?,
+                })
+            })
+            .map_err(|e| AppError::Database(e.to_string()))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(checks)
+    }
+
+    /// 删除某时间之前的检查记录，返回删除的条数
+    pub fn prune_uptime_checks(&self, before: i64) -> Result<usize, AppError> {
+        let conn = lock_conn!(self.conn);
+        conn.execute(
+            "DELETE FROM provider_uptime_checks WHERE checked_at < ?1",
+            params![before],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))
+    }
+}
@@ -0,0 +1,633 @@
+//! 数据库静态加密
+//!
+//! 启用后磁盘上不再保留明文 `cc-switch.db`，而是保存口令加密的 SQL 导出
+//! `cc-switch.db.enc`（格式见 [`crate::crypto`]）；运行时数据库完全位于内存中，
+//! 由后台任务在数据变化后重新加密写回。这样 Dropbox / git / WebDAV 等外部同步
+//! 手段只能看到密文，看不到供应商 API Key。
+//!
+//! 口令保存在系统钥匙串中；新设备上可通过环境变量 `CC_SWITCH_DB_PASSPHRASE` 提供。
+//!
+//! 也可改用主密码：密钥由 Argon2id 派生，不写入钥匙串。应用启动时数据库处于锁定
+//! 状态（仅有空的内存数据库），前端提示输入主密码解锁后才加载真实数据；派生出的
+//! 密钥缓存在内存中，锁定时清除。
+//!
+//! 同步基线、应用快照等落盘的数据库副本同样用这把密钥加密（见
+//! [`Database::seal_at_rest`]），磁盘上不会留下可直接读取的数据库内容。
+//!
+//! 命令行等其他进程也可能写回同一个加密文件。写回时持有跨进程文件锁并重新读取文件：
+//! 文件在本进程上次读写之后被其他进程改过时，本进程没有未写回的修改则重新加载对方的
+//! 内容；双方都有修改则把对方的文件另存为 `cc-switch.db.enc.conflict-<时间>` 后再写入。
+
+use super::profiles::database_dir;
+use super::{lock_conn, Database, SqlConn};
+use crate::crypto::MasterKey;
+use crate::error::AppError;
+use rusqlite::backup::Backup;
+use rusqlite::Connection;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 加密数据库文件名
+const ENCRYPTED_DB_FILE: &str = "cc-switch.db.enc";
+
+/// 钥匙串中保存数据库口令的键
+const PASSPHRASE_SECRET_KEY: &str = "database-passphrase";
+
+/// 口令的环境变量回退（钥匙串中没有口令或钥匙串不可用时使用）
+const PASSPHRASE_ENV: &str = "CC_SWITCH_DB_PASSPHRASE";
+
+/// 后台写回检查间隔
+const FLUSH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 加密密钥来源
+enum AtRestKey {
+    /// 钥匙串中保存的口令（PBKDF2）
+    Passphrase(String),
+    /// 主密码派生的密钥（Argon2id）；`None` 表示已锁定
+    Master(Option<MasterKey>),
+}
+
+impl AtRestKey {
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Option<Vec<u8>>, AppError> {
+        match self {
+            AtRestKey::Passphrase(passphrase) => {
+                crate::crypto::encrypt_with_passphrase(plaintext, passphrase).map(Some)
+            }
+            AtRestKey::Master(Some(key)) => key.encrypt(plaintext).map(Some),
+            AtRestKey::Master(None) => Ok(None),
+        }
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Option<Vec<u8>>, AppError> {
+        match self {
+            AtRestKey::Passphrase(passphrase) => {
+                crate::crypto::decrypt_with_passphrase(data, passphrase).map(Some)
+            }
+            AtRestKey::Master(Some(key)) => key.decrypt(data).map(Some),
+            AtRestKey::Master(None) => Ok(None),
+        }
+    }
+}
+
+/// 静态加密运行时状态
+pub(crate) struct AtRestEncryption {
+    key: AtRestKey,
+    /// 加密文件路径（打开时确定，之后切换配置档不影响写回位置）
+    path: PathBuf,
+    /// 上次写回时连接的 total_changes()，用于判断是否有新的修改
+    persisted_changes: i64,
+    /// 本进程上次读取或写入的加密文件摘要，用于发现其他进程的写入
+    file_digest: Option<[u8; 32]>,
+}
+
+/// 静态加密状态（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseEncryptionStatus {
+    pub enabled: bool,
+    /// 是否使用主密码（否则为钥匙串口令）
+    pub master_password: bool,
+    /// 主密码模式下数据库是否处于锁定状态
+    pub locked: bool,
+    pub path: String,
+}
+
+pub(crate) fn encrypted_db_path() -> PathBuf {
+    encrypted_db_path_in(&database_dir())
+}
+
+/// 指定配置档目录下的加密数据库文件
+pub(crate) fn encrypted_db_path_in(dir: &Path) -> PathBuf {
+    dir.join(ENCRYPTED_DB_FILE)
+}
+
+fn plain_db_path() -> PathBuf {
+    database_dir().join("cc-switch.db")
+}
+
+fn load_passphrase() -> Result<String, AppError> {
+    let keychain_error = match crate::secret_store::get_secret(PASSPHRASE_SECRET_KEY) {
+        Ok(Some(passphrase)) => return Ok(passphrase),
+        Ok(None) => None,
+        Err(e) => Some(e),
+    };
+    // 无桌面会话的服务器上通常没有 Secret Service，此时同样允许使用环境变量
+    if let Some(passphrase) = std::env::var(PASSPHRASE_ENV).ok().filter(|v| !v.is_empty()) {
+        return Ok(passphrase);
+    }
+    Err(keychain_error.unwrap_or_else(|| {
+            AppError::localized(
+                "database.encryption.passphrase_missing",
+                "数据库已加密，但系统钥匙串中没有口令。请设置环境变量 CC_SWITCH_DB_PASSPHRASE 后重新启动。",
+                "The database is encrypted but no passphrase was found in the system keychain. Set CC_SWITCH_DB_PASSPHRASE and restart.",
+            )
+        }))
+}
+
+fn total_changes(conn: &Connection) -> Result<i64, AppError> {
+    conn.query_row("SELECT total_changes()", [], |row| row.get(0))
+        .map_err(|e| AppError::Database(e.to_string()))
+}
+
+fn digest(bytes: &[u8]) -> [u8; 32] {
+    Sha256::digest(bytes).into()
+}
+
+/// 把其他进程写入、与本进程修改冲突的加密文件另存一份
+fn preserve_conflicting_file(path: &Path, bytes: &[u8]) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let target = path.with_file_name(format!(
+        "{name}.conflict-{}",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    ));
+    match crate::config::atomic_write(&target, bytes) {
+        Ok(()) => log::warn!(
+            "加密数据库已被其他进程修改，对方的版本另存为 {}",
+            target.display()
+        ),
+        Err(e) => log::error!("另存其他进程写入的加密数据库失败: {e}"),
+    }
+}
+
+/// 删除磁盘上的明文数据库及其自动备份
+fn remove_plaintext_files() {
+    let db_path = plain_db_path();
+    for suffix in ["", "-wal", "-shm", "-journal"] {
+        let path = PathBuf::from(format!("{}{suffix}", db_path.display()));
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("删除明文数据库文件失败 {}: {e}", path.display());
+            }
+        }
+    }
+
+    let backup_dir = database_dir().join("backups");
+    let Ok(entries) = fs::read_dir(&backup_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with("db_backup_") && name.ends_with(".db") {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                log::warn!("删除明文数据库备份失败 {name}: {e}");
+            }
+        }
+    }
+}
+
+fn open_memory() -> Result<Connection, AppError> {
+    let conn = Connection::open_in_memory().map_err(|e| AppError::Database(e.to_string()))?;
+    conn.execute("PRAGMA foreign_keys = ON;", [])
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(conn)
+}
+
+pub(super) fn locked_error() -> AppError {
+    AppError::localized(
+        "database.encryption.locked",
+        "数据库已锁定，请先输入主密码解锁",
+        "The database is locked. Unlock it with the master password first.",
+    )
+}
+
+impl SqlConn {
+    /// 替换连接并设置锁定状态
+    fn replace(&self, conn: Connection, locked: bool) -> Result<(), AppError> {
+        let mut current = self.lock_unchecked()?;
+        *current = conn;
+        self.locked.store(locked, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl Database {
+    /// 从加密文件打开数据库（数据库位于内存中）
+    ///
+    /// 主密码加密的文件不会在此解密：返回一个已锁定的空内存数据库，等待 [`Self::unlock`]。
+    /// 锁定期间任何数据库访问都返回错误。
+    pub(crate) fn init_encrypted(path: &Path) -> Result<Self, AppError> {
+        let bytes = fs::read(path).map_err(|e| AppError::io(path, e))?;
+        let key = if crate::crypto::is_master_encrypted(&bytes) {
+            log::info!("数据库使用主密码加密，等待解锁");
+            AtRestKey::Master(None)
+        } else {
+            AtRestKey::Passphrase(load_passphrase()?)
+        };
+        Self::open_encrypted_file(path, &bytes, key)
+    }
+
+    fn open_encrypted_file(path: &Path, bytes: &[u8], key: AtRestKey) -> Result<Self, AppError> {
+        let conn = match &key {
+            AtRestKey::Passphrase(passphrase) => Self::open_encrypted_bytes(bytes, passphrase)?,
+            AtRestKey::Master(_) => open_memory()?,
+        };
+        let persisted_changes = total_changes(&conn)?;
+
+        let locked = matches!(key, AtRestKey::Master(None));
+        let db = Self::from_connection(conn);
+        *db.lock_encryption()? = Some(AtRestEncryption {
+            key,
+            path: path.to_path_buf(),
+            persisted_changes,
+            file_digest: Some(digest(bytes)),
+        });
+        if locked {
+            db.conn.locked.store(true, Ordering::SeqCst);
+            return Ok(db);
+        }
+        db.create_tables()?;
+        db.apply_schema_migrations()?;
+        db.ensure_model_pricing_seeded()?;
+
+        Ok(db)
+    }
+
+    /// 解密加密数据库内容并加载为内存连接
+    pub(crate) fn open_encrypted_bytes(
+        bytes: &[u8],
+        passphrase: &str,
+    ) -> Result<Connection, AppError> {
+        let plain = crate::crypto::decrypt_with_passphrase(bytes, passphrase)?;
+        let sql = String::from_utf8(plain)
+            .map_err(|e| AppError::Database(format!("解密后的数据库不是有效的 SQL 文本: {e}")))?;
+        let conn = Self::sql_export_to_memory(&sql)?;
+        conn.execute("PRAGMA foreign_keys = ON;", [])
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok(conn)
+    }
+
+    pub(super) fn lock_encryption(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, Option<AtRestEncryption>>, AppError> {
+        self.encryption
+            .lock()
+            .map_err(|e| AppError::Database(format!("Mutex lock failed: {e}")))
+    }
+
+    /// 当前是否启用了静态加密
+    pub fn is_encrypted_at_rest(&self) -> bool {
+        self.lock_encryption()
+            .map(|guard| guard.is_some())
+            .unwrap_or(false)
+    }
+
+    /// 主密码模式下数据库是否处于锁定状态
+    pub fn is_locked(&self) -> bool {
+        self.lock_encryption()
+            .map(|guard| {
+                matches!(
+                    guard.as_ref().map(|state| &state.key),
+                    Some(AtRestKey::Master(None))
+                )
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn encryption_status(&self) -> DatabaseEncryptionStatus {
+        let (enabled, master_password) = self
+            .lock_encryption()
+            .map(|guard| match guard.as_ref().map(|state| &state.key) {
+                Some(AtRestKey::Master(_)) => (true, true),
+                Some(AtRestKey::Passphrase(_)) => (true, false),
+                None => (false, false),
+            })
+            .unwrap_or((false, false));
+        let path = if enabled {
+            encrypted_db_path()
+        } else {
+            plain_db_path()
+        };
+        DatabaseEncryptionStatus {
+            enabled,
+            master_password,
+            locked: self.is_locked(),
+            path: path.to_string_lossy().to_string(),
+        }
+    }
+
+    /// 启用静态加密：写入加密文件，切换到内存数据库，并删除明文数据库与自动备份
+    pub fn enable_encryption(&self, passphrase: &str) -> Result<(), AppError> {
+        if passphrase.is_empty() {
+            return Err(AppError::InvalidInput("加密口令不能为空".to_string()));
+        }
+        self.enable_with_key(AtRestKey::Passphrase(passphrase.to_string()))
+    }
+
+    /// 使用主密码启用静态加密（密钥由 Argon2id 派生，仅缓存在内存中，不写入钥匙串）
+    pub fn enable_master_password(&self, password: &str) -> Result<(), AppError> {
+        let key = MasterKey::generate(password)?;
+        self.enable_with_key(AtRestKey::Master(Some(key)))
+    }
+
+    fn enable_with_key(&self, key: AtRestKey) -> Result<(), AppError> {
+        let mut encryption = self.lock_encryption()?;
+        if encryption.is_some() {
+            return Err(AppError::InvalidInput("数据库已启用加密".to_string()));
+        }
+
+        let enc_path = encrypted_db_path();
+        {
+            let mut conn = lock_conn!(self.conn);
+            let mut memory = open_memory()?;
+            {
+                let backup = Backup::new(&conn, &mut memory)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                backup
+                    .step(-1)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+
+            let dump = Self::dump_sql(&memory)?;
+            let bytes = key.encrypt(dump.as_bytes())?.ok_or_else(locked_error)?;
+            if let AtRestKey::Passphrase(passphrase) = &key {
+                crate::secret_store::set_secret(PASSPHRASE_SECRET_KEY, passphrase)?;
+            }
+            {
+                let _lock = crate::file_lock::lock(&enc_path)?;
+                crate::config::atomic_write(&enc_path, &bytes)?;
+            }
+
+            *encryption = Some(AtRestEncryption {
+                key,
+                path: enc_path.clone(),
+                persisted_changes: total_changes(&memory)?,
+                file_digest: Some(digest(&bytes)),
+            });
+            // 替换连接后旧的文件连接被关闭，随后才能删除明文文件
+            *conn = memory;
+        }
+
+        remove_plaintext_files();
+        log::info!("数据库已启用静态加密: {}", enc_path.display());
+        Ok(())
+    }
+
+    /// 输入主密码解锁数据库：解密加密文件并替换当前的空内存数据库
+    pub fn unlock(&self, password: &str) -> Result<(), AppError> {
+        {
+            let mut encryption = self.lock_encryption()?;
+            let Some(state) = encryption.as_mut() else {
+                return Err(AppError::InvalidInput("数据库未启用加密".to_string()));
+            };
+            if !matches!(state.key, AtRestKey::Master(None)) {
+                return Err(AppError::InvalidInput("数据库未处于锁定状态".to_string()));
+            }
+
+            let path = state.path.clone();
+            let bytes = fs::read(&path).map_err(|e| AppError::io(&path, e))?;
+            let (key, plain) = MasterKey::unlock(&bytes, password)?;
+            let sql = String::from_utf8(plain).map_err(|e| {
+                AppError::Database(format!("解密后的数据库不是有效的 SQL 文本: {e}"))
+            })?;
+            let unlocked = Self::sql_export_to_memory(&sql)?;
+            unlocked
+                .execute("PRAGMA foreign_keys = ON;", [])
+                .map_err(|e| AppError::Database(e.to_string()))?;
+
+            state.persisted_changes = total_changes(&unlocked)?;
+            state.file_digest = Some(digest(&bytes));
+            state.key = AtRestKey::Master(Some(key));
+            self.conn.replace(unlocked, false)?;
+        }
+
+        self.create_tables()?;
+        self.apply_schema_migrations()?;
+        self.ensure_model_pricing_seeded()?;
+        log::info!("数据库已解锁");
+        Ok(())
+    }
+
+    /// 锁定数据库：写回加密文件后清除内存中的数据与主密钥
+    pub fn lock(&self) -> Result<(), AppError> {
+        self.flush_encrypted(true)?;
+        {
+            let mut encryption = self.lock_encryption()?;
+            let Some(state) = encryption.as_mut() else {
+                return Err(AppError::InvalidInput("数据库未启用加密".to_string()));
+            };
+            match state.key {
+                AtRestKey::Master(Some(_)) => {}
+                AtRestKey::Master(None) => return Ok(()),
+                AtRestKey::Passphrase(_) => {
+                    return Err(AppError::InvalidInput(
+                        "仅主密码加密的数据库可以锁定".to_string(),
+                    ))
+                }
+            }
+
+            let memory = open_memory()?;
+            state.persisted_changes = total_changes(&memory)?;
+            state.key = AtRestKey::Master(None);
+            self.conn.replace(memory, true)?;
+        }
+
+        log::info!("数据库已锁定");
+        Ok(())
+    }
+
+    /// 关闭静态加密：将内存数据库写回明文 `cc-switch.db`，删除加密文件与钥匙串口令
+    pub fn disable_encryption(&self) -> Result<(), AppError> {
+        let mut encryption = self.lock_encryption()?;
+        match encryption.as_ref().map(|state| &state.key) {
+            None => return Err(AppError::InvalidInput("数据库未启用加密".to_string())),
+            Some(AtRestKey::Master(None)) => return Err(locked_error()),
+            Some(_) => {}
+        }
+
+        let db_path = plain_db_path();
+        {
+            let mut conn = lock_conn!(self.conn);
+            let mut file_conn =
+                Connection::open(&db_path).map_err(|e| AppError::Database(e.to_string()))?;
+            {
+                let backup = Backup::new(&conn, &mut file_conn)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                backup
+                    .step(-1)
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+            }
+            super::configure_file_connection(&file_conn, &db_path)?;
+            *conn = file_conn;
+        }
+        *encryption = None;
+
+        let enc_path = encrypted_db_path();
+        if enc_path.exists() {
+            let _lock = crate::file_lock::lock(&enc_path)?;
+            fs::remove_file(&enc_path).map_err(|e| AppError::io(&enc_path, e))?;
+        }
+        crate::secret_store::delete_secret(PASSPHRASE_SECRET_KEY)?;
+        log::info!("数据库已关闭静态加密: {}", db_path.display());
+        Ok(())
+    }
+
+    /// 用静态加密密钥加密要落盘的数据库副本（未启用加密时原样返回）
+    ///
+    /// 主密码模式下数据库锁定时返回错误，而不是写出明文。
+    pub fn seal_at_rest(&self, plaintext: Vec<u8>) -> Result<Vec<u8>, AppError> {
+        let encryption = self.lock_encryption()?;
+        let Some(state) = encryption.as_ref() else {
+            return Ok(plaintext);
+        };
+        state.key.encrypt(&plaintext)?.ok_or_else(locked_error)
+    }
+
+    /// 解密 [`Self::seal_at_rest`] 写出的数据库副本（启用加密前写出的明文原样返回）
+    pub fn open_at_rest(&self, data: Vec<u8>) -> Result<Vec<u8>, AppError> {
+        if !crate::crypto::is_encrypted(&data) {
+            return Ok(data);
+        }
+        let encryption = self.lock_encryption()?;
+        let Some(state) = encryption.as_ref() else {
+            return Err(AppError::localized(
+                "database.encryption.sealed_copy",
+                "该数据库副本已加密，但当前数据库未启用静态加密",
+                "This database copy is encrypted, but at-rest encryption is not enabled",
+            ));
+        };
+        state.key.decrypt(&data)?.ok_or_else(locked_error)
+    }
+
+    /// 将内存数据库重新加密写回磁盘
+    ///
+    /// 未启用加密时直接返回；`force` 为 false 时仅在有新修改时写入。返回是否写入。
+    ///
+    /// 加密文件被其他进程改过而本进程没有未写回的修改时，改为加载对方的内容，
+    /// 空闲的界面由后台写回任务定期调用即可看到命令行等进程的修改。
+    pub(crate) fn flush_encrypted(&self, force: bool) -> Result<bool, AppError> {
+        let mut encryption = self.lock_encryption()?;
+        let Some(state) = encryption.as_mut() else {
+            return Ok(false);
+        };
+
+        // 锁定状态下内存中只有空数据库，不能覆盖加密文件
+        if matches!(state.key, AtRestKey::Master(None)) {
+            return Ok(false);
+        }
+
+        let path = state.path.clone();
+        if !force && total_changes(&lock_conn!(self.conn))? == state.persisted_changes {
+            // 没有本地修改：只有文件被其他进程改过时才需要继续（重新加载）
+            let on_disk = fs::read(&path).ok().map(|bytes| digest(&bytes));
+            if on_disk.is_none() || on_disk == state.file_digest {
+                return Ok(false);
+            }
+        }
+
+        // 持锁期间完成「重新读取 → 判断 → 写入」；等锁时不占用数据库连接
+        let _lock = crate::file_lock::lock(&path)?;
+        let mut conn = lock_conn!(self.conn);
+        let changes = total_changes(&conn)?;
+        let external = fs::read(&path)
+            .ok()
+            .filter(|bytes| Some(digest(bytes)) != state.file_digest);
+        if let Some(external) = external {
+            if changes == state.persisted_changes {
+                // 本进程没有未写回的修改：改为加载其他进程写入的内容
+                let plain = state.key.decrypt(&external)?.ok_or_else(locked_error)?;
+                let sql = String::from_utf8(plain).map_err(|e| {
+                    AppError::Database(format!("解密后的数据库不是有效的 SQL 文本: {e}"))
+                })?;
+                let reloaded = Self::sql_export_to_memory(&sql)?;
+                reloaded
+                    .execute("PRAGMA foreign_keys = ON;", [])
+                    .map_err(|e| AppError::Database(e.to_string()))?;
+                state.persisted_changes = total_changes(&reloaded)?;
+                state.file_digest = Some(digest(&external));
+                *conn = reloaded;
+                log::info!("已加载其他进程写入的加密数据库");
+                return Ok(false);
+            }
+            preserve_conflicting_file(&path, &external);
+        }
+
+        let dump = Self::dump_sql(&conn)?;
+        drop(conn);
+        let Some(bytes) = state.key.encrypt(dump.as_bytes())? else {
+            return Ok(false);
+        };
+        crate::config::atomic_write(&path, &bytes)?;
+        state.persisted_changes = changes;
+        state.file_digest = Some(digest(&bytes));
+        Ok(true)
+    }
+}
+
+/// 启动后台写回线程（未启用加密时每次检查都是空操作）
+pub fn start_encrypted_flusher(db: Arc<Database>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(FLUSH_INTERVAL);
+        if let Err(e) = db.flush_encrypted(false) {
+            log::error!("写回加密数据库失败: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const PASSPHRASE: &str = "test-passphrase";
+
+    fn open(path: &Path) -> Database {
+        let bytes = fs::read(path).expect("read encrypted database");
+        Database::open_encrypted_file(path, &bytes, AtRestKey::Passphrase(PASSPHRASE.into()))
+            .expect("open encrypted database")
+    }
+
+    fn provider(id: &str) -> Provider {
+        Provider::with_id(id.to_string(), id.to_string(), json!({ "env": {} }), None)
+    }
+
+    #[test]
+    fn idle_handle_reloads_changes_written_by_another_handle() {
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join(ENCRYPTED_DB_FILE);
+        Database::memory()
+            .expect("seed database")
+            .export_encrypted(&path, PASSPHRASE)
+            .expect("write encrypted database");
+
+        let writer = open(&path);
+        let idle = open(&path);
+
+        writer
+            .save_provider("claude", &provider("from-writer"))
+            .expect("save provider");
+        assert!(writer.flush_encrypted(false).expect("flush writer"));
+
+        // 空闲的一方没有本地修改，后台写回时应加载对方的内容而不是跳过
+        assert!(!idle.flush_encrypted(false).expect("tick idle handle"));
+        let providers = idle.get_all_providers("claude").expect("list providers");
+        assert!(providers.contains_key("from-writer"));
+
+        // 之后的修改基于最新内容写回，不会产生冲突副本
+        idle.save_provider("claude", &provider("from-idle"))
+            .expect("save provider");
+        assert!(idle.flush_encrypted(false).expect("flush idle handle"));
+        let conflicts = fs::read_dir(dir.path())
+            .expect("list dir")
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".conflict-"))
+            .count();
+        assert_eq!(conflicts, 0);
+
+        let reopened = open(&path);
+        let providers = reopened
+            .get_all_providers("claude")
+            .expect("list providers");
+        assert!(providers.contains_key("from-writer"));
+        assert!(providers.contains_key("from-idle"));
+    }
+}
@@ -0,0 +1,143 @@
+//! 数据库维护
+//!
+//! 校验数据库完整性，清理已删除供应商遗留的历史版本、测速与可用性记录，以及超出保留
+//! 数量的数据库备份，最后压缩数据库（`VACUUM`）并报告回收的空间。
+
+use super::profiles::database_dir;
+use super::{lock_conn, Database};
+use crate::error::AppError;
+use rusqlite::Connection;
+use serde::Serialize;
+
+/// 以供应商为键、供应商删除后不再有用的表
+const ORPHAN_TABLES: [&str; 3] = [
+    "provider_revisions",
+    "provider_benchmarks",
+    "provider_uptime_checks",
+];
+
+/// 维护结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    pub integrity_ok: bool,
+    /// `PRAGMA integrity_check` 报告的问题
+    pub integrity_problems: Vec<String>,
+    /// 已删除供应商遗留的历史版本数
+    pub pruned_revisions: usize,
+    /// 已删除供应商遗留的测速与可用性记录数
+    pub pruned_records: usize,
+    /// 删除的备份数
+    pub removed_backups: usize,
+    /// 删除的备份占用的空间（字节）
+    pub removed_backup_bytes: u64,
+    /// 压缩前后的数据库大小（字节）
+    pub size_before: u64,
+    pub size_after: u64,
+    /// 共回收的空间（字节）
+    pub reclaimed_bytes: u64,
+}
+
+impl MaintenanceReport {
+    /// 删除备份后重新计算回收的空间
+    pub fn add_removed_backups(&mut self, count: usize, bytes: u64) {
+        self.removed_backups += count;
+        self.removed_backup_bytes += bytes;
+        self.reclaimed_bytes =
+            self.size_before.saturating_sub(self.size_after) + self.removed_backup_bytes;
+    }
+}
+
+fn database_size(conn: &Connection) -> Result<u64, AppError> {
+    let page_count: i64 = conn
+        .query_row("PRAGMA page_count", [], |row| row.get(0))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let page_size: i64 = conn
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok((page_count * page_size) as u64)
+}
+
+fn integrity_problems(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    let results = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| AppError::Database(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    Ok(results
+        .into_iter()
+        .filter(|result| result != "ok")
+        .collect())
+}
+
+impl Database {
+    /// 校验完整性、清理孤立记录与旧备份并压缩数据库
+    ///
+    /// 完整性校验失败时不做任何修改，避免在损坏的数据库上继续写入。
+    pub fn maintain(&self) -> Result<MaintenanceReport, AppError> {
+        let mut report = self.prune_and_vacuum()?;
+        if !report.integrity_ok {
+            return Ok(report);
+        }
+
+        let backup_dir = database_dir().join("backups");
+        let (removed, bytes) = Self::cleanup_db_backups(&backup_dir)?;
+        report.add_removed_backups(removed, bytes);
+
+        self.flush_encrypted(false)?;
+        log::info!(
+            "数据库维护完成：清理 {} 个历史版本、{} 条记录、{} 个备份，回收 {} 字节",
+            report.pruned_revisions,
+            report.pruned_records,
+            report.removed_backups,
+            report.reclaimed_bytes
+        );
+        Ok(report)
+    }
+
+    /// 数据库内的维护步骤：完整性校验、清理孤立记录与压缩
+    pub(crate) fn prune_and_vacuum(&self) -> Result<MaintenanceReport, AppError> {
+        let mut report = MaintenanceReport::default();
+        let conn = lock_conn!(self.conn);
+        report.integrity_problems = integrity_problems(&conn)?;
+        report.integrity_ok = report.integrity_problems.is_empty();
+        if !report.integrity_ok {
+            log::error!("数据库完整性校验失败: {:?}", report.integrity_problems);
+            report.size_before = database_size(&conn)?;
+            report.size_after = report.size_before;
+            return Ok(report);
+        }
+
+        for table in ORPHAN_TABLES {
+            let removed = conn
+                .execute(
+                    &format!(
+                        "DELETE FROM {table} WHERE NOT EXISTS (
+                                 SELECT 1 FROM providers p
+                                 WHERE p.id = {table}.provider_id AND p.app_type = {table}.app_type
+                             )"
+                    ),
+                    [],
+                )
+                .map_err(|e| AppError::Database(e.to_string()))?;
+            if table == "provider_revisions" {
+                report.pruned_revisions += removed;
+            } else {
+                report.pruned_records += removed;
+            }
+        }
+
+        report.size_before = database_size(&conn)?;
+        conn.execute_batch("VACUUM")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        // WAL 模式下把压缩结果写回主文件并截断日志（其他模式下无效果）
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        report.size_after = database_size(&conn)?;
+        report.add_removed_backups(0, 0);
+        Ok(report)
+    }
+}
@@ -0,0 +1,98 @@
+//! 数据迁移
+//!
+//! 表结构的变更由 schema.rs 按 `user_version` 迁移；这里处理存储在 JSON 列中的数据
+//! 形状（供应商配置与 meta、MCP 服务器、设置项）的变更。每个迁移有递增的编号，只向前
+//! 执行一次，已执行的编号记录在 `data_migrations` 表中（随数据库一起备份与导出），
+//! 这样旧数据会按固定的顺序升级，而不是在读取时依赖 serde 默认值各自兜底。
+//!
+//! 新增迁移时在 [`MIGRATIONS`] 末尾追加，编号必须连续，已发布的迁移不能再修改。
+
+use super::Database;
+use crate::error::AppError;
+use rusqlite::{params, Connection};
+
+/// 单个数据迁移
+struct DataMigration {
+    version: i64,
+    description: &'static str,
+    apply: fn(&Connection) -> Result<(), AppError>,
+}
+
+/// 全部数据迁移（按编号排列）
+const MIGRATIONS: &[DataMigration] = &[DataMigration {
+    version: 1,
+    description: "规范化供应商 meta 与 MCP 标签的 JSON 值",
+    apply: normalize_json_columns,
+}];
+
+/// 当前的数据版本
+pub(crate) fn latest_data_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// v1：无效或类型不对的 JSON 值改为空对象/空数组，读取时不再需要各自兜底
+fn normalize_json_columns(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "UPDATE providers SET meta = '{}'
+         WHERE CASE WHEN json_valid(meta) THEN json_type(meta) != 'object' ELSE 1 END;
+         UPDATE mcp_servers SET tags = '[]'
+         WHERE CASE WHEN json_valid(tags) THEN json_type(tags) != 'array' ELSE 1 END;",
+    )
+    .map_err(|e| AppError::Database(format!("规范化 JSON 数据失败: {e}")))
+}
+
+impl Database {
+    /// 已执行的最大数据迁移编号（未执行过任何迁移时为 0）
+    pub(crate) fn get_data_version(conn: &Connection) -> Result<i64, AppError> {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM data_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| AppError::Database(format!("读取数据版本失败: {e}")))
+    }
+
+    /// 依次执行尚未执行的数据迁移
+    ///
+    /// 由 [`Database::apply_schema_migrations_on_conn`] 在表结构迁移完成后、同一个
+    /// savepoint 中调用，失败时与表结构迁移一起回滚。
+    pub(crate) fn apply_data_migrations_on_conn(conn: &Connection) -> Result<(), AppError> {
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS data_migrations (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        let version = Self::get_data_version(conn)?;
+        let latest = latest_data_version();
+        if version > latest {
+            return Err(AppError::Database(format!(
+                "数据版本过新（{version}），当前应用仅支持 {latest}，请升级应用后再尝试。"
+            )));
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+            log::info!(
+                "执行数据迁移 v{}（{}）",
+                migration.version,
+                migration.description
+            );
+            (migration.apply)(conn)?;
+            conn.execute(
+                "INSERT INTO data_migrations (version, description, applied_at)
+                 VALUES (?1, ?2, ?3)",
+                params![
+                    migration.version,
+                    migration.description,
+                    chrono::Utc::now().timestamp()
+                ],
+            )
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
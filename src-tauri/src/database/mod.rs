@@ -11,10 +11,16 @@
 //!
 //! ```text
 //! database/
-//! ├── mod.rs        - Database 结构体 + 初始化
+//! ├── mod.rs        - Database 结构体 + 初始化（磁盘数据库使用 WAL 模式）
 //! ├── schema.rs     - 表结构定义 + Schema 迁移
 //! ├── backup.rs     - SQL 导入导出 + 快照备份
+//! ├── batch.rs      - 事务化的批量写入
+//! ├── encryption.rs - 口令加密的静态存储
+//! ├── maintenance.rs - 完整性校验、清理与压缩
 //! ├── migration.rs  - JSON → SQLite 数据迁移
+//! ├── migrations.rs - 编号的数据形状迁移
+//! ├── profiles.rs   - 多个数据库配置档
+//! ├── recovery.rs   - 损坏数据库的隔离与恢复
 //! └── dao/          - 数据访问对象
 //!     ├── providers.rs
 //!     ├── mcp.rs
@@ -24,21 +30,36 @@
 //! ```
 
 mod backup;
+mod batch;
 mod dao;
+mod encryption;
+mod maintenance;
 mod migration;
+mod migrations;
+mod profiles;
+mod recovery;
 mod schema;
 
 #[cfg(test)]
 mod tests;
 
 // DAO 类型导出供外部使用
-pub use dao::FailoverQueueItem;
+pub use batch::BatchOp;
+#[cfg(feature = "gui")]
+pub use dao::SessionUsageCursor;
+pub use dao::{FailoverQueueItem, ProviderPage, ProviderRevision, ProviderSort};
+pub use encryption::{start_encrypted_flusher, DatabaseEncryptionStatus};
+pub use maintenance::MaintenanceReport;
+pub use profiles::{active_profile, list_profiles, DatabaseProfile};
 
-use crate::config::get_app_config_dir;
+use crate::change_events::ChangeEvent;
 use crate::error::AppError;
 use rusqlite::Connection;
 use serde::Serialize;
-use std::sync::Mutex;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
 
 // DAO 方法通过 impl Database 提供，无需额外导出
 
@@ -49,6 +70,39 @@ const DB_BACKUP_RETAIN: usize = 10;
 /// 每次修改表结构时递增，并在 schema.rs 中添加相应的迁移逻辑
 pub(crate) const SCHEMA_VERSION: i32 = 4;
 
+/// 数据库被其他连接（如同时运行的命令行）锁定时的最长等待时间
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 磁盘数据库连接的通用设置：外键约束、忙等待与 WAL 日志模式
+///
+/// WAL 模式下读取不会被写入阻塞，界面、后台任务与命令行可以同时访问数据库。
+/// 日志模式保存在数据库文件中，旧版本创建的数据库首次打开时自动转换。
+/// 网络路径上 SQLite 无法使用共享内存，保留默认的回滚日志。
+pub(crate) fn configure_file_connection(conn: &Connection, path: &Path) -> Result<(), AppError> {
+    conn.execute("PRAGMA foreign_keys = ON;", [])
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+    let target = if crate::network_fs::is_network_path(path) {
+        "DELETE"
+    } else {
+        "WAL"
+    };
+    let mode: String = conn
+        .pragma_update_and_check(None, "journal_mode", target, |row| row.get(0))
+        .map_err(|e| AppError::Database(e.to_string()))?;
+    if !mode.eq_ignore_ascii_case(target) {
+        log::warn!("数据库日志模式设置为 {target} 失败，当前为 {mode}");
+    }
+    if mode.eq_ignore_ascii_case("wal") {
+        // WAL 模式下 NORMAL 已能保证数据库不损坏，只在断电时可能丢失最后的提交
+        conn.pragma_update(None, "synchronous", "NORMAL")
+            .map_err(|e| AppError::Database(e.to_string()))?;
+    }
+    Ok(())
+}
+
 /// 安全地序列化 JSON，避免 unwrap panic
 pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError> {
     serde_json::to_string(value)
@@ -57,45 +111,96 @@ pub(crate) fn to_json_string<T: Serialize>(value: &T) -> Result<String, AppError
 
 /// 安全地获取 Mutex 锁，避免 unwrap panic
 macro_rules! lock_conn {
-    ($mutex:expr) => {
-        $mutex
-            .lock()
-            .map_err(|e| AppError::Database(format!("Mutex lock failed: {}", e)))?
+    ($conn:expr) => {
+        $conn.lock()?
     };
 }
 
 // 导出宏供子模块使用
 pub(crate) use lock_conn;
 
+/// 数据库连接
+///
+/// 主密码加密的数据库锁定时内存中只有空数据库，此时拒绝一切访问，
+/// 避免读到空数据或把数据写进随后被丢弃的空库。
+pub(crate) struct SqlConn {
+    conn: Mutex<Connection>,
+    locked: AtomicBool,
+}
+
+impl SqlConn {
+    fn new(conn: Connection) -> Self {
+        Self {
+            conn: Mutex::new(conn),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// 获取连接；数据库锁定时返回错误
+    pub(crate) fn lock(&self) -> Result<MutexGuard<'_, Connection>, AppError> {
+        let conn = self.lock_unchecked()?;
+        // 持有连接锁后再检查，锁定/解锁与替换连接在同一把锁下完成
+        if self.locked.load(Ordering::SeqCst) {
+            return Err(encryption::locked_error());
+        }
+        Ok(conn)
+    }
+
+    /// 获取连接而不检查锁定状态（仅供锁定/解锁与切换配置档时替换连接）
+    fn lock_unchecked(&self) -> Result<MutexGuard<'_, Connection>, AppError> {
+        self.conn
+            .lock()
+            .map_err(|e| AppError::Database(format!("Mutex lock failed: {e}")))
+    }
+
+    /// 与另一个连接交换锁定状态（调用方已持有两边的连接锁并交换了连接）
+    fn swap_locked(&self, other: &SqlConn) {
+        let locked = self.locked.load(Ordering::SeqCst);
+        self.locked
+            .store(other.locked.load(Ordering::SeqCst), Ordering::SeqCst);
+        other.locked.store(locked, Ordering::SeqCst);
+    }
+}
+
 /// 数据库连接封装
 ///
 /// 使用 Mutex 包装 Connection 以支持在多线程环境（如 Tauri State）中共享。
 /// rusqlite::Connection 本身不是 Sync 的，因此需要这层包装。
 pub struct Database {
-    pub(crate) conn: Mutex<Connection>,
+    pub(crate) conn: SqlConn,
+    /// 静态加密状态（未启用时为 None，此时 conn 直接对应磁盘文件）
+    pub(crate) encryption: Mutex<Option<encryption::AtRestEncryption>>,
+    /// 是否发出数据变更事件（仅应用的主数据库；导入、同步时打开的临时数据库不发出）
+    emit_changes: bool,
 }
 
 impl Database {
-    /// 初始化数据库连接并创建表
+    /// 初始化当前配置档的数据库连接并创建表
     ///
-    /// 数据库文件位于 `~/.cc-switch/cc-switch.db`
+    /// 默认配置档的数据库文件位于 `~/.cc-switch/cc-switch.db`（其他配置档见
+    /// [`profiles`]）；启用静态加密时改为从同目录的 `cc-switch.db.enc` 解密到内存
     pub fn init() -> Result<Self, AppError> {
-        let db_path = get_app_config_dir().join("cc-switch.db");
+        let dir = profiles::database_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+        let mut db = Self::open_in_dir(&dir)?;
+        db.emit_changes = true;
+        Ok(db)
+    }
 
-        // 确保父目录存在
-        if let Some(parent) = db_path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    /// 打开指定目录下的数据库
+    pub(crate) fn open_in_dir(dir: &Path) -> Result<Self, AppError> {
+        let encrypted_path = encryption::encrypted_db_path_in(dir);
+        if encrypted_path.exists() {
+            return Self::init_encrypted(&encrypted_path);
         }
 
-        let conn = Connection::open(&db_path).map_err(|e| AppError::Database(e.to_string()))?;
+        let db_path = dir.join("cc-switch.db");
+        recovery::recover_if_corrupt(&db_path)?;
 
-        // 启用外键约束
-        conn.execute("PRAGMA foreign_keys = ON;", [])
-            .map_err(|e| AppError::Database(e.to_string()))?;
+        let conn = Connection::open(&db_path).map_err(|e| AppError::Database(e.to_string()))?;
+        configure_file_connection(&conn, &db_path)?;
 
-        let db = Self {
-            conn: Mutex::new(conn),
-        };
+        let db = Self::from_connection(conn);
         db.create_tables()?;
         db.apply_schema_migrations()?;
         db.ensure_model_pricing_seeded()?;
@@ -111,15 +216,42 @@ impl Database {
         conn.execute("PRAGMA foreign_keys = ON;", [])
             .map_err(|e| AppError::Database(e.to_string()))?;
 
-        let db = Self {
-            conn: Mutex::new(conn),
-        };
+        let db = Self::from_connection(conn);
         db.create_tables()?;
         db.ensure_model_pricing_seeded()?;
 
         Ok(db)
     }
 
+    pub(crate) fn from_connection(conn: Connection) -> Self {
+        Self {
+            conn: SqlConn::new(conn),
+            encryption: Mutex::new(None),
+            emit_changes: false,
+        }
+    }
+
+    /// 发出数据变更事件（见 [`crate::change_events`]）
+    pub(crate) fn notify(&self, event: ChangeEvent) {
+        if self.emit_changes {
+            crate::change_events::emit(event);
+        }
+    }
+
+    /// 数据库内容的变更标记：本连接的写入次数与其他连接提交后变化的 `data_version`
+    ///
+    /// 两次读取的结果相同说明期间没有任何写入，用于判断内存缓存是否过期。
+    pub fn change_stamp(&self) -> Result<(i64, i64), AppError> {
+        let conn = lock_conn!(self.conn);
+        let total_changes = conn
+            .query_row("SELECT total_changes()", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        let data_version = conn
+            .query_row("PRAGMA data_version", [], |row| row.get(0))
+            .map_err(|e| AppError::Database(e.to_string()))?;
+        Ok((total_changes, data_version))
+    }
+
     /// 检查 MCP 服务器表是否为空
     pub fn is_mcp_table_empty(&self) -> Result<bool, AppError> {
         let conn = lock_conn!(self.conn);
@@ -0,0 +1,181 @@
+//! 数据库配置档
+//!
+//! 默认配置档使用 `~/.cc-switch/` 下的数据库，其他配置档的数据库位于
+//! `~/.cc-switch/profiles/<name>/`。每个配置档有独立的供应商、MCP、提示词、历史记录、
+//! 静态加密文件与数据库备份；`settings.json` 中的设备级设置在所有配置档间共享。
+//! 当前配置档保存在设置的 `databaseProfile` 中，命令行与界面打开同一个配置档。
+
+use super::{encryption, Database};
+use crate::config::get_app_config_dir;
+use crate::error::AppError;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// 默认配置档名称
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 配置档目录（位于应用配置目录下）
+const PROFILES_DIR: &str = "profiles";
+
+/// 配置档名称的最大长度
+const MAX_NAME_LEN: usize = 32;
+
+/// 配置档信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseProfile {
+    pub name: String,
+    pub path: String,
+    pub active: bool,
+    /// 数据库是否启用了静态加密
+    pub encrypted: bool,
+}
+
+/// 校验配置档名称：只允许字母、数字、`-` 与 `_`
+pub fn validate_profile_name(name: &str) -> Result<(), AppError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::localized(
+            "database.profile.invalid_name",
+            format!("配置档名称无效：{name}（只能包含字母、数字、- 与 _，最多 {MAX_NAME_LEN} 个字符）"),
+            format!("Invalid profile name: {name} (letters, digits, - and _ only, up to {MAX_NAME_LEN} characters)"),
+        ))
+    }
+}
+
+/// 配置档的数据库目录
+pub fn profile_dir(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        get_app_config_dir()
+    } else {
+        get_app_config_dir().join(PROFILES_DIR).join(name)
+    }
+}
+
+/// 当前配置档名称
+pub fn active_profile() -> String {
+    crate::settings::get_settings()
+        .database_profile
+        .filter(|name| validate_profile_name(name).is_ok())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// 当前配置档的数据库目录
+pub(crate) fn database_dir() -> PathBuf {
+    profile_dir(&active_profile())
+}
+
+/// 列出默认配置档与 `profiles/` 下已创建的配置档
+pub fn list_profiles() -> Vec<DatabaseProfile> {
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(entries) = fs::read_dir(get_app_config_dir().join(PROFILES_DIR)) {
+        let mut others = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| name != DEFAULT_PROFILE && validate_profile_name(name).is_ok())
+            .collect::<Vec<_>>();
+        others.sort();
+        names.extend(others);
+    }
+
+    let active = active_profile();
+    names
+        .into_iter()
+        .map(|name| {
+            let dir = profile_dir(&name);
+            DatabaseProfile {
+                path: dir.display().to_string(),
+                active: name == active,
+                encrypted: encryption::encrypted_db_path_in(&dir).exists(),
+                name,
+            }
+        })
+        .collect()
+}
+
+impl Database {
+    /// 切换到指定配置档（不存在时创建空数据库）
+    ///
+    /// 先写回当前配置档（加密时），再打开目标配置档并原地替换连接，持有本数据库的
+    /// 后台任务与命令无需重新获取。设置中的当前供应商 ID 属于旧配置档，切换后清除，
+    /// 改用新数据库中的 `is_current`。
+    pub fn switch_profile(&self, name: &str) -> Result<(), AppError> {
+        validate_profile_name(name)?;
+        if name == active_profile() {
+            return Ok(());
+        }
+
+        self.flush_encrypted(true)?;
+        let dir = profile_dir(name);
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+        let target = Self::open_in_dir(&dir)?;
+
+        {
+            // 与后台写回相同的加锁顺序：先加密状态，再连接
+            let mut encryption = self.lock_encryption()?;
+            let mut target_encryption = target.lock_encryption()?;
+            // 任一配置档可能处于主密码锁定状态，锁定标记随连接一起交换
+            let mut conn = self.conn.lock_unchecked()?;
+            let mut target_conn = target.conn.lock_unchecked()?;
+            std::mem::swap(&mut *conn, &mut *target_conn);
+            std::mem::swap(&mut *encryption, &mut *target_encryption);
+            self.conn.swap_locked(&target.conn);
+
+            // 持有锁时更新设置，避免后台写回把数据写到旧配置档的加密文件
+            let mut settings = crate::settings::get_settings();
+            settings.database_profile = (name != DEFAULT_PROFILE).then(|| name.to_string());
+            settings.current_provider_claude = None;
+            settings.current_provider_codex = None;
+            settings.current_provider_gemini = None;
+            settings.current_provider_opencode = None;
+            if let Err(e) = crate::settings::update_settings(settings) {
+                std::mem::swap(&mut *conn, &mut *target_conn);
+                std::mem::swap(&mut *encryption, &mut *target_encryption);
+                self.conn.swap_locked(&target.conn);
+                return Err(e);
+            }
+        }
+
+        log::info!("已切换到数据库配置档 {name}: {}", dir.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_restricted_to_safe_characters() {
+        for name in ["work", "personal-2", "team_a"] {
+            assert!(
+                validate_profile_name(name).is_ok(),
+                "{name} should be valid"
+            );
+        }
+        let too_long = "x".repeat(MAX_NAME_LEN + 1);
+        for name in ["", "../etc", "a/b", "with space", too_long.as_str()] {
+            assert!(
+                validate_profile_name(name).is_err(),
+                "{name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn default_profile_uses_the_app_config_dir() {
+        assert_eq!(profile_dir(DEFAULT_PROFILE), get_app_config_dir());
+        assert_eq!(
+            profile_dir("work"),
+            get_app_config_dir().join(PROFILES_DIR).join("work")
+        );
+    }
+}
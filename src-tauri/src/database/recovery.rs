@@ -0,0 +1,136 @@
+//! 损坏数据库的检测与恢复
+//!
+//! 打开数据库前先检查文件头并执行 `PRAGMA quick_check`。确认损坏（截断、文件头无效、
+//! SQLite 报告损坏）时把数据库及其 WAL 文件改名隔离，再从同目录 `backups/` 中最近一份
+//! 完好的数据库备份恢复，并记录到 [`crate::init_status`] 供前端提示，而不是直接创建空
+//! 数据库，看起来像是丢失了全部供应商。
+//!
+//! 数据库被其他进程锁定等不属于损坏的错误不会触发恢复。
+
+use crate::error::AppError;
+use crate::init_status::RecoveryPayload;
+use rusqlite::{Connection, ErrorCode, OpenFlags};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// SQLite 数据库文件头
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// SQLite 数据库文件头的长度（更短的文件一定被截断）
+const HEADER_LEN: u64 = 100;
+
+/// 数据库附带的日志文件后缀
+const SIDECAR_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+fn is_corruption(err: &rusqlite::Error) -> bool {
+    matches!(
+        err.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase)
+    )
+}
+
+fn quick_check(path: &Path) -> rusqlite::Result<Vec<String>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut stmt = conn.prepare("PRAGMA quick_check")?;
+    let results = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results
+        .into_iter()
+        .filter(|result| result != "ok")
+        .collect())
+}
+
+/// 检查数据库文件，确认损坏时返回原因（文件不存在或为空视为正常）
+pub(crate) fn detect_corruption(path: &Path) -> Option<String> {
+    let len = fs::metadata(path).ok()?.len();
+    if len == 0 {
+        return None;
+    }
+    if len < HEADER_LEN {
+        return Some(format!("数据库文件被截断（{len} 字节）"));
+    }
+    let mut header = [0u8; 16];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if &header != SQLITE_HEADER {
+        return Some("数据库文件头无效".to_string());
+    }
+
+    match quick_check(path) {
+        Ok(problems) if problems.is_empty() => None,
+        Ok(problems) => Some(problems.join("; ")),
+        Err(e) if is_corruption(&e) => Some(e.to_string()),
+        Err(e) => {
+            log::warn!("检查数据库完整性失败（不视为损坏）: {e}");
+            None
+        }
+    }
+}
+
+/// `backups/` 下最近一份完好的数据库备份
+fn latest_valid_backup(backup_dir: &Path) -> Option<PathBuf> {
+    let mut backups = fs::read_dir(backup_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with("db_backup_") && name.ends_with(".db")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    backups.sort_by(|a, b| b.0.cmp(&a.0));
+    backups.into_iter().map(|(_, path)| path).find(|path| {
+        fs::metadata(path).is_ok_and(|m| m.len() > 0) && detect_corruption(path).is_none()
+    })
+}
+
+/// 数据库损坏时隔离并从最近的备份恢复（正常时不做任何事）
+pub(crate) fn recover_if_corrupt(db_path: &Path) -> Result<(), AppError> {
+    let Some(problem) = detect_corruption(db_path) else {
+        return Ok(());
+    };
+    log::error!("数据库文件已损坏: {} ({problem})", db_path.display());
+
+    let quarantined = crate::config::quarantine_file(db_path)?;
+    // 旧的 WAL 不能应用到恢复的数据库上，与损坏的文件放在一起
+    for suffix in SIDECAR_SUFFIXES {
+        let sidecar = PathBuf::from(format!("{}{suffix}", db_path.display()));
+        if sidecar.exists() {
+            let target = PathBuf::from(format!("{}{suffix}", quarantined.display()));
+            fs::rename(&sidecar, &target).map_err(|e| AppError::io(&sidecar, e))?;
+        }
+    }
+
+    let backup_dir = db_path
+        .parent()
+        .map(|dir| dir.join("backups"))
+        .unwrap_or_default();
+    let restored_from = latest_valid_backup(&backup_dir);
+    match &restored_from {
+        Some(backup) => {
+            crate::config::copy_file(backup, db_path)?;
+            log::warn!(
+                "已隔离损坏的数据库 {}，并从备份 {} 恢复",
+                quarantined.display(),
+                backup.display()
+            );
+        }
+        None => log::warn!(
+            "已隔离损坏的数据库 {}，没有可用的备份，将创建新的数据库",
+            quarantined.display()
+        ),
+    }
+
+    crate::init_status::push_recovery(RecoveryPayload {
+        kind: "database".to_string(),
+        path: db_path.display().to_string(),
+        quarantined_path: quarantined.display().to_string(),
+        restored_from: restored_from.map(|path| path.display().to_string()),
+        error: problem,
+    });
+    Ok(())
+}
@@ -32,6 +32,7 @@ impl Database {
                 meta TEXT NOT NULL DEFAULT '{}',
                 is_current BOOLEAN NOT NULL DEFAULT 0,
                 in_failover_queue BOOLEAN NOT NULL DEFAULT 0,
+                locked BOOLEAN NOT NULL DEFAULT 0,
                 PRIMARY KEY (id, app_type)
             )",
             [],
@@ -238,6 +239,106 @@ impl Database {
         )
         .map_err(|e| AppError::Database(e.to_string()))?;
 
+        // 17. Provider Revisions 表（供应商配置历史版本）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, provider_id TEXT NOT NULL, app_type TEXT NOT NULL,
+            revision INTEGER NOT NULL, name TEXT NOT NULL, settings_config TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_revisions_provider
+             ON provider_revisions(app_type, provider_id, revision DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 18. Automation Rules 表（自动化规则，触发条件与动作以 JSON 保存）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS automation_rules (
+            id TEXT PRIMARY KEY, name TEXT NOT NULL, enabled INTEGER NOT NULL DEFAULT 1,
+            trigger_config TEXT NOT NULL, action_config TEXT NOT NULL,
+            last_run_at INTEGER, last_result TEXT, created_at INTEGER NOT NULL
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 19. Provider Switch History 表（切换记录，用于托盘「最近使用」）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_switch_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, app_type TEXT NOT NULL,
+            provider_id TEXT NOT NULL, switched_at INTEGER NOT NULL
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_switch_history_app
+             ON provider_switch_history(app_type, id DESC)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 20. Session Usage 表（从 Claude Code / Codex 会话日志统计的用量，按供应商、日期与模型聚合）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_usage (
+            app_type TEXT NOT NULL, provider_id TEXT NOT NULL, date TEXT NOT NULL,
+            model TEXT NOT NULL, input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+            cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+            message_count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (app_type, provider_id, date, model)
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 21. Session Usage Files 表（已统计的会话日志读取位置）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS session_usage_files (
+            path TEXT PRIMARY KEY, offset INTEGER NOT NULL, model TEXT, last_total INTEGER
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 22. Provider Benchmarks 表（端点测速，每个供应商保留最近一次结果）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_benchmarks (
+            app_type TEXT NOT NULL, provider_id TEXT NOT NULL, provider_name TEXT NOT NULL,
+            samples INTEGER NOT NULL, successes INTEGER NOT NULL, ttfb_ms INTEGER,
+            total_ms INTEGER, error TEXT, tested_at INTEGER NOT NULL,
+            PRIMARY KEY (app_type, provider_id)
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        // 23. Provider Uptime Checks 表（可用性监控的检查记录）
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provider_uptime_checks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT, app_type TEXT NOT NULL,
+            provider_id TEXT NOT NULL, up INTEGER NOT NULL, http_status INTEGER,
+            latency_ms INTEGER, reason TEXT NOT NULL, checked_at INTEGER NOT NULL
+        )",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provider_uptime_checks_provider
+             ON provider_uptime_checks(app_type, provider_id, checked_at)",
+            [],
+        )
+        .map_err(|e| AppError::Database(e.to_string()))?;
+
         // 尝试添加 live_takeover_active 列到 proxy_config 表
         let _ = conn.execute(
             "ALTER TABLE proxy_config ADD COLUMN live_takeover_active INTEGER NOT NULL DEFAULT 0",
@@ -292,6 +393,9 @@ impl Database {
             "BOOLEAN NOT NULL DEFAULT 0",
         )?;
 
+        // 确保 locked 列存在（供应商保护标记）
+        Self::add_column_if_missing(conn, "providers", "locked", "BOOLEAN NOT NULL DEFAULT 0")?;
+
         // 删除旧的 failover_queue 表（如果存在）
         let _ = conn.execute("DROP INDEX IF EXISTS idx_failover_queue_order", []);
         let _ = conn.execute("DROP TABLE IF EXISTS failover_queue", []);
@@ -360,7 +464,7 @@ impl Database {
                 }
                 version = Self::get_user_version(conn)?;
             }
-            Ok(())
+            Self::apply_data_migrations_on_conn(conn)
         })();
 
         match result {
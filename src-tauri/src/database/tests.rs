@@ -182,6 +182,63 @@ fn migration_rejects_future_version() {
     );
 }
 
+#[test]
+fn data_migrations_normalize_json_once_and_reject_future_version() {
+    let conn = Connection::open_in_memory().expect("open memory db");
+    Database::create_tables_on_conn(&conn).expect("create tables");
+    conn.execute_batch(
+        "INSERT INTO providers (id, app_type, name, settings_config, meta)
+         VALUES ('broken', 'claude', 'Broken', '{}', 'not json'),
+                ('array', 'claude', 'Array', '{}', '[]'),
+                ('ok', 'claude', 'Ok', '{}', '{\"notes\":\"keep\"}');
+         INSERT INTO mcp_servers (id, name, server_config, tags)
+         VALUES ('m1', 'M1', '{}', '\"single\"');",
+    )
+    .expect("seed rows");
+
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations");
+    assert_eq!(
+        Database::get_data_version(&conn).expect("data version"),
+        migrations::latest_data_version()
+    );
+    let meta = |id: &str| {
+        conn.query_row(
+            "SELECT meta FROM providers WHERE id = ?1",
+            params![id],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("read meta")
+    };
+    assert_eq!(meta("broken"), "{}");
+    assert_eq!(meta("array"), "{}");
+    assert_eq!(meta("ok"), r#"{"notes":"keep"}"#);
+    let tags: String = conn
+        .query_row("SELECT tags FROM mcp_servers WHERE id = 'm1'", [], |row| {
+            row.get(0)
+        })
+        .expect("read tags");
+    assert_eq!(tags, "[]");
+
+    // 已执行的迁移不会重复执行
+    Database::apply_schema_migrations_on_conn(&conn).expect("apply migrations again");
+    let applied: i64 = conn
+        .query_row("SELECT COUNT(*) FROM data_migrations", [], |row| row.get(0))
+        .expect("count migrations");
+    assert_eq!(applied, migrations::latest_data_version());
+
+    conn.execute(
+        "INSERT INTO data_migrations (version, description, applied_at) VALUES (?1, 'future', 0)",
+        params![migrations::latest_data_version() + 1],
+    )
+    .expect("insert future version");
+    let err = Database::apply_schema_migrations_on_conn(&conn)
+        .expect_err("should reject higher data version");
+    assert!(
+        err.to_string().contains("数据版本过新"),
+        "unexpected error: {err}"
+    );
+}
+
 #[test]
 fn migration_adds_missing_columns_for_providers() {
     let conn = Connection::open_in_memory().expect("open memory db");
@@ -477,6 +534,7 @@ fn dry_run_validates_schema_compatibility() {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            locked: false,
         },
     );
 
@@ -564,3 +622,550 @@ fn model_pricing_is_seeded_on_init() {
         gemini_count
     );
 }
+
+#[test]
+fn provider_revisions_are_numbered_and_cleared_on_delete() {
+    let db = Database::memory().expect("create memory db");
+    let provider = Provider::with_id(
+        "p1".to_string(),
+        "P1".to_string(),
+        json!({ "env": { "ANTHROPIC_BASE_URL": "https://a" } }),
+        None,
+    );
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+
+    let first = db
+        .record_provider_revision("claude", "p1", "P1", &json!({ "v": 1 }))
+        .expect("record rev 1");
+    let second = db
+        .record_provider_revision("claude", "p1", "P1", &json!({ "v": 2 }))
+        .expect("record rev 2");
+    assert_eq!((first, second), (1, 2));
+
+    let revisions = db
+        .get_provider_revisions("claude", "p1")
+        .expect("list revisions");
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(
+        revisions[0].revision, 2,
+        "newest revision should come first"
+    );
+    assert_eq!(
+        db.get_provider_revision("claude", "p1", 1)
+            .expect("get rev 1")
+            .map(|r| r.settings_config),
+        Some(json!({ "v": 1 }))
+    );
+
+    db.delete_provider("claude", "p1").expect("delete provider");
+    assert!(db
+        .get_provider_revisions("claude", "p1")
+        .expect("list after delete")
+        .is_empty());
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn automation_rules_keep_run_history_on_update() {
+    use crate::services::automation_rules::AutomationRule;
+
+    let db = Database::memory().expect("create memory db");
+    let rule: AutomationRule = serde_json::from_value(json!({
+        "name": "Nightly backup",
+        "trigger": { "type": "schedule", "cron": "0 3 * * *" },
+        "action": { "type": "createBackup" }
+    }))
+    .expect("parse rule");
+
+    let saved = db.save_automation_rule(&rule).expect("save rule");
+    assert!(!saved.id.is_empty(), "new rules should get an id");
+    db.record_automation_rule_run(&saved.id, 42, "backup ok")
+        .expect("record run");
+
+    let updated = db
+        .save_automation_rule(&AutomationRule {
+            enabled: false,
+            ..saved.clone()
+        })
+        .expect("update rule");
+    assert_eq!(updated.last_run_at, Some(42));
+    assert_eq!(updated.last_result.as_deref(), Some("backup ok"));
+
+    let rules = db.get_automation_rules().expect("list rules");
+    assert_eq!(rules, vec![updated]);
+
+    let invalid = AutomationRule {
+        id: String::new(),
+        trigger: serde_json::from_value(json!({ "type": "schedule", "cron": "every day" }))
+            .expect("parse trigger"),
+        ..rule
+    };
+    assert!(db.save_automation_rule(&invalid).is_err());
+
+    assert!(db.delete_automation_rule(&saved.id).expect("delete rule"));
+    assert!(db.get_automation_rules().expect("list rules").is_empty());
+}
+
+#[test]
+fn recent_providers_are_distinct_and_skip_deleted_ones() {
+    let db = Database::memory().expect("create memory db");
+    for id in ["a", "b", "c"] {
+        let provider = Provider::with_id(id.to_string(), id.to_uppercase(), json!({}), None);
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+    }
+    for (at, id) in ["a", "b", "a", "c", "a"].into_iter().enumerate() {
+        db.record_provider_switch("claude", id, at as i64)
+            .expect("record switch");
+    }
+
+    assert_eq!(
+        db.get_recent_provider_ids("claude", 2).expect("recent"),
+        vec!["a".to_string(), "c".to_string()]
+    );
+    assert!(db
+        .get_recent_provider_ids("codex", 3)
+        .expect("recent")
+        .is_empty());
+
+    db.delete_provider("claude", "c").expect("delete provider");
+    assert_eq!(
+        db.get_recent_provider_ids("claude", 3).expect("recent"),
+        vec!["a".to_string(), "b".to_string()]
+    );
+
+    let mut counts = db
+        .get_provider_switch_counts(Some(1))
+        .expect("switch counts");
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            ("claude".to_string(), "a".to_string(), 2),
+            ("claude".to_string(), "b".to_string(), 1),
+            ("claude".to_string(), "c".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn encrypted_database_bytes_round_trip() {
+    let db = Database::memory().expect("create memory db");
+    let provider = Provider::with_id(
+        "p1".to_string(),
+        "P1".to_string(),
+        json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-secret" } }),
+        None,
+    );
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+
+    let bytes = {
+        let conn = db.conn.lock().expect("lock conn");
+        let dump = Database::dump_sql(&conn).expect("dump");
+        crate::crypto::encrypt_with_passphrase(dump.as_bytes(), "correct horse").expect("encrypt")
+    };
+    assert!(crate::crypto::is_encrypted(&bytes));
+    assert!(
+        !String::from_utf8_lossy(&bytes).contains("sk-secret"),
+        "ciphertext must not contain plaintext API keys"
+    );
+
+    let restored = Database::from_connection(
+        Database::open_encrypted_bytes(&bytes, "correct horse").expect("decrypt"),
+    );
+    let loaded = restored
+        .get_provider_by_id("p1", "claude")
+        .expect("query provider")
+        .expect("provider exists");
+    assert_eq!(
+        loaded.settings_config["env"]["ANTHROPIC_AUTH_TOKEN"],
+        json!("sk-secret")
+    );
+
+    assert!(Database::open_encrypted_bytes(&bytes, "wrong").is_err());
+}
+
+#[test]
+fn locked_database_rejects_access() {
+    let db = Database::memory().expect("create memory db");
+    db.conn
+        .locked
+        .store(true, std::sync::atomic::Ordering::SeqCst);
+
+    let err = db
+        .get_all_providers("claude")
+        .expect_err("locked database must not be readable");
+    assert!(matches!(
+        err,
+        AppError::Localized {
+            key: "database.encryption.locked",
+            ..
+        }
+    ));
+    assert!(db.set_setting("k", "v").is_err());
+
+    db.conn
+        .locked
+        .store(false, std::sync::atomic::Ordering::SeqCst);
+    assert!(db.get_all_providers("claude").expect("query").is_empty());
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn latest_stream_check_log_spans_all_apps() {
+    use crate::services::stream_check::{HealthStatus, StreamCheckResult};
+
+    let db = Database::memory().expect("create memory db");
+    assert!(db.get_latest_stream_check_log().expect("query").is_none());
+
+    let result = |status: HealthStatus, tested_at: i64| StreamCheckResult {
+        success: status != HealthStatus::Failed,
+        status,
+        message: String::new(),
+        response_time_ms: Some(320),
+        http_status: Some(200),
+        model_used: "test".to_string(),
+        tested_at,
+        retry_count: 0,
+    };
+    db.save_stream_check_log(
+        "relay",
+        "Relay",
+        "claude",
+        &result(HealthStatus::Operational, 100),
+    )
+    .expect("save log");
+    db.save_stream_check_log(
+        "kimi",
+        "Kimi",
+        "codex",
+        &result(HealthStatus::Degraded, 200),
+    )
+    .expect("save log");
+
+    let latest = db
+        .get_latest_stream_check_log()
+        .expect("query")
+        .expect("log exists");
+    assert_eq!(latest.app_type, "codex");
+    assert_eq!(latest.provider_name, "Kimi");
+    assert_eq!(latest.status, "degraded");
+    assert_eq!(latest.response_time_ms, Some(320));
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn session_usage_accumulates_with_file_cursor() {
+    use crate::services::session_usage::SessionUsageRow;
+
+    let db = Database::memory().expect("create memory db");
+    assert!(db
+        .get_session_usage_cursor("/logs/a.jsonl")
+        .expect("query cursor")
+        .is_none());
+
+    let row = |provider_id: &str, date: &str, output_tokens| SessionUsageRow {
+        app_type: "claude".to_string(),
+        provider_id: provider_id.to_string(),
+        date: date.to_string(),
+        model: "claude-sonnet-4".to_string(),
+        input_tokens: 10,
+        output_tokens,
+        message_count: 1,
+        ..SessionUsageRow::default()
+    };
+    let cursor = SessionUsageCursor {
+        offset: 120,
+        ..SessionUsageCursor::default()
+    };
+    db.add_session_usage(
+        "/logs/a.jsonl",
+        &cursor,
+        &[row("relay", "2025-06-01", 50), row("kimi", "2025-06-02", 7)],
+    )
+    .expect("add usage");
+    let cursor = SessionUsageCursor {
+        offset: 240,
+        model: Some("gpt-5".to_string()),
+        last_total: Some(1300),
+    };
+    db.add_session_usage("/logs/a.jsonl", &cursor, &[row("relay", "2025-06-01", 25)])
+        .expect("add usage");
+
+    assert_eq!(
+        db.get_session_usage_cursor("/logs/a.jsonl")
+            .expect("query cursor"),
+        Some(cursor)
+    );
+    let relay = db
+        .get_session_usage(Some("relay"), None)
+        .expect("query usage");
+    assert_eq!(relay.len(), 1);
+    assert_eq!(
+        (
+            relay[0].input_tokens,
+            relay[0].output_tokens,
+            relay[0].message_count
+        ),
+        (20, 75, 2)
+    );
+    assert_eq!(
+        db.get_session_usage(None, Some("2025-06-02"))
+            .expect("query usage")
+            .iter()
+            .map(|row| row.provider_id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["kimi"]
+    );
+
+    db.record_provider_switch("codex", "b", 200)
+        .expect("record switch");
+    db.record_provider_switch("codex", "a", 100)
+        .expect("record switch");
+    assert_eq!(
+        db.get_provider_switch_timeline("codex").expect("timeline"),
+        vec![(100, "a".to_string()), (200, "b".to_string())]
+    );
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn uptime_checks_filter_by_app_and_prune() {
+    use crate::services::uptime::UptimeCheck;
+
+    let db = Database::memory().expect("create memory db");
+    let check = |app_type: &str, up: bool, checked_at: i64| UptimeCheck {
+        app_type: app_type.to_string(),
+        provider_id: "relay".to_string(),
+        up,
+        http_status: Some(if up { 200 } else { 502 }),
+        latency_ms: up.then_some(120),
+        reason: if up { "valid" } else { "upstream_error" }.to_string(),
+        checked_at,
+    };
+    for item in [
+        check("claude", true, 100),
+        check("claude", false, 200),
+        check("codex", true, 300),
+    ] {
+        db.record_uptime_check(&item).expect("record check");
+    }
+
+    let claude = db
+        .get_uptime_checks(Some("claude"), 0)
+        .expect("uptime checks");
+    assert_eq!(
+        claude,
+        vec![check("claude", true, 100), check("claude", false, 200)]
+    );
+    assert_eq!(
+        db.get_uptime_checks(None, 150)
+            .expect("uptime checks")
+            .len(),
+        2
+    );
+
+    assert_eq!(db.prune_uptime_checks(250).expect("prune"), 2);
+    assert_eq!(
+        db.get_uptime_checks(None, 0).expect("uptime checks"),
+        vec![check("codex", true, 300)]
+    );
+}
+
+#[test]
+fn file_database_uses_wal_and_snapshots_are_single_files() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db_path = dir.path().join("cc-switch.db");
+    let conn = Connection::open(&db_path).expect("open db");
+    configure_file_connection(&conn, &db_path).expect("configure connection");
+    let journal_mode = |conn: &Connection| {
+        conn.query_row("PRAGMA journal_mode", [], |row| row.get::<_, String>(0))
+            .expect("journal mode")
+    };
+    assert_eq!(journal_mode(&conn), "wal");
+
+    let db = Database::from_connection(conn);
+    db.create_tables().expect("create tables");
+    let provider = Provider::with_id("p1".to_string(), "P1".to_string(), json!({}), None);
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+
+    let snapshot = dir.path().join("snapshot.db");
+    db.backup_to_file(&snapshot).expect("backup");
+    let snapshot_conn = Connection::open(&snapshot).expect("open snapshot");
+    assert_eq!(journal_mode(&snapshot_conn), "delete");
+    let count: i64 = snapshot_conn
+        .query_row("SELECT COUNT(*) FROM providers", [], |row| row.get(0))
+        .expect("count providers");
+    assert_eq!(count, 1);
+}
+
+#[cfg(feature = "gui")]
+#[test]
+fn maintenance_prunes_records_of_deleted_providers() {
+    use crate::services::uptime::UptimeCheck;
+
+    let db = Database::memory().expect("create memory db");
+    let provider = Provider::with_id("p1".to_string(), "P1".to_string(), json!({}), None);
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+    for provider_id in ["p1", "gone"] {
+        db.record_uptime_check(&UptimeCheck {
+            app_type: "claude".to_string(),
+            provider_id: provider_id.to_string(),
+            up: true,
+            http_status: Some(200),
+            latency_ms: Some(100),
+            reason: "valid".to_string(),
+            checked_at: 100,
+        })
+        .expect("record check");
+    }
+
+    let report = db.prune_and_vacuum().expect("maintain");
+    assert!(report.integrity_ok);
+    assert!(report.integrity_problems.is_empty());
+    assert_eq!(report.pruned_records, 1);
+    let remaining = db.get_uptime_checks(None, 0).expect("uptime checks");
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].provider_id, "p1");
+}
+
+#[test]
+fn corrupt_database_is_quarantined_and_restored_from_backup() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let backup_dir = dir.path().join("backups");
+    std::fs::create_dir_all(&backup_dir).expect("create backup dir");
+
+    let db = Database::memory().expect("create memory db");
+    let provider = Provider::with_id("p1".to_string(), "P1".to_string(), json!({}), None);
+    db.save_provider("claude", &provider)
+        .expect("save provider");
+    db.backup_to_file(&backup_dir.join("db_backup_20250101_000000.db"))
+        .expect("backup");
+
+    let db_path = dir.path().join("cc-switch.db");
+    std::fs::write(&db_path, vec![0xAB; 4096]).expect("write corrupt db");
+    assert!(recovery::detect_corruption(&db_path).is_some());
+
+    let restored = Database::open_in_dir(dir.path()).expect("open recovered db");
+    assert!(restored
+        .get_all_providers("claude")
+        .expect("providers")
+        .contains_key("p1"));
+    let quarantined = std::fs::read_dir(dir.path())
+        .expect("read dir")
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("cc-switch.db.corrupt-")
+        })
+        .count();
+    assert_eq!(quarantined, 1);
+    assert!(crate::init_status::take_recoveries()
+        .iter()
+        .any(|event| event.kind == "database" && event.restored_from.is_some()));
+}
+
+#[test]
+fn batch_transaction_rolls_back_all_operations_on_failure() {
+    let db = Database::memory().expect("create memory db");
+    let existing = Provider::with_id("p1".to_string(), "P1".to_string(), json!({}), None);
+    db.save_provider("claude", &existing)
+        .expect("save provider");
+
+    let added = Provider::with_id("p2".to_string(), "P2".to_string(), json!({}), None);
+    let mut renamed = existing.clone();
+    renamed.name = "Renamed".to_string();
+    let ops = vec![
+        BatchOp::AddProvider {
+            app_type: "claude".to_string(),
+            provider: added.clone(),
+        },
+        BatchOp::UpdateProvider {
+            app_type: "claude".to_string(),
+            provider: renamed,
+        },
+        // 不存在的供应商：整个批次失败
+        BatchOp::DeleteProvider {
+            app_type: "claude".to_string(),
+            id: "missing".to_string(),
+        },
+    ];
+    let err = db.transaction(&ops).expect_err("batch should fail");
+    assert!(err.to_string().contains("claude/missing"));
+    let providers = db.get_all_providers("claude").expect("providers");
+    assert_eq!(providers.len(), 1);
+    assert_eq!(providers["p1"].name, "P1");
+
+    let applied = db.transaction(&ops[..2]).expect("apply batch");
+    assert_eq!(applied, 2);
+    let providers = db.get_all_providers("claude").expect("providers");
+    assert_eq!(providers.len(), 2);
+    assert_eq!(providers["p1"].name, "Renamed");
+
+    // 新增已存在的供应商同样失败
+    let err = db
+        .transaction(&[BatchOp::AddProvider {
+            app_type: "claude".to_string(),
+            provider: added,
+        }])
+        .expect_err("duplicate add should fail");
+    assert!(err.to_string().contains("已存在"));
+}
+
+#[test]
+fn provider_pages_are_sorted_and_bounded() {
+    let db = Database::memory().expect("create memory db");
+    for (index, name) in ["charlie", "Alpha", "bravo", "delta", "echo"]
+        .iter()
+        .enumerate()
+    {
+        let mut provider =
+            Provider::with_id(format!("p{index}"), name.to_string(), json!({}), None);
+        provider.created_at = Some(index as i64);
+        provider.sort_index = Some(4 - index);
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+    }
+
+    let names = |page: &ProviderPage| {
+        page.items
+            .iter()
+            .map(|p| p.name.clone())
+            .collect::<Vec<_>>()
+    };
+    let page = db
+        .list_providers_page("claude", 0, 2, ProviderSort::Name)
+        .expect("first page");
+    assert_eq!(page.total, 5);
+    assert_eq!(names(&page), vec!["Alpha", "bravo"]);
+    let page = db
+        .list_providers_page("claude", 4, 2, ProviderSort::Name)
+        .expect("last page");
+    assert_eq!(names(&page), vec!["echo"]);
+
+    let page = db
+        .list_providers_page("claude", 0, 10, ProviderSort::CreatedAtDesc)
+        .expect("newest first");
+    assert_eq!(page.items[0].id, "p4");
+    let page = db
+        .list_providers_page("claude", 0, 10, ProviderSort::Custom)
+        .expect("custom order");
+    let all = db.get_all_providers("claude").expect("all providers");
+    assert_eq!(
+        page.items.iter().map(|p| p.id.as_str()).collect::<Vec<_>>(),
+        all.keys().map(String::as_str).collect::<Vec<_>>()
+    );
+
+    // 页大小被限制在允许范围内
+    let page = db
+        .list_providers_page("claude", 0, 0, ProviderSort::Custom)
+        .expect("minimum page size");
+    assert_eq!(page.limit, 1);
+    assert_eq!(page.items.len(), 1);
+}
@@ -0,0 +1,69 @@
+//! Key checks for deep link imports
+//!
+//! Flags suspicious API keys in a deep link request before the user confirms
+//! the import (see [`crate::services::secret_lint`]).
+
+use std::str::FromStr;
+
+use super::utils::decode_base64_param;
+use super::{parse_and_merge_config, DeepLinkImportRequest};
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::secret_lint::{lint_config, lint_key, KeyWarning};
+use serde_json::Value;
+
+/// Check the keys carried by a provider or MCP deep link request
+pub fn lint_deeplink_keys(request: &DeepLinkImportRequest) -> Result<Vec<KeyWarning>, AppError> {
+    match request.resource.as_str() {
+        "provider" => lint_provider(request),
+        "mcp" => lint_mcp(request),
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn lint_provider(request: &DeepLinkImportRequest) -> Result<Vec<KeyWarning>, AppError> {
+    let merged = parse_and_merge_config(request)?;
+    let app_type = merged
+        .app
+        .as_deref()
+        .and_then(|app| AppType::from_str(app).ok());
+    // Only the primary endpoint receives the key
+    let endpoint = merged
+        .endpoint
+        .as_deref()
+        .and_then(|endpoints| endpoints.split(',').next())
+        .unwrap_or_default()
+        .trim();
+
+    let mut warnings = Vec::new();
+    if let Some(key) = merged.api_key.as_deref() {
+        warnings.extend(lint_key("apiKey", app_type.as_ref(), endpoint, key));
+    }
+    if let Some(key) = merged.usage_api_key.as_deref() {
+        let usage_endpoint = merged.usage_base_url.as_deref().unwrap_or(endpoint);
+        warnings.extend(lint_key(
+            "usageApiKey",
+            app_type.as_ref(),
+            usage_endpoint,
+            key,
+        ));
+    }
+    Ok(warnings)
+}
+
+fn lint_mcp(request: &DeepLinkImportRequest) -> Result<Vec<KeyWarning>, AppError> {
+    let Some(config_b64) = request.config.as_deref() else {
+        return Ok(Vec::new());
+    };
+    let decoded = decode_base64_param("config", config_b64)?;
+    let config: Value = serde_json::from_slice(&decoded)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON in MCP config: {e}")))?;
+
+    let Some(servers) = config.get("mcpServers").and_then(Value::as_object) else {
+        return Ok(Vec::new());
+    };
+    Ok(servers
+        .iter()
+        .flat_map(|(id, spec)| lint_config(id, None, "", spec))
+        .collect())
+}
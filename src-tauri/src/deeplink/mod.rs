@@ -9,6 +9,7 @@
 //!
 //! See docs/ccswitch-deeplink-design.md for detailed design.
 
+mod lint;
 mod mcp;
 mod parser;
 mod prompt;
@@ -22,9 +23,11 @@ mod tests;
 use serde::{Deserialize, Serialize};
 
 // Re-export public API
+pub use lint::lint_deeplink_keys;
 pub use mcp::import_mcp_from_deeplink;
-pub use parser::parse_deeplink_url;
+pub use parser::{parse_deeplink_action, parse_deeplink_url, DeepLinkAction};
 pub use prompt::import_prompt_from_deeplink;
+pub(crate) use provider::build_provider_from_request;
 pub use provider::{import_provider_from_deeplink, parse_and_merge_config};
 pub use skill::import_skill_from_deeplink;
 
@@ -2,28 +2,88 @@
 //!
 //! Parses ccswitch:// URLs into DeepLinkImportRequest structures.
 
-use super::utils::validate_url;
+use super::utils::{decode_base64_param, validate_url};
 use super::DeepLinkImportRequest;
+use crate::app_config::AppType;
 use crate::error::AppError;
+use percent_encoding::percent_decode_str;
+use serde_json::Value;
 use std::collections::HashMap;
+use std::str::FromStr;
 use url::Url;
 
-/// Parse a ccswitch:// URL into a DeepLinkImportRequest
-///
-/// Expected format:
-/// ccswitch://v1/import?resource={type}&...
-pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppError> {
-    // Parse URL
+/// Action triggered by a ccswitch:// URL
+#[derive(Debug, Clone)]
+pub enum DeepLinkAction {
+    /// Show the import confirmation dialog
+    Import(Box<DeepLinkImportRequest>),
+    /// Switch an app to an existing provider
+    Switch { app: AppType, provider_id: String },
+}
+
+fn parse_ccswitch_url(url_str: &str) -> Result<Url, AppError> {
     let url = Url::parse(url_str)
         .map_err(|e| AppError::InvalidInput(format!("Invalid deep link URL: {e}")))?;
 
-    // Validate scheme
     let scheme = url.scheme();
     if scheme != "ccswitch" {
         return Err(AppError::InvalidInput(format!(
             "Invalid scheme: expected 'ccswitch', got '{scheme}'"
         )));
     }
+    Ok(url)
+}
+
+/// Parse any ccswitch:// URL into the action it triggers
+///
+/// Supported formats:
+/// - ccswitch://switch/{app}/{provider-id}
+/// - ccswitch://import?payload={base64 JSON}
+/// - ccswitch://v1/import?resource={type}&...
+pub fn parse_deeplink_action(url_str: &str) -> Result<DeepLinkAction, AppError> {
+    let url = parse_ccswitch_url(url_str)?;
+    if url.host_str() != Some("switch") {
+        return parse_deeplink_url(url_str)
+            .map(|request| DeepLinkAction::Import(Box::new(request)));
+    }
+
+    let segments = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let [app, provider_id] = segments.as_slice() else {
+        return Err(AppError::InvalidInput(format!(
+            "Invalid switch link: expected 'ccswitch://switch/{{app}}/{{provider-id}}', got '{}'",
+            url.path()
+        )));
+    };
+    let app = AppType::from_str(app)
+        .map_err(|_| AppError::InvalidInput(format!("Invalid app type: {app}")))?;
+    let provider_id = percent_decode_str(provider_id)
+        .decode_utf8()
+        .map_err(|e| AppError::InvalidInput(format!("Invalid UTF-8 in '{provider_id}': {e}")))?
+        .into_owned();
+    Ok(DeepLinkAction::Switch { app, provider_id })
+}
+
+/// Parse a ccswitch:// import URL into a DeepLinkImportRequest
+///
+/// Expected format:
+/// ccswitch://v1/import?resource={type}&...
+///
+/// or, with all parameters packed into one Base64 encoded JSON object:
+/// ccswitch://import?payload={base64 JSON}
+pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppError> {
+    let url = parse_ccswitch_url(url_str)?;
+
+    if url.host_str() == Some("import") {
+        let payload = url
+            .query_pairs()
+            .find(|(key, _)| key == "payload")
+            .map(|(_, value)| value.into_owned())
+            .ok_or_else(|| AppError::InvalidInput("Missing 'payload' parameter".to_string()))?;
+        return parse_import_payload(&payload);
+    }
 
     // Extract version from host
     let version = url
@@ -48,7 +108,41 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
 
     // Parse query parameters
     let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    parse_import_params(&params, version)
+}
 
+/// Decode an `import?payload=` parameter: a Base64 encoded JSON object with the
+/// same keys as the v1 query parameters
+fn parse_import_payload(payload: &str) -> Result<DeepLinkImportRequest, AppError> {
+    let decoded = decode_base64_param("payload", payload)?;
+    let value: Value = serde_json::from_slice(&decoded)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid JSON in payload: {e}")))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| AppError::InvalidInput("Payload must be a JSON object".to_string()))?;
+
+    let mut params = HashMap::new();
+    for (key, value) in object {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Null => continue,
+            _ => {
+                return Err(AppError::InvalidInput(format!(
+                    "Invalid payload field '{key}': expected a string, number or boolean"
+                )))
+            }
+        };
+        params.insert(key.clone(), value);
+    }
+    parse_import_params(&params, "v1".to_string())
+}
+
+fn parse_import_params(
+    params: &HashMap<String, String>,
+    version: String,
+) -> Result<DeepLinkImportRequest, AppError> {
     // Extract and validate resource type
     let resource = params
         .get("resource")
@@ -57,10 +151,10 @@ pub fn parse_deeplink_url(url_str: &str) -> Result<DeepLinkImportRequest, AppErr
 
     // Dispatch to appropriate parser based on resource type
     match resource.as_str() {
-        "provider" => parse_provider_deeplink(&params, version, resource),
-        "prompt" => parse_prompt_deeplink(&params, version, resource),
-        "mcp" => parse_mcp_deeplink(&params, version, resource),
-        "skill" => parse_skill_deeplink(&params, version, resource),
+        "provider" => parse_provider_deeplink(params, version, resource),
+        "prompt" => parse_prompt_deeplink(params, version, resource),
+        "mcp" => parse_mcp_deeplink(params, version, resource),
+        "skill" => parse_skill_deeplink(params, version, resource),
         _ => Err(AppError::InvalidInput(format!(
             "Unsupported resource type: {resource}"
         ))),
@@ -1,7 +1,7 @@
 //! Deep link module tests
 
 use super::mcp::parse_mcp_apps;
-use super::parser::parse_deeplink_url;
+use super::parser::{parse_deeplink_action, parse_deeplink_url, DeepLinkAction};
 use super::prompt::import_prompt_from_deeplink;
 use super::provider::parse_and_merge_config;
 use super::utils::{infer_homepage_from_endpoint, validate_url};
@@ -77,6 +77,44 @@ fn test_parse_missing_required_field() {
         .contains("Missing 'name' parameter"));
 }
 
+#[test]
+fn test_parse_switch_deeplink() {
+    let action = parse_deeplink_action("ccswitch://switch/claude/my%20relay").unwrap();
+    match action {
+        DeepLinkAction::Switch { app, provider_id } => {
+            assert_eq!(app, AppType::Claude);
+            assert_eq!(provider_id, "my relay");
+        }
+        other => panic!("expected switch action, got {other:?}"),
+    }
+
+    assert!(parse_deeplink_action("ccswitch://switch/claude").is_err());
+    assert!(parse_deeplink_action("ccswitch://switch/unknown/id").is_err());
+    assert!(matches!(
+        parse_deeplink_action("ccswitch://v1/import?resource=provider&app=claude&name=Test"),
+        Ok(DeepLinkAction::Import(_))
+    ));
+}
+
+#[test]
+fn test_parse_import_payload_deeplink() {
+    let payload = BASE64_STANDARD.encode(
+        r#"{"resource":"provider","app":"codex","name":"Relay","endpoint":"https://api.example.com","usageAutoInterval":30,"enabled":true}"#,
+    );
+    let url = format!("ccswitch://import?payload={payload}");
+
+    let request = parse_deeplink_url(&url).unwrap();
+    assert_eq!(request.version, "v1");
+    assert_eq!(request.app, Some("codex".to_string()));
+    assert_eq!(request.name, Some("Relay".to_string()));
+    assert_eq!(request.usage_auto_interval, Some(30));
+    assert_eq!(request.enabled, Some(true));
+
+    let nested = BASE64_STANDARD.encode(r#"{"resource":"provider","name":{"x":1}}"#);
+    assert!(parse_deeplink_url(&format!("ccswitch://import?payload={nested}")).is_err());
+    assert!(parse_deeplink_url("ccswitch://import").is_err());
+}
+
 // =============================================================================
 // Utils Tests
 // =============================================================================
@@ -3,11 +3,12 @@ use std::sync::PoisonError;
 
 use thiserror::Error;
 
+/// 可能携带外部内容（配置片段、上游响应）的变体在显示前统一做密钥脱敏
 #[derive(Debug, Error)]
 pub enum AppError {
-    #[error("配置错误: {0}")]
+    #[error("配置错误: {}", crate::redact::redact_secrets(.0))]
     Config(String),
-    #[error("无效输入: {0}")]
+    #[error("无效输入: {}", crate::redact::redact_secrets(.0))]
     InvalidInput(String),
     #[error("IO 错误: {path}: {source}")]
     Io {
@@ -15,24 +16,30 @@ pub enum AppError {
         #[source]
         source: std::io::Error,
     },
-    #[error("{context}: {source}")]
+    #[error("网络路径暂时不可用（请确认 WSL/网络共享可访问）: {path}: {source}")]
+    NetworkPath {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{context}: {}", crate::redact::redact_secrets(&.source.to_string()))]
     IoContext {
         context: String,
         #[source]
         source: std::io::Error,
     },
-    #[error("JSON 解析错误: {path}: {source}")]
+    #[error("JSON 解析错误: {path}: {}", crate::redact::redact_secrets(&.source.to_string()))]
     Json {
         path: String,
         #[source]
         source: serde_json::Error,
     },
-    #[error("JSON 序列化失败: {source}")]
+    #[error("JSON 序列化失败: {}", crate::redact::redact_secrets(&.source.to_string()))]
     JsonSerialize {
         #[source]
         source: serde_json::Error,
     },
-    #[error("TOML 解析错误: {path}: {source}")]
+    #[error("TOML 解析错误: {path}: {}", crate::redact::redact_secrets(&.source.to_string()))]
     Toml {
         path: String,
         #[source]
@@ -40,18 +47,27 @@ pub enum AppError {
     },
     #[error("锁获取失败: {0}")]
     Lock(String),
-    #[error("MCP 校验失败: {0}")]
+    #[error("MCP 校验失败: {}", crate::redact::redact_secrets(.0))]
     McpValidation(String),
-    #[error("{0}")]
+    #[error("{}", crate::redact::redact_secrets(.0))]
     Message(String),
-    #[error("{zh} ({en})")]
+    #[error("{} ({})", crate::redact::redact_secrets(.zh), crate::redact::redact_secrets(.en))]
     Localized {
         key: &'static str,
         zh: String,
         en: String,
     },
-    #[error("数据库错误: {0}")]
+    #[error("数据库错误: {}", crate::redact::redact_secrets(.0))]
     Database(String),
+    #[error(
+        "文件正被其他 CC Switch 进程写入，等待超时: {path}{}",
+        .holder.map(|pid| format!("（PID {pid}）")).unwrap_or_default()
+    )]
+    FileLocked {
+        path: String,
+        /// 持有锁的进程
+        holder: Option<u32>,
+    },
     #[error("所有供应商已熔断，无可用渠道")]
     AllProvidersCircuitOpen,
     #[error("未配置供应商")]
@@ -66,6 +82,13 @@ impl AppError {
         }
     }
 
+    pub fn network_path(path: impl AsRef<Path>, source: std::io::Error) -> Self {
+        Self::NetworkPath {
+            path: path.as_ref().display().to_string(),
+            source,
+        }
+    }
+
     pub fn json(path: impl AsRef<Path>, source: serde_json::Error) -> Self {
         Self::Json {
             path: path.as_ref().display().to_string(),
@@ -0,0 +1,151 @@
+//! 限流与服务错误突增告警
+//!
+//! 后台定期读取会话日志（见 [`crate::services::error_burst`]）。当前供应商出现错误突增时
+//! 标记为不健康（与代理故障转移共用健康状态），并发送通知，建议切换到故障转移队列中
+//! 下一个健康的供应商。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::database::Database;
+use crate::notifications::{self, Notice};
+use crate::services::error_burst::{ErrorBurst, ErrorBurstDetector};
+use crate::settings::ErrorBurstConfig;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动后首次检查的延迟，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+
+/// 启动后台检测任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + STARTUP_DELAY;
+        let mut ticker = tokio::time::interval_at(start, CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut detector = ErrorBurstDetector::default();
+
+        loop {
+            ticker.tick().await;
+
+            let config = crate::settings::get_settings().error_burst;
+            if !config.enabled {
+                // 重新启用时不回溯关闭期间的日志
+                detector = ErrorBurstDetector::default();
+                continue;
+            }
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if state.db.is_locked() {
+                continue;
+            }
+            let db = state.db.clone();
+
+            let scan_db = db.clone();
+            let scan_config = config.clone();
+            let task = tauri::async_runtime::spawn_blocking(move || {
+                let result = detector.scan(&scan_db, &scan_config);
+                (detector, result)
+            })
+            .await;
+            let bursts = match task {
+                Ok((scanned, result)) => {
+                    detector = scanned;
+                    match result {
+                        Ok(bursts) => bursts,
+                        Err(e) => {
+                            log::warn!("检测会话日志错误失败: {e}");
+                            continue;
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("检测会话日志错误任务异常: {e}");
+                    detector = ErrorBurstDetector::default();
+                    continue;
+                }
+            };
+
+            for burst in bursts {
+                if let Err(e) = handle_burst(&app, &db, &config, &burst).await {
+                    log::warn!("处理错误突增失败: {e}");
+                }
+            }
+        }
+    });
+}
+
+/// 故障转移队列中下一个健康的供应商
+async fn next_in_failover_queue(
+    db: &Database,
+    app_type: &str,
+    current: &str,
+) -> Result<Option<String>, crate::error::AppError> {
+    for item in db.get_failover_queue(app_type)? {
+        if item.provider_id == current {
+            continue;
+        }
+        if db
+            .get_provider_health(&item.provider_id, app_type)
+            .await?
+            .is_healthy
+        {
+            return Ok(Some(item.provider_name));
+        }
+    }
+    Ok(None)
+}
+
+async fn handle_burst(
+    app: &AppHandle,
+    db: &Database,
+    config: &ErrorBurstConfig,
+    burst: &ErrorBurst,
+) -> Result<(), crate::error::AppError> {
+    let app_type = burst.app_type.as_str();
+    // 已切换到其它供应商时不再告警
+    let current = crate::settings::get_effective_current_provider(db, &burst.app_type)?;
+    if current.as_deref() != Some(burst.provider_id.as_str()) {
+        return Ok(());
+    }
+
+    log::warn!(
+        "{app_type} 供应商 {} 在 {} 分钟内出现 {} 次错误（最近 HTTP {}）",
+        burst.provider_id,
+        config.window_minutes,
+        burst.count,
+        burst.last_status
+    );
+    db.update_provider_health_with_threshold(
+        &burst.provider_id,
+        app_type,
+        false,
+        Some(format!(
+            "会话日志中 {} 分钟内出现 {} 次错误（最近 HTTP {}）",
+            config.window_minutes, burst.count, burst.last_status
+        )),
+        1,
+    )
+    .await?;
+
+    let provider_name = db
+        .get_provider_by_id(&burst.provider_id, app_type)?
+        .map(|provider| provider.name)
+        .unwrap_or_else(|| burst.provider_id.clone());
+    let next = next_in_failover_queue(db, app_type, &burst.provider_id).await?;
+    notifications::notify(
+        app,
+        Notice::ErrorBurst {
+            app_type: &burst.app_type,
+            provider: &provider_name,
+            count: burst.count,
+            minutes: config.window_minutes,
+            next: next.as_deref(),
+        },
+    );
+    Ok(())
+}
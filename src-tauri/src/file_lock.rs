@@ -0,0 +1,127 @@
+//! 跨进程的建议性文件锁
+//!
+//! 界面、命令行工具与意外启动的第二个实例共享同一个数据目录。修改 `settings.json`
+//! 与加密数据库文件时先持有同目录下 `<文件名>.lock` 的系统文件锁（Unix 上为 `flock`，
+//! Windows 上为 `LockFileEx`），在持锁期间完成「重新读取 → 应用修改 → 写入」；其他
+//! 进程等待锁释放，超时后返回 [`AppError::FileLocked`]，而不是交错写入损坏文件或覆盖
+//! 对方刚写入的内容。普通 SQLite 数据库文件的并发写入由 SQLite 自身的锁与
+//! busy_timeout 处理。
+//!
+//! 锁由操作系统随文件句柄释放，持有者崩溃时不会留下需要接管的锁。锁文件本身保留在
+//! 磁盘上（删除会让等待中的进程锁住已被删除的文件），内容为最后一个持有者的 PID。
+
+use std::fs;
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use fs4::fs_std::FileExt;
+
+use crate::error::AppError;
+
+/// 默认等待时间
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 重试间隔
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 持有中的文件锁，drop 时关闭句柄并释放
+#[derive(Debug)]
+pub struct FileLock {
+    _file: fs::File,
+}
+
+fn lock_path(target: &Path) -> PathBuf {
+    let mut name = target
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(".lock");
+    target.with_file_name(name)
+}
+
+/// 读取锁文件中的 PID（仅用于诊断；Windows 上被锁住的文件不可读，返回 None）
+fn holder_pid(lock_path: &Path) -> Option<u32> {
+    fs::read_to_string(lock_path).ok()?.trim().parse().ok()
+}
+
+/// 获取 `target` 的写锁（默认等待 [`DEFAULT_TIMEOUT`]）
+pub fn lock(target: &Path) -> Result<FileLock, AppError> {
+    lock_with_timeout(target, DEFAULT_TIMEOUT)
+}
+
+/// 获取 `target` 的写锁，最多等待 `timeout`
+///
+/// 同一进程内不可重入：持有锁时再次获取同一文件的锁会一直等到超时。
+pub fn lock_with_timeout(target: &Path, timeout: Duration) -> Result<FileLock, AppError> {
+    let path = lock_path(target);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)
+        .map_err(|e| AppError::io(&path, e))?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if FileExt::try_lock_exclusive(&file).map_err(|e| AppError::io(&path, e))? {
+            break;
+        }
+        if Instant::now() >= deadline {
+            return Err(AppError::FileLocked {
+                path: target.display().to_string(),
+                holder: holder_pid(&path),
+            });
+        }
+        std::thread::sleep(RETRY_INTERVAL);
+    }
+
+    // PID 仅用于诊断，写入失败不影响加锁
+    let _ = file
+        .set_len(0)
+        .and_then(|_| file.rewind())
+        .and_then(|_| write!(file, "{}", std::process::id()));
+    Ok(FileLock { _file: file })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn second_writer_times_out_until_the_lock_is_released() {
+        let dir = TempDir::new().expect("temp dir");
+        let target = dir.path().join("settings.json");
+
+        let guard = lock(&target).expect("first lock");
+        let err = lock_with_timeout(&target, Duration::from_millis(120))
+            .expect_err("second lock should time out");
+        match err {
+            AppError::FileLocked { holder, .. } => {
+                if cfg!(unix) {
+                    assert_eq!(holder, Some(std::process::id()));
+                }
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        drop(guard);
+        lock_with_timeout(&target, Duration::from_millis(120)).expect("lock after release");
+    }
+
+    #[test]
+    fn leftover_lock_files_do_not_block() {
+        let dir = TempDir::new().expect("temp dir");
+        let target = dir.path().join("cc-switch.db.enc");
+        // 崩溃的持有者只会留下未加锁的文件
+        fs::write(lock_path(&target), "999999").expect("create leftover lock file");
+
+        lock_with_timeout(&target, Duration::from_millis(120)).expect("lock leftover file");
+    }
+}
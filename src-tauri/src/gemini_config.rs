@@ -1,5 +1,6 @@
 use crate::config::write_text_file;
 use crate::error::AppError;
+use crate::settings::LiveFile;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
@@ -24,7 +25,7 @@ pub fn get_gemini_dir() -> PathBuf {
 
 /// 获取 Gemini .env 文件路径
 pub fn get_gemini_env_path() -> PathBuf {
-    get_gemini_dir().join(".env")
+    crate::settings::resolve_live_file(LiveFile::GeminiEnv, &get_gemini_dir(), ".env")
 }
 
 /// 解析 .env 文件内容为键值对
@@ -197,6 +198,34 @@ pub fn write_gemini_env_atomic(map: &HashMap<String, String>) -> Result<(), AppE
     Ok(())
 }
 
+/// 由供应商管理的 .env 键：切换时先从现有文件中移除，再写入新供应商的值
+const MANAGED_ENV_KEYS: [&str; 6] = [
+    "GEMINI_API_KEY",
+    "GOOGLE_API_KEY",
+    "GOOGLE_GEMINI_BASE_URL",
+    "GEMINI_MODEL",
+    "GOOGLE_GENAI_USE_VERTEXAI",
+    "GOOGLE_CLOUD_PROJECT",
+];
+
+/// 把供应商的环境变量合并到现有 .env
+///
+/// 托管的认证相关键（API Key、端点、模型等）以供应商为准，供应商未设置时被移除；
+/// 用户自行维护的其他变量（如代理设置）保留。
+pub fn merge_gemini_env(
+    existing: &HashMap<String, String>,
+    provider_env: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = existing.clone();
+    merged.retain(|key, _| !MANAGED_ENV_KEYS.contains(&key.as_str()));
+    merged.extend(
+        provider_env
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+    merged
+}
+
 /// 从 .env 格式转换为 Provider.settings_config (JSON Value)
 pub fn env_to_json(env_map: &HashMap<String, String>) -> Value {
     let mut json_map = serde_json::Map::new();
@@ -287,7 +316,7 @@ pub fn validate_gemini_settings_strict(settings: &Value) -> Result<(), AppError>
 ///
 /// 返回路径：`~/.gemini/settings.json`（与 `.env` 文件同级）
 pub fn get_gemini_settings_path() -> PathBuf {
-    get_gemini_dir().join("settings.json")
+    crate::settings::resolve_live_file(LiveFile::GeminiSettings, &get_gemini_dir(), "settings.json")
 }
 
 /// 更新 Gemini 目录 settings.json 中的 security.auth.selectedType 字段
@@ -650,6 +679,32 @@ KEY_WITH-DASH=value";
         assert!(validate_gemini_settings_strict(&settings).is_err());
     }
 
+    #[test]
+    fn test_merge_env_replaces_managed_keys_only() {
+        let existing = HashMap::from([
+            ("GEMINI_API_KEY".to_string(), "old-key".to_string()),
+            (
+                "GOOGLE_GEMINI_BASE_URL".to_string(),
+                "https://old".to_string(),
+            ),
+            (
+                "HTTPS_PROXY".to_string(),
+                "http://127.0.0.1:7890".to_string(),
+            ),
+        ]);
+
+        // 切换到 Google 官方（OAuth）：清除 API Key 与端点，保留代理设置
+        let merged = merge_gemini_env(&existing, &HashMap::new());
+        assert_eq!(merged.len(), 1);
+        assert!(merged.contains_key("HTTPS_PROXY"));
+
+        let provider_env = HashMap::from([("GEMINI_API_KEY".to_string(), "new-key".to_string())]);
+        let merged = merge_gemini_env(&existing, &provider_env);
+        assert_eq!(merged.get("GEMINI_API_KEY"), Some(&"new-key".to_string()));
+        assert!(!merged.contains_key("GOOGLE_GEMINI_BASE_URL"));
+        assert!(merged.contains_key("HTTPS_PROXY"));
+    }
+
     #[test]
     fn test_validate_invalid_env_type() {
         // 测试 env 不是对象时会失败
@@ -0,0 +1,1409 @@
+//! 桌面端入口（`gui` feature）
+//!
+//! 窗口、托盘、深链接与 Tauri 命令注册都在这里；供应商 / MCP / 设置等核心逻辑位于
+//! `services` 等模块，不依赖本模块，关闭 `gui` feature 后仍可通过 `cc-switch-cli` 使用。
+
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use std::sync::Arc;
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+use tauri::RunEvent;
+use tauri::{Emitter, Manager};
+
+use crate::services::SkillService;
+use crate::store::AppState;
+use crate::{
+    app_store, auto_select, aux_windows, backup_scheduler, commands, error_watcher, launch_args,
+    live_watcher, panic_hook, rule_engine, services, settings_watcher, store, tray, uptime_monitor,
+    webdav_scheduler,
+};
+
+fn redact_url_for_log(url_str: &str) -> String {
+    match url::Url::parse(url_str) {
+        Ok(url) => {
+            let mut output = format!("{}://", url.scheme());
+            if let Some(host) = url.host_str() {
+                output.push_str(host);
+            }
+            output.push_str(url.path());
+
+            let mut keys: Vec<String> = url.query_pairs().map(|(k, _)| k.to_string()).collect();
+            keys.sort();
+            keys.dedup();
+
+            if !keys.is_empty() {
+                output.push_str("?[keys:");
+                output.push_str(&keys.join(","));
+                output.push(']');
+            }
+
+            output
+        }
+        Err(_) => {
+            let base = url_str.split('#').next().unwrap_or(url_str);
+            match base.split_once('?') {
+                Some((prefix, _)) => format!("{prefix}?[redacted]"),
+                None => base.to_string(),
+            }
+        }
+    }
+}
+
+fn focus_main(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        log::info!("✓ Window shown and focused");
+    }
+}
+
+/// 统一处理 ccswitch:// 深链接 URL
+///
+/// - 解析 URL
+/// - 导入类链接：向前端发射 `deeplink-import` 事件，由前端弹出确认框
+/// - 切换类链接（`ccswitch://switch/{app}/{id}`）：向前端发射 `deeplink-switch` 事件，
+///   同样经确认框确认后才切换，避免网页通过链接静默更换供应商
+/// - 失败时向前端发射 `deeplink-error` 事件
+/// - 可选：在成功时聚焦主窗口
+fn handle_deeplink_url(
+    app: &tauri::AppHandle,
+    url_str: &str,
+    focus_main_window: bool,
+    source: &str,
+) -> bool {
+    if !url_str.starts_with("ccswitch://") {
+        return false;
+    }
+
+    let redacted_url = redact_url_for_log(url_str);
+    log::info!("✓ Deep link URL detected from {source}: {redacted_url}");
+    log::debug!("Deep link URL (raw) from {source}: {url_str}");
+
+    match crate::deeplink::parse_deeplink_action(url_str) {
+        Ok(crate::deeplink::DeepLinkAction::Switch {
+            app: app_type,
+            provider_id,
+        }) => {
+            log::info!(
+                "✓ Deep link switch: app={}, provider={provider_id}",
+                app_type.as_str()
+            );
+            let Some(provider_name) = app
+                .try_state::<AppState>()
+                .and_then(|state| {
+                    state
+                        .db
+                        .get_provider_by_id(&provider_id, app_type.as_str())
+                        .ok()
+                        .flatten()
+                })
+                .map(|provider| provider.name)
+            else {
+                log::error!("✗ Deep link switch target not found: {provider_id}");
+                if let Err(emit_err) = app.emit(
+                    "deeplink-error",
+                    serde_json::json!({
+                        "url": url_str,
+                        "error": format!(
+                            "Provider '{provider_id}' not found for {}",
+                            app_type.as_str()
+                        )
+                    }),
+                ) {
+                    log::error!("✗ Failed to emit deeplink-error event: {emit_err}");
+                }
+                return true;
+            };
+
+            if let Err(e) = app.emit(
+                "deeplink-switch",
+                serde_json::json!({
+                    "app": app_type.as_str(),
+                    "providerId": provider_id,
+                    "providerName": provider_name
+                }),
+            ) {
+                log::error!("✗ Failed to emit deeplink-switch event: {e}");
+            } else {
+                log::info!("✓ Emitted deeplink-switch event to frontend");
+            }
+
+            if focus_main_window {
+                focus_main(app);
+            }
+        }
+        Ok(crate::deeplink::DeepLinkAction::Import(request)) => {
+            log::info!(
+                "✓ Successfully parsed deep link: resource={}, app={:?}, name={:?}",
+                request.resource,
+                request.app,
+                request.name
+            );
+
+            if let Err(e) = app.emit("deeplink-import", &request) {
+                log::error!("✗ Failed to emit deeplink-import event: {e}");
+            } else {
+                log::info!("✓ Emitted deeplink-import event to frontend");
+            }
+
+            if focus_main_window {
+                focus_main(app);
+            }
+        }
+        Err(e) => {
+            log::error!("✗ Failed to parse deep link URL: {e}");
+
+            if let Err(emit_err) = app.emit(
+                "deeplink-error",
+                serde_json::json!({
+                    "url": url_str,
+                    "error": e.to_string()
+                }),
+            ) {
+                log::error!("✗ Failed to emit deeplink-error event: {emit_err}");
+            }
+        }
+    }
+
+    true
+}
+
+/// 处理启动参数（首个实例自身的参数，或单实例插件转交的第二个实例的参数）
+///
+/// 只包含 `--switch` 时在后台切换，不打开主界面，便于脚本调用；其余情况显示并聚焦主窗口。
+fn handle_launch_args(app: &tauri::AppHandle, args: &[String], source: &str) {
+    let requests = match launch_args::parse(args) {
+        Ok(requests) => requests,
+        Err(e) => {
+            log::error!("✗ Invalid launch arguments from {source}: {e}");
+            Vec::new()
+        }
+    };
+
+    let mut focus = requests.is_empty();
+    for request in requests {
+        match request {
+            launch_args::LaunchRequest::DeepLink(url) => {
+                handle_deeplink_url(app, &url, false, source);
+                focus = true;
+            }
+            launch_args::LaunchRequest::Switch {
+                app_type,
+                provider_id,
+            } => {
+                log::info!(
+                    "✓ Launch switch from {source}: app={}, provider={provider_id}",
+                    app_type.as_str()
+                );
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) =
+                        tray::switch_provider_internal(&app_handle, app_type, provider_id)
+                    {
+                        log::error!("✗ Launch switch failed: {e}");
+                    }
+                });
+            }
+        }
+    }
+
+    if focus {
+        tray::show_main_window(app);
+    }
+}
+
+/// 更新托盘菜单的Tauri命令
+#[tauri::command]
+async fn update_tray_menu(app: tauri::AppHandle) -> Result<bool, String> {
+    match tray::refresh_tray_menu(&app) {
+        Ok(updated) => Ok(updated),
+        Err(err) => {
+            log::error!("创建托盘菜单失败: {err}");
+            Ok(false)
+        }
+    }
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    // 设置 panic hook，在应用崩溃时记录日志到 <app_config_dir>/crash.log（默认 ~/.cc-switch/crash.log）
+    panic_hook::setup_panic_hook();
+
+    let mut builder = tauri::Builder::default();
+
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            log::info!("=== Single Instance Callback Triggered ===");
+            log::debug!("Args count: {}", args.len());
+            for (i, arg) in args.iter().enumerate() {
+                log::debug!("  arg[{i}]: {}", redact_url_for_log(arg));
+            }
+
+            // 第二个实例的参数转交给当前实例处理（args[0] 为程序路径）
+            handle_launch_args(
+                app,
+                args.get(1..).unwrap_or_default(),
+                "single_instance args",
+            );
+        }));
+    }
+
+    let builder = builder
+        // 注册 deep-link 插件（处理 macOS AppleEvent 和其他平台的深链接）
+        .plugin(tauri_plugin_deep_link::init())
+        // 拦截窗口关闭：辅助窗口保存位置后正常关闭，主窗口根据设置决定是否最小化到托盘；
+        // 主窗口的主题变化用于切换托盘图标
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::CloseRequested { api, .. } => {
+                if aux_windows::AuxWindow::from_label(window.label()).is_some() {
+                    aux_windows::remember_geometry(window);
+                    return;
+                }
+
+                let settings = crate::settings::get_settings();
+
+                if settings.minimize_to_tray_on_close {
+                    api.prevent_close();
+                    let _ = window.hide();
+                    #[cfg(target_os = "windows")]
+                    {
+                        let _ = window.set_skip_taskbar(true);
+                    }
+                    #[cfg(target_os = "macos")]
+                    {
+                        tray::apply_tray_policy(window.app_handle(), false);
+                    }
+                } else {
+                    window.app_handle().exit(0);
+                }
+            }
+            tauri::WindowEvent::ThemeChanged(theme) if window.label() == "main" => {
+                tray::apply_theme(window.app_handle(), *theme);
+            }
+            _ => {}
+        })
+        .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .setup(|app| {
+            // 预先刷新 Store 覆盖配置，确保后续路径读取正确（日志/数据库等）
+            if app_store::refresh_app_config_dir_override(app.handle()).is_some() {
+                // 设置文件位于数据目录下，覆盖生效前读到的是默认目录中的设置
+                if let Err(e) = crate::settings::reload_settings() {
+                    log::warn!("重新加载设置失败: {e}");
+                }
+            }
+            crate::settings::migrate_webdav_password();
+            panic_hook::init_app_config_dir(crate::config::get_app_config_dir());
+
+            // 注册 Updater 插件（桌面端）
+            #[cfg(desktop)]
+            {
+                if let Err(e) = app
+                    .handle()
+                    .plugin(tauri_plugin_updater::Builder::new().build())
+                {
+                    // 若配置不完整（如缺少 pubkey），跳过 Updater 而不中断应用
+                    log::warn!("初始化 Updater 插件失败，已跳过：{e}");
+                }
+            }
+            // 初始化日志（Debug 和 Release 模式都启用 Info 级别）
+            // 日志同时输出到控制台和文件（<app_config_dir>/logs/；若设置了覆盖则使用覆盖目录）
+            {
+                use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+                let log_dir = panic_hook::get_log_dir();
+
+                app.handle().plugin(
+                    tauri_plugin_log::Builder::default()
+                        .level(log::LevelFilter::Info)
+                        .targets([
+                            // 输出到控制台
+                            Target::new(TargetKind::Stdout),
+                            // 输出到日志文件
+                            Target::new(TargetKind::Folder {
+                                path: log_dir,
+                                file_name: Some("cc-switch".into()),
+                            }),
+                        ])
+                        .rotation_strategy(RotationStrategy::KeepAll)
+                        .max_file_size(5_000_000) // 5MB 单文件上限
+                        .timezone_strategy(TimezoneStrategy::UseLocal)
+                        // 写入前对 API Key / Token 等密钥脱敏
+                        .format(|out, message, record| {
+                            out.finish(format_args!(
+                                "{}[{}][{}] {}",
+                                chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
+                                record.target(),
+                                record.level(),
+                                crate::redact::redact_secrets(&message.to_string())
+                            ))
+                        })
+                        .build(),
+                )?;
+
+                // 清理旧日志文件，只保留最近 2 个
+                panic_hook::cleanup_old_logs();
+            }
+
+            // 初始化数据库
+            let app_config_dir = crate::config::get_app_config_dir();
+            let db_path = app_config_dir.join("cc-switch.db");
+            let json_path = app_config_dir.join("config.json");
+
+            // 检查是否需要从 config.json 迁移到 SQLite
+            let has_json = json_path.exists();
+            let has_db = db_path.exists();
+
+            // 如果需要迁移，先验证 config.json 是否可以加载（在创建数据库之前）
+            // 这样如果加载失败用户选择退出，数据库文件还没被创建，下次可以正常重试
+            let migration_config = if !has_db && has_json {
+                log::info!("检测到旧版配置文件，验证配置文件...");
+
+                // 循环：支持用户重试加载配置文件
+                loop {
+                    match crate::app_config::MultiAppConfig::load() {
+                        Ok(config) => {
+                            log::info!("✓ 配置文件加载成功");
+                            break Some(config);
+                        }
+                        Err(e) => {
+                            log::error!("加载旧配置文件失败: {e}");
+                            // 弹出系统对话框让用户选择
+                            if !show_migration_error_dialog(app.handle(), &e.to_string()) {
+                                // 用户选择退出（此时数据库还没创建，下次启动可以重试）
+                                log::info!("用户选择退出程序");
+                                std::process::exit(1);
+                            }
+                            // 用户选择重试，继续循环
+                            log::info!("用户选择重试加载配置文件");
+                        }
+                    }
+                }
+            } else {
+                None
+            };
+
+            // 现在创建数据库（包含 Schema 迁移）
+            //
+            // 说明：从 v3.8.* 升级的用户通常会走到这里的 SQLite schema 迁移，
+            // 若迁移失败（数据库损坏/权限不足/user_version 过新等），需要给用户明确提示，
+            // 否则表现可能只是“应用打不开/闪退”。
+            let db = loop {
+                match crate::database::Database::init() {
+                    Ok(db) => break Arc::new(db),
+                    Err(e) => {
+                        log::error!("Failed to init database: {e}");
+
+                        if !show_database_init_error_dialog(app.handle(), &db_path, &e.to_string())
+                        {
+                            log::info!("用户选择退出程序");
+                            std::process::exit(1);
+                        }
+
+                        log::info!("用户选择重试初始化数据库");
+                    }
+                }
+            };
+
+            // 如果有预加载的配置，执行迁移
+            if let Some(config) = migration_config {
+                log::info!("开始执行数据迁移...");
+
+                match db.migrate_from_json(&config) {
+                    Ok(_) => {
+                        log::info!("✓ 配置迁移成功");
+                        // 标记迁移成功，供前端显示 Toast
+                        crate::init_status::set_migration_success();
+                        // 归档旧配置文件（重命名而非删除，便于用户恢复）
+                        let archive_path = json_path.with_extension("json.migrated");
+                        if let Err(e) = std::fs::rename(&json_path, &archive_path) {
+                            log::warn!("归档旧配置文件失败: {e}");
+                        } else {
+                            log::info!("✓ 旧配置已归档为 config.json.migrated");
+                        }
+                    }
+                    Err(e) => {
+                        // 配置加载成功但迁移失败的情况极少（磁盘满等），仅记录日志
+                        log::error!("配置迁移失败: {e}，将从现有配置导入");
+                    }
+                }
+            }
+
+            let app_state = AppState::new(db);
+
+            // 设置 AppHandle 用于代理故障转移时的 UI 更新
+            app_state.proxy_service.set_app_handle(app.handle().clone());
+
+            // 主密码加密的数据库锁定时推迟到解锁后（见 unlock_database 命令）
+            if app_state.db.is_locked() {
+                log::info!("数据库已锁定，启动导入推迟到解锁后");
+            } else {
+                run_startup_imports(&app_state);
+            }
+
+            // 迁移旧的 app_config_dir 配置到 Store
+            if let Err(e) = app_store::migrate_app_config_dir_from_settings(app.handle()) {
+                log::warn!("迁移 app_config_dir 失败: {e}");
+            }
+
+            // 启动阶段不再无条件保存,避免意外覆盖用户配置。
+
+            // 注册 deep-link URL 处理器（使用正确的 DeepLinkExt API）
+            log::info!("=== Registering deep-link URL handler ===");
+
+            // Linux 和 Windows 调试模式需要显式注册
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            {
+                #[cfg(target_os = "linux")]
+                {
+                    // Use Tauri's path API to get correct path (includes app identifier)
+                    // tauri-plugin-deep-link writes to: ~/.local/share/com.ccswitch.desktop/applications/cc-switch-handler.desktop
+                    // Only register if .desktop file doesn't exist to avoid overwriting user customizations
+                    let should_register = app
+                        .path()
+                        .data_dir()
+                        .map(|d| !d.join("applications/cc-switch-handler.desktop").exists())
+                        .unwrap_or(true);
+
+                    if should_register {
+                        if let Err(e) = app.deep_link().register_all() {
+                            log::error!("✗ Failed to register deep link schemes: {}", e);
+                        } else {
+                            log::info!("✓ Deep link schemes registered (Linux)");
+                        }
+                    } else {
+                        log::info!("⊘ Deep link handler already exists, skipping registration");
+                    }
+                }
+
+                #[cfg(all(debug_assertions, windows))]
+                {
+                    if let Err(e) = app.deep_link().register_all() {
+                        log::error!("✗ Failed to register deep link schemes: {}", e);
+                    } else {
+                        log::info!("✓ Deep link schemes registered (Windows debug)");
+                    }
+                }
+            }
+
+            // 注册 URL 处理回调（所有平台通用）
+            app.deep_link().on_open_url({
+                let app_handle = app.handle().clone();
+                move |event| {
+                    log::info!("=== Deep Link Event Received (on_open_url) ===");
+                    let urls = event.urls();
+                    log::info!("Received {} URL(s)", urls.len());
+
+                    for (i, url) in urls.iter().enumerate() {
+                        let url_str = url.as_str();
+                        log::debug!("  URL[{i}]: {}", redact_url_for_log(url_str));
+
+                        if handle_deeplink_url(&app_handle, url_str, true, "on_open_url") {
+                            break; // Process only first ccswitch:// URL
+                        }
+                    }
+                }
+            });
+            log::info!("✓ Deep-link URL handler registered");
+
+            // 创建动态托盘菜单
+            let menu = tray::create_tray_menu(app.handle(), &app_state)?;
+
+            // 构建托盘
+            let mut tray_builder = TrayIconBuilder::with_id("main")
+                .on_tray_icon_event(|_tray, event| match event {
+                    // 左键点击已通过 show_menu_on_left_click(true) 打开菜单，这里不再额外处理
+                    TrayIconEvent::Click { .. } => {}
+                    _ => log::debug!("unhandled event {event:?}"),
+                })
+                .menu(&menu)
+                .on_menu_event(|app, event| {
+                    tray::handle_tray_menu_event(app, &event.id.0);
+                })
+                .show_menu_on_left_click(true);
+
+            // 托盘图标跟随系统深浅色，优先使用 ~/.cc-switch/icons/ 中的自定义图标
+            if let Some((icon, template)) = tray::initial_icon(app.handle()) {
+                tray_builder = tray_builder.icon(icon).icon_as_template(template);
+            } else {
+                log::warn!("Failed to load tray icon");
+            }
+
+            let _tray = tray_builder.build(app)?;
+
+            // 主窗口在配置中默认隐藏，由这里决定是否显示，避免「启动到托盘」时窗口闪现
+            if let Some(window) = app.get_webview_window("main") {
+                if crate::settings::get_settings().start_minimized {
+                    log::info!("启动到托盘，不显示主窗口");
+                    #[cfg(target_os = "windows")]
+                    {
+                        let _ = window.set_skip_taskbar(true);
+                    }
+                    #[cfg(target_os = "macos")]
+                    {
+                        tray::apply_tray_policy(app.handle(), false);
+                    }
+                } else if let Err(e) = window.show() {
+                    log::error!("显示主窗口失败: {e}");
+                }
+            }
+            // 启用静态加密时，后台定期将内存数据库加密写回磁盘
+            crate::database::start_encrypted_flusher(app_state.db.clone());
+            // 将同一个实例注入到全局状态，避免重复创建导致的不一致
+            app.manage(app_state);
+
+            // 首个实例自身的启动参数（深链接、`--switch`）；没有参数时保持原有的启动行为
+            let launch_args = std::env::args().skip(1).collect::<Vec<_>>();
+            if launch_args::parse(&launch_args).is_ok_and(|requests| !requests.is_empty()) {
+                handle_launch_args(app.handle(), &launch_args, "launch args");
+            }
+
+            // 数据库与设置层的变更事件（provider:added、settings:changed 等）
+            crate::change_events::set_app_handle(app.handle().clone());
+
+            // 监听 settings.json 的外部修改（手动编辑/网盘同步）
+            settings_watcher::start(app.handle().clone());
+            live_watcher::start(app.handle().clone());
+            backup_scheduler::start(app.handle().clone());
+            webdav_scheduler::start(app.handle().clone());
+            auto_select::start(app.handle().clone());
+            error_watcher::start(app.handle().clone());
+            uptime_monitor::start(app.handle().clone());
+            rule_engine::start(app.handle().clone());
+            tray::start_menu_watcher(app.handle().clone());
+            services::automation_api::start_if_enabled(app.handle().clone());
+
+            // 初始化 SkillService
+            let skill_service = SkillService::new();
+            app.manage(commands::skill::SkillServiceState(Arc::new(skill_service)));
+
+            // 初始化全局出站代理 HTTP 客户端
+            {
+                let db = &app.state::<AppState>().db;
+                let proxy_url = db.get_global_proxy_url().ok().flatten();
+
+                if let Err(e) = crate::proxy::http_client::init(proxy_url.as_deref()) {
+                    log::error!(
+                        "[GlobalProxy] [GP-005] Failed to initialize with saved config: {e}"
+                    );
+
+                    // 清除无效的代理配置
+                    if proxy_url.is_some() {
+                        log::warn!(
+                            "[GlobalProxy] [GP-006] Clearing invalid proxy config from database"
+                        );
+                        if let Err(clear_err) = db.set_global_proxy_url(None) {
+                            log::error!(
+                                "[GlobalProxy] [GP-007] Failed to clear invalid config: {clear_err}"
+                            );
+                        }
+                    }
+
+                    // 使用直连模式重新初始化
+                    if let Err(fallback_err) = crate::proxy::http_client::init(None) {
+                        log::error!(
+                            "[GlobalProxy] [GP-008] Failed to initialize direct connection: {fallback_err}"
+                        );
+                    }
+                }
+            }
+
+            // 异常退出恢复 + 代理状态自动恢复
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+
+                // 检查是否有 Live 备份（表示上次异常退出时可能处于接管状态）
+                let has_backups = match state.db.has_any_live_backup().await {
+                    Ok(v) => v,
+                    Err(e) => {
+                        log::error!("检查 Live 备份失败: {e}");
+                        false
+                    }
+                };
+                // 检查 Live 配置是否仍处于被接管状态（包含占位符）
+                let live_taken_over = state.proxy_service.detect_takeover_in_live_configs();
+
+                if has_backups || live_taken_over {
+                    log::warn!("检测到上次异常退出（存在接管残留），正在恢复 Live 配置...");
+                    if let Err(e) = state.proxy_service.recover_from_crash().await {
+                        log::error!("恢复 Live 配置失败: {e}");
+                    } else {
+                        log::info!("Live 配置已恢复");
+                    }
+                }
+
+                // 检查 settings 表中的代理状态，自动恢复代理服务
+                restore_proxy_state_on_startup(&state).await;
+            });
+
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            commands::get_providers,
+            commands::list_providers,
+            commands::get_current_provider,
+            commands::add_provider,
+            commands::update_provider,
+            commands::delete_provider,
+            commands::get_provider_history,
+            commands::restore_provider_revision,
+            commands::remove_provider_from_live_config,
+            commands::switch_provider,
+            commands::undo_last_switch,
+            commands::reapply_live_config,
+            commands::launch_app,
+            commands::print_env,
+            commands::write_envrc,
+            commands::import_default_config,
+            commands::adopt_live_config,
+            commands::copy_secret_to_clipboard,
+            commands::reveal_provider_secret,
+            commands::set_provider_locked,
+            commands::rotate_key,
+            commands::get_provider_env_script,
+            commands::scan_live_secrets,
+            commands::vault_live_secrets,
+            commands::get_claude_config_status,
+            commands::get_config_status,
+            commands::get_claude_code_config_path,
+            commands::get_config_dir,
+            commands::detect_wsl_config_dirs,
+            commands::check_config_dir_reachable,
+            commands::open_config_folder,
+            commands::pick_directory,
+            commands::open_external,
+            commands::get_init_error,
+            commands::get_migration_result,
+            commands::get_skills_migration_result,
+            commands::get_recovery_events,
+            commands::get_app_config_path,
+            commands::open_app_config_folder,
+            commands::get_audit_log,
+            commands::search_configs,
+            commands::get_claude_common_config_snippet,
+            commands::set_claude_common_config_snippet,
+            commands::get_common_config_snippet,
+            commands::set_common_config_snippet,
+            commands::extract_common_config_snippet,
+            commands::read_live_provider_settings,
+            commands::get_settings,
+            commands::save_settings,
+            commands::export_settings,
+            commands::import_settings,
+            commands::diagnose_settings,
+            commands::get_status,
+            commands::test_webhook,
+            commands::get_automation_rules,
+            commands::save_automation_rule,
+            commands::delete_automation_rule,
+            commands::run_automation_rule,
+            commands::quick_switch_candidates,
+            commands::execute_quick_switch,
+            commands::set_provider_pinned,
+            commands::get_runtime_state,
+            commands::set_runtime_paused,
+            commands::open_aux_window,
+            commands::get_rectifier_config,
+            commands::set_rectifier_config,
+            commands::restart_app,
+            commands::check_for_updates,
+            commands::is_portable_mode,
+            commands::get_claude_plugin_status,
+            commands::read_claude_plugin_config,
+            commands::apply_claude_plugin_config,
+            commands::is_claude_plugin_applied,
+            commands::apply_claude_onboarding_skip,
+            commands::clear_claude_onboarding_skip,
+            // Claude MCP management
+            commands::get_claude_mcp_status,
+            commands::read_claude_mcp_config,
+            commands::upsert_claude_mcp_server,
+            commands::delete_claude_mcp_server,
+            commands::validate_mcp_command,
+            // usage query
+            commands::queryProviderUsage,
+            commands::get_provider_balance,
+            commands::testUsageScript,
+            // New MCP via config.json (SSOT)
+            commands::get_mcp_config,
+            commands::upsert_mcp_server_in_config,
+            commands::delete_mcp_server_in_config,
+            commands::set_mcp_enabled,
+            // Unified MCP management
+            commands::get_mcp_servers,
+            commands::upsert_mcp_server,
+            commands::delete_mcp_server,
+            commands::toggle_mcp_app,
+            commands::import_mcp_from_apps,
+            // Prompt management
+            commands::get_prompts,
+            commands::upsert_prompt,
+            commands::delete_prompt,
+            commands::enable_prompt,
+            commands::import_prompt_from_file,
+            commands::get_current_prompt_file_content,
+            // ours: endpoint speed test + custom endpoint management
+            commands::test_api_endpoints,
+            commands::get_custom_endpoints,
+            commands::add_custom_endpoint,
+            commands::remove_custom_endpoint,
+            commands::update_endpoint_last_used,
+            // app_config_dir override via Store
+            commands::get_app_config_dir_override,
+            commands::set_app_config_dir_override,
+            // provider sort order management
+            commands::update_providers_sort_order,
+            // theirs: config import/export and dialogs
+            commands::export_config_to_file,
+            commands::import_config_from_file,
+            commands::import_config_with_strategy,
+            commands::apply_batch,
+            commands::import_external_config,
+            commands::export_database,
+            commands::export_bundle,
+            commands::import_bundle,
+            commands::migrate_legacy_config,
+            commands::export_encrypted_config_to_file,
+            commands::import_encrypted_config_from_file,
+            commands::save_file_dialog,
+            commands::open_file_dialog,
+            commands::sync_current_providers_live,
+            // Local backups
+            commands::list_backups,
+            commands::create_backup_now,
+            commands::restore_backup,
+            commands::verify_backup,
+            commands::create_snapshot,
+            commands::list_snapshots,
+            commands::restore_snapshot,
+            commands::delete_snapshot,
+            commands::undo_last_operation,
+            commands::get_database_encryption_status,
+            commands::enable_database_encryption,
+            commands::disable_database_encryption,
+            commands::enable_database_master_password,
+            commands::unlock_database,
+            commands::lock_database,
+            commands::maintain_database,
+            commands::list_database_profiles,
+            commands::switch_profile,
+            commands::get_automation_api_status,
+            commands::set_automation_api_enabled,
+            commands::get_automation_api_token,
+            commands::regenerate_automation_api_token,
+            commands::get_read_only_status,
+            commands::lock_read_only_mode,
+            commands::unlock_read_only_mode,
+            // WebDAV sync
+            commands::webdav_test_connection,
+            commands::webdav_sync_upload,
+            commands::webdav_sync_download,
+            commands::webdav_sync_merge,
+            commands::get_sync_conflicts,
+            commands::resolve_sync_conflict,
+            commands::get_sync_status,
+            // S3 encrypted backup
+            commands::set_s3_backup_credentials,
+            commands::get_s3_backup_credential_status,
+            commands::upload_backup,
+            commands::list_remote_backups,
+            commands::restore_remote_backup,
+            commands::start_lan_share,
+            commands::stop_lan_share,
+            commands::discover_lan_peers,
+            commands::pull_from_lan_peer,
+            // Deep link import
+            commands::parse_deeplink,
+            commands::merge_deeplink_config,
+            commands::lint_deeplink_request,
+            commands::import_from_deeplink,
+            commands::import_from_deeplink_unified,
+            update_tray_menu,
+            // Environment variable management
+            commands::check_env_conflicts,
+            commands::delete_env_vars,
+            commands::restore_env_backup,
+            // Skill management (v3.10.0+ unified)
+            commands::get_installed_skills,
+            commands::install_skill_unified,
+            commands::uninstall_skill_unified,
+            commands::toggle_skill_app,
+            commands::scan_unmanaged_skills,
+            commands::import_skills_from_apps,
+            commands::discover_available_skills,
+            // Skill management (legacy API compatibility)
+            commands::get_skills,
+            commands::get_skills_for_app,
+            commands::install_skill,
+            commands::install_skill_for_app,
+            commands::uninstall_skill,
+            commands::uninstall_skill_for_app,
+            commands::get_skill_repos,
+            commands::add_skill_repo,
+            commands::remove_skill_repo,
+            // Auto launch
+            commands::set_auto_launch,
+            commands::get_auto_launch_status,
+            // Proxy server management
+            commands::start_proxy_server,
+            commands::stop_proxy_with_restore,
+            commands::get_proxy_takeover_status,
+            commands::set_proxy_takeover_for_app,
+            commands::get_proxy_status,
+            commands::get_proxy_config,
+            commands::update_proxy_config,
+            // Global & Per-App Config
+            commands::get_global_proxy_config,
+            commands::update_global_proxy_config,
+            commands::get_proxy_config_for_app,
+            commands::update_proxy_config_for_app,
+            commands::is_proxy_running,
+            commands::is_live_takeover_active,
+            commands::switch_proxy_provider,
+            // Proxy failover commands
+            commands::get_provider_health,
+            commands::reset_circuit_breaker,
+            commands::get_circuit_breaker_config,
+            commands::update_circuit_breaker_config,
+            commands::get_circuit_breaker_stats,
+            // Failover queue management
+            commands::get_failover_queue,
+            commands::get_available_providers_for_failover,
+            commands::add_to_failover_queue,
+            commands::remove_from_failover_queue,
+            commands::get_auto_failover_enabled,
+            commands::set_auto_failover_enabled,
+            // Usage statistics
+            commands::get_usage_summary,
+            commands::get_usage_trends,
+            commands::get_provider_stats,
+            commands::get_model_stats,
+            commands::get_request_logs,
+            commands::get_request_detail,
+            commands::get_model_pricing,
+            commands::update_model_pricing,
+            commands::delete_model_pricing,
+            commands::check_provider_limits,
+            commands::get_usage,
+            commands::get_cost_report,
+            commands::get_usage_dashboard,
+            commands::list_sessions,
+            commands::export_usage_report,
+            commands::get_switch_stats,
+            // Stream health check
+            commands::stream_check_provider,
+            commands::stream_check_all_providers,
+            commands::benchmark_providers,
+            commands::get_provider_benchmarks,
+            commands::validate_key,
+            commands::list_models,
+            commands::get_cached_models,
+            commands::get_provider_uptime,
+            commands::compare_providers,
+            commands::get_stream_check_config,
+            commands::save_stream_check_config,
+            commands::get_tool_versions,
+            // Provider terminal
+            commands::open_provider_terminal,
+            // Universal Provider management
+            commands::get_universal_providers,
+            commands::get_universal_provider,
+            commands::upsert_universal_provider,
+            commands::delete_universal_provider,
+            commands::sync_universal_provider,
+            // OpenCode specific
+            commands::import_opencode_providers_from_live,
+            commands::get_opencode_live_provider_ids,
+            // Global upstream proxy
+            commands::get_global_proxy_url,
+            commands::set_global_proxy_url,
+            commands::test_proxy_url,
+            commands::get_upstream_proxy_status,
+            commands::scan_local_proxies,
+        ]);
+
+    let app = builder
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application");
+
+    app.run(|app_handle, event| {
+        // 处理退出请求（所有平台）
+        if let RunEvent::ExitRequested { api, .. } = &event {
+            log::info!("收到退出请求，开始清理...");
+            // 阻止立即退出，执行清理
+            api.prevent_exit();
+
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move {
+                cleanup_before_exit(&app_handle).await;
+                if let Some(state) = app_handle.try_state::<store::AppState>() {
+                    if let Err(e) = state.db.flush_encrypted(false) {
+                        log::error!("退出时写回加密数据库失败: {e}");
+                    }
+                }
+                log::info!("清理完成，退出应用");
+
+                // 短暂等待确保所有 I/O 操作（如数据库写入）刷新到磁盘
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+                // 使用 std::process::exit 避免再次触发 ExitRequested
+                std::process::exit(0);
+            });
+            return;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            match event {
+                // macOS 在 Dock 图标被点击并重新激活应用时会触发 Reopen 事件，这里手动恢复主窗口
+                RunEvent::Reopen { .. } => {
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        #[cfg(target_os = "windows")]
+                        {
+                            let _ = window.set_skip_taskbar(false);
+                        }
+                        let _ = window.unminimize();
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                        tray::apply_tray_policy(app_handle, true);
+                    }
+                }
+                // 处理通过自定义 URL 协议触发的打开事件（例如 ccswitch://...）
+                RunEvent::Opened { urls } => {
+                    if let Some(url) = urls.first() {
+                        let url_str = url.to_string();
+                        log::info!("RunEvent::Opened with URL: {url_str}");
+                        // 复用与 single_instance / on_open_url 相同的处理逻辑（含切换类链接）
+                        handle_deeplink_url(app_handle, &url_str, true, "RunEvent::Opened");
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = (app_handle, event);
+        }
+    });
+}
+
+// ============================================================
+// 应用退出清理
+// ============================================================
+
+/// 应用退出前的清理工作
+///
+/// 在应用退出前检查代理服务器状态，如果正在运行则停止代理并恢复 Live 配置。
+/// 确保 Claude Code/Codex/Gemini 的配置不会处于损坏状态。
+/// 使用 stop_with_restore_keep_state 保留 settings 表中的代理状态，下次启动时自动恢复。
+pub async fn cleanup_before_exit(app_handle: &tauri::AppHandle) {
+    if let Some(state) = app_handle.try_state::<store::AppState>() {
+        let proxy_service = &state.proxy_service;
+
+        // 退出时也需要兜底：代理可能已崩溃/未运行，但 Live 接管残留仍在（占位符/备份）。
+        let has_backups = match state.db.has_any_live_backup().await {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("退出时检查 Live 备份失败: {e}");
+                false
+            }
+        };
+        let live_taken_over = proxy_service.detect_takeover_in_live_configs();
+        let needs_restore = has_backups || live_taken_over;
+
+        if needs_restore {
+            log::info!("检测到接管残留，开始恢复 Live 配置（保留代理状态）...");
+            // 使用 keep_state 版本，保留 settings 表中的代理状态
+            if let Err(e) = proxy_service.stop_with_restore_keep_state().await {
+                log::error!("退出时恢复 Live 配置失败: {e}");
+            } else {
+                log::info!("已恢复 Live 配置（代理状态已保留，下次启动将自动恢复）");
+            }
+            return;
+        }
+
+        // 非接管模式：代理在运行则仅停止代理
+        if proxy_service.is_running().await {
+            log::info!("检测到代理服务器正在运行，开始停止...");
+            if let Err(e) = proxy_service.stop().await {
+                log::error!("退出时停止代理失败: {e}");
+            }
+            log::info!("代理服务器清理完成");
+        }
+    }
+}
+
+// ============================================================
+// 启动时恢复代理状态
+// ============================================================
+
+/// 启动时根据 proxy_config 表中的代理状态自动恢复代理服务
+///
+/// 检查 `proxy_config.enabled` 字段，如果有任一应用的状态为 `true`，
+/// 则自动启动代理服务并接管对应应用的 Live 配置。
+/// 启动时从各应用的 live 配置导入数据（各类数据独立判断，已有数据时跳过，可重复执行）
+pub(crate) fn run_startup_imports(app_state: &AppState) {
+    // ============================================================
+    // 按表独立判断的导入逻辑（各类数据独立检查，互不影响）
+    // ============================================================
+
+    // 1. 初始化默认 Skills 仓库（已有内置检查：表非空则跳过）
+    match app_state.db.init_default_skill_repos() {
+        Ok(count) if count > 0 => {
+            log::info!("✓ Initialized {count} default skill repositories");
+        }
+        Ok(_) => {} // 表非空，静默跳过
+        Err(e) => log::warn!("✗ Failed to initialize default skill repos: {e}"),
+    }
+
+    // 1.1. Skills 统一管理迁移：当数据库迁移到 v3 结构后，自动从各应用目录导入到 SSOT
+    // 触发条件由 schema 迁移设置 settings.skills_ssot_migration_pending = true 控制。
+    match app_state.db.get_setting("skills_ssot_migration_pending") {
+        Ok(Some(flag)) if flag == "true" || flag == "1" => {
+            // 安全保护：如果用户已经有 v3 结构的 Skills 数据，就不要自动清空重建。
+            let has_existing = app_state
+                .db
+                .get_all_installed_skills()
+                .map(|skills| !skills.is_empty())
+                .unwrap_or(false);
+
+            if has_existing {
+                log::info!(
+                    "Detected skills_ssot_migration_pending but skills table not empty; skipping auto import."
+                );
+                let _ = app_state
+                    .db
+                    .set_setting("skills_ssot_migration_pending", "false");
+            } else {
+                match crate::services::skill::migrate_skills_to_ssot(&app_state.db) {
+                    Ok(count) => {
+                        log::info!("✓ Auto imported {count} skill(s) into SSOT");
+                        if count > 0 {
+                            crate::init_status::set_skills_migration_result(count);
+                        }
+                        let _ = app_state
+                            .db
+                            .set_setting("skills_ssot_migration_pending", "false");
+                    }
+                    Err(e) => {
+                        log::warn!("✗ Failed to auto import legacy skills to SSOT: {e}");
+                        crate::init_status::set_skills_migration_error(e.to_string());
+                        // 保留 pending 标志，方便下次启动重试
+                    }
+                }
+            }
+        }
+        Ok(_) => {} // 未开启迁移标志，静默跳过
+        Err(e) => log::warn!("✗ Failed to read skills migration flag: {e}"),
+    }
+
+    // 2. 导入供应商配置（已有内置检查：该应用已有供应商则跳过）
+    for app in [
+        crate::app_config::AppType::Claude,
+        crate::app_config::AppType::Codex,
+        crate::app_config::AppType::Gemini,
+    ] {
+        match crate::services::provider::ProviderService::import_default_config(
+            &app_state,
+            app.clone(),
+        ) {
+            Ok(true) => {
+                log::info!("✓ Imported default provider for {}", app.as_str());
+
+                // 首次运行：自动提取通用配置片段（仅当通用配置为空时）
+                if app_state
+                    .db
+                    .get_config_snippet(app.as_str())
+                    .ok()
+                    .flatten()
+                    .is_none()
+                {
+                    match crate::services::provider::ProviderService::extract_common_config_snippet(
+                        &app_state,
+                        app.clone(),
+                    ) {
+                        Ok(snippet) if !snippet.is_empty() && snippet != "{}" => {
+                            if let Err(e) =
+                                app_state.db.set_config_snippet(app.as_str(), Some(snippet))
+                            {
+                                log::warn!(
+                                    "✗ Failed to save common config snippet for {}: {e}",
+                                    app.as_str()
+                                );
+                            } else {
+                                log::info!(
+                                    "✓ Extracted common config snippet for {}",
+                                    app.as_str()
+                                );
+                            }
+                        }
+                        Ok(_) => log::debug!("○ No common config to extract for {}", app.as_str()),
+                        Err(e) => log::debug!(
+                            "○ Failed to extract common config for {}: {e}",
+                            app.as_str()
+                        ),
+                    }
+                }
+            }
+            Ok(false) => {} // 已有供应商，静默跳过
+            Err(e) => {
+                log::debug!(
+                    "○ No default provider to import for {}: {}",
+                    app.as_str(),
+                    e
+                );
+            }
+        }
+    }
+
+    // 2.1 OpenCode 供应商导入（累加式模式，需特殊处理）
+    // OpenCode 与其他应用不同：配置文件中可同时存在多个供应商
+    // 需要遍历 provider 字段下的每个供应商并导入
+    match crate::services::provider::import_opencode_providers_from_live(&app_state) {
+        Ok(count) if count > 0 => {
+            log::info!("✓ Imported {count} OpenCode provider(s) from live config");
+        }
+        Ok(_) => log::debug!("○ No OpenCode providers found to import"),
+        Err(e) => log::debug!("○ Failed to import OpenCode providers: {e}"),
+    }
+
+    // 3. 导入 MCP 服务器配置（表空时触发）
+    if app_state.db.is_mcp_table_empty().unwrap_or(false) {
+        log::info!("MCP table empty, importing from live configurations...");
+
+        match crate::services::mcp::McpService::import_from_claude(&app_state) {
+            Ok(count) if count > 0 => {
+                log::info!("✓ Imported {count} MCP server(s) from Claude");
+            }
+            Ok(_) => log::debug!("○ No Claude MCP servers found to import"),
+            Err(e) => log::warn!("✗ Failed to import Claude MCP: {e}"),
+        }
+
+        match crate::services::mcp::McpService::import_from_codex(&app_state) {
+            Ok(count) if count > 0 => {
+                log::info!("✓ Imported {count} MCP server(s) from Codex");
+            }
+            Ok(_) => log::debug!("○ No Codex MCP servers found to import"),
+            Err(e) => log::warn!("✗ Failed to import Codex MCP: {e}"),
+        }
+
+        match crate::services::mcp::McpService::import_from_gemini(&app_state) {
+            Ok(count) if count > 0 => {
+                log::info!("✓ Imported {count} MCP server(s) from Gemini");
+            }
+            Ok(_) => log::debug!("○ No Gemini MCP servers found to import"),
+            Err(e) => log::warn!("✗ Failed to import Gemini MCP: {e}"),
+        }
+
+        match crate::services::mcp::McpService::import_from_opencode(&app_state) {
+            Ok(count) if count > 0 => {
+                log::info!("✓ Imported {count} MCP server(s) from OpenCode");
+            }
+            Ok(_) => log::debug!("○ No OpenCode MCP servers found to import"),
+            Err(e) => log::warn!("✗ Failed to import OpenCode MCP: {e}"),
+        }
+    }
+
+    // 4. 导入提示词文件（表空时触发）
+    if app_state.db.is_prompts_table_empty().unwrap_or(false) {
+        log::info!("Prompts table empty, importing from live configurations...");
+
+        for app in [
+            crate::app_config::AppType::Claude,
+            crate::app_config::AppType::Codex,
+            crate::app_config::AppType::Gemini,
+        ] {
+            match crate::services::prompt::PromptService::import_from_file_on_first_launch(
+                &app_state,
+                app.clone(),
+            ) {
+                Ok(count) if count > 0 => {
+                    log::info!("✓ Imported {count} prompt(s) for {}", app.as_str());
+                }
+                Ok(_) => log::debug!("○ No prompt file found for {}", app.as_str()),
+                Err(e) => log::warn!("✗ Failed to import prompt for {}: {e}", app.as_str()),
+            }
+        }
+    }
+}
+
+async fn restore_proxy_state_on_startup(state: &store::AppState) {
+    // 收集需要恢复接管的应用列表（从 proxy_config.enabled 读取）
+    let mut apps_to_restore = Vec::new();
+    for app_type in ["claude", "codex", "gemini"] {
+        if let Ok(config) = state.db.get_proxy_config_for_app(app_type).await {
+            if config.enabled {
+                apps_to_restore.push(app_type);
+            }
+        }
+    }
+
+    if apps_to_restore.is_empty() {
+        log::debug!("启动时无需恢复代理状态");
+        return;
+    }
+
+    log::info!("检测到上次代理状态需要恢复，应用列表: {apps_to_restore:?}");
+
+    // 逐个恢复接管状态
+    for app_type in apps_to_restore {
+        match state
+            .proxy_service
+            .set_takeover_for_app(app_type, true)
+            .await
+        {
+            Ok(()) => {
+                log::info!("✓ 已恢复 {app_type} 的代理接管状态");
+            }
+            Err(e) => {
+                log::error!("✗ 恢复 {app_type} 的代理接管状态失败: {e}");
+                // 失败时清除该应用的状态，避免下次启动再次尝试
+                if let Err(clear_err) = state
+                    .proxy_service
+                    .set_takeover_for_app(app_type, false)
+                    .await
+                {
+                    log::error!("清除 {app_type} 代理状态失败: {clear_err}");
+                }
+            }
+        }
+    }
+}
+
+// ============================================================
+// 迁移错误对话框辅助函数
+// ============================================================
+
+/// 检测是否为中文环境
+fn is_chinese_locale() -> bool {
+    std::env::var("LANG")
+        .or_else(|_| std::env::var("LC_ALL"))
+        .or_else(|_| std::env::var("LC_MESSAGES"))
+        .map(|lang| lang.starts_with("zh"))
+        .unwrap_or(false)
+}
+
+/// 显示迁移错误对话框
+/// 返回 true 表示用户选择重试，false 表示用户选择退出
+fn show_migration_error_dialog(app: &tauri::AppHandle, error: &str) -> bool {
+    let title = if is_chinese_locale() {
+        "配置迁移失败"
+    } else {
+        "Migration Failed"
+    };
+
+    let message = if is_chinese_locale() {
+        format!(
+            "从旧版本迁移配置时发生错误：\n\n{error}\n\n\
+            您的数据尚未丢失，旧配置文件仍然保留。\n\
+            建议回退到旧版本 CC Switch 以保护数据。\n\n\
+            点击「重试」重新尝试迁移\n\
+            点击「退出」关闭程序（可回退版本后重新打开）"
+        )
+    } else {
+        format!(
+            "An error occurred while migrating configuration:\n\n{error}\n\n\
+            Your data is NOT lost - the old config file is still preserved.\n\
+            Consider rolling back to an older CC Switch version.\n\n\
+            Click 'Retry' to attempt migration again\n\
+            Click 'Exit' to close the program"
+        )
+    };
+
+    let retry_text = if is_chinese_locale() {
+        "重试"
+    } else {
+        "Retry"
+    };
+    let exit_text = if is_chinese_locale() {
+        "退出"
+    } else {
+        "Exit"
+    };
+
+    // 使用 blocking_show 同步等待用户响应
+    // OkCancelCustom: 第一个按钮（重试）返回 true，第二个按钮（退出）返回 false
+    app.dialog()
+        .message(&message)
+        .title(title)
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            retry_text.to_string(),
+            exit_text.to_string(),
+        ))
+        .blocking_show()
+}
+
+/// 显示数据库初始化/Schema 迁移失败对话框
+/// 返回 true 表示用户选择重试，false 表示用户选择退出
+fn show_database_init_error_dialog(
+    app: &tauri::AppHandle,
+    db_path: &std::path::Path,
+    error: &str,
+) -> bool {
+    let title = if is_chinese_locale() {
+        "数据库初始化失败"
+    } else {
+        "Database Initialization Failed"
+    };
+
+    let message = if is_chinese_locale() {
+        format!(
+            "初始化数据库或迁移数据库结构时发生错误：\n\n{error}\n\n\
+            数据库文件路径：\n{db}\n\n\
+            您的数据尚未丢失，应用不会自动删除数据库文件。\n\
+            常见原因包括：数据库版本过新、文件损坏、权限不足、磁盘空间不足等。\n\n\
+            建议：\n\
+            1) 先备份整个配置目录（包含 cc-switch.db）\n\
+            2) 如果提示“数据库版本过新”，请升级到更新版本\n\
+            3) 如果刚升级出现异常，可回退旧版本导出/备份后再升级\n\n\
+            点击「重试」重新尝试初始化\n\
+            点击「退出」关闭程序",
+            db = db_path.display()
+        )
+    } else {
+        format!(
+            "An error occurred while initializing or migrating the database:\n\n{error}\n\n\
+            Database file path:\n{db}\n\n\
+            Your data is NOT lost - the app will not delete the database automatically.\n\
+            Common causes include: newer database version, corrupted file, permission issues, or low disk space.\n\n\
+            Suggestions:\n\
+            1) Back up the entire config directory (including cc-switch.db)\n\
+            2) If you see “database version is newer”, please upgrade CC Switch\n\
+            3) If this happened right after upgrading, consider rolling back to export/backup then upgrade again\n\n\
+            Click 'Retry' to attempt initialization again\n\
+            Click 'Exit' to close the program",
+            db = db_path.display()
+        )
+    };
+
+    let retry_text = if is_chinese_locale() {
+        "重试"
+    } else {
+        "Retry"
+    };
+    let exit_text = if is_chinese_locale() {
+        "退出"
+    } else {
+        "Exit"
+    };
+
+    app.dialog()
+        .message(&message)
+        .title(title)
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            retry_text.to_string(),
+            exit_text.to_string(),
+        ))
+        .blocking_show()
+}
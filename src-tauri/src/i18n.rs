@@ -0,0 +1,220 @@
+//! 后端界面文本
+//!
+//! 托盘菜单、系统通知与辅助窗口标题等由后端渲染的文本集中在这里，按设置 `language`
+//! （`zh` / `en` / `ja`，缺省为中文）取值。文本可以包含 `{name}` 形式的占位符，由
+//! [`format`] 替换。
+//!
+//! 托盘菜单的内容指纹包含语言，切换语言后菜单、提示文字随之重建；通知在下次发送时
+//! 使用新语言。
+
+use crate::settings;
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    Zh,
+    En,
+    Ja,
+}
+
+impl Language {
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "en" => Language::En,
+            "ja" => Language::Ja,
+            _ => Language::Zh,
+        }
+    }
+
+    /// 设置中的语言
+    pub fn current() -> Self {
+        settings::get_settings()
+            .language
+            .as_deref()
+            .map(Self::from_code)
+            .unwrap_or_default()
+    }
+}
+
+/// (键, 中文, English, 日本語)
+const TEXTS: &[(&str, &str, &str, &str)] = &[
+    // 托盘菜单
+    (
+        "tray.showMain",
+        "打开主界面",
+        "Open main window",
+        "メインウィンドウを開く",
+    ),
+    ("tray.recent", "最近使用", "Recent", "最近使用"),
+    (
+        "tray.noProviderHint",
+        "(无供应商，请在主界面添加)",
+        "(No providers yet, please add them from the main window)",
+        "(プロバイダーがまだありません。メイン画面から追加してください)",
+    ),
+    (
+        "tray.pause",
+        "暂停 CC Switch",
+        "Pause CC Switch",
+        "CC Switch を一時停止",
+    ),
+    ("tray.paused", "已暂停", "Paused", "一時停止中"),
+    ("tray.lastCheck", "最近检查", "Last check", "最終チェック"),
+    (
+        "tray.configDirOverrides",
+        "使用自定义配置目录",
+        "Use custom config directories",
+        "カスタム設定ディレクトリを使用",
+    ),
+    (
+        "tray.syncBothConfigDirs",
+        "切换时同步到两个目录",
+        "Sync switches to both directories",
+        "切り替えを両方のディレクトリに同期",
+    ),
+    ("tray.quit", "退出", "Quit", "終了"),
+    // 系统通知
+    (
+        "notification.switchSucceeded",
+        "{app} 已切换到 {provider}",
+        "{app} switched to {provider}",
+        "{app} を {provider} に切り替えました",
+    ),
+    (
+        "notification.switchFailed",
+        "{app} 切换失败: {error}",
+        "Failed to switch {app}: {error}",
+        "{app} の切り替えに失敗しました: {error}",
+    ),
+    (
+        "notification.failover",
+        "{app} 已故障转移到 {provider}",
+        "{app} failed over to {provider}",
+        "{app} は {provider} にフェイルオーバーしました",
+    ),
+    (
+        "notification.autoSelected",
+        "{app} 已自动切换到更快的 {provider}（首字节 {ms} ms）",
+        "{app} automatically switched to the faster {provider} ({ms} ms to first byte)",
+        "{app} をより高速な {provider} に自動で切り替えました（最初のバイトまで {ms} ms）",
+    ),
+    (
+        "notification.backupCreated",
+        "已创建备份: {id}",
+        "Backup created: {id}",
+        "バックアップを作成しました: {id}",
+    ),
+    (
+        "notification.backupFailed",
+        "备份失败: {error}",
+        "Backup failed: {error}",
+        "バックアップに失敗しました: {error}",
+    ),
+    (
+        "notification.healthCheckFailed",
+        "{app} 供应商 {provider} 健康检查失败: {detail}",
+        "{app} provider {provider} failed health check: {detail}",
+        "{app} のプロバイダー {provider} のヘルスチェックに失敗しました: {detail}",
+    ),
+    (
+        "notification.errorBurst",
+        "{app} 供应商 {provider} 在 {minutes} 分钟内出现 {count} 次限流或服务错误",
+        "{app} provider {provider} returned {count} rate-limit or server errors in {minutes} minutes",
+        "{app} のプロバイダー {provider} で {minutes} 分間に {count} 件のレート制限またはサーバーエラーが発生しました",
+    ),
+    (
+        "notification.errorBurstSuggest",
+        "{app} 供应商 {provider} 在 {minutes} 分钟内出现 {count} 次限流或服务错误，建议切换到 {next}",
+        "{app} provider {provider} returned {count} rate-limit or server errors in {minutes} minutes; consider switching to {next}",
+        "{app} のプロバイダー {provider} で {minutes} 分間に {count} 件のレート制限またはサーバーエラーが発生しました。{next} への切り替えをおすすめします",
+    ),
+    // 辅助窗口标题
+    (
+        "window.mcp",
+        "CC Switch · MCP",
+        "CC Switch · MCP",
+        "CC Switch · MCP",
+    ),
+    (
+        "window.logs",
+        "CC Switch · 请求日志",
+        "CC Switch · Request Logs",
+        "CC Switch · リクエストログ",
+    ),
+    (
+        "window.usage",
+        "CC Switch · 用量统计",
+        "CC Switch · Usage",
+        "CC Switch · 使用量",
+    ),
+    // 错误提示
+    (
+        "error.invalidJson",
+        "无效的 JSON 格式: {error}",
+        "Invalid JSON format: {error}",
+        "JSON形式が無効です: {error}",
+    ),
+];
+
+/// 取指定语言的文本；缺少的键原样返回
+pub fn text(language: Language, key: &'static str) -> &'static str {
+    let Some(&(_, zh, en, ja)) = TEXTS.iter().find(|(k, ..)| *k == key) else {
+        log::warn!("缺少界面文本: {key}");
+        return key;
+    };
+    match language {
+        Language::Zh => zh,
+        Language::En => en,
+        Language::Ja => ja,
+    }
+}
+
+/// 取文本并替换 `{name}` 占位符
+pub fn format(language: Language, key: &'static str, args: &[(&str, &str)]) -> String {
+    args.iter()
+        .fold(text(language, key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn keys_are_unique_and_translations_share_placeholders() {
+        for (index, (key, zh, en, ja)) in TEXTS.iter().enumerate() {
+            assert!(
+                TEXTS[index + 1..].iter().all(|(other, ..)| other != key),
+                "duplicate key {key}"
+            );
+            assert_eq!(placeholders(zh), placeholders(en), "{key}");
+            assert_eq!(placeholders(zh), placeholders(ja), "{key}");
+        }
+    }
+
+    #[test]
+    fn formats_with_fallbacks() {
+        assert_eq!(
+            format(
+                Language::En,
+                "notification.failover",
+                &[("app", "Codex"), ("provider", "Relay")]
+            ),
+            "Codex failed over to Relay"
+        );
+        assert_eq!(Language::from_code("fr"), Language::Zh);
+        assert_eq!(text(Language::Ja, "tray.missing"), "tray.missing");
+    }
+}
@@ -21,6 +21,7 @@ pub fn set_init_error(payload: InitErrorPayload) {
     }
 }
 
+#[cfg(any(feature = "gui", test))]
 pub fn get_init_error() -> Option<InitErrorPayload> {
     cell().read().ok()?.clone()
 }
@@ -29,12 +30,15 @@ pub fn get_init_error() -> Option<InitErrorPayload> {
 // 迁移结果状态
 // ============================================================
 
+#[cfg(feature = "gui")]
 static MIGRATION_SUCCESS: OnceLock<RwLock<bool>> = OnceLock::new();
 
+#[cfg(feature = "gui")]
 fn migration_cell() -> &'static RwLock<bool> {
     MIGRATION_SUCCESS.get_or_init(|| RwLock::new(false))
 }
 
+#[cfg(feature = "gui")]
 pub fn set_migration_success() {
     if let Ok(mut guard) = migration_cell().write() {
         *guard = true;
@@ -42,6 +46,7 @@ pub fn set_migration_success() {
 }
 
 /// 获取并消费迁移成功状态（只返回一次 true，之后返回 false）
+#[cfg(feature = "gui")]
 pub fn take_migration_success() -> bool {
     if let Ok(mut guard) = migration_cell().write() {
         let val = *guard;
@@ -56,6 +61,7 @@ pub fn take_migration_success() -> bool {
 // Skills SSOT 迁移结果状态
 // ============================================================
 
+#[cfg(feature = "gui")]
 #[derive(Debug, Clone, Serialize)]
 pub struct SkillsMigrationPayload {
     pub count: usize,
@@ -63,18 +69,22 @@ pub struct SkillsMigrationPayload {
     pub error: Option<String>,
 }
 
+#[cfg(feature = "gui")]
 static SKILLS_MIGRATION_RESULT: OnceLock<RwLock<Option<SkillsMigrationPayload>>> = OnceLock::new();
 
+#[cfg(feature = "gui")]
 fn skills_migration_cell() -> &'static RwLock<Option<SkillsMigrationPayload>> {
     SKILLS_MIGRATION_RESULT.get_or_init(|| RwLock::new(None))
 }
 
+#[cfg(feature = "gui")]
 pub fn set_skills_migration_result(count: usize) {
     if let Ok(mut guard) = skills_migration_cell().write() {
         *guard = Some(SkillsMigrationPayload { count, error: None });
     }
 }
 
+#[cfg(feature = "gui")]
 pub fn set_skills_migration_error(error: String) {
     if let Ok(mut guard) = skills_migration_cell().write() {
         *guard = Some(SkillsMigrationPayload {
@@ -85,6 +95,7 @@ pub fn set_skills_migration_error(error: String) {
 }
 
 /// 获取并消费 Skills 迁移结果（只返回一次 Some，之后返回 None）
+#[cfg(feature = "gui")]
 pub fn take_skills_migration_result() -> Option<SkillsMigrationPayload> {
     if let Ok(mut guard) = skills_migration_cell().write() {
         guard.take()
@@ -93,6 +104,47 @@ pub fn take_skills_migration_result() -> Option<SkillsMigrationPayload> {
     }
 }
 
+// ============================================================
+// 损坏文件恢复结果
+// ============================================================
+
+/// 启动时发现文件损坏并自动恢复的记录
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryPayload {
+    /// `database` 或 `settings`
+    pub kind: String,
+    pub path: String,
+    /// 损坏文件被移动到的位置
+    pub quarantined_path: String,
+    /// 恢复所用的备份；为空表示没有可用的备份，已使用空数据库或默认设置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_from: Option<String>,
+    pub error: String,
+}
+
+static RECOVERIES: OnceLock<RwLock<Vec<RecoveryPayload>>> = OnceLock::new();
+
+fn recoveries_cell() -> &'static RwLock<Vec<RecoveryPayload>> {
+    RECOVERIES.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+pub fn push_recovery(payload: RecoveryPayload) {
+    if let Ok(mut guard) = recoveries_cell().write() {
+        guard.push(payload);
+    }
+}
+
+/// 获取并消费损坏文件恢复记录（只返回一次）
+#[cfg(any(feature = "gui", test))]
+pub fn take_recoveries() -> Vec<RecoveryPayload> {
+    if let Ok(mut guard) = recoveries_cell().write() {
+        std::mem::take(&mut *guard)
+    } else {
+        Vec::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,96 @@
+//! 启动参数
+//!
+//! 再次启动 CC Switch 时，单实例插件把新进程的参数转交给已运行的实例，新进程随即退出，
+//! 避免两个实例同时读写 `settings.json` 与数据库。首个实例也用同样的规则处理自己的
+//! 启动参数。支持的参数：
+//!
+//! - `ccswitch://...` 深链接（只处理第一个）；
+//! - `--switch <app> <provider>`：切换供应商（不适用于 OpenCode），在后台完成，不打开主界面。
+//!
+//! 其他参数忽略（如系统附加的参数）。
+
+use std::str::FromStr;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 启动参数中的请求
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchRequest {
+    DeepLink(String),
+    Switch {
+        app_type: AppType,
+        provider_id: String,
+    },
+}
+
+fn parse_switch_app(value: &str) -> Result<AppType, AppError> {
+    let app_type = AppType::from_str(value)
+        .map_err(|_| AppError::InvalidInput(format!("--switch: 未知的应用 {value}")))?;
+    if app_type == AppType::OpenCode {
+        return Err(AppError::InvalidInput(
+            "--switch: OpenCode 为累加模式，不支持切换".to_string(),
+        ));
+    }
+    Ok(app_type)
+}
+
+/// 解析启动参数（不含程序路径）
+pub fn parse<S: AsRef<str>>(args: &[S]) -> Result<Vec<LaunchRequest>, AppError> {
+    let mut requests = Vec::new();
+    let mut has_deeplink = false;
+    let mut args = args.iter().map(AsRef::as_ref);
+    while let Some(arg) = args.next() {
+        if arg == "--switch" {
+            let (Some(app), Some(provider_id)) = (args.next(), args.next()) else {
+                return Err(AppError::InvalidInput(
+                    "--switch 需要 <app> <provider> 两个参数".to_string(),
+                ));
+            };
+            requests.push(LaunchRequest::Switch {
+                app_type: parse_switch_app(app)?,
+                provider_id: provider_id.to_string(),
+            });
+        } else if arg.starts_with("ccswitch://") && !has_deeplink {
+            has_deeplink = true;
+            requests.push(LaunchRequest::DeepLink(arg.to_string()));
+        }
+    }
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_deeplinks_and_switch_requests() {
+        let requests = parse(&[
+            "--hidden",
+            "ccswitch://v1/import?resource=provider",
+            "ccswitch://v1/import?resource=mcp",
+            "--switch",
+            "codex",
+            "my-relay",
+        ])
+        .unwrap();
+        assert_eq!(
+            requests,
+            vec![
+                LaunchRequest::DeepLink("ccswitch://v1/import?resource=provider".to_string()),
+                LaunchRequest::Switch {
+                    app_type: AppType::Codex,
+                    provider_id: "my-relay".to_string(),
+                },
+            ]
+        );
+        assert!(parse::<&str>(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_switch_requests() {
+        assert!(parse(&["--switch", "claude"]).is_err());
+        assert!(parse(&["--switch", "opencode", "relay"]).is_err());
+        assert!(parse(&["--switch", "unknown", "relay"]).is_err());
+    }
+}
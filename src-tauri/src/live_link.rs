@@ -0,0 +1,138 @@
+//! Live 配置软链接模式
+//!
+//! 启用 `live_config_symlink` 后，切换供应商时不再复制文件内容，而是将内容写入
+//! `~/.cc-switch/live/<app>/<provider>/<file>`，再让 live 路径（如 `~/.codex/auth.json`）
+//! 以软链接指向该文件。这样外部工具对 live 配置的修改可以追溯到对应的供应商。
+//!
+//! 文件系统或操作系统不支持软链接时（如 Windows 未开启开发者模式），自动回退为复制。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::config::{atomic_write, get_app_config_dir, sanitize_provider_name};
+use crate::error::AppError;
+
+/// 软链接目标文件的存储目录
+pub fn get_live_store_dir() -> PathBuf {
+    get_app_config_dir().join("live")
+}
+
+/// 计算某个供应商 live 文件在存储目录中的位置
+pub fn store_path_for(app_type: &AppType, provider_id: &str, live_path: &Path) -> PathBuf {
+    let file_name = live_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "config".to_string());
+    get_live_store_dir()
+        .join(app_type.as_str())
+        .join(sanitize_provider_name(provider_id))
+        .join(file_name)
+}
+
+/// 判断 live 路径是否为指向本应用存储目录的软链接
+pub fn is_managed_link(live_path: &Path) -> bool {
+    let Ok(meta) = fs::symlink_metadata(live_path) else {
+        return false;
+    };
+    if !meta.file_type().is_symlink() {
+        return false;
+    }
+    let Ok(target) = fs::read_link(live_path) else {
+        return false;
+    };
+    let store = get_live_store_dir();
+    target.starts_with(&store)
+        || fs::canonicalize(&store)
+            .map(|s| target.starts_with(s))
+            .unwrap_or(false)
+}
+
+/// 写入供应商的 live 文件
+///
+/// - 未启用软链接模式：原子写入 live 路径（若之前是本应用创建的软链接，先移除）
+/// - 启用软链接模式：写入存储文件并让 live 路径指向它，失败时回退为复制
+pub fn write_live_file(
+    app_type: &AppType,
+    provider_id: &str,
+    live_path: &Path,
+    data: &[u8],
+) -> Result<(), AppError> {
+    if !crate::settings::get_settings().live_config_symlink {
+        if is_managed_link(live_path) {
+            fs::remove_file(live_path).map_err(|e| AppError::io(live_path, e))?;
+        }
+        return atomic_write(live_path, data);
+    }
+
+    let store_path = store_path_for(app_type, provider_id, live_path);
+    if is_managed_link(live_path) {
+        // 先移除旧链接，避免 atomic_write 顺着旧链接写入其他供应商的存储文件
+        fs::remove_file(live_path).map_err(|e| AppError::io(live_path, e))?;
+    }
+    atomic_write(&store_path, data)?;
+
+    match replace_with_symlink(&store_path, live_path) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            log::warn!(
+                "创建软链接失败，回退为复制: {} -> {}: {e}",
+                live_path.display(),
+                store_path.display()
+            );
+            atomic_write(live_path, data)
+        }
+    }
+}
+
+/// 在 live 路径旁创建临时软链接，再 rename 覆盖，避免出现 live 文件缺失的窗口期
+fn replace_with_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let parent = link
+        .parent()
+        .ok_or_else(|| std::io::Error::other("invalid live path"))?;
+    fs::create_dir_all(parent)?;
+
+    let file_name = link
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("invalid live path"))?
+        .to_string_lossy()
+        .to_string();
+    let ts = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let tmp_link = parent.join(format!("{file_name}.link.{ts}"));
+
+    create_symlink(target, &tmp_link)?;
+
+    #[cfg(windows)]
+    {
+        if link.exists() {
+            let _ = fs::remove_file(link);
+        }
+    }
+
+    if let Err(e) = fs::rename(&tmp_link, link) {
+        let _ = fs::remove_file(&tmp_link);
+        return Err(e);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn create_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
@@ -0,0 +1,161 @@
+//! live 配置外部改写监听
+//!
+//! 部分 CLI 在登录或升级时会改写自己的配置文件，覆盖 CC Switch 写入的端点与密钥。
+//! 开启 `live_config_guard` 后定时检查各应用 live 文件的修改时间，变化时用
+//! [`status::detect_drift`] 比较当前供应商，发现不一致则按设置自动重新应用，或通过
+//! `live-config-drift` 事件通知前端，由用户一键重新应用。
+//!
+//! 代理接管期间 live 配置本就指向本地代理，不做处理。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::status::{self, DriftState};
+use crate::services::ProviderService;
+use crate::settings::{self, LiveConfigGuard};
+use crate::store::AppState;
+
+/// 检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 漂移事件名
+pub const LIVE_CONFIG_DRIFT_EVENT: &str = "live-config-drift";
+
+const WATCHED_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// `live-config-drift` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveConfigDriftEvent {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 被改写的字段（`baseUrl` / `apiKey`）
+    pub fields: Vec<String>,
+    /// 是否已自动重新应用
+    pub reapplied: bool,
+}
+
+fn live_paths(app_type: &AppType) -> Vec<PathBuf> {
+    match app_type {
+        AppType::Claude => vec![crate::config::get_claude_settings_path()],
+        AppType::Codex => vec![
+            crate::codex_config::get_codex_auth_path(),
+            crate::codex_config::get_codex_config_path(),
+        ],
+        AppType::Gemini => vec![
+            crate::gemini_config::get_gemini_env_path(),
+            crate::gemini_config::get_gemini_settings_path(),
+        ],
+        AppType::OpenCode => Vec::new(),
+    }
+}
+
+fn modified_times(app_type: &AppType) -> Vec<Option<SystemTime>> {
+    live_paths(app_type)
+        .iter()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// 重新应用当前供应商（与再次切换到当前供应商相同，不会回填被改写的 live 配置）
+pub fn reapply_current(state: &AppState, app_type: AppType) -> Result<String, AppError> {
+    let id = ProviderService::current(state, app_type.clone())?;
+    if id.is_empty() {
+        return Err(AppError::Message(format!(
+            "{} 尚未选择供应商",
+            app_type.as_str()
+        )));
+    }
+    ProviderService::switch(state, app_type, &id)?;
+    Ok(id)
+}
+
+/// 启动后台监听任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_modified = WATCHED_APPS
+            .iter()
+            .map(|app_type| (app_type.as_str(), modified_times(app_type)))
+            .collect::<HashMap<_, _>>();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            let mode = settings::get_settings().live_config_guard;
+            for app_type in WATCHED_APPS.iter() {
+                let current = modified_times(app_type);
+                if last_modified.get(app_type.as_str()) == Some(&current) {
+                    continue;
+                }
+                last_modified.insert(app_type.as_str(), current);
+
+                // 暂停期间只更新基线，恢复后不处理暂停期间的改动
+                if mode != LiveConfigGuard::Off && !crate::runtime_state::is_paused() {
+                    check_app(&app, app_type, mode).await;
+                }
+            }
+        }
+    });
+}
+
+async fn check_app(app: &AppHandle, app_type: &AppType, mode: LiveConfigGuard) {
+    let state = app.state::<AppState>();
+    let taken_over = matches!(
+        state.db.get_live_backup(app_type.as_str()).await,
+        Ok(Some(_))
+    );
+    if taken_over || crate::read_only::is_enabled() {
+        return;
+    }
+
+    let id = match ProviderService::current(&state, app_type.clone()) {
+        Ok(id) if !id.is_empty() => id,
+        _ => return,
+    };
+    let provider = match state.db.get_provider_by_id(&id, app_type.as_str()) {
+        Ok(Some(provider)) => provider,
+        _ => return,
+    };
+
+    let drift = status::detect_drift(app_type, &provider);
+    if drift.state != DriftState::Drifted {
+        return;
+    }
+    log::warn!(
+        "检测到 {} 的 live 配置被外部改写: {}",
+        app_type.as_str(),
+        drift.fields.join(", ")
+    );
+
+    let reapplied = mode == LiveConfigGuard::Reapply
+        && match reapply_current(&state, app_type.clone()) {
+            Ok(_) => {
+                log::info!("已重新应用 {} 的当前供应商: {id}", app_type.as_str());
+                true
+            }
+            Err(e) => {
+                log::error!("重新应用 {} 的当前供应商失败: {e}", app_type.as_str());
+                false
+            }
+        };
+
+    let event = LiveConfigDriftEvent {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id,
+        provider_name: provider.name,
+        fields: drift.fields,
+        reapplied,
+    };
+    if let Err(e) = app.emit(LIVE_CONFIG_DRIFT_EVENT, &event) {
+        log::error!("发射 live 配置漂移事件失败: {e}");
+    }
+}
@@ -0,0 +1,180 @@
+//! 网络路径（`\\wsl$`、SMB 共享等）文件操作辅助
+//!
+//! 目录覆盖指向网络路径时，读写可能因网络抖动、WSL 实例休眠等原因短暂失败。
+//! 本模块对这类瞬时错误做带退避的重试，并将最终失败归类为 `AppError::NetworkPath`，
+//! 避免在切换过程中只得到笼统的 IO 错误。
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::AppError;
+
+/// 最大尝试次数（含首次）
+const MAX_ATTEMPTS: u32 = 4;
+/// 首次重试前的等待时间，之后每次翻倍
+const INITIAL_BACKOFF: Duration = Duration::from_millis(150);
+
+/// 判断路径是否为网络路径（UNC：`\\server\share`、`\\?\UNC\...`、`//server/share`）
+pub fn is_network_path(path: &Path) -> bool {
+    let s = path.to_string_lossy();
+    if s.starts_with(r"\\?\UNC\") {
+        return true;
+    }
+    if s.starts_with(r"\\?\") || s.starts_with(r"\\.\") {
+        return false;
+    }
+    s.starts_with(r"\\") || s.starts_with("//")
+}
+
+/// 判断 IO 错误是否可能是网络瞬时故障，值得重试
+pub fn is_transient_io_error(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    if matches!(
+        err.kind(),
+        ErrorKind::TimedOut
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+    ) {
+        return true;
+    }
+
+    // Windows 网络相关错误码
+    #[cfg(windows)]
+    {
+        const ERROR_SHARING_VIOLATION: i32 = 32;
+        const ERROR_BAD_NETPATH: i32 = 53;
+        const ERROR_NETWORK_BUSY: i32 = 54;
+        const ERROR_UNEXP_NET_ERR: i32 = 59;
+        const ERROR_NETNAME_DELETED: i32 = 64;
+        const ERROR_BAD_NET_NAME: i32 = 67;
+        const ERROR_SEM_TIMEOUT: i32 = 121;
+        const ERROR_NETWORK_UNREACHABLE: i32 = 1231;
+        if let Some(code) = err.raw_os_error() {
+            return matches!(
+                code,
+                ERROR_SHARING_VIOLATION
+                    | ERROR_BAD_NETPATH
+                    | ERROR_NETWORK_BUSY
+                    | ERROR_UNEXP_NET_ERR
+                    | ERROR_NETNAME_DELETED
+                    | ERROR_BAD_NET_NAME
+                    | ERROR_SEM_TIMEOUT
+                    | ERROR_NETWORK_UNREACHABLE
+            );
+        }
+    }
+
+    false
+}
+
+/// 对网络路径上的 IO 操作做带退避的重试
+///
+/// 本地路径直接执行一次；网络路径在遇到瞬时错误时最多重试 `MAX_ATTEMPTS - 1` 次。
+/// 最终仍失败且属于瞬时错误时返回 `AppError::NetworkPath`，否则返回 `AppError::Io`。
+pub fn with_retry<T>(
+    path: &Path,
+    mut op: impl FnMut() -> std::io::Result<T>,
+) -> Result<T, AppError> {
+    if !is_network_path(path) {
+        return op().map_err(|e| AppError::io(path, e));
+    }
+
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(v) => return Ok(v),
+            Err(e) if is_transient_io_error(&e) && attempt < MAX_ATTEMPTS => {
+                log::warn!(
+                    "网络路径操作失败，{}ms 后重试（第 {attempt} 次）: {}: {e}",
+                    backoff.as_millis(),
+                    path.display()
+                );
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) if is_transient_io_error(&e) => return Err(AppError::network_path(path, e)),
+            Err(e) => return Err(AppError::io(path, e)),
+        }
+    }
+}
+
+/// 异步预检：在超时时间内确认目录可访问（用于切换前提示网络路径不可用）
+#[cfg(feature = "gui")]
+pub async fn check_reachable(path: &Path, timeout: Duration) -> Result<(), AppError> {
+    let owned = path.to_path_buf();
+    let probe = tokio::task::spawn_blocking(move || std::fs::metadata(&owned).map(|_| ()));
+
+    match tokio::time::timeout(timeout, probe).await {
+        Ok(Ok(Ok(()))) => Ok(()),
+        // 目录尚不存在不算不可达：切换时会自动创建
+        Ok(Ok(Err(e))) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Ok(Ok(Err(e))) if is_network_path(path) => Err(AppError::network_path(path, e)),
+        Ok(Ok(Err(e))) => Err(AppError::io(path, e)),
+        Ok(Err(e)) => Err(AppError::Message(format!("可达性检查任务失败: {e}"))),
+        Err(_) => Err(AppError::network_path(
+            path,
+            std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("{}ms 内无响应", timeout.as_millis()),
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn detects_unc_paths() {
+        assert!(is_network_path(Path::new(r"\\wsl$\Ubuntu\home\me\.codex")));
+        assert!(is_network_path(Path::new(r"\\?\UNC\server\share\dir")));
+        assert!(is_network_path(Path::new("//nas/share/.claude")));
+        assert!(!is_network_path(Path::new(r"\\?\C:\Users\me")));
+        assert!(!is_network_path(Path::new("/home/me/.codex")));
+    }
+
+    #[test]
+    fn with_retry_retries_transient_errors_on_network_path() {
+        let calls = Cell::new(0);
+        let result = with_retry(Path::new(r"\\nas\share\auth.json"), || {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(std::io::Error::from(std::io::ErrorKind::TimedOut))
+            } else {
+                Ok(42)
+            }
+        });
+        assert_eq!(result.expect("should succeed after retries"), 42);
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn with_retry_classifies_exhausted_network_errors() {
+        let err = with_retry(Path::new(r"\\nas\share\auth.json"), || {
+            Err::<(), _>(std::io::Error::from(std::io::ErrorKind::TimedOut))
+        })
+        .expect_err("should fail");
+        assert!(matches!(err, AppError::NetworkPath { .. }));
+    }
+
+    #[test]
+    fn with_retry_does_not_retry_local_paths() {
+        let calls = Cell::new(0);
+        let err = with_retry(Path::new("/tmp/auth.json"), || {
+            calls.set(calls.get() + 1);
+            Err::<(), _>(std::io::Error::from(std::io::ErrorKind::TimedOut))
+        })
+        .expect_err("should fail");
+        assert_eq!(calls.get(), 1);
+        assert!(matches!(err, AppError::Io { .. }));
+    }
+}
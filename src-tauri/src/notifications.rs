@@ -0,0 +1,212 @@
+//! 系统通知
+//!
+//! 托盘、快速切换、自动化规则与自动选择的切换结果、代理故障转移、自动备份、健康检查
+//! 与错误突增告警以系统通知提示，只发送设置 `notifications` 中启用的事件。通知文本
+//! 跟随界面语言（见 [`crate::i18n`]），发送失败只记录日志。
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::app_config::AppType;
+use crate::i18n::{self, Language};
+use crate::settings::{self, NotificationConfig, NotificationEvent};
+
+const TITLE: &str = "CC Switch";
+
+/// 通知内容
+pub enum Notice<'a> {
+    SwitchSucceeded {
+        app_type: &'a AppType,
+        provider: &'a str,
+    },
+    SwitchFailed {
+        app_type: &'a AppType,
+        error: &'a str,
+    },
+    Failover {
+        app_type: &'a AppType,
+        provider: &'a str,
+    },
+    AutoSelected {
+        app_type: &'a AppType,
+        provider: &'a str,
+        ttfb_ms: u64,
+    },
+    BackupCreated {
+        id: &'a str,
+    },
+    BackupFailed {
+        error: &'a str,
+    },
+    HealthCheckFailed {
+        app_type: &'a AppType,
+        provider: &'a str,
+        detail: &'a str,
+    },
+    ErrorBurst {
+        app_type: &'a AppType,
+        provider: &'a str,
+        count: usize,
+        minutes: u32,
+        /// 建议切换到的供应商（故障转移队列中的下一个）
+        next: Option<&'a str>,
+    },
+}
+
+fn app_title(app_type: &AppType) -> &'static str {
+    match app_type {
+        AppType::Claude => "Claude",
+        AppType::Codex => "Codex",
+        AppType::Gemini => "Gemini",
+        AppType::OpenCode => "OpenCode",
+    }
+}
+
+impl Notice<'_> {
+    fn event(&self) -> NotificationEvent {
+        match self {
+            Notice::SwitchSucceeded { .. } | Notice::AutoSelected { .. } => {
+                NotificationEvent::SwitchSucceeded
+            }
+            Notice::SwitchFailed { .. } => NotificationEvent::SwitchFailed,
+            Notice::Failover { .. } => NotificationEvent::Failover,
+            Notice::BackupCreated { .. } | Notice::BackupFailed { .. } => NotificationEvent::Backup,
+            Notice::HealthCheckFailed { .. } | Notice::ErrorBurst { .. } => {
+                NotificationEvent::HealthCheckFailed
+            }
+        }
+    }
+
+    fn body(&self, language: Language) -> String {
+        match self {
+            Notice::SwitchSucceeded { app_type, provider } => i18n::format(
+                language,
+                "notification.switchSucceeded",
+                &[("app", app_title(app_type)), ("provider", provider)],
+            ),
+            Notice::SwitchFailed { app_type, error } => i18n::format(
+                language,
+                "notification.switchFailed",
+                &[("app", app_title(app_type)), ("error", error)],
+            ),
+            Notice::Failover { app_type, provider } => i18n::format(
+                language,
+                "notification.failover",
+                &[("app", app_title(app_type)), ("provider", provider)],
+            ),
+            Notice::AutoSelected {
+                app_type,
+                provider,
+                ttfb_ms,
+            } => i18n::format(
+                language,
+                "notification.autoSelected",
+                &[
+                    ("app", app_title(app_type)),
+                    ("provider", provider),
+                    ("ms", &ttfb_ms.to_string()),
+                ],
+            ),
+            Notice::BackupCreated { id } => {
+                i18n::format(language, "notification.backupCreated", &[("id", id)])
+            }
+            Notice::BackupFailed { error } => {
+                i18n::format(language, "notification.backupFailed", &[("error", error)])
+            }
+            Notice::HealthCheckFailed {
+                app_type,
+                provider,
+                detail,
+            } => i18n::format(
+                language,
+                "notification.healthCheckFailed",
+                &[
+                    ("app", app_title(app_type)),
+                    ("provider", provider),
+                    ("detail", detail),
+                ],
+            ),
+            Notice::ErrorBurst {
+                app_type,
+                provider,
+                count,
+                minutes,
+                next,
+            } => {
+                let count = count.to_string();
+                let minutes = minutes.to_string();
+                let mut args = vec![
+                    ("app", app_title(app_type)),
+                    ("provider", *provider),
+                    ("count", count.as_str()),
+                    ("minutes", minutes.as_str()),
+                ];
+                let key = match next {
+                    Some(next) => {
+                        args.push(("next", *next));
+                        "notification.errorBurstSuggest"
+                    }
+                    None => "notification.errorBurst",
+                };
+                i18n::format(language, key, &args)
+            }
+        }
+    }
+}
+
+fn wants(config: &NotificationConfig, event: NotificationEvent) -> bool {
+    config.enabled && config.events.contains(&event)
+}
+
+/// 发送系统通知（未启用该事件时无操作）
+pub fn notify(app: &AppHandle, notice: Notice<'_>) {
+    let settings = settings::get_settings();
+    if !wants(&settings.notifications, notice.event()) {
+        return;
+    }
+    let language = settings
+        .language
+        .as_deref()
+        .map(Language::from_code)
+        .unwrap_or_default();
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(TITLE)
+        .body(notice.body(language))
+        .show()
+    {
+        log::warn!("发送系统通知失败: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_events_and_localizes_bodies() {
+        let config = NotificationConfig {
+            enabled: true,
+            events: vec![NotificationEvent::Failover],
+        };
+        assert!(wants(&config, NotificationEvent::Failover));
+        assert!(!wants(&config, NotificationEvent::Backup));
+        let disabled = NotificationConfig {
+            enabled: false,
+            ..NotificationConfig::default()
+        };
+        assert!(!wants(&disabled, NotificationEvent::Failover));
+
+        let notice = Notice::Failover {
+            app_type: &AppType::Codex,
+            provider: "Relay",
+        };
+        assert_eq!(notice.body(Language::En), "Codex failed over to Relay");
+        assert_eq!(notice.body(Language::Zh), "Codex 已故障转移到 Relay");
+        assert_eq!(
+            Notice::BackupFailed { error: "disk full" }.event(),
+            NotificationEvent::Backup
+        );
+    }
+}
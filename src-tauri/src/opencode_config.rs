@@ -24,7 +24,7 @@
 use crate::config::write_json_file;
 use crate::error::AppError;
 use crate::provider::OpenCodeProviderConfig;
-use crate::settings::get_opencode_override_dir;
+use crate::settings::{get_opencode_override_dir, LiveFile};
 use indexmap::IndexMap;
 use serde_json::{json, Map, Value};
 use std::path::PathBuf;
@@ -63,7 +63,11 @@ pub fn get_opencode_dir() -> PathBuf {
 ///
 /// 返回 `~/.config/opencode/opencode.json`
 pub fn get_opencode_config_path() -> PathBuf {
-    get_opencode_dir().join("opencode.json")
+    crate::settings::resolve_live_file(
+        LiveFile::OpenCodeConfig,
+        &get_opencode_dir(),
+        "opencode.json",
+    )
 }
 
 /// 获取 OpenCode 环境变量文件路径（如果存在）
@@ -122,7 +126,10 @@ pub fn get_providers() -> Result<Map<String, Value>, AppError> {
 }
 
 /// 设置供应商配置（原始 JSON）
+///
+/// 写入前按 opencode 的配置格式校验，格式错误的文件会导致 opencode 无法启动。
 pub fn set_provider(id: &str, config: Value) -> Result<(), AppError> {
+    validate_provider_config(id, &config)?;
     let mut full_config = read_opencode_config()?;
 
     if full_config.get("provider").is_none() {
@@ -180,6 +187,209 @@ pub fn set_typed_provider(id: &str, config: &OpenCodeProviderConfig) -> Result<(
     set_provider(id, value)
 }
 
+// ============================================================================
+// Schema Validation
+// ============================================================================
+
+/// 字段期望的类型（对应 https://opencode.ai/config.json 中供应商条目的定义）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    String,
+    Bool,
+    Number,
+    NonNegativeInteger,
+    Object,
+    StringArray,
+    StringMap,
+    /// `options.timeout`：正整数（毫秒）或 `false`
+    Timeout,
+}
+
+impl Expected {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Expected::String => value.is_string(),
+            Expected::Bool => value.is_boolean(),
+            Expected::Number => value.is_number(),
+            Expected::NonNegativeInteger => value.is_u64(),
+            Expected::Object => value.is_object(),
+            Expected::StringArray => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            Expected::StringMap => value
+                .as_object()
+                .is_some_and(|map| map.values().all(Value::is_string)),
+            Expected::Timeout => {
+                value.as_u64().is_some_and(|ms| ms > 0) || value == &Value::Bool(false)
+            }
+        }
+    }
+
+    fn zh(self) -> &'static str {
+        match self {
+            Expected::String => "字符串",
+            Expected::Bool => "布尔值",
+            Expected::Number => "数字",
+            Expected::NonNegativeInteger => "非负整数",
+            Expected::Object => "对象",
+            Expected::StringArray => "字符串数组",
+            Expected::StringMap => "值为字符串的对象",
+            Expected::Timeout => "正整数（毫秒）或 false",
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Expected::String => "a string",
+            Expected::Bool => "a boolean",
+            Expected::Number => "a number",
+            Expected::NonNegativeInteger => "a non-negative integer",
+            Expected::Object => "an object",
+            Expected::StringArray => "an array of strings",
+            Expected::StringMap => "an object of strings",
+            Expected::Timeout => "a positive integer (ms) or false",
+        }
+    }
+}
+
+/// 供应商条目的已知字段
+const PROVIDER_FIELDS: [(&str, Expected); 9] = [
+    ("npm", Expected::String),
+    ("name", Expected::String),
+    ("id", Expected::String),
+    ("api", Expected::String),
+    ("env", Expected::StringArray),
+    ("whitelist", Expected::StringArray),
+    ("blacklist", Expected::StringArray),
+    ("options", Expected::Object),
+    ("models", Expected::Object),
+];
+
+/// `options` 的已知字段（允许其他自定义字段）
+const OPTION_FIELDS: [(&str, Expected); 6] = [
+    ("apiKey", Expected::String),
+    ("baseURL", Expected::String),
+    ("enterpriseUrl", Expected::String),
+    ("setCacheKey", Expected::Bool),
+    ("timeout", Expected::Timeout),
+    ("headers", Expected::StringMap),
+];
+
+/// 模型条目的已知字段
+const MODEL_FIELDS: [(&str, Expected); 10] = [
+    ("id", Expected::String),
+    ("name", Expected::String),
+    ("attachment", Expected::Bool),
+    ("reasoning", Expected::Bool),
+    ("temperature", Expected::Bool),
+    ("tool_call", Expected::Bool),
+    ("cost", Expected::Object),
+    ("limit", Expected::Object),
+    ("options", Expected::Object),
+    ("headers", Expected::StringMap),
+];
+
+const MODEL_COST_FIELDS: [(&str, Expected); 4] = [
+    ("input", Expected::Number),
+    ("output", Expected::Number),
+    ("cache_read", Expected::Number),
+    ("cache_write", Expected::Number),
+];
+
+const MODEL_LIMIT_FIELDS: [(&str, Expected); 2] = [
+    ("context", Expected::NonNegativeInteger),
+    ("output", Expected::NonNegativeInteger),
+];
+
+/// 检查对象中的已知字段，返回 `(字段路径, 期望类型)` 列表
+fn check_fields(
+    object: &Map<String, Value>,
+    fields: &[(&str, Expected)],
+    path: &str,
+    errors: &mut Vec<(String, Expected)>,
+) {
+    for (field, expected) in fields {
+        if let Some(value) = object.get(*field) {
+            if !expected.matches(value) {
+                errors.push((format!("{path}.{field}"), *expected));
+            }
+        }
+    }
+}
+
+/// 按 opencode 的配置格式校验供应商条目，返回所有不符合的字段
+fn schema_errors(id: &str, config: &Value) -> Vec<(String, Expected)> {
+    let path = format!("provider.{id}");
+    let mut errors = Vec::new();
+    let Some(provider) = config.as_object() else {
+        errors.push((path, Expected::Object));
+        return errors;
+    };
+    check_fields(provider, &PROVIDER_FIELDS, &path, &mut errors);
+
+    if let Some(options) = provider.get("options").and_then(Value::as_object) {
+        check_fields(
+            options,
+            &OPTION_FIELDS,
+            &format!("{path}.options"),
+            &mut errors,
+        );
+    }
+    if let Some(models) = provider.get("models").and_then(Value::as_object) {
+        for (model_id, model) in models {
+            let model_path = format!("{path}.models.{model_id}");
+            let Some(model) = model.as_object() else {
+                errors.push((model_path, Expected::Object));
+                continue;
+            };
+            check_fields(model, &MODEL_FIELDS, &model_path, &mut errors);
+            if let Some(cost) = model.get("cost").and_then(Value::as_object) {
+                check_fields(
+                    cost,
+                    &MODEL_COST_FIELDS,
+                    &format!("{model_path}.cost"),
+                    &mut errors,
+                );
+            }
+            if let Some(limit) = model.get("limit").and_then(Value::as_object) {
+                check_fields(
+                    limit,
+                    &MODEL_LIMIT_FIELDS,
+                    &format!("{model_path}.limit"),
+                    &mut errors,
+                );
+            }
+        }
+    }
+    errors
+}
+
+/// 写入 `opencode.json` 前校验供应商条目
+///
+/// 错误信息逐条列出不符合格式的字段路径（如 `provider.my-provider.options.timeout`）
+/// 与期望的类型。
+pub fn validate_provider_config(id: &str, config: &Value) -> Result<(), AppError> {
+    let errors = schema_errors(id, config);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let zh = errors
+        .iter()
+        .map(|(path, expected)| format!("{path} 应为{}", expected.zh()))
+        .collect::<Vec<_>>()
+        .join("；");
+    let en = errors
+        .iter()
+        .map(|(path, expected)| format!("{path} must be {}", expected.en()))
+        .collect::<Vec<_>>()
+        .join("; ");
+    Err(AppError::localized(
+        "opencode.validation.invalid_provider",
+        format!("OpenCode 供应商配置格式错误: {zh}"),
+        format!("Invalid OpenCode provider config: {en}"),
+    ))
+}
+
 // ============================================================================
 // MCP Functions
 // ============================================================================
@@ -220,3 +430,44 @@ pub fn remove_mcp_server(id: &str) -> Result<(), AppError> {
     write_opencode_config(&config)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_provider_config_reports_each_invalid_field() {
+        let valid = json!({
+            "npm": "@ai-sdk/openai-compatible",
+            "options": { "baseURL": "https://relay.example/v1", "timeout": false },
+            "models": {
+                "gpt-4o": { "name": "GPT-4o", "limit": { "context": 128000, "output": 4096 } }
+            }
+        });
+        assert!(validate_provider_config("relay", &valid).is_ok());
+
+        let invalid = json!({
+            "npm": 1,
+            "options": { "baseURL": "https://relay.example/v1", "timeout": "30s", "custom": 1 },
+            "models": {
+                "gpt-4o": { "name": "GPT-4o", "limit": { "context": "128k" } },
+                "broken": "gpt"
+            }
+        });
+        let mut errors = schema_errors("relay", &invalid)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                "provider.relay.models.broken",
+                "provider.relay.models.gpt-4o.limit.context",
+                "provider.relay.npm",
+                "provider.relay.options.timeout",
+            ]
+        );
+        let err = validate_provider_config("relay", &invalid).expect_err("invalid config");
+        assert!(err.to_string().contains("provider.relay.options.timeout"));
+    }
+}
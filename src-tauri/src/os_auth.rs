@@ -0,0 +1,129 @@
+//! 系统身份验证（Touch ID / Windows Hello / polkit）
+//!
+//! 开启 `require_os_auth_for_secrets` 后，读取或导出 API Key 原文的命令需要先通过
+//! 系统身份验证；验证成功后的短时间内不再重复提示。
+//!
+//! - macOS：LocalAuthentication（Touch ID，失败时可回退到登录密码）
+//! - Windows：Windows Hello（UserConsentVerifier）
+//! - Linux：polkit（`pkexec`，由桌面环境的认证代理弹窗）
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::error::AppError;
+
+/// 验证成功后免再次验证的时长
+#[cfg(feature = "gui")]
+const GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+static LAST_VERIFIED: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn verify_user(reason: &str) -> Result<bool, AppError> {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSError, NSString};
+    use objc2_local_authentication::{LAContext, LAPolicy};
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let reply = RcBlock::new(move |success: Bool, _error: *mut NSError| {
+        let _ = tx.send(success.as_bool());
+    });
+
+    // SAFETY: LAContext 在回调结束前保持存活；回调只通过 channel 传回结果
+    unsafe {
+        let context = LAContext::new();
+        context.evaluatePolicy_localizedReason_reply(
+            LAPolicy::DeviceOwnerAuthentication,
+            &NSString::from_str(reason),
+            &reply,
+        );
+        let verified = rx
+            .recv_timeout(Duration::from_secs(120))
+            .map_err(|_| AppError::Message("系统身份验证超时".to_string()))?;
+        drop(context);
+        Ok(verified)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn verify_user(reason: &str) -> Result<bool, AppError> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{
+        UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability,
+    };
+
+    let unavailable =
+        |e: windows::core::Error| AppError::Message(format!("Windows Hello 不可用: {e}"));
+
+    let availability = UserConsentVerifier::CheckAvailabilityAsync()
+        .and_then(|op| op.get())
+        .map_err(unavailable)?;
+    if availability != UserConsentVerifierAvailability::Available {
+        return Err(AppError::localized(
+            "os_auth.unavailable",
+            "未设置 Windows Hello，无法进行系统身份验证",
+            "Windows Hello is not set up; OS authentication is unavailable",
+        ));
+    }
+
+    let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason))
+        .and_then(|op| op.get())
+        .map_err(unavailable)?;
+    Ok(result == UserConsentVerificationResult::Verified)
+}
+
+#[cfg(target_os = "linux")]
+fn verify_user(_reason: &str) -> Result<bool, AppError> {
+    let status = std::process::Command::new("pkexec")
+        .arg("--disable-internal-agent")
+        .arg("true")
+        .status()
+        .map_err(|e| AppError::Message(format!("无法调用 pkexec 进行身份验证: {e}")))?;
+    Ok(status.success())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn verify_user(_reason: &str) -> Result<bool, AppError> {
+    Err(AppError::localized(
+        "os_auth.unavailable",
+        "当前平台不支持系统身份验证",
+        "OS authentication is not supported on this platform",
+    ))
+}
+
+#[cfg(feature = "gui")]
+fn recently_verified() -> bool {
+    LAST_VERIFIED
+        .lock()
+        .ok()
+        .and_then(|guard| *guard)
+        .is_some_and(|at| at.elapsed() < GRACE_PERIOD)
+}
+
+/// 弹出系统身份验证（阻塞直到用户完成或取消）
+pub fn authenticate(reason: &str) -> Result<(), AppError> {
+    if !verify_user(reason)? {
+        return Err(AppError::localized(
+            "os_auth.denied",
+            "系统身份验证未通过",
+            "OS authentication failed or was cancelled",
+        ));
+    }
+    if let Ok(mut guard) = LAST_VERIFIED.lock() {
+        *guard = Some(Instant::now());
+    }
+    Ok(())
+}
+
+/// 按设置要求系统身份验证后才允许访问密钥原文
+#[cfg(feature = "gui")]
+pub async fn ensure_secret_access(reason: &str) -> Result<(), AppError> {
+    if !crate::settings::get_settings().require_os_auth_for_secrets || recently_verified() {
+        return Ok(());
+    }
+    let reason = reason.to_string();
+    tokio::task::spawn_blocking(move || authenticate(&reason))
+        .await
+        .map_err(|e| AppError::Message(format!("系统身份验证失败: {e}")))?
+}
@@ -21,11 +21,13 @@ pub fn init_app_config_dir(dir: PathBuf) {
     let _ = APP_CONFIG_DIR.set(dir);
 }
 
-/// 获取默认应用配置目录（不会 panic）
+/// 获取默认应用配置目录（不会 panic；`CC_SWITCH_HOME` 优先）
 fn default_app_config_dir() -> PathBuf {
-    dirs::home_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join(".cc-switch")
+    crate::paths::env_data_dir().unwrap_or_else(|| {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".cc-switch")
+    })
 }
 
 /// 获取应用配置目录（优先使用初始化时写入的值；不会 panic）
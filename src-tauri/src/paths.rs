@@ -0,0 +1,55 @@
+//! 应用数据目录的解析
+//!
+//! 数据库、备份、日志、设置等全部位于应用数据目录下，按以下顺序解析：
+//!
+//! 1. 环境变量 `CC_SWITCH_HOME`
+//! 2. 设置中的目录覆盖（保存在 Tauri Store 中，见 [`crate::app_store`]；`settings.json`
+//!    本身就位于数据目录下，不能用来保存这个值）
+//! 3. 默认的 `~/.cc-switch`
+//!
+//! 与设置中的覆盖不同，环境变量指向的目录不存在时照常使用、按需创建，而不是退回默认
+//! 目录，避免数据放在单独的加密卷上时被悄悄写回主目录。
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// 覆盖数据目录的环境变量
+pub const DATA_DIR_ENV: &str = "CC_SWITCH_HOME";
+
+/// 数据目录的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataDirSource {
+    Env,
+    Setting,
+    Default,
+}
+
+/// 环境变量中的数据目录（未设置或为空时返回 None）
+pub(crate) fn env_data_dir() -> Option<PathBuf> {
+    let raw = std::env::var(DATA_DIR_ENV).ok()?;
+    let raw = raw.trim();
+    (!raw.is_empty()).then(|| crate::settings::resolve_override_path(raw))
+}
+
+/// 解析当前的数据目录及其来源
+pub fn resolve_data_dir() -> (PathBuf, DataDirSource) {
+    if let Some(dir) = env_data_dir() {
+        return (dir, DataDirSource::Env);
+    }
+    if let Some(dir) = crate::app_store::get_app_config_dir_override() {
+        return (dir, DataDirSource::Setting);
+    }
+    (
+        dirs::home_dir()
+            .expect("无法获取用户主目录")
+            .join(".cc-switch"),
+        DataDirSource::Default,
+    )
+}
+
+/// 当前的数据目录
+pub fn data_dir() -> PathBuf {
+    resolve_data_dir().0
+}
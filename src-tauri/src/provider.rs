@@ -40,6 +40,9 @@ pub struct Provider {
     #[serde(default)]
     #[serde(rename = "inFailoverQueue")]
     pub in_failover_queue: bool,
+    /// 受保护的供应商：解锁前不能删除，也不能修改密钥
+    #[serde(default)]
+    pub locked: bool,
 }
 
 impl Provider {
@@ -63,6 +66,7 @@ impl Provider {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            locked: false,
         }
     }
 }
@@ -108,6 +112,16 @@ pub struct UsageScript {
     pub auto_query_interval: Option<u64>,
 }
 
+/// 中转站余额查询配置（one-api / new-api 等兼容接口）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BalanceQuery {
+    /// 余额接口地址，如 `https://relay.example.com/api/usage/token`
+    pub url: String,
+    /// 查询令牌，为空时使用供应商的 API Key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
 /// 用量数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageData {
@@ -151,6 +165,9 @@ pub struct ProviderMeta {
     /// 用量查询脚本配置
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_script: Option<UsageScript>,
+    /// 中转站余额查询
+    #[serde(rename = "balanceQuery", skip_serializing_if = "Option::is_none")]
+    pub balance_query: Option<BalanceQuery>,
     /// 请求地址管理：测速后自动选择最佳端点
     #[serde(rename = "endpointAutoSelect", skip_serializing_if = "Option::is_none")]
     pub endpoint_auto_select: Option<bool>,
@@ -172,6 +189,9 @@ pub struct ProviderMeta {
     /// 每月消费限额（USD）
     #[serde(rename = "limitMonthlyUsd", skip_serializing_if = "Option::is_none")]
     pub limit_monthly_usd: Option<String>,
+    /// 在快速切换面板中置顶
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<bool>,
 }
 
 impl ProviderManager {
@@ -366,6 +386,7 @@ impl UniversalProvider {
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            locked: false,
         })
     }
 
@@ -424,6 +445,7 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            locked: false,
         })
     }
 
@@ -459,6 +481,7 @@ requires_openai_auth = true"#
             icon: self.icon.clone(),
             icon_color: self.icon_color.clone(),
             in_failover_queue: false,
+            locked: false,
         })
     }
 }
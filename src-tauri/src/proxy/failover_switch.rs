@@ -4,7 +4,7 @@
 //! - 去重控制（避免多个请求同时触发）
 //! - 数据库更新
 //! - 托盘菜单更新
-//! - 前端事件发射
+//! - 前端事件发射与 Webhook 通知
 //! - Live 备份更新
 
 use crate::database::Database;
@@ -12,6 +12,7 @@ use crate::error::AppError;
 use std::collections::HashSet;
 use std::str::FromStr;
 use std::sync::Arc;
+#[cfg(feature = "gui")]
 use tauri::{Emitter, Manager};
 use tokio::sync::RwLock;
 
@@ -43,7 +44,7 @@ impl FailoverSwitchManager {
     /// - `Err(e)` - 切换过程中发生错误
     pub async fn try_switch(
         &self,
-        app_handle: Option<&tauri::AppHandle>,
+        app_handle: Option<&crate::AppHandle>,
         app_type: &str,
         provider_id: &str,
         provider_name: &str,
@@ -76,7 +77,7 @@ impl FailoverSwitchManager {
 
     async fn do_switch(
         &self,
-        app_handle: Option<&tauri::AppHandle>,
+        app_handle: Option<&crate::AppHandle>,
         app_type: &str,
         provider_id: &str,
         provider_name: &str,
@@ -96,6 +97,11 @@ impl FailoverSwitchManager {
             return Ok(false);
         }
 
+        if crate::runtime_state::is_paused() {
+            log::info!("[Failover] 已暂停，跳过切换: {app_type} → {provider_name}");
+            return Ok(false);
+        }
+
         log::info!("[FO-001] 切换: {app_type} → {provider_name}");
 
         // 1. 更新数据库 is_current
@@ -106,9 +112,19 @@ impl FailoverSwitchManager {
             .map_err(|_| AppError::Message(format!("无效的应用类型: {app_type}")))?;
         crate::settings::set_current_provider(&app_type_enum, Some(provider_id))?;
 
-        // 3. 更新托盘菜单和发射事件
+        crate::services::webhook::notify(
+            crate::settings::WebhookEvent::Failover,
+            app_type,
+            provider_id,
+            provider_name,
+            None,
+        );
+
+        // 3. 更新托盘菜单和发射事件（无界面构建中没有托盘与前端）
+        #[cfg(not(feature = "gui"))]
+        let _ = app_handle;
+        #[cfg(feature = "gui")]
         if let Some(app) = app_handle {
-            // 更新托盘菜单
             if let Some(app_state) = app.try_state::<crate::store::AppState>() {
                 // 更新 Live 备份（确保代理停止时恢复正确配置）
                 if let Ok(Some(provider)) = self.db.get_provider_by_id(provider_id, app_type) {
@@ -120,17 +136,21 @@ impl FailoverSwitchManager {
                         log::warn!("[FO-003] Live 备份更新失败: {e}");
                     }
                 }
+            }
 
-                // 重建托盘菜单
-                if let Ok(new_menu) = crate::tray::create_tray_menu(app, app_state.inner()) {
-                    if let Some(tray) = app.tray_by_id("main") {
-                        if let Err(e) = tray.set_menu(Some(new_menu)) {
-                            log::error!("[Failover] 更新托盘菜单失败: {e}");
-                        }
-                    }
-                }
+            // 重建托盘菜单
+            if let Err(e) = crate::tray::refresh_tray_menu(app) {
+                log::error!("[Failover] {e}");
             }
 
+            crate::notifications::notify(
+                app,
+                crate::notifications::Notice::Failover {
+                    app_type: &app_type_enum,
+                    provider: provider_name,
+                },
+            );
+
             // 发射事件到前端
             let event_data = serde_json::json!({
                 "appType": app_type,
@@ -60,6 +60,7 @@ pub fn init(proxy_url: Option<&str>) -> Result<(), String> {
 ///
 /// # Returns
 /// 验证成功返回 Ok(())，失败返回错误信息
+#[cfg(feature = "gui")]
 pub fn validate_proxy(proxy_url: Option<&str>) -> Result<(), String> {
     let effective_url = proxy_url.filter(|s| !s.trim().is_empty());
     // 只调用 build_client 来验证，但不应用
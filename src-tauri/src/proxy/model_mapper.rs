@@ -163,6 +163,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            locked: false,
         }
     }
 
@@ -180,6 +181,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            locked: false,
         }
     }
 
@@ -201,6 +203,7 @@ mod tests {
             icon: None,
             icon_color: None,
             in_failover_queue: false,
+            locked: false,
         }
     }
 
@@ -0,0 +1,92 @@
+//! 只读（展示）模式
+//!
+//! 开启后禁止切换、新增、编辑与删除供应商，适用于共享的演示机器，或在长时间运行的
+//! Agent 任务期间防止误切换。加锁时可设置解锁口令；未设置口令时，解锁需要通过系统
+//! 身份验证（见 [`crate::os_auth`]）。
+
+use crate::crypto;
+use crate::error::AppError;
+use crate::settings;
+
+/// 只读模式状态（不含口令哈希）
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadOnlyStatus {
+    pub enabled: bool,
+    /// 解锁方式为口令（否则为系统身份验证）
+    pub has_passphrase: bool,
+}
+
+pub fn status() -> ReadOnlyStatus {
+    let settings = settings::get_settings();
+    ReadOnlyStatus {
+        enabled: settings.read_only_mode,
+        has_passphrase: settings.read_only_passphrase_hash.is_some(),
+    }
+}
+
+pub fn is_enabled() -> bool {
+    settings::get_settings().read_only_mode
+}
+
+/// 只读模式下拒绝修改供应商
+pub fn ensure_writable() -> Result<(), AppError> {
+    if is_enabled() {
+        return Err(AppError::localized(
+            "read_only.locked",
+            "只读模式已开启，无法切换、编辑或删除供应商",
+            "Read-only mode is on; providers cannot be switched, edited or deleted",
+        ));
+    }
+    Ok(())
+}
+
+/// 开启只读模式；`passphrase` 为空时解锁需要系统身份验证
+///
+/// 已开启时拒绝再次加锁，否则可以用新口令（或改为系统身份验证）覆盖原有口令后解锁。
+pub fn lock(passphrase: Option<&str>) -> Result<(), AppError> {
+    if is_enabled() {
+        return Err(AppError::localized(
+            "read_only.already_locked",
+            "只读模式已开启，如需更换解锁口令请先解锁",
+            "Read-only mode is already on; unlock it first to change the passphrase",
+        ));
+    }
+    let hash = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => Some(crypto::hash_passphrase(passphrase)?),
+        None => None,
+    };
+    let mut settings = settings::get_settings();
+    settings.read_only_mode = true;
+    settings.read_only_passphrase_hash = hash;
+    settings::update_settings(settings)?;
+    log::info!("已开启只读模式");
+    Ok(())
+}
+
+/// 解除只读模式：设置了口令时校验口令，否则弹出系统身份验证（阻塞）
+pub fn unlock(passphrase: Option<&str>) -> Result<(), AppError> {
+    let mut settings = settings::get_settings();
+    if !settings.read_only_mode {
+        return Ok(());
+    }
+
+    match settings.read_only_passphrase_hash.as_deref() {
+        Some(hash) => {
+            if !crypto::verify_passphrase(passphrase.unwrap_or_default(), hash) {
+                return Err(AppError::localized(
+                    "read_only.wrong_passphrase",
+                    "解锁口令错误",
+                    "Incorrect unlock passphrase",
+                ));
+            }
+        }
+        None => crate::os_auth::authenticate("解除 CC Switch 只读模式")?,
+    }
+
+    settings.read_only_mode = false;
+    settings.read_only_passphrase_hash = None;
+    settings::update_settings(settings)?;
+    log::info!("已解除只读模式");
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! 自动化规则执行
+//!
+//! 后台定时读取数据库中的规则并检查触发条件（定义见 [`automation_rules`]）：cron 规则
+//! 在命中的分钟内执行一次，文件与网络规则在指纹变化时执行（启动后的首次读取只记录
+//! 基线）。每次执行都会记录结果并发送 `automation-rule-ran` 事件。
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::AppError;
+use crate::notifications::{self, Notice};
+use crate::services::automation_rules::{
+    self, AutomationRule, CronSchedule, RuleAction, RuleTrigger,
+};
+use crate::services::backup::BackupService;
+use crate::services::stream_check::StreamCheckService;
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 检查间隔（小于一分钟，保证每个 cron 分钟都能被检查到）
+const POLL_INTERVAL: Duration = Duration::from_secs(20);
+
+/// 执行事件名
+pub const AUTOMATION_RULE_RAN_EVENT: &str = "automation-rule-ran";
+
+/// `automation-rule-ran` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRuleRanEvent {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct TriggerState {
+    /// 每条 cron 规则最近一次执行的分钟
+    last_minute: HashMap<String, i64>,
+    /// 每条文件规则最近一次看到的修改时间
+    files: HashMap<String, Option<SystemTime>>,
+    network: Option<Option<IpAddr>>,
+}
+
+impl TriggerState {
+    /// 返回本轮需要执行的规则
+    fn due(&mut self, rules: &[AutomationRule]) -> Vec<AutomationRule> {
+        let now = chrono::Local::now();
+        let minute = now.timestamp() / 60;
+
+        let network = rules
+            .iter()
+            .any(|rule| rule.enabled && rule.trigger == RuleTrigger::NetworkChanged)
+            .then(automation_rules::network_fingerprint);
+        let network_changed = match network {
+            Some(current) => {
+                let previous = self.network.replace(current);
+                // 断网时不触发，恢复联网或出口地址改变时触发
+                current.is_some() && previous.is_some_and(|previous| previous != current)
+            }
+            None => {
+                self.network = None;
+                false
+            }
+        };
+
+        let mut due = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let fire = match &rule.trigger {
+                RuleTrigger::Schedule { cron } => {
+                    CronSchedule::from_str(cron).is_ok_and(|cron| cron.matches(&now))
+                        && self.last_minute.insert(rule.id.clone(), minute) != Some(minute)
+                }
+                RuleTrigger::FileChanged { path } => {
+                    let current = automation_rules::file_fingerprint(path);
+                    self.files
+                        .insert(rule.id.clone(), current)
+                        .is_some_and(|previous| previous != current)
+                }
+                RuleTrigger::NetworkChanged => network_changed,
+            };
+            if fire {
+                due.push(rule.clone());
+            }
+        }
+
+        // 清理已删除或停用规则的状态，重新启用后按新基线计算
+        let active = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(|rule| rule.id.as_str())
+            .collect::<HashSet<_>>();
+        self.last_minute
+            .retain(|id, _| active.contains(id.as_str()));
+        self.files.retain(|id, _| active.contains(id.as_str()));
+        due
+    }
+}
+
+/// 启动后台规则任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut triggers = TriggerState::default();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if state.db.is_locked() {
+                continue;
+            }
+            let rules = match state.db.get_automation_rules() {
+                Ok(rules) => rules,
+                Err(e) => {
+                    log::error!("读取自动化规则失败: {e}");
+                    continue;
+                }
+            };
+            // 暂停期间照常推进触发状态，恢复后不补执行暂停期间命中的规则
+            let due = triggers.due(&rules);
+            if crate::runtime_state::is_paused() {
+                continue;
+            }
+            for rule in due {
+                log::info!("触发自动化规则: {}", rule.name);
+                let _ = run_rule(&app, &rule).await;
+            }
+        }
+    });
+}
+
+/// 执行规则的动作，记录结果并通知前端
+pub async fn run_rule(app: &AppHandle, rule: &AutomationRule) -> Result<String, AppError> {
+    let result = execute(app, &rule.action).await;
+    let (success, message) = match &result {
+        Ok(message) => (true, message.clone()),
+        Err(e) => {
+            log::error!("自动化规则 {} 执行失败: {e}", rule.name);
+            (false, e.to_string())
+        }
+    };
+
+    if let Some(state) = app.try_state::<AppState>() {
+        let recorded = if success {
+            message.clone()
+        } else {
+            format!("error: {message}")
+        };
+        let ran_at = chrono::Utc::now().timestamp_millis();
+        if let Err(e) = state
+            .db
+            .record_automation_rule_run(&rule.id, ran_at, &recorded)
+        {
+            log::error!("记录自动化规则执行结果失败: {e}");
+        }
+    }
+
+    let event = AutomationRuleRanEvent {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        success,
+        message,
+    };
+    if let Err(e) = app.emit(AUTOMATION_RULE_RAN_EVENT, &event) {
+        log::error!("发射自动化规则事件失败: {e}");
+    }
+    result
+}
+
+async fn execute(app: &AppHandle, action: &RuleAction) -> Result<String, AppError> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| AppError::Message("应用状态尚未初始化".to_string()))?;
+
+    match action {
+        RuleAction::SwitchProvider {
+            app: app_type,
+            provider_id,
+        } => {
+            let handle = app.clone();
+            let (app_type, id) = (app_type.clone(), provider_id.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                crate::tray::switch_provider_internal(&handle, app_type, id)
+            })
+            .await
+            .map_err(|e| AppError::Message(format!("切换任务异常: {e}")))??;
+            Ok(format!("switched to {provider_id}"))
+        }
+        RuleAction::HealthCheck { app: app_type } => {
+            let id = ProviderService::current(&state, app_type.clone())?;
+            let provider = state
+                .db
+                .get_provider_by_id(&id, app_type.as_str())?
+                .ok_or_else(|| {
+                    AppError::Message(format!("{} 尚未选择供应商", app_type.as_str()))
+                })?;
+            let config = state.db.get_stream_check_config()?;
+            let result = StreamCheckService::check_with_retry(app_type, &provider, &config)
+                .await
+                .inspect_err(|e| {
+                    notifications::notify(
+                        app,
+                        Notice::HealthCheckFailed {
+                            app_type,
+                            provider: &provider.name,
+                            detail: &e.to_string(),
+                        },
+                    )
+                })?;
+            let _ = state.db.save_stream_check_log(
+                &provider.id,
+                &provider.name,
+                app_type.as_str(),
+                &result,
+            );
+            if !result.success {
+                notifications::notify(
+                    app,
+                    Notice::HealthCheckFailed {
+                        app_type,
+                        provider: &provider.name,
+                        detail: &result.message,
+                    },
+                );
+                return Err(AppError::Message(format!(
+                    "{}: {}",
+                    provider.name, result.message
+                )));
+            }
+            Ok(match result.response_time_ms {
+                Some(ms) => format!("{} healthy ({ms} ms)", provider.name),
+                None => format!("{} healthy", provider.name),
+            })
+        }
+        RuleAction::CreateBackup => {
+            let db = state.db.clone();
+            let entry = tauri::async_runtime::spawn_blocking(move || {
+                let entry = BackupService::create_backup(&db)?;
+                let config = crate::settings::get_settings().auto_backup;
+                BackupService::prune_backups(config.keep_daily, config.keep_weekly)?;
+                Ok::<_, AppError>(entry)
+            })
+            .await
+            .map_err(|e| AppError::Message(format!("备份任务异常: {e}")))?
+            .inspect_err(|e| {
+                notifications::notify(
+                    app,
+                    Notice::BackupFailed {
+                        error: &e.to_string(),
+                    },
+                )
+            })?;
+            notifications::notify(app, Notice::BackupCreated { id: &entry.id });
+            Ok(format!("backup {}", entry.id))
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! 运行时状态
+//!
+//! 「暂停」让 CC Switch 临时停止后台集成而不退出：live 配置与设置文件监听、自动化规则
+//! （定时切换等）、代理故障转移与 Claude 插件联动都会跳过，手动切换不受影响。暂停状态
+//! 只保存在内存中，重启应用后恢复正常。
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// 暂停开始时间（毫秒时间戳），未暂停时为 None
+static PAUSED_AT: Mutex<Option<i64>> = Mutex::new(None);
+
+/// 运行时状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeState {
+    pub paused: bool,
+    /// 暂停开始时间（毫秒时间戳）
+    pub paused_at: Option<i64>,
+}
+
+fn paused_at() -> std::sync::MutexGuard<'static, Option<i64>> {
+    PAUSED_AT.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 后台集成是否已暂停
+pub fn is_paused() -> bool {
+    paused_at().is_some()
+}
+
+/// 当前运行时状态
+pub fn get() -> RuntimeState {
+    let paused_at = *paused_at();
+    RuntimeState {
+        paused: paused_at.is_some(),
+        paused_at,
+    }
+}
+
+/// 暂停或恢复后台集成（重复暂停保留最初的暂停时间）
+#[cfg(feature = "gui")]
+pub fn set_paused(paused: bool) -> RuntimeState {
+    {
+        let mut paused_at = paused_at();
+        if !paused {
+            *paused_at = None;
+        } else if paused_at.is_none() {
+            *paused_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+    }
+    get()
+}
@@ -0,0 +1,58 @@
+//! 系统钥匙串存储
+//!
+//! 敏感凭据（云存储密钥、备份口令等）存放在系统钥匙串中
+//! （macOS Keychain / Windows Credential Manager / Linux Secret Service），
+//! 不写入 settings.json 或数据库。
+//!
+//! Linux 上的 Secret Service 由 `secret-service` 特性提供；未启用时（如无 libdbus 的
+//! 无界面构建）读写凭据均返回错误，数据库口令等改从环境变量读取。
+
+use crate::error::AppError;
+
+#[cfg(any(target_os = "macos", target_os = "windows", feature = "secret-service"))]
+const SERVICE: &str = "cc-switch";
+
+#[cfg(any(target_os = "macos", target_os = "windows", feature = "secret-service"))]
+fn entry(key: &str) -> Result<keyring::Entry, AppError> {
+    keyring::Entry::new(SERVICE, key)
+        .map_err(|e| AppError::Message(format!("访问系统钥匙串失败 ({key}): {e}")))
+}
+
+/// 没有钥匙串后端时 keyring 会退回到不持久化的内存存储，写入的凭据随即丢失，因此直接报错
+#[cfg(not(any(target_os = "macos", target_os = "windows", feature = "secret-service")))]
+fn entry(key: &str) -> Result<keyring::Entry, AppError> {
+    Err(AppError::Message(format!(
+        "此构建未启用系统钥匙串支持 ({key})，请使用 `--features secret-service` 重新构建"
+    )))
+}
+
+/// 写入凭据（值为空时删除）
+pub fn set_secret(key: &str, value: &str) -> Result<(), AppError> {
+    if value.is_empty() {
+        return delete_secret(key);
+    }
+    entry(key)?
+        .set_password(value)
+        .map_err(|e| AppError::Message(format!("写入系统钥匙串失败 ({key}): {e}")))
+}
+
+/// 读取凭据（不存在时返回 None）
+pub fn get_secret(key: &str) -> Result<Option<String>, AppError> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::Message(format!(
+            "读取系统钥匙串失败 ({key}): {e}"
+        ))),
+    }
+}
+
+/// 删除凭据（不存在时视为成功）
+pub fn delete_secret(key: &str) -> Result<(), AppError> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Message(format!(
+            "删除系统钥匙串凭据失败 ({key}): {e}"
+        ))),
+    }
+}
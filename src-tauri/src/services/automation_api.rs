@@ -0,0 +1,352 @@
+//! 本地自动化 HTTP API
+//!
+//! 默认关闭。开启后仅监听 `127.0.0.1`，供脚本、编辑器插件等不经过 Tauri IPC 直接控制
+//! CC Switch。所有请求都需要携带 `Authorization: Bearer <token>`，token 随机生成并
+//! 保存在系统钥匙串中。
+//!
+//! - `GET  /v1/status`：版本、只读模式、代理运行状态与各应用的当前供应商
+//! - `GET  /v1/providers/{app}`：供应商列表
+//! - `GET  /v1/current`、`GET /v1/current/{app}`：当前供应商
+//! - `POST /v1/switch/{app}/{provider-id}`：切换供应商（与托盘切换行为一致）
+//!
+//! 启用状态与端口只能通过专门的命令修改（见 [`set_enabled`]），保存或导入设置时不会改变。
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::services::ProviderService;
+use crate::settings::{self, AutomationApiConfig};
+use crate::store::AppState;
+
+/// 钥匙串中保存 token 的键
+const TOKEN_KEY: &str = "automation-api:token";
+
+/// 存在「当前供应商」的应用（OpenCode 为累加模式）
+const CURRENT_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 自动化 API 状态（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    /// 与请求处理共享，重新生成 token 时无需重启服务
+    token: Arc<RwLock<String>>,
+}
+
+static RUNNING: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+fn lock_running() -> std::sync::MutexGuard<'static, Option<RunningServer>> {
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: Arc<RwLock<String>>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        ApiError(StatusCode::BAD_REQUEST, e.to_string())
+    }
+}
+
+fn generate_token() -> String {
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// 读取 token，不存在时生成并保存
+pub fn token() -> Result<String, AppError> {
+    if let Some(token) = crate::secret_store::get_secret(TOKEN_KEY)? {
+        return Ok(token);
+    }
+    let token = generate_token();
+    crate::secret_store::set_secret(TOKEN_KEY, &token)?;
+    Ok(token)
+}
+
+/// 逐字节比较，耗时与不匹配的位置无关
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+async fn require_token(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    let expected = state
+        .token
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    if !token_matches(&expected, provided) {
+        return Err(ApiError(
+            StatusCode::UNAUTHORIZED,
+            "missing or invalid token".to_string(),
+        ));
+    }
+    Ok(next.run(request).await)
+}
+
+fn parse_app(app: &str) -> Result<AppType, ApiError> {
+    AppType::from_str(app)
+        .map_err(|_| ApiError(StatusCode::NOT_FOUND, format!("unknown app: {app}")))
+}
+
+fn current_provider(app_state: &AppState, app_type: &AppType) -> Result<Value, AppError> {
+    let id = ProviderService::current(app_state, app_type.clone())?;
+    if id.is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(
+        match app_state.db.get_provider_by_id(&id, app_type.as_str())? {
+            Some(provider) => json!({ "id": provider.id, "name": provider.name }),
+            None => Value::Null,
+        },
+    )
+}
+
+fn all_current(app_state: &AppState) -> Result<Value, AppError> {
+    let mut current = Map::new();
+    for app_type in CURRENT_APPS {
+        current.insert(
+            app_type.as_str().to_string(),
+            current_provider(app_state, &app_type)?,
+        );
+    }
+    Ok(Value::Object(current))
+}
+
+async fn status_handler(State(state): State<ApiState>) -> Result<Json<Value>, ApiError> {
+    let app_state = state.app.state::<AppState>();
+    Ok(Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "readOnly": crate::read_only::is_enabled(),
+        "proxyRunning": app_state.proxy_service.is_running().await,
+        "current": all_current(&app_state)?,
+    })))
+}
+
+async fn providers_handler(
+    State(state): State<ApiState>,
+    Path(app): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let app_type = parse_app(&app)?;
+    let app_state = state.app.state::<AppState>();
+    let current = ProviderService::current(&app_state, app_type.clone())?;
+    let providers = ProviderService::list(&app_state, app_type)?
+        .values()
+        .map(|provider| {
+            json!({
+                "id": provider.id,
+                "name": provider.name,
+                "current": provider.id == current,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(Json(Value::Array(providers)))
+}
+
+async fn all_current_handler(State(state): State<ApiState>) -> Result<Json<Value>, ApiError> {
+    Ok(Json(all_current(&state.app.state::<AppState>())?))
+}
+
+async fn current_handler(
+    State(state): State<ApiState>,
+    Path(app): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let app_type = parse_app(&app)?;
+    Ok(Json(current_provider(
+        &state.app.state::<AppState>(),
+        &app_type,
+    )?))
+}
+
+async fn switch_handler(
+    State(state): State<ApiState>,
+    Path((app, provider_id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    let app_type = parse_app(&app)?;
+    let app_handle = state.app.clone();
+    let id = provider_id.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::tray::switch_provider_internal(&app_handle, app_type, id)
+    })
+    .await
+    .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))??;
+
+    log::info!("自动化 API 已切换 {app} 供应商: {provider_id}");
+    Ok(Json(json!({ "app": app, "providerId": provider_id })))
+}
+
+fn build_router(state: ApiState) -> Router {
+    Router::new()
+        .route("/v1/status", get(status_handler))
+        .route("/v1/providers/:app", get(providers_handler))
+        .route("/v1/current", get(all_current_handler))
+        .route("/v1/current/:app", get(current_handler))
+        .route("/v1/switch/:app/:provider_id", post(switch_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// 在指定端口启动服务（已在运行时先停止）
+pub async fn start(app: AppHandle, port: u16) -> Result<(), AppError> {
+    stop();
+
+    let token = Arc::new(RwLock::new(token()?));
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| AppError::Message(format!("自动化 API 监听 {addr} 失败: {e}")))?;
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    *lock_running() = Some(RunningServer {
+        port,
+        shutdown,
+        token: token.clone(),
+    });
+
+    let router = build_router(ApiState { app, token });
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            })
+            .await
+        {
+            log::error!("自动化 API 异常退出: {e}");
+        }
+    });
+
+    log::info!("自动化 API 已启动: http://{addr}");
+    Ok(())
+}
+
+/// 停止服务（未运行时无操作）
+pub fn stop() {
+    if let Some(server) = lock_running().take() {
+        let _ = server.shutdown.send(());
+        log::info!("自动化 API 已停止");
+    }
+}
+
+pub fn status() -> AutomationApiStatus {
+    let config = settings::get_settings().automation_api;
+    let running_port = lock_running().as_ref().map(|server| server.port);
+    AutomationApiStatus {
+        enabled: config.enabled,
+        running: running_port.is_some(),
+        port: running_port.unwrap_or(config.port),
+        url: running_port.map(|port| format!("http://127.0.0.1:{port}")),
+    }
+}
+
+/// 开启或关闭自动化 API，并保存到设置
+pub async fn set_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<AutomationApiStatus, AppError> {
+    let mut current = settings::get_settings();
+    let config = AutomationApiConfig {
+        enabled,
+        port: port.unwrap_or(current.automation_api.port),
+    };
+
+    let running_port = lock_running().as_ref().map(|server| server.port);
+    if enabled {
+        if running_port != Some(config.port) {
+            start(app, config.port).await?;
+        }
+    } else {
+        stop();
+    }
+    current.automation_api = config;
+    settings::update_settings(current)?;
+    Ok(status())
+}
+
+/// 重新生成 token，旧 token 立即失效
+pub fn regenerate_token() -> Result<String, AppError> {
+    let token = generate_token();
+    crate::secret_store::set_secret(TOKEN_KEY, &token)?;
+    if let Some(server) = lock_running().as_ref() {
+        *server.token.write().unwrap_or_else(|e| e.into_inner()) = token.clone();
+    }
+    Ok(token)
+}
+
+/// 应用启动时按设置启动服务
+pub fn start_if_enabled(app: AppHandle) {
+    let config = settings::get_settings().automation_api;
+    if !config.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start(app, config.port).await {
+            log::error!("启动自动化 API 失败: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_tokens_exactly() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc123", "abc124"));
+        assert!(!token_matches("abc123", "abc12"));
+        assert!(!token_matches("abc123", ""));
+        assert_eq!(generate_token().len(), 64);
+    }
+}
@@ -0,0 +1,302 @@
+//! 自动化规则
+//!
+//! 每条规则由一个触发条件和一个动作组成，保存在数据库 `automation_rules` 表中：
+//!
+//! - 触发条件：cron 表达式（`schedule`）、文件修改（`fileChanged`）、网络变化
+//!   （`networkChanged`，本机出口地址改变，如切换 Wi-Fi 或连上 VPN）；
+//! - 动作：切换供应商、对当前供应商做健康检查、创建备份。
+//!
+//! 本模块只负责规则定义、校验与触发条件的计算，后台轮询与动作执行见 `rule_engine`。
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+
+/// 自动化规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRule {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    pub action: RuleAction,
+    /// 最近一次执行时间（毫秒时间戳）
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// 最近一次执行结果，失败时以 `error: ` 开头
+    #[serde(default)]
+    pub last_result: Option<String>,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 触发条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleTrigger {
+    /// 五段式 cron 表达式（分 时 日 月 周），按本地时间计算
+    Schedule { cron: String },
+    /// 文件修改时间变化（支持 `~` 开头的路径）
+    FileChanged { path: String },
+    /// 本机出口地址变化
+    NetworkChanged,
+}
+
+/// 动作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleAction {
+    #[serde(rename_all = "camelCase")]
+    SwitchProvider {
+        app: AppType,
+        provider_id: String,
+    },
+    /// 对应用的当前供应商执行流式健康检查
+    HealthCheck {
+        app: AppType,
+    },
+    CreateBackup,
+}
+
+impl AutomationRule {
+    /// 保存前校验
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::InvalidInput("规则名称不能为空".to_string()));
+        }
+        match &self.trigger {
+            RuleTrigger::Schedule { cron } => {
+                CronSchedule::from_str(cron)?;
+            }
+            RuleTrigger::FileChanged { path } if path.trim().is_empty() => {
+                return Err(AppError::InvalidInput("监听的文件路径不能为空".to_string()));
+            }
+            _ => {}
+        }
+        match &self.action {
+            RuleAction::SwitchProvider {
+                app: AppType::OpenCode,
+                ..
+            } => Err(AppError::InvalidInput(
+                "OpenCode 为累加模式，不支持切换供应商".to_string(),
+            )),
+            RuleAction::SwitchProvider { provider_id, .. } if provider_id.trim().is_empty() => {
+                Err(AppError::InvalidInput("未指定目标供应商".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 解析后的 cron 表达式
+///
+/// 支持 `*`、数字、范围 `a-b`、列表 `a,b` 与步长 `*/n`、`a-b/n`；星期中 0 和 7 都表示
+/// 周日。与标准 cron 相同，日和星期都被限定时，满足其一即可。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("无效的步长: {part}"))?,
+            ),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start
+                .parse::<u32>()
+                .map_err(|_| format!("无效的范围: {part}"))?;
+            let end = end
+                .parse::<u32>()
+                .map_err(|_| format!("无效的范围: {part}"))?;
+            (start, end)
+        } else {
+            let value = range
+                .parse::<u32>()
+                .map_err(|_| format!("无效的值: {part}"))?;
+            // `5/15` 表示从 5 开始每 15 个单位
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("超出范围 {min}-{max}: {part}"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1u64 << value;
+        }
+    }
+    Ok(mask)
+}
+
+impl FromStr for CronSchedule {
+    type Err = AppError;
+
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let fields = expr.split_whitespace().collect::<Vec<_>>();
+        let invalid = |reason: String| {
+            AppError::InvalidInput(format!("无效的 cron 表达式 \"{expr}\": {reason}"))
+        };
+        if fields.len() != 5 {
+            return Err(invalid("需要 5 个字段（分 时 日 月 周）".to_string()));
+        }
+
+        let mut days_of_week = parse_cron_field(fields[4], 0, 7).map_err(invalid)?;
+        if days_of_week & (1u64 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes: parse_cron_field(fields[0], 0, 59).map_err(invalid)?,
+            hours: parse_cron_field(fields[1], 0, 23).map_err(invalid)?,
+            days_of_month: parse_cron_field(fields[2], 1, 31).map_err(invalid)?,
+            months: parse_cron_field(fields[3], 1, 12).map_err(invalid)?,
+            days_of_week,
+            day_of_month_any: fields[2] == "*",
+            day_of_week_any: fields[4] == "*",
+        })
+    }
+}
+
+impl CronSchedule {
+    /// 给定时间所在的分钟是否命中
+    pub fn matches<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        let hit = |mask: u64, value: u32| mask & (1u64 << value) != 0;
+        let day_of_month = hit(self.days_of_month, time.day());
+        let day_of_week = hit(self.days_of_week, time.weekday().num_days_from_sunday());
+        let day = match (self.day_of_month_any, self.day_of_week_any) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        };
+        day && hit(self.minutes, time.minute())
+            && hit(self.hours, time.hour())
+            && hit(self.months, time.month())
+    }
+}
+
+/// 文件触发器监听的路径
+pub fn watched_path(path: &str) -> PathBuf {
+    crate::settings::resolve_override_path(path.trim())
+}
+
+/// 文件的修改时间（不存在时为 None，文件被创建或删除同样视为变化）
+pub fn file_fingerprint(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(watched_path(path))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// 本机出口地址
+///
+/// 对公网地址建立 UDP "连接" 只会查路由表、不会发包，由此得到默认路由所用的本机地址；
+/// 网络不可用时为 None。
+pub fn network_fingerprint() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))).ok()?;
+    socket
+        .connect(SocketAddr::from((Ipv4Addr::new(1, 1, 1, 1), 53)))
+        .ok()?;
+    socket.local_addr().ok().map(|addr| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn cron_matches_fields() {
+        // 工作日 9:00-18:00 每 30 分钟
+        let cron = CronSchedule::from_str("*/30 9-18 * * 1-5").unwrap();
+        assert!(cron.matches(&at(2025, 1, 6, 9, 0))); // 周一
+        assert!(cron.matches(&at(2025, 1, 6, 18, 30)));
+        assert!(!cron.matches(&at(2025, 1, 6, 9, 15)));
+        assert!(!cron.matches(&at(2025, 1, 5, 9, 0))); // 周日
+
+        // 日与星期同时限定时满足其一即可；7 表示周日
+        let cron = CronSchedule::from_str("0 0 1 * 7").unwrap();
+        assert!(cron.matches(&at(2025, 1, 1, 0, 0))); // 1 号（周三）
+        assert!(cron.matches(&at(2025, 1, 5, 0, 0))); // 周日
+        assert!(!cron.matches(&at(2025, 1, 6, 0, 0)));
+
+        let cron = CronSchedule::from_str("5/20 0,12 * 6 *").unwrap();
+        assert!(cron.matches(&at(2025, 6, 3, 12, 45)));
+        assert!(!cron.matches(&at(2025, 6, 3, 12, 40)));
+        assert!(!cron.matches(&at(2025, 7, 3, 12, 45)));
+    }
+
+    #[test]
+    fn cron_rejects_invalid_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(CronSchedule::from_str(expr).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn rule_round_trips_and_validates() {
+        let rule: AutomationRule = serde_json::from_value(json!({
+            "name": "Night relay",
+            "trigger": { "type": "schedule", "cron": "0 22 * * *" },
+            "action": { "type": "switchProvider", "app": "claude", "providerId": "relay" }
+        }))
+        .unwrap();
+        assert!(rule.enabled);
+        assert!(rule.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&rule.action).unwrap(),
+            json!({ "type": "switchProvider", "app": "claude", "providerId": "relay" })
+        );
+
+        let invalid = AutomationRule {
+            action: RuleAction::SwitchProvider {
+                app: AppType::OpenCode,
+                provider_id: "relay".to_string(),
+            },
+            ..rule
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,480 @@
+//! 定时自动备份
+//!
+//! 每个备份是 `~/.cc-switch/backups/auto/<id>/` 下的一个目录：
+//!
+//! ```text
+//! <id>/
+//! ├── manifest.json   - 备份元信息、live 文件原始路径与各文件 SHA-256
+//! ├── cc-switch.db    - 数据库一致性快照
+//! └── live/           - 各应用 live 配置文件副本
+//! ```
+//!
+//! 保留策略：每天保留最新一份（最近 N 天），每周保留最新一份（最近 M 周），
+//! 最新的一份始终保留。
+//!
+//! 恢复前会校验各文件的 SHA-256，截断或损坏的备份直接报错而不是静默导入。
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::config::{get_app_config_dir, live_config_files};
+use crate::database::Database;
+use crate::error::AppError;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const DB_FILE: &str = "cc-switch.db";
+/// 启用静态加密时改为保存加密后的 SQL 导出，备份目录中不出现明文数据库
+const SEALED_DB_FILE: &str = "cc-switch.sql.enc";
+const LIVE_DIR: &str = "live";
+
+/// 备份中的 live 文件记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveFileRecord {
+    pub app: String,
+    /// 原始绝对路径（恢复时写回此处）
+    pub original_path: String,
+    /// 备份目录内的相对路径
+    pub stored: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupManifest {
+    id: String,
+    created_at: i64,
+    live_files: Vec<LiveFileRecord>,
+    /// 备份目录内相对路径 -> SHA-256（旧版本备份没有此字段）
+    #[serde(default)]
+    checksums: BTreeMap<String, String>,
+}
+
+/// 备份列表条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupEntry {
+    pub id: String,
+    pub created_at: i64,
+    pub size: u64,
+    pub live_files: Vec<LiveFileRecord>,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRestoreResult {
+    /// 恢复前自动生成的数据库备份 ID
+    pub safety_backup_id: String,
+    pub restored_files: usize,
+}
+
+/// 完整性校验结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupVerification {
+    pub id: String,
+    /// 已校验的文件数
+    pub checked_files: usize,
+    /// 备份未记录校验和（旧版本创建），无法校验
+    pub legacy: bool,
+    /// 缺失或校验和不一致的文件
+    pub problems: Vec<String>,
+}
+
+impl BackupVerification {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// 校验失败时转为错误，用于恢复前拦截
+    pub(crate) fn ensure_valid(&self) -> Result<(), AppError> {
+        if self.legacy {
+            log::warn!("备份 {} 未记录校验和，跳过完整性校验", self.id);
+        }
+        if self.is_valid() {
+            return Ok(());
+        }
+        Err(AppError::localized(
+            "backup.integrity_failed",
+            format!(
+                "备份 {} 完整性校验失败，已取消恢复：{}",
+                self.id,
+                self.problems.join("; ")
+            ),
+            format!(
+                "Backup {} failed integrity verification; restore aborted: {}",
+                self.id,
+                self.problems.join("; ")
+            ),
+        ))
+    }
+}
+
+/// 按记录的校验和逐个比对文件（`read` 返回 None 表示文件缺失）
+pub(crate) fn verify_checksums(
+    id: &str,
+    checksums: &BTreeMap<String, String>,
+    mut read: impl FnMut(&str) -> Option<Vec<u8>>,
+) -> BackupVerification {
+    let mut problems = Vec::new();
+    for (name, expected) in checksums {
+        match read(name) {
+            Some(data) if crate::crypto::sha256_hex(&data) == *expected => {}
+            Some(_) => problems.push(format!("{name}: 校验和不一致")),
+            None => problems.push(format!("{name}: 文件缺失")),
+        }
+    }
+    BackupVerification {
+        id: id.to_string(),
+        checked_files: checksums.len(),
+        legacy: checksums.is_empty(),
+        problems,
+    }
+}
+
+/// 自动备份业务
+pub struct BackupService;
+
+impl BackupService {
+    pub fn backups_dir() -> PathBuf {
+        get_app_config_dir().join("backups").join("auto")
+    }
+
+    fn backup_dir(id: &str) -> Result<PathBuf, AppError> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(AppError::InvalidInput(format!("无效的备份 ID: {id}")));
+        }
+        Ok(Self::backups_dir().join(id))
+    }
+
+    /// 创建一份备份（数据库 + live 配置）
+    pub fn create_backup(db: &Database) -> Result<BackupEntry, AppError> {
+        let now = Local::now();
+        let root = Self::backups_dir();
+        let base_id = format!("auto_{}", now.format("%Y%m%d_%H%M%S"));
+        let mut id = base_id.clone();
+        let mut counter = 1;
+        while root.join(&id).exists() {
+            id = format!("{base_id}_{counter}");
+            counter += 1;
+        }
+
+        let dir = root.join(&id);
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+
+        let result = Self::write_backup(db, &dir, &id, now.timestamp_millis());
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&dir);
+        }
+        result
+    }
+
+    fn write_backup(
+        db: &Database,
+        dir: &Path,
+        id: &str,
+        created_at: i64,
+    ) -> Result<BackupEntry, AppError> {
+        let mut checksums = BTreeMap::new();
+        if db.is_encrypted_at_rest() {
+            let sealed = db.seal_at_rest(db.export_sql_string()?.into_bytes())?;
+            let sealed_path = dir.join(SEALED_DB_FILE);
+            fs::write(&sealed_path, &sealed).map_err(|e| AppError::io(&sealed_path, e))?;
+            checksums.insert(
+                SEALED_DB_FILE.to_string(),
+                crate::crypto::sha256_hex(&sealed),
+            );
+        } else {
+            let db_path = dir.join(DB_FILE);
+            db.backup_to_file(&db_path)?;
+            let db_data = fs::read(&db_path).map_err(|e| AppError::io(&db_path, e))?;
+            checksums.insert(DB_FILE.to_string(), crate::crypto::sha256_hex(&db_data));
+        }
+
+        let mut live_files = Vec::new();
+        for (app, name, path) in live_config_files() {
+            if !path.is_file() {
+                continue;
+            }
+            let stored = format!("{LIVE_DIR}/{}/{name}", app.as_str());
+            let target = dir.join(&stored);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+            }
+            let data = fs::read(&path).map_err(|e| AppError::io(&path, e))?;
+            fs::write(&target, &data).map_err(|e| AppError::io(&target, e))?;
+            checksums.insert(stored.clone(), crate::crypto::sha256_hex(&data));
+            live_files.push(LiveFileRecord {
+                app: app.as_str().to_string(),
+                original_path: path.to_string_lossy().to_string(),
+                stored,
+            });
+        }
+
+        let manifest = BackupManifest {
+            id: id.to_string(),
+            created_at,
+            live_files,
+            checksums,
+        };
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let json = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        fs::write(&manifest_path, json).map_err(|e| AppError::io(&manifest_path, e))?;
+
+        Ok(BackupEntry {
+            id: manifest.id,
+            created_at,
+            size: dir_size(dir),
+            live_files: manifest.live_files,
+        })
+    }
+
+    fn read_manifest(dir: &Path) -> Option<BackupManifest> {
+        let content = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 列出所有备份（按时间倒序）
+    pub fn list_backups() -> Result<Vec<BackupEntry>, AppError> {
+        let root = Self::backups_dir();
+        let iter = match fs::read_dir(&root) {
+            Ok(iter) => iter,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::io(&root, e)),
+        };
+
+        let mut entries = iter
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let dir = entry.path();
+                let manifest = Self::read_manifest(&dir)?;
+                Some(BackupEntry {
+                    id: manifest.id,
+                    created_at: manifest.created_at,
+                    size: dir_size(&dir),
+                    live_files: manifest.live_files,
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(entries)
+    }
+
+    /// 最近一次备份时间（毫秒）
+    pub fn latest_backup_time() -> Option<i64> {
+        Self::list_backups()
+            .ok()?
+            .first()
+            .map(|entry| entry.created_at)
+    }
+
+    fn load_manifest(id: &str) -> Result<(PathBuf, BackupManifest), AppError> {
+        let dir = Self::backup_dir(id)?;
+        let manifest = Self::read_manifest(&dir).ok_or_else(|| {
+            AppError::localized(
+                "backup.not_found",
+                format!("备份不存在或已损坏: {id}"),
+                format!("Backup not found or corrupted: {id}"),
+            )
+        })?;
+        Ok((dir, manifest))
+    }
+
+    /// 校验备份中各文件的 SHA-256
+    pub fn verify_backup(id: &str) -> Result<BackupVerification, AppError> {
+        let (dir, manifest) = Self::load_manifest(id)?;
+        Ok(verify_checksums(id, &manifest.checksums, |name| {
+            fs::read(dir.join(name)).ok()
+        }))
+    }
+
+    /// 恢复指定备份：替换数据库并写回 live 配置文件
+    pub fn restore_backup(db: &Database, id: &str) -> Result<BackupRestoreResult, AppError> {
+        let (dir, manifest) = Self::load_manifest(id)?;
+        verify_checksums(id, &manifest.checksums, |name| {
+            fs::read(dir.join(name)).ok()
+        })
+        .ensure_valid()?;
+
+        let sealed_path = dir.join(SEALED_DB_FILE);
+        let safety_backup_id = if sealed_path.is_file() {
+            let data = fs::read(&sealed_path).map_err(|e| AppError::io(&sealed_path, e))?;
+            let sql = String::from_utf8(db.open_at_rest(data)?)
+                .map_err(|e| AppError::InvalidInput(format!("备份数据库内容无效: {e}")))?;
+            db.import_sql_string(&sql)?
+        } else {
+            db.restore_from_file(&dir.join(DB_FILE))?
+        };
+
+        let mut restored_files = 0;
+        for record in &manifest.live_files {
+            let source = dir.join(&record.stored);
+            let data = match fs::read(&source) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("备份中的 live 文件缺失 {}: {e}", source.display());
+                    continue;
+                }
+            };
+            let target = PathBuf::from(&record.original_path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+            }
+            crate::config::atomic_write(&target, &data)?;
+            restored_files += 1;
+        }
+
+        log::info!("已恢复备份 {id}（{restored_files} 个 live 文件）");
+        Ok(BackupRestoreResult {
+            safety_backup_id,
+            restored_files,
+        })
+    }
+
+    /// 按保留策略清理旧备份，返回删除数量
+    pub fn prune_backups(keep_daily: u32, keep_weekly: u32) -> Result<usize, AppError> {
+        let entries = Self::list_backups()?;
+        let stamps = entries
+            .iter()
+            .filter_map(|e| {
+                Local
+                    .timestamp_millis_opt(e.created_at)
+                    .single()
+                    .map(|t| (e.id.clone(), t))
+            })
+            .collect::<Vec<_>>();
+        let retained = retained_ids(&stamps, keep_daily, keep_weekly);
+
+        let mut removed = 0;
+        for entry in entries.iter().filter(|e| !retained.contains(&e.id)) {
+            let dir = Self::backups_dir().join(&entry.id);
+            match fs::remove_dir_all(&dir) {
+                Ok(()) => removed += 1,
+                Err(e) => log::warn!("删除旧备份失败 {}: {e}", dir.display()),
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// 计算需要保留的备份 ID（输入顺序任意）
+fn retained_ids(
+    entries: &[(String, DateTime<Local>)],
+    keep_daily: u32,
+    keep_weekly: u32,
+) -> HashSet<String> {
+    let mut sorted = entries.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mut retained = HashSet::new();
+    let mut days = HashSet::new();
+    let mut weeks = HashSet::new();
+
+    for (idx, (id, time)) in sorted.into_iter().enumerate() {
+        let day = time.date_naive();
+        let week = (time.iso_week().year(), time.iso_week().week());
+
+        let mut keep = idx == 0;
+        if days.len() < keep_daily as usize && days.insert(day) {
+            keep = true;
+        }
+        if weeks.len() < keep_weekly as usize && weeks.insert(week) {
+            keep = true;
+        }
+        if keep {
+            retained.insert(id.clone());
+        }
+    }
+    retained
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(iter) = fs::read_dir(dir) else {
+        return 0;
+    };
+    iter.filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                dir_size(&path)
+            } else {
+                entry.metadata().map(|m| m.len()).unwrap_or(0)
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn keeps_latest_per_day_within_daily_window() {
+        let entries = vec![
+            ("a".to_string(), at(10, 8)),
+            ("b".to_string(), at(10, 20)),
+            ("c".to_string(), at(9, 20)),
+            ("d".to_string(), at(8, 20)),
+        ];
+        let retained = retained_ids(&entries, 2, 0);
+        assert_eq!(retained, HashSet::from(["b".to_string(), "c".to_string()]));
+    }
+
+    #[test]
+    fn weekly_window_keeps_older_weeks() {
+        // 2024-01-01 为周一；15、8、1 日分属三个不同的 ISO 周
+        let entries = vec![
+            ("w3".to_string(), at(15, 12)),
+            ("w2".to_string(), at(8, 12)),
+            ("w1".to_string(), at(1, 12)),
+        ];
+        let retained = retained_ids(&entries, 1, 2);
+        assert_eq!(
+            retained,
+            HashSet::from(["w3".to_string(), "w2".to_string()])
+        );
+    }
+
+    #[test]
+    fn newest_backup_is_always_retained() {
+        let entries = vec![("only".to_string(), at(1, 0))];
+        assert!(retained_ids(&entries, 0, 0).contains("only"));
+    }
+
+    #[test]
+    fn verify_checksums_reports_corrupted_and_missing_files() {
+        let checksums = BTreeMap::from([
+            ("a".to_string(), crate::crypto::sha256_hex(b"alpha")),
+            ("b".to_string(), crate::crypto::sha256_hex(b"beta")),
+            ("c".to_string(), crate::crypto::sha256_hex(b"gamma")),
+        ]);
+        let files = BTreeMap::from([("a", b"alpha".to_vec()), ("b", b"bet".to_vec())]);
+
+        let result = verify_checksums("x", &checksums, |name| files.get(name).cloned());
+        assert_eq!(result.checked_files, 3);
+        assert!(!result.legacy);
+        assert_eq!(result.problems.len(), 2);
+        assert!(result.ensure_valid().is_err());
+
+        let legacy = verify_checksums("old", &BTreeMap::new(), |_| None);
+        assert!(legacy.legacy);
+        assert!(legacy.ensure_valid().is_ok());
+    }
+
+    #[test]
+    fn backup_id_rejects_path_traversal() {
+        assert!(BackupService::backup_dir("../etc").is_err());
+        assert!(BackupService::backup_dir("").is_err());
+        assert!(BackupService::backup_dir("auto_20240101_000000").is_ok());
+    }
+}
@@ -0,0 +1,264 @@
+//! 中转站余额查询
+//!
+//! 供应商可在元数据中配置余额接口（`meta.balanceQuery`，地址 + 可选令牌），比用量脚本
+//! 更简单：直接 `GET` 接口并识别常见的响应格式：
+//!
+//! - new-api 令牌用量 `/api/usage/token`：`data.total_available` / `data.total_used`
+//! - one-api / new-api 用户信息 `/api/user/self`：`data.quota` / `data.used_quota`
+//! - OpenAI 兼容 `/dashboard/billing/credit_grants`：`total_available` / `total_used`
+//! - 其他接口：顶层或 `data` 下的 `balance` / `remaining` / `used` / `total` 字段
+//!
+//! one-api / new-api 的额度以内部单位返回（500000 = 1 美元），这里统一换算为美元。
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+
+/// 请求超时
+const QUERY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// one-api / new-api 每美元对应的额度
+const QUOTA_PER_USD: f64 = 500_000.0;
+
+/// 供应商余额
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBalance {
+    /// 剩余额度，不限额时为空
+    pub remaining: Option<f64>,
+    pub used: Option<f64>,
+    pub total: Option<f64>,
+    pub unit: String,
+    /// 不限额度
+    pub unlimited: bool,
+    /// Unix 时间戳（秒）
+    pub checked_at: i64,
+}
+
+fn number(value: &Value, key: &str) -> Option<f64> {
+    match value.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 识别余额接口的响应
+fn parse_balance(body: &Value) -> Option<ProviderBalance> {
+    let data = body.get("data").filter(|data| data.is_object());
+    let balance = |remaining: Option<f64>, used: Option<f64>, total: Option<f64>| {
+        let total = total.or_else(|| Some(remaining? + used?));
+        ProviderBalance {
+            remaining: remaining.or_else(|| Some(total? - used?)),
+            used,
+            total,
+            unit: "USD".to_string(),
+            unlimited: false,
+            checked_at: chrono::Utc::now().timestamp(),
+        }
+    };
+    let quota = |value: Option<f64>| value.map(|v| v / QUOTA_PER_USD);
+
+    if let Some(data) = data {
+        // new-api 令牌用量
+        if data.get("total_available").is_some() {
+            let unlimited = data
+                .get("unlimited_quota")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let used = quota(number(data, "total_used"));
+            if unlimited {
+                return Some(ProviderBalance {
+                    unlimited: true,
+                    ..balance(None, used, None)
+                });
+            }
+            return Some(balance(
+                quota(number(data, "total_available")),
+                used,
+                quota(number(data, "total_granted")),
+            ));
+        }
+        // one-api / new-api 用户信息
+        if data.get("quota").is_some() {
+            return Some(balance(
+                quota(number(data, "quota")),
+                quota(number(data, "used_quota")),
+                None,
+            ));
+        }
+    }
+
+    // OpenAI 兼容 credit_grants（已是美元）
+    if body.get("total_available").is_some() {
+        return Some(balance(
+            number(body, "total_available"),
+            number(body, "total_used"),
+            number(body, "total_granted"),
+        ));
+    }
+
+    [Some(body), data].into_iter().flatten().find_map(|value| {
+        let remaining = number(value, "balance").or_else(|| number(value, "remaining"));
+        let used = number(value, "used");
+        let total = number(value, "total");
+        if remaining.is_none() && total.is_none() {
+            return None;
+        }
+        let unit = value
+            .get("unit")
+            .or_else(|| value.get("currency"))
+            .and_then(Value::as_str)
+            .unwrap_or("USD")
+            .to_string();
+        Some(ProviderBalance {
+            unit,
+            ..balance(remaining, used, total)
+        })
+    })
+}
+
+/// 按供应商的余额查询配置查询余额
+pub async fn query_balance(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<ProviderBalance, AppError> {
+    let query = provider
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.balance_query.as_ref())
+        .filter(|query| !query.url.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.balance.missing",
+                "未配置余额查询接口",
+                "Balance query is not configured",
+            )
+        })?;
+    let token = query
+        .token
+        .clone()
+        .filter(|token| !token.is_empty())
+        .or_else(|| match app_type {
+            AppType::OpenCode => provider
+                .settings_config
+                .pointer("/options/apiKey")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            _ => get_adapter(app_type)
+                .extract_auth(provider)
+                .map(|auth| auth.api_key),
+        })
+        .unwrap_or_default();
+
+    let client = crate::proxy::http_client::get();
+    let mut request = client
+        .get(query.url.trim())
+        .header("Accept", "application/json")
+        .timeout(QUERY_TIMEOUT);
+    if !token.is_empty() {
+        request = request.bearer_auth(&token);
+    }
+    let response = request.send().await.map_err(|e| {
+        AppError::localized(
+            "provider.balance.request_failed",
+            format!("余额查询失败: {e}"),
+            format!("Balance request failed: {e}"),
+        )
+    })?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let snippet = body.chars().take(200).collect::<String>();
+        return Err(AppError::localized(
+            "provider.balance.http_error",
+            format!("余额接口返回 HTTP {status}: {snippet}"),
+            format!("Balance endpoint returned HTTP {status}: {snippet}"),
+        ));
+    }
+    serde_json::from_str::<Value>(&body)
+        .ok()
+        .as_ref()
+        .and_then(parse_balance)
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.balance.unrecognized",
+                "无法识别余额接口的响应格式",
+                "Unrecognized balance response format",
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_new_api_token_usage() {
+        let balance = parse_balance(&json!({
+            "code": true,
+            "data": {
+                "total_available": 5_000_000,
+                "total_used": 1_000_000,
+                "total_granted": 6_000_000,
+                "unlimited_quota": false
+            }
+        }))
+        .unwrap();
+        assert_eq!(balance.remaining, Some(10.0));
+        assert_eq!(balance.used, Some(2.0));
+        assert_eq!(balance.total, Some(12.0));
+        assert!(!balance.unlimited);
+
+        let unlimited = parse_balance(&json!({
+            "data": { "total_available": 0, "total_used": 500_000, "unlimited_quota": true }
+        }))
+        .unwrap();
+        assert!(unlimited.unlimited);
+        assert_eq!(unlimited.remaining, None);
+        assert_eq!(unlimited.used, Some(1.0));
+    }
+
+    #[test]
+    fn parses_one_api_user_and_openai_credit_grants() {
+        let user = parse_balance(&json!({
+            "success": true,
+            "data": { "quota": 2_500_000, "used_quota": 500_000 }
+        }))
+        .unwrap();
+        assert_eq!(user.remaining, Some(5.0));
+        assert_eq!(user.total, Some(6.0));
+
+        let grants = parse_balance(&json!({
+            "total_granted": 20.0,
+            "total_used": 7.5,
+            "total_available": 12.5
+        }))
+        .unwrap();
+        assert_eq!(grants.remaining, Some(12.5));
+        assert_eq!(grants.used, Some(7.5));
+    }
+
+    #[test]
+    fn parses_generic_balance_fields() {
+        let balance = parse_balance(&json!({
+            "data": { "balance": "42.5", "currency": "CNY" }
+        }))
+        .unwrap();
+        assert_eq!(balance.remaining, Some(42.5));
+        assert_eq!(balance.unit, "CNY");
+
+        let derived = parse_balance(&json!({ "total": 100, "used": 30 })).unwrap();
+        assert_eq!(derived.remaining, Some(70.0));
+
+        assert!(parse_balance(&json!({ "message": "ok" })).is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! 供应商端点测速
+//!
+//! 对应用的每个供应商发送多次极小的流式请求（与健康检查相同的请求与测试模型），记录
+//! 首字节耗时（TTFB）与完整响应耗时，取中位数保存到数据库，界面据此按速度排序。
+//! 每个供应商只保留最近一次的结果。
+//!
+//! 同一供应商的多次请求依次发送，避免互相影响；不同供应商并发测试。
+//!
+//! 自动选择（设置 `autoSelect`）定期测试候选供应商，由 [`pick_faster`] 决定是否切换。
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::stream_check::{StreamCheckConfig, StreamCheckService};
+
+/// 默认每个供应商的请求次数
+pub const DEFAULT_SAMPLES: u32 = 3;
+const MAX_SAMPLES: u32 = 10;
+
+/// 供应商测速结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBenchmark {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 请求次数
+    pub samples: u32,
+    /// 成功次数
+    pub successes: u32,
+    /// 首字节耗时中位数（毫秒），全部失败时为空
+    pub ttfb_ms: Option<u64>,
+    /// 完整响应耗时中位数（毫秒）
+    pub total_ms: Option<u64>,
+    /// 最近一次失败的原因
+    pub error: Option<String>,
+    /// Unix 时间戳（秒）
+    pub tested_at: i64,
+}
+
+fn median(values: &mut [u64]) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+/// 按速度排序：有成功结果的在前，按首字节耗时、完整耗时升序
+pub fn rank(results: &mut [ProviderBenchmark]) {
+    results.sort_by_key(|result| (result.ttfb_ms.is_none(), result.ttfb_ms, result.total_ms));
+}
+
+async fn benchmark_provider(
+    app_type: &AppType,
+    provider: &Provider,
+    config: &StreamCheckConfig,
+    samples: u32,
+) -> ProviderBenchmark {
+    let mut first_bytes = Vec::new();
+    let mut totals = Vec::new();
+    let mut error = None;
+    for _ in 0..samples {
+        match StreamCheckService::measure_once(app_type, provider, config).await {
+            Ok((first_byte, total)) => {
+                first_bytes.push(first_byte);
+                totals.push(total);
+            }
+            Err(e) => error = Some(e.to_string()),
+        }
+    }
+
+    ProviderBenchmark {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        samples,
+        successes: first_bytes.len() as u32,
+        ttfb_ms: median(&mut first_bytes),
+        total_ms: median(&mut totals),
+        error,
+        tested_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// 自动选择：在按速度排序的结果中找出应切换到的供应商
+///
+/// 只考虑所有请求都成功的供应商。当前供应商健康、且最快的供应商首字节耗时没有低出
+/// `margin_percent` 时不切换（迟滞），避免在速度相近的供应商之间来回切换。
+pub fn pick_faster<'a>(
+    ranked: &'a [ProviderBenchmark],
+    current: &str,
+    margin_percent: u32,
+) -> Option<&'a ProviderBenchmark> {
+    let healthy = |result: &&ProviderBenchmark| {
+        result.samples > 0 && result.successes == result.samples && result.ttfb_ms.is_some()
+    };
+    let fastest = ranked.iter().find(healthy)?;
+    if fastest.provider_id == current {
+        return None;
+    }
+    let current_ttfb = ranked
+        .iter()
+        .filter(healthy)
+        .find(|result| result.provider_id == current)
+        .and_then(|result| result.ttfb_ms);
+    match (current_ttfb, fastest.ttfb_ms) {
+        (Some(current), Some(fastest_ttfb))
+            if fastest_ttfb * 100 > current * u64::from(100 - margin_percent.min(100)) =>
+        {
+            None
+        }
+        _ => Some(fastest),
+    }
+}
+
+/// 测试应用的所有供应商并保存结果，返回按速度排序的结果
+pub async fn benchmark_providers(
+    db: &Database,
+    app_type: &AppType,
+    samples: u32,
+) -> Result<Vec<ProviderBenchmark>, AppError> {
+    benchmark_candidates(db, app_type, &[], samples).await
+}
+
+/// 测试指定的供应商（为空时测试全部）并保存结果，返回按速度排序的结果
+pub async fn benchmark_candidates(
+    db: &Database,
+    app_type: &AppType,
+    candidates: &[String],
+    samples: u32,
+) -> Result<Vec<ProviderBenchmark>, AppError> {
+    if *app_type == AppType::OpenCode {
+        return Err(AppError::localized(
+            "opencode_no_stream_check",
+            "OpenCode 暂不支持健康检查",
+            "OpenCode does not support health check yet",
+        ));
+    }
+    let samples = samples.clamp(1, MAX_SAMPLES);
+    let config = db.get_stream_check_config()?;
+    let providers = db.get_all_providers(app_type.as_str())?;
+
+    let mut results = join_all(
+        providers
+            .values()
+            .filter(|provider| candidates.is_empty() || candidates.contains(&provider.id))
+            .map(|provider| benchmark_provider(app_type, provider, &config, samples)),
+    )
+    .await;
+    for result in &results {
+        db.save_provider_benchmark(result)?;
+    }
+    rank(&mut results);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, ttfb_ms: Option<u64>, total_ms: Option<u64>) -> ProviderBenchmark {
+        ProviderBenchmark {
+            app_type: "claude".to_string(),
+            provider_id: id.to_string(),
+            provider_name: id.to_string(),
+            samples: 3,
+            successes: if ttfb_ms.is_some() { 3 } else { 0 },
+            ttfb_ms,
+            total_ms,
+            error: None,
+            tested_at: 0,
+        }
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&mut []), None);
+        assert_eq!(median(&mut [300, 100, 200]), Some(200));
+        assert_eq!(median(&mut [400, 100, 200, 300]), Some(250));
+    }
+
+    #[test]
+    fn ranks_fastest_first_and_failures_last() {
+        let mut results = vec![
+            result("down", None, None),
+            result("slow", Some(900), Some(1200)),
+            result("fast", Some(200), Some(800)),
+            result("fast-finish", Some(200), Some(500)),
+        ];
+        rank(&mut results);
+        let ids = results
+            .iter()
+            .map(|result| result.provider_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["fast-finish", "fast", "slow", "down"]);
+    }
+
+    #[test]
+    fn picks_faster_provider_with_hysteresis() {
+        let ranked = vec![
+            result("fast", Some(700), Some(900)),
+            result("current", Some(800), Some(1000)),
+            result("down", None, None),
+        ];
+        // 只快 12.5%，低于 20% 的阈值
+        assert!(pick_faster(&ranked, "current", 20).is_none());
+        assert_eq!(
+            pick_faster(&ranked, "current", 10).map(|r| r.provider_id.as_str()),
+            Some("fast")
+        );
+        // 当前供应商不可用或不在候选中时直接切换
+        assert_eq!(
+            pick_faster(&ranked, "down", 20).map(|r| r.provider_id.as_str()),
+            Some("fast")
+        );
+        assert_eq!(
+            pick_faster(&ranked, "other", 20).map(|r| r.provider_id.as_str()),
+            Some("fast")
+        );
+        assert!(pick_faster(&ranked, "fast", 20).is_none());
+
+        let flaky = ProviderBenchmark {
+            successes: 2,
+            ..result("flaky", Some(100), Some(200))
+        };
+        let ranked = vec![flaky, result("current", Some(800), Some(1000))];
+        assert!(pick_faster(&ranked, "current", 20).is_none());
+    }
+}
@@ -2,6 +2,7 @@ use super::provider::ProviderService;
 use crate::app_config::{AppType, MultiAppConfig};
 use crate::error::AppError;
 use crate::provider::Provider;
+use crate::store::AppState;
 use chrono::Utc;
 use serde_json::Value;
 use std::fs;
@@ -13,6 +14,19 @@ const MAX_BACKUPS: usize = 10;
 pub struct ConfigService;
 
 impl ConfigService {
+    /// 数据库整体被替换（SQL 导入、远程同步下载等）后刷新运行时状态
+    ///
+    /// 将当前供应商同步到 live 配置，并重新加载设备级设置缓存。失败只记录日志。
+    pub fn refresh_after_database_restore(state: &AppState) {
+        if let Err(err) = ProviderService::sync_current_to_live(state) {
+            log::warn!("导入后同步 live 配置失败: {err}");
+        }
+
+        if let Err(err) = crate::settings::reload_settings() {
+            log::warn!("导入后重载设置失败: {err}");
+        }
+    }
+
     /// 为当前 config.json 创建备份，返回备份 ID（若文件不存在则返回空字符串）。
     pub fn create_backup(config_path: &Path) -> Result<String, AppError> {
         if !config_path.exists() {
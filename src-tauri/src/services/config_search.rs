@@ -0,0 +1,357 @@
+//! 配置全文搜索
+//!
+//! 把各应用供应商的 `settings_config`、元数据、备注与网址，以及 MCP 服务器定义展开为
+//! 「字段 + JSON Pointer + 行」的文本索引，在其中做不区分大小写的子串匹配，例如查找仍然
+//! 指向某个已下线 base URL 的全部供应商。
+//!
+//! 索引缓存在内存中，数据库有新的写入（见 [`Database::change_stamp`]）或切换配置档后
+//! 重建。密钥字段与文本中的密钥在结果中脱敏，且不标注匹配位置。
+
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::sync_secrets::is_secret_field;
+
+/// 建立索引的应用
+const SEARCH_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 默认返回的最大匹配数
+const DEFAULT_LIMIT: usize = 200;
+
+/// 结果中单行文本的最大长度（超出时截取匹配附近的内容）
+const MAX_SNIPPET_CHARS: usize = 160;
+
+/// 匹配所在的对象类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchTarget {
+    Provider,
+    Mcp,
+}
+
+/// 索引中的一个文本值
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    target: SearchTarget,
+    app_type: Option<String>,
+    item_id: String,
+    item_name: String,
+    field: &'static str,
+    path: String,
+    value: String,
+}
+
+/// 一处匹配
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSearchMatch {
+    pub target: SearchTarget,
+    /// 供应商所属的应用（MCP 服务器为空）
+    pub app_type: Option<String>,
+    pub item_id: String,
+    pub item_name: String,
+    /// 字段，如 `settingsConfig`、`meta`、`notes`、`server`
+    pub field: String,
+    /// 字段内的 JSON Pointer（纯文本字段为空）
+    pub path: String,
+    /// 值内的行号（从 1 开始）
+    pub line: usize,
+    /// 匹配所在的行（过长时截取匹配附近的内容）
+    pub snippet: String,
+    /// 匹配在 `snippet` 中的字符范围 `[start, end)`
+    pub ranges: Vec<(usize, usize)>,
+    /// 是否为脱敏后的密钥
+    pub masked: bool,
+}
+
+/// 内存中的索引：(数据库实例, 配置档, 变更标记) -> 索引项
+type SearchIndex = ((usize, String, (i64, i64)), Arc<Vec<IndexEntry>>);
+
+static INDEX: Mutex<Option<SearchIndex>> = Mutex::new(None);
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// 把 JSON 展开为 (JSON Pointer, 文本) 列表
+fn flatten(value: &Value, path: String, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                flatten(child, format!("{path}/{}", escape_pointer(key)), out);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                flatten(child, format!("{path}/{index}"), out);
+            }
+        }
+        Value::String(text) => out.push((path, text.clone())),
+        Value::Number(number) => out.push((path, number.to_string())),
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+/// 建立索引时正在展开的供应商或 MCP 服务器
+struct Item<'a> {
+    target: SearchTarget,
+    app_type: Option<&'a str>,
+    id: &'a str,
+    name: &'a str,
+}
+
+impl Item<'_> {
+    fn entry(&self, field: &'static str, path: String, value: String) -> IndexEntry {
+        IndexEntry {
+            target: self.target,
+            app_type: self.app_type.map(str::to_string),
+            item_id: self.id.to_string(),
+            item_name: self.name.to_string(),
+            field,
+            path,
+            value,
+        }
+    }
+
+    fn push_text(&self, out: &mut Vec<IndexEntry>, field: &'static str, value: Option<&String>) {
+        if let Some(value) = value {
+            out.push(self.entry(field, String::new(), value.clone()));
+        }
+    }
+
+    fn push_json(&self, out: &mut Vec<IndexEntry>, field: &'static str, value: &Value) {
+        let mut values = Vec::new();
+        flatten(value, String::new(), &mut values);
+        out.extend(
+            values
+                .into_iter()
+                .map(|(path, value)| self.entry(field, path, value)),
+        );
+    }
+}
+
+fn build_index(db: &Database) -> Result<Vec<IndexEntry>, AppError> {
+    let mut entries = Vec::new();
+    for app_type in SEARCH_APPS {
+        for provider in db.get_all_providers(app_type.as_str())?.into_values() {
+            let item = Item {
+                target: SearchTarget::Provider,
+                app_type: Some(app_type.as_str()),
+                id: &provider.id,
+                name: &provider.name,
+            };
+            item.push_text(&mut entries, "name", Some(&provider.name));
+            item.push_json(&mut entries, "settingsConfig", &provider.settings_config);
+            if let Some(meta) = provider
+                .meta
+                .as_ref()
+                .and_then(|meta| serde_json::to_value(meta).ok())
+            {
+                item.push_json(&mut entries, "meta", &meta);
+            }
+            item.push_text(&mut entries, "notes", provider.notes.as_ref());
+            item.push_text(&mut entries, "websiteUrl", provider.website_url.as_ref());
+        }
+    }
+
+    for server in db.get_all_mcp_servers()?.into_values() {
+        let item = Item {
+            target: SearchTarget::Mcp,
+            app_type: None,
+            id: &server.id,
+            name: &server.name,
+        };
+        item.push_text(&mut entries, "name", Some(&server.name));
+        item.push_json(&mut entries, "server", &server.server);
+        item.push_text(&mut entries, "description", server.description.as_ref());
+        item.push_text(&mut entries, "homepage", server.homepage.as_ref());
+        item.push_text(&mut entries, "docs", server.docs.as_ref());
+        for (index, tag) in server.tags.iter().enumerate() {
+            entries.push(item.entry("tags", format!("/{index}"), tag.clone()));
+        }
+    }
+    Ok(entries)
+}
+
+/// 当前数据库的索引（有写入时重建）
+fn current_index(db: &Database) -> Result<Arc<Vec<IndexEntry>>, AppError> {
+    let key = (
+        db as *const Database as usize,
+        crate::database::active_profile(),
+        db.change_stamp()?,
+    );
+    let mut cache = INDEX
+        .lock()
+        .map_err(|e| AppError::Message(format!("搜索索引锁失败: {e}")))?;
+    if let Some((cached_key, index)) = cache.as_ref() {
+        if *cached_key == key {
+            return Ok(index.clone());
+        }
+    }
+    let index = Arc::new(build_index(db)?);
+    *cache = Some((key, index.clone()));
+    Ok(index)
+}
+
+/// 不区分大小写查找全部匹配，返回字符范围
+fn find_ranges(line: &[char], query: &[char]) -> Vec<(usize, usize)> {
+    let lower = |c: &char| c.to_lowercase().next().unwrap_or(*c);
+    let line = line.iter().map(lower).collect::<Vec<_>>();
+    let query = query.iter().map(lower).collect::<Vec<_>>();
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start + query.len() <= line.len() {
+        if line[start..start + query.len()] == query[..] {
+            ranges.push((start, start + query.len()));
+            start += query.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+/// 在索引中匹配，每个匹配行返回一条结果
+fn search_index(entries: &[IndexEntry], query: &str, limit: usize) -> Vec<ConfigSearchMatch> {
+    let query = query.chars().collect::<Vec<_>>();
+    let mut matches = Vec::new();
+    for entry in entries {
+        let secret_field = entry.path.rsplit('/').next().is_some_and(is_secret_field);
+        for (line_index, line) in entry.value.lines().enumerate() {
+            let chars = line.chars().collect::<Vec<_>>();
+            let ranges = find_ranges(&chars, &query);
+            let Some(&(first_start, _)) = ranges.first() else {
+                continue;
+            };
+
+            let (snippet, ranges, masked) = if secret_field {
+                (crate::redact::mask_secret(line.trim()), Vec::new(), true)
+            } else if let std::borrow::Cow::Owned(redacted) = crate::redact::redact_secrets(line) {
+                (redacted, Vec::new(), true)
+            } else if chars.len() <= MAX_SNIPPET_CHARS {
+                (line.to_string(), ranges, false)
+            } else {
+                let start = first_start.saturating_sub(MAX_SNIPPET_CHARS / 2);
+                let end = (start + MAX_SNIPPET_CHARS).min(chars.len());
+                let ranges = ranges
+                    .into_iter()
+                    .filter(|(s, e)| *s >= start && *e <= end)
+                    .map(|(s, e)| (s - start, e - start))
+                    .collect();
+                (chars[start..end].iter().collect(), ranges, false)
+            };
+
+            matches.push(ConfigSearchMatch {
+                target: entry.target,
+                app_type: entry.app_type.clone(),
+                item_id: entry.item_id.clone(),
+                item_name: entry.item_name.clone(),
+                field: entry.field.to_string(),
+                path: entry.path.clone(),
+                line: line_index + 1,
+                snippet,
+                ranges,
+                masked,
+            });
+            if matches.len() >= limit {
+                return matches;
+            }
+        }
+    }
+    matches
+}
+
+/// 在全部供应商与 MCP 服务器配置中搜索文本
+pub fn search_configs(
+    db: &Database,
+    query: &str,
+    limit: Option<usize>,
+) -> Result<Vec<ConfigSearchMatch>, AppError> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(AppError::InvalidInput("请输入要搜索的内容".to_string()));
+    }
+    let index = current_index(db)?;
+    Ok(search_index(
+        &index,
+        query,
+        limit.unwrap_or(DEFAULT_LIMIT).max(1),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+
+    #[test]
+    fn finds_base_urls_and_masks_secrets() {
+        let db = Database::memory().expect("create memory db");
+        let relay = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": "https://old.relay.example/api",
+                    "ANTHROPIC_AUTH_TOKEN": "sk-old-relay-0123456789"
+                }
+            }),
+            None,
+        );
+        db.save_provider("claude", &relay).expect("save provider");
+        let codex = Provider::with_id(
+            "codex".to_string(),
+            "Codex Relay".to_string(),
+            json!({
+                "config": "model = \"gpt-5\"\nbase_url = \"https://OLD.relay.example/v1\"\n"
+            }),
+            None,
+        );
+        db.save_provider("codex", &codex).expect("save provider");
+
+        let matches = search_configs(&db, "old.relay", None).expect("search");
+        assert_eq!(matches.len(), 2);
+        let url = matches
+            .iter()
+            .find(|m| m.path == "/env/ANTHROPIC_BASE_URL")
+            .expect("base url match");
+        assert_eq!(url.app_type.as_deref(), Some("claude"));
+        assert_eq!(url.field, "settingsConfig");
+        assert_eq!(url.ranges, vec![(8, 17)]);
+        let toml = matches
+            .iter()
+            .find(|m| m.path == "/config")
+            .expect("codex config match");
+        assert_eq!(toml.app_type.as_deref(), Some("codex"));
+        assert_eq!(toml.line, 2);
+        assert_eq!(toml.ranges, vec![(20, 29)]);
+
+        let token = search_configs(&db, "sk-old", None).expect("search");
+        assert_eq!(token.len(), 1);
+        assert_eq!(token[0].path, "/env/ANTHROPIC_AUTH_TOKEN");
+        assert!(token[0].masked);
+        assert!(token[0].ranges.is_empty());
+        assert!(!token[0].snippet.contains("0123456789"));
+
+        // 写入后重建索引
+        db.delete_provider("claude", "relay")
+            .expect("delete provider");
+        assert_eq!(
+            search_configs(&db, "old.relay", None)
+                .expect("search")
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! 成本估算
+//!
+//! 用模型定价表（`model_pricing`，可在用量统计的定价设置中编辑）把会话日志统计出的用量
+//! （见 [`super::session_usage`]）换算成成本，按供应商与日/周/月汇总。供应商设置了成本
+//! 倍数（`meta.costMultiplier`）时按倍数计算，与代理请求日志的成本一致。
+//!
+//! 模型名与代理相同的规则匹配定价（去掉 `vendor/` 前缀与 `:` 后缀）；没有定价的模型不计入
+//! 成本，在报告中单独列出，方便补充定价。
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::str::FromStr;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::database::{lock_conn, Database};
+use crate::error::AppError;
+use crate::proxy::usage::ModelPricing;
+use crate::services::session_usage::{self, SessionUsageRow, UsageRange};
+use crate::services::usage_stats::find_model_pricing_row;
+
+/// 汇总粒度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CostPeriod {
+    #[default]
+    Day,
+    /// 自然周（周一开始）
+    Week,
+    Month,
+}
+
+impl CostPeriod {
+    /// 日期所在周期的标识：日与周为起始日期（`YYYY-MM-DD`），月为 `YYYY-MM`
+    fn key(self, date: &str) -> String {
+        let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return date.to_string();
+        };
+        match self {
+            CostPeriod::Day => date.to_string(),
+            CostPeriod::Week => {
+                let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            CostPeriod::Month => day.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// 一个周期的用量与成本
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodCost {
+    pub period: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// USD
+    pub cost: String,
+}
+
+/// 供应商的成本
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCost {
+    pub app_type: String,
+    /// 为空表示无法判断供应商的用量
+    pub provider_id: String,
+    pub provider_name: String,
+    pub total_cost: String,
+    /// 按周期先后排列
+    pub periods: Vec<PeriodCost>,
+}
+
+/// 成本报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CostReport {
+    pub range: UsageRange,
+    pub period: CostPeriod,
+    pub total_cost: String,
+    /// 按成本从高到低排列
+    pub providers: Vec<ProviderCost>,
+    /// 没有定价、未计入成本的模型
+    pub unpriced_models: Vec<String>,
+}
+
+/// 供应商名称与成本倍数
+struct ProviderInfo {
+    name: String,
+    multiplier: Decimal,
+}
+
+fn row_cost(row: &SessionUsageRow, pricing: &ModelPricing, multiplier: Decimal) -> Decimal {
+    let million = Decimal::from(1_000_000u64);
+    (Decimal::from(row.input_tokens) * pricing.input_cost_per_million
+        + Decimal::from(row.output_tokens) * pricing.output_cost_per_million
+        + Decimal::from(row.cache_read_tokens) * pricing.cache_read_cost_per_million
+        + Decimal::from(row.cache_creation_tokens) * pricing.cache_creation_cost_per_million)
+        / million
+        * multiplier
+}
+
+fn build_report(
+    rows: Vec<SessionUsageRow>,
+    range: UsageRange,
+    period: CostPeriod,
+    pricing: &HashMap<String, Option<ModelPricing>>,
+    providers: &HashMap<(String, String), ProviderInfo>,
+) -> CostReport {
+    let mut unpriced = BTreeSet::new();
+    // (app_type, provider_id) -> period -> (用量, 成本)
+    let mut grouped: BTreeMap<(String, String), BTreeMap<String, (PeriodCost, Decimal)>> =
+        BTreeMap::new();
+
+    for row in rows {
+        let provider = providers.get(&(row.app_type.clone(), row.provider_id.clone()));
+        let multiplier = provider.map_or(Decimal::ONE, |info| info.multiplier);
+        let cost = match pricing.get(&row.model).and_then(Option::as_ref) {
+            Some(pricing) => row_cost(&row, pricing, multiplier),
+            None => {
+                unpriced.insert(row.model.clone());
+                Decimal::ZERO
+            }
+        };
+
+        let key = period.key(&row.date);
+        let (entry, total) = grouped
+            .entry((row.app_type, row.provider_id))
+            .or_default()
+            .entry(key.clone())
+            .or_insert_with(|| {
+                (
+                    PeriodCost {
+                        period: key,
+                        ..PeriodCost::default()
+                    },
+                    Decimal::ZERO,
+                )
+            });
+        entry.input_tokens += row.input_tokens;
+        entry.output_tokens += row.output_tokens;
+        entry.cache_read_tokens += row.cache_read_tokens;
+        entry.cache_creation_tokens += row.cache_creation_tokens;
+        *total += cost;
+    }
+
+    let mut total_cost = Decimal::ZERO;
+    let mut provider_costs = grouped
+        .into_iter()
+        .map(|((app_type, provider_id), periods)| {
+            let provider_total = periods.values().map(|(_, cost)| *cost).sum::<Decimal>();
+            total_cost += provider_total;
+            let provider_name = providers
+                .get(&(app_type.clone(), provider_id.clone()))
+                .map(|info| info.name.clone())
+                .unwrap_or_default();
+            (
+                provider_total,
+                ProviderCost {
+                    app_type,
+                    provider_id,
+                    provider_name,
+                    total_cost: format!("{provider_total:.6}"),
+                    periods: periods
+                        .into_values()
+                        .map(|(entry, cost)| PeriodCost {
+                            cost: format!("{cost:.6}"),
+                            ..entry
+                        })
+                        .collect(),
+                },
+            )
+        })
+        .collect::<Vec<_>>();
+    provider_costs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    CostReport {
+        range,
+        period,
+        total_cost: format!("{total_cost:.6}"),
+        providers: provider_costs
+            .into_iter()
+            .map(|(_, provider)| provider)
+            .collect(),
+        unpriced_models: unpriced.into_iter().collect(),
+    }
+}
+
+fn load_pricing(
+    db: &Database,
+    rows: &[SessionUsageRow],
+) -> Result<HashMap<String, Option<ModelPricing>>, AppError> {
+    db.ensure_model_pricing_seeded()?;
+    let conn = lock_conn!(db.conn);
+    let mut pricing = HashMap::new();
+    for row in rows {
+        if pricing.contains_key(&row.model) {
+            continue;
+        }
+        let found = match find_model_pricing_row(&conn, &row.model)? {
+            Some((input, output, cache_read, cache_creation)) => Some(
+                ModelPricing::from_strings(&input, &output, &cache_read, &cache_creation)
+                    .map_err(|e| AppError::Database(format!("解析定价数据失败: {e}")))?,
+            ),
+            None => None,
+        };
+        pricing.insert(row.model.clone(), found);
+    }
+    Ok(pricing)
+}
+
+fn load_providers(
+    db: &Database,
+    rows: &[SessionUsageRow],
+) -> Result<HashMap<(String, String), ProviderInfo>, AppError> {
+    let mut providers = HashMap::new();
+    for row in rows {
+        let key = (row.app_type.clone(), row.provider_id.clone());
+        if row.provider_id.is_empty() || providers.contains_key(&key) {
+            continue;
+        }
+        // 已删除的供应商保留用量，名称为空
+        let Some(provider) = db.get_provider_by_id(&row.provider_id, &row.app_type)? else {
+            continue;
+        };
+        let multiplier = provider
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.cost_multiplier.as_deref())
+            .and_then(|value| Decimal::from_str(value).ok())
+            .unwrap_or(Decimal::ONE);
+        providers.insert(
+            key,
+            ProviderInfo {
+                name: provider.name,
+                multiplier,
+            },
+        );
+    }
+    Ok(providers)
+}
+
+/// 按供应商与周期汇总的成本报告（先统计新增的会话日志）
+pub fn get_cost_report(
+    db: &Database,
+    range: UsageRange,
+    period: CostPeriod,
+) -> Result<CostReport, AppError> {
+    session_usage::sync(db)?;
+    let rows = db.get_session_usage(None, range.since().as_deref())?;
+    let pricing = load_pricing(db, &rows)?;
+    let providers = load_providers(db, &rows)?;
+    Ok(build_report(rows, range, period, &pricing, &providers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(provider_id: &str, date: &str, model: &str) -> SessionUsageRow {
+        SessionUsageRow {
+            app_type: "claude".to_string(),
+            provider_id: provider_id.to_string(),
+            date: date.to_string(),
+            model: model.to_string(),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_read_tokens: 1_000_000,
+            message_count: 1,
+            ..SessionUsageRow::default()
+        }
+    }
+
+    #[test]
+    fn period_keys() {
+        assert_eq!(CostPeriod::Day.key("2025-06-04"), "2025-06-04");
+        // 2025-06-04 是周三
+        assert_eq!(CostPeriod::Week.key("2025-06-04"), "2025-06-02");
+        assert_eq!(CostPeriod::Week.key("2025-06-02"), "2025-06-02");
+        assert_eq!(CostPeriod::Month.key("2025-06-04"), "2025-06");
+    }
+
+    #[test]
+    fn prices_rows_per_provider_and_period() {
+        let pricing = HashMap::from([(
+            "sonnet".to_string(),
+            Some(ModelPricing::from_strings("3", "15", "0.3", "3.75").unwrap()),
+        )]);
+        let providers = HashMap::from([
+            (
+                ("claude".to_string(), "relay".to_string()),
+                ProviderInfo {
+                    name: "Relay".to_string(),
+                    multiplier: Decimal::from_str("0.5").unwrap(),
+                },
+            ),
+            (
+                ("claude".to_string(), "official".to_string()),
+                ProviderInfo {
+                    name: "Official".to_string(),
+                    multiplier: Decimal::ONE,
+                },
+            ),
+        ]);
+        let rows = vec![
+            row("official", "2025-06-02", "sonnet"),
+            row("official", "2025-06-04", "sonnet"),
+            row("relay", "2025-06-04", "sonnet"),
+            row("relay", "2025-06-04", "mystery"),
+        ];
+
+        let report = build_report(
+            rows,
+            UsageRange::All,
+            CostPeriod::Week,
+            &pricing,
+            &providers,
+        );
+        // 每行: 3 + 1.5 + 0.3 = 4.8
+        assert_eq!(report.total_cost, "12.000000");
+        assert_eq!(report.unpriced_models, vec!["mystery".to_string()]);
+        assert_eq!(report.providers[0].provider_name, "Official");
+        assert_eq!(report.providers[0].total_cost, "9.600000");
+        assert_eq!(report.providers[0].periods.len(), 1);
+        assert_eq!(report.providers[0].periods[0].period, "2025-06-02");
+        assert_eq!(report.providers[0].periods[0].input_tokens, 2_000_000);
+        assert_eq!(report.providers[1].total_cost, "2.400000");
+    }
+}
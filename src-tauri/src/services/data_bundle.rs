@@ -0,0 +1,276 @@
+//! 便携数据包
+//!
+//! 把整台机器上的 CC Switch 数据打包为一个带版本号的 zip 归档，用于迁移到新设备：
+//!
+//! ```text
+//! manifest.json   - 数据包版本、数据库结构版本与内容统计
+//! cc-switch.sql   - 数据库 SQL 导出（供应商、MCP 服务器、提示词、统一供应商等）
+//! settings.json   - 设备设置（含各应用当前供应商）
+//! ```
+//!
+//! 与快照不同，数据包不包含 live 配置文件（导入后按当前供应商重新生成），数据库内容
+//! 也不使用本机的静态加密密钥，因此包含明文 API Key，需妥善保管。
+//!
+//! 导入前先检查数据包格式版本与数据库结构版本：由更新版本的应用导出的数据包直接拒绝，
+//! 不做任何修改；旧版本的数据库结构在导入时按正常流程迁移。
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+
+use crate::app_config::AppType;
+use crate::database::{Database, SCHEMA_VERSION};
+use crate::error::AppError;
+use crate::settings::AppSettings;
+
+/// 数据包格式版本
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "cc-switch.sql";
+const SETTINGS_ENTRY: &str = "settings.json";
+
+const BUNDLE_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 数据包内容统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleContents {
+    pub providers: usize,
+    pub mcp_servers: usize,
+    pub prompts: usize,
+    pub universal_providers: usize,
+}
+
+/// 数据包元信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub schema_version: i32,
+    /// 导出时的应用版本
+    pub app_version: String,
+    pub created_at: i64,
+    pub contents: BundleContents,
+}
+
+/// 数据包导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImportResult {
+    pub manifest: BundleManifest,
+    /// 导入前自动生成的数据库备份 ID
+    pub safety_backup_id: String,
+}
+
+fn zip_error(context: &str, e: zip::result::ZipError) -> AppError {
+    AppError::Message(format!("{context}: {e}"))
+}
+
+fn count_contents(db: &Database) -> Result<BundleContents, AppError> {
+    let mut contents = BundleContents {
+        mcp_servers: db.get_all_mcp_servers()?.len(),
+        universal_providers: db.get_all_universal_providers()?.len(),
+        ..Default::default()
+    };
+    for app in BUNDLE_APPS {
+        contents.providers += db.get_all_providers(app.as_str())?.len();
+        contents.prompts += db.get_prompts(app.as_str())?.len();
+    }
+    Ok(contents)
+}
+
+/// 导出数据包到 `target`
+pub fn export_bundle(db: &Database, target: &Path) -> Result<BundleManifest, AppError> {
+    let sql = db.export_sql_string()?;
+    let settings_json = crate::settings::export_settings(true)?;
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        schema_version: SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now().timestamp_millis(),
+        contents: count_contents(db)?,
+    };
+    let manifest_json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| AppError::JsonSerialize { source: e })?;
+
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    {
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut add = |name: &str, data: &[u8]| -> Result<(), AppError> {
+            writer
+                .start_file(name, options)
+                .map_err(|e| zip_error("写入数据包失败", e))?;
+            writer.write_all(data).map_err(|e| AppError::IoContext {
+                context: "写入数据包失败".to_string(),
+                source: e,
+            })
+        };
+        add(MANIFEST_ENTRY, &manifest_json)?;
+        add(DATABASE_ENTRY, sql.as_bytes())?;
+        add(SETTINGS_ENTRY, settings_json.as_bytes())?;
+        writer
+            .finish()
+            .map_err(|e| zip_error("写入数据包失败", e))?;
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    crate::config::atomic_write(target, &buffer.into_inner())?;
+    log::info!(
+        "已导出数据包 {}（{} 个供应商，{} 个 MCP 服务器）",
+        target.display(),
+        manifest.contents.providers,
+        manifest.contents.mcp_servers
+    );
+    Ok(manifest)
+}
+
+fn open_archive(path: &Path) -> Result<zip::ZipArchive<fs::File>, AppError> {
+    let file = fs::File::open(path).map_err(|e| AppError::io(path, e))?;
+    zip::ZipArchive::new(file).map_err(|e| zip_error("读取数据包失败", e))
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<fs::File>, name: &str) -> Result<String, AppError> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|e| zip_error(&format!("数据包缺少 {name}"), e))?;
+    let mut data = String::new();
+    entry
+        .read_to_string(&mut data)
+        .map_err(|e| AppError::IoContext {
+            context: format!("读取数据包条目 {name} 失败"),
+            source: e,
+        })?;
+    Ok(data)
+}
+
+/// 检查数据包能否由当前版本导入
+fn check_versions(manifest: &BundleManifest) -> Result<(), AppError> {
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(AppError::localized(
+            "bundle.format_too_new",
+            format!(
+                "数据包格式版本过新（{}），请升级 CC Switch 后再导入",
+                manifest.format_version
+            ),
+            format!(
+                "Bundle format version {} is newer than supported; upgrade CC Switch to import it",
+                manifest.format_version
+            ),
+        ));
+    }
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(AppError::localized(
+            "bundle.schema_too_new",
+            format!(
+                "数据包由 CC Switch {} 导出，数据库版本 {} 高于当前支持的 {SCHEMA_VERSION}，请升级后再导入",
+                manifest.app_version, manifest.schema_version
+            ),
+            format!(
+                "Bundle was exported by CC Switch {} with database schema {}, newer than the supported {SCHEMA_VERSION}; upgrade to import it",
+                manifest.app_version, manifest.schema_version
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 读取并检查数据包元信息（不做任何修改）
+pub fn read_bundle_manifest(path: &Path) -> Result<BundleManifest, AppError> {
+    let mut archive = open_archive(path)?;
+    let manifest: BundleManifest = serde_json::from_str(&read_entry(&mut archive, MANIFEST_ENTRY)?)
+        .map_err(|e| AppError::InvalidInput(format!("数据包元信息无效: {e}")))?;
+    check_versions(&manifest)?;
+    Ok(manifest)
+}
+
+/// 导入数据包：替换数据库与设置
+///
+/// 本机的配置目录覆盖保持不变（其他设备上的路径在这里通常不存在）。导入后需要调用方
+/// 把当前供应商同步到 live 配置并重载设置。
+pub fn import_bundle(db: &Database, path: &Path) -> Result<BundleImportResult, AppError> {
+    let manifest = read_bundle_manifest(path)?;
+    let mut archive = open_archive(path)?;
+    let sql = read_entry(&mut archive, DATABASE_ENTRY)?;
+    // 先解析设置，避免数据库已替换后才发现设置无效
+    let mut settings: AppSettings = serde_json::from_str(
+        read_entry(&mut archive, SETTINGS_ENTRY)?.trim_start_matches('\u{feff}'),
+    )
+    .map_err(|e| AppError::InvalidInput(format!("数据包设置内容无效: {e}")))?;
+    settings.keep_device_paths(&crate::settings::get_settings());
+    let settings_json =
+        serde_json::to_string(&settings).map_err(|e| AppError::JsonSerialize { source: e })?;
+
+    let safety_backup_id = db.import_sql_string(&sql)?;
+    crate::settings::import_settings(&settings_json)?;
+
+    log::info!(
+        "已导入数据包 {}（CC Switch {} 导出）",
+        path.display(),
+        manifest.app_version
+    );
+    Ok(BundleImportResult {
+        manifest,
+        safety_backup_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn manifest_counts_contents_and_rejects_newer_schemas() {
+        let dir = TempDir::new().expect("temp dir");
+        let db = Database::memory().expect("memory db");
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "P1".to_string(),
+            json!({ "env": {} }),
+            None,
+        );
+        db.save_provider("claude", &provider)
+            .expect("save provider");
+
+        let path = dir.path().join("bundle.zip");
+        let exported = export_bundle(&db, &path).expect("export bundle");
+        assert_eq!(exported.contents.providers, 1);
+        let manifest = read_bundle_manifest(&path).expect("read manifest");
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.contents, exported.contents);
+
+        // 伪造一个由更新版本导出的数据包，导入应在修改任何数据前失败
+        let mut future = manifest.clone();
+        future.schema_version = SCHEMA_VERSION + 1;
+        let future_path = dir.path().join("future.zip");
+        {
+            let mut writer = zip::ZipWriter::new(fs::File::create(&future_path).expect("create"));
+            writer
+                .start_file(MANIFEST_ENTRY, SimpleFileOptions::default())
+                .expect("start manifest");
+            writer
+                .write_all(&serde_json::to_vec(&future).expect("manifest json"))
+                .expect("write manifest");
+            writer.finish().expect("finish zip");
+        }
+        let err = import_bundle(&db, &future_path).expect_err("newer schema rejected");
+        assert!(err.to_string().contains(&(SCHEMA_VERSION + 1).to_string()));
+        assert_eq!(db.get_all_providers("claude").expect("providers").len(), 1);
+    }
+}
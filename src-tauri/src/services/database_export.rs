@@ -0,0 +1,165 @@
+//! 数据库转储
+//!
+//! 将供应商、统一供应商、MCP 服务器、提示词与通用配置片段导出为规范化的 JSON
+//! （对象键排序、列表按 ID 排序，同样的数据总是得到同样的输出），或导出为
+//! CC Switch SQL，便于脚本处理、审计以及迁移到其他工具。JSON 转储可按排除规则
+//! （见 [`ExcludeRules`]）删除字段；SQL 为完整备份，不受排除规则影响。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::app_config::AppType;
+use crate::database::{Database, SCHEMA_VERSION};
+use crate::error::AppError;
+use crate::services::export_rules::ExcludeRules;
+
+/// JSON 转储格式版本
+const DUMP_FORMAT_VERSION: u32 = 1;
+
+const DUMP_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 转储格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpFormat {
+    Json,
+    Sql,
+}
+
+fn to_value<T: Serialize>(item: &T) -> Result<Value, AppError> {
+    serde_json::to_value(item).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+/// 递归排序对象键，保证输出与插入顺序无关
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect()),
+        other => other,
+    }
+}
+
+/// 将按 ID 索引的集合转为按 ID 排序的列表
+fn sorted_values<T: Serialize>(
+    items: impl IntoIterator<Item = (String, T)>,
+) -> Result<Value, AppError> {
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    items.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(Value::Array(
+        items
+            .iter()
+            .map(|(_, item)| to_value(item))
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+/// 构建规范化的 JSON 转储
+pub fn dump_json(db: &Database) -> Result<Value, AppError> {
+    let mut providers = Map::new();
+    let mut prompts = Map::new();
+    let mut current = Map::new();
+    let mut snippets = Map::new();
+    for app in DUMP_APPS {
+        let key = app.as_str();
+        providers.insert(key.to_string(), sorted_values(db.get_all_providers(key)?)?);
+        prompts.insert(key.to_string(), sorted_values(db.get_prompts(key)?)?);
+        current.insert(key.to_string(), to_value(&db.get_current_provider(key)?)?);
+        snippets.insert(key.to_string(), to_value(&db.get_config_snippet(key)?)?);
+    }
+
+    Ok(canonicalize(json!({
+        "formatVersion": DUMP_FORMAT_VERSION,
+        "schemaVersion": SCHEMA_VERSION,
+        "providers": providers,
+        "currentProviders": current,
+        "universalProviders": sorted_values(db.get_all_universal_providers()?)?,
+        "mcpServers": sorted_values(db.get_all_mcp_servers()?)?,
+        "prompts": prompts,
+        "commonConfigSnippets": snippets,
+    })))
+}
+
+/// 按指定格式转储数据库
+pub fn export_database(
+    db: &Database,
+    format: DumpFormat,
+    exclude: &ExcludeRules,
+) -> Result<String, AppError> {
+    match format {
+        DumpFormat::Json => {
+            let mut dump = dump_json(db)?;
+            let removed = exclude.apply(&mut dump);
+            if removed > 0 {
+                log::info!("JSON 转储已按排除规则删除 {removed} 个字段");
+            }
+            serde_json::to_string_pretty(&dump).map_err(|e| AppError::JsonSerialize { source: e })
+        }
+        DumpFormat::Sql => db.export_sql_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+
+    #[test]
+    fn json_dump_is_sorted_and_deterministic() {
+        let db = Database::memory().expect("memory db");
+        for id in ["b", "a"] {
+            let provider = Provider::with_id(
+                id.to_string(),
+                id.to_uppercase(),
+                json!({ "z": 1, "a": 2 }),
+                None,
+            );
+            db.save_provider("claude", &provider)
+                .expect("save provider");
+        }
+
+        let no_rules = ExcludeRules::default();
+        let first = export_database(&db, DumpFormat::Json, &no_rules).expect("dump");
+        let second = export_database(&db, DumpFormat::Json, &no_rules).expect("dump again");
+        assert_eq!(first, second);
+
+        let value: Value = serde_json::from_str(&first).expect("valid json");
+        let ids = value["providers"]["claude"]
+            .as_array()
+            .expect("provider list")
+            .iter()
+            .map(|p| p["id"].as_str().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["a", "b"]);
+
+        let keys = value["providers"]["claude"][0]["settingsConfig"]
+            .as_object()
+            .expect("settings object")
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["a", "z"]);
+
+        let rules = ExcludeRules::parse(&["settingsConfig.z", "/universalProviders"]);
+        let filtered = export_database(&db, DumpFormat::Json, &rules).expect("filtered dump");
+        let value: Value = serde_json::from_str(&filtered).expect("valid json");
+        assert!(value.get("universalProviders").is_none());
+        assert_eq!(
+            value["providers"]["claude"][0]["settingsConfig"],
+            json!({ "a": 2 })
+        );
+    }
+}
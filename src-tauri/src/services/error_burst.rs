@@ -0,0 +1,293 @@
+//! 限流与服务错误突增检测
+//!
+//! 直接写入配置（不经过代理）时，CLI 的请求失败不会进入代理的熔断器。这里读取 Claude Code
+//! 与 Codex 会话日志中新增的 API 错误（429 与 5xx），按切换记录归属到当时的供应商；某个
+//! 供应商在时间窗口内的错误数达到阈值即视为突增：
+//!
+//! - Claude Code：`isApiErrorMessage` 回复中的 `API Error: 429 ...`，以及重试时记录的
+//!   `api_error` 系统消息；
+//! - Codex：`error` / `stream_error` 事件信息中的 HTTP 状态码。
+//!
+//! 只读取开始检测后新增的内容，历史日志中的错误不会触发告警。同一供应商告警后一段时间内
+//! 不再重复告警。
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::session_usage::{
+    collect_logs, log_dir, parse_timestamp, provider_at, read_new_lines,
+};
+use crate::settings::ErrorBurstConfig;
+
+/// 同一供应商两次告警的最短间隔（毫秒）
+const ALERT_COOLDOWN_MS: i64 = 30 * 60 * 1000;
+
+/// 检测到的错误突增
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorBurst {
+    pub app_type: AppType,
+    pub provider_id: String,
+    /// 窗口内的错误数
+    pub count: usize,
+    /// 最近一次错误的状态码
+    pub last_status: u16,
+}
+
+fn is_burst_status(status: u16) -> bool {
+    status == 429 || (500..600).contains(&status)
+}
+
+/// 从错误信息中找出 429 或 5xx 状态码
+fn status_in(text: &str) -> Option<u16> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter(|part| part.len() == 3)
+        .filter_map(|part| part.parse().ok())
+        .find(|status| is_burst_status(*status))
+}
+
+/// 解析 Claude Code 日志中的 API 错误，返回 (毫秒时间戳, 状态码)
+fn parse_claude_error(line: &Value) -> Option<(i64, u16)> {
+    let status = if line.get("isApiErrorMessage").and_then(Value::as_bool) == Some(true) {
+        let text = match line.pointer("/message/content")? {
+            Value::String(text) => text.clone(),
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| item.get("text")?.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => return None,
+        };
+        status_in(&text)?
+    } else if line.get("subtype").and_then(Value::as_str) == Some("api_error") {
+        let status = u16::try_from(line.pointer("/error/status")?.as_u64()?).ok()?;
+        if !is_burst_status(status) {
+            return None;
+        }
+        status
+    } else {
+        return None;
+    };
+    let (timestamp, _) = parse_timestamp(line)?;
+    Some((timestamp, status))
+}
+
+/// 解析 Codex 日志中的错误事件，返回 (毫秒时间戳, 状态码)
+fn parse_codex_error(line: &Value) -> Option<(i64, u16)> {
+    if line.get("type")?.as_str()? != "event_msg" {
+        return None;
+    }
+    let payload = line.get("payload")?;
+    if !matches!(payload.get("type")?.as_str()?, "error" | "stream_error") {
+        return None;
+    }
+    let status = status_in(payload.get("message")?.as_str()?)?;
+    let (timestamp, _) = parse_timestamp(line)?;
+    Some((timestamp, status))
+}
+
+/// 错误突增检测器（在后台任务中反复调用 [`ErrorBurstDetector::scan`]）
+#[derive(Debug, Default)]
+pub struct ErrorBurstDetector {
+    /// 日志文件 -> 已读取的位置
+    offsets: HashMap<PathBuf, u64>,
+    /// 首次扫描只记录各文件的位置
+    primed: bool,
+    /// (应用, 供应商) -> 错误的 (毫秒时间戳, 状态码)，按时间先后
+    errors: HashMap<(String, String), VecDeque<(i64, u16)>>,
+    /// (应用, 供应商) -> 上次告警时间
+    alerted: HashMap<(String, String), i64>,
+}
+
+impl ErrorBurstDetector {
+    fn record(&mut self, app_type: &AppType, provider_id: &str, timestamp: i64, status: u16) {
+        self.errors
+            .entry((app_type.as_str().to_string(), provider_id.to_string()))
+            .or_default()
+            .push_back((timestamp, status));
+    }
+
+    /// 丢弃窗口外的错误，返回达到阈值且不在冷却期内的供应商
+    fn evaluate(&mut self, now: i64, window_ms: i64, threshold: usize) -> Vec<ErrorBurst> {
+        let mut bursts = Vec::new();
+        for (key, errors) in &mut self.errors {
+            while errors
+                .front()
+                .is_some_and(|(timestamp, _)| *timestamp < now - window_ms)
+            {
+                errors.pop_front();
+            }
+            // 无法判断供应商的错误不告警
+            if key.1.is_empty() || errors.len() < threshold.max(1) {
+                continue;
+            }
+            if self
+                .alerted
+                .get(key)
+                .is_some_and(|last| now - last < ALERT_COOLDOWN_MS)
+            {
+                continue;
+            }
+            let Ok(app_type) = key.0.parse::<AppType>() else {
+                continue;
+            };
+            bursts.push(ErrorBurst {
+                app_type,
+                provider_id: key.1.clone(),
+                count: errors.len(),
+                last_status: errors.back().map_or(0, |(_, status)| *status),
+            });
+            self.alerted.insert(key.clone(), now);
+            errors.clear();
+        }
+        bursts
+    }
+
+    fn scan_app(&mut self, db: &Database, app_type: &AppType) -> Result<(), AppError> {
+        let timeline = db.get_provider_switch_timeline(app_type.as_str())?;
+        let mut files = Vec::new();
+        collect_logs(&log_dir(app_type), &mut files);
+        for path in files {
+            let Ok(metadata) = std::fs::metadata(&path) else {
+                continue;
+            };
+            let len = metadata.len();
+            // 开始检测前已有的内容跳过；之后新建的文件从头读取
+            let offset = match self.offsets.get(&path) {
+                Some(offset) if *offset <= len => *offset,
+                Some(_) => 0,
+                None if self.primed => 0,
+                None => len,
+            };
+            if offset == len {
+                self.offsets.insert(path, len);
+                continue;
+            }
+            let (content, new_offset) = match read_new_lines(&path, offset) {
+                Ok(result) => result,
+                Err(e) => {
+                    log::debug!("读取会话日志 {} 失败: {e}", path.display());
+                    continue;
+                }
+            };
+            for line in content
+                .lines()
+                .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            {
+                let error = match app_type {
+                    AppType::Codex => parse_codex_error(&line),
+                    _ => parse_claude_error(&line),
+                };
+                if let Some((timestamp, status)) = error {
+                    let provider_id = provider_at(&timeline, timestamp).to_string();
+                    self.record(app_type, &provider_id, timestamp, status);
+                }
+            }
+            self.offsets.insert(path, new_offset);
+        }
+        Ok(())
+    }
+
+    /// 读取新增的日志并返回新出现的错误突增
+    pub fn scan(
+        &mut self,
+        db: &Database,
+        config: &ErrorBurstConfig,
+    ) -> Result<Vec<ErrorBurst>, AppError> {
+        for app_type in [AppType::Claude, AppType::Codex] {
+            self.scan_app(db, &app_type)?;
+        }
+        self.primed = true;
+        let window_ms = i64::from(config.window_minutes.max(1)) * 60 * 1000;
+        Ok(self.evaluate(
+            chrono::Utc::now().timestamp_millis(),
+            window_ms,
+            config.threshold as usize,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn finds_rate_limit_and_server_statuses() {
+        assert_eq!(status_in("API Error: 429 {\"type\":\"error\"}"), Some(429));
+        assert_eq!(
+            status_in("stream error: exceeded retry limit, last status: 503 Service Unavailable"),
+            Some(503)
+        );
+        assert_eq!(status_in("API Error: 401 invalid x-api-key"), None);
+        assert_eq!(status_in("retrying 1/5 in 200ms"), None);
+    }
+
+    #[test]
+    fn parses_cli_error_lines() {
+        let claude = json!({
+            "type": "assistant",
+            "timestamp": "2025-06-01T12:00:00.000Z",
+            "isApiErrorMessage": true,
+            "message": {
+                "model": "<synthetic>",
+                "content": [{ "type": "text", "text": "API Error: 529 {\"type\":\"overloaded_error\"}" }]
+            }
+        });
+        assert_eq!(parse_claude_error(&claude), Some((1_748_779_200_000, 529)));
+
+        let retry = json!({
+            "type": "system",
+            "subtype": "api_error",
+            "timestamp": "2025-06-01T12:00:00.000Z",
+            "error": { "status": 429 }
+        });
+        assert_eq!(parse_claude_error(&retry), Some((1_748_779_200_000, 429)));
+        assert_eq!(
+            parse_claude_error(&json!({ "type": "assistant", "message": {} })),
+            None
+        );
+
+        let codex = json!({
+            "timestamp": "2025-06-01T12:00:00.000Z",
+            "type": "event_msg",
+            "payload": { "type": "stream_error", "message": "unexpected status 429 Too Many Requests" }
+        });
+        assert_eq!(parse_codex_error(&codex), Some((1_748_779_200_000, 429)));
+    }
+
+    #[test]
+    fn reports_bursts_once_per_cooldown() {
+        let mut detector = ErrorBurstDetector::default();
+        let minute = 60 * 1000;
+        for timestamp in [0, minute, 2 * minute] {
+            detector.record(&AppType::Claude, "relay", timestamp, 429);
+        }
+        // 窗口外的错误不计入
+        assert!(detector.evaluate(7 * minute, 5 * minute, 3).is_empty());
+
+        for timestamp in [10 * minute, 11 * minute, 12 * minute] {
+            detector.record(&AppType::Claude, "relay", timestamp, 503);
+            detector.record(&AppType::Codex, "", timestamp, 503);
+        }
+        let bursts = detector.evaluate(12 * minute, 5 * minute, 3);
+        assert_eq!(
+            bursts,
+            vec![ErrorBurst {
+                app_type: AppType::Claude,
+                provider_id: "relay".to_string(),
+                count: 3,
+                last_status: 503,
+            }]
+        );
+
+        for timestamp in [13 * minute, 14 * minute, 15 * minute] {
+            detector.record(&AppType::Claude, "relay", timestamp, 429);
+        }
+        assert!(detector.evaluate(15 * minute, 5 * minute, 3).is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+//! 导出排除规则
+//!
+//! 类似 `.gitignore` 的规则，按 JSON 路径从导出内容中删除字段，便于直接生成可提交到
+//! 仓库的供应商目录。路径以 `.` 分隔，数组下标同样是一段（如 `providers.claude.0.name`）：
+//! - `*` 匹配段内任意字符，`?` 匹配单个字符，`**` 匹配任意多段；
+//! - 规则默认匹配任意深度的路径后缀（`*.apiKey` 会删除所有对象中的 `apiKey`），
+//!   以 `/` 开头时从根路径开始匹配；
+//! - `!` 开头表示重新包含，后出现的规则优先；空行与 `#` 开头的行被忽略。
+//!
+//! 与 `.gitignore` 相同，父节点被排除后其子节点无法再被重新包含。
+
+use serde_json::Value;
+
+#[derive(Debug, Clone)]
+struct Rule {
+    negated: bool,
+    anchored: bool,
+    segments: Vec<String>,
+}
+
+/// 解析后的排除规则
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeRules {
+    rules: Vec<Rule>,
+}
+
+/// 段内通配符匹配（`*` / `?`）
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn segments_match(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|skip| segments_match(rest, &path[skip..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((segment, path)) => wildcard_match(first, segment) && segments_match(rest, path),
+            None => false,
+        },
+    }
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (anchored, line) = match line.strip_prefix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let segments = line
+            .split('.')
+            .filter(|segment| !segment.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if segments.is_empty() {
+            return None;
+        }
+        Some(Self {
+            negated,
+            anchored,
+            segments,
+        })
+    }
+
+    fn matches(&self, path: &[&str]) -> bool {
+        if self.anchored {
+            segments_match(&self.segments, path)
+        } else {
+            (0..path.len()).any(|start| segments_match(&self.segments, &path[start..]))
+        }
+    }
+}
+
+impl ExcludeRules {
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Self {
+        Self {
+            rules: lines
+                .iter()
+                .filter_map(|line| Rule::parse(line.as_ref()))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 路径是否被排除（最后一条匹配的规则生效）
+    pub fn is_excluded(&self, path: &[&str]) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path))
+            .is_some_and(|rule| !rule.negated)
+    }
+
+    /// 从 JSON 中删除被排除的字段，返回删除的数量
+    pub fn apply(&self, value: &mut Value) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut path = Vec::new();
+        self.apply_at(value, &mut path)
+    }
+
+    fn apply_at(&self, value: &mut Value, path: &mut Vec<String>) -> usize {
+        let mut removed = 0;
+        match value {
+            Value::Object(map) => {
+                let keys = map.keys().cloned().collect::<Vec<_>>();
+                for key in keys {
+                    path.push(key.clone());
+                    if self.is_excluded(&path.iter().map(String::as_str).collect::<Vec<_>>()) {
+                        map.remove(&key);
+                        removed += 1;
+                    } else if let Some(child) = map.get_mut(&key) {
+                        removed += self.apply_at(child, path);
+                    }
+                    path.pop();
+                }
+            }
+            Value::Array(items) => {
+                let mut index = 0;
+                items.retain_mut(|item| {
+                    path.push(index.to_string());
+                    index += 1;
+                    let excluded =
+                        self.is_excluded(&path.iter().map(String::as_str).collect::<Vec<_>>());
+                    if excluded {
+                        removed += 1;
+                    } else {
+                        removed += self.apply_at(item, path);
+                    }
+                    path.pop();
+                    !excluded
+                });
+            }
+            _ => {}
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn matches_wildcards_within_segments() {
+        assert!(wildcard_match("*Key", "apiKey"));
+        assert!(wildcard_match("ANTHROPIC_*", "ANTHROPIC_AUTH_TOKEN"));
+        assert!(wildcard_match("a?c", "abc"));
+        assert!(!wildcard_match("*Key", "apiKeys"));
+    }
+
+    #[test]
+    fn removes_matching_paths_at_any_depth() {
+        let rules = ExcludeRules::parse(&[
+            "# 提交到仓库前去掉密钥与备注",
+            "*.apiKey",
+            "meta.notes",
+            "env.*_TOKEN",
+            "",
+        ]);
+        let mut value = json!({
+            "providers": [
+                {
+                    "id": "a",
+                    "apiKey": "sk-a",
+                    "meta": { "notes": "internal", "tier": 1 },
+                    "settingsConfig": { "env": { "ANTHROPIC_AUTH_TOKEN": "t", "ANTHROPIC_BASE_URL": "u" } }
+                }
+            ],
+            "apiKey": "root"
+        });
+        assert_eq!(rules.apply(&mut value), 3);
+        assert_eq!(
+            value,
+            json!({
+                "providers": [
+                    {
+                        "id": "a",
+                        "meta": { "tier": 1 },
+                        "settingsConfig": { "env": { "ANTHROPIC_BASE_URL": "u" } }
+                    }
+                ],
+                "apiKey": "root"
+            })
+        );
+    }
+
+    #[test]
+    fn supports_anchors_negation_and_globstar() {
+        let rules = ExcludeRules::parse(&["/prompts", "**.env.*", "!**.env.*_BASE_URL"]);
+        let mut value = json!({
+            "prompts": { "claude": [] },
+            "providers": {
+                "prompts": 1,
+                "claude": [{ "env": { "KEY": "x", "ANTHROPIC_BASE_URL": "u" } }]
+            }
+        });
+        rules.apply(&mut value);
+        assert_eq!(
+            value,
+            json!({
+                "providers": {
+                    "prompts": 1,
+                    "claude": [{ "env": { "ANTHROPIC_BASE_URL": "u" } }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn removes_array_elements_by_index() {
+        let rules = ExcludeRules::parse(&["/items.1"]);
+        let mut value = json!({ "items": ["a", "b", "c"] });
+        rules.apply(&mut value);
+        assert_eq!(value, json!({ "items": ["a", "c"] }));
+    }
+}
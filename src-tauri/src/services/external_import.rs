@@ -0,0 +1,700 @@
+//! 从其他供应商切换工具迁移配置
+//!
+//! 支持的来源：
+//! - claude-code-router 的 `config.json`（`Providers` 数组）
+//! - 常见切换工具的 JSON/YAML 配置（`profiles` / `providers` / `configs`，数组或以名称为键的对象）
+//! - 基于 shell 脚本的环境变量切换器（按函数、`case` 分支或注释分段的 `export KEY=VALUE`）
+//!
+//! 解析结果统一映射为深链接导入请求，复用深链接的供应商构建逻辑生成 `Provider`。
+
+#[cfg(feature = "gui")]
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+#[cfg(feature = "gui")]
+use std::collections::HashSet;
+#[cfg(feature = "gui")]
+use std::path::Path;
+#[cfg(feature = "gui")]
+use std::str::FromStr;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::deeplink::{build_provider_from_request, DeepLinkImportRequest};
+use crate::error::AppError;
+use crate::provider::Provider;
+#[cfg(feature = "gui")]
+use crate::services::secret_lint::{lint_key, KeyWarning};
+#[cfg(feature = "gui")]
+use crate::services::ProviderService;
+#[cfg(feature = "gui")]
+use crate::store::AppState;
+
+/// 识别出的外部配置格式
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalFormat {
+    ClaudeCodeRouter,
+    ProfileList,
+    EnvScript,
+}
+
+/// 从外部配置中解析出的单个供应商
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalProvider {
+    pub app: AppType,
+    pub name: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub model: Option<String>,
+    pub haiku_model: Option<String>,
+    pub sonnet_model: Option<String>,
+    pub opus_model: Option<String>,
+}
+
+/// 单个导入项（不包含 API Key）
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportItem {
+    pub app: String,
+    pub id: String,
+    pub name: String,
+    pub base_url: String,
+}
+
+/// 迁移导入报告
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportReport {
+    pub format: ExternalFormat,
+    pub dry_run: bool,
+    pub imported: Vec<ExternalImportItem>,
+    /// 跳过的条目及原因
+    pub skipped: Vec<String>,
+    /// 可疑的 API Key（格式异常、占位符、厂商不匹配等），不影响导入
+    pub warnings: Vec<KeyWarning>,
+}
+
+#[cfg(feature = "gui")]
+const PROFILE_LIST_KEYS: [&str; 4] = ["profiles", "providers", "configs", "environments"];
+#[cfg(feature = "gui")]
+const BASE_URL_KEYS: [&str; 8] = [
+    "base_url",
+    "baseUrl",
+    "baseURL",
+    "api_base_url",
+    "apiBaseUrl",
+    "endpoint",
+    "url",
+    "host",
+];
+#[cfg(feature = "gui")]
+const API_KEY_KEYS: [&str; 7] = [
+    "api_key",
+    "apiKey",
+    "auth_token",
+    "authToken",
+    "token",
+    "key",
+    "secret",
+];
+
+#[cfg(feature = "gui")]
+fn str_field(obj: &serde_json::Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| obj.get(*k).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 从一组环境变量推断供应商（同一分段可同时包含多个应用的变量）
+fn providers_from_env(name: &str, vars: &BTreeMap<String, String>) -> Vec<ExternalProvider> {
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|k| vars.get(*k))
+            .find(|v| !v.is_empty())
+            .cloned()
+    };
+    let mut out = Vec::new();
+
+    if let Some(api_key) = get(&["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"]) {
+        out.push(ExternalProvider {
+            app: AppType::Claude,
+            name: name.to_string(),
+            base_url: get(&["ANTHROPIC_BASE_URL"]).unwrap_or_default(),
+            api_key,
+            model: get(&["ANTHROPIC_MODEL"]),
+            haiku_model: get(&[
+                "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+                "ANTHROPIC_SMALL_FAST_MODEL",
+            ]),
+            sonnet_model: get(&["ANTHROPIC_DEFAULT_SONNET_MODEL"]),
+            opus_model: get(&["ANTHROPIC_DEFAULT_OPUS_MODEL"]),
+        });
+    }
+    if let Some(api_key) = get(&["OPENAI_API_KEY"]) {
+        out.push(ExternalProvider {
+            app: AppType::Codex,
+            name: name.to_string(),
+            base_url: get(&["OPENAI_BASE_URL", "OPENAI_API_BASE"]).unwrap_or_default(),
+            api_key,
+            model: get(&["OPENAI_MODEL", "CODEX_MODEL"]),
+            haiku_model: None,
+            sonnet_model: None,
+            opus_model: None,
+        });
+    }
+    if let Some(api_key) = get(&["GEMINI_API_KEY", "GOOGLE_API_KEY"]) {
+        out.push(ExternalProvider {
+            app: AppType::Gemini,
+            name: name.to_string(),
+            base_url: get(&["GOOGLE_GEMINI_BASE_URL", "GEMINI_BASE_URL"]).unwrap_or_default(),
+            api_key,
+            model: get(&["GEMINI_MODEL"]),
+            haiku_model: None,
+            sonnet_model: None,
+            opus_model: None,
+        });
+    }
+    out
+}
+
+/// claude-code-router：`api_base_url` 为完整的 chat/completions 地址，按 OpenAI 兼容端点导入
+#[cfg(feature = "gui")]
+fn parse_claude_code_router(providers: &[Value], app: &AppType) -> Vec<ExternalProvider> {
+    providers
+        .iter()
+        .filter_map(Value::as_object)
+        .map(|obj| {
+            let base_url = str_field(obj, &["api_base_url"]).unwrap_or_default();
+            let base_url = base_url
+                .trim_end_matches('/')
+                .trim_end_matches("/chat/completions")
+                .trim_end_matches("/v1/messages")
+                .to_string();
+            ExternalProvider {
+                app: app.clone(),
+                name: str_field(obj, &["name"]).unwrap_or_default(),
+                base_url,
+                api_key: str_field(obj, &["api_key"]).unwrap_or_default(),
+                model: obj
+                    .get("models")
+                    .and_then(Value::as_array)
+                    .and_then(|models| models.first())
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                haiku_model: None,
+                sonnet_model: None,
+                opus_model: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "gui")]
+fn parse_profile(
+    name: &str,
+    obj: &serde_json::Map<String, Value>,
+    default_app: &AppType,
+) -> Vec<ExternalProvider> {
+    let name = str_field(obj, &["name", "title", "label"]).unwrap_or_else(|| name.to_string());
+
+    // 以环境变量形式保存的配置（如 `env: { ANTHROPIC_BASE_URL: ... }`）
+    if let Some(env) = obj.get("env").and_then(Value::as_object) {
+        let vars: BTreeMap<String, String> = env
+            .iter()
+            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+            .collect();
+        let found = providers_from_env(&name, &vars);
+        if !found.is_empty() {
+            return found;
+        }
+    }
+
+    let app = str_field(obj, &["app", "tool", "type"])
+        .and_then(|s| AppType::from_str(&s).ok())
+        .unwrap_or_else(|| default_app.clone());
+
+    vec![ExternalProvider {
+        app,
+        name,
+        base_url: str_field(obj, &BASE_URL_KEYS).unwrap_or_default(),
+        api_key: str_field(obj, &API_KEY_KEYS).unwrap_or_default(),
+        model: str_field(obj, &["model", "defaultModel", "default_model"]),
+        haiku_model: str_field(obj, &["haikuModel", "haiku_model", "smallFastModel"]),
+        sonnet_model: str_field(obj, &["sonnetModel", "sonnet_model"]),
+        opus_model: str_field(obj, &["opusModel", "opus_model"]),
+    }]
+}
+
+#[cfg(feature = "gui")]
+fn parse_profile_collection(value: &Value, default_app: &AppType) -> Vec<ExternalProvider> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| item.as_object().map(|obj| (i, obj)))
+            .flat_map(|(i, obj)| parse_profile(&format!("profile-{}", i + 1), obj, default_app))
+            .collect(),
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(name, item)| item.as_object().map(|obj| (name, obj)))
+            .flat_map(|(name, obj)| parse_profile(name, obj, default_app))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "gui")]
+fn looks_like_profile(value: &Value) -> bool {
+    value.as_object().is_some_and(|obj| {
+        obj.contains_key("env")
+            || (str_field(obj, &BASE_URL_KEYS).is_some() && str_field(obj, &API_KEY_KEYS).is_some())
+    })
+}
+
+#[cfg(feature = "gui")]
+fn parse_structured(
+    value: &Value,
+    default_app: Option<&AppType>,
+) -> Option<(ExternalFormat, Vec<ExternalProvider>)> {
+    let obj = value.as_object()?;
+
+    if let Some(providers) = obj.get("Providers").and_then(Value::as_array) {
+        let app = default_app.cloned().unwrap_or(AppType::Codex);
+        return Some((
+            ExternalFormat::ClaudeCodeRouter,
+            parse_claude_code_router(providers, &app),
+        ));
+    }
+
+    let app = default_app.cloned().unwrap_or(AppType::Claude);
+    if let Some(list) = PROFILE_LIST_KEYS.iter().find_map(|k| obj.get(*k)) {
+        return Some((
+            ExternalFormat::ProfileList,
+            parse_profile_collection(list, &app),
+        ));
+    }
+
+    // 顶层直接以名称为键
+    if !obj.is_empty() && obj.values().any(looks_like_profile) {
+        let profiles: serde_json::Map<String, Value> = obj
+            .iter()
+            .filter(|(_, v)| looks_like_profile(v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        return Some((
+            ExternalFormat::ProfileList,
+            parse_profile_collection(&Value::Object(profiles), &app),
+        ));
+    }
+
+    None
+}
+
+#[cfg(feature = "gui")]
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            return rest.split(quote).next().unwrap_or_default().to_string();
+        }
+    }
+    value
+        .split(" #")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// 解析 shell 脚本中的环境变量分段
+#[cfg(feature = "gui")]
+fn parse_env_script(content: &str) -> Vec<ExternalProvider> {
+    let mut sections: Vec<(String, BTreeMap<String, String>)> = Vec::new();
+    let mut current_name = String::from("default");
+    let mut current: BTreeMap<String, String> = BTreeMap::new();
+    let mut in_block = false;
+
+    let mut flush = |name: &str, vars: &mut BTreeMap<String, String>| {
+        if !vars.is_empty() {
+            sections.push((name.to_string(), std::mem::take(vars)));
+        }
+    };
+
+    for raw in content.lines() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with("#!") {
+            continue;
+        }
+
+        let heading = if let Some(comment) = line.strip_prefix('#') {
+            let comment = comment.trim().trim_matches(|c| c == '=' || c == '-').trim();
+            // 函数/分支内部的注释不作为分段名
+            if comment.is_empty() || comment.contains('=') || (in_block && current.is_empty()) {
+                continue;
+            }
+            Some(comment.to_string())
+        } else if let Some(rest) = line.strip_prefix("function ") {
+            rest.split(|c: char| c == '(' || c == '{' || c.is_whitespace())
+                .next()
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        } else if let Some((name, _)) = line.split_once("()") {
+            let name = name.trim();
+            (!name.is_empty() && !name.contains(' ')).then(|| name.to_string())
+        } else if line.ends_with(')') && !line.contains('=') && !line.contains('(') {
+            // case 分支标签，如 `work)` 或 `"kimi"|kimi)`
+            line.trim_end_matches(')')
+                .split('|')
+                .next()
+                .map(|s| s.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+                .filter(|s| !s.is_empty() && s != "*")
+        } else {
+            None
+        };
+
+        if let Some(name) = heading {
+            flush(&current_name, &mut current);
+            in_block = !line.starts_with('#');
+            current_name = name;
+            continue;
+        }
+
+        // 一行内可能含多条语句（如 `work) export A=1; export B=2;;`）
+        for stmt in line.split(';') {
+            let stmt = stmt.trim();
+            let stmt = stmt
+                .strip_prefix("export ")
+                .or_else(|| stmt.strip_prefix("set -gx "))
+                .unwrap_or(stmt);
+            let Some((key, value)) = stmt
+                .split_once('=')
+                .or_else(|| stmt.split_once(char::is_whitespace))
+            else {
+                continue;
+            };
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                continue;
+            }
+            let value = unquote(value);
+            // 引用其他变量的值无法静态解析
+            if value.contains('$') {
+                continue;
+            }
+            current.insert(key.to_string(), value);
+        }
+    }
+    flush(&current_name, &mut current);
+
+    sections
+        .iter()
+        .flat_map(|(name, vars)| providers_from_env(name, vars))
+        .collect()
+}
+
+/// 识别格式并解析外部配置内容
+#[cfg(feature = "gui")]
+pub fn parse_external_config(
+    content: &str,
+    default_app: Option<&AppType>,
+) -> Result<(ExternalFormat, Vec<ExternalProvider>), AppError> {
+    let structured = serde_json::from_str::<Value>(content).ok().or_else(|| {
+        serde_yaml::from_str::<serde_yaml::Value>(content)
+            .ok()
+            .and_then(|v| serde_json::to_value(v).ok())
+    });
+
+    if let Some(parsed) = structured
+        .as_ref()
+        .and_then(|value| parse_structured(value, default_app))
+    {
+        return Ok(parsed);
+    }
+
+    let mut providers = parse_env_script(content);
+    if let Some(app) = default_app {
+        providers.retain(|p| &p.app == app);
+    }
+    if providers.is_empty() {
+        return Err(AppError::localized(
+            "external_import.unrecognized",
+            "无法识别的配置格式：未找到任何供应商",
+            "Unrecognized config format: no providers found",
+        ));
+    }
+    Ok((ExternalFormat::EnvScript, providers))
+}
+
+impl ExternalProvider {
+    fn to_request(&self) -> DeepLinkImportRequest {
+        DeepLinkImportRequest {
+            version: "v1".to_string(),
+            resource: "provider".to_string(),
+            app: Some(self.app.as_str().to_string()),
+            name: Some(self.name.clone()),
+            enabled: None,
+            homepage: None,
+            endpoint: Some(self.base_url.clone()),
+            api_key: Some(self.api_key.clone()),
+            icon: None,
+            model: self.model.clone(),
+            notes: Some("Migrated from external config".to_string()),
+            haiku_model: self.haiku_model.clone(),
+            sonnet_model: self.sonnet_model.clone(),
+            opus_model: self.opus_model.clone(),
+            content: None,
+            description: None,
+            apps: None,
+            repo: None,
+            directory: None,
+            branch: None,
+            config: None,
+            config_format: None,
+            config_url: None,
+            usage_enabled: None,
+            usage_script: None,
+            usage_api_key: None,
+            usage_base_url: None,
+            usage_access_token: None,
+            usage_user_id: None,
+            usage_auto_interval: None,
+        }
+    }
+
+    /// 构建供应商（ID 由调用方生成）
+    pub fn to_provider(&self) -> Result<Provider, AppError> {
+        let mut provider = build_provider_from_request(&self.app, &self.to_request())?;
+        if self.base_url.is_empty() {
+            // 官方 Claude 账号无需自定义端点
+            if let Some(env) = provider
+                .settings_config
+                .get_mut("env")
+                .and_then(Value::as_object_mut)
+            {
+                env.remove("ANTHROPIC_BASE_URL");
+            }
+        }
+        provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+        Ok(provider)
+    }
+}
+
+/// 从一组环境变量中取出指定应用的供应商（命令行 `add --from-env`）
+pub fn provider_from_env_vars(
+    app_type: &AppType,
+    name: &str,
+    vars: &BTreeMap<String, String>,
+) -> Option<ExternalProvider> {
+    providers_from_env(name, vars)
+        .into_iter()
+        .find(|provider| &provider.app == app_type)
+}
+
+#[cfg(feature = "gui")]
+fn provider_id(name: &str, taken: &HashSet<String>) -> String {
+    let sanitized: String = name
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+        .collect::<String>()
+        .to_lowercase();
+    let base = if sanitized.is_empty() {
+        "imported".to_string()
+    } else {
+        format!("imported-{sanitized}")
+    };
+    if !taken.contains(&base) {
+        return base;
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|id| !taken.contains(id))
+        .expect("unbounded id search")
+}
+
+/// 判断已有供应商是否已包含相同的端点与密钥
+#[cfg(feature = "gui")]
+fn already_present(existing: &[Value], base_url: &str, api_key: &str) -> bool {
+    existing.iter().any(|settings| {
+        let text = settings.to_string();
+        text.contains(api_key) && (base_url.is_empty() || text.contains(base_url))
+    })
+}
+
+/// 从外部工具的配置文件迁移供应商；`dry_run` 时只返回解析结果不写入
+#[cfg(feature = "gui")]
+pub fn import_external_config(
+    state: &AppState,
+    path: &Path,
+    default_app: Option<AppType>,
+    dry_run: bool,
+) -> Result<ExternalImportReport, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    let (format, providers) = parse_external_config(&content, default_app.as_ref())?;
+    if !dry_run {
+        crate::read_only::ensure_writable()?;
+    }
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    let mut warnings = Vec::new();
+    let mut taken: BTreeMap<String, (HashSet<String>, Vec<Value>)> = BTreeMap::new();
+
+    for entry in providers {
+        let label = format!("{}/{}", entry.app.as_str(), entry.name);
+        if entry.name.trim().is_empty() || entry.api_key.is_empty() {
+            skipped.push(format!("{label}: missing name or API key"));
+            continue;
+        }
+        if entry.base_url.is_empty() && entry.app != AppType::Claude {
+            skipped.push(format!("{label}: missing base URL"));
+            continue;
+        }
+        if matches!(entry.app, AppType::OpenCode) {
+            skipped.push(format!("{label}: OpenCode providers are not supported"));
+            continue;
+        }
+
+        let app_key = entry.app.as_str().to_string();
+        let (ids, settings) = match taken.entry(app_key.clone()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let existing = state.db.get_all_providers(&app_key)?;
+                let ids = existing.keys().cloned().collect();
+                let settings = existing
+                    .values()
+                    .map(|p| p.settings_config.clone())
+                    .collect();
+                e.insert((ids, settings))
+            }
+        };
+
+        if already_present(settings, &entry.base_url, &entry.api_key) {
+            skipped.push(format!("{label}: already exists"));
+            continue;
+        }
+
+        warnings.extend(lint_key(
+            &label,
+            Some(&entry.app),
+            &entry.base_url,
+            &entry.api_key,
+        ));
+
+        let mut provider = entry.to_provider()?;
+        provider.id = provider_id(&entry.name, ids);
+
+        ids.insert(provider.id.clone());
+        settings.push(provider.settings_config.clone());
+        imported.push(ExternalImportItem {
+            app: app_key,
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            base_url: entry.base_url.clone(),
+        });
+
+        if !dry_run {
+            ProviderService::add(state, entry.app.clone(), provider)?;
+        }
+    }
+
+    Ok(ExternalImportReport {
+        format,
+        dry_run,
+        imported,
+        skipped,
+        warnings,
+    })
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_claude_code_router_config() {
+        let content = r#"{
+            "LOG": true,
+            "Providers": [
+                {
+                    "name": "openrouter",
+                    "api_base_url": "https://openrouter.ai/api/v1/chat/completions",
+                    "api_key": "sk-or-123",
+                    "models": ["anthropic/claude-sonnet-4"]
+                }
+            ],
+            "Router": { "default": "openrouter,anthropic/claude-sonnet-4" }
+        }"#;
+        let (format, providers) = parse_external_config(content, None).unwrap();
+        assert_eq!(format, ExternalFormat::ClaudeCodeRouter);
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].app, AppType::Codex);
+        assert_eq!(providers[0].base_url, "https://openrouter.ai/api/v1");
+        assert_eq!(
+            providers[0].model.as_deref(),
+            Some("anthropic/claude-sonnet-4")
+        );
+    }
+
+    #[test]
+    fn parses_yaml_profiles_with_env_and_flat_fields() {
+        let content = r#"
+profiles:
+  kimi:
+    env:
+      ANTHROPIC_BASE_URL: https://api.moonshot.cn/anthropic
+      ANTHROPIC_AUTH_TOKEN: sk-kimi
+      ANTHROPIC_MODEL: kimi-k2
+  relay:
+    base_url: https://relay.example.com
+    api_key: sk-relay
+    app: codex
+"#;
+        let (format, providers) = parse_external_config(content, None).unwrap();
+        assert_eq!(format, ExternalFormat::ProfileList);
+        assert_eq!(providers.len(), 2);
+        assert_eq!(providers[0].name, "kimi");
+        assert_eq!(providers[0].app, AppType::Claude);
+        assert_eq!(providers[0].model.as_deref(), Some("kimi-k2"));
+        assert_eq!(providers[1].app, AppType::Codex);
+        assert_eq!(providers[1].api_key, "sk-relay");
+    }
+
+    #[test]
+    fn parses_shell_env_switcher_sections() {
+        let content = r#"#!/bin/bash
+# GLM
+glm() {
+    export ANTHROPIC_BASE_URL="https://open.bigmodel.cn/api/anthropic"
+    export ANTHROPIC_AUTH_TOKEN='sk-glm'
+}
+
+case "$1" in
+  work)
+    export OPENAI_BASE_URL=https://work.example.com/v1; export OPENAI_API_KEY=sk-work;;
+  *)
+    echo "unknown"
+esac
+"#;
+        let (format, providers) = parse_external_config(content, None).unwrap();
+        assert_eq!(format, ExternalFormat::EnvScript);
+        assert_eq!(providers.len(), 2);
+        assert_eq!(providers[0].name, "glm");
+        assert_eq!(providers[0].api_key, "sk-glm");
+        assert_eq!(providers[1].name, "work");
+        assert_eq!(providers[1].app, AppType::Codex);
+        assert_eq!(providers[1].base_url, "https://work.example.com/v1");
+    }
+
+    #[test]
+    fn rejects_unrecognized_content() {
+        assert!(parse_external_config("hello world", None).is_err());
+    }
+}
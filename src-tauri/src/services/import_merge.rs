@@ -0,0 +1,246 @@
+//! 合并导入
+//!
+//! 将 CC Switch SQL 导出中的供应商按指定策略合并到当前数据库，而不是整库替换。
+//! 对每个供应商返回处理结果，便于前端展示导入报告。
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::database::{BatchOp, Database};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::ProviderService;
+
+/// 与现有供应商 ID 冲突时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// 用导入的供应商覆盖现有供应商
+    Overwrite,
+    /// 保留现有供应商，跳过导入项
+    KeepExisting,
+    /// 以新 ID 导入，与现有供应商并存
+    RenameIncoming,
+    /// 字段级合并：导入项的字段覆盖现有同名字段，其余保留
+    MergeFields,
+}
+
+/// 单个供应商的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportAction {
+    Added,
+    Overwritten,
+    Skipped,
+    Renamed,
+    Merged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemReport {
+    pub app: String,
+    pub id: String,
+    pub name: String,
+    pub action: ImportAction,
+    /// 重命名导入时的新 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportMergeReport {
+    /// 合并前自动生成的数据库备份 ID
+    pub backup_id: String,
+    pub items: Vec<ImportItemReport>,
+}
+
+const MERGE_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 从 SQL 导出文件按策略合并供应商
+pub fn import_with_strategy(
+    db: &Database,
+    source_path: &Path,
+    strategy: MergeStrategy,
+) -> Result<ImportMergeReport, AppError> {
+    let sql = std::fs::read_to_string(source_path).map_err(|e| AppError::io(source_path, e))?;
+    import_sql_with_strategy(db, &sql, strategy)
+}
+
+/// 从 SQL 导出文本按策略合并供应商
+pub fn import_sql_with_strategy(
+    db: &Database,
+    sql: &str,
+    strategy: MergeStrategy,
+) -> Result<ImportMergeReport, AppError> {
+    crate::read_only::ensure_writable()?;
+    let source = Database::open_sql_export(sql)?;
+
+    let backup_id = db
+        .backup_database_file()?
+        .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .unwrap_or_default();
+
+    let mut items = Vec::new();
+    let mut ops = Vec::new();
+    for app in MERGE_APPS {
+        let app_key = app.as_str();
+        let existing = db.get_all_providers(app_key)?;
+        let mut taken: HashSet<String> = existing.keys().cloned().collect();
+
+        for (_, incoming) in source.get_all_providers(app_key)? {
+            let id = incoming.id.clone();
+            let name = incoming.name.clone();
+            let (to_save, action) = resolve_provider(existing.get(&id), incoming, strategy, &taken);
+
+            let new_id = match (&to_save, action) {
+                (Some(provider), ImportAction::Renamed) => Some(provider.id.clone()),
+                _ => None,
+            };
+            if let Some(provider) = to_save {
+                taken.insert(provider.id.clone());
+                let app_type = app_key.to_string();
+                ops.push(match action {
+                    ImportAction::Overwritten | ImportAction::Merged => {
+                        BatchOp::UpdateProvider { app_type, provider }
+                    }
+                    _ => BatchOp::AddProvider { app_type, provider },
+                });
+            }
+
+            items.push(ImportItemReport {
+                app: app_key.to_string(),
+                id,
+                name,
+                action,
+                new_id,
+            });
+        }
+    }
+
+    // 全部写入放在一个事务中，中途失败时不会留下部分导入的数据
+    db.transaction(&ops)?;
+    Ok(ImportMergeReport { backup_id, items })
+}
+
+/// 决定单个导入供应商的处理方式，返回需要保存的供应商（None 表示跳过）
+fn resolve_provider(
+    existing: Option<&Provider>,
+    incoming: Provider,
+    strategy: MergeStrategy,
+    taken_ids: &HashSet<String>,
+) -> (Option<Provider>, ImportAction) {
+    let Some(existing) = existing else {
+        return (Some(incoming), ImportAction::Added);
+    };
+
+    match strategy {
+        MergeStrategy::Overwrite => (Some(incoming), ImportAction::Overwritten),
+        MergeStrategy::KeepExisting => (None, ImportAction::Skipped),
+        MergeStrategy::RenameIncoming => {
+            let mut renamed = incoming;
+            renamed.id = unique_id(&renamed.id, taken_ids);
+            renamed.name = format!("{} (imported)", renamed.name);
+            (Some(renamed), ImportAction::Renamed)
+        }
+        MergeStrategy::MergeFields => {
+            let mut merged = existing.clone();
+            ProviderService::merge_json(&mut merged.settings_config, &incoming.settings_config);
+            merged.name = incoming.name;
+            merged.website_url = incoming.website_url.or(merged.website_url);
+            merged.category = incoming.category.or(merged.category);
+            merged.notes = incoming.notes.or(merged.notes);
+            merged.icon = incoming.icon.or(merged.icon);
+            merged.icon_color = incoming.icon_color.or(merged.icon_color);
+            (Some(merged), ImportAction::Merged)
+        }
+    }
+}
+
+fn unique_id(base: &str, taken_ids: &HashSet<String>) -> String {
+    let mut candidate = format!("{base}-imported");
+    let mut counter = 2;
+    while taken_ids.contains(&candidate) {
+        candidate = format!("{base}-imported-{counter}");
+        counter += 1;
+    }
+    candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn provider(id: &str, config: serde_json::Value) -> Provider {
+        Provider::with_id(id.to_string(), id.to_uppercase(), config, None)
+    }
+
+    #[test]
+    fn new_providers_are_always_added() {
+        let (saved, action) = resolve_provider(
+            None,
+            provider("a", json!({})),
+            MergeStrategy::KeepExisting,
+            &HashSet::new(),
+        );
+        assert_eq!(action, ImportAction::Added);
+        assert_eq!(saved.unwrap().id, "a");
+    }
+
+    #[test]
+    fn rename_incoming_picks_free_id() {
+        let existing = provider("a", json!({}));
+        let taken = HashSet::from(["a".to_string(), "a-imported".to_string()]);
+        let (saved, action) = resolve_provider(
+            Some(&existing),
+            provider("a", json!({})),
+            MergeStrategy::RenameIncoming,
+            &taken,
+        );
+        assert_eq!(action, ImportAction::Renamed);
+        assert_eq!(saved.unwrap().id, "a-imported-2");
+    }
+
+    #[test]
+    fn merge_fields_keeps_existing_keys_and_applies_incoming() {
+        let existing = provider(
+            "a",
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "old", "KEEP": "1" } }),
+        );
+        let incoming = provider("a", json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "new" } }));
+        let (saved, action) = resolve_provider(
+            Some(&existing),
+            incoming,
+            MergeStrategy::MergeFields,
+            &HashSet::new(),
+        );
+        assert_eq!(action, ImportAction::Merged);
+        assert_eq!(
+            saved.unwrap().settings_config,
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "new", "KEEP": "1" } })
+        );
+    }
+
+    #[test]
+    fn keep_existing_skips_conflicts() {
+        let existing = provider("a", json!({}));
+        let (saved, action) = resolve_provider(
+            Some(&existing),
+            provider("a", json!({ "x": 1 })),
+            MergeStrategy::KeepExisting,
+            &HashSet::new(),
+        );
+        assert_eq!(action, ImportAction::Skipped);
+        assert!(saved.is_none());
+    }
+}
@@ -0,0 +1,343 @@
+//! API Key 有效性检查
+//!
+//! 与流式健康检查不同，这里不发起真实对话，而是按供应商类型请求轻量的模型列表接口：
+//! - Claude：Anthropic `GET /v1/models`
+//! - Codex：OpenAI 兼容 `GET /v1/models`
+//! - Gemini：`GET /v1beta/models`（`x-goog-api-key`）
+//!
+//! 并根据状态码与响应内容给出具体原因（密钥无效、额度耗尽、Base URL 错误、TLS 失败等）。
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+
+/// 单次探测超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 响应内容在结果中保留的最大长度
+const MESSAGE_LIMIT: usize = 300;
+
+/// 检查结论
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyCheckReason {
+    Valid,
+    InvalidKey,
+    QuotaExhausted,
+    RateLimited,
+    WrongBaseUrl,
+    TlsFailure,
+    ConnectionFailed,
+    Timeout,
+    UpstreamError,
+    Unknown,
+}
+
+/// Key 检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyCheckResult {
+    pub valid: bool,
+    pub reason: KeyCheckReason,
+    /// 已脱敏的上游响应或错误信息
+    pub message: String,
+    pub http_status: Option<u16>,
+    pub probe_url: String,
+    pub response_time_ms: Option<u64>,
+    pub checked_at: i64,
+}
+
+pub struct KeyCheckService;
+
+impl KeyCheckService {
+    /// 使用供应商对应的探测接口检查 API Key
+    pub async fn validate(
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<KeyCheckResult, AppError> {
+        if matches!(app_type, AppType::OpenCode) {
+            return Err(AppError::localized(
+                "opencode_no_key_check",
+                "OpenCode 暂不支持 Key 检查",
+                "OpenCode does not support key validation yet",
+            ));
+        }
+
+        let adapter = get_adapter(app_type);
+        let base_url = adapter
+            .extract_base_url(provider)
+            .map_err(|e| AppError::Message(format!("Failed to extract base_url: {e}")))?;
+        let auth = adapter
+            .extract_auth(provider)
+            .ok_or_else(|| AppError::Message("API Key not found".to_string()))?;
+
+        let probe_url = Self::probe_url(app_type, &base_url, |base, endpoint| {
+            adapter.build_url(base, endpoint)
+        });
+        let checked_at = chrono::Utc::now().timestamp();
+
+        if url::Url::parse(&probe_url).is_err() {
+            return Ok(KeyCheckResult {
+                valid: false,
+                reason: KeyCheckReason::WrongBaseUrl,
+                message: format!("Invalid base URL: {base_url}"),
+                http_status: None,
+                probe_url,
+                response_time_ms: None,
+                checked_at,
+            });
+        }
+
+        let client = crate::proxy::http_client::get();
+        let mut request = adapter
+            .add_auth_headers(client.get(&probe_url), &auth)
+            .timeout(PROBE_TIMEOUT);
+        if matches!(app_type, AppType::Claude) {
+            request = request.header("anthropic-version", "2023-06-01");
+        }
+
+        let start = Instant::now();
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                return Ok(KeyCheckResult {
+                    valid: false,
+                    reason: Self::classify_request_error(&e),
+                    message: Self::truncate(&Self::error_chain(&e)),
+                    http_status: None,
+                    probe_url,
+                    response_time_ms: Some(start.elapsed().as_millis() as u64),
+                    checked_at,
+                });
+            }
+        };
+
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response.text().await.unwrap_or_default();
+        let reason = Self::classify_response(status, &content_type, &body);
+
+        Ok(KeyCheckResult {
+            valid: reason == KeyCheckReason::Valid,
+            reason,
+            message: Self::truncate(&body),
+            http_status: Some(status),
+            probe_url,
+            response_time_ms: Some(start.elapsed().as_millis() as u64),
+            checked_at,
+        })
+    }
+
+    pub(crate) fn probe_url(
+        app_type: &AppType,
+        base_url: &str,
+        build_url: impl Fn(&str, &str) -> String,
+    ) -> String {
+        let base = base_url.trim_end_matches('/');
+        match app_type {
+            AppType::Gemini => build_url(base, "/v1beta/models"),
+            _ if base.ends_with("/v1") => format!("{base}/models"),
+            _ => format!("{base}/v1/models"),
+        }
+    }
+
+    /// 根据状态码和响应内容判断原因
+    fn classify_response(status: u16, content_type: &str, body: &str) -> KeyCheckReason {
+        let lower = body.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+        let quota_words = [
+            "quota",
+            "insufficient",
+            "balance",
+            "billing",
+            "credit",
+            "余额",
+            "额度",
+        ];
+        let key_words = [
+            "api key",
+            "api_key",
+            "apikey",
+            "invalid key",
+            "invalid_key",
+            "authentication",
+            "unauthorized",
+            "令牌",
+        ];
+
+        match status {
+            200..=299 => {
+                // 返回网页而非 JSON，通常是 Base URL 指向了站点首页
+                if content_type.contains("text/html") || lower.trim_start().starts_with("<!doctype")
+                {
+                    KeyCheckReason::WrongBaseUrl
+                } else {
+                    KeyCheckReason::Valid
+                }
+            }
+            402 => KeyCheckReason::QuotaExhausted,
+            401 | 403 if mentions(&quota_words) => KeyCheckReason::QuotaExhausted,
+            401 | 403 => KeyCheckReason::InvalidKey,
+            429 if mentions(&quota_words) => KeyCheckReason::QuotaExhausted,
+            429 => KeyCheckReason::RateLimited,
+            404 | 405 => KeyCheckReason::WrongBaseUrl,
+            // Gemini 对无效 Key 返回 400 API_KEY_INVALID
+            400 if mentions(&key_words) => KeyCheckReason::InvalidKey,
+            400 if mentions(&quota_words) => KeyCheckReason::QuotaExhausted,
+            500..=599 => KeyCheckReason::UpstreamError,
+            _ => KeyCheckReason::Unknown,
+        }
+    }
+
+    fn error_chain(e: &reqwest::Error) -> String {
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(e);
+        while let Some(inner) = source {
+            message.push_str(": ");
+            message.push_str(&inner.to_string());
+            source = inner.source();
+        }
+        message
+    }
+
+    fn classify_request_error(e: &reqwest::Error) -> KeyCheckReason {
+        if e.is_timeout() {
+            return KeyCheckReason::Timeout;
+        }
+        Self::classify_error_text(&Self::error_chain(e))
+    }
+
+    fn classify_error_text(text: &str) -> KeyCheckReason {
+        let lower = text.to_lowercase();
+        if ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|w| lower.contains(w))
+        {
+            KeyCheckReason::TlsFailure
+        } else if [
+            "dns error",
+            "failed to lookup",
+            "no such host",
+            "name or service",
+        ]
+        .iter()
+        .any(|w| lower.contains(w))
+        {
+            KeyCheckReason::WrongBaseUrl
+        } else if lower.contains("timed out") {
+            KeyCheckReason::Timeout
+        } else {
+            KeyCheckReason::ConnectionFailed
+        }
+    }
+
+    fn truncate(text: &str) -> String {
+        let redacted = crate::redact::redact_secrets(text.trim());
+        if redacted.chars().count() <= MESSAGE_LIMIT {
+            return redacted.into_owned();
+        }
+        let mut out: String = redacted.chars().take(MESSAGE_LIMIT).collect();
+        out.push('…');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_url_per_app() {
+        let join = |base: &str, endpoint: &str| format!("{base}{endpoint}");
+        assert_eq!(
+            KeyCheckService::probe_url(&AppType::Claude, "https://api.anthropic.com", join),
+            "https://api.anthropic.com/v1/models"
+        );
+        assert_eq!(
+            KeyCheckService::probe_url(&AppType::Codex, "https://relay.example.com/v1/", join),
+            "https://relay.example.com/v1/models"
+        );
+        assert_eq!(
+            KeyCheckService::probe_url(
+                &AppType::Gemini,
+                "https://generativelanguage.googleapis.com",
+                join
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models"
+        );
+    }
+
+    #[test]
+    fn classifies_responses() {
+        use KeyCheckReason::*;
+        let json = "application/json";
+        assert_eq!(
+            KeyCheckService::classify_response(200, json, "{\"data\":[]}"),
+            Valid
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(200, "text/html", "<!DOCTYPE html>"),
+            WrongBaseUrl
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(401, json, "{\"error\":\"invalid x-api-key\"}"),
+            InvalidKey
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(
+                429,
+                json,
+                "{\"error\":{\"code\":\"insufficient_quota\"}}"
+            ),
+            QuotaExhausted
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(429, json, "slow down"),
+            RateLimited
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(
+                400,
+                json,
+                "{\"error\":{\"status\":\"INVALID_ARGUMENT\",\"details\":[{\"reason\":\"API_KEY_INVALID\"}]}}"
+            ),
+            InvalidKey
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(404, json, "not found"),
+            WrongBaseUrl
+        );
+        assert_eq!(
+            KeyCheckService::classify_response(502, json, "bad gateway"),
+            UpstreamError
+        );
+    }
+
+    #[test]
+    fn classifies_transport_errors() {
+        use KeyCheckReason::*;
+        assert_eq!(
+            KeyCheckService::classify_error_text(
+                "error sending request: invalid peer certificate: UnknownIssuer"
+            ),
+            TlsFailure
+        );
+        assert_eq!(
+            KeyCheckService::classify_error_text("dns error: failed to lookup address information"),
+            WrongBaseUrl
+        );
+        assert_eq!(
+            KeyCheckService::classify_error_text("tcp connect error: Connection refused"),
+            ConnectionFailed
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! API Key 轮换
+//!
+//! 引导式更换供应商密钥：
+//! 1. 先用新 Key 请求供应商的探测接口（见 [`KeyCheckService`]），无效则直接拒绝；
+//! 2. 通过常规更新流程写入数据库，若该供应商为当前供应商则同时更新 live 配置；
+//! 3. 旧配置（含旧 Key）作为带时间戳的历史版本保留，可在历史记录中恢复；
+//! 4. 在审计日志中记录一次轮换（仅记录新旧 Key 的哈希）。
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::audit_log;
+use crate::error::AppError;
+use crate::redact::mask_secret;
+use crate::services::key_check::{KeyCheckResult, KeyCheckService};
+use crate::services::secret_access::{self, API_KEY_FIELD};
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 轮换结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotationResult {
+    pub provider_id: String,
+    /// 新 Key 的检查结果（OpenCode 暂不支持检查，为 None）
+    pub validation: Option<KeyCheckResult>,
+    /// 保存旧 Key 的历史版本号
+    pub archived_revision: Option<i64>,
+    pub old_key: Option<String>,
+    pub new_key: String,
+    /// 毫秒时间戳
+    pub rotated_at: i64,
+}
+
+pub struct KeyRotationService;
+
+impl KeyRotationService {
+    /// 校验并替换供应商的主 API Key
+    pub async fn rotate(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        new_key: &str,
+    ) -> Result<KeyRotationResult, AppError> {
+        let new_key = new_key.trim();
+        if new_key.is_empty() {
+            return Err(AppError::InvalidInput("新的 API Key 不能为空".to_string()));
+        }
+
+        let mut provider = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+        let old_key =
+            secret_access::provider_secret(state, &app_type, provider_id, API_KEY_FIELD).ok();
+        if old_key.as_deref() == Some(new_key) {
+            return Err(AppError::localized(
+                "key_rotation.unchanged",
+                "新的 API Key 与当前 Key 相同",
+                "The new API key is the same as the current one",
+            ));
+        }
+
+        secret_access::set_api_key(&app_type, &mut provider, new_key)?;
+
+        let validation = if matches!(app_type, AppType::OpenCode) {
+            None
+        } else {
+            let result = KeyCheckService::validate(&app_type, &provider).await?;
+            if !result.valid {
+                return Err(AppError::localized(
+                    "key_rotation.invalid_key",
+                    format!(
+                        "新的 API Key 未通过检查（{:?}）：{}",
+                        result.reason, result.message
+                    ),
+                    format!(
+                        "The new API key failed validation ({:?}): {}",
+                        result.reason, result.message
+                    ),
+                ));
+            }
+            Some(result)
+        };
+
+        // 更新流程中会同步阻塞地写入 live 配置
+        tokio::task::block_in_place(|| ProviderService::update(state, app_type.clone(), provider))?;
+
+        let archived_revision = state
+            .db
+            .get_provider_revisions(app_type.as_str(), provider_id)?
+            .first()
+            .map(|revision| revision.revision);
+        audit_log::record_key_rotation(app_type.as_str(), provider_id, old_key.as_deref(), new_key);
+        log::info!(
+            "已轮换供应商 {provider_id} ({}) 的 API Key",
+            app_type.as_str()
+        );
+
+        Ok(KeyRotationResult {
+            provider_id: provider_id.to_string(),
+            validation,
+            archived_revision,
+            old_key: old_key.as_deref().map(mask_secret),
+            new_key: mask_secret(new_key),
+            rotated_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+}
@@ -0,0 +1,583 @@
+//! 局域网点对点同步
+//!
+//! 不经过任何云服务，在同一局域网内的两台 CC Switch 之间直接交换供应商与 MCP 数据：
+//!
+//! 1. 分享方调用 [`LanSyncService::start_share`]：监听随机 TCP 端口，通过 mDNS
+//!    广播 `_cc-switch._tcp` 服务，并生成 6 位配对码显示给用户；
+//! 2. 接收方通过 [`LanSyncService::discover_peers`] 发现对端，输入配对码后调用
+//!    [`LanSyncService::pull_from_peer`]；
+//! 3. 握手：双方以配对码执行 SPAKE2 密钥交换，得到高熵的会话密钥。窃听者无法据此
+//!    离线穷举配对码，主动攻击者每次连接只能猜一次；
+//! 4. 接收方先发送会话密钥的确认值，分享方验证通过后回复自己的确认值，并发送以
+//!    会话密钥加密的 SQL 导出；接收方验证分享方的确认值后才解密与合并，双方都确认
+//!    对方知道配对码。
+//!
+//! 会话一次有效，5 分钟过期，连续 3 次配对失败即关闭。
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::prelude::*;
+use hmac::{Hmac, Mac};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use spake2::{Ed25519Group, Identity, Password, Spake2};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::import_merge::{self, ImportMergeReport, MergeStrategy};
+use crate::services::webdav_sync::device_name;
+
+/// mDNS 服务类型
+const SERVICE_TYPE: &str = "_cc-switch._tcp.local.";
+const PROTOCOL_VERSION: u32 = 2;
+/// SPAKE2 双方共用的身份标识
+const PAKE_IDENTITY: &[u8] = b"cc-switch/lan-sync";
+const SESSION_TTL: Duration = Duration::from_secs(5 * 60);
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_FAILED_ATTEMPTS: u32 = 3;
+/// 单次传输上限，防止恶意对端耗尽内存
+const MAX_PAYLOAD_BYTES: usize = 64 * 1024 * 1024;
+const MAX_LINE_BYTES: u64 = 4096;
+
+/// 正在进行的分享会话（返回给前端）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanShareSession {
+    pub code: String,
+    pub port: u16,
+    pub device: String,
+    pub expires_at: i64,
+}
+
+/// 发现的对端
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPeer {
+    pub device: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+}
+
+/// 拉取结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanPullResult {
+    pub device: String,
+    pub providers: ImportMergeReport,
+    /// 新增或覆盖的 MCP 服务器 ID
+    pub mcp_servers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    version: u32,
+    device: String,
+    /// 分享方的 SPAKE2 消息（Base64）
+    pake: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Proof {
+    device: String,
+    /// 接收方的 SPAKE2 消息（Base64）
+    pake: String,
+    /// 接收方对会话密钥的确认值（Base64）
+    confirm: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Reply {
+    ok: bool,
+    #[serde(default)]
+    size: usize,
+    /// 分享方对会话密钥的确认值（Base64）
+    #[serde(default)]
+    confirm: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// 确认值与载荷密钥的用途标签，避免同一会话密钥被用于不同目的
+const SHARER_CONFIRM: &[u8] = b"confirm:sharer";
+const RECEIVER_CONFIRM: &[u8] = b"confirm:receiver";
+const PAYLOAD_KEY: &[u8] = b"payload";
+
+struct ActiveShare {
+    shutdown: oneshot::Sender<()>,
+    mdns: ServiceDaemon,
+}
+
+static ACTIVE_SHARE: Mutex<Option<ActiveShare>> = Mutex::new(None);
+
+/// 以配对码开始 SPAKE2 交换，返回本方状态与要发送的消息
+fn start_pairing(code: &str) -> (Spake2<Ed25519Group>, String) {
+    let (pake, message) = Spake2::<Ed25519Group>::start_symmetric(
+        &Password::new(code.trim().as_bytes()),
+        &Identity::new(PAKE_IDENTITY),
+    );
+    (pake, BASE64_STANDARD.encode(message))
+}
+
+/// 结合对方的消息完成交换，得到会话密钥（双方配对码不同时得到的密钥也不同）
+fn finish_pairing(pake: Spake2<Ed25519Group>, peer_message: &str) -> Result<Vec<u8>, AppError> {
+    let message = BASE64_STANDARD
+        .decode(peer_message)
+        .map_err(|e| net_error("对端配对消息格式无效", e))?;
+    pake.finish(&message)
+        .map_err(|e| net_error("配对密钥交换失败", e))
+}
+
+fn session_mac(session_key: &[u8], label: &[u8]) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(session_key).expect("HMAC accepts keys of any length");
+    mac.update(label);
+    mac
+}
+
+fn confirmation(session_key: &[u8], role: &[u8]) -> String {
+    BASE64_STANDARD.encode(session_mac(session_key, role).finalize().into_bytes())
+}
+
+/// 常数时间校验对方的确认值
+fn verify_confirmation(session_key: &[u8], role: &[u8], confirm: &str) -> bool {
+    BASE64_STANDARD
+        .decode(confirm)
+        .is_ok_and(|tag| session_mac(session_key, role).verify_slice(&tag).is_ok())
+}
+
+fn payload_key(session_key: &[u8]) -> [u8; 32] {
+    session_mac(session_key, PAYLOAD_KEY)
+        .finalize()
+        .into_bytes()
+        .into()
+}
+
+fn net_error(context: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::Message(format!("{context}: {e}"))
+}
+
+async fn read_json_line<T: for<'de> Deserialize<'de>>(
+    reader: &mut BufReader<TcpStream>,
+) -> Result<T, AppError> {
+    let mut line = String::new();
+    let read = tokio::time::timeout(
+        IO_TIMEOUT,
+        (&mut *reader).take(MAX_LINE_BYTES).read_line(&mut line),
+    )
+    .await
+    .map_err(|_| AppError::Message("局域网同步超时".to_string()))?
+    .map_err(|e| net_error("读取对端数据失败", e))?;
+    if read == 0 {
+        return Err(AppError::Message("对端已关闭连接".to_string()));
+    }
+    serde_json::from_str(line.trim()).map_err(|e| net_error("对端数据格式无效", e))
+}
+
+async fn write_json_line<T: Serialize>(
+    stream: &mut BufReader<TcpStream>,
+    value: &T,
+) -> Result<(), AppError> {
+    let mut line = serde_json::to_vec(value).map_err(|e| AppError::JsonSerialize { source: e })?;
+    line.push(b'\n');
+    stream
+        .get_mut()
+        .write_all(&line)
+        .await
+        .map_err(|e| net_error("发送数据失败", e))
+}
+
+fn lock_share() -> std::sync::MutexGuard<'static, Option<ActiveShare>> {
+    ACTIVE_SHARE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 局域网同步业务
+pub struct LanSyncService;
+
+impl LanSyncService {
+    /// 开始分享本机数据：监听端口、广播 mDNS 并返回配对码
+    pub async fn start_share(db: Arc<Database>) -> Result<LanShareSession, AppError> {
+        Self::stop_share();
+
+        let listener = TcpListener::bind(("0.0.0.0", 0))
+            .await
+            .map_err(|e| net_error("监听局域网端口失败", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| net_error("读取监听端口失败", e))?
+            .port();
+
+        let code = format!("{:06}", uuid::Uuid::new_v4().as_u128() % 1_000_000);
+        let device = device_name();
+        let instance = format!("{device}-{port}");
+        let host_name = format!("{}.local.", instance.replace([' ', '.'], "-"));
+
+        let mdns = ServiceDaemon::new().map_err(|e| net_error("启动 mDNS 失败", e))?;
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance,
+            &host_name,
+            "",
+            port,
+            &[("device", device.as_str())][..],
+        )
+        .map_err(|e| net_error("创建 mDNS 服务失败", e))?
+        .enable_addr_auto();
+        mdns.register(info)
+            .map_err(|e| net_error("注册 mDNS 服务失败", e))?;
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        *lock_share() = Some(ActiveShare { shutdown, mdns });
+
+        let expires_at = chrono::Utc::now().timestamp_millis() + SESSION_TTL.as_millis() as i64;
+        tokio::spawn(Self::serve(listener, db, code.clone(), shutdown_rx));
+
+        log::info!("局域网分享已开启: 端口 {port}");
+        Ok(LanShareSession {
+            code,
+            port,
+            device,
+            expires_at,
+        })
+    }
+
+    /// 停止分享（未在分享时无操作）
+    pub fn stop_share() {
+        if let Some(share) = lock_share().take() {
+            let _ = share.shutdown.send(());
+            if let Err(e) = share.mdns.shutdown() {
+                log::warn!("关闭 mDNS 失败: {e}");
+            }
+            log::info!("局域网分享已停止");
+        }
+    }
+
+    async fn serve(
+        listener: TcpListener,
+        db: Arc<Database>,
+        code: String,
+        mut shutdown: oneshot::Receiver<()>,
+    ) {
+        let deadline = tokio::time::sleep(SESSION_TTL);
+        tokio::pin!(deadline);
+        let mut failed_attempts = 0;
+
+        loop {
+            let (stream, peer) = tokio::select! {
+                _ = &mut shutdown => return,
+                _ = &mut deadline => {
+                    log::info!("局域网分享已过期");
+                    break;
+                }
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        log::warn!("接受局域网连接失败: {e}");
+                        continue;
+                    }
+                },
+            };
+
+            match Self::handle_peer(stream, &db, &code).await {
+                Ok(device) => {
+                    log::info!("已向 {device} ({peer}) 发送数据");
+                    break;
+                }
+                Err(e) => {
+                    failed_attempts += 1;
+                    log::warn!("局域网同步请求失败 ({peer}): {e}");
+                    if failed_attempts >= MAX_FAILED_ATTEMPTS {
+                        log::warn!("配对失败次数过多，关闭局域网分享");
+                        break;
+                    }
+                }
+            }
+        }
+
+        Self::stop_share();
+    }
+
+    async fn handle_peer(
+        stream: TcpStream,
+        db: &Arc<Database>,
+        code: &str,
+    ) -> Result<String, AppError> {
+        let mut stream = BufReader::new(stream);
+        let (pake, message) = start_pairing(code);
+        write_json_line(
+            &mut stream,
+            &Hello {
+                version: PROTOCOL_VERSION,
+                device: device_name(),
+                pake: message,
+            },
+        )
+        .await?;
+
+        let proof: Proof = read_json_line(&mut stream).await?;
+        let session_key = finish_pairing(pake, &proof.pake)?;
+        if !verify_confirmation(&session_key, RECEIVER_CONFIRM, &proof.confirm) {
+            write_json_line(
+                &mut stream,
+                &Reply {
+                    ok: false,
+                    size: 0,
+                    confirm: None,
+                    error: Some("配对码错误".to_string()),
+                },
+            )
+            .await?;
+            return Err(AppError::Message(format!(
+                "{} 提供的配对码错误",
+                proof.device
+            )));
+        }
+
+        let db = db.clone();
+        let sql = tokio::task::spawn_blocking(move || db.export_sql_string())
+            .await
+            .map_err(|e| net_error("导出数据库失败", e))??;
+        let payload = crate::crypto::encrypt_with_key(sql.as_bytes(), &payload_key(&session_key))?;
+
+        write_json_line(
+            &mut stream,
+            &Reply {
+                ok: true,
+                size: payload.len(),
+                confirm: Some(confirmation(&session_key, SHARER_CONFIRM)),
+                error: None,
+            },
+        )
+        .await?;
+        stream
+            .get_mut()
+            .write_all(&payload)
+            .await
+            .map_err(|e| net_error("发送数据失败", e))?;
+        stream
+            .get_mut()
+            .shutdown()
+            .await
+            .map_err(|e| net_error("关闭连接失败", e))?;
+        Ok(proof.device)
+    }
+
+    /// 通过 mDNS 发现局域网内正在分享的 CC Switch
+    pub async fn discover_peers(timeout: Duration) -> Result<Vec<LanPeer>, AppError> {
+        tokio::task::spawn_blocking(move || {
+            let mdns = ServiceDaemon::new().map_err(|e| net_error("启动 mDNS 失败", e))?;
+            let receiver = mdns
+                .browse(SERVICE_TYPE)
+                .map_err(|e| net_error("mDNS 发现失败", e))?;
+
+            let deadline = std::time::Instant::now() + timeout;
+            let mut peers: Vec<LanPeer> = Vec::new();
+            while let Some(remaining) = deadline.checked_duration_since(std::time::Instant::now()) {
+                let Ok(event) = receiver.recv_timeout(remaining) else {
+                    break;
+                };
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    let mut addresses = info
+                        .get_addresses()
+                        .iter()
+                        .map(IpAddr::to_string)
+                        .collect::<Vec<_>>();
+                    addresses.sort();
+                    let peer = LanPeer {
+                        device: info
+                            .get_property_val_str("device")
+                            .unwrap_or_else(|| info.get_fullname())
+                            .to_string(),
+                        addresses,
+                        port: info.get_port(),
+                    };
+                    if !peers
+                        .iter()
+                        .any(|p| p.port == peer.port && p.addresses == peer.addresses)
+                    {
+                        peers.push(peer);
+                    }
+                }
+            }
+
+            if let Err(e) = mdns.shutdown() {
+                log::warn!("关闭 mDNS 失败: {e}");
+            }
+            Ok(peers)
+        })
+        .await
+        .map_err(|e| net_error("mDNS 发现失败", e))?
+    }
+
+    /// 从对端拉取数据，并按策略合并供应商；本地没有的 MCP 服务器会被添加
+    /// （策略为覆盖时同时覆盖同 ID 的 MCP 服务器）
+    pub async fn pull_from_peer(
+        db: Arc<Database>,
+        address: &str,
+        port: u16,
+        code: &str,
+        strategy: MergeStrategy,
+    ) -> Result<LanPullResult, AppError> {
+        let ip: IpAddr = address
+            .trim()
+            .parse()
+            .map_err(|e| AppError::InvalidInput(format!("对端地址无效: {e}")))?;
+        let stream =
+            tokio::time::timeout(IO_TIMEOUT, TcpStream::connect(SocketAddr::new(ip, port)))
+                .await
+                .map_err(|_| AppError::Message("连接对端超时".to_string()))?
+                .map_err(|e| net_error("连接对端失败", e))?;
+        let mut stream = BufReader::new(stream);
+
+        let hello: Hello = read_json_line(&mut stream).await?;
+        if hello.version != PROTOCOL_VERSION {
+            return Err(AppError::Message(format!(
+                "对端协议版本不兼容: {}",
+                hello.version
+            )));
+        }
+        let (pake, message) = start_pairing(code);
+        let session_key = finish_pairing(pake, &hello.pake)?;
+        write_json_line(
+            &mut stream,
+            &Proof {
+                device: device_name(),
+                pake: message,
+                confirm: confirmation(&session_key, RECEIVER_CONFIRM),
+            },
+        )
+        .await?;
+
+        let reply: Reply = read_json_line(&mut stream).await?;
+        if !reply.ok {
+            return Err(AppError::localized(
+                "lan_sync.pairing_failed",
+                format!("配对失败: {}", reply.error.unwrap_or_default()),
+                "Pairing failed: the confirmation code was rejected",
+            ));
+        }
+        // 分享方同样须证明知道配对码，否则可能是冒充的对端
+        if !reply
+            .confirm
+            .is_some_and(|confirm| verify_confirmation(&session_key, SHARER_CONFIRM, &confirm))
+        {
+            return Err(AppError::localized(
+                "lan_sync.peer_unverified",
+                "无法验证对端身份，已中止同步",
+                "Could not verify the sharing device; sync aborted",
+            ));
+        }
+        if reply.size > MAX_PAYLOAD_BYTES {
+            return Err(AppError::Message(format!(
+                "对端数据过大: {} 字节",
+                reply.size
+            )));
+        }
+
+        let mut payload = vec![0u8; reply.size];
+        tokio::time::timeout(IO_TIMEOUT, stream.read_exact(&mut payload))
+            .await
+            .map_err(|_| AppError::Message("接收数据超时".to_string()))?
+            .map_err(|e| net_error("接收数据失败", e))?;
+
+        let plain = crate::crypto::decrypt_with_key(&payload, &payload_key(&session_key))?;
+        let sql = String::from_utf8(plain)
+            .map_err(|e| AppError::InvalidInput(format!("对端数据不是有效的 SQL 文本: {e}")))?;
+
+        let device = hello.device;
+        tokio::task::spawn_blocking(move || {
+            let providers = import_merge::import_sql_with_strategy(&db, &sql, strategy)?;
+            let mcp_servers = Self::merge_mcp_servers(&db, &sql, strategy)?;
+            Ok(LanPullResult {
+                device,
+                providers,
+                mcp_servers,
+            })
+        })
+        .await
+        .map_err(|e| net_error("合并对端数据失败", e))?
+    }
+
+    fn merge_mcp_servers(
+        db: &Database,
+        sql: &str,
+        strategy: MergeStrategy,
+    ) -> Result<Vec<String>, AppError> {
+        let source = Database::open_sql_export(sql)?;
+        let existing = db.get_all_mcp_servers()?;
+        let mut applied = Vec::new();
+        for (id, server) in source.get_all_mcp_servers()? {
+            if existing.contains_key(&id) && strategy != MergeStrategy::Overwrite {
+                continue;
+            }
+            db.save_mcp_server(&server)?;
+            applied.push(id);
+        }
+        Ok(applied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(sharer_code: &str, receiver_code: &str) -> (Vec<u8>, Vec<u8>) {
+        let (sharer, sharer_message) = start_pairing(sharer_code);
+        let (receiver, receiver_message) = start_pairing(receiver_code);
+        (
+            finish_pairing(sharer, &receiver_message).unwrap(),
+            finish_pairing(receiver, &sharer_message).unwrap(),
+        )
+    }
+
+    #[test]
+    fn matching_codes_agree_on_session_key_and_confirm_each_other() {
+        let (sharer, receiver) = pair("123456", " 123456 ");
+        assert_eq!(sharer, receiver);
+        assert!(verify_confirmation(
+            &sharer,
+            RECEIVER_CONFIRM,
+            &confirmation(&receiver, RECEIVER_CONFIRM)
+        ));
+        assert!(verify_confirmation(
+            &receiver,
+            SHARER_CONFIRM,
+            &confirmation(&sharer, SHARER_CONFIRM)
+        ));
+        // 角色标签不同，确认值不能被反射回去
+        assert!(!verify_confirmation(
+            &sharer,
+            SHARER_CONFIRM,
+            &confirmation(&receiver, RECEIVER_CONFIRM)
+        ));
+        // 载荷密钥与确认值分离，且每次交换都不同
+        assert_ne!(payload_key(&sharer).to_vec(), sharer);
+        assert_ne!(pair("123456", "123456").0, sharer);
+    }
+
+    #[test]
+    fn wrong_code_fails_confirmation_in_both_directions() {
+        let (sharer, receiver) = pair("123456", "654321");
+        assert_ne!(sharer, receiver);
+        assert!(!verify_confirmation(
+            &sharer,
+            RECEIVER_CONFIRM,
+            &confirmation(&receiver, RECEIVER_CONFIRM)
+        ));
+        assert!(!verify_confirmation(
+            &receiver,
+            SHARER_CONFIRM,
+            &confirmation(&sharer, SHARER_CONFIRM)
+        ));
+        assert!(!verify_confirmation(
+            &sharer,
+            RECEIVER_CONFIRM,
+            "not base64!"
+        ));
+    }
+}
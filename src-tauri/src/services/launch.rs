@@ -0,0 +1,257 @@
+//! 以指定供应商启动 CLI 与会话级环境变量
+//!
+//! 把供应商的端点与密钥作为环境变量注入到单个 `claude` / `codex` / `gemini` /
+//! `opencode` 进程或单个终端会话中，不修改任何全局配置文件，便于不同终端窗格同时使用
+//! 不同供应商：
+//!
+//! - Claude / Gemini：供应商配置 `env` 中的全部字符串项；
+//! - Codex：`auth` 中的密钥，以及配置中的端点（`OPENAI_BASE_URL`）；
+//! - OpenCode：通过 `OPENCODE_CONFIG_CONTENT` 追加该供应商的配置。
+//!
+//! 会话级注入有两种方式：[`session_script`] 生成可 `source` 的脚本（tmux 窗格、
+//! 临时 shell）；[`write_envrc`] 在项目目录生成 direnv 的 `.envrc`，进入目录时通过
+//! `cc-switch-cli env <app> <provider> --session` 注入，密钥不落在项目目录中。
+//!
+//! CLI 需要在 PATH 中可以找到。
+
+#[cfg(any(feature = "gui", test))]
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::{json, Map, Value};
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::services::provider::{render_env_script, EnvShell};
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+/// 生成文件的首行标记，用于判断 `.envrc` 是否可以覆盖
+const GENERATED_MARKER: &str = "# cc-switch:";
+
+/// 各应用的可执行文件名
+pub fn program(app_type: &AppType) -> &'static str {
+    match app_type {
+        AppType::Claude => "claude",
+        AppType::Codex => "codex",
+        AppType::Gemini => "gemini",
+        AppType::OpenCode => "opencode",
+    }
+}
+
+fn string_entries(section: Option<&Value>) -> Vec<(String, String)> {
+    section
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 启动时注入的环境变量
+pub fn launch_env(app_type: &AppType, provider: &Provider) -> Vec<(String, String)> {
+    let settings = &provider.settings_config;
+    match app_type {
+        AppType::Claude | AppType::Gemini => string_entries(settings.get("env")),
+        AppType::Codex => {
+            let mut vars = string_entries(settings.get("auth"));
+            if let Ok(base_url) = get_adapter(app_type).extract_base_url(provider) {
+                vars.push(("OPENAI_BASE_URL".to_string(), base_url));
+            }
+            vars
+        }
+        AppType::OpenCode => {
+            let mut providers = Map::new();
+            providers.insert(provider.id.clone(), settings.clone());
+            let config = json!({ "provider": providers });
+            vec![("OPENCODE_CONFIG_CONTENT".to_string(), config.to_string())]
+        }
+    }
+}
+
+fn header(provider: &Provider) -> String {
+    format!(
+        "{GENERATED_MARKER} {} ({})\n",
+        provider.name.replace(['\r', '\n'], " "),
+        provider.id
+    )
+}
+
+/// 可在当前 shell 中 `source` 的会话脚本（包含端点、模型与密钥）
+pub fn session_script(app_type: &AppType, provider: &Provider, shell: EnvShell) -> String {
+    format!(
+        "{}{}",
+        header(provider),
+        render_env_script(&launch_env(app_type, provider), shell)
+    )
+}
+
+/// direnv `.envrc` 内容：进入目录时由命令行工具注入，密钥不写入文件
+#[cfg(any(feature = "gui", test))]
+pub fn envrc(app_type: &AppType, provider: &Provider) -> String {
+    format!(
+        "{}# Generated by CC Switch; credentials are injected by cc-switch-cli, not stored here.\n\
+         eval \"$(cc-switch-cli env {} '{}' --session)\"\n",
+        header(provider),
+        app_type.as_str(),
+        provider.id.replace('\'', "'\\''")
+    )
+}
+
+/// 在目录中写入 `.envrc`；已存在且不是由 CC Switch 生成时拒绝覆盖
+#[cfg(any(feature = "gui", test))]
+pub fn write_envrc(
+    dir: &Path,
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<PathBuf, AppError> {
+    let path = dir.join(".envrc");
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        if !existing.starts_with(GENERATED_MARKER) {
+            return Err(AppError::localized(
+                "launch.envrc_exists",
+                format!("{} 已存在且不是由 CC Switch 生成，未覆盖", path.display()),
+                format!(
+                    "{} already exists and was not generated by CC Switch; not overwritten",
+                    path.display()
+                ),
+            ));
+        }
+    }
+    crate::config::atomic_write(&path, envrc(app_type, provider).as_bytes())?;
+    Ok(path)
+}
+
+/// 查找供应商（为空时使用当前供应商）
+pub fn resolve(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: Option<&str>,
+) -> Result<Provider, AppError> {
+    let id = match provider_id {
+        Some(id) => id.to_string(),
+        None => ProviderService::current(state, app_type.clone())?,
+    };
+    if id.is_empty() {
+        return Err(AppError::Message(format!(
+            "{} 尚未选择供应商",
+            app_type.as_str()
+        )));
+    }
+    state
+        .db
+        .get_provider_by_id(&id, app_type.as_str())?
+        .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))
+}
+
+/// 构造启动命令（供应商为空时使用当前供应商）
+pub fn command(
+    state: &AppState,
+    app_type: AppType,
+    provider_id: Option<&str>,
+    args: &[String],
+) -> Result<(Provider, Command), AppError> {
+    let provider = resolve(state, &app_type, provider_id)?;
+
+    // Windows 上通过 npm 安装的 CLI 是 .cmd 脚本，需要经由 cmd 解析
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(program(&app_type));
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = Command::new(program(&app_type));
+
+    cmd.args(args).envs(launch_env(&app_type, &provider));
+    Ok((provider, cmd))
+}
+
+/// 启动失败时的错误信息
+pub fn spawn_error(app_type: &AppType, e: std::io::Error) -> AppError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AppError::localized(
+            "launch.not_found",
+            format!("未找到 {}，请确认已安装并在 PATH 中", program(app_type)),
+            format!(
+                "{} was not found; make sure it is installed and on PATH",
+                program(app_type)
+            ),
+        )
+    } else {
+        AppError::Message(format!("启动 {} 失败: {e}", program(app_type)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(settings: Value) -> Provider {
+        Provider::with_id("relay".to_string(), "Relay".to_string(), settings, None)
+    }
+
+    #[test]
+    fn exposes_credentials_per_app() {
+        let claude = provider(json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-claude",
+                "ANTHROPIC_BASE_URL": "https://relay.example.com",
+                "CLAUDE_CODE_MAX_OUTPUT_TOKENS": 8192
+            }
+        }));
+        let mut vars = launch_env(&AppType::Claude, &claude);
+        vars.sort();
+        assert_eq!(
+            vars,
+            vec![
+                ("ANTHROPIC_AUTH_TOKEN".to_string(), "sk-claude".to_string()),
+                (
+                    "ANTHROPIC_BASE_URL".to_string(),
+                    "https://relay.example.com".to_string()
+                ),
+            ]
+        );
+
+        let codex = provider(json!({
+            "auth": { "OPENAI_API_KEY": "sk-codex" },
+            "config": "model_provider = \"relay\"\n[model_providers.relay]\nbase_url = \"https://relay.example.com/v1\"\n"
+        }));
+        assert_eq!(
+            launch_env(&AppType::Codex, &codex),
+            vec![
+                ("OPENAI_API_KEY".to_string(), "sk-codex".to_string()),
+                (
+                    "OPENAI_BASE_URL".to_string(),
+                    "https://relay.example.com/v1".to_string()
+                ),
+            ]
+        );
+
+        let opencode = provider(json!({ "options": { "apiKey": "sk-oc" } }));
+        let vars = launch_env(&AppType::OpenCode, &opencode);
+        let config: Value = serde_json::from_str(&vars[0].1).unwrap();
+        assert_eq!(config["provider"]["relay"]["options"]["apiKey"], "sk-oc");
+    }
+
+    #[test]
+    fn envrc_never_overwrites_foreign_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let relay = provider(json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-claude" } }));
+
+        let path = write_envrc(dir.path(), &AppType::Claude, &relay).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("cc-switch-cli env claude 'relay' --session"));
+        assert!(!content.contains("sk-claude"));
+        // 重新生成允许覆盖
+        write_envrc(dir.path(), &AppType::Claude, &relay).unwrap();
+
+        std::fs::write(&path, "use nix\n").unwrap();
+        assert!(write_envrc(dir.path(), &AppType::Claude, &relay).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "use nix\n");
+    }
+}
@@ -0,0 +1,253 @@
+//! 旧版 config.json 迁移
+//!
+//! 数据库版本之前的 CC Switch 把供应商、自定义端点与各应用的当前供应商保存在
+//! `~/.cc-switch/config.json`。首次启动时如果还没有数据库会自动迁移；已经有数据库后
+//! 再发现旧文件（例如从旧设备拷贝过来）则需要显式迁移：
+//!
+//! - 数据库中不存在的供应商整体导入（含自定义端点），已存在的保持不变，只补充缺少的
+//!   自定义端点
+//! - 应用尚未选择当前供应商时沿用旧文件中的选择
+//! - 完成后把旧文件改名为 `config.json.migrated`
+//!
+//! dry-run 只生成报告，不做任何修改。
+
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::database::{BatchOp, Database};
+use crate::error::AppError;
+
+/// 单个应用的迁移结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyAppMigration {
+    pub app: String,
+    /// 新导入的供应商 ID
+    pub providers_added: Vec<String>,
+    /// 数据库中已存在、保持不变的供应商 ID
+    pub providers_skipped: Vec<String>,
+    /// 导入的自定义端点数（含已存在供应商补充的端点）
+    pub endpoints_added: usize,
+    /// 旧文件中的当前供应商
+    pub legacy_current: Option<String>,
+    /// 是否采用了旧文件中的当前供应商（应用已有当前供应商时不覆盖）
+    pub current_applied: bool,
+}
+
+/// 迁移报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyMigrationReport {
+    pub source_path: String,
+    /// 是否找到旧版 config.json
+    pub found: bool,
+    pub dry_run: bool,
+    pub apps: Vec<LegacyAppMigration>,
+    /// 无法识别的应用等提示
+    pub warnings: Vec<String>,
+    /// 旧文件归档后的路径
+    pub archived_to: Option<String>,
+}
+
+/// 读取旧版配置（不做 `MultiAppConfig::load` 中的自动升级与回写）
+fn read_legacy_config(path: &Path) -> Result<MultiAppConfig, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
+    serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| AppError::json(path, e))
+}
+
+/// 迁移 `config.json` 中的供应商、自定义端点与当前供应商
+pub fn migrate_legacy_config(
+    db: &Database,
+    dry_run: bool,
+) -> Result<LegacyMigrationReport, AppError> {
+    migrate_legacy_config_from(db, &crate::config::get_app_config_path(), dry_run)
+}
+
+/// 同 [`migrate_legacy_config`]，从指定路径读取旧版配置
+pub(crate) fn migrate_legacy_config_from(
+    db: &Database,
+    path: &Path,
+    dry_run: bool,
+) -> Result<LegacyMigrationReport, AppError> {
+    let mut report = LegacyMigrationReport {
+        source_path: path.display().to_string(),
+        found: path.is_file(),
+        dry_run,
+        apps: Vec::new(),
+        warnings: Vec::new(),
+        archived_to: None,
+    };
+    if !report.found {
+        return Ok(report);
+    }
+    let config = read_legacy_config(path)?;
+
+    let mut app_keys = config.apps.keys().cloned().collect::<Vec<_>>();
+    app_keys.sort();
+    let mut ops = Vec::new();
+    // (应用, 供应商 ID, 缺少的端点)
+    let mut missing_endpoints = Vec::new();
+    let mut currents = Vec::new();
+    for key in app_keys {
+        let Ok(app_type) = AppType::from_str(&key) else {
+            report.warnings.push(format!("跳过无法识别的应用: {key}"));
+            continue;
+        };
+        let manager = &config.apps[&key];
+        let existing = db.get_all_providers(app_type.as_str())?;
+        let mut app = LegacyAppMigration {
+            app: app_type.as_str().to_string(),
+            legacy_current: Some(manager.current.clone()).filter(|id| !id.is_empty()),
+            ..Default::default()
+        };
+
+        for (id, provider) in &manager.providers {
+            let legacy_endpoints = provider
+                .meta
+                .as_ref()
+                .map(|meta| meta.custom_endpoints.keys().cloned().collect::<Vec<_>>())
+                .unwrap_or_default();
+            match existing.get(id) {
+                Some(current) => {
+                    let known = current.meta.as_ref().map(|meta| &meta.custom_endpoints);
+                    let missing = legacy_endpoints
+                        .into_iter()
+                        .filter(|url| !known.is_some_and(|known| known.contains_key(url)))
+                        .collect::<Vec<_>>();
+                    app.endpoints_added += missing.len();
+                    if !missing.is_empty() {
+                        missing_endpoints.push((app_type.clone(), id.clone(), missing));
+                    }
+                    app.providers_skipped.push(id.clone());
+                }
+                None => {
+                    let mut provider = provider.clone();
+                    provider.id = id.clone();
+                    app.endpoints_added += legacy_endpoints.len();
+                    app.providers_added.push(id.clone());
+                    ops.push(BatchOp::AddProvider {
+                        app_type: app_type.as_str().to_string(),
+                        provider,
+                    });
+                }
+            }
+        }
+
+        if let Some(legacy_current) = &app.legacy_current {
+            let known = existing.contains_key(legacy_current)
+                || app.providers_added.contains(legacy_current);
+            if known && crate::settings::get_effective_current_provider(db, &app_type)?.is_none() {
+                app.current_applied = true;
+                currents.push((app_type.clone(), legacy_current.clone()));
+            }
+        }
+        report.apps.push(app);
+    }
+
+    if dry_run {
+        return Ok(report);
+    }
+
+    db.transaction(&ops)?;
+    for (app_type, id, urls) in &missing_endpoints {
+        for url in urls {
+            db.add_custom_endpoint(app_type.as_str(), id, url)?;
+        }
+    }
+    for (app_type, id) in &currents {
+        db.set_current_provider(app_type.as_str(), id)?;
+        crate::settings::set_current_provider(app_type, Some(id))?;
+    }
+
+    let archive_path = path.with_extension("json.migrated");
+    std::fs::rename(path, &archive_path).map_err(|e| AppError::io(path, e))?;
+    report.archived_to = Some(archive_path.display().to_string());
+    log::info!(
+        "已迁移旧版配置 {}（新增 {} 个供应商），旧文件归档为 {}",
+        path.display(),
+        report
+            .apps
+            .iter()
+            .map(|app| app.providers_added.len())
+            .sum::<usize>(),
+        archive_path.display()
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Provider;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn keeps_existing_providers_and_reports_before_writing() {
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join("config.json");
+        std::fs::write(
+            &path,
+            json!({
+                "version": 2,
+                "claude": {
+                    "current": "b",
+                    "providers": {
+                        "a": { "id": "a", "name": "Legacy A", "settingsConfig": {} },
+                        "b": {
+                            "id": "b",
+                            "name": "B",
+                            "settingsConfig": {},
+                            "meta": {
+                                "custom_endpoints": {
+                                    "https://b.example": {
+                                        "url": "https://b.example",
+                                        "addedAt": 1
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+            .to_string(),
+        )
+        .expect("write legacy config");
+
+        let db = Database::memory().expect("memory db");
+        let existing = Provider::with_id("a".to_string(), "A".to_string(), json!({}), None);
+        db.save_provider("claude", &existing)
+            .expect("save provider");
+        db.set_current_provider("claude", "a").expect("set current");
+
+        let preview = migrate_legacy_config_from(&db, &path, true).expect("dry run");
+        let claude = &preview.apps[0];
+        assert_eq!(claude.providers_added, vec!["b"]);
+        assert_eq!(claude.providers_skipped, vec!["a"]);
+        assert_eq!(claude.endpoints_added, 1);
+        assert!(!claude.current_applied);
+        assert!(path.exists());
+        assert!(!db
+            .get_all_providers("claude")
+            .expect("providers")
+            .contains_key("b"));
+
+        let report = migrate_legacy_config_from(&db, &path, false).expect("migrate");
+        assert!(report.archived_to.is_some());
+        assert!(!path.exists());
+        let providers = db.get_all_providers("claude").expect("providers");
+        assert_eq!(providers["a"].name, "A");
+        let endpoints = &providers["b"].meta.as_ref().expect("meta").custom_endpoints;
+        assert!(endpoints.contains_key("https://b.example"));
+        assert_eq!(
+            db.get_current_provider("claude")
+                .expect("current")
+                .as_deref(),
+            Some("a")
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! live 配置中的明文密钥扫描
+//!
+//! 检查各应用 live 配置文件中的 API Key / Token，找出不属于任何供应商的「未托管」密钥
+//! （例如手动粘贴进 `settings.json` 的 Key）。托管时把当前 live 配置收编为供应商，
+//! 密钥原文另存一份到系统钥匙串，再按托管后的供应商重写 live 配置——开启环境变量模式
+//! 时，密钥会因此从 live 文件中移出。
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::redact::mask_secret;
+use crate::services::provider::{read_live_settings, write_live_snapshot};
+use crate::services::sync_secrets::strip_secrets;
+use crate::services::ProviderService;
+use crate::store::AppState;
+
+const SCAN_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 钥匙串键前缀
+const VAULT_KEY_PREFIX: &str = "live-vault";
+
+/// live 配置中发现的一个密钥
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveSecretFinding {
+    pub app: String,
+    pub file: String,
+    /// 密钥在配置中的 JSON Pointer
+    pub pointer: String,
+    pub masked: String,
+    /// 拥有相同密钥的供应商；为空表示未托管
+    pub provider_id: Option<String>,
+}
+
+/// 托管结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultLiveSecretsResult {
+    pub app: String,
+    /// 收编 live 配置后新建的供应商
+    pub providers: Vec<String>,
+    /// 被托管的未知密钥数量
+    pub vaulted: usize,
+}
+
+/// 密钥所在的 live 文件
+fn live_file_for(app_type: &AppType, pointer: &str) -> PathBuf {
+    match app_type {
+        AppType::Claude => crate::config::get_claude_settings_path(),
+        AppType::Codex => crate::codex_config::get_codex_auth_path(),
+        AppType::Gemini if pointer.starts_with("/env/") => {
+            crate::gemini_config::get_gemini_env_path()
+        }
+        AppType::Gemini => crate::gemini_config::get_gemini_settings_path(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_config_path(),
+    }
+}
+
+/// 已保存在供应商中的密钥：原值 -> 供应商 ID
+fn known_secrets(
+    state: &AppState,
+    app_type: &AppType,
+) -> Result<HashMap<String, String>, AppError> {
+    let mut known = HashMap::new();
+    for (id, provider) in state.db.get_all_providers(app_type.as_str())? {
+        let mut settings = provider.settings_config;
+        for secret in strip_secrets(&mut settings).into_values() {
+            known.entry(secret).or_insert_with(|| id.clone());
+        }
+    }
+    Ok(known)
+}
+
+fn scan_app(state: &AppState, app_type: &AppType) -> Result<Vec<LiveSecretFinding>, AppError> {
+    // live 配置不存在时没有可扫描的内容
+    let Ok(mut live) = read_live_settings(app_type.clone()) else {
+        return Ok(Vec::new());
+    };
+    let known = known_secrets(state, app_type)?;
+
+    Ok(strip_secrets(&mut live)
+        .into_iter()
+        .map(|(pointer, secret)| LiveSecretFinding {
+            app: app_type.as_str().to_string(),
+            file: live_file_for(app_type, &pointer).display().to_string(),
+            masked: mask_secret(&secret),
+            provider_id: known.get(&secret).cloned(),
+            pointer,
+        })
+        .collect())
+}
+
+/// 扫描所有应用的 live 配置
+pub fn scan(state: &AppState) -> Result<Vec<LiveSecretFinding>, AppError> {
+    let mut findings = Vec::new();
+    for app_type in SCAN_APPS {
+        findings.extend(scan_app(state, &app_type)?);
+    }
+    Ok(findings)
+}
+
+/// 托管指定应用 live 配置中的未知密钥
+pub fn vault(state: &AppState, app_type: AppType) -> Result<VaultLiveSecretsResult, AppError> {
+    crate::read_only::ensure_writable()?;
+
+    let unknown = scan_app(state, &app_type)?
+        .into_iter()
+        .filter(|finding| finding.provider_id.is_none())
+        .count();
+    if unknown == 0 {
+        return Ok(VaultLiveSecretsResult {
+            app: app_type.as_str().to_string(),
+            providers: Vec::new(),
+            vaulted: 0,
+        });
+    }
+
+    let adopted = ProviderService::adopt_live_config(state, app_type.clone(), None)?.adopted;
+    for id in &adopted {
+        let Some(provider) = state.db.get_provider_by_id(id, app_type.as_str())? else {
+            continue;
+        };
+        let mut settings = provider.settings_config.clone();
+        let secrets = strip_secrets(&mut settings);
+        let json =
+            serde_json::to_string(&secrets).map_err(|e| AppError::JsonSerialize { source: e })?;
+        crate::secret_store::set_secret(
+            &format!("{VAULT_KEY_PREFIX}:{}:{id}", app_type.as_str()),
+            &json,
+        )?;
+
+        // OpenCode 为累加模式，收编时已与 live 配置一致，无需重写
+        if !matches!(app_type, AppType::OpenCode) {
+            write_live_snapshot(&app_type, &provider)?;
+        }
+    }
+
+    log::info!(
+        "已托管 {} live 配置中的 {unknown} 个未知密钥（新供应商: {}）",
+        app_type.as_str(),
+        adopted.join(", ")
+    );
+    Ok(VaultLiveSecretsResult {
+        app: app_type.as_str().to_string(),
+        providers: adopted,
+        vaulted: unknown,
+    })
+}
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use crate::app_config::{AppType, McpServer};
 use crate::error::AppError;
 use crate::mcp;
+use crate::services::undo::{self, UndoEntry};
 use crate::store::AppState;
 
 /// MCP 相关业务逻辑（v3.7.0 统一结构）
@@ -56,6 +57,9 @@ impl McpService {
 
             // 从所有应用的 live 配置中移除
             Self::remove_server_from_all_apps(state, id, &server)?;
+            undo::record(UndoEntry::McpServerDeleted {
+                server: Box::new(server),
+            });
             Ok(true)
         } else {
             Ok(false)
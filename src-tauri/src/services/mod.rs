@@ -1,14 +1,91 @@
+#[cfg(feature = "gui")]
+pub mod automation_api;
+#[cfg(feature = "gui")]
+pub mod automation_rules;
+#[cfg(feature = "gui")]
+pub mod backup;
+#[cfg(feature = "gui")]
+pub mod balance;
+#[cfg(feature = "gui")]
+pub mod benchmark;
 pub mod config;
+#[cfg(feature = "gui")]
+pub mod config_search;
+#[cfg(feature = "gui")]
+pub mod cost_report;
+#[cfg(feature = "gui")]
+pub mod data_bundle;
+#[cfg(feature = "gui")]
+pub mod database_export;
+#[cfg(feature = "gui")]
 pub mod env_checker;
+#[cfg(feature = "gui")]
 pub mod env_manager;
+#[cfg(feature = "gui")]
+pub mod error_burst;
+#[cfg(feature = "gui")]
+pub mod export_rules;
+pub mod external_import;
+#[cfg(feature = "gui")]
+pub mod import_merge;
+pub mod key_check;
+#[cfg(feature = "gui")]
+pub mod key_rotation;
+#[cfg(feature = "gui")]
+pub mod lan_sync;
+pub mod launch;
+#[cfg(feature = "gui")]
+pub mod legacy_migration;
+#[cfg(feature = "gui")]
+pub mod live_secrets;
 pub mod mcp;
+#[cfg(feature = "gui")]
+pub mod model_list;
 pub mod prompt;
 pub mod provider;
+#[cfg(feature = "gui")]
+pub mod provider_compare;
 pub mod proxy;
+#[cfg(feature = "gui")]
+pub mod quick_switch;
+#[cfg(feature = "gui")]
+pub mod s3_backup;
+#[cfg(feature = "gui")]
+pub mod secret_access;
+pub mod secret_lint;
+#[cfg(feature = "gui")]
+pub mod session_browser;
+#[cfg(feature = "gui")]
+pub mod session_usage;
+pub mod settings_diagnostics;
 pub mod skill;
+#[cfg(feature = "gui")]
+pub mod snapshot;
 pub mod speedtest;
+pub mod status;
+#[cfg(feature = "gui")]
 pub mod stream_check;
+pub mod switch_backup;
+#[cfg(feature = "gui")]
+pub mod switch_stats;
+#[cfg(feature = "gui")]
+pub mod sync_merge;
+pub mod sync_secrets;
+#[cfg(feature = "gui")]
+pub mod sync_status;
+pub mod undo;
+#[cfg(feature = "gui")]
+pub mod uptime;
+#[cfg(feature = "gui")]
+pub mod usage_dashboard;
+#[cfg(feature = "gui")]
+pub mod usage_report;
 pub mod usage_stats;
+#[cfg(feature = "gui")]
+pub mod webdav_sync;
+pub mod webhook;
+#[cfg(feature = "gui")]
+pub mod wsl;
 
 pub use config::ConfigService;
 pub use mcp::McpService;
@@ -0,0 +1,307 @@
+//! 供应商模型列表
+//!
+//! 请求供应商的模型列表接口（与 Key 检查相同，见 [`super::key_check`]），并与配置中
+//! 填写的模型比对。中转站下线某个模型后，CLI 往往只在对话时报错，这里可以提前标记出
+//! 不再提供配置中模型的供应商。
+//!
+//! 结果按供应商缓存十分钟。
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::services::key_check::KeyCheckService;
+
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 缓存有效期
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Claude 配置中可以指定模型的环境变量
+const CLAUDE_MODEL_KEYS: [&str; 5] = [
+    "ANTHROPIC_MODEL",
+    "ANTHROPIC_DEFAULT_HAIKU_MODEL",
+    "ANTHROPIC_DEFAULT_SONNET_MODEL",
+    "ANTHROPIC_DEFAULT_OPUS_MODEL",
+    "ANTHROPIC_SMALL_FAST_MODEL",
+];
+
+/// 每个供应商最近一次的结果
+static CACHE: Mutex<Vec<(Instant, ProviderModels)>> = Mutex::new(Vec::new());
+
+/// 供应商的模型列表
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderModels {
+    pub app_type: String,
+    pub provider_id: String,
+    /// 接口返回的模型
+    pub models: Vec<String>,
+    /// 配置中填写的模型
+    pub configured_models: Vec<String>,
+    /// 配置中填写但接口未返回的模型
+    pub missing_models: Vec<String>,
+    /// Unix 时间戳（秒）
+    pub checked_at: i64,
+}
+
+/// 解析模型列表接口的响应（OpenAI / Anthropic 的 `data[].id`，Gemini 的 `models[].name`）
+fn parse_models(body: &Value) -> Option<Vec<String>> {
+    let items = body
+        .get("data")
+        .or_else(|| body.get("models"))?
+        .as_array()?;
+    let mut models = items
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("id").or_else(|| item.get("name"))?.as_str()?;
+            Some(id.trim_start_matches("models/").to_string())
+        })
+        .collect::<Vec<_>>();
+    models.sort();
+    models.dedup();
+    Some(models)
+}
+
+fn env_model(provider: &Provider, key: &str) -> Option<String> {
+    provider
+        .settings_config
+        .pointer(&format!("/env/{key}"))
+        .and_then(Value::as_str)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 配置中填写的模型（去重，保持顺序）
+fn configured_models(app_type: &AppType, provider: &Provider) -> Vec<String> {
+    let candidates = match app_type {
+        AppType::Claude => CLAUDE_MODEL_KEYS
+            .iter()
+            .filter_map(|key| env_model(provider, key))
+            .collect(),
+        AppType::Codex => provider
+            .settings_config
+            .get("config")
+            .and_then(Value::as_str)
+            .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+            .and_then(|table| table.get("model")?.as_str().map(str::to_string))
+            .filter(|model| !model.trim().is_empty())
+            .into_iter()
+            .collect(),
+        AppType::Gemini => env_model(provider, "GEMINI_MODEL").into_iter().collect(),
+        AppType::OpenCode => Vec::new(),
+    };
+    let mut models: Vec<String> = Vec::new();
+    for model in candidates {
+        if !models.contains(&model) {
+            models.push(model);
+        }
+    }
+    models
+}
+
+/// 接口是否提供该模型（忽略大小写与 Claude Code 的 `[1m]` 等后缀）
+fn is_served(models: &[String], model: &str) -> bool {
+    let name = model.split('[').next().unwrap_or(model).trim();
+    models
+        .iter()
+        .any(|served| served.eq_ignore_ascii_case(name))
+}
+
+fn missing_models(models: &[String], configured: &[String]) -> Vec<String> {
+    configured
+        .iter()
+        .filter(|model| !is_served(models, model))
+        .cloned()
+        .collect()
+}
+
+async fn fetch_models(app_type: &AppType, provider: &Provider) -> Result<Vec<String>, AppError> {
+    let adapter = get_adapter(app_type);
+    let base_url = adapter
+        .extract_base_url(provider)
+        .map_err(|e| AppError::Message(format!("Failed to extract base_url: {e}")))?;
+    let auth = adapter
+        .extract_auth(provider)
+        .ok_or_else(|| AppError::Message("API Key not found".to_string()))?;
+    let url = KeyCheckService::probe_url(app_type, &base_url, |base, endpoint| {
+        adapter.build_url(base, endpoint)
+    });
+
+    let client = crate::proxy::http_client::get();
+    let mut request = adapter
+        .add_auth_headers(client.get(&url), &auth)
+        .timeout(REQUEST_TIMEOUT);
+    // 官方接口默认分页，一次取完
+    request = match app_type {
+        AppType::Claude => request
+            .header("anthropic-version", "2023-06-01")
+            .query(&[("limit", "1000")]),
+        AppType::Gemini => request.query(&[("pageSize", "1000")]),
+        _ => request,
+    };
+    let response = request.send().await.map_err(|e| {
+        AppError::localized(
+            "provider.models.request_failed",
+            format!("模型列表请求失败: {e}"),
+            format!("Model list request failed: {e}"),
+        )
+    })?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let snippet = crate::redact::redact_secrets(&body)
+            .chars()
+            .take(200)
+            .collect::<String>();
+        return Err(AppError::localized(
+            "provider.models.http_error",
+            format!("模型列表接口返回 HTTP {status}: {snippet}"),
+            format!("Model list endpoint returned HTTP {status}: {snippet}"),
+        ));
+    }
+    serde_json::from_str::<Value>(&body)
+        .ok()
+        .as_ref()
+        .and_then(parse_models)
+        .ok_or_else(|| {
+            AppError::localized(
+                "provider.models.unrecognized",
+                "无法识别模型列表接口的响应格式",
+                "Unrecognized model list response format",
+            )
+        })
+}
+
+/// 查询供应商提供的模型（十分钟内的重复查询使用缓存，`refresh` 为 true 时重新请求）
+pub async fn list_models(
+    app_type: &AppType,
+    provider: &Provider,
+    refresh: bool,
+) -> Result<ProviderModels, AppError> {
+    if matches!(app_type, AppType::OpenCode) {
+        return Err(AppError::localized(
+            "opencode_no_model_list",
+            "OpenCode 暂不支持查询模型列表",
+            "OpenCode does not support model listing yet",
+        ));
+    }
+    let is_same = |cached: &ProviderModels| {
+        cached.app_type == app_type.as_str() && cached.provider_id == provider.id
+    };
+    if !refresh {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, cached)) = cache
+            .iter()
+            .find(|(at, cached)| is_same(cached) && at.elapsed() < CACHE_TTL)
+        {
+            return Ok(cached.clone());
+        }
+    }
+
+    let models = fetch_models(app_type, provider).await?;
+    let configured_models = configured_models(app_type, provider);
+    let result = ProviderModels {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        missing_models: missing_models(&models, &configured_models),
+        models,
+        configured_models,
+        checked_at: chrono::Utc::now().timestamp(),
+    };
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(_, cached)| !is_same(cached));
+    cache.push((Instant::now(), result.clone()));
+    Ok(result)
+}
+
+/// 应用下已查询过的供应商（含已过期的结果），供列表标记缺失模型
+pub fn cached_models(app_type: &AppType) -> Vec<ProviderModels> {
+    CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|(_, cached)| cached.app_type == app_type.as_str())
+        .map(|(_, cached)| cached.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_openai_anthropic_and_gemini_lists() {
+        let openai = json!({
+            "object": "list",
+            "data": [{ "id": "gpt-5", "object": "model" }, { "id": "gpt-5-codex" }]
+        });
+        assert_eq!(
+            parse_models(&openai),
+            Some(vec!["gpt-5".to_string(), "gpt-5-codex".to_string()])
+        );
+
+        let gemini = json!({
+            "models": [{ "name": "models/gemini-2.5-pro" }, { "name": "models/gemini-2.5-flash" }]
+        });
+        assert_eq!(
+            parse_models(&gemini),
+            Some(vec![
+                "gemini-2.5-flash".to_string(),
+                "gemini-2.5-pro".to_string()
+            ])
+        );
+        assert_eq!(parse_models(&json!({ "error": "not found" })), None);
+    }
+
+    #[test]
+    fn flags_configured_models_missing_from_list() {
+        let provider = Provider::with_id(
+            "relay".to_string(),
+            "Relay".to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_MODEL": "claude-sonnet-4-5[1m]",
+                    "ANTHROPIC_DEFAULT_SONNET_MODEL": "claude-sonnet-4-5",
+                    "ANTHROPIC_DEFAULT_OPUS_MODEL": "claude-opus-4-1"
+                }
+            }),
+            None,
+        );
+        let configured = configured_models(&AppType::Claude, &provider);
+        assert_eq!(
+            configured,
+            vec![
+                "claude-sonnet-4-5[1m]".to_string(),
+                "claude-sonnet-4-5".to_string(),
+                "claude-opus-4-1".to_string()
+            ]
+        );
+        let served = vec!["Claude-Sonnet-4-5".to_string()];
+        assert_eq!(
+            missing_models(&served, &configured),
+            vec!["claude-opus-4-1".to_string()]
+        );
+
+        let codex = Provider::with_id(
+            "codex".to_string(),
+            "Codex".to_string(),
+            json!({ "config": "model_provider = \"relay\"\nmodel = \"gpt-5-codex\"\n" }),
+            None,
+        );
+        assert_eq!(
+            configured_models(&AppType::Codex, &codex),
+            vec!["gpt-5-codex".to_string()]
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! Adopt the current live config as a provider
+//!
+//! First-time users often already have a hand-written `~/.claude/settings.json`,
+//! Codex `auth.json`/`config.toml`, Gemini `.env` or OpenCode config. Adopting
+//! captures that setup as a provider (and marks it current, since it is what is
+//! live) instead of letting the first switch overwrite it.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::store::AppState;
+
+use super::live::{import_opencode_providers_from_live, read_live_settings};
+use super::{normalize_claude_models_in_value, ProviderService};
+
+/// Result of adopting the live config
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdoptLiveResult {
+    pub app: String,
+    /// Newly created provider IDs
+    pub adopted: Vec<String>,
+    /// Existing provider whose config already equals the live config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matched: Option<String>,
+}
+
+/// Base URL configured in the live config, used to derive a readable name
+fn live_base_url(app_type: &AppType, settings: &Value) -> Option<String> {
+    match app_type {
+        AppType::Claude => settings
+            .pointer("/env/ANTHROPIC_BASE_URL")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        AppType::Gemini => settings
+            .pointer("/env/GOOGLE_GEMINI_BASE_URL")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        AppType::Codex => {
+            static BASE_URL: OnceLock<Regex> = OnceLock::new();
+            let re = BASE_URL.get_or_init(|| {
+                Regex::new(r#"(?m)^\s*base_url\s*=\s*"([^"]+)""#).expect("valid regex")
+            });
+            let config = settings.get("config").and_then(Value::as_str)?;
+            re.captures(config).map(|c| c[1].to_string())
+        }
+        AppType::OpenCode => None,
+    }
+}
+
+fn derive_name(app_type: &AppType, settings: &Value) -> String {
+    live_base_url(app_type, settings)
+        .and_then(|url| url::Url::parse(&url).ok())
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| format!("Imported {}", chrono::Local::now().format("%Y-%m-%d %H:%M")))
+}
+
+fn unique_id(base: &str, taken: &indexmap::IndexMap<String, Provider>) -> String {
+    if !taken.contains_key(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}-{n}"))
+        .find(|id| !taken.contains_key(id))
+        .expect("unbounded id search")
+}
+
+impl ProviderService {
+    /// Create a provider from whatever is currently in the app's live config files
+    pub fn adopt_live_config(
+        state: &AppState,
+        app_type: AppType,
+        name: Option<String>,
+    ) -> Result<AdoptLiveResult, AppError> {
+        crate::read_only::ensure_writable()?;
+        let app_key = app_type.as_str();
+        let existing = state.db.get_all_providers(app_key)?;
+
+        if matches!(app_type, AppType::OpenCode) {
+            // OpenCode is additive: every provider in opencode.json is adopted individually
+            import_opencode_providers_from_live(state)?;
+            let adopted = state
+                .db
+                .get_all_providers(app_key)?
+                .into_keys()
+                .filter(|id| !existing.contains_key(id))
+                .collect();
+            return Ok(AdoptLiveResult {
+                app: app_key.to_string(),
+                adopted,
+                matched: None,
+            });
+        }
+
+        let mut settings = read_live_settings(app_type.clone())?;
+        if matches!(app_type, AppType::Claude) {
+            let _ = normalize_claude_models_in_value(&mut settings);
+        }
+
+        if let Some(provider) = existing.values().find(|p| p.settings_config == settings) {
+            return Ok(AdoptLiveResult {
+                app: app_key.to_string(),
+                adopted: Vec::new(),
+                matched: Some(provider.id.clone()),
+            });
+        }
+
+        let name = name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| derive_name(&app_type, &settings));
+        let id = unique_id("live-import", &existing);
+
+        let mut provider = Provider::with_id(id.clone(), name, settings, None);
+        provider.category = Some("custom".to_string());
+        provider.created_at = Some(chrono::Utc::now().timestamp_millis());
+        state.db.save_provider(app_key, &provider)?;
+
+        // The adopted config is what is live right now, so it becomes current
+        // without rewriting any live file.
+        crate::settings::set_current_provider(&app_type, Some(&id))?;
+        state.db.set_current_provider(app_key, &id)?;
+
+        log::info!("Adopted live {app_key} config as provider {id}");
+        Ok(AdoptLiveResult {
+            app: app_key.to_string(),
+            adopted: vec![id],
+            matched: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn derives_name_from_base_url_host() {
+        let claude = json!({ "env": { "ANTHROPIC_BASE_URL": "https://api.example.com/v1" } });
+        assert_eq!(derive_name(&AppType::Claude, &claude), "api.example.com");
+
+        let codex = json!({
+            "auth": {},
+            "config": "model_provider = \"x\"\n[model_providers.x]\nbase_url = \"https://relay.test/v1\"\n"
+        });
+        assert_eq!(derive_name(&AppType::Codex, &codex), "relay.test");
+
+        assert!(derive_name(&AppType::Claude, &json!({})).starts_with("Imported "));
+    }
+}
@@ -0,0 +1,140 @@
+//! Guarded batch writes
+//!
+//! `Database::transaction` applies a batch at the DAO level. Batches coming from the
+//! frontend go through here first so every operation passes the same checks as a single
+//! add/update/delete (read-only mode, locked providers, the current provider), and the
+//! revision history and undo journal are kept after the batch commits.
+
+use std::str::FromStr;
+
+use crate::app_config::{AppType, McpServer};
+use crate::database::BatchOp;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::undo::{self, UndoEntry};
+use crate::store::AppState;
+
+use super::ProviderService;
+
+/// Data captured before the batch runs, recorded once it has committed
+enum Pending {
+    Revision {
+        app_type: AppType,
+        previous: Box<Provider>,
+    },
+    ProviderDeleted {
+        app_type: AppType,
+        provider: Box<Provider>,
+    },
+    McpServerDeleted {
+        server: Box<McpServer>,
+    },
+}
+
+fn parse_app_type(app_type: &str) -> Result<AppType, AppError> {
+    AppType::from_str(app_type)
+        .map_err(|_| AppError::InvalidInput(format!("未知的应用类型: {app_type}")))
+}
+
+impl ProviderService {
+    /// Check one operation and normalize the provider it carries
+    fn check_batch_op(
+        state: &AppState,
+        op: &mut BatchOp,
+        pending: &mut Vec<Pending>,
+    ) -> Result<(), AppError> {
+        match op {
+            BatchOp::AddProvider { app_type, provider } => {
+                let app_type = parse_app_type(app_type)?;
+                Self::normalize_provider_if_claude(&app_type, provider);
+                Self::validate_provider_settings(&app_type, provider)?;
+            }
+            BatchOp::UpdateProvider { app_type, provider } => {
+                let app_type = parse_app_type(app_type)?;
+                Self::normalize_provider_if_claude(&app_type, provider);
+                Self::validate_provider_settings(&app_type, provider)?;
+                Self::ensure_secrets_unchanged(state, &app_type, provider)?;
+                if let Some(previous) = state
+                    .db
+                    .get_provider_by_id(&provider.id, app_type.as_str())?
+                    .filter(|previous| previous.settings_config != provider.settings_config)
+                {
+                    pending.push(Pending::Revision {
+                        app_type,
+                        previous: Box::new(previous),
+                    });
+                }
+            }
+            BatchOp::DeleteProvider { app_type, id } => {
+                let app_type = parse_app_type(app_type)?;
+                Self::ensure_deletable(state, &app_type, id)?;
+                if !matches!(app_type, AppType::OpenCode) {
+                    let local_current = crate::settings::get_current_provider(&app_type);
+                    let db_current = state.db.get_current_provider(app_type.as_str())?;
+                    if local_current.as_deref() == Some(id.as_str())
+                        || db_current.as_deref() == Some(id.as_str())
+                    {
+                        return Err(AppError::Message(
+                            "无法删除当前正在使用的供应商".to_string(),
+                        ));
+                    }
+                }
+                if let Some(provider) = state.db.get_provider_by_id(id, app_type.as_str())? {
+                    pending.push(Pending::ProviderDeleted {
+                        app_type,
+                        provider: Box::new(provider),
+                    });
+                }
+            }
+            BatchOp::DeleteMcpServer { id } => {
+                if let Some(server) = state.db.get_all_mcp_servers()?.shift_remove(id.as_str()) {
+                    pending.push(Pending::McpServerDeleted {
+                        server: Box::new(server),
+                    });
+                }
+            }
+            BatchOp::AddMcpServer { .. } | BatchOp::UpdateMcpServer { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Apply a batch of provider and MCP server changes in one transaction
+    ///
+    /// All operations are checked before the transaction starts; the first rejected
+    /// operation aborts the batch without any change.
+    pub fn apply_batch(state: &AppState, mut ops: Vec<BatchOp>) -> Result<usize, AppError> {
+        crate::read_only::ensure_writable()?;
+        let mut pending = Vec::new();
+        for (index, op) in ops.iter_mut().enumerate() {
+            if let Err(e) = Self::check_batch_op(state, op, &mut pending) {
+                return Err(AppError::Message(format!(
+                    "批量操作第 {} 项（{}）被拒绝: {e}",
+                    index + 1,
+                    op.target()
+                )));
+            }
+        }
+
+        let applied = state.db.transaction(&ops)?;
+
+        for item in pending {
+            match item {
+                Pending::Revision { app_type, previous } => {
+                    state.db.record_provider_revision(
+                        app_type.as_str(),
+                        &previous.id,
+                        &previous.name,
+                        &previous.settings_config,
+                    )?;
+                }
+                Pending::ProviderDeleted { app_type, provider } => {
+                    undo::record(UndoEntry::ProviderDeleted { app_type, provider });
+                }
+                Pending::McpServerDeleted { server } => {
+                    undo::record(UndoEntry::McpServerDeleted { server });
+                }
+            }
+        }
+        Ok(applied)
+    }
+}
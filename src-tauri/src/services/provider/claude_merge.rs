@@ -0,0 +1,194 @@
+//! Surgical merge of a provider config into Claude's live `settings.json`
+//!
+//! Claude Code keeps hooks, permissions, the status line and other user settings in the
+//! same file as the provider credentials. When switching providers only the keys owned by
+//! the provider (`env.ANTHROPIC_*`, the model fields and any key the outgoing provider set
+//! itself) are replaced; the rest of the live file is kept and the provider config is
+//! deep-merged on top of it, so any value the provider does set still wins.
+//!
+//! The reverse applies when the live file is backfilled into the outgoing provider: only
+//! the provider-owned keys are taken from the live file (see [`managed_fragment`]), so the
+//! user's own sections are not copied into every provider.
+
+use serde_json::Value;
+
+use super::ProviderService;
+
+/// Environment variables owned by the active provider
+const MANAGED_ENV_PREFIX: &str = "ANTHROPIC_";
+
+/// Top-level keys owned by the active provider
+const MANAGED_TOP_LEVEL_KEYS: [&str; 5] = [
+    "model",
+    "apiBaseUrl",
+    "primaryModel",
+    "smallFastModel",
+    "apiKeyHelper",
+];
+
+/// Build the new live settings from the current live file and the provider config
+///
+/// `previous` is the outgoing provider's config as recorded at backfill time; the keys it
+/// set (e.g. `env.API_TIMEOUT_MS`) are removed as well so they do not leak into the new
+/// provider. Falls back to the provider config as a whole when either side is not a JSON
+/// object (missing or unreadable live file).
+pub(crate) fn merge_into_live(
+    existing: Option<Value>,
+    previous: Option<&Value>,
+    provider: &Value,
+) -> Value {
+    let (Some(Value::Object(mut live)), Value::Object(_)) = (existing, provider) else {
+        return provider.clone();
+    };
+
+    let previous = previous.and_then(Value::as_object);
+    for key in MANAGED_TOP_LEVEL_KEYS {
+        live.remove(key);
+    }
+    if let Some(previous) = previous {
+        for key in previous.keys().filter(|key| *key != "env") {
+            live.remove(key);
+        }
+    }
+    let previous_env = previous
+        .and_then(|previous| previous.get("env"))
+        .and_then(Value::as_object);
+    let env_emptied = match live.get_mut("env") {
+        Some(Value::Object(env)) => {
+            env.retain(|key, _| {
+                !key.starts_with(MANAGED_ENV_PREFIX)
+                    && !previous_env.is_some_and(|previous| previous.contains_key(key))
+            });
+            env.is_empty()
+        }
+        _ => false,
+    };
+    if env_emptied {
+        live.remove("env");
+    }
+
+    let mut merged = Value::Object(live);
+    ProviderService::merge_json(&mut merged, provider);
+    merged
+}
+
+/// Extract the part of the live settings that belongs to the outgoing provider
+///
+/// Keeps `env.ANTHROPIC_*`, the managed top-level keys and any key the provider already
+/// defined itself (e.g. `env.API_TIMEOUT_MS`), with their current live values.
+pub(crate) fn managed_fragment(live: &Value, provider: &Value) -> Value {
+    let Value::Object(live) = live else {
+        return live.clone();
+    };
+    let owns = |key: &str| MANAGED_TOP_LEVEL_KEYS.contains(&key) || provider.get(key).is_some();
+    let mut fragment = serde_json::Map::new();
+    for (key, value) in live {
+        if key == "env" {
+            let Value::Object(env) = value else {
+                continue;
+            };
+            let provider_env = provider.get("env");
+            let env = env
+                .iter()
+                .filter(|(name, _)| {
+                    name.starts_with(MANAGED_ENV_PREFIX)
+                        || provider_env.is_some_and(|env| env.get(name.as_str()).is_some())
+                })
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect::<serde_json::Map<_, _>>();
+            if !env.is_empty() {
+                fragment.insert(key.clone(), Value::Object(env));
+            }
+        } else if owns(key) {
+            fragment.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(fragment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn replaces_managed_keys_and_keeps_user_settings() {
+        let live = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-old",
+                "ANTHROPIC_SMALL_FAST_MODEL": "old-haiku",
+                "DISABLE_TELEMETRY": "1"
+            },
+            "model": "old-model",
+            "hooks": { "PreToolUse": [{ "matcher": "Bash" }] },
+            "permissions": { "allow": ["Bash(git:*)"] },
+            "statusLine": { "type": "command", "command": "~/status.sh" }
+        });
+        let provider = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-new",
+                "ANTHROPIC_BASE_URL": "https://relay.example"
+            }
+        });
+
+        let merged = merge_into_live(Some(live.clone()), None, &provider);
+        assert_eq!(
+            merged["env"],
+            json!({
+                "ANTHROPIC_AUTH_TOKEN": "sk-new",
+                "ANTHROPIC_BASE_URL": "https://relay.example",
+                "DISABLE_TELEMETRY": "1"
+            })
+        );
+        assert!(merged.get("model").is_none());
+        assert_eq!(merged["hooks"], live["hooks"]);
+        assert_eq!(merged["permissions"], live["permissions"]);
+        assert_eq!(merged["statusLine"], live["statusLine"]);
+
+        // Backfill only takes the provider-owned keys back out of the live file
+        let provider_with_timeout = json!({ "env": { "API_TIMEOUT_MS": "600000" } });
+        let live = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": "sk-live", "API_TIMEOUT_MS": "1", "DISABLE_TELEMETRY": "1" },
+            "apiKeyHelper": "~/key.sh",
+            "hooks": { "PreToolUse": [] }
+        });
+        assert_eq!(
+            managed_fragment(&live, &provider_with_timeout),
+            json!({
+                "env": { "ANTHROPIC_AUTH_TOKEN": "sk-live", "API_TIMEOUT_MS": "1" },
+                "apiKeyHelper": "~/key.sh"
+            })
+        );
+
+        // A live file that is not an object is replaced as a whole
+        assert_eq!(merge_into_live(Some(json!([1])), None, &provider), provider);
+        assert_eq!(merge_into_live(None, None, &provider), provider);
+    }
+
+    #[test]
+    fn removes_keys_set_by_the_outgoing_provider() {
+        let live = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-old",
+                "API_TIMEOUT_MS": "600000",
+                "DISABLE_TELEMETRY": "1"
+            },
+            "alwaysThinkingEnabled": true,
+            "hooks": { "PreToolUse": [] }
+        });
+        let previous = json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": "sk-old", "API_TIMEOUT_MS": "600000" },
+            "alwaysThinkingEnabled": true
+        });
+        let provider = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new" } });
+
+        let merged = merge_into_live(Some(live), Some(&previous), &provider);
+        assert_eq!(
+            merged,
+            json!({
+                "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new", "DISABLE_TELEMETRY": "1" },
+                "hooks": { "PreToolUse": [] }
+            })
+        );
+    }
+}
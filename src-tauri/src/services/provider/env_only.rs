@@ -0,0 +1,211 @@
+//! Environment-variable-only switching
+//!
+//! When `env_only_switching` is enabled, credentials are never written into the
+//! live config files of Claude, Codex or Gemini. Instead they are split out of
+//! the provider config and written to sourceable env files under
+//! `~/.cc-switch/env/` (`<app>.sh` for POSIX shells, `<app>.ps1` for
+//! PowerShell); the live files only receive the remaining non-secret settings.
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::config::{atomic_write, get_app_config_dir};
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::sync_secrets::is_secret_field;
+use crate::store::AppState;
+
+use super::ProviderService;
+
+/// Target shell for a generated env script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvShell {
+    Posix,
+    PowerShell,
+}
+
+impl EnvShell {
+    fn extension(self) -> &'static str {
+        match self {
+            EnvShell::Posix => "sh",
+            EnvShell::PowerShell => "ps1",
+        }
+    }
+}
+
+impl std::str::FromStr for EnvShell {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sh" | "bash" | "zsh" | "posix" => Ok(EnvShell::Posix),
+            "ps1" | "powershell" | "pwsh" => Ok(EnvShell::PowerShell),
+            other => Err(AppError::InvalidInput(format!(
+                "不支持的 shell 类型: {other}"
+            ))),
+        }
+    }
+}
+
+/// Whether live writes for this app should keep credentials out of the live files
+pub(crate) fn is_enabled(app_type: &AppType) -> bool {
+    !matches!(app_type, AppType::OpenCode) && crate::settings::get_settings().env_only_switching
+}
+
+/// Section of the provider config that holds credentials for each app
+fn secret_section(app_type: &AppType) -> Option<&'static str> {
+    match app_type {
+        AppType::Claude | AppType::Gemini => Some("env"),
+        AppType::Codex => Some("auth"),
+        AppType::OpenCode => None,
+    }
+}
+
+/// Remove credential entries from the provider config, returning them as env vars
+pub(crate) fn split_secrets(
+    app_type: &AppType,
+    settings: &Value,
+) -> (Value, Vec<(String, String)>) {
+    let mut stripped = settings.clone();
+    let mut vars = Vec::new();
+    let section = secret_section(app_type)
+        .and_then(|name| stripped.get_mut(name))
+        .and_then(Value::as_object_mut);
+    if let Some(section) = section {
+        section.retain(|key, value| match value.as_str() {
+            Some(secret) if is_secret_field(key) => {
+                vars.push((key.clone(), secret.to_string()));
+                false
+            }
+            _ => true,
+        });
+    }
+    (stripped, vars)
+}
+
+/// Render `vars` as a script that can be sourced by the given shell
+pub fn render_env_script(vars: &[(String, String)], shell: EnvShell) -> String {
+    let mut out = String::new();
+    for (key, value) in vars {
+        let line = match shell {
+            EnvShell::Posix => format!("export {key}='{}'\n", value.replace('\'', "'\\''")),
+            EnvShell::PowerShell => format!("$env:{key} = '{}'\n", value.replace('\'', "''")),
+        };
+        out.push_str(&line);
+    }
+    out
+}
+
+pub fn env_file_path(app_type: &AppType, shell: EnvShell) -> PathBuf {
+    get_app_config_dir()
+        .join("env")
+        .join(format!("{}.{}", app_type.as_str(), shell.extension()))
+}
+
+/// Write the provider's credentials to the env files and return a copy of the
+/// provider with those credentials removed (to be written to the live files)
+pub(crate) fn export_secrets(
+    app_type: &AppType,
+    provider: &Provider,
+) -> Result<Provider, AppError> {
+    let (stripped, vars) = split_secrets(app_type, &provider.settings_config);
+
+    for shell in [EnvShell::Posix, EnvShell::PowerShell] {
+        let path = env_file_path(app_type, shell);
+        let script = format!(
+            "# cc-switch: {} ({})\n{}",
+            provider.name.replace(['\r', '\n'], " "),
+            provider.id,
+            render_env_script(&vars, shell)
+        );
+        atomic_write(&path, script.as_bytes())?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| AppError::io(&path, e))?;
+        }
+    }
+    log::info!(
+        "Env-only mode: wrote {} credential(s) for {} to {}",
+        vars.len(),
+        app_type.as_str(),
+        env_file_path(app_type, EnvShell::Posix).display()
+    );
+
+    let mut provider = provider.clone();
+    provider.settings_config = stripped;
+    Ok(provider)
+}
+
+impl ProviderService {
+    /// Env script exporting a provider's credentials (for `eval` / dot-sourcing)
+    pub fn env_script(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        shell: EnvShell,
+    ) -> Result<String, AppError> {
+        if secret_section(&app_type).is_none() {
+            return Err(AppError::localized(
+                "env_only.unsupported_app",
+                "OpenCode 不支持环境变量模式",
+                "OpenCode does not support env-only mode",
+            ));
+        }
+        let provider = state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        let (_, vars) = split_secrets(&app_type, &provider.settings_config);
+        Ok(render_env_script(&vars, shell))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn splits_credentials_out_of_the_config() {
+        let settings = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-claude",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            },
+            "permissions": {}
+        });
+        let (stripped, vars) = split_secrets(&AppType::Claude, &settings);
+        assert_eq!(
+            vars,
+            vec![("ANTHROPIC_AUTH_TOKEN".to_string(), "sk-claude".to_string())]
+        );
+        assert_eq!(
+            stripped,
+            json!({ "env": { "ANTHROPIC_BASE_URL": "https://api.example.com" }, "permissions": {} })
+        );
+
+        let codex = json!({ "auth": { "OPENAI_API_KEY": "sk-codex" }, "config": "model = \"x\"" });
+        let (stripped, vars) = split_secrets(&AppType::Codex, &codex);
+        assert_eq!(vars[0].0, "OPENAI_API_KEY");
+        assert_eq!(stripped["auth"], json!({}));
+        assert_eq!(stripped["config"], codex["config"]);
+    }
+
+    #[test]
+    fn renders_quoted_scripts() {
+        let vars = vec![("KEY".to_string(), "a'b".to_string())];
+        assert_eq!(
+            render_env_script(&vars, EnvShell::Posix),
+            "export KEY='a'\\''b'\n"
+        );
+        assert_eq!(
+            render_env_script(&vars, EnvShell::PowerShell),
+            "$env:KEY = 'a''b'\n"
+        );
+    }
+}
@@ -1,8 +1,19 @@
 //! Gemini authentication type detection
 //!
-//! Detects whether a Gemini provider uses PackyCode API Key, Google OAuth, or generic API Key.
+//! Detects whether a Gemini provider uses PackyCode API Key, Google OAuth, or generic API Key,
+//! and validates that the provider config fits the detected mode before it is written.
+//!
+//! Switching only touches `~/.gemini/.env` (API keys) and `security.auth.selectedType` in
+//! `~/.gemini/settings.json`. The Google login credentials in `~/.gemini/oauth_creds.json` are
+//! owned by Gemini CLI and never modified, so switching back to Google login does not require
+//! signing in again.
+
+use serde_json::Value;
 
 use crate::error::AppError;
+use crate::gemini_config::{
+    json_to_env, validate_gemini_settings, validate_gemini_settings_strict,
+};
 use crate::provider::Provider;
 
 /// Gemini authentication type enumeration
@@ -18,6 +29,9 @@ pub(crate) enum GeminiAuthType {
     Generic,
 }
 
+/// Env keys that make Gemini CLI authenticate with an API key
+const API_KEY_ENV_KEYS: [&str; 2] = ["GEMINI_API_KEY", "GOOGLE_API_KEY"];
+
 // Partner Promotion Key constants
 const PACKYCODE_PARTNER_KEY: &str = "packycode";
 const GOOGLE_OFFICIAL_PARTNER_KEY: &str = "google-official";
@@ -98,6 +112,49 @@ pub(crate) fn is_google_official_gemini(provider: &Provider) -> bool {
     detect_gemini_auth_type(provider) == GeminiAuthType::GoogleOfficial
 }
 
+/// Validate the provider config against the detected auth mode
+///
+/// - Google login must not carry an API key, otherwise the key would silently be dropped
+///   from `.env` while the user expects it to be used
+/// - API key providers need a non-empty `GEMINI_API_KEY`, unless the key is exported
+///   through env-only switching instead of the live files (`key_exported`)
+pub(crate) fn validate_gemini_auth_mode(
+    auth_type: GeminiAuthType,
+    settings: &Value,
+    key_exported: bool,
+) -> Result<(), AppError> {
+    validate_gemini_settings(settings)?;
+    let env = json_to_env(settings)?;
+    let has_key = |key: &str| env.get(key).is_some_and(|value| !value.trim().is_empty());
+
+    match auth_type {
+        GeminiAuthType::GoogleOfficial => {
+            if let Some(key) = API_KEY_ENV_KEYS.into_iter().find(|key| has_key(key)) {
+                return Err(AppError::localized(
+                    "gemini.validation.oauth_with_api_key",
+                    format!("Google 官方登录（OAuth）不使用 API Key，请移除 {key} 或改用 API Key 供应商"),
+                    format!("Google login (OAuth) does not use an API key; remove {key} or use an API key provider"),
+                ));
+            }
+        }
+        GeminiAuthType::Packycode | GeminiAuthType::Generic => {
+            if key_exported {
+                return Ok(());
+            }
+            validate_gemini_settings_strict(settings)?;
+            // The strict check treats an empty env as OAuth; here the mode is known to be API key
+            if !has_key("GEMINI_API_KEY") {
+                return Err(AppError::localized(
+                    "gemini.validation.missing_api_key",
+                    "Gemini 配置缺少必需字段: GEMINI_API_KEY",
+                    "Gemini config missing required field: GEMINI_API_KEY",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Ensure Google Official Gemini provider security flag is correctly set (OAuth mode)
 ///
 /// Google Official Gemini uses OAuth personal authentication, no API Key needed.
@@ -0,0 +1,205 @@
+//! Provider config revision history
+//!
+//! Keeps previous `settings_config` snapshots on every update and computes
+//! structural diffs so accidental edits can be reviewed and rolled back.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::ProviderRevision;
+use crate::error::AppError;
+use crate::store::AppState;
+
+use super::ProviderService;
+
+/// Kind of a single change between two configs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A single changed leaf, addressed by a JSON pointer path
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChange {
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+/// History entry with the diff from that revision to the current config
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHistoryEntry {
+    #[serde(flatten)]
+    pub revision: ProviderRevision,
+    /// Changes that restoring this revision would revert (revision -> current)
+    pub diff: Vec<ConfigChange>,
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Structural diff between two JSON values (objects are compared key by key,
+/// everything else as a whole)
+pub fn diff_json(old: &Value, new: &Value) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    diff_into(old, new, String::new(), &mut changes);
+    changes
+}
+
+fn diff_into(old: &Value, new: &Value, path: String, out: &mut Vec<ConfigChange>) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old_value) in a {
+                let child = format!("{path}/{}", escape_pointer(key));
+                match b.get(key) {
+                    Some(new_value) => diff_into(old_value, new_value, child, out),
+                    None => out.push(ConfigChange {
+                        path: child,
+                        kind: ChangeKind::Removed,
+                        old: Some(old_value.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new_value) in b {
+                if !a.contains_key(key) {
+                    out.push(ConfigChange {
+                        path: format!("{path}/{}", escape_pointer(key)),
+                        kind: ChangeKind::Added,
+                        old: None,
+                        new: Some(new_value.clone()),
+                    });
+                }
+            }
+        }
+        _ if old == new => {}
+        _ => out.push(ConfigChange {
+            path,
+            kind: ChangeKind::Changed,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+/// Record the stored config as a revision if the incoming update changes it
+pub(crate) fn record_before_update(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    incoming: &Value,
+) -> Result<(), AppError> {
+    let Some(existing) = state
+        .db
+        .get_provider_by_id(provider_id, app_type.as_str())?
+    else {
+        return Ok(());
+    };
+    if &existing.settings_config == incoming {
+        return Ok(());
+    }
+    state.db.record_provider_revision(
+        app_type.as_str(),
+        provider_id,
+        &existing.name,
+        &existing.settings_config,
+    )?;
+    Ok(())
+}
+
+impl ProviderService {
+    /// List revisions of a provider, newest first, each with a diff to the current config
+    pub fn get_history(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+    ) -> Result<Vec<ProviderHistoryEntry>, AppError> {
+        let current = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .map(|p| p.settings_config)
+            .unwrap_or(Value::Null);
+
+        Ok(state
+            .db
+            .get_provider_revisions(app_type.as_str(), provider_id)?
+            .into_iter()
+            .map(|revision| ProviderHistoryEntry {
+                diff: diff_json(&revision.settings_config, &current),
+                revision,
+            })
+            .collect())
+    }
+
+    /// Restore a revision's `settings_config` through the regular update path,
+    /// so the config being replaced is itself kept as a new revision
+    pub fn restore_revision(
+        state: &AppState,
+        app_type: AppType,
+        provider_id: &str,
+        revision: i64,
+    ) -> Result<bool, AppError> {
+        let mut provider = state
+            .db
+            .get_provider_by_id(provider_id, app_type.as_str())?
+            .ok_or_else(|| AppError::Message(format!("供应商不存在: {provider_id}")))?;
+        let target = state
+            .db
+            .get_provider_revision(app_type.as_str(), provider_id, revision)?
+            .ok_or_else(|| {
+                AppError::Message(format!("供应商 {provider_id} 不存在历史版本 {revision}"))
+            })?;
+
+        provider.settings_config = target.settings_config;
+        Self::update(state, app_type, provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_leaves() {
+        let old = json!({
+            "env": { "ANTHROPIC_BASE_URL": "https://a", "KEEP": 1, "OLD": true }
+        });
+        let new = json!({
+            "env": { "ANTHROPIC_BASE_URL": "https://b", "KEEP": 1, "NEW": "x" }
+        });
+
+        let changes = diff_json(&old, &new);
+        assert_eq!(changes.len(), 3);
+        assert!(changes.contains(&ConfigChange {
+            path: "/env/ANTHROPIC_BASE_URL".into(),
+            kind: ChangeKind::Changed,
+            old: Some(json!("https://a")),
+            new: Some(json!("https://b")),
+        }));
+        assert!(changes
+            .iter()
+            .any(|c| c.path == "/env/OLD" && c.kind == ChangeKind::Removed));
+        assert!(changes
+            .iter()
+            .any(|c| c.path == "/env/NEW" && c.kind == ChangeKind::Added));
+    }
+
+    #[test]
+    fn diff_escapes_pointer_segments_and_ignores_equal_values() {
+        let old = json!({ "a/b": 1 });
+        let new = json!({ "a/b": 2 });
+        assert_eq!(diff_json(&old, &new)[0].path, "/a~1b");
+        assert!(diff_json(&old, &old).is_empty());
+    }
+}
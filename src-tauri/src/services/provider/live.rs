@@ -10,12 +10,15 @@ use crate::app_config::AppType;
 use crate::codex_config::{get_codex_auth_path, get_codex_config_path};
 use crate::config::{delete_file, get_claude_settings_path, read_json_file, write_json_file};
 use crate::error::AppError;
+use crate::live_link::write_live_file;
 use crate::provider::Provider;
 use crate::services::mcp::McpService;
 use crate::store::AppState;
 
+use super::claude_merge;
 use super::gemini_auth::{
-    detect_gemini_auth_type, ensure_google_oauth_security_flag, GeminiAuthType,
+    detect_gemini_auth_type, ensure_google_oauth_security_flag, validate_gemini_auth_mode,
+    GeminiAuthType,
 };
 use super::normalize_claude_models_in_value;
 
@@ -94,10 +97,46 @@ impl LiveSnapshot {
 
 /// Write live configuration snapshot for a provider
 pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Result<(), AppError> {
+    write_live_snapshot_replacing(app_type, provider, None)
+}
+
+/// Write live configuration snapshot for a provider that replaces `previous`
+///
+/// For Claude the keys set by the outgoing provider are removed from `settings.json`
+/// before the new provider is merged in (see [`claude_merge::merge_into_live`]).
+pub(crate) fn write_live_snapshot_replacing(
+    app_type: &AppType,
+    provider: &Provider,
+    previous: Option<&Provider>,
+) -> Result<(), AppError> {
+    let _audit = crate::audit_log::scope(app_type.as_str(), &provider.id);
+    let stripped;
+    let provider = if super::env_only::is_enabled(app_type) {
+        stripped = super::env_only::export_secrets(app_type, provider)?;
+        &stripped
+    } else {
+        provider
+    };
     match app_type {
         AppType::Claude => {
             let path = get_claude_settings_path();
-            write_json_file(&path, &provider.settings_config)?;
+            // Keep the user's hooks, permissions, status line etc. and only replace the
+            // provider-owned keys
+            let existing = if path.exists() {
+                read_json_file::<Value>(&path)
+                    .inspect_err(|e| log::warn!("Claude live settings unreadable, replacing: {e}"))
+                    .ok()
+            } else {
+                None
+            };
+            let merged = claude_merge::merge_into_live(
+                existing,
+                previous.map(|previous| &previous.settings_config),
+                &provider.settings_config,
+            );
+            let json = serde_json::to_string_pretty(&merged)
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            write_live_file(app_type, &provider.id, &path, json.as_bytes())?;
         }
         AppType::Codex => {
             let obj = provider
@@ -112,9 +151,13 @@ pub(crate) fn write_live_snapshot(app_type: &AppType, provider: &Provider) -> Re
             })?;
 
             let auth_path = get_codex_auth_path();
-            write_json_file(&auth_path, auth)?;
+            let auth_json = serde_json::to_string_pretty(auth)
+                .map_err(|e| AppError::JsonSerialize { source: e })?;
+            write_live_file(app_type, &provider.id, &auth_path, auth_json.as_bytes())?;
+            // Keep user-maintained sections such as [tui] and trusted projects
+            let config_text = crate::codex_config::merge_into_live_config(config_str)?;
             let config_path = get_codex_config_path();
-            std::fs::write(&config_path, config_str).map_err(|e| AppError::io(&config_path, e))?;
+            write_live_file(app_type, &provider.id, &config_path, config_text.as_bytes())?;
         }
         AppType::Gemini => {
             // Delegate to write_gemini_live which handles env file writing correctly
@@ -407,14 +450,23 @@ pub fn import_default_config(state: &AppState, app_type: AppType) -> Result<bool
 /// Write Gemini live configuration with authentication handling
 pub(crate) fn write_gemini_live(provider: &Provider) -> Result<(), AppError> {
     use crate::gemini_config::{
-        get_gemini_settings_path, json_to_env, validate_gemini_settings_strict,
+        get_gemini_settings_path, json_to_env, merge_gemini_env, read_gemini_env,
         write_gemini_env_atomic,
     };
 
     // One-time auth type detection to avoid repeated detection
     let auth_type = detect_gemini_auth_type(provider);
 
-    let mut env_map = json_to_env(&provider.settings_config)?;
+    // Validate before touching any file; in env-only mode the API key lives in the env script
+    let key_exported = super::env_only::is_enabled(&AppType::Gemini);
+    validate_gemini_auth_mode(auth_type, &provider.settings_config, key_exported)?;
+
+    // Only the auth-related keys are replaced, other user variables in .env are kept.
+    // Google login keeps its credentials in oauth_creds.json, which is left untouched.
+    let env_map = merge_gemini_env(
+        &read_gemini_env()?,
+        &json_to_env(&provider.settings_config)?,
+    );
 
     // Prepare config to write to ~/.gemini/settings.json
     // Behavior:
@@ -456,23 +508,7 @@ pub(crate) fn write_gemini_live(provider: &Provider) -> Result<(), AppError> {
         config_to_write = Some(read_json_file(&settings_path)?);
     }
 
-    match auth_type {
-        GeminiAuthType::GoogleOfficial => {
-            // Google official uses OAuth, clear env
-            env_map.clear();
-            write_gemini_env_atomic(&env_map)?;
-        }
-        GeminiAuthType::Packycode => {
-            // PackyCode provider, uses API Key (strict validation on switch)
-            validate_gemini_settings_strict(&provider.settings_config)?;
-            write_gemini_env_atomic(&env_map)?;
-        }
-        GeminiAuthType::Generic => {
-            // Generic provider, uses API Key (strict validation on switch)
-            validate_gemini_settings_strict(&provider.settings_config)?;
-            write_gemini_env_atomic(&env_map)?;
-        }
-    }
+    write_gemini_env_atomic(&env_map)?;
 
     if let Some(config_value) = config_to_write {
         write_json_file(&settings_path, &config_value)?;
@@ -0,0 +1,154 @@
+//! Provider protection (locking)
+//!
+//! A locked provider cannot be deleted and its credentials cannot be changed
+//! until it is explicitly unlocked. Other fields (name, notes, base URL, models)
+//! stay editable.
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::sync_secrets::is_secret_field;
+use crate::store::AppState;
+
+use super::history::diff_json;
+use super::ProviderService;
+
+/// JSON pointer paths of credential fields that differ between two configs
+pub(super) fn changed_secret_paths(
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+) -> Vec<String> {
+    diff_json(old, new)
+        .into_iter()
+        .filter(|change| {
+            change
+                .path
+                .rsplit('/')
+                .next()
+                .is_some_and(|key| is_secret_field(&key.replace("~1", "/").replace("~0", "~")))
+        })
+        .map(|change| change.path)
+        .collect()
+}
+
+fn locked_error(id: &str, zh_action: &str, en_action: &str) -> AppError {
+    AppError::localized(
+        "provider.locked",
+        format!("供应商 {id} 已锁定，请先解锁再{zh_action}"),
+        format!("Provider {id} is locked; unlock it before {en_action}"),
+    )
+}
+
+impl ProviderService {
+    /// Lock or unlock a provider
+    pub fn set_locked(
+        state: &AppState,
+        app_type: AppType,
+        id: &str,
+        locked: bool,
+    ) -> Result<(), AppError> {
+        if state
+            .db
+            .get_provider_by_id(id, app_type.as_str())?
+            .is_none()
+        {
+            return Err(AppError::Message(format!("供应商 {id} 不存在")));
+        }
+        state
+            .db
+            .set_provider_locked(app_type.as_str(), id, locked)?;
+        log::info!(
+            "{} provider {id} ({})",
+            if locked { "Locked" } else { "Unlocked" },
+            app_type.as_str()
+        );
+        Ok(())
+    }
+
+    /// Reject deleting a locked provider
+    pub(super) fn ensure_deletable(
+        state: &AppState,
+        app_type: &AppType,
+        id: &str,
+    ) -> Result<(), AppError> {
+        match state.db.get_provider_by_id(id, app_type.as_str())? {
+            Some(provider) if provider.locked => Err(locked_error(id, "删除", "deleting it")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reject changing the credentials of a locked provider
+    pub(super) fn ensure_secrets_unchanged(
+        state: &AppState,
+        app_type: &AppType,
+        provider: &Provider,
+    ) -> Result<(), AppError> {
+        let Some(existing) = state
+            .db
+            .get_provider_by_id(&provider.id, app_type.as_str())?
+        else {
+            return Ok(());
+        };
+        if !existing.locked {
+            return Ok(());
+        }
+        let changed = changed_secret_paths(&existing.settings_config, &provider.settings_config);
+        if changed.is_empty() {
+            return Ok(());
+        }
+        log::warn!(
+            "Rejected credential change on locked provider {}: {}",
+            provider.id,
+            changed.join(", ")
+        );
+        Err(locked_error(
+            &provider.id,
+            "修改密钥",
+            "changing its credentials",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_credential_fields_only() {
+        let old = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-old",
+                "ANTHROPIC_BASE_URL": "https://a.example.com",
+                "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "32000"
+            }
+        });
+        let new = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-old",
+                "ANTHROPIC_BASE_URL": "https://b.example.com",
+                "CLAUDE_CODE_MAX_OUTPUT_TOKENS": "64000"
+            }
+        });
+        assert!(changed_secret_paths(&old, &new).is_empty());
+
+        let rotated = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-new" } });
+        assert!(
+            changed_secret_paths(&old, &rotated).contains(&"/env/ANTHROPIC_AUTH_TOKEN".to_string())
+        );
+
+        let codex_old = json!({ "auth": { "OPENAI_API_KEY": "sk-a" }, "config": "" });
+        let codex_new = json!({ "auth": {}, "config": "" });
+        assert_eq!(
+            changed_secret_paths(&codex_old, &codex_new),
+            vec!["/auth/OPENAI_API_KEY".to_string()]
+        );
+
+        let opencode = json!({ "options": { "apiKey": "sk-a", "baseURL": "x" } });
+        let opencode_new = json!({ "options": { "apiKey": "sk-b", "baseURL": "x" } });
+        assert_eq!(
+            changed_secret_paths(&opencode, &opencode_new),
+            vec!["/options/apiKey".to_string()]
+        );
+    }
+}
@@ -2,9 +2,15 @@
 //!
 //! Handles provider CRUD operations, switching, and configuration management.
 
+mod adopt;
+mod batch;
+mod claude_merge;
 mod endpoints;
+mod env_only;
 mod gemini_auth;
+mod history;
 mod live;
+mod lock;
 mod usage;
 
 use indexmap::IndexMap;
@@ -13,10 +19,14 @@ use serde::Deserialize;
 use serde_json::Value;
 
 use crate::app_config::AppType;
+use crate::database::{ProviderPage, ProviderSort};
 use crate::error::AppError;
 use crate::provider::{Provider, UsageResult};
 use crate::services::mcp::McpService;
-use crate::settings::CustomEndpoint;
+use crate::services::switch_backup::SwitchBackupService;
+use crate::services::undo::{self, UndoEntry};
+use crate::services::webhook;
+use crate::settings::{CustomEndpoint, WebhookEvent};
 use crate::store::AppState;
 
 // Re-export sub-module functions for external access
@@ -25,11 +35,16 @@ pub use live::{
     sync_current_to_live,
 };
 
+pub use adopt::AdoptLiveResult;
+pub use env_only::{render_env_script, EnvShell};
+pub use history::{ChangeKind, ConfigChange, ProviderHistoryEntry};
+
 // Internal re-exports (pub(crate))
+pub(crate) use env_only::is_enabled as is_env_only;
 pub(crate) use live::write_live_snapshot;
 
 // Internal re-exports
-use live::{remove_opencode_provider_from_live, write_gemini_live};
+use live::{remove_opencode_provider_from_live, write_gemini_live, write_live_snapshot_replacing};
 use usage::validate_usage_script;
 
 /// Provider business logic service
@@ -135,6 +150,22 @@ impl ProviderService {
         state.db.get_all_providers(app_type.as_str())
     }
 
+    /// List one page of providers for an app type
+    ///
+    /// Large installations (imported team catalogs) page through providers instead of
+    /// serializing the whole set on every UI refresh.
+    pub fn list_page(
+        state: &AppState,
+        app_type: AppType,
+        offset: usize,
+        limit: usize,
+        sort: ProviderSort,
+    ) -> Result<ProviderPage, AppError> {
+        state
+            .db
+            .list_providers_page(app_type.as_str(), offset, limit, sort)
+    }
+
     /// Get current provider ID
     ///
     /// 使用有效的当前供应商 ID（验证过存在性）。
@@ -153,6 +184,7 @@ impl ProviderService {
 
     /// Add a new provider
     pub fn add(state: &AppState, app_type: AppType, provider: Provider) -> Result<bool, AppError> {
+        crate::read_only::ensure_writable()?;
         let mut provider = provider;
         // Normalize Claude model keys
         Self::normalize_provider_if_claude(&app_type, &mut provider);
@@ -186,10 +218,15 @@ impl ProviderService {
         app_type: AppType,
         provider: Provider,
     ) -> Result<bool, AppError> {
+        crate::read_only::ensure_writable()?;
         let mut provider = provider;
         // Normalize Claude model keys
         Self::normalize_provider_if_claude(&app_type, &mut provider);
         Self::validate_provider_settings(&app_type, &provider)?;
+        Self::ensure_secrets_unchanged(state, &app_type, &provider)?;
+
+        // Keep the previous config as a revision before overwriting it
+        history::record_before_update(state, &app_type, &provider.id, &provider.settings_config)?;
 
         // Save to database
         state.db.save_provider(app_type.as_str(), &provider)?;
@@ -239,26 +276,40 @@ impl ProviderService {
     /// 同时检查本地 settings 和数据库的当前供应商，防止删除任一端正在使用的供应商。
     /// 对于 OpenCode（累加模式），可以随时删除任意供应商，同时从 live 配置中移除。
     pub fn delete(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
+        crate::read_only::ensure_writable()?;
+        Self::ensure_deletable(state, &app_type, id)?;
+        let deleted = state
+            .db
+            .get_all_providers(app_type.as_str())?
+            .shift_remove(id);
         // OpenCode uses additive mode - no current provider concept
         if matches!(app_type, AppType::OpenCode) {
             // Remove from database
             state.db.delete_provider(app_type.as_str(), id)?;
             // Also remove from live config
             remove_opencode_provider_from_live(id)?;
-            return Ok(());
-        }
-
-        // For other apps: Check both local settings and database
-        let local_current = crate::settings::get_current_provider(&app_type);
-        let db_current = state.db.get_current_provider(app_type.as_str())?;
+        } else {
+            // For other apps: Check both local settings and database
+            let local_current = crate::settings::get_current_provider(&app_type);
+            let db_current = state.db.get_current_provider(app_type.as_str())?;
+
+            if local_current.as_deref() == Some(id) || db_current.as_deref() == Some(id) {
+                return Err(AppError::Message(
+                    "无法删除当前正在使用的供应商".to_string(),
+                ));
+            }
 
-        if local_current.as_deref() == Some(id) || db_current.as_deref() == Some(id) {
-            return Err(AppError::Message(
-                "无法删除当前正在使用的供应商".to_string(),
-            ));
+            state.db.delete_provider(app_type.as_str(), id)?;
         }
 
-        state.db.delete_provider(app_type.as_str(), id)
+        // Keep the deleted provider so it can be restored within this session
+        if let Some(provider) = deleted {
+            undo::record(UndoEntry::ProviderDeleted {
+                app_type,
+                provider: Box::new(provider),
+            });
+        }
+        Ok(())
     }
 
     /// Remove provider from live config only (for additive mode apps like OpenCode)
@@ -295,11 +346,16 @@ impl ProviderService {
     ///    d. Write target provider config to live files
     ///    e. Sync MCP configuration
     pub fn switch(state: &AppState, app_type: AppType, id: &str) -> Result<(), AppError> {
+        crate::read_only::ensure_writable()?;
         // Check if provider exists
         let providers = state.db.get_all_providers(app_type.as_str())?;
-        let _provider = providers
+        let target = providers
             .get(id)
             .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
+        // Re-applying the current provider is not a switch as far as webhooks are concerned
+        let is_change = crate::settings::get_effective_current_provider(&state.db, &app_type)?
+            .as_deref()
+            != Some(id);
 
         // Check if proxy takeover mode is active AND proxy server is actually running
         // Both conditions must be true to use hot-switch mode
@@ -354,11 +410,29 @@ impl ProviderService {
 
             // Note: No Live config write, no MCP sync
             // The proxy server will route requests to the new provider via is_current
-            return Ok(());
+        } else {
+            // Normal mode: full switch with Live config write
+            Self::switch_normal(state, app_type.clone(), id, &providers)?;
         }
 
-        // Normal mode: full switch with Live config write
-        Self::switch_normal(state, app_type, id, &providers)
+        if is_change {
+            // Feeds the tray's "Recent" section; losing one entry is not worth failing the switch
+            if let Err(e) = state.db.record_provider_switch(
+                app_type.as_str(),
+                id,
+                chrono::Utc::now().timestamp_millis(),
+            ) {
+                log::warn!("Failed to record provider switch: {e}");
+            }
+            webhook::notify(
+                WebhookEvent::ProviderSwitched,
+                app_type.as_str(),
+                id,
+                &target.name,
+                None,
+            );
+        }
+        Ok(())
     }
 
     /// Normal switch flow (non-proxy mode)
@@ -372,21 +446,56 @@ impl ProviderService {
             .get(id)
             .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))?;
 
+        // Opt-in safety backup of the live files about to be overwritten (for undo_last_switch)
+        SwitchBackupService::backup_before_switch(&state.db, &app_type, id)?;
+
         // Backfill: Backfill current live config to current provider
         // Use effective current provider (validated existence) to ensure backfill targets valid provider
         let current_id = crate::settings::get_effective_current_provider(&state.db, &app_type)?;
 
+        // The outgoing provider as recorded at backfill time; its own keys are removed
+        // from the live file before the new provider is written
+        let mut outgoing = None;
         if let Some(current_id) = current_id {
             if current_id != id {
+                outgoing = providers.get(&current_id).cloned();
                 // OpenCode uses additive mode - all providers coexist in the same file,
                 // no backfill needed (backfill is for exclusive mode apps like Claude/Codex/Gemini)
-                if !matches!(app_type, AppType::OpenCode) {
+                // In env-only mode the live files carry no credentials, so backfilling
+                // them would wipe the stored keys
+                if !matches!(app_type, AppType::OpenCode) && !env_only::is_enabled(&app_type) {
                     // Only backfill when switching to a different provider
-                    if let Ok(live_config) = read_live_settings(app_type.clone()) {
+                    if let Ok(mut live_config) = read_live_settings(app_type.clone()) {
+                        // Codex user sections stay in config.toml, only the managed
+                        // keys belong to the provider
+                        if matches!(app_type, AppType::Codex) {
+                            if let Some(text) = live_config.get("config").and_then(Value::as_str) {
+                                let fragment = crate::codex_config::managed_fragment(text);
+                                live_config["config"] = Value::String(fragment);
+                            }
+                        }
                         if let Some(mut current_provider) = providers.get(&current_id).cloned() {
-                            current_provider.settings_config = live_config;
-                            // Ignore backfill failure, don't affect switch flow
-                            let _ = state.db.save_provider(app_type.as_str(), &current_provider);
+                            // Same for Claude: hooks, permissions etc. stay in settings.json
+                            if matches!(app_type, AppType::Claude) {
+                                live_config = claude_merge::managed_fragment(
+                                    &live_config,
+                                    &current_provider.settings_config,
+                                );
+                            }
+                            // Never let live edits overwrite a locked provider's credentials
+                            let keeps_secrets = !current_provider.locked
+                                || lock::changed_secret_paths(
+                                    &current_provider.settings_config,
+                                    &live_config,
+                                )
+                                .is_empty();
+                            if keeps_secrets {
+                                current_provider.settings_config = live_config;
+                                // Ignore backfill failure, don't affect switch flow
+                                let _ =
+                                    state.db.save_provider(app_type.as_str(), &current_provider);
+                                outgoing = Some(current_provider);
+                            }
                         }
                     }
                 }
@@ -403,7 +512,22 @@ impl ProviderService {
         }
 
         // Sync to live (write_gemini_live handles security flag internally for Gemini)
-        write_live_snapshot(&app_type, provider)?;
+        write_live_snapshot_replacing(&app_type, provider, outgoing.as_ref())?;
+
+        // Keep the default config dir in sync as well when it is overridden (e.g. WSL + Windows)
+        let settings = crate::settings::get_settings();
+        if settings.sync_provider_switch_to_both_config_dirs
+            && settings.config_dir_override(&app_type).is_some()
+        {
+            if let Err(e) = crate::settings::with_default_config_dirs(|| {
+                write_live_snapshot_replacing(&app_type, provider, outgoing.as_ref())
+            }) {
+                log::warn!(
+                    "Failed to sync {} provider to the default config dir: {e}",
+                    app_type.as_str()
+                );
+            }
+        }
 
         // Sync MCP
         McpService::sync_all_enabled(state)?;
@@ -778,12 +902,19 @@ impl ProviderService {
             AppType::OpenCode => {
                 // OpenCode uses a different config structure: { npm, options, models }
                 // Basic validation - must be an object
-                if !provider.settings_config.is_object() {
+                let Some(obj) = provider.settings_config.as_object() else {
                     return Err(AppError::localized(
                         "provider.opencode.settings.not_object",
                         "OpenCode 配置必须是 JSON 对象",
                         "OpenCode configuration must be a JSON object",
                     ));
+                };
+                // Full config structures are unwrapped when written, validate fragments early
+                if !obj.contains_key("$schema") && !obj.contains_key("provider") {
+                    crate::opencode_config::validate_provider_config(
+                        &provider.id,
+                        &provider.settings_config,
+                    )?;
                 }
             }
         }
@@ -1070,6 +1201,7 @@ impl ProviderService {
         state: &AppState,
         provider: UniversalProvider,
     ) -> Result<bool, AppError> {
+        crate::read_only::ensure_writable()?;
         // 保存统一供应商
         state.db.save_universal_provider(&provider)?;
 
@@ -1078,6 +1210,7 @@ impl ProviderService {
 
     /// 删除统一供应商
     pub fn delete_universal(state: &AppState, id: &str) -> Result<bool, AppError> {
+        crate::read_only::ensure_writable()?;
         // 获取统一供应商（用于删除生成的子供应商）
         let provider = state.db.get_universal_provider(id)?;
 
@@ -1157,7 +1290,7 @@ impl ProviderService {
     }
 
     /// 递归合并 JSON：base 为底，patch 覆盖同名字段
-    fn merge_json(base: &mut serde_json::Value, patch: &serde_json::Value) {
+    pub(crate) fn merge_json(base: &mut serde_json::Value, patch: &serde_json::Value) {
         use serde_json::Value;
 
         match (base, patch) {
@@ -0,0 +1,132 @@
+//! 供应商对比
+//!
+//! 把同一应用下几个供应商的测速结果（见 [`super::benchmark`]）、最近 7 天的可用性（见
+//! [`super::uptime`]）、会话用量成本（见 [`super::cost_report`]）与模型列表（见
+//! [`super::model_list`]）合并到一起，方便决定继续使用哪个中转站。
+//!
+//! 测速与可用性使用已保存的结果，不会重新测试；模型列表按需请求（十分钟内使用缓存）。
+
+use futures::future::join_all;
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::services::benchmark::ProviderBenchmark;
+use crate::services::cost_report::{self, CostPeriod, ProviderCost};
+use crate::services::model_list::{self, ProviderModels};
+use crate::services::session_usage::UsageRange;
+use crate::services::uptime::{self, ProviderUptime};
+
+/// 可用性统计的时间范围（小时）
+const UPTIME_HOURS: u32 = 7 * 24;
+
+/// 单个供应商的对比数据（没有对应记录的项为空）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparison {
+    pub provider_id: String,
+    pub provider_name: String,
+    pub benchmark: Option<ProviderBenchmark>,
+    pub uptime: Option<ProviderUptime>,
+    /// 统计范围内的用量与成本（按月汇总）
+    pub cost: Option<ProviderCost>,
+    pub models: Option<ProviderModels>,
+    /// 模型列表请求失败的原因
+    pub models_error: Option<String>,
+}
+
+/// 供应商对比结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderComparisonReport {
+    pub app_type: String,
+    /// 成本的统计范围
+    pub range: UsageRange,
+    pub uptime_hours: u32,
+    /// 与请求的顺序一致
+    pub providers: Vec<ProviderComparison>,
+    /// Unix 时间戳（秒）
+    pub generated_at: i64,
+}
+
+/// 读取要对比的供应商，不存在时报错
+pub fn load_providers(
+    db: &Database,
+    app_type: &AppType,
+    ids: &[String],
+) -> Result<Vec<Provider>, AppError> {
+    if ids.is_empty() {
+        return Err(AppError::InvalidInput("请选择要对比的供应商".to_string()));
+    }
+    ids.iter()
+        .map(|id| {
+            db.get_provider_by_id(id, app_type.as_str())?
+                .ok_or_else(|| AppError::Message(format!("供应商 {id} 不存在")))
+        })
+        .collect()
+}
+
+/// 汇总已保存的测速、可用性与成本数据（会读取会话日志，应在阻塞线程中调用）
+pub fn build_report(
+    db: &Database,
+    app_type: &AppType,
+    providers: &[Provider],
+    range: UsageRange,
+) -> Result<ProviderComparisonReport, AppError> {
+    let benchmarks = db.get_provider_benchmarks(app_type.as_str())?;
+    let uptimes = uptime::get_uptime(db, Some(app_type), UPTIME_HOURS)?;
+    let costs = cost_report::get_cost_report(db, range, CostPeriod::Month)?;
+
+    let providers = providers
+        .iter()
+        .map(|provider| ProviderComparison {
+            provider_id: provider.id.clone(),
+            provider_name: provider.name.clone(),
+            benchmark: benchmarks
+                .iter()
+                .find(|result| result.provider_id == provider.id)
+                .cloned(),
+            uptime: uptimes
+                .iter()
+                .find(|result| result.provider_id == provider.id)
+                .cloned(),
+            cost: costs
+                .providers
+                .iter()
+                .find(|cost| cost.app_type == app_type.as_str() && cost.provider_id == provider.id)
+                .cloned(),
+            models: None,
+            models_error: None,
+        })
+        .collect();
+
+    Ok(ProviderComparisonReport {
+        app_type: app_type.as_str().to_string(),
+        range,
+        uptime_hours: UPTIME_HOURS,
+        providers,
+        generated_at: chrono::Utc::now().timestamp(),
+    })
+}
+
+/// 并发请求各供应商的模型列表并填入对比结果
+pub async fn attach_models(
+    app_type: &AppType,
+    providers: &[Provider],
+    report: &mut ProviderComparisonReport,
+) {
+    let results = join_all(
+        providers
+            .iter()
+            .map(|provider| model_list::list_models(app_type, provider, false)),
+    )
+    .await;
+    for (comparison, result) in report.providers.iter_mut().zip(results) {
+        match result {
+            Ok(models) => comparison.models = Some(models),
+            Err(e) => comparison.models_error = Some(e.to_string()),
+        }
+    }
+}
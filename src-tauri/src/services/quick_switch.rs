@@ -0,0 +1,272 @@
+//! 快速切换（命令面板）
+//!
+//! 在所有应用的供应商中按查询做模糊匹配并排序，供 Spotlight 式的命令面板使用：
+//!
+//! - 查询按空白拆分，每一段都要能匹配供应商名称、ID 或应用名（如 `codex relay`）；
+//! - 连续命中、单词开头命中与前缀命中得分更高；
+//! - 置顶（`meta.pinned`）与最近切换过的供应商额外加分，当前供应商略微降权；
+//! - 查询为空时只按置顶与最近使用排序。
+//!
+//! OpenCode 为累加模式，没有「切换」，不参与快速切换。
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::store::AppState;
+
+/// 参与快速切换的应用
+const APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 默认返回的候选数
+pub const DEFAULT_LIMIT: usize = 20;
+
+const PIN_BONUS: i64 = 40;
+/// 按最近使用的先后加分（最近一次切换的供应商在前）
+const RECENT_BONUS: [i64; 5] = [30, 20, 12, 6, 3];
+const CURRENT_PENALTY: i64 = 15;
+
+/// 候选项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickSwitchCandidate {
+    /// `<app>:<providerId>`，传给 `execute_quick_switch`
+    pub id: String,
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub current: bool,
+    pub pinned: bool,
+    pub score: i64,
+}
+
+/// 参与排序的供应商
+struct Entry {
+    app_type: AppType,
+    provider_id: String,
+    name: String,
+    current: bool,
+    pinned: bool,
+    /// 在最近使用中的位置
+    recent_rank: Option<usize>,
+}
+
+/// 子序列模糊匹配得分，不匹配时为 None（忽略大小写）
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars() {
+        let index = next + chars[next..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || !chars[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        previous = Some(index);
+        next = index + 1;
+    }
+
+    if text.starts_with(&query) {
+        score += 15;
+    } else if text.contains(&query) {
+        score += 10;
+    }
+    Some(score)
+}
+
+fn match_score(query: &str, entry: &Entry) -> Option<i64> {
+    query.split_whitespace().try_fold(0, |total, token| {
+        let best = [
+            fuzzy_score(token, &entry.name),
+            fuzzy_score(token, &entry.provider_id).map(|score| score / 2),
+            fuzzy_score(token, entry.app_type.as_str()).map(|score| score / 2),
+        ]
+        .into_iter()
+        .flatten()
+        .max()?;
+        Some(total + best)
+    })
+}
+
+fn rank(query: &str, entries: Vec<Entry>, limit: usize) -> Vec<QuickSwitchCandidate> {
+    let mut candidates = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let mut score = match_score(query, &entry)?;
+            if entry.pinned {
+                score += PIN_BONUS;
+            }
+            if let Some(rank) = entry.recent_rank {
+                score += RECENT_BONUS.get(rank).copied().unwrap_or_default();
+            }
+            if entry.current {
+                score -= CURRENT_PENALTY;
+            }
+            Some(QuickSwitchCandidate {
+                id: format!("{}:{}", entry.app_type.as_str(), entry.provider_id),
+                app_type: entry.app_type.as_str().to_string(),
+                provider_id: entry.provider_id,
+                provider_name: entry.name,
+                current: entry.current,
+                pinned: entry.pinned,
+                score,
+            })
+        })
+        .collect::<Vec<_>>();
+    // 稳定排序：同分时保持应用与供应商的原有顺序
+    candidates.sort_by(|a, b| b.score.cmp(&a.score));
+    candidates.truncate(limit);
+    candidates
+}
+
+/// 按查询排序的候选供应商
+pub fn candidates(
+    state: &AppState,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<QuickSwitchCandidate>, AppError> {
+    let mut entries = Vec::new();
+    for app_type in APPS {
+        let providers = state.db.get_all_providers(app_type.as_str())?;
+        let current = crate::settings::get_effective_current_provider(&state.db, &app_type)?
+            .unwrap_or_default();
+        let recent = state
+            .db
+            .get_recent_provider_ids(app_type.as_str(), RECENT_BONUS.len())?;
+
+        for provider in providers.into_values() {
+            entries.push(Entry {
+                app_type: app_type.clone(),
+                current: provider.id == current,
+                pinned: provider
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.pinned)
+                    .unwrap_or(false),
+                recent_rank: recent.iter().position(|id| *id == provider.id),
+                provider_id: provider.id,
+                name: provider.name,
+            });
+        }
+    }
+    Ok(rank(query, entries, limit))
+}
+
+/// 解析候选项 ID（`<app>:<providerId>`）
+pub fn parse_candidate_id(candidate_id: &str) -> Result<(AppType, String), AppError> {
+    let invalid = || AppError::InvalidInput(format!("无效的快速切换候选项: {candidate_id}"));
+    let (app, provider_id) = candidate_id.split_once(':').ok_or_else(invalid)?;
+    let app_type = AppType::from_str(app).map_err(|_| invalid())?;
+    if !APPS.contains(&app_type) || provider_id.is_empty() {
+        return Err(invalid());
+    }
+    Ok((app_type, provider_id.to_string()))
+}
+
+/// 置顶或取消置顶供应商
+pub fn set_pinned(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    pinned: bool,
+) -> Result<(), AppError> {
+    let mut provider = state
+        .db
+        .get_provider_by_id(provider_id, app_type.as_str())?
+        .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+    provider.meta.get_or_insert_default().pinned = pinned.then_some(true);
+    state.db.save_provider(app_type.as_str(), &provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(app_type: AppType, id: &str, name: &str) -> Entry {
+        Entry {
+            app_type,
+            provider_id: id.to_string(),
+            name: name.to_string(),
+            current: false,
+            pinned: false,
+            recent_rank: None,
+        }
+    }
+
+    fn ids(candidates: &[QuickSwitchCandidate]) -> Vec<&str> {
+        candidates.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_prefixes_and_word_starts() {
+        assert!(fuzzy_score("xyz", "OpenRouter").is_none());
+        let prefix = fuzzy_score("open", "OpenRouter").unwrap();
+        let inner = fuzzy_score("rout", "OpenRouter").unwrap();
+        let scattered = fuzzy_score("ontr", "OpenRouter").unwrap();
+        assert!(prefix > inner && inner > scattered);
+        assert!(fuzzy_score("kr", "Kimi Relay").unwrap() > fuzzy_score("kr", "Kilroy").unwrap());
+    }
+
+    #[test]
+    fn ranks_by_match_pins_and_recency() {
+        let entries = vec![
+            entry(AppType::Claude, "relay", "Relay"),
+            Entry {
+                pinned: true,
+                ..entry(AppType::Codex, "relay", "Relay")
+            },
+            entry(AppType::Claude, "official", "Official"),
+        ];
+        // 同名时置顶的排前
+        assert_eq!(
+            ids(&rank("relay", entries, 10)),
+            vec!["codex:relay", "claude:relay"]
+        );
+
+        let entries = vec![
+            entry(AppType::Claude, "relay", "Relay"),
+            Entry {
+                current: true,
+                recent_rank: Some(0),
+                ..entry(AppType::Claude, "official", "Official")
+            },
+            Entry {
+                recent_rank: Some(1),
+                ..entry(AppType::Claude, "kimi", "Kimi")
+            },
+        ];
+        assert_eq!(
+            ids(&rank("", entries, 10)),
+            vec!["claude:kimi", "claude:official", "claude:relay"]
+        );
+
+        let entries = vec![
+            entry(AppType::Claude, "relay", "Relay"),
+            entry(AppType::Gemini, "relay", "Relay"),
+        ];
+        // 应用名可参与匹配
+        assert_eq!(ids(&rank("gem rel", entries, 10)), vec!["gemini:relay"]);
+    }
+
+    #[test]
+    fn parses_candidate_ids() {
+        let (app_type, id) = parse_candidate_id("codex:team:relay").unwrap();
+        assert_eq!(app_type, AppType::Codex);
+        assert_eq!(id, "team:relay");
+        assert!(parse_candidate_id("opencode:relay").is_err());
+        assert!(parse_candidate_id("claude:").is_err());
+        assert!(parse_candidate_id("relay").is_err());
+    }
+}
@@ -0,0 +1,530 @@
+//! S3 兼容存储的加密备份
+//!
+//! 将数据库 SQL 快照与设备设置打包为 JSON，使用备份口令加密后上传到
+//! S3 / R2 / MinIO 等 S3 兼容服务。请求使用 AWS Signature V4 签名，
+//! 访问密钥与口令保存在系统钥匙串中。
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::secret_store;
+use crate::settings::S3BackupConfig;
+
+const ACCESS_KEY_ID_SECRET: &str = "s3-access-key-id";
+const SECRET_ACCESS_KEY_SECRET: &str = "s3-secret-access-key";
+const PASSPHRASE_SECRET: &str = "backup-passphrase";
+
+const BACKUP_EXTENSION: &str = ".ccbak";
+const PAYLOAD_VERSION: u32 = 1;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 远端备份条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteBackupEntry {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: String,
+}
+
+/// 钥匙串中的凭据状态（不返回明文）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3CredentialStatus {
+    pub has_access_key: bool,
+    pub has_passphrase: bool,
+}
+
+/// 加密前的备份内容
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupPayload {
+    version: u32,
+    created_at: i64,
+    device: String,
+    database_sql: String,
+    settings: serde_json::Value,
+}
+
+struct S3Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+/// S3 加密备份业务
+pub struct S3BackupService;
+
+impl S3BackupService {
+    /// 保存访问密钥与备份口令到系统钥匙串（传入空字符串表示删除）
+    pub fn set_credentials(
+        access_key_id: &str,
+        secret_access_key: &str,
+        passphrase: Option<&str>,
+    ) -> Result<(), AppError> {
+        secret_store::set_secret(ACCESS_KEY_ID_SECRET, access_key_id.trim())?;
+        secret_store::set_secret(SECRET_ACCESS_KEY_SECRET, secret_access_key.trim())?;
+        if let Some(passphrase) = passphrase {
+            secret_store::set_secret(PASSPHRASE_SECRET, passphrase)?;
+        }
+        Ok(())
+    }
+
+    pub fn credential_status() -> Result<S3CredentialStatus, AppError> {
+        Ok(S3CredentialStatus {
+            has_access_key: secret_store::get_secret(ACCESS_KEY_ID_SECRET)?.is_some()
+                && secret_store::get_secret(SECRET_ACCESS_KEY_SECRET)?.is_some(),
+            has_passphrase: secret_store::get_secret(PASSPHRASE_SECRET)?.is_some(),
+        })
+    }
+
+    fn load_credentials() -> Result<S3Credentials, AppError> {
+        let access_key_id = secret_store::get_secret(ACCESS_KEY_ID_SECRET)?;
+        let secret_access_key = secret_store::get_secret(SECRET_ACCESS_KEY_SECRET)?;
+        match (access_key_id, secret_access_key) {
+            (Some(access_key_id), Some(secret_access_key)) => Ok(S3Credentials {
+                access_key_id,
+                secret_access_key,
+            }),
+            _ => Err(AppError::localized(
+                "s3.credentials_missing",
+                "尚未设置 S3 访问密钥",
+                "S3 access keys are not configured",
+            )),
+        }
+    }
+
+    fn load_passphrase() -> Result<String, AppError> {
+        secret_store::get_secret(PASSPHRASE_SECRET)?.ok_or_else(|| {
+            AppError::localized(
+                "s3.passphrase_missing",
+                "尚未设置备份加密口令",
+                "Backup passphrase is not configured",
+            )
+        })
+    }
+
+    fn config_from_settings() -> Result<S3BackupConfig, AppError> {
+        let config = crate::settings::get_settings().s3_backup.ok_or_else(|| {
+            AppError::localized(
+                "s3.not_configured",
+                "尚未配置 S3 备份",
+                "S3 backup is not configured",
+            )
+        })?;
+        if config.bucket.trim().is_empty() {
+            return Err(AppError::InvalidInput("S3 存储桶名称不能为空".to_string()));
+        }
+        Url::parse(config.endpoint.trim())
+            .map_err(|e| AppError::InvalidInput(format!("S3 端点地址无效: {e}")))?;
+        Ok(config)
+    }
+
+    /// 打包并加密当前数据库与设置，上传到 S3
+    pub async fn upload_backup(db: Arc<Database>) -> Result<RemoteBackupEntry, AppError> {
+        let config = Self::config_from_settings()?;
+        let credentials = Self::load_credentials()?;
+        let passphrase = Self::load_passphrase()?;
+
+        let now = Utc::now();
+        let encrypted = tokio::task::spawn_blocking(move || {
+            let settings_json = crate::settings::export_settings(false)?;
+            let payload = BackupPayload {
+                version: PAYLOAD_VERSION,
+                created_at: now.timestamp_millis(),
+                device: super::webdav_sync::device_name(),
+                database_sql: db.export_sql_string()?,
+                settings: serde_json::from_str(&settings_json)
+                    .map_err(|e| AppError::JsonSerialize { source: e })?,
+            };
+            let plain =
+                serde_json::to_vec(&payload).map_err(|e| AppError::JsonSerialize { source: e })?;
+            crate::crypto::encrypt_with_passphrase(&plain, &passphrase)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("生成备份失败: {e}")))??;
+
+        let key = format!(
+            "{}cc-switch-{}{BACKUP_EXTENSION}",
+            key_prefix(&config),
+            now.format("%Y%m%d_%H%M%S")
+        );
+        let size = encrypted.len() as u64;
+        let url = object_url(&config, &key, &[])?;
+        send_signed(&config, &credentials, Method::PUT, url, encrypted).await?;
+
+        log::info!("S3 备份上传完成: {key} ({size} 字节)");
+        Ok(RemoteBackupEntry {
+            key,
+            size,
+            last_modified: now.to_rfc3339(),
+        })
+    }
+
+    /// 列出远端备份（按时间倒序）
+    pub async fn list_backups() -> Result<Vec<RemoteBackupEntry>, AppError> {
+        let config = Self::config_from_settings()?;
+        let credentials = Self::load_credentials()?;
+
+        let prefix = key_prefix(&config);
+        let mut entries = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut query = vec![
+                ("list-type".to_string(), "2".to_string()),
+                ("prefix".to_string(), prefix.clone()),
+            ];
+            if let Some(token) = &continuation {
+                query.push(("continuation-token".to_string(), token.clone()));
+            }
+            let url = object_url(&config, "", &query)?;
+            let body = send_signed(&config, &credentials, Method::GET, url, Vec::new()).await?;
+            let xml = String::from_utf8_lossy(&body);
+
+            entries.extend(
+                parse_list_objects(&xml)
+                    .into_iter()
+                    .filter(|e| e.key.ends_with(BACKUP_EXTENSION)),
+            );
+
+            continuation = xml_tag_values(&xml, "NextContinuationToken")
+                .into_iter()
+                .next();
+            if continuation.is_none() {
+                break;
+            }
+        }
+
+        entries.sort_by(|a, b| b.key.cmp(&a.key));
+        Ok(entries)
+    }
+
+    /// 下载并解密指定备份，恢复数据库与设置，返回本地数据库备份 ID
+    pub async fn restore_backup(db: Arc<Database>, key: &str) -> Result<String, AppError> {
+        let config = Self::config_from_settings()?;
+        let credentials = Self::load_credentials()?;
+        let passphrase = Self::load_passphrase()?;
+
+        let url = object_url(&config, key, &[])?;
+        let encrypted = send_signed(&config, &credentials, Method::GET, url, Vec::new()).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let plain = crate::crypto::decrypt_with_passphrase(&encrypted, &passphrase)?;
+            let payload: BackupPayload = serde_json::from_slice(&plain)
+                .map_err(|e| AppError::InvalidInput(format!("备份内容格式无效: {e}")))?;
+            if payload.version > PAYLOAD_VERSION {
+                return Err(AppError::InvalidInput(format!(
+                    "备份格式版本 {} 过新，请升级 CC Switch",
+                    payload.version
+                )));
+            }
+
+            let backup_id = db.import_sql_string(&payload.database_sql)?;
+            crate::settings::import_settings(&payload.settings.to_string())?;
+            super::ConfigService::refresh_after_database_restore(&crate::store::AppState::new(db));
+            Ok(backup_id)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("恢复备份失败: {e}")))?
+    }
+}
+
+/// 对象键前缀（非空时以 `/` 结尾）
+fn key_prefix(config: &S3BackupConfig) -> String {
+    let prefix = config.prefix.trim().trim_matches('/');
+    if prefix.is_empty() {
+        String::new()
+    } else {
+        format!("{prefix}/")
+    }
+}
+
+/// 构造对象地址（`key` 为空时返回存储桶地址）
+fn object_url(
+    config: &S3BackupConfig,
+    key: &str,
+    query: &[(String, String)],
+) -> Result<Url, AppError> {
+    let endpoint = Url::parse(config.endpoint.trim())
+        .map_err(|e| AppError::InvalidInput(format!("S3 端点地址无效: {e}")))?;
+    let bucket = config.bucket.trim();
+    let base_path = endpoint.path().trim_end_matches('/');
+    let encoded_key = key.split('/').map(uri_encode).collect::<Vec<_>>().join("/");
+
+    let mut url = endpoint.clone();
+    if config.path_style {
+        url.set_path(&format!("{base_path}/{bucket}/{encoded_key}"));
+    } else {
+        let host = endpoint
+            .host_str()
+            .ok_or_else(|| AppError::InvalidInput("S3 端点缺少主机名".to_string()))?;
+        url.set_host(Some(&format!("{bucket}.{host}")))
+            .map_err(|e| AppError::InvalidInput(format!("S3 端点地址无效: {e}")))?;
+        url.set_path(&format!("{base_path}/{encoded_key}"));
+    }
+
+    if query.is_empty() {
+        url.set_query(None);
+    } else {
+        url.set_query(Some(&canonical_query(query)));
+    }
+    Ok(url)
+}
+
+async fn send_signed(
+    config: &S3BackupConfig,
+    credentials: &S3Credentials,
+    method: Method,
+    url: Url,
+    body: Vec<u8>,
+) -> Result<Vec<u8>, AppError> {
+    let now = Utc::now();
+    let payload_hash = hex(&Sha256::digest(&body));
+    let authorization = authorization_header(
+        method.as_str(),
+        &url,
+        &payload_hash,
+        config.region.trim(),
+        credentials,
+        now,
+    );
+
+    let resp = crate::proxy::http_client::get()
+        .request(method, url.as_str())
+        .header("x-amz-content-sha256", &payload_hash)
+        .header("x-amz-date", amz_date(now))
+        .header("Authorization", authorization)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| AppError::Message(format!("S3 请求失败: {e}")))?;
+
+    let status = resp.status();
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| AppError::Message(format!("读取 S3 响应失败: {e}")))?;
+    if !status.is_success() {
+        let body = String::from_utf8_lossy(&bytes);
+        let code = xml_tag_values(&body, "Code").into_iter().next();
+        return Err(AppError::Message(format!(
+            "S3 请求失败: HTTP {status}{}",
+            code.map(|c| format!(" ({c})")).unwrap_or_default()
+        )));
+    }
+    Ok(bytes.to_vec())
+}
+
+fn amz_date(now: DateTime<Utc>) -> String {
+    now.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// 生成 AWS Signature V4 `Authorization` 头
+fn authorization_header(
+    method: &str,
+    url: &Url,
+    payload_hash: &str,
+    region: &str,
+    credentials: &S3Credentials,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = amz_date(now);
+    let date = now.format("%Y%m%d").to_string();
+    let host = match url.port() {
+        Some(port) => format!("{}:{port}", url.host_str().unwrap_or_default()),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+
+    let query_pairs = url
+        .query_pairs()
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>();
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{method}\n{}\n{}\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}",
+        url.path(),
+        canonical_query(&query_pairs),
+    );
+
+    let scope = format!("{date}/{region}/s3/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = signing_key(&credentials.secret_access_key, &date, region, "s3");
+    let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id
+    )
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let k_date = hmac_sha256(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let k_region = hmac_sha256(&k_date, region.as_bytes());
+    let k_service = hmac_sha256(&k_region, service.as_bytes());
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// RFC 3986 编码（仅保留非保留字符）
+fn uri_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for b in input.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+fn canonical_query(pairs: &[(String, String)]) -> String {
+    let mut encoded = pairs
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect::<Vec<_>>();
+    encoded.sort();
+    encoded
+        .into_iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 提取 XML 中指定标签的所有文本值（S3 响应结构简单，无需完整解析器）
+fn xml_tag_values(xml: &str, tag: &str) -> Vec<String> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut values = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else {
+            break;
+        };
+        values.push(xml_unescape(&after[..end]));
+        rest = &after[end + close.len()..];
+    }
+    values
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn parse_list_objects(xml: &str) -> Vec<RemoteBackupEntry> {
+    xml_tag_values(xml, "Contents")
+        .iter()
+        .filter_map(|item| {
+            let key = xml_tag_values(item, "Key").into_iter().next()?;
+            let size = xml_tag_values(item, "Size")
+                .into_iter()
+                .next()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0);
+            let last_modified = xml_tag_values(item, "LastModified")
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            Some(RemoteBackupEntry {
+                key,
+                size,
+                last_modified,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(path_style: bool) -> S3BackupConfig {
+        S3BackupConfig {
+            endpoint: "https://s3.example.com".to_string(),
+            region: "us-east-1".to_string(),
+            bucket: "my-bucket".to_string(),
+            prefix: "/cc-switch/".to_string(),
+            path_style,
+        }
+    }
+
+    #[test]
+    fn signing_key_matches_aws_reference() {
+        // AWS 文档示例：Deriving the signing key
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn object_url_supports_path_and_virtual_host_style() {
+        let key = format!(
+            "{}cc-switch-20240101_000000.ccbak",
+            key_prefix(&config(true))
+        );
+        assert_eq!(
+            object_url(&config(true), &key, &[]).unwrap().as_str(),
+            "https://s3.example.com/my-bucket/cc-switch/cc-switch-20240101_000000.ccbak"
+        );
+        assert_eq!(
+            object_url(&config(false), &key, &[]).unwrap().as_str(),
+            "https://my-bucket.s3.example.com/cc-switch/cc-switch-20240101_000000.ccbak"
+        );
+    }
+
+    #[test]
+    fn canonical_query_is_sorted_and_encoded() {
+        let pairs = vec![
+            ("prefix".to_string(), "cc switch/".to_string()),
+            ("list-type".to_string(), "2".to_string()),
+        ];
+        assert_eq!(canonical_query(&pairs), "list-type=2&prefix=cc%20switch%2F");
+    }
+
+    #[test]
+    fn parse_list_objects_extracts_entries() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult>
+  <Contents><Key>cc-switch/a.ccbak</Key><LastModified>2024-01-01T00:00:00.000Z</LastModified><Size>42</Size></Contents>
+  <Contents><Key>cc-switch/b&amp;c.ccbak</Key><Size>7</Size></Contents>
+</ListBucketResult>"#;
+        let entries = parse_list_objects(xml);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, "cc-switch/a.ccbak");
+        assert_eq!(entries[0].size, 42);
+        assert_eq!(entries[1].key, "cc-switch/b&c.ccbak");
+    }
+}
@@ -0,0 +1,227 @@
+//! 供应商密钥的读取与复制
+//!
+//! 前端列表中的密钥默认不回显；需要原文时通过这里按字段读取，复制到剪贴板后按设置
+//! 自动清除，避免密钥长期留在剪贴板管理器的历史中。
+
+#[cfg(feature = "gui")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "gui")]
+use std::time::Duration;
+
+use serde_json::Value;
+#[cfg(feature = "gui")]
+use tauri::AppHandle;
+#[cfg(feature = "gui")]
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::store::AppState;
+
+/// 表示供应商主 API Key 的字段名（按应用类型自动定位）
+pub const API_KEY_FIELD: &str = "apiKey";
+
+/// 每次复制递增；定时清除前比对，避免清掉之后又复制的内容
+#[cfg(feature = "gui")]
+static COPY_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 读取供应商配置中的字段
+///
+/// `field` 可以是 `apiKey`、JSON Pointer（如 `/env/ANTHROPIC_AUTH_TOKEN`），
+/// 或 `env` / `auth` / `options` 下的键名。
+fn provider_field(app_type: &AppType, provider: &Provider, field: &str) -> Option<String> {
+    let settings = &provider.settings_config;
+    if field == API_KEY_FIELD {
+        return match app_type {
+            AppType::OpenCode => settings
+                .pointer("/options/apiKey")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            _ => get_adapter(app_type)
+                .extract_auth(provider)
+                .map(|auth| auth.api_key),
+        };
+    }
+    if field.starts_with('/') {
+        return settings
+            .pointer(field)
+            .and_then(Value::as_str)
+            .map(str::to_string);
+    }
+    ["env", "auth", "options"]
+        .iter()
+        .find_map(|section| settings.get(*section)?.get(field)?.as_str())
+        .map(str::to_string)
+}
+
+/// 各应用主 API Key 可能所在的位置（按优先级），第一个为新建时的默认位置
+fn api_key_pointers(app_type: &AppType) -> &'static [&'static str] {
+    match app_type {
+        AppType::Claude => &[
+            "/env/ANTHROPIC_AUTH_TOKEN",
+            "/env/ANTHROPIC_API_KEY",
+            "/env/OPENROUTER_API_KEY",
+            "/env/OPENAI_API_KEY",
+        ],
+        AppType::Codex => &["/auth/OPENAI_API_KEY", "/env/OPENAI_API_KEY"],
+        AppType::Gemini => &["/env/GEMINI_API_KEY"],
+        AppType::OpenCode => &["/options/apiKey"],
+    }
+}
+
+/// 替换供应商配置中的主 API Key（写入当前 Key 所在的字段）
+pub fn set_api_key(app_type: &AppType, provider: &mut Provider, key: &str) -> Result<(), AppError> {
+    let pointers = api_key_pointers(app_type);
+    let pointer = pointers
+        .iter()
+        .find(|p| {
+            provider
+                .settings_config
+                .pointer(p)
+                .and_then(Value::as_str)
+                .is_some_and(|v| !v.is_empty())
+        })
+        .unwrap_or(&pointers[0]);
+
+    let (section, name) = pointer[1..]
+        .split_once('/')
+        .expect("api key pointers have two segments");
+    let settings = provider
+        .settings_config
+        .as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput("供应商配置不是 JSON 对象".to_string()))?;
+    let section = settings
+        .entry(section)
+        .or_insert_with(|| Value::Object(Default::default()))
+        .as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput(format!("供应商配置中的 {section} 不是对象")))?;
+    section.insert(name.to_string(), Value::String(key.to_string()));
+    Ok(())
+}
+
+/// 读取供应商密钥原文
+pub fn provider_secret(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+    field: &str,
+) -> Result<String, AppError> {
+    let provider = state
+        .db
+        .get_provider_by_id(provider_id, app_type.as_str())?
+        .ok_or_else(|| AppError::Message(format!("供应商 {provider_id} 不存在")))?;
+    provider_field(app_type, &provider, field)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| AppError::InvalidInput(format!("供应商 {provider_id} 未配置字段 {field}")))
+}
+
+/// 复制文本到剪贴板，并在 `clear_after_secs` 秒后清除（0 表示不清除）
+///
+/// 仅当剪贴板内容仍是这次复制的文本时才清除。
+#[cfg(feature = "gui")]
+pub fn copy_with_auto_clear(
+    app: &AppHandle,
+    text: String,
+    clear_after_secs: u32,
+) -> Result<(), AppError> {
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| AppError::Message(format!("写入剪贴板失败: {e}")))?;
+    let generation = COPY_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    if clear_after_secs == 0 {
+        return Ok(());
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(u64::from(clear_after_secs))).await;
+        if COPY_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let clipboard = app.clipboard();
+        if clipboard.read_text().ok().as_deref() == Some(text.as_str()) {
+            if let Err(e) = clipboard.write_text(String::new()) {
+                log::warn!("清除剪贴板失败: {e}");
+            } else {
+                log::info!("已清除剪贴板中的密钥");
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolves_fields_by_name_pointer_and_api_key() {
+        let provider = Provider::with_id(
+            "p1".to_string(),
+            "P1".to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_AUTH_TOKEN": "sk-claude",
+                    "ANTHROPIC_BASE_URL": "https://api.example.com"
+                }
+            }),
+            None,
+        );
+        assert_eq!(
+            provider_field(&AppType::Claude, &provider, API_KEY_FIELD).as_deref(),
+            Some("sk-claude")
+        );
+        assert_eq!(
+            provider_field(&AppType::Claude, &provider, "ANTHROPIC_BASE_URL").as_deref(),
+            Some("https://api.example.com")
+        );
+        assert_eq!(
+            provider_field(&AppType::Claude, &provider, "/env/ANTHROPIC_AUTH_TOKEN").as_deref(),
+            Some("sk-claude")
+        );
+        assert!(provider_field(&AppType::Claude, &provider, "missing").is_none());
+
+        let opencode = Provider::with_id(
+            "o1".to_string(),
+            "O1".to_string(),
+            json!({ "npm": "@ai-sdk/openai-compatible", "options": { "apiKey": "sk-oc" } }),
+            None,
+        );
+        assert_eq!(
+            provider_field(&AppType::OpenCode, &opencode, API_KEY_FIELD).as_deref(),
+            Some("sk-oc")
+        );
+    }
+
+    #[test]
+    fn set_api_key_replaces_the_field_in_use() {
+        let mut provider = Provider::with_id(
+            "p1".to_string(),
+            "P1".to_string(),
+            json!({ "env": { "ANTHROPIC_API_KEY": "sk-old", "ANTHROPIC_BASE_URL": "x" } }),
+            None,
+        );
+        set_api_key(&AppType::Claude, &mut provider, "sk-new").unwrap();
+        assert_eq!(
+            provider.settings_config["env"]["ANTHROPIC_API_KEY"],
+            "sk-new"
+        );
+        assert!(provider.settings_config["env"]
+            .get("ANTHROPIC_AUTH_TOKEN")
+            .is_none());
+
+        let mut codex = Provider::with_id(
+            "c1".to_string(),
+            "C1".to_string(),
+            json!({ "config": "" }),
+            None,
+        );
+        set_api_key(&AppType::Codex, &mut codex, "sk-codex").unwrap();
+        assert_eq!(codex.settings_config["auth"]["OPENAI_API_KEY"], "sk-codex");
+    }
+}
@@ -0,0 +1,535 @@
+//! 会话浏览
+//!
+//! 列出 Claude Code（`<claude 配置目录>/projects/<项目>/<会话 ID>.jsonl`）与 Codex
+//! （`<codex 配置目录>/sessions/<年>/<月>/<日>/rollout-*.jsonl`）的本地会话日志，解析每个
+//! 会话的标题、工作目录、起止时间、token 用量与模型，并按切换记录判断会话期间使用的
+//! 供应商，方便查看哪些会话跑在哪个端点上。token 用量的解析规则与
+//! [`super::session_usage`] 相同。
+//!
+//! 标题优先取 Claude Code 生成的摘要（`summary`），否则取第一条用户消息；Codex 会话中
+//! 没有用户消息时取 `history.jsonl` 中该会话的第一条输入。每次只解析最近修改的若干个
+//! 会话，避免读取全部历史；Claude Code 子代理的日志不单独列出。
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::{Database, SessionUsageCursor};
+use crate::error::AppError;
+use crate::services::session_usage::{
+    collect_logs, log_dir, parse_claude_line, parse_codex_line, parse_timestamp, provider_at,
+    UsageEntry,
+};
+
+/// 默认列出的会话数
+pub const DEFAULT_LIMIT: usize = 50;
+
+/// 标题的最大字符数
+const TITLE_MAX_CHARS: usize = 80;
+
+/// 会话期间使用的供应商
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionProvider {
+    /// 为空表示无法判断供应商（早于第一次切换记录）
+    pub provider_id: String,
+    /// 已删除的供应商为空
+    pub provider_name: String,
+    /// 该供应商的回复条数
+    pub message_count: u64,
+}
+
+/// 会话摘要
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub app_type: String,
+    pub session_id: String,
+    pub title: String,
+    /// 会话的工作目录
+    pub project: Option<String>,
+    /// 日志文件路径
+    pub path: String,
+    /// 毫秒时间戳
+    pub started_at: Option<i64>,
+    pub updated_at: Option<i64>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// 回复条数
+    pub message_count: u64,
+    /// 使用过的模型（按首次使用的先后）
+    pub models: Vec<String>,
+    /// 使用过的供应商（按首次使用的先后）
+    pub providers: Vec<SessionProvider>,
+}
+
+/// 解析中的会话
+#[derive(Debug, Default)]
+struct SessionDraft {
+    session_id: Option<String>,
+    summary: Option<String>,
+    first_prompt: Option<String>,
+    project: Option<String>,
+    started_at: Option<i64>,
+    updated_at: Option<i64>,
+    entries: Vec<UsageEntry>,
+}
+
+impl SessionDraft {
+    fn touch(&mut self, line: &Value) {
+        if let Some((timestamp, _)) = parse_timestamp(line) {
+            self.started_at = Some(self.started_at.map_or(timestamp, |at| at.min(timestamp)));
+            self.updated_at = Some(self.updated_at.map_or(timestamp, |at| at.max(timestamp)));
+        }
+    }
+
+    fn finish(self, app_type: &AppType, path: &Path, timeline: &[(i64, String)]) -> SessionSummary {
+        let session_id = self.session_id.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let mut summary = SessionSummary {
+            app_type: app_type.as_str().to_string(),
+            session_id,
+            title: self.summary.or(self.first_prompt).unwrap_or_default(),
+            project: self.project,
+            path: path.to_string_lossy().into_owned(),
+            started_at: self.started_at,
+            updated_at: self.updated_at,
+            ..SessionSummary::default()
+        };
+        for entry in &self.entries {
+            summary.input_tokens += entry.input_tokens;
+            summary.output_tokens += entry.output_tokens;
+            summary.cache_read_tokens += entry.cache_read_tokens;
+            summary.cache_creation_tokens += entry.cache_creation_tokens;
+            summary.message_count += 1;
+            if !entry.model.is_empty() && !summary.models.contains(&entry.model) {
+                summary.models.push(entry.model.clone());
+            }
+            let provider_id = provider_at(timeline, entry.timestamp);
+            match summary
+                .providers
+                .iter_mut()
+                .find(|provider| provider.provider_id == provider_id)
+            {
+                Some(provider) => provider.message_count += 1,
+                None => summary.providers.push(SessionProvider {
+                    provider_id: provider_id.to_string(),
+                    provider_name: String::new(),
+                    message_count: 1,
+                }),
+            }
+        }
+        summary
+    }
+}
+
+/// 消息内容中的文本（字符串或 `text` 片段）
+fn message_text(content: &Value) -> Option<String> {
+    match content {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(items) => {
+            let texts = items
+                .iter()
+                .filter_map(|item| item.get("text")?.as_str())
+                .collect::<Vec<_>>();
+            (!texts.is_empty()).then(|| texts.join(" "))
+        }
+        _ => None,
+    }
+}
+
+/// 把用户消息整理为标题；命令输出、系统提示等以 `<` 开头的内容不作为标题
+fn title_from(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() || text.starts_with('<') {
+        return None;
+    }
+    Some(text.chars().take(TITLE_MAX_CHARS).collect())
+}
+
+fn str_field(line: &Value, key: &str) -> Option<String> {
+    line.get(key)?.as_str().map(str::to_string)
+}
+
+/// 解析 Claude Code 会话日志
+fn parse_claude_session(content: &str) -> SessionDraft {
+    let mut draft = SessionDraft::default();
+    let mut seen = HashSet::new();
+    for line in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let line_type = line.get("type").and_then(Value::as_str).unwrap_or_default();
+        if line_type == "summary" {
+            draft.summary = str_field(&line, "summary").or(draft.summary);
+            continue;
+        }
+        draft.touch(&line);
+        if draft.session_id.is_none() {
+            draft.session_id = str_field(&line, "sessionId");
+        }
+        if draft.project.is_none() {
+            draft.project = str_field(&line, "cwd");
+        }
+        let is_meta = ["isMeta", "isCompactSummary"]
+            .iter()
+            .any(|key| line.get(*key).and_then(Value::as_bool) == Some(true));
+        if line_type == "user" && draft.first_prompt.is_none() && !is_meta {
+            draft.first_prompt = line
+                .pointer("/message/content")
+                .and_then(message_text)
+                .and_then(|text| title_from(&text));
+        }
+        if let Some(entry) = parse_claude_line(&line, &mut seen) {
+            draft.entries.push(entry);
+        }
+    }
+    draft
+}
+
+/// 解析 Codex 会话日志
+fn parse_codex_session(content: &str) -> SessionDraft {
+    let mut draft = SessionDraft::default();
+    let mut cursor = SessionUsageCursor::default();
+    for line in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        draft.touch(&line);
+        let payload = line.get("payload").unwrap_or(&Value::Null);
+        match line.get("type").and_then(Value::as_str).unwrap_or_default() {
+            "session_meta" => {
+                draft.session_id = str_field(payload, "id").or(draft.session_id);
+                draft.project = str_field(payload, "cwd").or(draft.project);
+            }
+            "turn_context" if draft.project.is_none() => {
+                draft.project = str_field(payload, "cwd");
+            }
+            // 用户输入；环境信息、项目说明等以 `<` 开头的内容由 title_from 跳过
+            "event_msg"
+                if draft.first_prompt.is_none()
+                    && payload.get("type").and_then(Value::as_str) == Some("user_message") =>
+            {
+                draft.first_prompt = payload
+                    .get("message")
+                    .and_then(message_text)
+                    .and_then(|text| title_from(&text));
+            }
+            "response_item"
+                if draft.first_prompt.is_none()
+                    && payload.get("role").and_then(Value::as_str) == Some("user") =>
+            {
+                draft.first_prompt = payload
+                    .get("content")
+                    .and_then(message_text)
+                    .and_then(|text| title_from(&text));
+            }
+            _ => {}
+        }
+        if let Some(entry) = parse_codex_line(&line, &mut cursor) {
+            draft.entries.push(entry);
+        }
+    }
+    draft
+}
+
+/// Codex 输入历史（`history.jsonl`）中每个会话的第一条输入
+fn codex_history_titles() -> HashMap<String, String> {
+    let path = crate::codex_config::get_codex_config_dir().join("history.jsonl");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    parse_codex_history(&content)
+}
+
+fn parse_codex_history(content: &str) -> HashMap<String, String> {
+    let mut titles = HashMap::new();
+    for line in content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+    {
+        let (Some(session_id), Some(title)) = (
+            str_field(&line, "session_id"),
+            line.get("text")
+                .and_then(Value::as_str)
+                .and_then(title_from),
+        ) else {
+            continue;
+        };
+        titles.entry(session_id).or_insert(title);
+    }
+    titles
+}
+
+/// 支持浏览会话的应用
+fn session_apps(app_type: Option<AppType>) -> Result<Vec<AppType>, AppError> {
+    match app_type {
+        None => Ok(vec![AppType::Claude, AppType::Codex]),
+        Some(AppType::Claude) => Ok(vec![AppType::Claude]),
+        Some(AppType::Codex) => Ok(vec![AppType::Codex]),
+        Some(other) => Err(AppError::InvalidInput(format!(
+            "{} 暂不支持浏览会话",
+            other.as_str()
+        ))),
+    }
+}
+
+/// 应用的会话日志文件（不含 Claude Code 子代理日志）
+fn session_files(app_type: &AppType) -> Vec<PathBuf> {
+    let root = log_dir(app_type);
+    let mut files = Vec::new();
+    collect_logs(&root, &mut files);
+    // Claude Code 的会话日志直接位于项目目录下；Codex 按日期分目录
+    if *app_type == AppType::Claude {
+        files.retain(|path| path.parent().and_then(Path::parent) == Some(root.as_path()));
+    }
+    files
+}
+
+/// 列出最近的会话（按最后修改时间倒序），`app_type` 为空时列出所有支持的应用
+pub fn list_sessions(
+    db: &Database,
+    app_type: Option<AppType>,
+    limit: usize,
+) -> Result<Vec<SessionSummary>, AppError> {
+    let apps = session_apps(app_type)?;
+    let mut timelines = HashMap::new();
+    let mut files = Vec::new();
+    for app_type in apps {
+        timelines.insert(
+            app_type.as_str().to_string(),
+            db.get_provider_switch_timeline(app_type.as_str())?,
+        );
+        for path in session_files(&app_type) {
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((modified, app_type.clone(), path));
+        }
+    }
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files.truncate(limit.max(1));
+
+    let mut history_titles = None;
+    let mut names: HashMap<(String, String), String> = HashMap::new();
+    let mut sessions = Vec::new();
+    for (_, app_type, path) in files {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("读取会话日志 {} 失败: {e}", path.display());
+                continue;
+            }
+        };
+        let app = app_type.as_str();
+        let draft = match app_type {
+            AppType::Codex => {
+                let mut draft = parse_codex_session(&content);
+                if draft.first_prompt.is_none() {
+                    let titles = history_titles.get_or_insert_with(codex_history_titles);
+                    draft.first_prompt = draft
+                        .session_id
+                        .as_ref()
+                        .and_then(|id| titles.get(id))
+                        .cloned();
+                }
+                draft
+            }
+            _ => parse_claude_session(&content),
+        };
+        let mut session = draft.finish(&app_type, &path, &timelines[app]);
+        for provider in &mut session.providers {
+            if provider.provider_id.is_empty() {
+                continue;
+            }
+            let key = (app.to_string(), provider.provider_id.clone());
+            provider.provider_name = match names.get(&key) {
+                Some(name) => name.clone(),
+                None => {
+                    let name = db
+                        .get_provider_by_id(&provider.provider_id, app)?
+                        .map(|provider| provider.name)
+                        .unwrap_or_default();
+                    names.insert(key, name.clone());
+                    name
+                }
+            };
+        }
+        sessions.push(session);
+    }
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn jsonl(lines: &[Value]) -> String {
+        lines
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn reply(id: &str, timestamp: &str, model: &str) -> Value {
+        json!({
+            "type": "assistant",
+            "sessionId": "s1",
+            "timestamp": timestamp,
+            "message": {
+                "id": id,
+                "model": model,
+                "usage": { "input_tokens": 10, "output_tokens": 20 }
+            }
+        })
+    }
+
+    #[test]
+    fn summarizes_claude_sessions_with_providers() {
+        let content = jsonl(&[
+            json!({
+                "type": "user",
+                "isMeta": true,
+                "sessionId": "s1",
+                "cwd": "/work/app",
+                "timestamp": "2025-06-01T12:00:00.000Z",
+                "message": { "role": "user", "content": "<command-name>/clear</command-name>" }
+            }),
+            json!({
+                "type": "user",
+                "sessionId": "s1",
+                "cwd": "/work/app",
+                "timestamp": "2025-06-01T12:00:01.000Z",
+                "message": { "role": "user", "content": [{ "type": "text", "text": "Fix the\n  login bug" }] }
+            }),
+            reply("m1", "2025-06-01T12:00:05.000Z", "claude-sonnet-4"),
+            reply("m1", "2025-06-01T12:00:05.000Z", "claude-sonnet-4"),
+            reply("m2", "2025-06-01T12:10:00.000Z", "claude-opus-4"),
+        ]);
+        let draft = parse_claude_session(&content);
+        assert_eq!(draft.first_prompt.as_deref(), Some("Fix the login bug"));
+
+        // 12:05 切换到 b
+        let timeline = vec![
+            (1_748_779_000_000, "a".to_string()),
+            (1_748_779_500_000, "b".to_string()),
+        ];
+        let session = draft.finish(&AppType::Claude, Path::new("/p/s1.jsonl"), &timeline);
+        assert_eq!(session.session_id, "s1");
+        assert_eq!(session.title, "Fix the login bug");
+        assert_eq!(session.project.as_deref(), Some("/work/app"));
+        assert_eq!(session.started_at, Some(1_748_779_200_000));
+        assert_eq!(session.updated_at, Some(1_748_779_800_000));
+        assert_eq!((session.message_count, session.output_tokens), (2, 40));
+        assert_eq!(session.models, vec!["claude-sonnet-4", "claude-opus-4"]);
+        let providers = session
+            .providers
+            .iter()
+            .map(|provider| (provider.provider_id.as_str(), provider.message_count))
+            .collect::<Vec<_>>();
+        assert_eq!(providers, vec![("a", 1), ("b", 1)]);
+    }
+
+    #[test]
+    fn summarizes_codex_sessions() {
+        let content = jsonl(&[
+            json!({
+                "timestamp": "2025-09-01T08:00:00.000Z",
+                "type": "session_meta",
+                "payload": { "id": "c1", "cwd": "/work/api", "originator": "codex_cli_rs" }
+            }),
+            json!({
+                "timestamp": "2025-09-01T08:00:00.100Z",
+                "type": "response_item",
+                "payload": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": "<environment_context>\n</environment_context>" }]
+                }
+            }),
+            json!({
+                "timestamp": "2025-09-01T08:00:01.000Z",
+                "type": "event_msg",
+                "payload": { "type": "user_message", "message": "Add retries", "kind": "plain" }
+            }),
+            json!({
+                "timestamp": "2025-09-01T08:00:01.500Z",
+                "type": "turn_context",
+                "payload": { "cwd": "/work/api", "model": "gpt-5-codex" }
+            }),
+            json!({
+                "timestamp": "2025-09-01T08:00:05.000Z",
+                "type": "event_msg",
+                "payload": {
+                    "type": "token_count",
+                    "info": {
+                        "total_token_usage": { "total_tokens": 1300 },
+                        "last_token_usage": {
+                            "input_tokens": 1000,
+                            "cached_input_tokens": 800,
+                            "output_tokens": 300
+                        }
+                    }
+                }
+            }),
+        ]);
+        let timeline = vec![(0, "relay".to_string())];
+        let session = parse_codex_session(&content).finish(
+            &AppType::Codex,
+            Path::new("/s/rollout-2025-09-01T08-00-00-c1.jsonl"),
+            &timeline,
+        );
+        assert_eq!(session.session_id, "c1");
+        assert_eq!(session.title, "Add retries");
+        assert_eq!(session.project.as_deref(), Some("/work/api"));
+        assert_eq!(
+            (
+                session.input_tokens,
+                session.cache_read_tokens,
+                session.output_tokens
+            ),
+            (200, 800, 300)
+        );
+        assert_eq!(session.models, vec!["gpt-5-codex"]);
+        assert_eq!(session.providers[0].provider_id, "relay");
+    }
+
+    #[test]
+    fn reads_first_prompt_per_session_from_codex_history() {
+        let titles = parse_codex_history(&jsonl(&[
+            json!({ "session_id": "c1", "ts": 1, "text": "first" }),
+            json!({ "session_id": "c1", "ts": 2, "text": "second" }),
+            json!({ "session_id": "c2", "ts": 3, "text": "other" }),
+        ]));
+        assert_eq!(titles.get("c1").map(String::as_str), Some("first"));
+        assert_eq!(titles.len(), 2);
+    }
+
+    #[test]
+    fn prefers_generated_summary_as_title() {
+        let content = jsonl(&[
+            json!({ "type": "summary", "summary": "Login bug fix", "leafUuid": "u1" }),
+            json!({
+                "type": "user",
+                "timestamp": "2025-06-01T12:00:01.000Z",
+                "message": { "role": "user", "content": "Fix the login bug" }
+            }),
+        ]);
+        let session =
+            parse_claude_session(&content).finish(&AppType::Claude, Path::new("/p/s2.jsonl"), &[]);
+        assert_eq!(session.title, "Login bug fix");
+        assert_eq!(session.session_id, "s2");
+        assert!(session.providers.is_empty());
+    }
+}
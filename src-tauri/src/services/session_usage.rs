@@ -0,0 +1,450 @@
+//! 会话日志用量统计
+//!
+//! 读取 Claude Code（`<claude 配置目录>/projects/**/*.jsonl`）与 Codex
+//! （`<codex 配置目录>/sessions/**/*.jsonl`）的本地会话日志，把每条回复的 token 用量归属到
+//! 当时正在使用的供应商（按切换记录 `provider_switch_history` 判断），按供应商、日期与模型
+//! 聚合保存到数据库：
+//!
+//! - 每个日志文件记录已读取的位置，之后只统计新增的行；文件变短（被重写）时从头统计；
+//! - Claude Code 同一条回复可能分多行写入，按消息 ID 去重；
+//! - Codex 的用量来自 `token_count` 事件，模型取自最近的 `turn_context`，累计用量未变化
+//!   的重复事件跳过；
+//! - 早于第一次切换记录的用量无法判断供应商，归入空供应商 ID（不属于任何供应商）。
+//!
+//! 不经过代理的请求也能统计，但只覆盖这两个应用；日期按本地时区划分。
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::database::{Database, SessionUsageCursor};
+use crate::error::AppError;
+
+/// 统计范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageRange {
+    Today,
+    /// 最近 7 天（含今天）
+    Week,
+    /// 最近 30 天（含今天）
+    #[default]
+    Month,
+    All,
+}
+
+impl UsageRange {
+    /// 起始日期（`YYYY-MM-DD`），`All` 为 None
+    pub fn since(self) -> Option<String> {
+        let days = match self {
+            UsageRange::Today => 0,
+            UsageRange::Week => 6,
+            UsageRange::Month => 29,
+            UsageRange::All => return None,
+        };
+        Some(
+            (Local::now() - Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string(),
+        )
+    }
+}
+
+/// 按供应商、日期与模型聚合的用量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsageRow {
+    pub app_type: String,
+    pub provider_id: String,
+    /// 本地日期 `YYYY-MM-DD`
+    pub date: String,
+    pub model: String,
+    /// 不含缓存命中的输入 token
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// 回复条数
+    pub message_count: u64,
+}
+
+/// 供应商在某个范围内的用量
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider_id: String,
+    pub range: UsageRange,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub message_count: u64,
+    /// 按日期、模型的明细
+    pub rows: Vec<SessionUsageRow>,
+}
+
+/// 日志中的一条用量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UsageEntry {
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    pub date: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+}
+
+fn token(value: &Value, key: &str) -> u64 {
+    value.get(key).and_then(Value::as_u64).unwrap_or_default()
+}
+
+/// 解析时间戳，返回 (毫秒时间戳, 本地日期)
+pub(crate) fn parse_timestamp(line: &Value) -> Option<(i64, String)> {
+    let time = DateTime::parse_from_rfc3339(line.get("timestamp")?.as_str()?).ok()?;
+    let date = time.with_timezone(&Local).format("%Y-%m-%d").to_string();
+    Some((time.timestamp_millis(), date))
+}
+
+/// 解析 Claude Code 日志的一行；`seen` 用于按消息 ID 去重
+pub(crate) fn parse_claude_line(line: &Value, seen: &mut HashSet<String>) -> Option<UsageEntry> {
+    if line.get("type")?.as_str()? != "assistant" {
+        return None;
+    }
+    let message = line.get("message")?;
+    let usage = message.get("usage")?;
+    let model = message.get("model")?.as_str()?;
+    // 本地生成的提示（如 API 错误）没有实际请求
+    if model == "<synthetic>" {
+        return None;
+    }
+    if let Some(id) = message.get("id").and_then(Value::as_str) {
+        let request_id = line
+            .get("requestId")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !seen.insert(format!("{id}:{request_id}")) {
+            return None;
+        }
+    }
+
+    let (timestamp, date) = parse_timestamp(line)?;
+    Some(UsageEntry {
+        timestamp,
+        date,
+        model: model.to_string(),
+        input_tokens: token(usage, "input_tokens"),
+        output_tokens: token(usage, "output_tokens"),
+        cache_read_tokens: token(usage, "cache_read_input_tokens"),
+        cache_creation_tokens: token(usage, "cache_creation_input_tokens"),
+    })
+}
+
+/// 解析 Codex 日志的一行；`cursor` 携带最近的模型与累计用量
+pub(crate) fn parse_codex_line(
+    line: &Value,
+    cursor: &mut SessionUsageCursor,
+) -> Option<UsageEntry> {
+    let payload = line.get("payload")?;
+    match line.get("type")?.as_str()? {
+        "turn_context" => {
+            if let Some(model) = payload.get("model").and_then(Value::as_str) {
+                cursor.model = Some(model.to_string());
+            }
+            None
+        }
+        "event_msg" if payload.get("type")?.as_str()? == "token_count" => {
+            let info = payload.get("info")?;
+            let usage = info.get("last_token_usage")?;
+            let total = info
+                .get("total_token_usage")
+                .and_then(|total| total.get("total_tokens"))
+                .and_then(Value::as_u64);
+            if total.is_some() && total == cursor.last_total {
+                return None;
+            }
+            cursor.last_total = total;
+
+            let (timestamp, date) = parse_timestamp(line)?;
+            // OpenAI 的 input_tokens 包含缓存命中部分
+            let cached = token(usage, "cached_input_tokens");
+            Some(UsageEntry {
+                timestamp,
+                date,
+                model: cursor.model.clone().unwrap_or_default(),
+                input_tokens: token(usage, "input_tokens").saturating_sub(cached),
+                output_tokens: token(usage, "output_tokens"),
+                cache_read_tokens: cached,
+                cache_creation_tokens: 0,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// 时间点正在使用的供应商（切换时间线按时间先后排列）
+pub(crate) fn provider_at(timeline: &[(i64, String)], timestamp: i64) -> &str {
+    let index = timeline.partition_point(|(switched_at, _)| *switched_at <= timestamp);
+    match index {
+        0 => "",
+        index => &timeline[index - 1].1,
+    }
+}
+
+/// 按供应商、日期与模型聚合
+fn aggregate(
+    app_type: &AppType,
+    timeline: &[(i64, String)],
+    entries: Vec<UsageEntry>,
+) -> Vec<SessionUsageRow> {
+    let mut rows: BTreeMap<(String, String, String), SessionUsageRow> = BTreeMap::new();
+    for entry in entries {
+        let provider_id = provider_at(timeline, entry.timestamp).to_string();
+        let row = rows
+            .entry((provider_id.clone(), entry.date.clone(), entry.model.clone()))
+            .or_insert_with(|| SessionUsageRow {
+                app_type: app_type.as_str().to_string(),
+                provider_id,
+                date: entry.date,
+                model: entry.model,
+                ..SessionUsageRow::default()
+            });
+        row.input_tokens += entry.input_tokens;
+        row.output_tokens += entry.output_tokens;
+        row.cache_read_tokens += entry.cache_read_tokens;
+        row.cache_creation_tokens += entry.cache_creation_tokens;
+        row.message_count += 1;
+    }
+    rows.into_values().collect()
+}
+
+/// 递归列出目录下的 `.jsonl` 文件
+pub(crate) fn collect_logs(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_logs(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+}
+
+pub(crate) fn log_dir(app_type: &AppType) -> PathBuf {
+    match app_type {
+        AppType::Codex => crate::codex_config::get_codex_config_dir().join("sessions"),
+        _ => crate::config::get_claude_config_dir().join("projects"),
+    }
+}
+
+/// 读取 `offset` 之后完整的行，返回 (内容, 新位置)；最后一行未写完时留到下次
+pub(crate) fn read_new_lines(path: &Path, offset: u64) -> Result<(String, u64), AppError> {
+    let mut file = File::open(path).map_err(|e| AppError::io(path, e))?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| AppError::io(path, e))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)
+        .map_err(|e| AppError::io(path, e))?;
+    let complete = buffer
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |index| index + 1);
+    buffer.truncate(complete);
+    Ok((
+        String::from_utf8_lossy(&buffer).into_owned(),
+        offset + complete as u64,
+    ))
+}
+
+fn sync_file(
+    db: &Database,
+    app_type: &AppType,
+    timeline: &[(i64, String)],
+    path: &Path,
+) -> Result<(), AppError> {
+    let key = path.to_string_lossy();
+    let len = std::fs::metadata(path)
+        .map_err(|e| AppError::io(path, e))?
+        .len();
+    let mut cursor = db.get_session_usage_cursor(&key)?.unwrap_or_default();
+    if len < cursor.offset {
+        cursor = SessionUsageCursor::default();
+    }
+    if len == cursor.offset {
+        return Ok(());
+    }
+
+    let (content, offset) = read_new_lines(path, cursor.offset)?;
+    if offset == cursor.offset {
+        return Ok(());
+    }
+    let mut seen = HashSet::new();
+    let entries = content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|line| match app_type {
+            AppType::Codex => parse_codex_line(&line, &mut cursor),
+            _ => parse_claude_line(&line, &mut seen),
+        })
+        .collect();
+    cursor.offset = offset;
+    db.add_session_usage(&key, &cursor, &aggregate(app_type, timeline, entries))
+}
+
+/// 统计新增的会话日志
+pub fn sync(db: &Database) -> Result<(), AppError> {
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let timeline = db.get_provider_switch_timeline(app_type.as_str())?;
+        let mut files = Vec::new();
+        collect_logs(&log_dir(&app_type), &mut files);
+        for path in files {
+            // 单个文件读取失败（如正在被删除）不影响其他文件
+            if let Err(e) = sync_file(db, &app_type, &timeline, &path) {
+                log::warn!("统计会话日志 {} 失败: {e}", path.display());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 供应商在指定范围内的用量（先统计新增的会话日志）
+pub fn get_usage(
+    db: &Database,
+    provider_id: &str,
+    range: UsageRange,
+) -> Result<ProviderUsage, AppError> {
+    sync(db)?;
+    let rows = db.get_session_usage(Some(provider_id), range.since().as_deref())?;
+    let mut usage = ProviderUsage {
+        provider_id: provider_id.to_string(),
+        range,
+        ..ProviderUsage::default()
+    };
+    for row in &rows {
+        usage.input_tokens += row.input_tokens;
+        usage.output_tokens += row.output_tokens;
+        usage.cache_read_tokens += row.cache_read_tokens;
+        usage.cache_creation_tokens += row.cache_creation_tokens;
+        usage.message_count += row.message_count;
+    }
+    usage.rows = rows;
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_claude_lines_once_per_message() {
+        let line = json!({
+            "type": "assistant",
+            "timestamp": "2025-06-01T12:00:00.000Z",
+            "requestId": "req_1",
+            "message": {
+                "id": "msg_1",
+                "model": "claude-sonnet-4",
+                "usage": {
+                    "input_tokens": 10,
+                    "output_tokens": 50,
+                    "cache_read_input_tokens": 2000,
+                    "cache_creation_input_tokens": 100
+                }
+            }
+        });
+        let mut seen = HashSet::new();
+        let entry = parse_claude_line(&line, &mut seen).unwrap();
+        assert_eq!(entry.model, "claude-sonnet-4");
+        assert_eq!(entry.timestamp, 1_748_779_200_000);
+        assert_eq!(
+            (
+                entry.input_tokens,
+                entry.output_tokens,
+                entry.cache_read_tokens,
+                entry.cache_creation_tokens
+            ),
+            (10, 50, 2000, 100)
+        );
+        assert!(parse_claude_line(&line, &mut seen).is_none());
+        assert!(parse_claude_line(&json!({"type": "user"}), &mut seen).is_none());
+    }
+
+    #[test]
+    fn parses_codex_token_counts_with_context_model() {
+        let mut cursor = SessionUsageCursor::default();
+        let context = json!({
+            "timestamp": "2025-09-01T08:00:00.000Z",
+            "type": "turn_context",
+            "payload": { "model": "gpt-5-codex" }
+        });
+        assert!(parse_codex_line(&context, &mut cursor).is_none());
+
+        let count = json!({
+            "timestamp": "2025-09-01T08:00:05.000Z",
+            "type": "event_msg",
+            "payload": {
+                "type": "token_count",
+                "info": {
+                    "total_token_usage": { "total_tokens": 1300 },
+                    "last_token_usage": {
+                        "input_tokens": 1000,
+                        "cached_input_tokens": 800,
+                        "output_tokens": 300
+                    }
+                }
+            }
+        });
+        let entry = parse_codex_line(&count, &mut cursor).unwrap();
+        assert_eq!(entry.model, "gpt-5-codex");
+        assert_eq!(
+            (
+                entry.input_tokens,
+                entry.cache_read_tokens,
+                entry.output_tokens
+            ),
+            (200, 800, 300)
+        );
+        // 累计用量不变的重复事件
+        assert!(parse_codex_line(&count, &mut cursor).is_none());
+    }
+
+    #[test]
+    fn attributes_usage_to_the_provider_active_at_the_time() {
+        let timeline = vec![(1_000, "a".to_string()), (2_000, "b".to_string())];
+        assert_eq!(provider_at(&timeline, 999), "");
+        assert_eq!(provider_at(&timeline, 1_000), "a");
+        assert_eq!(provider_at(&timeline, 1_999), "a");
+        assert_eq!(provider_at(&timeline, 5_000), "b");
+
+        let entry = |timestamp, output_tokens| UsageEntry {
+            timestamp,
+            date: "2025-06-01".to_string(),
+            model: "m".to_string(),
+            input_tokens: 1,
+            output_tokens,
+            cache_read_tokens: 0,
+            cache_creation_tokens: 0,
+        };
+        let rows = aggregate(
+            &AppType::Claude,
+            &timeline,
+            vec![entry(1_500, 10), entry(1_600, 20), entry(2_500, 5)],
+        );
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].provider_id, "a");
+        assert_eq!((rows[0].output_tokens, rows[0].message_count), (30, 2));
+        assert_eq!(rows[1].provider_id, "b");
+        assert_eq!(rows[1].app_type, "claude");
+    }
+}
@@ -0,0 +1,425 @@
+//! 设置诊断
+//!
+//! 检查设备级设置（目录覆盖等）中可能导致切换失败的问题，
+//! 返回结构化的诊断结果供前端展示可操作的警告。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::settings::{resolve_override_path, AppSettings};
+
+const ALL_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 诊断结果严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// 单条诊断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFinding {
+    /// 机器可读的问题代码，前端据此做国际化
+    pub code: String,
+    pub severity: FindingSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl SettingsFinding {
+    fn new(
+        code: &str,
+        severity: FindingSeverity,
+        app: Option<&AppType>,
+        path: Option<&Path>,
+        message: String,
+    ) -> Self {
+        Self {
+            code: code.to_string(),
+            severity,
+            app: app.map(|a| a.as_str().to_string()),
+            path: path.map(|p| p.to_string_lossy().to_string()),
+            message,
+        }
+    }
+}
+
+/// 各应用配置目录中的典型文件，用于判断目录是否属于目标应用
+pub(crate) fn expected_markers(app_type: &AppType) -> &'static [&'static str] {
+    match app_type {
+        AppType::Claude => &[
+            "settings.json",
+            "claude.json",
+            ".credentials.json",
+            "projects",
+        ],
+        AppType::Codex => &["auth.json", "config.toml", "sessions", "history.jsonl"],
+        AppType::Gemini => &[".env", "settings.json", "oauth_creds.json"],
+        AppType::OpenCode => &["opencode.json", "opencode.jsonc"],
+    }
+}
+
+/// 目录中是否存在任一典型文件
+pub(crate) fn looks_like_app_dir(app_type: &AppType, dir: &Path) -> bool {
+    expected_markers(app_type)
+        .iter()
+        .any(|marker| dir.join(marker).exists())
+}
+
+/// 诊断设置，返回全部发现的问题（无问题时为空列表）
+pub fn diagnose_settings(settings: &AppSettings) -> Vec<SettingsFinding> {
+    let mut findings = Vec::new();
+    let mut seen: HashMap<PathBuf, AppType> = HashMap::new();
+
+    for app_type in ALL_APPS.iter() {
+        let Some(raw) = settings.config_dir_override(app_type) else {
+            continue;
+        };
+        let dir = resolve_override_path(raw);
+
+        if let Some(other) = seen.get(&dir) {
+            findings.push(SettingsFinding::new(
+                "override_dir_shared",
+                FindingSeverity::Warning,
+                Some(app_type),
+                Some(&dir),
+                format!(
+                    "{} 与 {} 使用了同一个覆盖目录，切换时配置文件可能互相覆盖",
+                    app_type.as_str(),
+                    other.as_str()
+                ),
+            ));
+        } else {
+            seen.insert(dir.clone(), app_type.clone());
+        }
+
+        findings.extend(diagnose_override_dir(app_type, &dir));
+    }
+
+    findings.extend(diagnose_cloud_drive(
+        None,
+        &crate::config::get_app_config_dir(),
+        &["settings.json", "cc-switch.db"],
+    ));
+
+    findings
+}
+
+/// 检查目录是否位于网盘同步目录中，以及是否存在网盘生成的冲突副本
+fn diagnose_cloud_drive(
+    app_type: Option<&AppType>,
+    dir: &Path,
+    files: &[&str],
+) -> Vec<SettingsFinding> {
+    let Some(drive) = crate::cloud_drive::detect_cloud_drive(dir) else {
+        return Vec::new();
+    };
+
+    let mut findings = vec![SettingsFinding::new(
+        "cloud_drive_detected",
+        FindingSeverity::Warning,
+        app_type,
+        Some(dir),
+        format!(
+            "目录位于 {} 同步目录中，多台设备同时修改时可能产生冲突副本；已改用网盘安全的写入方式",
+            drive.display_name()
+        ),
+    )];
+
+    for file in files {
+        for copy in crate::cloud_drive::find_conflict_copies(dir, file) {
+            findings.push(SettingsFinding::new(
+                "cloud_drive_conflict_copy",
+                FindingSeverity::Warning,
+                app_type,
+                Some(&copy),
+                format!(
+                    "发现 {} 生成的 {file} 冲突副本，请确认后手动合并或删除",
+                    drive.display_name()
+                ),
+            ));
+        }
+    }
+
+    findings
+}
+
+/// 检查单个覆盖目录
+fn diagnose_override_dir(app_type: &AppType, dir: &Path) -> Vec<SettingsFinding> {
+    let mut findings = Vec::new();
+
+    if !dir.is_absolute() {
+        findings.push(SettingsFinding::new(
+            "override_dir_relative",
+            FindingSeverity::Warning,
+            Some(app_type),
+            Some(dir),
+            "覆盖目录是相对路径，实际位置取决于应用的启动目录".to_string(),
+        ));
+    }
+
+    let metadata = match fs::metadata(dir) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            findings.push(SettingsFinding::new(
+                "override_dir_missing",
+                FindingSeverity::Warning,
+                Some(app_type),
+                Some(dir),
+                "覆盖目录不存在，切换供应商时将自动创建".to_string(),
+            ));
+            return findings;
+        }
+        Err(e) => {
+            findings.push(SettingsFinding::new(
+                "override_dir_unreadable",
+                FindingSeverity::Error,
+                Some(app_type),
+                Some(dir),
+                format!("无法访问覆盖目录: {e}"),
+            ));
+            return findings;
+        }
+    };
+
+    if !metadata.is_dir() {
+        findings.push(SettingsFinding::new(
+            "override_dir_not_directory",
+            FindingSeverity::Error,
+            Some(app_type),
+            Some(dir),
+            "覆盖路径指向的是文件而不是目录".to_string(),
+        ));
+        return findings;
+    }
+
+    if let Err(e) = fs::read_dir(dir) {
+        findings.push(SettingsFinding::new(
+            "override_dir_unreadable",
+            FindingSeverity::Error,
+            Some(app_type),
+            Some(dir),
+            format!("无法读取覆盖目录: {e}"),
+        ));
+        return findings;
+    }
+
+    if metadata.permissions().readonly() {
+        findings.push(SettingsFinding::new(
+            "override_dir_readonly",
+            FindingSeverity::Error,
+            Some(app_type),
+            Some(dir),
+            "覆盖目录为只读，无法写入配置文件".to_string(),
+        ));
+    }
+
+    findings.extend(diagnose_cloud_drive(
+        Some(app_type),
+        dir,
+        expected_markers(app_type),
+    ));
+
+    if !looks_like_app_dir(app_type, dir) {
+        findings.push(SettingsFinding::new(
+            "override_dir_app_not_detected",
+            FindingSeverity::Info,
+            Some(app_type),
+            Some(dir),
+            format!(
+                "目录中未找到 {} 的典型配置文件（{}），请确认路径是否正确",
+                app_type.as_str(),
+                expected_markers(app_type).join(", ")
+            ),
+        ));
+    }
+
+    findings
+}
+
+/// 校验保存设置时新修改的目录覆盖
+///
+/// 仅检查与旧设置不同的目录；会在目录中创建并删除一个临时文件以确认写权限。
+#[cfg(any(feature = "gui", test))]
+pub fn validate_changed_overrides(old: &AppSettings, new: &AppSettings) -> Vec<SettingsFinding> {
+    ALL_APPS
+        .iter()
+        .filter_map(|app_type| {
+            let raw = new.config_dir_override(app_type)?;
+            if old.config_dir_override(app_type) == Some(raw) {
+                return None;
+            }
+            Some(validate_override_dir(app_type, &resolve_override_path(raw)))
+        })
+        .flatten()
+        .collect()
+}
+
+/// 校验单个目录覆盖：存在或可创建、可写、像目标应用的配置目录
+#[cfg(any(feature = "gui", test))]
+pub fn validate_override_dir(app_type: &AppType, dir: &Path) -> Vec<SettingsFinding> {
+    if !dir.exists() {
+        return match nearest_existing_ancestor(dir) {
+            Some(ancestor) if ancestor.is_dir() && probe_writable(&ancestor) => {
+                vec![SettingsFinding::new(
+                    "override_dir_missing",
+                    FindingSeverity::Warning,
+                    Some(app_type),
+                    Some(dir),
+                    "覆盖目录不存在，切换供应商时将自动创建".to_string(),
+                )]
+            }
+            _ => vec![SettingsFinding::new(
+                "override_dir_not_creatable",
+                FindingSeverity::Error,
+                Some(app_type),
+                Some(dir),
+                "覆盖目录不存在且无法创建，请检查路径或权限".to_string(),
+            )],
+        };
+    }
+
+    let mut findings = diagnose_override_dir(app_type, dir);
+    let blocked = findings
+        .iter()
+        .any(|f| f.severity == FindingSeverity::Error);
+    if !blocked && !probe_writable(dir) {
+        findings.push(SettingsFinding::new(
+            "override_dir_not_writable",
+            FindingSeverity::Error,
+            Some(app_type),
+            Some(dir),
+            "没有覆盖目录的写入权限".to_string(),
+        ));
+    }
+    findings
+}
+
+#[cfg(any(feature = "gui", test))]
+fn nearest_existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .skip(1)
+        .find(|p| !p.as_os_str().is_empty() && p.exists())
+        .map(Path::to_path_buf)
+}
+
+/// 通过创建临时文件确认目录可写
+#[cfg(any(feature = "gui", test))]
+fn probe_writable(dir: &Path) -> bool {
+    tempfile::Builder::new()
+        .prefix(".cc-switch-probe")
+        .tempfile_in(dir)
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn codes(findings: &[SettingsFinding]) -> Vec<&str> {
+        findings.iter().map(|f| f.code.as_str()).collect()
+    }
+
+    #[test]
+    fn default_settings_have_no_findings() {
+        assert!(diagnose_settings(&AppSettings::default()).is_empty());
+    }
+
+    #[test]
+    fn missing_override_dir_is_reported() {
+        let tmp = tempdir().expect("tempdir");
+        let settings = AppSettings {
+            codex_config_dir: Some(tmp.path().join("nope").to_string_lossy().to_string()),
+            ..AppSettings::default()
+        };
+        let findings = diagnose_settings(&settings);
+        assert_eq!(codes(&findings), vec!["override_dir_missing"]);
+        assert_eq!(findings[0].app.as_deref(), Some("codex"));
+    }
+
+    #[test]
+    fn dir_without_markers_is_flagged_and_marker_clears_it() {
+        let tmp = tempdir().expect("tempdir");
+        let settings = AppSettings {
+            claude_config_dir: Some(tmp.path().to_string_lossy().to_string()),
+            ..AppSettings::default()
+        };
+        assert_eq!(
+            codes(&diagnose_settings(&settings)),
+            vec!["override_dir_app_not_detected"]
+        );
+
+        fs::write(tmp.path().join("settings.json"), "{}").expect("write marker");
+        assert!(diagnose_settings(&settings).is_empty());
+    }
+
+    #[test]
+    fn shared_override_dir_is_reported() {
+        let tmp = tempdir().expect("tempdir");
+        fs::write(tmp.path().join("settings.json"), "{}").expect("write marker");
+        let dir = tmp.path().to_string_lossy().to_string();
+        let settings = AppSettings {
+            claude_config_dir: Some(dir.clone()),
+            gemini_config_dir: Some(dir),
+            ..AppSettings::default()
+        };
+        assert!(codes(&diagnose_settings(&settings)).contains(&"override_dir_shared"));
+    }
+
+    #[test]
+    fn file_path_is_not_a_directory() {
+        let tmp = tempdir().expect("tempdir");
+        let file = tmp.path().join("config.toml");
+        fs::write(&file, "").expect("write file");
+        let settings = AppSettings {
+            codex_config_dir: Some(file.to_string_lossy().to_string()),
+            ..AppSettings::default()
+        };
+        assert_eq!(
+            codes(&diagnose_settings(&settings)),
+            vec!["override_dir_not_directory"]
+        );
+    }
+
+    #[test]
+    fn validate_changed_overrides_only_checks_modified_dirs() {
+        let tmp = tempdir().expect("tempdir");
+        let missing = tmp.path().join("missing").to_string_lossy().to_string();
+        let old = AppSettings {
+            claude_config_dir: Some(missing.clone()),
+            ..AppSettings::default()
+        };
+        assert!(validate_changed_overrides(&old, &old).is_empty());
+
+        let new = AppSettings {
+            codex_config_dir: Some(missing),
+            ..old.clone()
+        };
+        let findings = validate_changed_overrides(&old, &new);
+        assert_eq!(codes(&findings), vec!["override_dir_missing"]);
+        assert_eq!(findings[0].app.as_deref(), Some("codex"));
+    }
+
+    #[test]
+    fn validate_override_dir_accepts_writable_app_dir() {
+        let tmp = tempdir().expect("tempdir");
+        fs::write(tmp.path().join("auth.json"), "{}").expect("write marker");
+        assert!(validate_override_dir(&AppType::Codex, tmp.path()).is_empty());
+    }
+}
@@ -0,0 +1,327 @@
+//! 完整应用状态快照
+//!
+//! 将供应商、MCP 服务器等数据库内容、设备设置以及各应用当前的 live 配置
+//! 打包为一个 zip 归档，保存在 `~/.cc-switch/snapshots/<id>.zip`，用于
+//! 一步回滚到实验前的状态。
+//!
+//! 归档结构：
+//!
+//! ```text
+//! manifest.json   - 快照元信息
+//! cc-switch.sql   - 数据库 SQL 导出
+//! settings.json   - 设备设置
+//! live/<app>/...  - live 配置文件副本
+//! ```
+//!
+//! 归档旁边的 `<id>.zip.sha256` 记录整个归档的 SHA-256，恢复前校验。
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+
+use super::backup::{verify_checksums, BackupVerification, LiveFileRecord};
+use crate::config::{get_app_config_dir, live_config_files};
+use crate::database::Database;
+use crate::error::AppError;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "cc-switch.sql";
+const SETTINGS_ENTRY: &str = "settings.json";
+
+/// 快照元信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub label: String,
+    pub created_at: i64,
+    #[serde(default)]
+    pub size: u64,
+    pub live_files: Vec<LiveFileRecord>,
+}
+
+/// 快照恢复结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRestoreResult {
+    /// 恢复前自动生成的数据库备份 ID
+    pub safety_backup_id: String,
+    pub restored_files: usize,
+}
+
+fn zip_error(context: &str, e: zip::result::ZipError) -> AppError {
+    AppError::Message(format!("{context}: {e}"))
+}
+
+/// 应用状态快照业务
+pub struct SnapshotService;
+
+impl SnapshotService {
+    pub fn snapshots_dir() -> PathBuf {
+        get_app_config_dir().join("snapshots")
+    }
+
+    fn snapshot_path(id: &str) -> Result<PathBuf, AppError> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(AppError::InvalidInput(format!("无效的快照 ID: {id}")));
+        }
+        Ok(Self::snapshots_dir().join(format!("{id}.zip")))
+    }
+
+    fn checksum_path(archive: &Path) -> PathBuf {
+        let mut name = archive.as_os_str().to_os_string();
+        name.push(".sha256");
+        PathBuf::from(name)
+    }
+
+    /// 创建快照
+    pub fn create_snapshot(db: &Database, label: &str) -> Result<SnapshotInfo, AppError> {
+        let now = Utc::now();
+        let base_id = format!("snapshot_{}", now.format("%Y%m%d_%H%M%S"));
+        let mut id = base_id.clone();
+        let mut counter = 1;
+        while Self::snapshot_path(&id)?.exists() {
+            id = format!("{base_id}_{counter}");
+            counter += 1;
+        }
+
+        // 数据库启用静态加密时快照中的数据库内容同样加密
+        let database = db.seal_at_rest(db.export_sql_string()?.into_bytes())?;
+        let settings_json = crate::settings::export_settings(true)?;
+
+        let mut live_entries = Vec::new();
+        let mut live_files = Vec::new();
+        for (app, name, path) in live_config_files() {
+            if !path.is_file() {
+                continue;
+            }
+            let data = fs::read(&path).map_err(|e| AppError::io(&path, e))?;
+            let stored = format!("live/{}/{name}", app.as_str());
+            live_files.push(LiveFileRecord {
+                app: app.as_str().to_string(),
+                original_path: path.to_string_lossy().to_string(),
+                stored: stored.clone(),
+            });
+            live_entries.push((stored, data));
+        }
+
+        let label = label.trim();
+        let mut info = SnapshotInfo {
+            id: id.clone(),
+            label: if label.is_empty() {
+                id.clone()
+            } else {
+                label.to_string()
+            },
+            created_at: now.timestamp_millis(),
+            size: 0,
+            live_files,
+        };
+        let manifest =
+            serde_json::to_vec_pretty(&info).map_err(|e| AppError::JsonSerialize { source: e })?;
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buffer);
+            let options =
+                SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+            let mut add = |name: &str, data: &[u8]| -> Result<(), AppError> {
+                writer
+                    .start_file(name, options)
+                    .map_err(|e| zip_error("写入快照失败", e))?;
+                writer.write_all(data).map_err(|e| AppError::IoContext {
+                    context: "写入快照失败".to_string(),
+                    source: e,
+                })
+            };
+            add(MANIFEST_ENTRY, &manifest)?;
+            add(DATABASE_ENTRY, &database)?;
+            add(SETTINGS_ENTRY, settings_json.as_bytes())?;
+            for (stored, data) in &live_entries {
+                add(stored, data)?;
+            }
+            writer.finish().map_err(|e| zip_error("写入快照失败", e))?;
+        }
+
+        let bytes = buffer.into_inner();
+        let path = Self::snapshot_path(&id)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+        }
+        crate::config::atomic_write(&path, &bytes)?;
+        crate::config::atomic_write(
+            &Self::checksum_path(&path),
+            crate::crypto::sha256_hex(&bytes).as_bytes(),
+        )?;
+
+        info.size = bytes.len() as u64;
+        log::info!("已创建应用状态快照 {id}（{}）", info.label);
+        Ok(info)
+    }
+
+    fn open_archive(path: &Path) -> Result<zip::ZipArchive<fs::File>, AppError> {
+        let file = fs::File::open(path).map_err(|e| AppError::io(path, e))?;
+        zip::ZipArchive::new(file).map_err(|e| zip_error("读取快照失败", e))
+    }
+
+    fn read_entry(
+        archive: &mut zip::ZipArchive<fs::File>,
+        name: &str,
+    ) -> Result<Vec<u8>, AppError> {
+        let mut entry = archive
+            .by_name(name)
+            .map_err(|e| zip_error(&format!("快照缺少 {name}"), e))?;
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| AppError::IoContext {
+                context: format!("读取快照条目 {name} 失败"),
+                source: e,
+            })?;
+        Ok(data)
+    }
+
+    fn read_info(path: &Path) -> Result<SnapshotInfo, AppError> {
+        let mut archive = Self::open_archive(path)?;
+        let manifest = Self::read_entry(&mut archive, MANIFEST_ENTRY)?;
+        let mut info: SnapshotInfo = serde_json::from_slice(&manifest)
+            .map_err(|e| AppError::InvalidInput(format!("快照元信息无效: {e}")))?;
+        info.size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        Ok(info)
+    }
+
+    /// 列出所有快照（按时间倒序）
+    pub fn list_snapshots() -> Result<Vec<SnapshotInfo>, AppError> {
+        let root = Self::snapshots_dir();
+        let iter = match fs::read_dir(&root) {
+            Ok(iter) => iter,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::io(&root, e)),
+        };
+
+        let mut snapshots = iter
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().map(|ext| ext == "zip").unwrap_or(false))
+            .filter_map(|path| match Self::read_info(&path) {
+                Ok(info) => Some(info),
+                Err(e) => {
+                    log::warn!("跳过无效快照 {}: {e}", path.display());
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(snapshots)
+    }
+
+    fn existing_snapshot_path(id: &str) -> Result<PathBuf, AppError> {
+        let path = Self::snapshot_path(id)?;
+        if !path.exists() {
+            return Err(AppError::localized(
+                "snapshot.not_found",
+                format!("快照不存在: {id}"),
+                format!("Snapshot not found: {id}"),
+            ));
+        }
+        Ok(path)
+    }
+
+    /// 快照是否存在
+    pub fn exists(id: &str) -> bool {
+        Self::snapshot_path(id)
+            .map(|path| path.exists())
+            .unwrap_or(false)
+    }
+
+    /// 校验快照归档的 SHA-256，并逐个读取条目以检查 zip 内置的 CRC
+    pub fn verify_snapshot(id: &str) -> Result<BackupVerification, AppError> {
+        let path = Self::existing_snapshot_path(id)?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut checksums = std::collections::BTreeMap::new();
+        if let Ok(expected) = fs::read_to_string(Self::checksum_path(&path)) {
+            checksums.insert(file_name, expected.trim().to_string());
+        }
+        let mut result = verify_checksums(id, &checksums, |_| fs::read(&path).ok());
+
+        match Self::open_archive(&path) {
+            Ok(mut archive) => {
+                for index in 0..archive.len() {
+                    let mut sink = Vec::new();
+                    let outcome =
+                        archive
+                            .by_index(index)
+                            .map_err(|e| e.to_string())
+                            .and_then(|mut entry| {
+                                entry
+                                    .read_to_end(&mut sink)
+                                    .map_err(|e| format!("{}: {e}", entry.name()))
+                            });
+                    if let Err(e) = outcome {
+                        result.problems.push(e);
+                    }
+                }
+            }
+            Err(e) => result.problems.push(e.to_string()),
+        }
+        Ok(result)
+    }
+
+    /// 恢复快照：替换数据库、设置，并写回 live 配置文件
+    pub fn restore_snapshot(db: &Database, id: &str) -> Result<SnapshotRestoreResult, AppError> {
+        let path = Self::existing_snapshot_path(id)?;
+        Self::verify_snapshot(id)?.ensure_valid()?;
+
+        let mut archive = Self::open_archive(&path)?;
+        let manifest = Self::read_entry(&mut archive, MANIFEST_ENTRY)?;
+        let info: SnapshotInfo = serde_json::from_slice(&manifest)
+            .map_err(|e| AppError::InvalidInput(format!("快照元信息无效: {e}")))?;
+        let database = db.open_at_rest(Self::read_entry(&mut archive, DATABASE_ENTRY)?)?;
+        let sql = String::from_utf8(database)
+            .map_err(|e| AppError::InvalidInput(format!("快照数据库内容无效: {e}")))?;
+        let settings_json = String::from_utf8(Self::read_entry(&mut archive, SETTINGS_ENTRY)?)
+            .map_err(|e| AppError::InvalidInput(format!("快照设置内容无效: {e}")))?;
+
+        // 先读出全部 live 文件，避免恢复到一半才发现归档损坏
+        let mut live_files = Vec::new();
+        for record in &info.live_files {
+            let data = Self::read_entry(&mut archive, &record.stored)?;
+            live_files.push((PathBuf::from(&record.original_path), data));
+        }
+
+        let safety_backup_id = db.import_sql_string(&sql)?;
+        crate::settings::import_settings(&settings_json)?;
+
+        for (target, data) in &live_files {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+            }
+            crate::config::atomic_write(target, data)?;
+        }
+
+        log::info!("已恢复应用状态快照 {id}（{}）", info.label);
+        Ok(SnapshotRestoreResult {
+            safety_backup_id,
+            restored_files: live_files.len(),
+        })
+    }
+
+    /// 删除快照
+    pub fn delete_snapshot(id: &str) -> Result<(), AppError> {
+        let path = Self::snapshot_path(id)?;
+        fs::remove_file(&path).map_err(|e| AppError::io(&path, e))?;
+        let checksum = Self::checksum_path(&path);
+        if checksum.exists() {
+            fs::remove_file(&checksum).map_err(|e| AppError::io(&checksum, e))?;
+        }
+        Ok(())
+    }
+}
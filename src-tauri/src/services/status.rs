@@ -0,0 +1,274 @@
+//! 机器可读的运行状态
+//!
+//! 汇总各应用的当前供应商、生效的配置目录与覆盖情况、Live 配置漂移检测与供应商健康
+//! 状态，供 `cc-switch-cli status --json` 与前端使用。字段名保持稳定，结构变化时递增
+//! [`STATUS_SCHEMA_VERSION`]，便于脚本判断兼容性。
+//!
+//! 漂移检测只比较供应商管理的端点与密钥：Live 配置中这两项与当前供应商不一致，通常
+//! 意味着 CLI 登录或升级时改写了自己的配置。代理接管期间 Live 配置本就指向本地代理，
+//! 不做检测。
+
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::paths::DataDirSource;
+use crate::provider::Provider;
+use crate::proxy::providers::get_adapter;
+use crate::services::provider::is_env_only;
+use crate::services::settings_diagnostics::{self, SettingsFinding};
+use crate::services::ProviderService;
+use crate::settings;
+use crate::store::AppState;
+
+/// 状态结构版本
+pub const STATUS_SCHEMA_VERSION: u32 = 1;
+
+const ALL_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusReport {
+    pub schema_version: u32,
+    pub version: String,
+    pub read_only: bool,
+    /// CC Switch 自身的数据目录
+    pub app_config_dir: String,
+    pub app_config_dir_overridden: bool,
+    /// 数据目录的来源（环境变量 `CC_SWITCH_HOME`、设置或默认）
+    pub app_config_dir_source: crate::paths::DataDirSource,
+    pub apps: Vec<AppStatus>,
+    /// 设置诊断结果（见 `settings_diagnostics`）
+    pub findings: Vec<SettingsFinding>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppStatus {
+    pub app: String,
+    /// 当前供应商；OpenCode 为累加模式，始终为 null
+    pub current: Option<CurrentProvider>,
+    pub config_dir: String,
+    pub config_dir_overridden: bool,
+    pub proxy_takeover: bool,
+    pub env_only: bool,
+    pub drift: DriftStatus,
+    /// 当前供应商的健康状态（来自代理故障转移记录）
+    pub health: Option<ProviderHealthStatus>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentProvider {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DriftState {
+    InSync,
+    Drifted,
+    /// Live 配置文件缺失或无法解析
+    LiveUnreadable,
+    /// 未选择供应商、代理接管中或应用不支持
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriftStatus {
+    pub state: DriftState,
+    /// 与当前供应商不一致的字段（`baseUrl` / `apiKey`）
+    pub fields: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl DriftStatus {
+    fn skipped() -> Self {
+        Self {
+            state: DriftState::Skipped,
+            fields: Vec::new(),
+            detail: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderHealthStatus {
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    pub last_failure_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+fn config_dir(app_type: &AppType) -> String {
+    let dir = match app_type {
+        AppType::Claude => crate::config::get_claude_config_dir(),
+        AppType::Codex => crate::codex_config::get_codex_config_dir(),
+        AppType::Gemini => crate::gemini_config::get_gemini_dir(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_dir(),
+    };
+    dir.to_string_lossy().to_string()
+}
+
+/// 比较 Live 配置与供应商配置中的端点和密钥
+///
+/// 两者都按应用的代理适配器解析，因此与 Live 文件的具体布局（通用配置片段等）无关。
+pub(crate) fn compare_managed_fields(
+    app_type: &AppType,
+    provider: &Provider,
+    live: &Provider,
+    compare_key: bool,
+) -> Vec<String> {
+    let adapter = get_adapter(app_type);
+    let base_url = |p: &Provider| adapter.extract_base_url(p).ok();
+    let api_key = |p: &Provider| adapter.extract_auth(p).map(|auth| auth.api_key);
+
+    let mut fields = Vec::new();
+    if base_url(provider) != base_url(live) {
+        fields.push("baseUrl".to_string());
+    }
+    if compare_key && api_key(provider) != api_key(live) {
+        fields.push("apiKey".to_string());
+    }
+    fields
+}
+
+/// 检测当前供应商的 Live 配置是否被外部改写
+pub fn detect_drift(app_type: &AppType, provider: &Provider) -> DriftStatus {
+    let live = match ProviderService::read_live_settings(app_type.clone()) {
+        Ok(live) => live,
+        Err(e) => {
+            return DriftStatus {
+                state: DriftState::LiveUnreadable,
+                fields: Vec::new(),
+                detail: Some(e.to_string()),
+            }
+        }
+    };
+    let live = Provider::with_id(provider.id.clone(), provider.name.clone(), live, None);
+    // 环境变量模式下密钥不写入 Live 文件
+    let fields = compare_managed_fields(app_type, provider, &live, !is_env_only(app_type));
+    DriftStatus {
+        state: if fields.is_empty() {
+            DriftState::InSync
+        } else {
+            DriftState::Drifted
+        },
+        fields,
+        detail: None,
+    }
+}
+
+async fn app_status(state: &AppState, app_type: &AppType) -> Result<AppStatus, AppError> {
+    let settings = settings::get_settings();
+    let mut status = AppStatus {
+        app: app_type.as_str().to_string(),
+        current: None,
+        config_dir: config_dir(app_type),
+        config_dir_overridden: settings.config_dir_override(app_type).is_some(),
+        proxy_takeover: false,
+        env_only: is_env_only(app_type),
+        drift: DriftStatus::skipped(),
+        health: None,
+    };
+    if matches!(app_type, AppType::OpenCode) {
+        return Ok(status);
+    }
+
+    status.proxy_takeover = state.db.get_live_backup(app_type.as_str()).await?.is_some();
+    let id = ProviderService::current(state, app_type.clone())?;
+    let provider = match id.as_str() {
+        "" => None,
+        id => state.db.get_provider_by_id(id, app_type.as_str())?,
+    };
+    let Some(provider) = provider else {
+        return Ok(status);
+    };
+
+    if !status.proxy_takeover {
+        status.drift = detect_drift(app_type, &provider);
+    }
+    let health = state
+        .db
+        .get_provider_health(&provider.id, app_type.as_str())
+        .await?;
+    status.health = Some(ProviderHealthStatus {
+        healthy: health.is_healthy,
+        consecutive_failures: health.consecutive_failures,
+        last_failure_at: health.last_failure_at,
+        last_error: health.last_error,
+    });
+    status.current = Some(CurrentProvider {
+        id: provider.id,
+        name: provider.name,
+    });
+    Ok(status)
+}
+
+/// 收集完整状态
+pub async fn collect(state: &AppState) -> Result<StatusReport, AppError> {
+    let mut apps = Vec::with_capacity(ALL_APPS.len());
+    for app_type in ALL_APPS.iter() {
+        apps.push(app_status(state, app_type).await?);
+    }
+
+    let (app_config_dir, source) = crate::paths::resolve_data_dir();
+    Ok(StatusReport {
+        schema_version: STATUS_SCHEMA_VERSION,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        read_only: crate::read_only::is_enabled(),
+        app_config_dir: app_config_dir.to_string_lossy().to_string(),
+        app_config_dir_overridden: source != DataDirSource::Default,
+        app_config_dir_source: source,
+        apps,
+        findings: settings_diagnostics::diagnose_settings(&settings::get_settings()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn claude(base_url: &str, token: &str) -> Provider {
+        Provider::with_id(
+            "p".to_string(),
+            "P".to_string(),
+            json!({
+                "env": {
+                    "ANTHROPIC_BASE_URL": base_url,
+                    "ANTHROPIC_AUTH_TOKEN": token
+                },
+                "permissions": { "allow": [] }
+            }),
+            None,
+        )
+    }
+
+    #[test]
+    fn reports_changed_endpoint_and_key() {
+        let provider = claude("https://relay.example.com/", "sk-relay");
+
+        let same = claude("https://relay.example.com", "sk-relay");
+        assert!(compare_managed_fields(&AppType::Claude, &provider, &same, true).is_empty());
+
+        let clobbered = claude("https://api.anthropic.com", "sk-other");
+        assert_eq!(
+            compare_managed_fields(&AppType::Claude, &provider, &clobbered, true),
+            vec!["baseUrl".to_string(), "apiKey".to_string()]
+        );
+        assert_eq!(
+            compare_managed_fields(&AppType::Claude, &provider, &clobbered, false),
+            vec!["baseUrl".to_string()]
+        );
+    }
+}
@@ -13,6 +13,8 @@ use crate::app_config::AppType;
 use crate::error::AppError;
 use crate::provider::Provider;
 use crate::proxy::providers::{get_adapter, AuthInfo};
+use crate::services::webhook;
+use crate::settings::WebhookEvent;
 
 /// 健康状态枚举
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -73,15 +75,57 @@ pub struct StreamCheckResult {
     pub retry_count: u32,
 }
 
+/// 最近一次检查的摘要（托盘提示文字使用）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StreamCheckLogSummary {
+    pub app_type: String,
+    pub provider_name: String,
+    /// `operational` / `degraded` / `failed`
+    pub status: String,
+    pub response_time_ms: Option<u64>,
+    /// Unix 时间戳（秒）
+    pub tested_at: i64,
+}
+
+/// 检查目标
+struct CheckTarget {
+    base_url: String,
+    auth: AuthInfo,
+    model: String,
+}
+
 /// 流式健康检查服务
 pub struct StreamCheckService;
 
 impl StreamCheckService {
-    /// 执行流式健康检查（带重试）
+    /// 执行流式健康检查（带重试），失败时通知 Webhook
     pub async fn check_with_retry(
         app_type: &AppType,
         provider: &Provider,
         config: &StreamCheckConfig,
+    ) -> Result<StreamCheckResult, AppError> {
+        let result = Self::run_with_retry(app_type, provider, config).await;
+        let failure = match &result {
+            Ok(r) if r.success => None,
+            Ok(r) => Some(r.message.clone()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(detail) = failure {
+            webhook::notify(
+                WebhookEvent::HealthCheckFailed,
+                app_type.as_str(),
+                &provider.id,
+                &provider.name,
+                Some(detail),
+            );
+        }
+        result
+    }
+
+    async fn run_with_retry(
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
     ) -> Result<StreamCheckResult, AppError> {
         let mut last_result = None;
 
@@ -134,6 +178,79 @@ impl StreamCheckService {
         config: &StreamCheckConfig,
     ) -> Result<StreamCheckResult, AppError> {
         let start = Instant::now();
+        let target = Self::prepare(app_type, provider, config)?;
+        let result = match Self::send(app_type, &target, config).await {
+            Ok(response) => Self::read_first_chunk(response).await,
+            Err(e) => Err(e),
+        };
+
+        let response_time = start.elapsed().as_millis() as u64;
+        let tested_at = chrono::Utc::now().timestamp();
+
+        match result {
+            Ok(status_code) => {
+                let health_status =
+                    Self::determine_status(response_time, config.degraded_threshold_ms);
+                Ok(StreamCheckResult {
+                    status: health_status,
+                    success: true,
+                    message: "Check succeeded".to_string(),
+                    response_time_ms: Some(response_time),
+                    http_status: Some(status_code),
+                    model_used: target.model,
+                    tested_at,
+                    retry_count: 0,
+                })
+            }
+            Err(e) => Ok(StreamCheckResult {
+                status: HealthStatus::Failed,
+                success: false,
+                message: e.to_string(),
+                response_time_ms: Some(response_time),
+                http_status: None,
+                model_used: String::new(),
+                tested_at,
+                retry_count: 0,
+            }),
+        }
+    }
+
+    /// 单次测速：返回 (首字节耗时, 完整响应耗时)，单位毫秒
+    pub async fn measure_once(
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
+    ) -> Result<(u64, u64), AppError> {
+        let target = Self::prepare(app_type, provider, config)?;
+        let start = Instant::now();
+        let response = Self::send(app_type, &target, config).await?;
+        let mut stream = response.bytes_stream();
+        match stream.next().await {
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(AppError::Message(format!("Stream read failed: {e}"))),
+            None => return Err(AppError::Message("No response data received".to_string())),
+        }
+        let first_byte_ms = start.elapsed().as_millis() as u64;
+        while let Some(chunk) = stream.next().await {
+            chunk.map_err(|e| AppError::Message(format!("Stream read failed: {e}")))?;
+        }
+        Ok((first_byte_ms, start.elapsed().as_millis() as u64))
+    }
+
+    /// 解析检查目标（地址、认证与测试模型）
+    fn prepare(
+        app_type: &AppType,
+        provider: &Provider,
+        config: &StreamCheckConfig,
+    ) -> Result<CheckTarget, AppError> {
+        if *app_type == AppType::OpenCode {
+            // OpenCode doesn't support stream check yet
+            return Err(AppError::localized(
+                "opencode_no_stream_check",
+                "OpenCode 暂不支持健康检查",
+                "OpenCode does not support health check yet",
+            ));
+        }
         let adapter = get_adapter(app_type);
 
         let base_url = adapter
@@ -144,99 +261,95 @@ impl StreamCheckService {
             .extract_auth(provider)
             .ok_or_else(|| AppError::Message("API Key not found".to_string()))?;
 
+        Ok(CheckTarget {
+            base_url,
+            auth,
+            model: Self::resolve_test_model(app_type, provider, config),
+        })
+    }
+
+    /// 发送流式请求，返回状态码为成功的响应
+    async fn send(
+        app_type: &AppType,
+        target: &CheckTarget,
+        config: &StreamCheckConfig,
+    ) -> Result<reqwest::Response, AppError> {
         // 使用全局 HTTP 客户端（已包含代理配置）
         let client = crate::proxy::http_client::get();
         let request_timeout = std::time::Duration::from_secs(config.timeout_secs);
-
-        let model_to_test = Self::resolve_test_model(app_type, provider, config);
+        let (base_url, auth, model) = (&target.base_url, &target.auth, &target.model);
         let test_prompt = &config.test_prompt;
 
-        let result = match app_type {
+        let response = match app_type {
             AppType::Claude => {
-                Self::check_claude_stream(
+                Self::send_claude_stream(
                     &client,
-                    &base_url,
-                    &auth,
-                    &model_to_test,
+                    base_url,
+                    auth,
+                    model,
                     test_prompt,
                     request_timeout,
                 )
-                .await
+                .await?
             }
             AppType::Codex => {
-                Self::check_codex_stream(
+                Self::send_codex_stream(
                     &client,
-                    &base_url,
-                    &auth,
-                    &model_to_test,
+                    base_url,
+                    auth,
+                    model,
                     test_prompt,
                     request_timeout,
                 )
-                .await
+                .await?
             }
-            AppType::Gemini => {
-                Self::check_gemini_stream(
+            // OpenCode 已在 prepare 中排除
+            AppType::Gemini | AppType::OpenCode => {
+                Self::send_gemini_stream(
                     &client,
-                    &base_url,
-                    &auth,
-                    &model_to_test,
+                    base_url,
+                    auth,
+                    model,
                     test_prompt,
                     request_timeout,
                 )
-                .await
-            }
-            AppType::OpenCode => {
-                // OpenCode doesn't support stream check yet
-                return Err(AppError::localized(
-                    "opencode_no_stream_check",
-                    "OpenCode 暂不支持健康检查",
-                    "OpenCode does not support health check yet",
-                ));
+                .await?
             }
         };
 
-        let response_time = start.elapsed().as_millis() as u64;
-        let tested_at = chrono::Utc::now().timestamp();
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AppError::Message(format!("HTTP {status}: {error_text}")));
+        }
+        Ok(response)
+    }
 
-        match result {
-            Ok((status_code, model)) => {
-                let health_status =
-                    Self::determine_status(response_time, config.degraded_threshold_ms);
-                Ok(StreamCheckResult {
-                    status: health_status,
-                    success: true,
-                    message: "Check succeeded".to_string(),
-                    response_time_ms: Some(response_time),
-                    http_status: Some(status_code),
-                    model_used: model,
-                    tested_at,
-                    retry_count: 0,
-                })
+    /// 只读取首个 chunk，返回状态码
+    async fn read_first_chunk(response: reqwest::Response) -> Result<u16, AppError> {
+        let status = response.status().as_u16();
+        let mut stream = response.bytes_stream();
+        if let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(_) => Ok(status),
+                Err(e) => Err(AppError::Message(format!("Stream read failed: {e}"))),
             }
-            Err(e) => Ok(StreamCheckResult {
-                status: HealthStatus::Failed,
-                success: false,
-                message: e.to_string(),
-                response_time_ms: Some(response_time),
-                http_status: None,
-                model_used: String::new(),
-                tested_at,
-                retry_count: 0,
-            }),
+        } else {
+            Err(AppError::Message("No response data received".to_string()))
         }
     }
 
-    /// Claude 流式检查
+    /// Claude 流式请求
     ///
     /// 严格按照 Claude CLI 真实请求格式构建请求
-    async fn check_claude_stream(
+    async fn send_claude_stream(
         client: &Client,
         base_url: &str,
         auth: &AuthInfo,
         model: &str,
         test_prompt: &str,
         timeout: std::time::Duration,
-    ) -> Result<(u16, String), AppError> {
+    ) -> Result<reqwest::Response, AppError> {
         let base = base_url.trim_end_matches('/');
         // URL 必须包含 ?beta=true 参数（某些中转服务依赖此参数验证请求来源）
         let url = if base.ends_with("/v1") {
@@ -257,7 +370,7 @@ impl StreamCheckService {
         let arch_name = Self::get_arch_name();
 
         // 严格按照 Claude CLI 请求格式设置 headers
-        let response = client
+        client
             .post(&url)
             // 认证 headers（双重认证）
             .header("authorization", format!("Bearer {}", auth.api_key))
@@ -293,38 +406,20 @@ impl StreamCheckService {
             .json(&body)
             .send()
             .await
-            .map_err(Self::map_request_error)?;
-
-        let status = response.status().as_u16();
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Message(format!("HTTP {status}: {error_text}")));
-        }
-
-        // 流式读取：只需首个 chunk
-        let mut stream = response.bytes_stream();
-        if let Some(chunk) = stream.next().await {
-            match chunk {
-                Ok(_) => Ok((status, model.to_string())),
-                Err(e) => Err(AppError::Message(format!("Stream read failed: {e}"))),
-            }
-        } else {
-            Err(AppError::Message("No response data received".to_string()))
-        }
+            .map_err(Self::map_request_error)
     }
 
-    /// Codex 流式检查
+    /// Codex 流式请求
     ///
     /// 严格按照 Codex CLI 真实请求格式构建请求 (Responses API)
-    async fn check_codex_stream(
+    async fn send_codex_stream(
         client: &Client,
         base_url: &str,
         auth: &AuthInfo,
         model: &str,
         test_prompt: &str,
         timeout: std::time::Duration,
-    ) -> Result<(u16, String), AppError> {
+    ) -> Result<reqwest::Response, AppError> {
         let base = base_url.trim_end_matches('/');
         // Codex CLI 使用 /v1/responses 端点 (OpenAI Responses API)
         let url = if base.ends_with("/v1") {
@@ -353,7 +448,7 @@ impl StreamCheckService {
         }
 
         // 严格按照 Codex CLI 请求格式设置 headers
-        let response = client
+        client
             .post(&url)
             .header("authorization", format!("Bearer {}", auth.api_key))
             .header("content-type", "application/json")
@@ -368,35 +463,18 @@ impl StreamCheckService {
             .json(&body)
             .send()
             .await
-            .map_err(Self::map_request_error)?;
-
-        let status = response.status().as_u16();
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Message(format!("HTTP {status}: {error_text}")));
-        }
-
-        let mut stream = response.bytes_stream();
-        if let Some(chunk) = stream.next().await {
-            match chunk {
-                Ok(_) => Ok((status, model.to_string())),
-                Err(e) => Err(AppError::Message(format!("Stream read failed: {e}"))),
-            }
-        } else {
-            Err(AppError::Message("No response data received".to_string()))
-        }
+            .map_err(Self::map_request_error)
     }
 
-    /// Gemini 流式检查
-    async fn check_gemini_stream(
+    /// Gemini 流式请求（OpenAI 兼容格式）
+    async fn send_gemini_stream(
         client: &Client,
         base_url: &str,
         auth: &AuthInfo,
         model: &str,
         test_prompt: &str,
         timeout: std::time::Duration,
-    ) -> Result<(u16, String), AppError> {
+    ) -> Result<reqwest::Response, AppError> {
         let base = base_url.trim_end_matches('/');
         let url = format!("{base}/v1/chat/completions");
 
@@ -408,7 +486,7 @@ impl StreamCheckService {
             "stream": true
         });
 
-        let response = client
+        client
             .post(&url)
             .header("Authorization", format!("Bearer {}", auth.api_key))
             .header("Content-Type", "application/json")
@@ -416,24 +494,7 @@ impl StreamCheckService {
             .json(&body)
             .send()
             .await
-            .map_err(Self::map_request_error)?;
-
-        let status = response.status().as_u16();
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Message(format!("HTTP {status}: {error_text}")));
-        }
-
-        let mut stream = response.bytes_stream();
-        if let Some(chunk) = stream.next().await {
-            match chunk {
-                Ok(_) => Ok((status, model.to_string())),
-                Err(e) => Err(AppError::Message(format!("Stream read failed: {e}"))),
-            }
-        } else {
-            Err(AppError::Message("No response data received".to_string()))
-        }
+            .map_err(Self::map_request_error)
     }
 
     fn determine_status(latency_ms: u64, threshold: u64) -> HealthStatus {
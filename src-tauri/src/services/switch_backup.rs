@@ -0,0 +1,230 @@
+//! 切换前安全备份
+//!
+//! 启用 `switchSafetyBackup` 后，每次切换供应商写入 live 配置前，先把目标应用的
+//! live 文件原样复制到 `~/.cc-switch/backups/switch/<app>/<id>/`，按应用保留最近
+//! [`SWITCH_BACKUP_RETAIN`] 份（环形缓冲）。`undo_last_switch` 按字节恢复最近一份，
+//! 切换前不存在的文件会被删除，并把当前供应商指回切换前的供应商。
+
+use std::fs;
+#[cfg(feature = "gui")]
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, live_config_files};
+use crate::database::Database;
+use crate::error::AppError;
+
+/// 每个应用保留的切换备份数量
+pub const SWITCH_BACKUP_RETAIN: usize = 10;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// 切换备份中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchBackupFile {
+    pub original_path: String,
+    /// 备份目录内的文件名；切换前文件不存在时为 None
+    pub stored: Option<String>,
+}
+
+/// 切换备份元信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchBackup {
+    pub id: String,
+    pub app: String,
+    pub created_at: i64,
+    /// 切换前的当前供应商
+    pub from_provider: Option<String>,
+    pub to_provider: String,
+    pub files: Vec<SwitchBackupFile>,
+}
+
+/// 撤销结果
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSwitchResult {
+    pub backup: SwitchBackup,
+    pub restored_files: usize,
+    pub removed_files: usize,
+}
+
+pub struct SwitchBackupService;
+
+impl SwitchBackupService {
+    fn app_dir(app_type: &AppType) -> PathBuf {
+        get_app_config_dir()
+            .join("backups")
+            .join("switch")
+            .join(app_type.as_str())
+    }
+
+    /// 切换前备份目标应用的 live 文件（未启用设置时不做任何事）
+    pub fn backup_before_switch(
+        db: &Database,
+        app_type: &AppType,
+        to_provider: &str,
+    ) -> Result<Option<SwitchBackup>, AppError> {
+        if !crate::settings::get_settings().switch_safety_backup {
+            return Ok(None);
+        }
+
+        let from_provider = crate::settings::get_effective_current_provider(db, app_type)?;
+        let now = Local::now();
+        let root = Self::app_dir(app_type);
+        let base_id = format!("switch_{}", now.format("%Y%m%d_%H%M%S_%3f"));
+        let mut id = base_id.clone();
+        let mut counter = 1;
+        while root.join(&id).exists() {
+            id = format!("{base_id}_{counter}");
+            counter += 1;
+        }
+        let dir = root.join(&id);
+        fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+
+        let mut files = Vec::new();
+        for (index, (app, name, path)) in live_config_files().into_iter().enumerate() {
+            if app != *app_type {
+                continue;
+            }
+            let stored = match fs::read(&path) {
+                Ok(data) => {
+                    let stored = format!("{index}-{name}");
+                    let target = dir.join(&stored);
+                    fs::write(&target, data).map_err(|e| AppError::io(&target, e))?;
+                    Some(stored)
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => return Err(AppError::io(&path, e)),
+            };
+            files.push(SwitchBackupFile {
+                original_path: path.to_string_lossy().to_string(),
+                stored,
+            });
+        }
+
+        let backup = SwitchBackup {
+            id,
+            app: app_type.as_str().to_string(),
+            created_at: now.timestamp_millis(),
+            from_provider,
+            to_provider: to_provider.to_string(),
+            files,
+        };
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let json = serde_json::to_vec_pretty(&backup)
+            .map_err(|e| AppError::JsonSerialize { source: e })?;
+        fs::write(&manifest_path, json).map_err(|e| AppError::io(&manifest_path, e))?;
+
+        Self::prune(app_type);
+        Ok(Some(backup))
+    }
+
+    /// 列出某应用的切换备份（按时间倒序）
+    pub fn list(app_type: &AppType) -> Vec<(PathBuf, SwitchBackup)> {
+        let Ok(iter) = fs::read_dir(Self::app_dir(app_type)) else {
+            return Vec::new();
+        };
+        let mut backups = iter
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter_map(|dir| {
+                let content = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+                let backup: SwitchBackup = serde_json::from_str(&content).ok()?;
+                Some((dir, backup))
+            })
+            .collect::<Vec<_>>();
+        backups.sort_by(|a, b| b.1.created_at.cmp(&a.1.created_at));
+        backups
+    }
+
+    fn prune(app_type: &AppType) {
+        for (dir, _) in Self::list(app_type).into_iter().skip(SWITCH_BACKUP_RETAIN) {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                log::warn!("清理切换备份失败 {}: {e}", dir.display());
+            }
+        }
+    }
+
+    /// 撤销最近一次切换：按字节恢复 live 文件并恢复当前供应商，随后删除该备份
+    #[cfg(feature = "gui")]
+    pub fn undo_last_switch(
+        db: &Database,
+        app_type: &AppType,
+    ) -> Result<UndoSwitchResult, AppError> {
+        crate::read_only::ensure_writable()?;
+        let (dir, backup) = Self::list(app_type).into_iter().next().ok_or_else(|| {
+            AppError::localized(
+                "switch_backup.none",
+                format!("{} 没有可撤销的切换", app_type.as_str()),
+                format!("No switch to undo for {}", app_type.as_str()),
+            )
+        })?;
+
+        // 先读出全部文件，避免恢复到一半才发现备份缺失
+        let mut contents = Vec::new();
+        for file in &backup.files {
+            let data = match &file.stored {
+                Some(stored) => {
+                    let source = dir.join(stored);
+                    Some(fs::read(&source).map_err(|e| AppError::io(&source, e))?)
+                }
+                None => None,
+            };
+            contents.push((PathBuf::from(&file.original_path), data));
+        }
+
+        let mut restored_files = 0;
+        let mut removed_files = 0;
+        for (target, data) in &contents {
+            match data {
+                Some(data) => {
+                    restore_file(target, data)?;
+                    restored_files += 1;
+                }
+                None if target.exists() => {
+                    fs::remove_file(target).map_err(|e| AppError::io(target, e))?;
+                    removed_files += 1;
+                }
+                None => {}
+            }
+        }
+
+        if let Some(previous) = &backup.from_provider {
+            if !matches!(app_type, AppType::OpenCode) {
+                crate::settings::set_current_provider(app_type, Some(previous))?;
+                db.set_current_provider(app_type.as_str(), previous)?;
+            }
+        }
+
+        if let Err(e) = fs::remove_dir_all(&dir) {
+            log::warn!("删除已撤销的切换备份失败 {}: {e}", dir.display());
+        }
+
+        log::info!(
+            "已撤销 {} 的切换（{} -> {}）",
+            backup.app,
+            backup.from_provider.as_deref().unwrap_or("-"),
+            backup.to_provider
+        );
+        Ok(UndoSwitchResult {
+            backup,
+            restored_files,
+            removed_files,
+        })
+    }
+}
+
+#[cfg(feature = "gui")]
+fn restore_file(target: &Path, data: &[u8]) -> Result<(), AppError> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    }
+    crate::config::atomic_write(target, data)
+}
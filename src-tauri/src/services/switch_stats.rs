@@ -0,0 +1,232 @@
+//! 切换习惯统计
+//!
+//! 根据本地的切换记录（`provider_switch_history`）统计切换频率、切换发生的时段，以及
+//! 最常见的「从哪个供应商切换到哪个供应商」，为最近使用排序与定时切换建议提供依据。
+//! 数据只在本机读取，不会上传。
+//!
+//! 切换记录每个应用只保留最近的 200 条，统计只覆盖其中的部分。
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Local, TimeZone, Timelike};
+use serde::Serialize;
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+
+/// 记录切换历史的应用
+const SWITCH_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 返回的最常见切换路径数
+const TOP_TRANSITIONS: usize = 20;
+
+/// 两个供应商之间的切换次数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchTransition {
+    pub app_type: String,
+    pub from_provider_id: String,
+    pub from_provider_name: String,
+    pub to_provider_id: String,
+    pub to_provider_name: String,
+    pub count: u64,
+}
+
+/// 切换习惯统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchStats {
+    pub total_switches: u64,
+    /// 毫秒时间戳
+    pub first_switch_at: Option<i64>,
+    pub last_switch_at: Option<i64>,
+    /// 第一次到最后一次切换之间平均每天的切换次数
+    pub per_day: f64,
+    /// 按本地时间的小时统计（0-23 点）
+    pub by_hour: Vec<u64>,
+    /// 按星期统计（周一到周日）
+    pub by_weekday: Vec<u64>,
+    /// 各应用的切换次数
+    pub by_app: BTreeMap<String, u64>,
+    /// 按次数从多到少排列
+    pub transitions: Vec<SwitchTransition>,
+}
+
+/// 统计切换记录（`timelines` 为各应用按时间先后的 `(毫秒时间戳, 供应商)`）
+fn compute(
+    timelines: &[(String, Vec<(i64, String)>)],
+    since: Option<i64>,
+    names: &HashMap<(String, String), String>,
+) -> SwitchStats {
+    let name = |app_type: &str, provider_id: &str| {
+        names
+            .get(&(app_type.to_string(), provider_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let mut by_hour = vec![0; 24];
+    let mut by_weekday = vec![0; 7];
+    let mut by_app = BTreeMap::new();
+    let mut transitions: BTreeMap<(String, String, String), u64> = BTreeMap::new();
+    let mut first_switch_at: Option<i64> = None;
+    let mut last_switch_at: Option<i64> = None;
+    let mut total_switches = 0;
+
+    for (app_type, timeline) in timelines {
+        let mut previous: Option<&str> = None;
+        for (switched_at, provider_id) in timeline {
+            let from = previous.replace(provider_id.as_str());
+            if since.is_some_and(|since| *switched_at < since) {
+                continue;
+            }
+            let Some(time) = Local.timestamp_millis_opt(*switched_at).single() else {
+                continue;
+            };
+            total_switches += 1;
+            by_hour[time.hour() as usize] += 1;
+            by_weekday[time.weekday().num_days_from_monday() as usize] += 1;
+            *by_app.entry(app_type.clone()).or_insert(0) += 1;
+            first_switch_at = Some(first_switch_at.map_or(*switched_at, |t| t.min(*switched_at)));
+            last_switch_at = Some(last_switch_at.map_or(*switched_at, |t| t.max(*switched_at)));
+            // 重新应用同一个供应商不算作切换路径
+            if let Some(from) = from.filter(|from| *from != provider_id.as_str()) {
+                *transitions
+                    .entry((app_type.clone(), from.to_string(), provider_id.clone()))
+                    .or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut transitions = transitions
+        .into_iter()
+        .map(|((app_type, from, to), count)| SwitchTransition {
+            from_provider_name: name(&app_type, &from),
+            to_provider_name: name(&app_type, &to),
+            app_type,
+            from_provider_id: from,
+            to_provider_id: to,
+            count,
+        })
+        .collect::<Vec<_>>();
+    transitions.sort_by(|a, b| b.count.cmp(&a.count));
+    transitions.truncate(TOP_TRANSITIONS);
+
+    let per_day = match (first_switch_at, last_switch_at) {
+        (Some(first), Some(last)) => {
+            let days = ((last - first) as f64 / 86_400_000.0).max(1.0);
+            total_switches as f64 / days
+        }
+        _ => 0.0,
+    };
+
+    SwitchStats {
+        total_switches,
+        first_switch_at,
+        last_switch_at,
+        per_day,
+        by_hour,
+        by_weekday,
+        by_app,
+        transitions,
+    }
+}
+
+/// 最近若干天（为空时为全部记录）的切换习惯统计
+pub fn get_switch_stats(
+    db: &Database,
+    app_type: Option<&AppType>,
+    days: Option<u32>,
+) -> Result<SwitchStats, AppError> {
+    let mut timelines = Vec::new();
+    for app in SWITCH_APPS
+        .iter()
+        .filter(|app| app_type.is_none_or(|a| a == *app))
+    {
+        let app_name = app.as_str().to_string();
+        timelines.push((app_name, db.get_provider_switch_timeline(app.as_str())?));
+    }
+
+    let mut names = HashMap::new();
+    for (app_name, timeline) in &timelines {
+        for (_, provider_id) in timeline {
+            let key = (app_name.clone(), provider_id.clone());
+            if names.contains_key(&key) {
+                continue;
+            }
+            if let Some(provider) = db.get_provider_by_id(provider_id, app_name)? {
+                names.insert(key, provider.name);
+            }
+        }
+    }
+
+    let since =
+        days.map(|days| chrono::Utc::now().timestamp_millis() - i64::from(days) * 86_400_000);
+    Ok(compute(&timelines, since, &names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local_millis(day: u32, hour: u32) -> i64 {
+        // 2025-06-02 是周一
+        Local
+            .with_ymd_and_hms(2025, 6, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn counts_hours_weekdays_and_transitions() {
+        let timelines = vec![
+            (
+                "claude".to_string(),
+                vec![
+                    (local_millis(1, 8), "official".to_string()),
+                    (local_millis(2, 9), "relay".to_string()),
+                    (local_millis(2, 9), "relay".to_string()),
+                    (local_millis(3, 18), "official".to_string()),
+                    (local_millis(4, 9), "relay".to_string()),
+                ],
+            ),
+            (
+                "codex".to_string(),
+                vec![(local_millis(5, 22), "openai".to_string())],
+            ),
+        ];
+        let names = HashMap::from([(
+            ("claude".to_string(), "relay".to_string()),
+            "Relay".to_string(),
+        )]);
+
+        // 6 月 1 日的记录只作为切换路径的起点
+        let stats = compute(&timelines, Some(local_millis(2, 0)), &names);
+        assert_eq!(stats.total_switches, 5);
+        assert_eq!(stats.by_hour[9], 3);
+        assert_eq!(stats.by_hour[18], 1);
+        assert_eq!(stats.by_weekday, vec![2, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(stats.by_app.get("claude"), Some(&4));
+        assert_eq!(stats.first_switch_at, Some(local_millis(2, 9)));
+        assert!(stats.per_day > 1.0);
+
+        assert_eq!(
+            stats.transitions[0],
+            SwitchTransition {
+                app_type: "claude".to_string(),
+                from_provider_id: "official".to_string(),
+                from_provider_name: String::new(),
+                to_provider_id: "relay".to_string(),
+                to_provider_name: "Relay".to_string(),
+                count: 2,
+            }
+        );
+        assert_eq!(stats.transitions.len(), 2);
+    }
+}
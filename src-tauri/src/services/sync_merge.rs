@@ -0,0 +1,436 @@
+//! 多设备同步的三方合并
+//!
+//! 以上次成功同步时的快照为基线（base），将本地（local）与远端（remote）的
+//! 供应商逐个做三方合并：只有一方修改时直接采用修改方；双方都修改时在字段级
+//! 合并，若同一字段被改成不同的值则记为冲突，交给前端选择，而不是后写覆盖先写。
+//!
+//! 基线与待解决冲突保存在数据目录（默认 `~/.cc-switch`，见 [`crate::paths`]）的 `sync/` 下：
+//!
+//! ```text
+//! sync/
+//! ├── base.sql         - 上次同步时的数据库快照
+//! └── conflicts.json   - 待解决的冲突
+//! ```
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::config::get_app_config_dir;
+use crate::database::{BatchOp, Database};
+use crate::error::AppError;
+use crate::provider::Provider;
+
+const BASE_FILE: &str = "base.sql";
+const CONFLICTS_FILE: &str = "conflicts.json";
+
+/// 冲突事件名（负载为全部待解决冲突）
+pub const SYNC_CONFLICTS_EVENT: &str = "sync-conflicts";
+
+const SYNC_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 单个供应商的同步冲突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderConflict {
+    pub app: String,
+    pub id: String,
+    pub base: Option<Provider>,
+    pub local: Option<Provider>,
+    pub remote: Option<Provider>,
+    /// 双方改成不同值的字段（JSON Pointer）
+    pub paths: Vec<String>,
+}
+
+/// 合并结果汇总
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncMergeResult {
+    /// 采用远端修改的供应商（`app/id`）
+    pub applied_remote: Vec<String>,
+    /// 字段级自动合并的供应商（`app/id`）
+    pub auto_merged: Vec<String>,
+    pub conflicts: Vec<ProviderConflict>,
+}
+
+/// 冲突解决方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    Local,
+    Remote,
+    Custom,
+}
+
+/// 单个供应商的三方合并结论
+#[derive(Debug, PartialEq)]
+enum ProviderOutcome {
+    Unchanged,
+    /// 采用远端（None 表示远端已删除）
+    TakeRemote(Option<Value>),
+    Merged(Value),
+    Conflict(Vec<String>),
+}
+
+pub(crate) fn sync_dir() -> PathBuf {
+    get_app_config_dir().join("sync")
+}
+
+/// 记录本次同步后的基线快照（数据库启用静态加密时加密保存）
+pub fn save_base(db: &Database, sql: &str) -> Result<(), AppError> {
+    let dir = sync_dir();
+    fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+    let data = db.seal_at_rest(sql.as_bytes().to_vec())?;
+    crate::config::atomic_write(&dir.join(BASE_FILE), &data)
+}
+
+fn load_base(db: &Database) -> Option<String> {
+    let data = fs::read(sync_dir().join(BASE_FILE)).ok()?;
+    match db
+        .open_at_rest(data)
+        .and_then(|plain| String::from_utf8(plain).map_err(|e| AppError::Message(e.to_string())))
+    {
+        Ok(sql) => Some(sql),
+        Err(e) => {
+            log::warn!("读取同步基线失败，按首次同步处理: {e}");
+            None
+        }
+    }
+}
+
+/// 读取待解决的冲突
+pub fn load_conflicts() -> Vec<ProviderConflict> {
+    fs::read_to_string(sync_dir().join(CONFLICTS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_conflicts(conflicts: &[ProviderConflict]) -> Result<(), AppError> {
+    let dir = sync_dir();
+    fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+    let json =
+        serde_json::to_vec_pretty(conflicts).map_err(|e| AppError::JsonSerialize { source: e })?;
+    crate::config::atomic_write(&dir.join(CONFLICTS_FILE), &json)
+}
+
+fn to_value(provider: Option<&Provider>) -> Result<Option<Value>, AppError> {
+    provider
+        .map(|p| serde_json::to_value(p).map_err(|e| AppError::JsonSerialize { source: e }))
+        .transpose()
+}
+
+fn from_value(value: Value) -> Result<Provider, AppError> {
+    serde_json::from_value(value).map_err(|e| AppError::Message(format!("合并后的供应商无效: {e}")))
+}
+
+/// 值级三方合并；双方修改同一叶子为不同值时记录冲突路径并保留本地值
+fn merge_values(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    remote: Option<&Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if local == remote || remote == base {
+        return local.cloned();
+    }
+    if local == base {
+        return remote.cloned();
+    }
+
+    if let (Some(Value::Object(l)), Some(Value::Object(r))) = (local, remote) {
+        let empty = Map::new();
+        let b = match base {
+            Some(Value::Object(b)) => b,
+            _ => &empty,
+        };
+        let keys: BTreeSet<&String> = b.keys().chain(l.keys()).chain(r.keys()).collect();
+        let mut merged = Map::new();
+        for key in keys {
+            let child = format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+            if let Some(value) = merge_values(b.get(key), l.get(key), r.get(key), &child, conflicts)
+            {
+                merged.insert(key.clone(), value);
+            }
+        }
+        return Some(Value::Object(merged));
+    }
+
+    conflicts.push(if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    });
+    local.cloned()
+}
+
+fn merge_provider(
+    base: Option<&Value>,
+    local: Option<&Value>,
+    remote: Option<&Value>,
+) -> ProviderOutcome {
+    if local == remote || remote == base {
+        return ProviderOutcome::Unchanged;
+    }
+    if local == base {
+        return ProviderOutcome::TakeRemote(remote.cloned());
+    }
+    // 一方删除、另一方修改：无法自动决定
+    let (Some(_), Some(_)) = (local, remote) else {
+        return ProviderOutcome::Conflict(vec!["/".to_string()]);
+    };
+
+    let mut paths = Vec::new();
+    let merged = merge_values(base, local, remote, "", &mut paths);
+    match merged {
+        Some(value) if paths.is_empty() => ProviderOutcome::Merged(value),
+        _ => ProviderOutcome::Conflict(paths),
+    }
+}
+
+/// 将远端快照三方合并到本地数据库
+///
+/// 没有基线（首次同步）时只合并双方都存在且内容相同、或仅一方存在的供应商，
+/// 不做删除。合并完成后以远端快照作为新基线；冲突写入待解决列表。
+pub fn merge_remote(db: &Database, remote_sql: &str) -> Result<SyncMergeResult, AppError> {
+    crate::read_only::ensure_writable()?;
+    let remote_db = Database::open_sql_export(remote_sql)?;
+    let base_db = match load_base(db) {
+        Some(sql) => match Database::open_sql_export(&sql) {
+            Ok(base) => Some(base),
+            Err(e) => {
+                log::warn!("同步基线快照无效，按首次同步处理: {e}");
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut result = SyncMergeResult::default();
+    let mut ops = Vec::new();
+    for app in SYNC_APPS {
+        let key = app.as_str();
+        let local = db.get_all_providers(key)?;
+        let remote = remote_db.get_all_providers(key)?;
+        let base = match &base_db {
+            Some(base_db) => base_db.get_all_providers(key)?,
+            None => Default::default(),
+        };
+
+        let ids: BTreeSet<&String> = local
+            .keys()
+            .chain(remote.keys())
+            .chain(base.keys())
+            .collect();
+        for id in ids {
+            let base_value = to_value(base.get(id))?;
+            let local_value = to_value(local.get(id))?;
+            let remote_value = to_value(remote.get(id))?;
+
+            // 无基线时缺失的一方视为“未修改”，避免首次同步误删
+            let effective_base = match (&base_db, &local_value, &remote_value) {
+                (None, None, Some(_)) => None,
+                (None, Some(_), None) => local_value.clone(),
+                _ => base_value.clone(),
+            };
+
+            let label = format!("{key}/{id}");
+            match merge_provider(
+                effective_base.as_ref(),
+                local_value.as_ref(),
+                remote_value.as_ref(),
+            ) {
+                ProviderOutcome::Unchanged => {}
+                ProviderOutcome::TakeRemote(Some(value)) => {
+                    let app_type = key.to_string();
+                    let provider = from_value(value)?;
+                    ops.push(if local.contains_key(id) {
+                        BatchOp::UpdateProvider { app_type, provider }
+                    } else {
+                        BatchOp::AddProvider { app_type, provider }
+                    });
+                    result.applied_remote.push(label);
+                }
+                ProviderOutcome::TakeRemote(None) => {
+                    let is_current = crate::settings::get_effective_current_provider(db, &app)?
+                        .as_deref()
+                        == Some(id.as_str());
+                    if is_current {
+                        result.conflicts.push(ProviderConflict {
+                            app: key.to_string(),
+                            id: id.clone(),
+                            base: base.get(id).cloned(),
+                            local: local.get(id).cloned(),
+                            remote: None,
+                            paths: vec!["/".to_string()],
+                        });
+                    } else {
+                        ops.push(BatchOp::DeleteProvider {
+                            app_type: key.to_string(),
+                            id: id.clone(),
+                        });
+                        result.applied_remote.push(label);
+                    }
+                }
+                ProviderOutcome::Merged(value) => {
+                    ops.push(BatchOp::UpdateProvider {
+                        app_type: key.to_string(),
+                        provider: from_value(value)?,
+                    });
+                    result.auto_merged.push(label);
+                }
+                ProviderOutcome::Conflict(paths) => {
+                    result.conflicts.push(ProviderConflict {
+                        app: key.to_string(),
+                        id: id.clone(),
+                        base: base.get(id).cloned(),
+                        local: local.get(id).cloned(),
+                        remote: remote.get(id).cloned(),
+                        paths,
+                    });
+                }
+            }
+        }
+    }
+
+    // 远端的修改整体应用，失败时本地数据与基线都保持不变，下次同步重新合并
+    db.transaction(&ops)?;
+    save_conflicts(&result.conflicts)?;
+    save_base(db, remote_sql)?;
+    Ok(result)
+}
+
+/// 统计本地相对上次同步基线有改动的供应商数量（从未同步时返回 None）
+pub fn count_pending_changes(db: &Database) -> Result<Option<usize>, AppError> {
+    let Some(sql) = load_base(db) else {
+        return Ok(None);
+    };
+    let base_db = Database::open_sql_export(&sql)?;
+    // 排除密钥模式下基线不含密钥，比较前同样置空本地密钥
+    let exclude_secrets = crate::settings::get_settings()
+        .webdav_sync
+        .is_some_and(|c| c.exclude_secrets);
+    let comparable = |provider: Option<&Provider>| -> Result<Option<Value>, AppError> {
+        let mut value = to_value(provider)?;
+        if exclude_secrets {
+            if let Some(value) = value.as_mut() {
+                crate::services::sync_secrets::strip_secrets(value);
+            }
+        }
+        Ok(value)
+    };
+
+    let mut count = 0;
+    for app in SYNC_APPS {
+        let key = app.as_str();
+        let local = db.get_all_providers(key)?;
+        let base = base_db.get_all_providers(key)?;
+        let ids: BTreeSet<&String> = local.keys().chain(base.keys()).collect();
+        for id in ids {
+            if comparable(local.get(id))? != comparable(base.get(id))? {
+                count += 1;
+            }
+        }
+    }
+    Ok(Some(count))
+}
+
+/// 解决一个冲突，返回剩余冲突
+pub fn resolve_conflict(
+    db: &Database,
+    app: &str,
+    id: &str,
+    resolution: ConflictResolution,
+    custom: Option<Provider>,
+) -> Result<Vec<ProviderConflict>, AppError> {
+    let mut conflicts = load_conflicts();
+    let Some(index) = conflicts.iter().position(|c| c.app == app && c.id == id) else {
+        return Err(AppError::InvalidInput(format!(
+            "不存在待解决的冲突: {app}/{id}"
+        )));
+    };
+    let conflict = conflicts.remove(index);
+
+    let chosen = match resolution {
+        ConflictResolution::Local => conflict.local,
+        ConflictResolution::Remote => conflict.remote,
+        ConflictResolution::Custom => Some(custom.ok_or_else(|| {
+            AppError::InvalidInput("自定义解决方式需要提供供应商内容".to_string())
+        })?),
+    };
+
+    match chosen {
+        Some(mut provider) => {
+            provider.id = id.to_string();
+            db.save_provider(app, &provider)?;
+        }
+        None => db.delete_provider(app, id)?,
+    }
+
+    save_conflicts(&conflicts)?;
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn one_sided_changes_do_not_conflict() {
+        let base = json!({ "name": "A", "settingsConfig": { "env": { "K": "1" } } });
+        let remote = json!({ "name": "A", "settingsConfig": { "env": { "K": "2" } } });
+        assert_eq!(
+            merge_provider(Some(&base), Some(&base), Some(&remote)),
+            ProviderOutcome::TakeRemote(Some(remote.clone()))
+        );
+        assert_eq!(
+            merge_provider(Some(&base), Some(&remote), Some(&base)),
+            ProviderOutcome::Unchanged
+        );
+    }
+
+    #[test]
+    fn disjoint_field_edits_are_merged() {
+        let base = json!({ "name": "A", "settingsConfig": { "env": { "K": "1", "U": "x" } } });
+        let local = json!({ "name": "A2", "settingsConfig": { "env": { "K": "1", "U": "x" } } });
+        let remote = json!({ "name": "A", "settingsConfig": { "env": { "K": "1", "U": "y" } } });
+        assert_eq!(
+            merge_provider(Some(&base), Some(&local), Some(&remote)),
+            ProviderOutcome::Merged(
+                json!({ "name": "A2", "settingsConfig": { "env": { "K": "1", "U": "y" } } })
+            )
+        );
+    }
+
+    #[test]
+    fn same_field_edited_differently_is_a_conflict() {
+        let base = json!({ "settingsConfig": { "env": { "K": "1" } } });
+        let local = json!({ "settingsConfig": { "env": { "K": "2" } } });
+        let remote = json!({ "settingsConfig": { "env": { "K": "3" } } });
+        assert_eq!(
+            merge_provider(Some(&base), Some(&local), Some(&remote)),
+            ProviderOutcome::Conflict(vec!["/settingsConfig/env/K".to_string()])
+        );
+    }
+
+    #[test]
+    fn delete_versus_edit_is_a_conflict() {
+        let base = json!({ "name": "A" });
+        let local = json!({ "name": "B" });
+        assert_eq!(
+            merge_provider(Some(&base), Some(&local), None),
+            ProviderOutcome::Conflict(vec!["/".to_string()])
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! 同步时排除密钥
+//!
+//! 启用 WebDAV 配置中的 `excludeSecrets` 后，上传的快照只包含供应商结构、名称、
+//! 端点与 MCP 服务器，API Key / Token 等字段被置空；各字段的本地值按设备保存在
+//! 系统钥匙串中（每个供应商 / MCP 服务器一条），下载或合并后再回填。
+//! 这样即使同步文件泄露，也不会一次性暴露所有设备上的密钥。
+
+use std::collections::BTreeMap;
+
+#[cfg(feature = "gui")]
+use serde::de::DeserializeOwned;
+#[cfg(feature = "gui")]
+use serde::Serialize;
+use serde_json::Value;
+
+#[cfg(feature = "gui")]
+use crate::app_config::AppType;
+#[cfg(feature = "gui")]
+use crate::database::Database;
+#[cfg(feature = "gui")]
+use crate::error::AppError;
+
+#[cfg(feature = "gui")]
+const SYNC_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// 钥匙串键前缀
+#[cfg(feature = "gui")]
+const SECRET_KEY_PREFIX: &str = "sync-secret";
+
+/// 被剥离的密钥：JSON Pointer -> 原值
+pub type SecretMap = BTreeMap<String, String>;
+
+/// 判断字段名是否表示密钥（API Key、Token、密码等）
+pub fn is_secret_field(name: &str) -> bool {
+    let normalized: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    normalized == "authorization"
+        || ["apikey", "token", "secret", "password", "credentials"]
+            .iter()
+            .any(|suffix| normalized.ends_with(suffix))
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn strip_at(value: &mut Value, path: &str, secrets: &mut SecretMap) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let child_path = format!("{path}/{}", escape_pointer(key));
+                match child {
+                    Value::String(s) if is_secret_field(key) => {
+                        if !s.is_empty() {
+                            secrets.insert(child_path, std::mem::take(s));
+                        }
+                    }
+                    _ => strip_at(child, &child_path, secrets),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                strip_at(child, &format!("{path}/{index}"), secrets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 将值中的密钥字段置空，返回被剥离的原值
+pub fn strip_secrets(value: &mut Value) -> SecretMap {
+    let mut secrets = SecretMap::new();
+    strip_at(value, "", &mut secrets);
+    secrets
+}
+
+/// 回填本地保存的密钥（仅填充仍为空的字段），返回是否有修改
+#[cfg(any(feature = "gui", test))]
+pub fn restore_secrets(value: &mut Value, secrets: &SecretMap) -> bool {
+    let mut changed = false;
+    for (pointer, secret) in secrets {
+        if let Some(Value::String(current)) = value.pointer_mut(pointer) {
+            if current.is_empty() {
+                *current = secret.clone();
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(feature = "gui")]
+fn secret_key(scope: &str, id: &str) -> String {
+    format!("{SECRET_KEY_PREFIX}:{scope}:{id}")
+}
+
+#[cfg(feature = "gui")]
+fn stash(scope: &str, id: &str, secrets: &SecretMap) -> Result<(), AppError> {
+    if secrets.is_empty() {
+        return Ok(());
+    }
+    let json = serde_json::to_string(secrets).map_err(|e| AppError::JsonSerialize { source: e })?;
+    crate::secret_store::set_secret(&secret_key(scope, id), &json)
+}
+
+#[cfg(feature = "gui")]
+fn load_stash(scope: &str, id: &str) -> Result<Option<SecretMap>, AppError> {
+    let Some(json) = crate::secret_store::get_secret(&secret_key(scope, id))? else {
+        return Ok(None);
+    };
+    Ok(serde_json::from_str(&json).ok())
+}
+
+#[cfg(feature = "gui")]
+fn to_value<T: Serialize>(item: &T) -> Result<Value, AppError> {
+    serde_json::to_value(item).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+#[cfg(feature = "gui")]
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, AppError> {
+    serde_json::from_value(value).map_err(|e| AppError::Message(format!("同步数据无效: {e}")))
+}
+
+/// 将本地数据库中的密钥保存到本设备的钥匙串
+#[cfg(feature = "gui")]
+pub fn stash_local_secrets(db: &Database) -> Result<usize, AppError> {
+    let mut count = 0;
+    for app in SYNC_APPS {
+        for (id, provider) in db.get_all_providers(app.as_str())? {
+            let secrets = strip_secrets(&mut to_value(&provider)?);
+            stash(app.as_str(), &id, &secrets)?;
+            count += secrets.len();
+        }
+    }
+    for (id, server) in db.get_all_mcp_servers()? {
+        let secrets = strip_secrets(&mut to_value(&server)?);
+        stash("mcp", &id, &secrets)?;
+        count += secrets.len();
+    }
+    Ok(count)
+}
+
+/// 导出不含密钥的 SQL 快照（同时把本地密钥保存到钥匙串）
+#[cfg(feature = "gui")]
+pub fn export_without_secrets(db: &Database) -> Result<String, AppError> {
+    stash_local_secrets(db)?;
+
+    let copy = Database::open_sql_export(&db.export_sql_string()?)?;
+    for app in SYNC_APPS {
+        for (_, provider) in copy.get_all_providers(app.as_str())? {
+            let mut value = to_value(&provider)?;
+            if !strip_secrets(&mut value).is_empty() {
+                copy.save_provider(app.as_str(), &from_value(value)?)?;
+            }
+        }
+    }
+    for (_, server) in copy.get_all_mcp_servers()? {
+        let mut value = to_value(&server)?;
+        if !strip_secrets(&mut value).is_empty() {
+            copy.save_mcp_server(&from_value(value)?)?;
+        }
+    }
+    copy.export_sql_string()
+}
+
+/// 用本设备保存的密钥回填数据库中被置空的字段，返回回填的条目数
+#[cfg(feature = "gui")]
+pub fn restore_local_secrets(db: &Database) -> Result<usize, AppError> {
+    let mut restored = 0;
+    for app in SYNC_APPS {
+        for (id, provider) in db.get_all_providers(app.as_str())? {
+            let Some(secrets) = load_stash(app.as_str(), &id)? else {
+                continue;
+            };
+            let mut value = to_value(&provider)?;
+            if restore_secrets(&mut value, &secrets) {
+                db.save_provider(app.as_str(), &from_value(value)?)?;
+                restored += 1;
+            }
+        }
+    }
+    for (id, server) in db.get_all_mcp_servers()? {
+        let Some(secrets) = load_stash("mcp", &id)? else {
+            continue;
+        };
+        let mut value = to_value(&server)?;
+        if restore_secrets(&mut value, &secrets) {
+            db.save_mcp_server(&from_value(value)?)?;
+            restored += 1;
+        }
+    }
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_secret_field_names() {
+        for name in [
+            "ANTHROPIC_AUTH_TOKEN",
+            "ANTHROPIC_API_KEY",
+            "OPENAI_API_KEY",
+            "apiKey",
+            "accessToken",
+            "Authorization",
+            "GITHUB_PERSONAL_ACCESS_TOKEN",
+        ] {
+            assert!(is_secret_field(name), "{name} should be secret");
+        }
+        for name in [
+            "ANTHROPIC_BASE_URL",
+            "CLAUDE_CODE_MAX_OUTPUT_TOKENS",
+            "model",
+            "name",
+        ] {
+            assert!(!is_secret_field(name), "{name} should not be secret");
+        }
+    }
+
+    #[test]
+    fn strip_and_restore_round_trip() {
+        let original = json!({
+            "env": {
+                "ANTHROPIC_AUTH_TOKEN": "sk-1",
+                "ANTHROPIC_BASE_URL": "https://api.example.com"
+            },
+            "headers": [{ "Authorization": "Bearer x" }]
+        });
+
+        let mut value = original.clone();
+        let secrets = strip_secrets(&mut value);
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(value["env"]["ANTHROPIC_AUTH_TOKEN"], json!(""));
+        assert_eq!(
+            value["env"]["ANTHROPIC_BASE_URL"],
+            json!("https://api.example.com")
+        );
+
+        assert!(restore_secrets(&mut value, &secrets));
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn restore_does_not_overwrite_values_set_elsewhere() {
+        let mut value = json!({ "env": { "OPENAI_API_KEY": "sk-new" } });
+        let secrets = SecretMap::from([("/env/OPENAI_API_KEY".to_string(), "sk-old".to_string())]);
+        assert!(!restore_secrets(&mut value, &secrets));
+        assert_eq!(value["env"]["OPENAI_API_KEY"], json!("sk-new"));
+    }
+}
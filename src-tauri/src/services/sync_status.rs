@@ -0,0 +1,176 @@
+//! 同步状态跟踪
+//!
+//! 记录最近一次远程同步的时间、操作与错误（持久化到 `~/.cc-switch/sync/status.json`），
+//! 并通过 `sync://progress` 事件向前端/托盘推送进度，便于展示本机是否已是最新。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::database::Database;
+use crate::error::AppError;
+
+use super::sync_merge;
+
+/// 同步进度事件名
+pub const SYNC_PROGRESS_EVENT: &str = "sync://progress";
+
+const STATUS_FILE: &str = "status.json";
+
+static IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+
+/// 同步操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncOperation {
+    Upload,
+    Download,
+    Merge,
+}
+
+/// 同步阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStage {
+    Started,
+    Completed,
+    Failed,
+}
+
+/// `sync://progress` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub operation: SyncOperation,
+    pub stage: SyncStage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedSyncState {
+    last_sync_at: Option<i64>,
+    last_operation: Option<SyncOperation>,
+    last_error: Option<String>,
+    last_error_at: Option<i64>,
+}
+
+/// 同步状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub configured: bool,
+    pub in_progress: bool,
+    pub last_sync_at: Option<i64>,
+    pub last_operation: Option<SyncOperation>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+    /// 相对上次同步有改动的供应商数（从未同步时为 null）
+    pub pending_changes: Option<usize>,
+    pub conflicts: usize,
+    /// 已同步过、无本地改动且无冲突
+    pub up_to_date: bool,
+}
+
+fn load_state() -> PersistedSyncState {
+    std::fs::read_to_string(sync_merge::sync_dir().join(STATUS_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &PersistedSyncState) {
+    let dir = sync_merge::sync_dir();
+    let result = std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::io(&dir, e))
+        .and_then(|_| {
+            serde_json::to_vec_pretty(state).map_err(|e| AppError::JsonSerialize { source: e })
+        })
+        .and_then(|json| crate::config::atomic_write(&dir.join(STATUS_FILE), &json));
+    if let Err(e) = result {
+        log::warn!("保存同步状态失败: {e}");
+    }
+}
+
+fn emit(app: &AppHandle, progress: SyncProgress) {
+    if let Err(e) = app.emit(SYNC_PROGRESS_EVENT, &progress) {
+        log::error!("发射同步进度事件失败: {e}");
+    }
+}
+
+/// 是否有同步正在进行
+pub fn is_in_progress() -> bool {
+    IN_PROGRESS.load(Ordering::SeqCst)
+}
+
+/// 标记同步开始
+pub fn begin(app: &AppHandle, operation: SyncOperation) {
+    IN_PROGRESS.store(true, Ordering::SeqCst);
+    emit(
+        app,
+        SyncProgress {
+            operation,
+            stage: SyncStage::Started,
+            error: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+}
+
+/// 记录同步结果并发出完成/失败事件
+pub fn finish<T>(app: &AppHandle, operation: SyncOperation, result: &Result<T, String>) {
+    IN_PROGRESS.store(false, Ordering::SeqCst);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let mut state = load_state();
+    let (stage, error) = match result {
+        Ok(_) => {
+            state.last_sync_at = Some(now);
+            state.last_operation = Some(operation);
+            state.last_error = None;
+            state.last_error_at = None;
+            (SyncStage::Completed, None)
+        }
+        Err(e) => {
+            state.last_error = Some(e.clone());
+            state.last_error_at = Some(now);
+            (SyncStage::Failed, Some(e.clone()))
+        }
+    };
+    save_state(&state);
+
+    emit(
+        app,
+        SyncProgress {
+            operation,
+            stage,
+            error,
+            timestamp: now,
+        },
+    );
+}
+
+/// 获取当前同步状态
+pub fn get_status(db: &Database) -> Result<SyncStatus, AppError> {
+    let state = load_state();
+    let pending_changes = sync_merge::count_pending_changes(db).unwrap_or_else(|e| {
+        log::warn!("统计待同步改动失败: {e}");
+        None
+    });
+    let conflicts = sync_merge::load_conflicts().len();
+
+    Ok(SyncStatus {
+        configured: crate::settings::get_settings().webdav_sync.is_some(),
+        in_progress: is_in_progress(),
+        last_sync_at: state.last_sync_at,
+        last_operation: state.last_operation,
+        last_error: state.last_error,
+        last_error_at: state.last_error_at,
+        up_to_date: pending_changes == Some(0) && conflicts == 0,
+        pending_changes,
+        conflicts,
+    })
+}
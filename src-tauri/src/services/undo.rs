@@ -0,0 +1,155 @@
+//! 破坏性操作的撤销日志
+//!
+//! 记录本次运行中删除的供应商、删除的 MCP 服务器以及设置修改前的内容，通过
+//! [`undo_last_operation`] 按后进先出的顺序撤销。日志只保存在内存中，退出应用即清空；
+//! 更早的改动通过供应商历史版本与备份恢复。
+
+use std::sync::Mutex;
+
+#[cfg(feature = "gui")]
+use serde::Serialize;
+
+use crate::app_config::{AppType, McpServer};
+#[cfg(feature = "gui")]
+use crate::error::AppError;
+use crate::provider::Provider;
+#[cfg(feature = "gui")]
+use crate::services::mcp::McpService;
+#[cfg(feature = "gui")]
+use crate::services::ProviderService;
+use crate::settings::AppSettings;
+#[cfg(feature = "gui")]
+use crate::store::AppState;
+
+/// 最多保留的撤销记录数
+const MAX_UNDO_ENTRIES: usize = 20;
+
+/// 可撤销的操作（保存撤销所需的原始数据）
+#[derive(Debug, Clone)]
+pub enum UndoEntry {
+    ProviderDeleted {
+        app_type: AppType,
+        provider: Box<Provider>,
+    },
+    McpServerDeleted {
+        server: Box<McpServer>,
+    },
+    SettingsChanged {
+        previous: Box<AppSettings>,
+    },
+}
+
+/// 已撤销操作的描述
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoSummary {
+    /// `providerDeleted`、`mcpServerDeleted` 或 `settingsChanged`
+    pub kind: &'static str,
+    /// 供应商所属的应用
+    pub app_type: Option<String>,
+    /// 恢复的供应商或 MCP 服务器 ID
+    pub target_id: Option<String>,
+    pub target_name: Option<String>,
+    /// 剩余可撤销的操作数
+    pub remaining: usize,
+}
+
+static JOURNAL: Mutex<Vec<UndoEntry>> = Mutex::new(Vec::new());
+
+#[cfg(feature = "gui")]
+impl UndoEntry {
+    fn summary(&self, remaining: usize) -> UndoSummary {
+        let (kind, app_type, target_id, target_name) = match self {
+            Self::ProviderDeleted { app_type, provider } => (
+                "providerDeleted",
+                Some(app_type.as_str().to_string()),
+                Some(provider.id.clone()),
+                Some(provider.name.clone()),
+            ),
+            Self::McpServerDeleted { server } => (
+                "mcpServerDeleted",
+                None,
+                Some(server.id.clone()),
+                Some(server.name.clone()),
+            ),
+            Self::SettingsChanged { .. } => ("settingsChanged", None, None, None),
+        };
+        UndoSummary {
+            kind,
+            app_type,
+            target_id,
+            target_name,
+            remaining,
+        }
+    }
+
+    fn apply(self, state: &AppState) -> Result<(), AppError> {
+        match self {
+            Self::ProviderDeleted { app_type, provider } => {
+                if state
+                    .db
+                    .get_all_providers(app_type.as_str())?
+                    .contains_key(&provider.id)
+                {
+                    return Err(AppError::Message(format!(
+                        "无法撤销删除：供应商 {} 已重新创建",
+                        provider.id
+                    )));
+                }
+                ProviderService::add(state, app_type, *provider).map(|_| ())
+            }
+            Self::McpServerDeleted { server } => {
+                if state.db.get_all_mcp_servers()?.contains_key(&server.id) {
+                    return Err(AppError::Message(format!(
+                        "无法撤销删除：MCP 服务器 {} 已重新创建",
+                        server.id
+                    )));
+                }
+                McpService::upsert_server(state, *server)
+            }
+            Self::SettingsChanged { previous } => {
+                let mut previous = *previous;
+                // 只读模式等状态与当前供应商在此之后可能已通过其他操作改变，保持现状
+                let local = crate::settings::get_settings();
+                previous.keep_command_managed_state(&local);
+                previous.keep_current_providers(&local);
+                crate::settings::update_settings(previous)
+            }
+        }
+    }
+}
+
+/// 记录一次可撤销的操作（超过上限时丢弃最早的记录）
+pub fn record(entry: UndoEntry) {
+    match JOURNAL.lock() {
+        Ok(mut journal) => {
+            journal.push(entry);
+            if journal.len() > MAX_UNDO_ENTRIES {
+                journal.remove(0);
+            }
+        }
+        Err(e) => log::warn!("记录撤销日志失败: {e}"),
+    }
+}
+
+/// 撤销最近一次操作，没有可撤销的操作时返回 `None`
+///
+/// 撤销失败（如同 ID 的供应商已重新创建）时记录保留在日志中，处理冲突后可以重试。
+#[cfg(feature = "gui")]
+pub fn undo_last_operation(state: &AppState) -> Result<Option<UndoSummary>, AppError> {
+    let (entry, remaining) = {
+        let mut journal = JOURNAL.lock()?;
+        match journal.pop() {
+            Some(entry) => (entry, journal.len()),
+            None => return Ok(None),
+        }
+    };
+    let summary = entry.summary(remaining);
+    if let Err(e) = entry.clone().apply(state) {
+        record(entry);
+        return Err(e);
+    }
+    log::info!("已撤销操作 {}（{:?}）", summary.kind, summary.target_id);
+    Ok(Some(summary))
+}
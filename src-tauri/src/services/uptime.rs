@@ -0,0 +1,287 @@
+//! 供应商可用性监控
+//!
+//! 可用性监控（设置 `uptimeMonitor`）开启后，后台按配置的间隔检查各应用的当前供应商与
+//! 故障转移队列中的供应商，每次检查记录到数据库。检查方式与 Key 检查相同（请求模型
+//! 列表接口，见 [`super::key_check`]），不消耗 token；被限流视为可用。
+//!
+//! 查询时按供应商统计可用率，连续失败的检查合并为一次故障，记录开始与恢复时间。
+//! 没有 API Key 的供应商（如官方登录）无法检查，不会记录。
+
+use std::collections::{BTreeMap, HashMap};
+
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::key_check::{KeyCheckReason, KeyCheckService};
+
+/// 监控的应用（OpenCode 不支持 Key 检查）
+const MONITORED_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+/// 一次可用性检查
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimeCheck {
+    pub app_type: String,
+    pub provider_id: String,
+    pub up: bool,
+    pub http_status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    /// Key 检查的结论（如 `valid`、`timeout`）
+    pub reason: String,
+    /// Unix 时间戳（秒）
+    pub checked_at: i64,
+}
+
+/// 一次故障（连续失败的检查）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimeIncident {
+    /// 第一次失败的检查时间
+    pub started_at: i64,
+    /// 恢复后第一次成功的检查时间，仍未恢复时为空
+    pub ended_at: Option<i64>,
+    pub failed_checks: u32,
+    /// 最后一次失败的原因
+    pub reason: String,
+}
+
+/// 供应商的可用性统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUptime {
+    pub app_type: String,
+    pub provider_id: String,
+    /// 已删除的供应商为空
+    pub provider_name: String,
+    pub total_checks: u32,
+    pub up_checks: u32,
+    /// 可用率（百分比）
+    pub availability: f64,
+    /// 成功检查的平均耗时（毫秒）
+    pub avg_latency_ms: Option<u64>,
+    pub last_checked_at: i64,
+    pub last_up: bool,
+    /// 按时间先后
+    pub incidents: Vec<UptimeIncident>,
+}
+
+fn reason_name(reason: KeyCheckReason) -> String {
+    serde_json::to_value(reason)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// 按供应商汇总检查记录（记录需按时间先后排列）
+fn summarize(
+    checks: &[UptimeCheck],
+    names: &HashMap<(String, String), String>,
+) -> Vec<ProviderUptime> {
+    let mut grouped: BTreeMap<(String, String), Vec<&UptimeCheck>> = BTreeMap::new();
+    for check in checks {
+        grouped
+            .entry((check.app_type.clone(), check.provider_id.clone()))
+            .or_default()
+            .push(check);
+    }
+
+    grouped
+        .into_iter()
+        .filter_map(|(key, checks)| {
+            let last = *checks.last()?;
+            let mut incidents: Vec<UptimeIncident> = Vec::new();
+            let mut open = false;
+            let mut latencies = Vec::new();
+            for check in &checks {
+                if check.up {
+                    latencies.extend(check.latency_ms);
+                    if open {
+                        if let Some(incident) = incidents.last_mut() {
+                            incident.ended_at = Some(check.checked_at);
+                        }
+                        open = false;
+                    }
+                } else if open {
+                    if let Some(incident) = incidents.last_mut() {
+                        incident.failed_checks += 1;
+                        incident.reason = check.reason.clone();
+                    }
+                } else {
+                    incidents.push(UptimeIncident {
+                        started_at: check.checked_at,
+                        ended_at: None,
+                        failed_checks: 1,
+                        reason: check.reason.clone(),
+                    });
+                    open = true;
+                }
+            }
+
+            let total_checks = checks.len() as u32;
+            let up_checks = checks.iter().filter(|check| check.up).count() as u32;
+            Some(ProviderUptime {
+                provider_name: names.get(&key).cloned().unwrap_or_default(),
+                app_type: key.0,
+                provider_id: key.1,
+                total_checks,
+                up_checks,
+                availability: f64::from(up_checks) * 100.0 / f64::from(total_checks),
+                avg_latency_ms: (!latencies.is_empty())
+                    .then(|| latencies.iter().sum::<u64>() / latencies.len() as u64),
+                last_checked_at: last.checked_at,
+                last_up: last.up,
+                incidents,
+            })
+        })
+        .collect()
+}
+
+/// 应用中需要监控的供应商：当前供应商与故障转移队列
+fn monitored_ids(db: &Database, app_type: &AppType) -> Result<Vec<String>, AppError> {
+    let mut ids = Vec::new();
+    if let Some(current) = crate::settings::get_effective_current_provider(db, app_type)? {
+        ids.push(current);
+    }
+    for item in db.get_failover_queue(app_type.as_str())? {
+        if !ids.contains(&item.provider_id) {
+            ids.push(item.provider_id);
+        }
+    }
+    Ok(ids)
+}
+
+/// 检查一轮所有需要监控的供应商并保存结果
+pub async fn check_all(db: &Database) -> Result<Vec<UptimeCheck>, AppError> {
+    let mut targets = Vec::new();
+    for app_type in MONITORED_APPS {
+        for id in monitored_ids(db, &app_type)? {
+            if let Some(provider) = db.get_provider_by_id(&id, app_type.as_str())? {
+                targets.push((app_type.clone(), provider));
+            }
+        }
+    }
+
+    let results = join_all(targets.iter().map(|(app_type, provider)| async move {
+        match KeyCheckService::validate(app_type, provider).await {
+            Ok(result) => Some(UptimeCheck {
+                app_type: app_type.as_str().to_string(),
+                provider_id: provider.id.clone(),
+                up: matches!(
+                    result.reason,
+                    KeyCheckReason::Valid | KeyCheckReason::RateLimited
+                ),
+                http_status: result.http_status,
+                latency_ms: result.response_time_ms,
+                reason: reason_name(result.reason),
+                checked_at: result.checked_at,
+            }),
+            Err(e) => {
+                log::debug!("跳过可用性检查 {}/{}: {e}", app_type.as_str(), provider.id);
+                None
+            }
+        }
+    }))
+    .await;
+
+    let checks = results.into_iter().flatten().collect::<Vec<_>>();
+    for check in &checks {
+        db.record_uptime_check(check)?;
+    }
+    Ok(checks)
+}
+
+/// 最近若干小时内各供应商的可用性（按应用、供应商排列）
+pub fn get_uptime(
+    db: &Database,
+    app_type: Option<&AppType>,
+    hours: u32,
+) -> Result<Vec<ProviderUptime>, AppError> {
+    let since = chrono::Utc::now().timestamp() - i64::from(hours.max(1)) * 3600;
+    let checks = db.get_uptime_checks(app_type.map(AppType::as_str), since)?;
+    let mut names = HashMap::new();
+    for check in &checks {
+        let key = (check.app_type.clone(), check.provider_id.clone());
+        if names.contains_key(&key) {
+            continue;
+        }
+        if let Some(provider) = db.get_provider_by_id(&check.provider_id, &check.app_type)? {
+            names.insert(key, provider.name);
+        }
+    }
+    Ok(summarize(&checks, &names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(provider_id: &str, up: bool, checked_at: i64) -> UptimeCheck {
+        UptimeCheck {
+            app_type: "claude".to_string(),
+            provider_id: provider_id.to_string(),
+            up,
+            http_status: Some(if up { 200 } else { 503 }),
+            latency_ms: up.then_some(100),
+            reason: if up { "valid" } else { "upstream_error" }.to_string(),
+            checked_at,
+        }
+    }
+
+    #[test]
+    fn summarizes_availability_and_incidents() {
+        let checks = vec![
+            check("relay", true, 0),
+            check("relay", false, 300),
+            check("official", true, 300),
+            check("relay", false, 600),
+            check("relay", true, 900),
+            check("relay", false, 1200),
+        ];
+        let names = HashMap::from([(
+            ("claude".to_string(), "relay".to_string()),
+            "Relay".to_string(),
+        )]);
+
+        let summary = summarize(&checks, &names);
+        assert_eq!(summary.len(), 2);
+
+        let official = &summary[0];
+        assert_eq!(official.provider_id, "official");
+        assert_eq!(official.provider_name, "");
+        assert_eq!(official.availability, 100.0);
+        assert!(official.incidents.is_empty());
+
+        let relay = &summary[1];
+        assert_eq!(relay.provider_name, "Relay");
+        assert_eq!((relay.total_checks, relay.up_checks), (5, 2));
+        assert_eq!(relay.availability, 40.0);
+        assert_eq!(relay.avg_latency_ms, Some(100));
+        assert!(!relay.last_up);
+        assert_eq!(
+            relay.incidents,
+            vec![
+                UptimeIncident {
+                    started_at: 300,
+                    ended_at: Some(900),
+                    failed_checks: 2,
+                    reason: "upstream_error".to_string(),
+                },
+                UptimeIncident {
+                    started_at: 1200,
+                    ended_at: None,
+                    failed_checks: 1,
+                    reason: "upstream_error".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reason_names_match_serialized_form() {
+        assert_eq!(reason_name(KeyCheckReason::Valid), "valid");
+        assert_eq!(reason_name(KeyCheckReason::UpstreamError), "upstream_error");
+    }
+}
@@ -0,0 +1,333 @@
+//! 用量面板数据
+//!
+//! 基于会话日志用量（见 [`super::session_usage`]）与切换记录，为用量面板提供已聚合好的
+//! 数据：每个应用的每日 token、各供应商的用量、用量最多的模型与各供应商的切换次数，
+//! 前端不需要再处理原始记录。
+//!
+//! 统计会话日志需要读取文件，结果按统计范围缓存一分钟；切换记录每个应用只保留最近的
+//! 200 条，切换次数只统计其中的部分。
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use serde::Serialize;
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::session_usage::{self, SessionUsageRow, UsageRange};
+
+/// 缓存有效期
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 面板中显示的模型数
+const TOP_MODELS: usize = 10;
+
+/// 每个统计范围最近一次的结果
+static CACHE: Mutex<Vec<(UsageRange, Instant, UsageDashboard)>> = Mutex::new(Vec::new());
+
+/// token 用量合计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// 以上四项之和
+    pub total_tokens: u64,
+    pub message_count: u64,
+}
+
+impl TokenTotals {
+    fn add(&mut self, row: &SessionUsageRow) {
+        self.input_tokens += row.input_tokens;
+        self.output_tokens += row.output_tokens;
+        self.cache_read_tokens += row.cache_read_tokens;
+        self.cache_creation_tokens += row.cache_creation_tokens;
+        self.total_tokens += row.input_tokens
+            + row.output_tokens
+            + row.cache_read_tokens
+            + row.cache_creation_tokens;
+        self.message_count += row.message_count;
+    }
+}
+
+/// 应用某一天的用量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyAppTokens {
+    pub date: String,
+    pub app_type: String,
+    #[serde(flatten)]
+    pub tokens: TokenTotals,
+}
+
+/// 供应商的用量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTokens {
+    pub app_type: String,
+    /// 为空表示无法判断供应商的用量
+    pub provider_id: String,
+    /// 已删除的供应商为空
+    pub provider_name: String,
+    #[serde(flatten)]
+    pub tokens: TokenTotals,
+}
+
+/// 模型的用量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTokens {
+    pub model: String,
+    #[serde(flatten)]
+    pub tokens: TokenTotals,
+}
+
+/// 供应商的切换次数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchFrequency {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    pub count: u64,
+}
+
+/// 用量面板数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageDashboard {
+    pub range: UsageRange,
+    pub totals: TokenTotals,
+    /// 按日期、应用排列
+    pub daily: Vec<DailyAppTokens>,
+    /// 按总 token 从多到少排列
+    pub providers: Vec<ProviderTokens>,
+    /// 总 token 最多的模型
+    pub top_models: Vec<ModelTokens>,
+    /// 按切换次数从多到少排列
+    pub switches: Vec<SwitchFrequency>,
+    /// Unix 时间戳（秒）
+    pub generated_at: i64,
+}
+
+fn build_dashboard(
+    range: UsageRange,
+    rows: &[SessionUsageRow],
+    switch_counts: Vec<(String, String, u64)>,
+    names: &HashMap<(String, String), String>,
+) -> UsageDashboard {
+    let name = |app_type: &str, provider_id: &str| {
+        names
+            .get(&(app_type.to_string(), provider_id.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let mut totals = TokenTotals::default();
+    let mut daily: BTreeMap<(String, String), TokenTotals> = BTreeMap::new();
+    let mut providers: BTreeMap<(String, String), TokenTotals> = BTreeMap::new();
+    let mut models: BTreeMap<String, TokenTotals> = BTreeMap::new();
+    for row in rows {
+        totals.add(row);
+        daily
+            .entry((row.date.clone(), row.app_type.clone()))
+            .or_default()
+            .add(row);
+        providers
+            .entry((row.app_type.clone(), row.provider_id.clone()))
+            .or_default()
+            .add(row);
+        models.entry(row.model.clone()).or_default().add(row);
+    }
+
+    let mut providers = providers
+        .into_iter()
+        .map(|((app_type, provider_id), tokens)| ProviderTokens {
+            provider_name: name(&app_type, &provider_id),
+            app_type,
+            provider_id,
+            tokens,
+        })
+        .collect::<Vec<_>>();
+    providers.sort_by(|a, b| b.tokens.total_tokens.cmp(&a.tokens.total_tokens));
+
+    let mut top_models = models
+        .into_iter()
+        .map(|(model, tokens)| ModelTokens { model, tokens })
+        .collect::<Vec<_>>();
+    top_models.sort_by(|a, b| b.tokens.total_tokens.cmp(&a.tokens.total_tokens));
+    top_models.truncate(TOP_MODELS);
+
+    let mut switches = switch_counts
+        .into_iter()
+        .map(|(app_type, provider_id, count)| SwitchFrequency {
+            provider_name: name(&app_type, &provider_id),
+            app_type,
+            provider_id,
+            count,
+        })
+        .collect::<Vec<_>>();
+    switches.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.provider_id.cmp(&b.provider_id))
+    });
+
+    UsageDashboard {
+        range,
+        totals,
+        daily: daily
+            .into_iter()
+            .map(|((date, app_type), tokens)| DailyAppTokens {
+                date,
+                app_type,
+                tokens,
+            })
+            .collect(),
+        providers,
+        top_models,
+        switches,
+        generated_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+/// 起始日期当天零点（本地时区）的毫秒时间戳
+fn since_millis(since: &str) -> Option<i64> {
+    let day = NaiveDate::parse_from_str(since, "%Y-%m-%d").ok()?;
+    Local
+        .from_local_datetime(&day.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|time| time.timestamp_millis())
+}
+
+fn load_names(
+    db: &Database,
+    keys: impl Iterator<Item = (String, String)>,
+) -> Result<HashMap<(String, String), String>, AppError> {
+    let mut names = HashMap::new();
+    for (app_type, provider_id) in keys {
+        if provider_id.is_empty() || names.contains_key(&(app_type.clone(), provider_id.clone())) {
+            continue;
+        }
+        if let Some(provider) = db.get_provider_by_id(&provider_id, &app_type)? {
+            names.insert((app_type, provider_id), provider.name);
+        }
+    }
+    Ok(names)
+}
+
+/// 用量面板数据（一分钟内的重复查询使用缓存，`refresh` 为 true 时重新统计）
+pub fn get_dashboard(
+    db: &Database,
+    range: UsageRange,
+    refresh: bool,
+) -> Result<UsageDashboard, AppError> {
+    if !refresh {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((_, _, dashboard)) = cache
+            .iter()
+            .find(|(cached, at, _)| *cached == range && at.elapsed() < CACHE_TTL)
+        {
+            return Ok(dashboard.clone());
+        }
+    }
+
+    session_usage::sync(db)?;
+    let since = range.since();
+    let rows = db.get_session_usage(None, since.as_deref())?;
+    let switch_counts = db.get_provider_switch_counts(since.as_deref().and_then(since_millis))?;
+    let names = load_names(
+        db,
+        rows.iter()
+            .map(|row| (row.app_type.clone(), row.provider_id.clone()))
+            .chain(
+                switch_counts
+                    .iter()
+                    .map(|(app_type, provider_id, _)| (app_type.clone(), provider_id.clone())),
+            ),
+    )?;
+    let dashboard = build_dashboard(range, &rows, switch_counts, &names);
+
+    let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|(cached, _, _)| *cached != range);
+    cache.push((range, Instant::now(), dashboard.clone()));
+    Ok(dashboard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(app_type: &str, provider_id: &str, date: &str, model: &str) -> SessionUsageRow {
+        SessionUsageRow {
+            app_type: app_type.to_string(),
+            provider_id: provider_id.to_string(),
+            date: date.to_string(),
+            model: model.to_string(),
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_read_tokens: 1000,
+            message_count: 2,
+            ..SessionUsageRow::default()
+        }
+    }
+
+    #[test]
+    fn aggregates_daily_provider_model_and_switch_data() {
+        let rows = vec![
+            row("claude", "relay", "2025-06-01", "sonnet"),
+            row("claude", "relay", "2025-06-01", "opus"),
+            row("claude", "official", "2025-06-02", "sonnet"),
+            row("codex", "", "2025-06-02", "gpt-5"),
+        ];
+        let names = HashMap::from([
+            (
+                ("claude".to_string(), "relay".to_string()),
+                "Relay".to_string(),
+            ),
+            (
+                ("claude".to_string(), "official".to_string()),
+                "Official".to_string(),
+            ),
+        ]);
+        let switches = vec![
+            ("claude".to_string(), "official".to_string(), 1),
+            ("claude".to_string(), "relay".to_string(), 4),
+        ];
+
+        let dashboard = build_dashboard(UsageRange::All, &rows, switches, &names);
+        assert_eq!(dashboard.totals.total_tokens, 4 * 1150);
+        assert_eq!(dashboard.totals.message_count, 8);
+
+        let daily = dashboard
+            .daily
+            .iter()
+            .map(|day| {
+                (
+                    day.date.as_str(),
+                    day.app_type.as_str(),
+                    day.tokens.total_tokens,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            daily,
+            vec![
+                ("2025-06-01", "claude", 2300),
+                ("2025-06-02", "claude", 1150),
+                ("2025-06-02", "codex", 1150),
+            ]
+        );
+
+        assert_eq!(dashboard.providers[0].provider_name, "Relay");
+        assert_eq!(dashboard.providers[0].tokens.total_tokens, 2300);
+        assert_eq!(dashboard.top_models[0].model, "sonnet");
+        assert_eq!(dashboard.top_models[0].tokens.message_count, 4);
+        assert_eq!(dashboard.switches[0].provider_name, "Relay");
+        assert_eq!(dashboard.switches[0].count, 4);
+    }
+}
@@ -0,0 +1,278 @@
+//! 用量报告导出
+//!
+//! 把成本报告（见 [`super::cost_report`]，按月汇总）与切换记录合并为每月、每个供应商
+//! 一行的报告，导出为 CSV 或 JSON，方便报销与团队内部分摊费用。
+//!
+//! 切换记录每个应用只保留最近的 200 条，较早月份的切换次数可能不完整。
+
+use std::collections::BTreeMap;
+
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::cost_report::{self, CostPeriod, CostReport};
+use crate::services::session_usage::UsageRange;
+
+/// 记录切换历史的应用
+const SWITCH_APPS: [AppType; 4] = [
+    AppType::Claude,
+    AppType::Codex,
+    AppType::Gemini,
+    AppType::OpenCode,
+];
+
+/// CSV 表头（与 [`UsageReportRow`] 的字段顺序一致）
+const CSV_HEADER: &str = "month,app,provider_id,provider_name,input_tokens,output_tokens,\
+cache_read_tokens,cache_creation_tokens,cost_usd,switches";
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+/// 供应商某个月的用量、成本与切换次数
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReportRow {
+    /// `YYYY-MM`
+    pub month: String,
+    pub app_type: String,
+    /// 为空表示无法判断供应商的用量
+    pub provider_id: String,
+    pub provider_name: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_creation_tokens: u64,
+    /// USD
+    pub cost: String,
+    /// 当月切换到该供应商的次数
+    pub switches: u64,
+}
+
+/// 用量报告
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub range: UsageRange,
+    /// USD
+    pub total_cost: String,
+    /// 按月份、应用、供应商排列
+    pub rows: Vec<UsageReportRow>,
+    /// 没有定价、未计入成本的模型
+    pub unpriced_models: Vec<String>,
+    /// Unix 时间戳（秒）
+    pub generated_at: i64,
+}
+
+/// 合并成本报告与切换记录（`switches` 为 `(应用, 毫秒时间戳, 供应商)`）
+fn build_report(
+    costs: CostReport,
+    switches: &[(String, i64, String)],
+    since: Option<&str>,
+) -> UsageReport {
+    let mut rows: BTreeMap<(String, String, String), UsageReportRow> = BTreeMap::new();
+    for provider in costs.providers {
+        for period in provider.periods {
+            rows.insert(
+                (
+                    period.period.clone(),
+                    provider.app_type.clone(),
+                    provider.provider_id.clone(),
+                ),
+                UsageReportRow {
+                    month: period.period,
+                    app_type: provider.app_type.clone(),
+                    provider_id: provider.provider_id.clone(),
+                    provider_name: provider.provider_name.clone(),
+                    input_tokens: period.input_tokens,
+                    output_tokens: period.output_tokens,
+                    cache_read_tokens: period.cache_read_tokens,
+                    cache_creation_tokens: period.cache_creation_tokens,
+                    cost: period.cost,
+                    switches: 0,
+                },
+            );
+        }
+    }
+
+    for (app_type, switched_at, provider_id) in switches {
+        let Some(date) = Local
+            .timestamp_millis_opt(*switched_at)
+            .single()
+            .map(|time| time.format("%Y-%m-%d").to_string())
+        else {
+            continue;
+        };
+        if since.is_some_and(|since| date.as_str() < since) {
+            continue;
+        }
+        let month = date[..7].to_string();
+        rows.entry((month.clone(), app_type.clone(), provider_id.clone()))
+            .or_insert_with(|| UsageReportRow {
+                month,
+                app_type: app_type.clone(),
+                provider_id: provider_id.clone(),
+                cost: "0.000000".to_string(),
+                ..UsageReportRow::default()
+            })
+            .switches += 1;
+    }
+
+    UsageReport {
+        range: costs.range,
+        total_cost: costs.total_cost,
+        rows: rows.into_values().collect(),
+        unpriced_models: costs.unpriced_models,
+        generated_at: chrono::Utc::now().timestamp(),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(report: &UsageReport) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for row in &report.rows {
+        let fields = [
+            csv_field(&row.month),
+            csv_field(&row.app_type),
+            csv_field(&row.provider_id),
+            csv_field(&row.provider_name),
+            row.input_tokens.to_string(),
+            row.output_tokens.to_string(),
+            row.cache_read_tokens.to_string(),
+            row.cache_creation_tokens.to_string(),
+            row.cost.clone(),
+            row.switches.to_string(),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// 生成用量报告（先统计新增的会话日志）并按指定格式导出
+pub fn export_usage_report(
+    db: &Database,
+    range: UsageRange,
+    format: ReportFormat,
+) -> Result<String, AppError> {
+    let costs = cost_report::get_cost_report(db, range, CostPeriod::Month)?;
+    let mut switches = Vec::new();
+    for app_type in SWITCH_APPS {
+        for (switched_at, provider_id) in db.get_provider_switch_timeline(app_type.as_str())? {
+            switches.push((app_type.as_str().to_string(), switched_at, provider_id));
+        }
+    }
+    let since = range.since();
+    let mut report = build_report(costs, &switches, since.as_deref());
+
+    // 只有切换记录的行补充供应商名称
+    for row in report
+        .rows
+        .iter_mut()
+        .filter(|row| row.provider_name.is_empty() && !row.provider_id.is_empty())
+    {
+        if let Some(provider) = db.get_provider_by_id(&row.provider_id, &row.app_type)? {
+            row.provider_name = provider.name;
+        }
+    }
+
+    match format {
+        ReportFormat::Csv => Ok(to_csv(&report)),
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(&report).map_err(|e| AppError::JsonSerialize { source: e })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cost_report::{PeriodCost, ProviderCost};
+
+    fn local_millis(date: &str) -> i64 {
+        let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Local
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn merges_monthly_costs_with_switch_counts() {
+        let costs = CostReport {
+            range: UsageRange::All,
+            period: CostPeriod::Month,
+            total_cost: "1.500000".to_string(),
+            providers: vec![ProviderCost {
+                app_type: "claude".to_string(),
+                provider_id: "relay".to_string(),
+                provider_name: "Relay, Inc".to_string(),
+                total_cost: "1.500000".to_string(),
+                periods: vec![PeriodCost {
+                    period: "2025-06".to_string(),
+                    input_tokens: 1000,
+                    output_tokens: 200,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                    cost: "1.500000".to_string(),
+                }],
+            }],
+            unpriced_models: Vec::new(),
+        };
+        let switches = vec![
+            (
+                "claude".to_string(),
+                local_millis("2025-05-31"),
+                "relay".to_string(),
+            ),
+            (
+                "claude".to_string(),
+                local_millis("2025-06-02"),
+                "relay".to_string(),
+            ),
+            (
+                "claude".to_string(),
+                local_millis("2025-06-03"),
+                "relay".to_string(),
+            ),
+            (
+                "codex".to_string(),
+                local_millis("2025-06-04"),
+                "openai".to_string(),
+            ),
+        ];
+
+        let report = build_report(costs, &switches, Some("2025-06-01"));
+        assert_eq!(report.rows.len(), 2);
+        assert_eq!(report.rows[0].provider_id, "relay");
+        assert_eq!(report.rows[0].switches, 2);
+        assert_eq!(report.rows[1].provider_id, "openai");
+        assert_eq!(report.rows[1].cost, "0.000000");
+        assert_eq!(report.rows[1].switches, 1);
+
+        let csv = to_csv(&report);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "2025-06,claude,relay,\"Relay, Inc\",1000,200,0,0,1.500000,2"
+        );
+        assert_eq!(lines[2], "2025-06,codex,openai,,0,0,0,0,0.000000,1");
+    }
+}
@@ -0,0 +1,577 @@
+//! WebDAV 数据库同步
+//!
+//! 将数据库导出的 SQL 快照上传到 WebDAV 服务（坚果云、Nextcloud 等），
+//! 或从远端下载快照并导入本地数据库，实现多设备共享供应商配置。
+//! 启用 `exclude_secrets` 时快照不包含密钥（见 [`sync_secrets`]）。
+//!
+//! 远端目录结构：
+//!
+//! ```text
+//! <url>/<remote_dir>/
+//! ├── cc-switch.sql        - 数据库 SQL 快照
+//! └── cc-switch-meta.json  - 快照元信息（上传时间、设备名）
+//! ```
+//!
+//! 上次同步的快照 ETag 保存在数据目录下的 `sync/webdav.etag`：上传时带 `If-Match`
+//! （远端尚无快照时带 `If-None-Match: *`），远端已被其他设备更新时服务器返回
+//! 412，按冲突处理而不是覆盖；定时同步下载时带 `If-None-Match`，远端未变化时跳过。
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Utc;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::database::Database;
+use crate::error::AppError;
+use crate::services::sync_merge::{self, SyncMergeResult};
+use crate::services::sync_secrets;
+use crate::settings::WebDavSyncConfig;
+
+const SNAPSHOT_FILE: &str = "cc-switch.sql";
+const META_FILE: &str = "cc-switch-meta.json";
+const ETAG_FILE: &str = "webdav.etag";
+
+/// 上传快照的前置条件
+enum Precondition {
+    /// 远端仍是上次同步的版本（`If-Match`）
+    Matches(String),
+    /// 远端尚无快照（`If-None-Match: *`）
+    Absent,
+}
+
+/// GET 请求结果
+enum Fetched {
+    Missing,
+    /// 远端与 `If-None-Match` 给出的版本相同（304）
+    NotModified,
+    Body {
+        bytes: Vec<u8>,
+        etag: Option<String>,
+    },
+}
+
+/// 远端快照元信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSnapshotMeta {
+    pub uploaded_at: i64,
+    pub device: String,
+    pub size: usize,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavSyncResult {
+    pub bytes: usize,
+    pub timestamp: i64,
+    /// 下载导入时生成的本地备份 ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_meta: Option<RemoteSnapshotMeta>,
+}
+
+/// 定时同步结果
+#[derive(Debug, Clone, Default)]
+pub struct AutoSyncReport {
+    /// 远端有更新时的合并结果
+    pub merged: Option<SyncMergeResult>,
+    pub uploaded: bool,
+}
+
+/// WebDAV 同步业务
+pub struct WebDavSyncService;
+
+impl WebDavSyncService {
+    /// 读取设置中的 WebDAV 配置
+    pub fn config_from_settings() -> Result<WebDavSyncConfig, AppError> {
+        let config = crate::settings::get_settings().webdav_sync.ok_or_else(|| {
+            AppError::localized(
+                "webdav.not_configured",
+                "尚未配置 WebDAV 同步",
+                "WebDAV sync is not configured",
+            )
+        })?;
+        Self::validate_config(&config)?;
+        Ok(config)
+    }
+
+    fn validate_config(config: &WebDavSyncConfig) -> Result<(), AppError> {
+        let url = url::Url::parse(config.url.trim())
+            .map_err(|e| AppError::InvalidInput(format!("WebDAV 地址无效: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::InvalidInput(
+                "WebDAV 地址必须以 http:// 或 https:// 开头".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 拼接远端目录地址（以 `/` 结尾）
+    fn remote_dir_url(config: &WebDavSyncConfig) -> String {
+        let base = config.url.trim().trim_end_matches('/');
+        let dir = config.remote_dir.trim().trim_matches('/');
+        if dir.is_empty() {
+            format!("{base}/")
+        } else {
+            format!("{base}/{dir}/")
+        }
+    }
+
+    fn file_url(config: &WebDavSyncConfig, name: &str) -> String {
+        format!("{}{name}", Self::remote_dir_url(config))
+    }
+
+    fn request(
+        config: &WebDavSyncConfig,
+        method: Method,
+        url: &str,
+    ) -> Result<reqwest::RequestBuilder, AppError> {
+        let client = crate::proxy::http_client::get();
+        let builder = client.request(method, url);
+        if config.username.is_empty() {
+            return Ok(builder);
+        }
+        Ok(builder.basic_auth(&config.username, Some(config.password()?)))
+    }
+
+    fn status_error(action: &str, status: StatusCode) -> AppError {
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AppError::localized(
+                "webdav.auth_failed",
+                format!("WebDAV {action}失败：认证失败 ({status})"),
+                format!("WebDAV {action} failed: authentication error ({status})"),
+            ),
+            StatusCode::PRECONDITION_FAILED => conflict_error(),
+            _ => AppError::Message(format!("WebDAV {action}失败: HTTP {status}")),
+        }
+    }
+
+    /// 测试连接：对远端根地址发起 PROPFIND（Depth: 0）
+    pub async fn test_connection(config: &WebDavSyncConfig) -> Result<(), AppError> {
+        Self::validate_config(config)?;
+        let method = Method::from_bytes(b"PROPFIND").expect("valid method");
+        let resp = Self::request(config, method, config.url.trim())?
+            .header("Depth", "0")
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("连接 WebDAV 失败: {e}")))?;
+
+        let status = resp.status();
+        if status.is_success() || status == StatusCode::MULTI_STATUS {
+            Ok(())
+        } else {
+            Err(Self::status_error("连接测试", status))
+        }
+    }
+
+    /// 确保远端目录存在（MKCOL，目录已存在时服务器返回 405）
+    async fn ensure_remote_dir(config: &WebDavSyncConfig) -> Result<(), AppError> {
+        if config.remote_dir.trim().trim_matches('/').is_empty() {
+            return Ok(());
+        }
+        let method = Method::from_bytes(b"MKCOL").expect("valid method");
+        let resp = Self::request(config, method, &Self::remote_dir_url(config))?
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("创建 WebDAV 目录失败: {e}")))?;
+
+        let status = resp.status();
+        if status.is_success() || status == StatusCode::METHOD_NOT_ALLOWED {
+            Ok(())
+        } else {
+            Err(Self::status_error("创建目录", status))
+        }
+    }
+
+    /// 上传文件，返回服务器给出的新 ETag
+    async fn put(
+        config: &WebDavSyncConfig,
+        name: &str,
+        body: Vec<u8>,
+        precondition: Option<Precondition>,
+    ) -> Result<Option<String>, AppError> {
+        let mut request =
+            Self::request(config, Method::PUT, &Self::file_url(config, name))?.body(body);
+        request = match precondition {
+            Some(Precondition::Matches(etag)) => request.header(IF_MATCH, etag),
+            Some(Precondition::Absent) => request.header(IF_NONE_MATCH, "*"),
+            None => request,
+        };
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("上传 {name} 失败: {e}")))?;
+        if resp.status().is_success() {
+            Ok(etag_of(&resp))
+        } else {
+            Err(Self::status_error("上传", resp.status()))
+        }
+    }
+
+    async fn get(
+        config: &WebDavSyncConfig,
+        name: &str,
+        if_none_match: Option<&str>,
+    ) -> Result<Fetched, AppError> {
+        let mut request = Self::request(config, Method::GET, &Self::file_url(config, name))?;
+        if let Some(etag) = if_none_match {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("下载 {name} 失败: {e}")))?;
+        let status = resp.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(Fetched::Missing);
+        }
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(Fetched::NotModified);
+        }
+        if !status.is_success() {
+            return Err(Self::status_error("下载", status));
+        }
+        let etag = etag_of(&resp);
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|e| AppError::Message(format!("读取 {name} 失败: {e}")))?;
+        Ok(Fetched::Body {
+            bytes: bytes.to_vec(),
+            etag,
+        })
+    }
+
+    /// 读取远端文件的 ETag（用于 PUT 响应未返回 ETag 的服务器）
+    async fn head_etag(config: &WebDavSyncConfig, name: &str) -> Result<Option<String>, AppError> {
+        let resp = Self::request(config, Method::HEAD, &Self::file_url(config, name))?
+            .send()
+            .await
+            .map_err(|e| AppError::Message(format!("读取 {name} 信息失败: {e}")))?;
+        if resp.status().is_success() {
+            Ok(etag_of(&resp))
+        } else {
+            Err(Self::status_error("读取", resp.status()))
+        }
+    }
+
+    /// 读取远端快照元信息（不存在时返回 None）
+    pub async fn fetch_remote_meta(
+        config: &WebDavSyncConfig,
+    ) -> Result<Option<RemoteSnapshotMeta>, AppError> {
+        let Fetched::Body { bytes, .. } = Self::get(config, META_FILE, None).await? else {
+            return Ok(None);
+        };
+        Ok(serde_json::from_slice(&bytes).ok())
+    }
+
+    /// 上传本地数据库快照（远端已被其他设备更新时返回冲突错误）
+    pub async fn upload(
+        db: Arc<Database>,
+        config: &WebDavSyncConfig,
+    ) -> Result<WebDavSyncResult, AppError> {
+        Self::validate_config(config)?;
+
+        let exclude_secrets = config.exclude_secrets;
+        let export_db = db.clone();
+        let sql = tokio::task::spawn_blocking(move || {
+            let db = export_db;
+            if exclude_secrets {
+                sync_secrets::export_without_secrets(&db)
+            } else {
+                db.export_sql_string()
+            }
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("导出数据库失败: {e}")))??;
+        let bytes = sql.len();
+
+        Self::ensure_remote_dir(config).await?;
+        let precondition = match load_etag() {
+            Some(etag) => Precondition::Matches(etag),
+            None => Precondition::Absent,
+        };
+        let etag = match Self::put(
+            config,
+            SNAPSHOT_FILE,
+            sql.clone().into_bytes(),
+            Some(precondition),
+        )
+        .await?
+        {
+            Some(etag) => Some(etag),
+            None => Self::head_etag(config, SNAPSHOT_FILE)
+                .await
+                .unwrap_or_else(|e| {
+                    log::warn!("读取 WebDAV 快照 ETag 失败: {e}");
+                    None
+                }),
+        };
+        // 拿不到新 ETag 时保留旧值：清除后下次上传会带 `If-None-Match: *`，
+        // 远端已有快照必然返回 412，被误判为冲突
+        if let Some(etag) = etag.as_deref() {
+            save_etag(Some(etag));
+        }
+        if let Err(e) = sync_merge::save_base(&db, &sql) {
+            log::warn!("保存同步基线失败: {e}");
+        }
+
+        let meta = RemoteSnapshotMeta {
+            uploaded_at: Utc::now().timestamp_millis(),
+            device: device_name(),
+            size: bytes,
+        };
+        let meta_json =
+            serde_json::to_vec_pretty(&meta).map_err(|e| AppError::JsonSerialize { source: e })?;
+        Self::put(config, META_FILE, meta_json, None).await?;
+
+        log::info!("WebDAV 上传完成: {bytes} 字节");
+        Ok(WebDavSyncResult {
+            bytes,
+            timestamp: meta.uploaded_at,
+            backup_id: None,
+            remote_meta: Some(meta),
+        })
+    }
+
+    /// 下载远端快照并导入本地数据库（导入前自动备份本地数据库）
+    pub async fn download(
+        db: Arc<Database>,
+        config: &WebDavSyncConfig,
+    ) -> Result<WebDavSyncResult, AppError> {
+        Self::validate_config(config)?;
+
+        let (sql, etag) = Self::fetch_snapshot(config).await?;
+        let size = sql.len();
+        let remote_meta = Self::fetch_remote_meta(config).await.unwrap_or(None);
+
+        let exclude_secrets = config.exclude_secrets;
+        let backup_id = tokio::task::spawn_blocking(move || {
+            if exclude_secrets {
+                sync_secrets::stash_local_secrets(&db)?;
+            }
+            let backup_id = db.import_sql_string(&sql)?;
+            if exclude_secrets {
+                sync_secrets::restore_local_secrets(&db)?;
+            }
+            if let Err(e) = sync_merge::save_base(&db, &sql) {
+                log::warn!("保存同步基线失败: {e}");
+            }
+            Ok::<_, AppError>(backup_id)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("导入数据库失败: {e}")))??;
+        save_etag(etag.as_deref());
+
+        log::info!("WebDAV 下载并导入完成: {size} 字节");
+        Ok(WebDavSyncResult {
+            bytes: size,
+            timestamp: Utc::now().timestamp_millis(),
+            backup_id: Some(backup_id),
+            remote_meta,
+        })
+    }
+}
+
+impl WebDavSyncService {
+    /// 下载远端快照，返回 SQL 文本与 ETag
+    async fn fetch_snapshot(
+        config: &WebDavSyncConfig,
+    ) -> Result<(String, Option<String>), AppError> {
+        let Fetched::Body { bytes, etag } = Self::get(config, SNAPSHOT_FILE, None).await? else {
+            return Err(AppError::localized(
+                "webdav.snapshot_missing",
+                "远端尚无同步快照，请先在其他设备上传",
+                "No snapshot found on the WebDAV server; upload from another device first",
+            ));
+        };
+        Ok((snapshot_text(bytes)?, etag))
+    }
+
+    /// 将远端快照三方合并到本地，成功后记录其 ETag
+    async fn merge_snapshot(
+        db: Arc<Database>,
+        config: &WebDavSyncConfig,
+        sql: String,
+        etag: Option<String>,
+    ) -> Result<SyncMergeResult, AppError> {
+        let exclude_secrets = config.exclude_secrets;
+        let result = tokio::task::spawn_blocking(move || {
+            if exclude_secrets {
+                sync_secrets::stash_local_secrets(&db)?;
+            }
+            let result = sync_merge::merge_remote(&db, &sql)?;
+            if exclude_secrets {
+                sync_secrets::restore_local_secrets(&db)?;
+            }
+            Ok::<_, AppError>(result)
+        })
+        .await
+        .map_err(|e| AppError::Message(format!("合并远端快照失败: {e}")))??;
+        save_etag(etag.as_deref());
+        Ok(result)
+    }
+
+    /// 下载远端快照并与本地做三方合并（不整库替换），冲突留待用户解决
+    pub async fn merge_download(
+        db: Arc<Database>,
+        config: &WebDavSyncConfig,
+    ) -> Result<SyncMergeResult, AppError> {
+        Self::validate_config(config)?;
+        let (sql, etag) = Self::fetch_snapshot(config).await?;
+        Self::merge_snapshot(db, config, sql, etag).await
+    }
+
+    /// 定时同步：远端有更新时先三方合并；没有待解决冲突且本地有未上传的改动时再上传
+    pub async fn auto_sync(
+        db: Arc<Database>,
+        config: &WebDavSyncConfig,
+    ) -> Result<AutoSyncReport, AppError> {
+        Self::validate_config(config)?;
+        let mut report = AutoSyncReport::default();
+
+        let known = load_etag();
+        match Self::get(config, SNAPSHOT_FILE, known.as_deref()).await? {
+            Fetched::Body { bytes, etag } => {
+                let sql = snapshot_text(bytes)?;
+                report.merged = Some(Self::merge_snapshot(db.clone(), config, sql, etag).await?);
+            }
+            // 远端快照被删除时按首次上传处理
+            Fetched::Missing => save_etag(None),
+            Fetched::NotModified => {}
+        }
+        if !sync_merge::load_conflicts().is_empty() {
+            return Ok(report);
+        }
+
+        let pending_db = db.clone();
+        let pending =
+            tokio::task::spawn_blocking(move || sync_merge::count_pending_changes(&pending_db))
+                .await
+                .map_err(|e| AppError::Message(format!("统计待同步改动失败: {e}")))??;
+        // 没有基线（从未同步）时也上传，远端已有快照会以冲突拒绝
+        if pending != Some(0) {
+            Self::upload(db, config).await?;
+            report.uploaded = true;
+        }
+        Ok(report)
+    }
+}
+
+/// 远端快照已被其他设备更新
+fn conflict_error() -> AppError {
+    AppError::localized(
+        "webdav.conflict",
+        "远端快照已被其他设备更新，请先下载或合并后再上传",
+        "The snapshot on the WebDAV server was updated by another device; download or merge before uploading",
+    )
+}
+
+fn snapshot_text(bytes: Vec<u8>) -> Result<String, AppError> {
+    String::from_utf8(bytes)
+        .map_err(|e| AppError::InvalidInput(format!("远端快照不是有效的 UTF-8 文本: {e}")))
+}
+
+fn etag_of(resp: &reqwest::Response) -> Option<String> {
+    resp.headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn etag_path() -> PathBuf {
+    sync_merge::sync_dir().join(ETAG_FILE)
+}
+
+/// 上次同步的远端快照 ETag
+fn load_etag() -> Option<String> {
+    let etag = fs::read_to_string(etag_path()).ok()?;
+    let etag = etag.trim();
+    (!etag.is_empty()).then(|| etag.to_string())
+}
+
+/// 记录远端快照 ETag（服务器未提供时清除，下次上传按远端不存在处理）
+fn save_etag(etag: Option<&str>) {
+    let path = etag_path();
+    let result = match etag {
+        Some(etag) => fs::create_dir_all(sync_merge::sync_dir())
+            .map_err(|e| AppError::io(&path, e))
+            .and_then(|_| crate::config::atomic_write(&path, etag.as_bytes())),
+        None => match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::io(&path, e)),
+            _ => Ok(()),
+        },
+    };
+    if let Err(e) = result {
+        log::warn!("保存 WebDAV 快照 ETag 失败: {e}");
+    }
+}
+
+/// 当前设备名称（用于远端元信息展示）
+pub(crate) fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| std::env::consts::OS.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(url: &str, dir: &str) -> WebDavSyncConfig {
+        WebDavSyncConfig {
+            url: url.to_string(),
+            username: String::new(),
+            password: String::new(),
+            remote_dir: dir.to_string(),
+            exclude_secrets: false,
+            auto_sync_interval_minutes: 0,
+        }
+    }
+
+    #[test]
+    fn remote_urls_are_joined_with_single_slashes() {
+        let cfg = config("https://dav.example.com/files/me/", "/cc-switch/");
+        assert_eq!(
+            WebDavSyncService::remote_dir_url(&cfg),
+            "https://dav.example.com/files/me/cc-switch/"
+        );
+        assert_eq!(
+            WebDavSyncService::file_url(&cfg, SNAPSHOT_FILE),
+            "https://dav.example.com/files/me/cc-switch/cc-switch.sql"
+        );
+
+        let root = config("https://dav.example.com", "");
+        assert_eq!(
+            WebDavSyncService::file_url(&root, META_FILE),
+            "https://dav.example.com/cc-switch-meta.json"
+        );
+    }
+
+    #[test]
+    fn precondition_failed_is_reported_as_conflict() {
+        let err = WebDavSyncService::status_error("上传", StatusCode::PRECONDITION_FAILED);
+        assert!(matches!(
+            err,
+            AppError::Localized {
+                key: "webdav.conflict",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn validate_config_rejects_non_http_urls() {
+        assert!(WebDavSyncService::validate_config(&config("ftp://x", "d")).is_err());
+        assert!(WebDavSyncService::validate_config(&config("not a url", "d")).is_err());
+        assert!(WebDavSyncService::validate_config(&config("https://x", "d")).is_ok());
+    }
+}
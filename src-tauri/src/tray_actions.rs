@@ -0,0 +1,139 @@
+//! 托盘自定义操作
+//!
+//! 设置 `trayQuickActions` 中定义的操作显示在托盘菜单中，点击后由这里执行：
+//!
+//! - 运行脚本：通过系统 shell（Windows 为 `cmd /C`，其他平台为 `sh -c`）在用户主目录中
+//!   运行，不等待结束；环境变量 `CC_SWITCH_<APP>_PROVIDER` 为各应用当前供应商的 ID；
+//! - 打开配置目录：与主界面的「打开配置文件夹」相同，目录不存在时先创建；
+//! - 打开供应商网站：使用供应商的 `websiteUrl`，未指定供应商时使用当前供应商。
+//!
+//! 执行失败只记录日志。
+
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+
+use crate::app_config::AppType;
+use crate::error::AppError;
+use crate::settings::{self, TrayQuickActionKind};
+use crate::store::AppState;
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// 注入脚本环境变量的应用
+const SCRIPT_APPS: [AppType; 3] = [AppType::Claude, AppType::Codex, AppType::Gemini];
+
+fn config_dir(app_type: &AppType) -> PathBuf {
+    match app_type {
+        AppType::Claude => crate::config::get_claude_config_dir(),
+        AppType::Codex => crate::codex_config::get_codex_config_dir(),
+        AppType::Gemini => crate::gemini_config::get_gemini_dir(),
+        AppType::OpenCode => crate::opencode_config::get_opencode_dir(),
+    }
+}
+
+fn run_script(app: &AppHandle, command: &str) -> Result<(), AppError> {
+    if command.trim().is_empty() {
+        return Err(AppError::InvalidInput("脚本命令为空".to_string()));
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command).creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+
+    if let Some(home) = dirs::home_dir() {
+        cmd.current_dir(home);
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        for app_type in SCRIPT_APPS {
+            let current =
+                settings::get_effective_current_provider(&state.db, &app_type)?.unwrap_or_default();
+            cmd.env(
+                format!("CC_SWITCH_{}_PROVIDER", app_type.as_str().to_uppercase()),
+                current,
+            );
+        }
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| AppError::Message(format!("运行脚本失败: {e}")))?;
+    let command = command.to_string();
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) if !status.success() => log::warn!("托盘脚本 `{command}` 退出: {status}"),
+        Ok(_) => log::info!("托盘脚本 `{command}` 已完成"),
+        Err(e) => log::warn!("等待托盘脚本 `{command}` 失败: {e}"),
+    });
+    Ok(())
+}
+
+fn open_config_dir(app: &AppHandle, app_type: &AppType) -> Result<(), AppError> {
+    let dir = config_dir(app_type);
+    if !dir.exists() {
+        std::fs::create_dir_all(&dir).map_err(|e| AppError::io(&dir, e))?;
+    }
+    app.opener()
+        .open_path(dir.to_string_lossy().to_string(), None::<String>)
+        .map_err(|e| AppError::Message(format!("打开文件夹失败: {e}")))
+}
+
+fn open_provider_dashboard(
+    app: &AppHandle,
+    app_type: &AppType,
+    provider_id: Option<&str>,
+) -> Result<(), AppError> {
+    let state = app
+        .try_state::<AppState>()
+        .ok_or_else(|| AppError::Message("应用状态尚未初始化".to_string()))?;
+    let provider = crate::services::launch::resolve(state.inner(), app_type, provider_id)?;
+    let url = provider
+        .website_url
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| AppError::Message(format!("供应商 {} 没有设置网站", provider.name)))?;
+    app.opener()
+        .open_url(url, None::<String>)
+        .map_err(|e| AppError::Message(format!("打开链接失败: {e}")))
+}
+
+/// 执行第 `index` 个自定义操作（序号与设置中的顺序一致）
+pub fn run(app: &AppHandle, index: usize) {
+    let Some(action) = settings::get_settings()
+        .tray_quick_actions
+        .into_iter()
+        .nth(index)
+    else {
+        log::warn!("托盘自定义操作 {index} 不存在");
+        return;
+    };
+    log::info!("执行托盘自定义操作: {}", action.label);
+
+    let result = match &action.kind {
+        TrayQuickActionKind::RunScript { command } => run_script(app, command),
+        TrayQuickActionKind::OpenConfigDir { app: app_type } => open_config_dir(app, app_type),
+        TrayQuickActionKind::OpenProviderDashboard {
+            app: app_type,
+            provider_id,
+        } => open_provider_dashboard(app, app_type, provider_id.as_deref()),
+    };
+    if let Err(e) = result {
+        log::error!("托盘自定义操作「{}」失败: {e}", action.label);
+    }
+}
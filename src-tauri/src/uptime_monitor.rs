@@ -0,0 +1,69 @@
+//! 供应商可用性监控
+//!
+//! 设置 `uptimeMonitor` 开启时，后台按配置的间隔检查当前与故障转移队列中的供应商（见
+//! [`crate::services::uptime`]），并清理超过保留天数的检查记录。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::services::uptime;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动后首次检查的延迟，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(90);
+
+/// 最短检查间隔（分钟），避免频繁请求供应商
+const MIN_INTERVAL_MINUTES: u32 = 1;
+
+/// 启动后台监控任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + STARTUP_DELAY;
+        let mut ticker = tokio::time::interval_at(start, CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut last_run: Option<tokio::time::Instant> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let config = crate::settings::get_settings().uptime_monitor;
+            if !config.enabled {
+                last_run = None;
+                continue;
+            }
+            let interval = Duration::from_secs(
+                u64::from(config.interval_minutes.max(MIN_INTERVAL_MINUTES)) * 60,
+            );
+            let now = tokio::time::Instant::now();
+            if last_run.is_some_and(|last| now.duration_since(last) < interval) {
+                continue;
+            }
+            last_run = Some(now);
+
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if state.db.is_locked() {
+                continue;
+            }
+            let db = state.db.clone();
+            match uptime::check_all(&db).await {
+                Ok(checks) => {
+                    let down = checks.iter().filter(|check| !check.up).count();
+                    log::debug!("可用性检查完成: {} 个供应商，{down} 个不可用", checks.len());
+                }
+                Err(e) => log::warn!("可用性检查失败: {e}"),
+            }
+
+            let before =
+                chrono::Utc::now().timestamp() - i64::from(config.retention_days.max(1)) * 86_400;
+            if let Err(e) = db.prune_uptime_checks(before) {
+                log::warn!("清理可用性检查记录失败: {e}");
+            }
+        }
+    });
+}
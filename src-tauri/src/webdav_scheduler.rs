@@ -0,0 +1,91 @@
+//! WebDAV 定时同步
+//!
+//! 按 `webdav_sync.auto_sync_interval_minutes` 定期与远端同步：远端有更新时三方合并，
+//! 本地有未上传的改动时再上传。冲突通过 `sync-conflicts` 事件交给前端处理。
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::services::sync_merge::SYNC_CONFLICTS_EVENT;
+use crate::services::sync_status::{self, SyncOperation};
+use crate::services::webdav_sync::WebDavSyncService;
+use crate::services::ConfigService;
+use crate::store::AppState;
+
+/// 检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 启动后首次检查的延迟，避免拖慢启动
+const STARTUP_DELAY: Duration = Duration::from_secs(90);
+
+/// 启动后台同步任务
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let start = tokio::time::Instant::now() + STARTUP_DELAY;
+        let mut ticker = tokio::time::interval_at(start, CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let mut last_run: Option<tokio::time::Instant> = None;
+
+        loop {
+            ticker.tick().await;
+
+            let Some(config) = crate::settings::get_settings().webdav_sync else {
+                continue;
+            };
+            // 只读模式下合并会被拒绝，跳过而不是每次都报错
+            if config.auto_sync_interval_minutes == 0
+                || sync_status::is_in_progress()
+                || crate::read_only::is_enabled()
+            {
+                continue;
+            }
+            let interval = Duration::from_secs(u64::from(config.auto_sync_interval_minutes) * 60);
+            if last_run.is_some_and(|last| last.elapsed() < interval) {
+                continue;
+            }
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            if state.db.is_locked() {
+                continue;
+            }
+            last_run = Some(tokio::time::Instant::now());
+
+            sync_status::begin(&app, SyncOperation::Merge);
+            let result = WebDavSyncService::auto_sync(state.db.clone(), &config)
+                .await
+                .map_err(|e| e.to_string());
+            sync_status::finish(&app, SyncOperation::Merge, &result);
+
+            let report = match result {
+                Ok(report) => report,
+                Err(e) => {
+                    log::warn!("WebDAV 定时同步失败: {e}");
+                    continue;
+                }
+            };
+            if report.uploaded {
+                log::info!("WebDAV 定时同步: 已上传本地改动");
+            }
+            let Some(merged) = report.merged else {
+                continue;
+            };
+            if !merged.applied_remote.is_empty() || !merged.auto_merged.is_empty() {
+                let db = state.db.clone();
+                if let Err(e) = tauri::async_runtime::spawn_blocking(move || {
+                    ConfigService::refresh_after_database_restore(&AppState::new(db));
+                })
+                .await
+                {
+                    log::error!("同步 live 配置失败: {e}");
+                }
+            }
+            if !merged.conflicts.is_empty() {
+                if let Err(e) = app.emit(SYNC_CONFLICTS_EVENT, &merged.conflicts) {
+                    log::error!("发射同步冲突事件失败: {e}");
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,52 @@
+use serde_json::json;
+
+use cc_switch_lib::{run_cli, Database, Provider};
+
+#[path = "support.rs"]
+mod support;
+use support::{ensure_test_home, reset_test_fs, test_mutex};
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn cli_switch_is_written_back_to_the_encrypted_database() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+    let passphrase = "cli-test-passphrase";
+    std::env::set_var("CC_SWITCH_DB_PASSPHRASE", passphrase);
+
+    let seed = Database::memory().expect("create seed database");
+    for id in ["first", "second"] {
+        let provider = Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": format!("sk-{id}") } }),
+            None,
+        );
+        seed.save_provider("claude", &provider)
+            .expect("seed provider");
+    }
+    seed.set_current_provider("claude", "first")
+        .expect("seed current provider");
+    let encrypted_path = home.join(".cc-switch").join("cc-switch.db.enc");
+    seed.export_encrypted(&encrypted_path, passphrase)
+        .expect("write encrypted database");
+
+    assert_eq!(run_cli(args(&["use", "claude", "second", "-q"])), 0);
+
+    // 重新打开磁盘上的加密文件，切换结果必须已经写回
+    let reopened = Database::init().expect("reopen encrypted database");
+    assert_eq!(
+        reopened
+            .get_current_provider("claude")
+            .expect("read current provider")
+            .as_deref(),
+        Some("second")
+    );
+    assert!(!home.join(".cc-switch").join("cc-switch.db").exists());
+
+    std::env::remove_var("CC_SWITCH_DB_PASSPHRASE");
+}
@@ -3,8 +3,8 @@ use std::fs;
 use std::path::PathBuf;
 
 use cc_switch_lib::{
-    get_claude_settings_path, read_json_file, AppError, AppType, ConfigService, MultiAppConfig,
-    Provider, ProviderMeta,
+    get_claude_settings_path, read_json_file, update_settings, AppError, AppSettings, AppType,
+    ConfigService, MultiAppConfig, Provider, ProviderMeta,
 };
 
 #[path = "support.rs"]
@@ -1068,3 +1068,93 @@ fn import_sql_accepts_cc_switch_exported_backup() {
         "imported providers should contain test-provider"
     );
 }
+
+#[test]
+fn import_sql_is_rejected_in_read_only_mode() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let state = create_test_state().expect("create test state");
+    let export_path = home.join("cc-switch-export.sql");
+    state
+        .db
+        .export_sql(&export_path)
+        .expect("export should succeed");
+    let sql = fs::read_to_string(&export_path).expect("read export");
+
+    update_settings(AppSettings {
+        read_only_mode: true,
+        ..AppSettings::default()
+    })
+    .expect("enable read-only mode");
+    let file_result = state.db.import_sql(&export_path);
+    let string_result = state.db.import_sql_string(&sql);
+    update_settings(AppSettings::default()).expect("disable read-only mode");
+
+    for result in [file_result, string_result] {
+        match result.expect_err("restore should be rejected while read-only") {
+            AppError::Localized { key, .. } => assert_eq!(key, "read_only.locked"),
+            other => panic!("expected Localized error, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn encrypted_export_roundtrip_requires_passphrase() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "secret-provider".to_string();
+        manager.providers.insert(
+            "secret-provider".to_string(),
+            Provider::with_id(
+                "secret-provider".to_string(),
+                "Secret Provider".to_string(),
+                json!({"env": {"ANTHROPIC_API_KEY": "sk-very-secret"}}),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    let export_path = home.join("cc-switch-export.ccenc");
+    state
+        .db
+        .export_encrypted(&export_path, "correct horse")
+        .expect("encrypted export should succeed");
+
+    let raw = fs::read(&export_path).expect("read encrypted export");
+    assert!(
+        !String::from_utf8_lossy(&raw).contains("sk-very-secret"),
+        "encrypted export must not contain plaintext secrets"
+    );
+
+    reset_test_fs();
+    let state = create_test_state().expect("create test state");
+
+    match state.db.import_sql(&export_path) {
+        Err(AppError::Localized { key, .. }) => assert_eq!(key, "backup.sql.encrypted"),
+        other => panic!("expected encrypted-file error, got {other:?}"),
+    }
+    state
+        .db
+        .import_encrypted(&export_path, "wrong")
+        .expect_err("wrong passphrase should be rejected");
+    state
+        .db
+        .import_encrypted(&export_path, "correct horse")
+        .expect("import with passphrase should succeed");
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("load providers");
+    assert!(providers.contains_key("secret-provider"));
+}
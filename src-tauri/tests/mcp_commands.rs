@@ -1,3 +1,6 @@
+// 依赖 Tauri 命令层的测试钩子
+#![cfg(feature = "gui")]
+
 use std::collections::HashMap;
 use std::fs;
 
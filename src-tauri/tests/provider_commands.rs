@@ -1,3 +1,6 @@
+// 依赖 Tauri 命令层的测试钩子
+#![cfg(feature = "gui")]
+
 use serde_json::json;
 
 use cc_switch_lib::{
@@ -268,11 +271,12 @@ fn switch_provider_updates_claude_live_and_state() {
     let legacy_provider = providers
         .get("old-provider")
         .expect("legacy provider still exists");
-    // 回填机制：切换前会将 live 配置回填到当前供应商
-    // 这保护了用户在 live 文件中的手动修改
+    // 回填机制：切换前会将 live 配置中属于供应商的部分回填到当前供应商
+    // 这保护了用户在 live 文件中的手动修改，用户自己的配置段（workspace）不会被带入
     assert_eq!(
-        legacy_provider.settings_config, legacy_live,
-        "previous provider should be backfilled with live config"
+        legacy_provider.settings_config,
+        json!({ "env": { "ANTHROPIC_API_KEY": "legacy-key" } }),
+        "previous provider should be backfilled with the provider-owned live keys"
     );
 
     let new_provider = providers.get("new-provider").expect("new provider exists");
@@ -1,8 +1,9 @@
 use serde_json::json;
 
 use cc_switch_lib::{
-    get_claude_settings_path, read_json_file, write_codex_live_atomic, AppError, AppType, McpApps,
-    McpServer, MultiAppConfig, Provider, ProviderMeta, ProviderService,
+    get_claude_settings_path, read_json_file, update_settings, write_codex_live_atomic, AppError,
+    AppSettings, AppType, McpApps, McpServer, MultiAppConfig, Provider, ProviderMeta,
+    ProviderService,
 };
 
 #[path = "support.rs"]
@@ -326,6 +327,89 @@ fn switch_google_official_gemini_sets_oauth_security() {
     );
 }
 
+#[test]
+fn switch_gemini_between_api_key_and_google_login_keeps_oauth_creds() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let gemini_dir = home.join(".gemini");
+    std::fs::create_dir_all(&gemini_dir).expect("create gemini dir");
+    let oauth_creds = gemini_dir.join("oauth_creds.json");
+    let creds = r#"{"access_token":"ya29.token","refresh_token":"1//refresh"}"#;
+    std::fs::write(&oauth_creds, creds).expect("seed oauth creds");
+    std::fs::write(
+        gemini_dir.join(".env"),
+        "GEMINI_API_KEY=relay-key\nHTTPS_PROXY=http://127.0.0.1:7890\n",
+    )
+    .expect("seed gemini env");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Gemini)
+            .expect("gemini manager");
+        manager.current = "relay".to_string();
+        manager.providers.insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({ "env": { "GEMINI_API_KEY": "relay-key" } }),
+                None,
+            ),
+        );
+        let mut google = Provider::with_id(
+            "google-official".to_string(),
+            "Google".to_string(),
+            json!({ "env": {} }),
+            None,
+        );
+        google.meta = Some(ProviderMeta {
+            partner_promotion_key: Some("google-official".to_string()),
+            ..ProviderMeta::default()
+        });
+        manager
+            .providers
+            .insert("google-official".to_string(), google);
+        manager.providers.insert(
+            "no-key".to_string(),
+            Provider::with_id(
+                "no-key".to_string(),
+                "No Key".to_string(),
+                json!({ "env": {} }),
+                None,
+            ),
+        );
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+    let read_env = || std::fs::read_to_string(gemini_dir.join(".env")).expect("read gemini env");
+
+    ProviderService::switch(&state, AppType::Gemini, "google-official")
+        .expect("switching to Google login should succeed");
+    let env = read_env();
+    assert!(!env.contains("GEMINI_API_KEY"), "API key should be cleared");
+    assert!(env.contains("HTTPS_PROXY"), "user variables should be kept");
+
+    ProviderService::switch(&state, AppType::Gemini, "relay")
+        .expect("switching back to the API key provider should succeed");
+    let env = read_env();
+    assert!(env.contains("GEMINI_API_KEY=relay-key"));
+    assert!(env.contains("HTTPS_PROXY"));
+
+    // An API key provider without a key is rejected before anything is written
+    let err = ProviderService::switch(&state, AppType::Gemini, "no-key")
+        .expect_err("API key provider without key should be rejected");
+    assert!(err.to_string().contains("GEMINI_API_KEY"));
+    assert!(read_env().contains("GEMINI_API_KEY=relay-key"));
+
+    assert_eq!(
+        std::fs::read_to_string(&oauth_creds).expect("read oauth creds"),
+        creds,
+        "oauth_creds.json must never be modified"
+    );
+}
+
 #[test]
 fn provider_service_switch_claude_updates_live_and_state() {
     let _guard = test_mutex().lock().expect("acquire test mutex");
@@ -415,8 +499,163 @@ fn provider_service_switch_claude_updates_live_and_state() {
         .get("old-provider")
         .expect("legacy provider still exists");
     assert_eq!(
-        legacy_provider.settings_config, legacy_live,
-        "previous provider should receive backfilled live config"
+        legacy_provider.settings_config,
+        json!({ "env": { "ANTHROPIC_API_KEY": "legacy-key" } }),
+        "previous provider should receive only the provider-owned live keys"
+    );
+}
+
+#[test]
+fn switch_claude_keeps_user_settings_out_of_providers() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let _home = ensure_test_home();
+
+    let settings_path = get_claude_settings_path();
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent).expect("create claude settings dir");
+    }
+    let user_sections = json!({
+        "hooks": { "PreToolUse": [{ "matcher": "Bash", "hooks": [] }] },
+        "permissions": { "allow": ["Bash(git status)"] },
+        "statusLine": { "type": "command", "command": "~/statusline.sh" }
+    });
+    let mut live = user_sections.clone();
+    live["env"] = json!({ "ANTHROPIC_AUTH_TOKEN": "helper-token", "DISABLE_TELEMETRY": "1" });
+    live["apiKeyHelper"] = json!("~/get-key.sh");
+    std::fs::write(
+        &settings_path,
+        serde_json::to_string_pretty(&live).expect("serialize live"),
+    )
+    .expect("seed claude live config");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.current = "helper".to_string();
+        manager.providers.insert(
+            "helper".to_string(),
+            Provider::with_id(
+                "helper".to_string(),
+                "Key Helper".to_string(),
+                json!({
+                    "env": { "ANTHROPIC_AUTH_TOKEN": "helper-token" },
+                    "apiKeyHelper": "~/get-key.sh"
+                }),
+                None,
+            ),
+        );
+        manager.providers.insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({
+                    "env": {
+                        "ANTHROPIC_AUTH_TOKEN": "relay-token",
+                        "ANTHROPIC_BASE_URL": "https://relay.example.com"
+                    }
+                }),
+                None,
+            ),
+        );
+    }
+
+    let state = create_test_state_with_config(&config).expect("create test state");
+    ProviderService::switch(&state, AppType::Claude, "relay").expect("switch to relay");
+
+    let live_after: serde_json::Value =
+        read_json_file(&settings_path).expect("read claude live settings");
+    for key in ["hooks", "permissions", "statusLine"] {
+        assert_eq!(live_after[key], user_sections[key], "{key} must be kept");
+    }
+    assert!(
+        live_after.get("apiKeyHelper").is_none(),
+        "apiKeyHelper of the previous provider must be removed"
+    );
+    assert_eq!(live_after["env"]["ANTHROPIC_AUTH_TOKEN"], "relay-token");
+    assert_eq!(live_after["env"]["DISABLE_TELEMETRY"], "1");
+
+    let providers = state
+        .db
+        .get_all_providers(AppType::Claude.as_str())
+        .expect("get all providers");
+    assert_eq!(
+        providers
+            .get("helper")
+            .expect("helper provider")
+            .settings_config,
+        json!({
+            "env": { "ANTHROPIC_AUTH_TOKEN": "helper-token" },
+            "apiKeyHelper": "~/get-key.sh"
+        }),
+        "backfill must not copy user sections into the provider"
+    );
+    let relay = &providers
+        .get("relay")
+        .expect("relay provider")
+        .settings_config;
+    assert!(relay.get("hooks").is_none() && relay.get("permissions").is_none());
+}
+
+#[test]
+fn provider_service_switch_syncs_default_dir_when_overridden() {
+    let _guard = test_mutex().lock().expect("acquire test mutex");
+    reset_test_fs();
+    let home = ensure_test_home();
+
+    let override_dir = home.join(".cc-switch").join("wsl-claude");
+    update_settings(AppSettings {
+        claude_config_dir: Some(override_dir.to_string_lossy().to_string()),
+        sync_provider_switch_to_both_config_dirs: true,
+        ..AppSettings::default()
+    })
+    .expect("enable override and sync");
+
+    let mut config = MultiAppConfig::default();
+    {
+        let manager = config
+            .get_manager_mut(&AppType::Claude)
+            .expect("claude manager");
+        manager.providers.insert(
+            "relay".to_string(),
+            Provider::with_id(
+                "relay".to_string(),
+                "Relay".to_string(),
+                json!({ "env": { "ANTHROPIC_API_KEY": "relay-key" } }),
+                None,
+            ),
+        );
+    }
+    let state = create_test_state_with_config(&config).expect("create test state");
+
+    ProviderService::switch(&state, AppType::Claude, "relay").expect("switch should succeed");
+
+    for path in [
+        override_dir.join("settings.json"),
+        home.join(".claude").join("settings.json"),
+    ] {
+        let live: serde_json::Value = read_json_file(&path).expect("read live settings");
+        assert_eq!(
+            live["env"]["ANTHROPIC_API_KEY"],
+            json!("relay-key"),
+            "{} should receive the provider",
+            path.display()
+        );
+    }
+
+    // Disabling overrides keeps the path but falls back to the default directory
+    update_settings(AppSettings {
+        claude_config_dir: Some(override_dir.to_string_lossy().to_string()),
+        enable_config_dir_overrides: false,
+        ..AppSettings::default()
+    })
+    .expect("disable overrides");
+    assert_eq!(
+        get_claude_settings_path(),
+        home.join(".claude").join("settings.json")
     );
 }
 
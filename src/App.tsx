@@ -15,11 +15,15 @@ import {
   RefreshCw,
   Search,
   Download,
+  ExternalLink,
 } from "lucide-react";
 import type { Provider } from "@/types";
 import type { EnvConflict } from "@/types/env";
 import { useProvidersQuery } from "@/lib/query";
 import {
+  automationRulesApi,
+  auxWindowsApi,
+  changeEventsApi,
   providersApi,
   settingsApi,
   type AppId,
@@ -153,6 +157,84 @@ function App() {
     };
   }, [activeApp, refetch]);
 
+  // 监听 live 配置被外部改写（需在设置中开启 liveConfigGuard）
+  useEffect(() => {
+    let unsubscribe: (() => void) | undefined;
+
+    const setupListener = async () => {
+      try {
+        unsubscribe = await providersApi.onLiveConfigDrift((event) => {
+          const params = {
+            app: event.appType,
+            provider: event.providerName,
+            fields: event.fields.join(", "),
+          };
+          if (event.reapplied) {
+            toast.info(t("notifications.liveConfigReapplied", params));
+            return;
+          }
+          toast.warning(t("notifications.liveConfigDrifted", params), {
+            duration: Infinity,
+            action: {
+              label: t("notifications.reapplyLiveConfig"),
+              onClick: async () => {
+                try {
+                  await providersApi.reapplyLiveConfig(event.appType);
+                } catch (error) {
+                  toast.error(
+                    t("notifications.reapplyLiveConfigFailed", {
+                      error: String(error),
+                    }),
+                  );
+                }
+              },
+            },
+          });
+        });
+      } catch (error) {
+        console.error(
+          "[App] Failed to subscribe live-config-drift event",
+          error,
+        );
+      }
+    };
+
+    setupListener();
+    return () => {
+      unsubscribe?.();
+    };
+  }, [t]);
+
+  // 监听自动化规则执行结果，失败时提示
+  useEffect(() => {
+    let unsubscribe: (() => void) | undefined;
+
+    const setupListener = async () => {
+      try {
+        unsubscribe = await automationRulesApi.onRuleRan((event) => {
+          if (!event.success) {
+            toast.error(
+              t("notifications.automationRuleFailed", {
+                name: event.ruleName,
+                error: event.message,
+              }),
+            );
+          }
+        });
+      } catch (error) {
+        console.error(
+          "[App] Failed to subscribe automation-rule-ran event",
+          error,
+        );
+      }
+    };
+
+    setupListener();
+    return () => {
+      unsubscribe?.();
+    };
+  }, [t]);
+
   // 监听统一供应商同步事件，刷新所有应用的供应商列表
   useEffect(() => {
     let unsubscribe: (() => void) | undefined;
@@ -185,6 +267,43 @@ function App() {
     };
   }, [queryClient]);
 
+  // 监听后端的数据变更事件，只刷新受影响的查询（含同步合并等后台写入）
+  useEffect(() => {
+    const unsubscribers: Array<() => void> = [];
+    let disposed = false;
+
+    const setupListeners = async () => {
+      try {
+        const listeners = await Promise.all([
+          changeEventsApi.onProviderChanged((event) => {
+            void queryClient.invalidateQueries({
+              queryKey: ["providers", event.appType],
+            });
+          }),
+          changeEventsApi.onMcpChanged(() => {
+            void queryClient.invalidateQueries({ queryKey: ["mcp", "all"] });
+          }),
+          changeEventsApi.onSettingsChanged(() => {
+            void queryClient.invalidateQueries({ queryKey: ["settings"] });
+          }),
+        ]);
+        if (disposed) {
+          listeners.forEach((unlisten) => unlisten());
+        } else {
+          unsubscribers.push(...listeners);
+        }
+      } catch (error) {
+        console.error("[App] Failed to subscribe change events", error);
+      }
+    };
+
+    setupListeners();
+    return () => {
+      disposed = true;
+      unsubscribers.forEach((unlisten) => unlisten());
+    };
+  }, [queryClient]);
+
   // 应用启动时检测所有应用的环境变量冲突
   useEffect(() => {
     const checkEnvOnStartup = async () => {
@@ -258,6 +377,47 @@ function App() {
     checkSkillsMigration();
   }, [t, queryClient]);
 
+  // 应用启动时检查数据库或设置文件是否损坏并已从备份恢复
+  useEffect(() => {
+    const checkRecovery = async () => {
+      try {
+        const events = await invoke<
+          {
+            kind: "database" | "settings";
+            path: string;
+            quarantinedPath: string;
+            restoredFrom?: string;
+            error: string;
+          }[]
+        >("get_recovery_events");
+        for (const event of events) {
+          const file = t(`recovery.${event.kind}`);
+          const description = t("recovery.quarantined", {
+            path: event.quarantinedPath,
+          });
+          if (event.restoredFrom) {
+            toast.warning(t("recovery.restored", { file }), {
+              description,
+              closeButton: true,
+              duration: Infinity,
+            });
+          } else {
+            toast.error(t("recovery.noBackup", { file }), {
+              description,
+              closeButton: true,
+              duration: Infinity,
+            });
+          }
+          console.error("[App] Recovered corrupt file:", event);
+        }
+      } catch (error) {
+        console.error("[App] Failed to check recovery events:", error);
+      }
+    };
+
+    checkRecovery();
+  }, [t]);
+
   // 切换应用时检测当前应用的环境变量冲突
   useEffect(() => {
     const checkEnvOnSwitch = async () => {
@@ -727,6 +887,15 @@ function App() {
             )}
             {currentView === "mcp" && (
               <>
+                <Button
+                  variant="ghost"
+                  size="sm"
+                  onClick={() => void auxWindowsApi.open("mcp")}
+                  className="hover:bg-black/5 dark:hover:bg-white/5"
+                >
+                  <ExternalLink className="w-4 h-4 mr-2" />
+                  {t("mcp.openInWindow")}
+                </Button>
                 <Button
                   variant="ghost"
                   size="sm"
@@ -0,0 +1,35 @@
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import UnifiedMcpPanel from "@/components/mcp/UnifiedMcpPanel";
+import { RequestLogTable } from "@/components/usage/RequestLogTable";
+import { UsageDashboard } from "@/components/usage/UsageDashboard";
+import type { AuxWindowKind } from "@/lib/api";
+
+interface AuxWindowViewProps {
+  kind: AuxWindowKind;
+}
+
+/**
+ * 独立窗口中的面板（MCP 管理、请求日志、用量统计）
+ */
+export function AuxWindowView({ kind }: AuxWindowViewProps) {
+  const closeWindow = () => {
+    void getCurrentWindow().close();
+  };
+
+  switch (kind) {
+    case "mcp":
+      return <UnifiedMcpPanel onOpenChange={closeWindow} />;
+    case "logs":
+      return (
+        <div className="h-screen overflow-y-auto p-6">
+          <RequestLogTable />
+        </div>
+      );
+    case "usage":
+      return (
+        <div className="h-screen overflow-y-auto p-6">
+          <UsageDashboard />
+        </div>
+      );
+  }
+}
@@ -1,6 +1,12 @@
 import { useState, useEffect, useMemo } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { DeepLinkImportRequest, deeplinkApi } from "@/lib/api/deeplink";
+import {
+  DeepLinkImportRequest,
+  DeepLinkSwitchRequest,
+  KeyWarning,
+  deeplinkApi,
+} from "@/lib/api/deeplink";
+import { useSwitchProviderMutation } from "@/lib/query";
 import {
   Dialog,
   DialogContent,
@@ -27,8 +33,14 @@ export function DeepLinkImportDialog() {
   const { t } = useTranslation();
   const queryClient = useQueryClient();
   const [request, setRequest] = useState<DeepLinkImportRequest | null>(null);
+  const [switchRequest, setSwitchRequest] =
+    useState<DeepLinkSwitchRequest | null>(null);
   const [isImporting, setIsImporting] = useState(false);
   const [isOpen, setIsOpen] = useState(false);
+  const [keyWarnings, setKeyWarnings] = useState<KeyWarning[]>([]);
+  const switchMutation = useSwitchProviderMutation(
+    switchRequest?.app ?? "claude",
+  );
 
   // 容错判断：MCP 导入结果可能缺少 type 字段
   const isMcpImportResult = (
@@ -48,12 +60,33 @@ export function DeepLinkImportDialog() {
     );
   };
 
+  useEffect(() => {
+    if (!request) {
+      setKeyWarnings([]);
+      return;
+    }
+    let cancelled = false;
+    deeplinkApi
+      .lintDeeplinkRequest(request)
+      .then((warnings) => {
+        if (!cancelled) setKeyWarnings(warnings);
+      })
+      .catch((error) => {
+        console.error("Failed to check deep link keys:", error);
+        if (!cancelled) setKeyWarnings([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [request]);
+
   useEffect(() => {
     // Listen for deep link import events
     const unlistenImport = listen<DeepLinkImportRequest>(
       "deeplink-import",
       async (event) => {
         console.log("Deep link import event received:", event.payload);
+        setSwitchRequest(null);
 
         // If config is present, merge it to get the complete configuration
         if (event.payload.config || event.payload.configUrl) {
@@ -80,6 +113,17 @@ export function DeepLinkImportDialog() {
       },
     );
 
+    // Switch links go through the same confirmation dialog
+    const unlistenSwitch = listen<DeepLinkSwitchRequest>(
+      "deeplink-switch",
+      (event) => {
+        console.log("Deep link switch event received:", event.payload);
+        setRequest(null);
+        setSwitchRequest(event.payload);
+        setIsOpen(true);
+      },
+    );
+
     // Listen for deep link error events
     const unlistenError = listen<DeeplinkError>("deeplink-error", (event) => {
       console.error("Deep link error:", event.payload);
@@ -90,6 +134,7 @@ export function DeepLinkImportDialog() {
 
     return () => {
       unlistenImport.then((fn) => fn());
+      unlistenSwitch.then((fn) => fn());
       unlistenError.then((fn) => fn());
     };
   }, [t]);
@@ -205,6 +250,17 @@ export function DeepLinkImportDialog() {
     }
   };
 
+  const handleSwitch = async () => {
+    if (!switchRequest) return;
+    try {
+      // Success and failure toasts come from the mutation
+      await switchMutation.mutateAsync(switchRequest.providerId);
+      setIsOpen(false);
+    } catch (error) {
+      console.error("Failed to switch provider from deep link:", error);
+    }
+  };
+
   const handleCancel = () => {
     setIsOpen(false);
   };
@@ -321,8 +377,59 @@ export function DeepLinkImportDialog() {
   };
 
   return (
-    <Dialog open={isOpen && !!request} onOpenChange={setIsOpen}>
+    <Dialog
+      open={isOpen && (!!request || !!switchRequest)}
+      onOpenChange={setIsOpen}
+    >
       <DialogContent className="sm:max-w-[500px]" zIndex="top">
+        {switchRequest && (
+          <>
+            <DialogHeader className="text-left sm:text-left">
+              <DialogTitle>{t("deeplink.confirmSwitch")}</DialogTitle>
+              <DialogDescription>
+                {t("deeplink.confirmSwitchDescription")}
+              </DialogDescription>
+            </DialogHeader>
+
+            <div className="space-y-4 px-8 py-4">
+              <div className="grid grid-cols-3 items-center gap-4">
+                <div className="font-medium text-sm text-muted-foreground">
+                  {t("deeplink.app")}
+                </div>
+                <div className="col-span-2 text-sm font-medium capitalize">
+                  {switchRequest.app}
+                </div>
+              </div>
+
+              <div className="grid grid-cols-3 items-center gap-4">
+                <div className="font-medium text-sm text-muted-foreground">
+                  {t("deeplink.providerName")}
+                </div>
+                <div className="col-span-2 text-sm font-medium break-all">
+                  {switchRequest.providerName}
+                  <span className="ml-2 font-mono text-xs text-muted-foreground">
+                    {switchRequest.providerId}
+                  </span>
+                </div>
+              </div>
+            </div>
+
+            <DialogFooter>
+              <Button
+                variant="outline"
+                onClick={handleCancel}
+                disabled={switchMutation.isPending}
+              >
+                {t("common.cancel")}
+              </Button>
+              <Button onClick={handleSwitch} disabled={switchMutation.isPending}>
+                {switchMutation.isPending
+                  ? t("deeplink.switching")
+                  : t("deeplink.switch")}
+              </Button>
+            </DialogFooter>
+          </>
+        )}
         {request && (
           <>
             {/* 标题显式左对齐，避免默认居中样式影响 */}
@@ -333,6 +440,27 @@ export function DeepLinkImportDialog() {
 
             {/* 主体内容整体右移，略大于标题内边距，让内容看起来不贴边 */}
             <div className="space-y-4 px-8 py-4 max-h-[60vh] overflow-y-auto [scrollbar-width:thin] [&::-webkit-scrollbar]:w-1.5 [&::-webkit-scrollbar]:block [&::-webkit-scrollbar-thumb]:rounded-full [&::-webkit-scrollbar-thumb]:bg-gray-200 dark:[&::-webkit-scrollbar-thumb]:bg-gray-700">
+              {keyWarnings.length > 0 && (
+                <div className="rounded-lg bg-red-50 dark:bg-red-900/20 p-3 text-sm text-red-800 dark:text-red-200 space-y-1">
+                  <div className="font-medium">
+                    {t("deeplink.keyWarnings.title")}
+                  </div>
+                  <ul className="list-disc pl-4 space-y-0.5">
+                    {keyWarnings.map((warning, index) => (
+                      <li key={`${warning.target}-${warning.issue}-${index}`}>
+                        <span className="font-mono">{warning.target}</span>
+                        {" ("}
+                        <span className="font-mono">{warning.masked}</span>
+                        {"): "}
+                        {t(`deeplink.keyWarnings.${warning.issue}`, {
+                          keyVendor: warning.keyVendor,
+                          expectedVendor: warning.expectedVendor,
+                        })}
+                      </li>
+                    ))}
+                  </ul>
+                </div>
+              )}
               {request.resource === "prompt" && (
                 <PromptConfirmation request={request} />
               )}
@@ -2,9 +2,14 @@ import { useMemo } from "react";
 import { FolderSearch, Undo2 } from "lucide-react";
 import { Input } from "@/components/ui/input";
 import { Button } from "@/components/ui/button";
+import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
 import { useTranslation } from "react-i18next";
 import type { AppId } from "@/lib/api";
-import type { ResolvedDirectories } from "@/hooks/useSettings";
+import type {
+  ResolvedDirectories,
+  SettingsFormState,
+} from "@/hooks/useSettings";
 
 interface DirectorySettingsProps {
   appConfigDir?: string;
@@ -18,6 +23,9 @@ interface DirectorySettingsProps {
   onDirectoryChange: (app: AppId, value?: string) => void;
   onBrowseDirectory: (app: AppId) => Promise<void>;
   onResetDirectory: (app: AppId) => Promise<void>;
+  overridesEnabled: boolean;
+  syncToBothDirs: boolean;
+  onOptionsChange: (updates: Partial<SettingsFormState>) => void;
 }
 
 export function DirectorySettings({
@@ -32,6 +40,9 @@ export function DirectorySettings({
   onDirectoryChange,
   onBrowseDirectory,
   onResetDirectory,
+  overridesEnabled,
+  syncToBothDirs,
+  onOptionsChange,
 }: DirectorySettingsProps) {
   const { t } = useTranslation();
 
@@ -85,6 +96,37 @@ export function DirectorySettings({
           </p>
         </header>
 
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label>{t("settings.enableConfigDirOverrides")}</Label>
+            <p className="text-xs text-muted-foreground">
+              {t("settings.enableConfigDirOverridesDescription")}
+            </p>
+          </div>
+          <Switch
+            checked={overridesEnabled}
+            onCheckedChange={(checked) =>
+              onOptionsChange({ enableConfigDirOverrides: checked })
+            }
+          />
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-0.5">
+            <Label>{t("settings.syncSwitchToBothConfigDirs")}</Label>
+            <p className="text-xs text-muted-foreground">
+              {t("settings.syncSwitchToBothConfigDirsDescription")}
+            </p>
+          </div>
+          <Switch
+            checked={syncToBothDirs}
+            disabled={!overridesEnabled}
+            onCheckedChange={(checked) =>
+              onOptionsChange({ syncProviderSwitchToBothConfigDirs: checked })
+            }
+          />
+        </div>
+
         <DirectoryInput
           label={t("settings.claudeConfigDir")}
           description={undefined}
@@ -33,6 +33,7 @@ import { settingsApi } from "@/lib/api";
 import { LanguageSettings } from "@/components/settings/LanguageSettings";
 import { ThemeSettings } from "@/components/settings/ThemeSettings";
 import { WindowSettings } from "@/components/settings/WindowSettings";
+import { TrayMenuSettings } from "@/components/settings/TrayMenuSettings";
 import { DirectorySettings } from "@/components/settings/DirectorySettings";
 import { ImportExportSection } from "@/components/settings/ImportExportSection";
 import { AboutSection } from "@/components/settings/AboutSection";
@@ -242,6 +243,10 @@ export function SettingsPage({
                     settings={settings}
                     onChange={handleAutoSave}
                   />
+                  <TrayMenuSettings
+                    settings={settings}
+                    onChange={handleAutoSave}
+                  />
                 </motion.div>
               ) : null}
             </TabsContent>
@@ -289,6 +294,13 @@ export function SettingsPage({
                           onDirectoryChange={updateDirectory}
                           onBrowseDirectory={browseDirectory}
                           onResetDirectory={resetDirectory}
+                          overridesEnabled={
+                            settings.enableConfigDirOverrides ?? true
+                          }
+                          syncToBothDirs={
+                            !!settings.syncProviderSwitchToBothConfigDirs
+                          }
+                          onOptionsChange={updateSettings}
                         />
                       </AccordionContent>
                     </AccordionItem>
@@ -0,0 +1,109 @@
+import { Button } from "@/components/ui/button";
+import { cn } from "@/lib/utils";
+import { useTranslation } from "react-i18next";
+import type { SettingsFormState } from "@/hooks/useSettings";
+import type { TrayMenuAction, TrayMenuConfig } from "@/types";
+import type { AppId } from "@/lib/api";
+
+interface TrayMenuSettingsProps {
+  settings: SettingsFormState;
+  onChange: (updates: Partial<SettingsFormState>) => void;
+}
+
+const TRAY_APPS: { id: AppId; label: string }[] = [
+  { id: "claude", label: "Claude" },
+  { id: "codex", label: "Codex" },
+  { id: "gemini", label: "Gemini" },
+];
+
+const TRAY_ACTIONS: TrayMenuAction[] = [
+  "showMain",
+  "recent",
+  "pause",
+  "configDirs",
+];
+
+function toggle<T>(list: T[] | undefined, value: T, shown: boolean): T[] {
+  const rest = (list ?? []).filter((item) => item !== value);
+  return shown ? rest : [...rest, value];
+}
+
+export function TrayMenuSettings({
+  settings,
+  onChange,
+}: TrayMenuSettingsProps) {
+  const { t } = useTranslation();
+  const trayMenu: TrayMenuConfig = settings.trayMenu ?? {};
+
+  const update = (patch: Partial<TrayMenuConfig>) =>
+    onChange({ trayMenu: { ...trayMenu, ...patch } });
+
+  return (
+    <section className="space-y-2">
+      <header className="space-y-1">
+        <h3 className="text-sm font-medium">{t("settings.trayMenu")}</h3>
+        <p className="text-xs text-muted-foreground">
+          {t("settings.trayMenuHint")}
+        </p>
+      </header>
+      <div className="flex flex-wrap gap-1 rounded-md border border-border-default bg-background p-1">
+        {TRAY_APPS.map(({ id, label }) => {
+          const shown = !trayMenu.hiddenApps?.includes(id);
+          return (
+            <ChipButton
+              key={id}
+              active={shown}
+              onClick={() =>
+                update({ hiddenApps: toggle(trayMenu.hiddenApps, id, !shown) })
+              }
+            >
+              {label}
+            </ChipButton>
+          );
+        })}
+        {TRAY_ACTIONS.map((action) => {
+          const shown = !trayMenu.hiddenActions?.includes(action);
+          return (
+            <ChipButton
+              key={action}
+              active={shown}
+              onClick={() =>
+                update({
+                  hiddenActions: toggle(trayMenu.hiddenActions, action, !shown),
+                })
+              }
+            >
+              {t(`settings.trayMenuAction.${action}`)}
+            </ChipButton>
+          );
+        })}
+      </div>
+    </section>
+  );
+}
+
+interface ChipButtonProps {
+  active: boolean;
+  onClick: () => void;
+  children: React.ReactNode;
+}
+
+function ChipButton({ active, onClick, children }: ChipButtonProps) {
+  return (
+    <Button
+      type="button"
+      onClick={onClick}
+      size="sm"
+      variant={active ? "default" : "ghost"}
+      aria-pressed={active}
+      className={cn(
+        "min-w-[80px]",
+        active
+          ? "shadow-sm"
+          : "text-muted-foreground hover:text-foreground hover:bg-muted",
+      )}
+    >
+      {children}
+    </Button>
+  );
+}
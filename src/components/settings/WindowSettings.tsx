@@ -1,7 +1,7 @@
 import { Switch } from "@/components/ui/switch";
 import { useTranslation } from "react-i18next";
 import type { SettingsFormState } from "@/hooks/useSettings";
-import { AppWindow, MonitorUp, Power } from "lucide-react";
+import { AppWindow, Bell, EyeOff, MonitorUp, Power, Tag } from "lucide-react";
 
 interface WindowSettingsProps {
   settings: SettingsFormState;
@@ -27,6 +27,14 @@ export function WindowSettings({ settings, onChange }: WindowSettingsProps) {
           onCheckedChange={(value) => onChange({ launchOnStartup: value })}
         />
 
+        <ToggleRow
+          icon={<EyeOff className="h-4 w-4 text-slate-500" />}
+          title={t("settings.startMinimized")}
+          description={t("settings.startMinimizedDescription")}
+          checked={!!settings.startMinimized}
+          onCheckedChange={(value) => onChange({ startMinimized: value })}
+        />
+
         <ToggleRow
           icon={<AppWindow className="h-4 w-4 text-blue-500" />}
           title={t("settings.minimizeToTray")}
@@ -37,6 +45,28 @@ export function WindowSettings({ settings, onChange }: WindowSettingsProps) {
           }
         />
 
+        <ToggleRow
+          icon={<Tag className="h-4 w-4 text-green-500" />}
+          title={t("settings.showProviderInTrayTitle")}
+          description={t("settings.showProviderInTrayTitleDescription")}
+          checked={settings.showProviderInTrayTitle ?? true}
+          onCheckedChange={(value) =>
+            onChange({ showProviderInTrayTitle: value })
+          }
+        />
+
+        <ToggleRow
+          icon={<Bell className="h-4 w-4 text-amber-500" />}
+          title={t("settings.systemNotifications")}
+          description={t("settings.systemNotificationsDescription")}
+          checked={settings.notifications?.enabled ?? true}
+          onCheckedChange={(value) =>
+            onChange({
+              notifications: { ...settings.notifications, enabled: value },
+            })
+          }
+        />
+
         <ToggleRow
           icon={<MonitorUp className="h-4 w-4 text-purple-500" />}
           title={t("settings.enableClaudePluginIntegration")}
@@ -0,0 +1,62 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { AppId } from "./types";
+
+export type RuleTrigger =
+  | { type: "schedule"; cron: string }
+  | { type: "fileChanged"; path: string }
+  | { type: "networkChanged" };
+
+export type RuleAction =
+  | { type: "switchProvider"; app: AppId; providerId: string }
+  | { type: "healthCheck"; app: AppId }
+  | { type: "createBackup" };
+
+export interface AutomationRule {
+  /** 为空时由后端生成 */
+  id: string;
+  name: string;
+  enabled: boolean;
+  trigger: RuleTrigger;
+  action: RuleAction;
+  lastRunAt?: number | null;
+  /** 失败时以 `error: ` 开头 */
+  lastResult?: string | null;
+  createdAt?: number;
+}
+
+export interface AutomationRuleRanEvent {
+  ruleId: string;
+  ruleName: string;
+  success: boolean;
+  message: string;
+}
+
+export const automationRulesApi = {
+  async list(): Promise<AutomationRule[]> {
+    return await invoke("get_automation_rules");
+  },
+
+  async save(rule: AutomationRule): Promise<AutomationRule> {
+    return await invoke("save_automation_rule", { rule });
+  },
+
+  async delete(id: string): Promise<boolean> {
+    return await invoke("delete_automation_rule", { id });
+  },
+
+  /**
+   * 立即执行规则的动作（不检查触发条件）
+   */
+  async run(id: string): Promise<string> {
+    return await invoke("run_automation_rule", { id });
+  },
+
+  async onRuleRan(
+    handler: (event: AutomationRuleRanEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("automation-rule-ran", (event) => {
+      handler(event.payload as AutomationRuleRanEvent);
+    });
+  },
+};
@@ -0,0 +1,25 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** 可以在独立窗口中打开的面板（同时是窗口标签与 URL 参数 `window` 的值） */
+export type AuxWindowKind = "mcp" | "logs" | "usage";
+
+export const AUX_WINDOW_KINDS: AuxWindowKind[] = ["mcp", "logs", "usage"];
+
+export const auxWindowsApi = {
+  /**
+   * 打开辅助窗口；已打开时聚焦
+   */
+  async open(window: AuxWindowKind): Promise<boolean> {
+    return await invoke("open_aux_window", { window });
+  },
+
+  /**
+   * 当前页面所在的辅助窗口（主窗口返回 null）
+   */
+  current(): AuxWindowKind | null {
+    const kind = new URLSearchParams(globalThis.location?.search ?? "").get(
+      "window",
+    );
+    return AUX_WINDOW_KINDS.find((item) => item === kind) ?? null;
+  },
+};
@@ -0,0 +1,71 @@
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { AppId } from "./types";
+
+export type ProviderChangeKind = "added" | "updated" | "deleted" | "switched";
+
+export interface ProviderChangeEvent {
+  kind: ProviderChangeKind;
+  appType: AppId;
+  providerId: string;
+}
+
+export type McpChangeKind = "updated" | "deleted";
+
+export interface McpChangeEvent {
+  kind: McpChangeKind;
+  id: string;
+}
+
+const PROVIDER_CHANGE_KINDS: ProviderChangeKind[] = [
+  "added",
+  "updated",
+  "deleted",
+  "switched",
+];
+const MCP_CHANGE_KINDS: McpChangeKind[] = ["updated", "deleted"];
+
+/** 同时监听多个事件，返回统一的取消函数 */
+async function listenAll<K extends string, P>(
+  prefix: string,
+  kinds: K[],
+  handler: (kind: K, payload: P) => void,
+): Promise<UnlistenFn> {
+  const unlisteners = await Promise.all(
+    kinds.map((kind) =>
+      listen<P>(`${prefix}:${kind}`, (event) => handler(kind, event.payload)),
+    ),
+  );
+  return () => unlisteners.forEach((unlisten) => unlisten());
+}
+
+/**
+ * 后端数据库与设置层在写入提交后发出的细粒度变更事件
+ * 用于只刷新受影响的数据，包括同步合并、自动化规则等后台写入
+ */
+export const changeEventsApi = {
+  async onProviderChanged(
+    handler: (event: ProviderChangeEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listenAll(
+      "provider",
+      PROVIDER_CHANGE_KINDS,
+      (kind, payload: Omit<ProviderChangeEvent, "kind">) =>
+        handler({ kind, ...payload }),
+    );
+  },
+
+  async onMcpChanged(
+    handler: (event: McpChangeEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listenAll(
+      "mcp",
+      MCP_CHANGE_KINDS,
+      (kind, payload: Omit<McpChangeEvent, "kind">) =>
+        handler({ kind, ...payload }),
+    );
+  },
+
+  async onSettingsChanged(handler: () => void): Promise<UnlistenFn> {
+    return await listen("settings:changed", () => handler());
+  },
+};
@@ -75,3 +75,32 @@ export async function extractCommonConfigSnippet(
 
   return invoke<string>("extract_common_config_snippet", args);
 }
+
+export interface ConfigSearchMatch {
+  target: "provider" | "mcp";
+  // 供应商所属的应用（MCP 服务器为空）
+  appType?: string | null;
+  itemId: string;
+  itemName: string;
+  // 字段，如 settingsConfig、meta、notes、server
+  field: string;
+  // 字段内的 JSON Pointer（纯文本字段为空）
+  path: string;
+  line: number;
+  snippet: string;
+  // 匹配在 snippet 中的字符范围 [start, end)
+  ranges: [number, number][];
+  masked: boolean;
+}
+
+/**
+ * 在全部供应商与 MCP 服务器配置中搜索文本
+ * @param query - 要查找的文本（不区分大小写）
+ * @param limit - 最多返回的匹配数（默认 200）
+ */
+export async function searchConfigs(
+  query: string,
+  limit?: number,
+): Promise<ConfigSearchMatch[]> {
+  return invoke<ConfigSearchMatch[]>("search_configs", { query, limit });
+}
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
 
 export type ResourceType = "provider" | "prompt" | "mcp" | "skill";
 
@@ -49,6 +50,29 @@ export interface DeepLinkImportRequest {
   usageAutoInterval?: number;
 }
 
+// ccswitch://switch/{app}/{id}: switch to an existing provider after confirmation
+export interface DeepLinkSwitchRequest {
+  app: AppId;
+  providerId: string;
+  providerName: string;
+}
+
+export type KeyIssue =
+  | "malformed"
+  | "placeholder"
+  | "legacyFormat"
+  | "vendorMismatch";
+
+export type KeyVendor = "anthropic" | "openai" | "google" | "openrouter";
+
+export interface KeyWarning {
+  target: string;
+  issue: KeyIssue;
+  masked: string;
+  keyVendor?: KeyVendor;
+  expectedVendor?: KeyVendor;
+}
+
 export interface McpImportResult {
   importedCount: number;
   importedIds: string[];
@@ -91,6 +115,17 @@ export const deeplinkApi = {
     return invoke("merge_deeplink_config", { request });
   },
 
+  /**
+   * Check the API keys carried by a deep link request
+   * @param request The deep link import request
+   * @returns Suspicious keys (malformed, placeholder, legacy format, wrong vendor)
+   */
+  lintDeeplinkRequest: async (
+    request: DeepLinkImportRequest,
+  ): Promise<KeyWarning[]> => {
+    return invoke("lint_deeplink_request", { request });
+  },
+
   /**
    * Import a resource from a deep link request (unified handler)
    * @param request The deep link import request
@@ -6,6 +6,16 @@ export { promptsApi } from "./prompts";
 export { usageApi } from "./usage";
 export { vscodeApi } from "./vscode";
 export { proxyApi } from "./proxy";
+export { automationRulesApi } from "./automationRules";
+export { quickSwitchApi } from "./quickSwitch";
+export { runtimeApi } from "./runtime";
+export { auxWindowsApi } from "./auxWindows";
 export * as configApi from "./config";
+export { changeEventsApi } from "./changeEvents";
 export type { ProviderSwitchEvent } from "./providers";
+export type { ProviderChangeEvent, McpChangeEvent } from "./changeEvents";
 export type { Prompt } from "./prompts";
+export type { AutomationRule } from "./automationRules";
+export type { QuickSwitchCandidate } from "./quickSwitch";
+export type { RuntimeState } from "./runtime";
+export type { AuxWindowKind } from "./auxWindows";
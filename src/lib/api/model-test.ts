@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { ProviderCost, SessionUsageRange } from "@/types/usage";
 import type { AppId } from "./types";
 
 // ===== 流式健康检查类型 =====
@@ -26,6 +27,65 @@ export interface StreamCheckResult {
   retryCount: number;
 }
 
+export interface ProviderBenchmark {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  samples: number;
+  successes: number;
+  ttfbMs?: number;
+  totalMs?: number;
+  error?: string;
+  testedAt: number;
+}
+
+export interface ProviderModels {
+  appType: string;
+  providerId: string;
+  models: string[];
+  configuredModels: string[];
+  missingModels: string[];
+  checkedAt: number;
+}
+
+export interface UptimeIncident {
+  startedAt: number;
+  endedAt?: number;
+  failedChecks: number;
+  reason: string;
+}
+
+export interface ProviderUptime {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  totalChecks: number;
+  upChecks: number;
+  availability: number;
+  avgLatencyMs?: number;
+  lastCheckedAt: number;
+  lastUp: boolean;
+  incidents: UptimeIncident[];
+}
+
+export interface ProviderComparison {
+  providerId: string;
+  providerName: string;
+  benchmark?: ProviderBenchmark;
+  uptime?: ProviderUptime;
+  cost?: ProviderCost;
+  models?: ProviderModels;
+  modelsError?: string;
+}
+
+export interface ProviderComparisonReport {
+  appType: string;
+  range: SessionUsageRange;
+  uptimeHours: number;
+  providers: ProviderComparison[];
+  generatedAt: number;
+}
+
 // ===== 流式健康检查 API =====
 
 /**
@@ -48,6 +108,66 @@ export async function streamCheckAllProviders(
   return invoke("stream_check_all_providers", { appType, proxyTargetsOnly });
 }
 
+/**
+ * 端点测速（每个供应商请求多次，结果按速度排序）
+ */
+export async function benchmarkProviders(
+  appType: AppId,
+  samples?: number,
+): Promise<ProviderBenchmark[]> {
+  return invoke("benchmark_providers", { appType, samples });
+}
+
+/**
+ * 获取保存的测速结果
+ */
+export async function getProviderBenchmarks(
+  appType: AppId,
+): Promise<ProviderBenchmark[]> {
+  return invoke("get_provider_benchmarks", { appType });
+}
+
+/**
+ * 查询供应商提供的模型（结果缓存十分钟）
+ */
+export async function listModels(
+  appType: AppId,
+  providerId: string,
+  refresh: boolean = false,
+): Promise<ProviderModels> {
+  return invoke("list_models", { appType, providerId, refresh });
+}
+
+/**
+ * 获取已缓存的模型列表结果
+ */
+export async function getCachedModels(
+  appType: AppId,
+): Promise<ProviderModels[]> {
+  return invoke("get_cached_models", { appType });
+}
+
+/**
+ * 获取最近若干小时内各供应商的可用率与故障记录
+ */
+export async function getProviderUptime(
+  appType?: AppId,
+  hours?: number,
+): Promise<ProviderUptime[]> {
+  return invoke("get_provider_uptime", { appType, hours });
+}
+
+/**
+ * 对比多个供应商的测速、可用性、成本与模型列表
+ */
+export async function compareProviders(
+  appType: AppId,
+  ids: string[],
+  range?: SessionUsageRange,
+): Promise<ProviderComparisonReport> {
+  return invoke("compare_providers", { appType, ids, range });
+}
+
 /**
  * 获取流式检查配置
  */
@@ -1,6 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import type {
+  McpServer,
   Provider,
   UniversalProvider,
   UniversalProvidersMap,
@@ -17,11 +18,58 @@ export interface ProviderSwitchEvent {
   providerId: string;
 }
 
+export type ProviderSort =
+  | "custom"
+  | "name"
+  | "nameDesc"
+  | "createdAt"
+  | "createdAtDesc";
+
+export interface ProviderPage {
+  items: Provider[];
+  /** 该应用的供应商总数 */
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+export interface LiveConfigDriftEvent {
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  fields: string[];
+  reapplied: boolean;
+}
+
+/** 批量操作：新增要求对象不存在，更新与删除要求对象已存在 */
+export type BatchOp =
+  | { op: "addProvider"; appType: AppId; provider: Provider }
+  | { op: "updateProvider"; appType: AppId; provider: Provider }
+  | { op: "deleteProvider"; appType: AppId; id: string }
+  | { op: "addMcpServer"; server: McpServer }
+  | { op: "updateMcpServer"; server: McpServer }
+  | { op: "deleteMcpServer"; id: string };
+
 export const providersApi = {
   async getAll(appId: AppId): Promise<Record<string, Provider>> {
     return await invoke("get_providers", { app: appId });
   },
 
+  /** 分页获取供应商（默认每页 50 条，按自定义顺序） */
+  async listPage(
+    appId: AppId,
+    offset = 0,
+    limit = 50,
+    sort: ProviderSort = "custom",
+  ): Promise<ProviderPage> {
+    return await invoke("list_providers", {
+      app: appId,
+      offset,
+      limit,
+      sort,
+    });
+  },
+
   async getCurrent(appId: AppId): Promise<string> {
     return await invoke("get_current_provider", { app: appId });
   },
@@ -50,6 +98,13 @@ export const providersApi = {
     return await invoke("switch_provider", { id, app: appId });
   },
 
+  /**
+   * 在快速切换面板中置顶或取消置顶
+   */
+  async setPinned(id: string, appId: AppId, pinned: boolean): Promise<boolean> {
+    return await invoke("set_provider_pinned", { id, app: appId, pinned });
+  },
+
   async importDefault(appId: AppId): Promise<boolean> {
     return await invoke("import_default_config", { app: appId });
   },
@@ -74,6 +129,54 @@ export const providersApi = {
     });
   },
 
+  async onLiveConfigDrift(
+    handler: (event: LiveConfigDriftEvent) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("live-config-drift", (event) => {
+      handler(event.payload as LiveConfigDriftEvent);
+    });
+  },
+
+  /**
+   * 重新应用当前供应商（live 配置被外部改写后使用）
+   */
+  async reapplyLiveConfig(appId: AppId): Promise<string> {
+    return await invoke("reapply_live_config", { app: appId });
+  },
+
+  /**
+   * 以指定供应商启动应用的 CLI（密钥仅注入该进程的环境变量），返回进程 ID
+   */
+  async launchApp(
+    appId: AppId,
+    providerId: string,
+    args?: string[],
+  ): Promise<number> {
+    return await invoke("launch_app", { appType: appId, providerId, args });
+  },
+
+  /**
+   * 供应商的会话级环境变量脚本（端点、模型与密钥），供单个终端 source
+   */
+  async printEnv(
+    appId: AppId,
+    providerId: string,
+    shell?: "posix" | "powershell",
+  ): Promise<string> {
+    return await invoke("print_env", { appType: appId, providerId, shell });
+  },
+
+  /**
+   * 在项目目录生成 direnv `.envrc`（不含密钥），返回文件路径
+   */
+  async writeEnvrc(
+    appId: AppId,
+    providerId: string,
+    dir: string,
+  ): Promise<string> {
+    return await invoke("write_envrc", { appType: appId, providerId, dir });
+  },
+
   /**
    * 打开指定提供商的终端
    * 任何提供商都可以打开终端，不受是否为当前激活提供商的限制
@@ -98,6 +201,14 @@ export const providersApi = {
   async getOpenCodeLiveProviderIds(): Promise<string[]> {
     return await invoke("get_opencode_live_provider_ids");
   },
+
+  /**
+   * 在一个事务中执行一组新增/更新/删除操作，任何一项失败时全部回滚
+   * 返回执行的操作数
+   */
+  async applyBatch(ops: BatchOp[]): Promise<number> {
+    return await invoke("apply_batch", { ops });
+  },
 };
 
 // ============================================================================
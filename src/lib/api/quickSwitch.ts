@@ -0,0 +1,29 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AppId } from "./types";
+
+export interface QuickSwitchCandidate {
+  /** `<app>:<providerId>`，传给 execute */
+  id: string;
+  appType: AppId;
+  providerId: string;
+  providerName: string;
+  current: boolean;
+  pinned: boolean;
+  score: number;
+}
+
+export const quickSwitchApi = {
+  /**
+   * 按查询模糊匹配所有应用的供应商（查询为空时按置顶与最近使用排序）
+   */
+  async candidates(
+    query: string,
+    limit?: number,
+  ): Promise<QuickSwitchCandidate[]> {
+    return await invoke("quick_switch_candidates", { query, limit });
+  },
+
+  async execute(candidateId: string): Promise<boolean> {
+    return await invoke("execute_quick_switch", { candidateId });
+  },
+};
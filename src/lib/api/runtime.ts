@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export interface RuntimeState {
+  /** 是否已暂停后台集成（文件监听、自动化规则、故障转移、插件联动） */
+  paused: boolean;
+  /** 暂停开始时间（毫秒时间戳） */
+  pausedAt?: number | null;
+}
+
+export const runtimeApi = {
+  async getState(): Promise<RuntimeState> {
+    return await invoke("get_runtime_state");
+  },
+
+  async setPaused(paused: boolean): Promise<RuntimeState> {
+    return await invoke("set_runtime_paused", { paused });
+  },
+
+  async onChanged(
+    handler: (state: RuntimeState) => void,
+  ): Promise<UnlistenFn> {
+    return await listen("runtime-state-changed", (event) => {
+      handler(event.payload as RuntimeState);
+    });
+  },
+};
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Settings } from "@/types";
+import type { Settings, WebhookConfig } from "@/types";
 import type { AppId } from "./types";
 
 export interface ConfigTransferResult {
@@ -9,15 +9,119 @@ export interface ConfigTransferResult {
   backupId?: string;
 }
 
+export interface BundleManifest {
+  formatVersion: number;
+  schemaVersion: number;
+  appVersion: string;
+  createdAt: number;
+  contents: {
+    providers: number;
+    mcpServers: number;
+    prompts: number;
+    universalProviders: number;
+  };
+}
+
+export interface BundleImportResult {
+  manifest: BundleManifest;
+  safetyBackupId: string;
+}
+
+export interface LegacyAppMigration {
+  app: AppId;
+  providersAdded: string[];
+  providersSkipped: string[];
+  endpointsAdded: number;
+  legacyCurrent: string | null;
+  currentApplied: boolean;
+}
+
+export interface LegacyMigrationReport {
+  sourcePath: string;
+  found: boolean;
+  dryRun: boolean;
+  apps: LegacyAppMigration[];
+  warnings: string[];
+  archivedTo: string | null;
+}
+
+export interface UndoSummary {
+  kind: "providerDeleted" | "mcpServerDeleted" | "settingsChanged";
+  appType: AppId | null;
+  targetId: string | null;
+  targetName: string | null;
+  remaining: number;
+}
+
+export interface SettingsFinding {
+  code: string;
+  severity: "info" | "warning" | "error";
+  app?: AppId;
+  path?: string;
+  message: string;
+}
+
+export interface AppStatus {
+  app: AppId;
+  current: { id: string; name: string } | null;
+  configDir: string;
+  configDirOverridden: boolean;
+  proxyTakeover: boolean;
+  envOnly: boolean;
+  drift: {
+    state: "inSync" | "drifted" | "liveUnreadable" | "skipped";
+    fields: string[];
+    detail?: string;
+  };
+  health: {
+    healthy: boolean;
+    consecutiveFailures: number;
+    lastFailureAt: string | null;
+    lastError: string | null;
+  } | null;
+}
+
+export interface StatusReport {
+  schemaVersion: number;
+  version: string;
+  readOnly: boolean;
+  appConfigDir: string;
+  appConfigDirOverridden: boolean;
+  /** 数据目录来源：环境变量 CC_SWITCH_HOME、设置中的覆盖或默认 */
+  appConfigDirSource: "env" | "setting" | "default";
+  apps: AppStatus[];
+  findings: SettingsFinding[];
+}
+
+export interface DatabaseProfile {
+  name: string;
+  path: string;
+  active: boolean;
+  encrypted: boolean;
+}
+
+export interface SaveSettingsResult {
+  success: boolean;
+  warnings: SettingsFinding[];
+}
+
 export const settingsApi = {
   async get(): Promise<Settings> {
     return await invoke("get_settings");
   },
 
-  async save(settings: Settings): Promise<boolean> {
+  async save(settings: Settings): Promise<SaveSettingsResult> {
     return await invoke("save_settings", { settings });
   },
 
+  async getStatus(): Promise<StatusReport> {
+    return await invoke("get_status");
+  },
+
+  async testWebhook(webhook: WebhookConfig): Promise<void> {
+    await invoke("test_webhook", { webhook });
+  },
+
   async restart(): Promise<boolean> {
     return await invoke("restart_app");
   },
@@ -93,6 +197,22 @@ export const settingsApi = {
     return await invoke("import_config_from_file", { filePath });
   },
 
+  async exportBundle(filePath: string): Promise<BundleManifest> {
+    return await invoke("export_bundle", { filePath });
+  },
+
+  async importBundle(filePath: string): Promise<BundleImportResult> {
+    return await invoke("import_bundle", { filePath });
+  },
+
+  async migrateLegacyConfig(dryRun = false): Promise<LegacyMigrationReport> {
+    return await invoke("migrate_legacy_config", { dryRun });
+  },
+
+  async undoLastOperation(): Promise<UndoSummary | null> {
+    return await invoke("undo_last_operation");
+  },
+
   async syncCurrentProvidersLive(): Promise<void> {
     const result = (await invoke("sync_current_providers_live")) as {
       success?: boolean;
@@ -142,6 +262,14 @@ export const settingsApi = {
   async setRectifierConfig(config: RectifierConfig): Promise<boolean> {
     return await invoke("set_rectifier_config", { config });
   },
+
+  async listDatabaseProfiles(): Promise<DatabaseProfile[]> {
+    return await invoke("list_database_profiles");
+  },
+
+  async switchProfile(name: string): Promise<DatabaseProfile[]> {
+    return await invoke("switch_profile", { name });
+  },
 };
 
 export interface RectifierConfig {
@@ -9,8 +9,16 @@ import type {
   ModelPricing,
   ProviderLimitStatus,
   PaginatedLogs,
+  ProviderUsage,
+  SessionUsageRange,
+  CostReport,
+  CostPeriod,
+  UsageDashboard,
+  SessionSummary,
+  UsageReportFormat,
+  SwitchStats,
 } from "@/types/usage";
-import type { UsageResult } from "@/types";
+import type { ProviderBalance, UsageResult } from "@/types";
 import type { AppId } from "./types";
 
 export const usageApi = {
@@ -43,6 +51,13 @@ export const usageApi = {
     });
   },
 
+  getBalance: async (
+    providerId: string,
+    appId: AppId,
+  ): Promise<ProviderBalance> => {
+    return invoke("get_provider_balance", { app: appId, id: providerId });
+  },
+
   // Proxy usage statistics methods
   getUsageSummary: async (
     startDate?: number,
@@ -114,4 +129,48 @@ export const usageApi = {
   ): Promise<ProviderLimitStatus> => {
     return invoke("check_provider_limits", { providerId, appType });
   },
+
+  // Session log usage (Claude Code / Codex)
+  getUsage: async (
+    providerId: string,
+    range?: SessionUsageRange,
+  ): Promise<ProviderUsage> => {
+    return invoke("get_usage", { providerId, range });
+  },
+
+  getCostReport: async (
+    range?: SessionUsageRange,
+    period?: CostPeriod,
+  ): Promise<CostReport> => {
+    return invoke("get_cost_report", { range, period });
+  },
+
+  getDashboard: async (
+    range?: SessionUsageRange,
+    refresh?: boolean,
+  ): Promise<UsageDashboard> => {
+    return invoke("get_usage_dashboard", { range, refresh });
+  },
+
+  listSessions: async (
+    appType?: AppId,
+    limit?: number,
+  ): Promise<SessionSummary[]> => {
+    return invoke("list_sessions", { appType, limit });
+  },
+
+  exportReport: async (
+    range?: SessionUsageRange,
+    format?: UsageReportFormat,
+    filePath?: string,
+  ): Promise<string> => {
+    return invoke("export_usage_report", { range, format, filePath });
+  },
+
+  getSwitchStats: async (
+    appType?: AppId,
+    days?: number,
+  ): Promise<SwitchStats> => {
+    return invoke("get_switch_stats", { appType, days });
+  },
 };
@@ -209,13 +209,35 @@ export const useSwitchProviderMutation = (appId: AppId) => {
 
 export const useSaveSettingsMutation = () => {
   const queryClient = useQueryClient();
+  const { t } = useTranslation();
 
   return useMutation({
     mutationFn: async (settings: Settings) => {
-      await settingsApi.save(settings);
+      return await settingsApi.save(settings);
     },
-    onSuccess: async () => {
+    onSuccess: async (result) => {
       await queryClient.invalidateQueries({ queryKey: ["settings"] });
+
+      // 目录覆盖的校验结果不阻止保存，只提示用户
+      if (result.warnings.length > 0) {
+        toast.warning(
+          t("settings.overrideWarnings", {
+            defaultValue: "设置已保存，但目录覆盖存在问题",
+          }),
+          {
+            description: result.warnings
+              .map((warning) =>
+                warning.path
+                  ? `${warning.path}: ${warning.message}`
+                  : warning.message,
+              )
+              .join("\n"),
+            classNames: { description: "whitespace-pre-line" },
+            duration: 8000,
+            closeButton: true,
+          },
+        );
+      }
     },
   });
 };
@@ -13,6 +13,8 @@ import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
 import { message } from "@tauri-apps/plugin-dialog";
 import { exit } from "@tauri-apps/plugin-process";
+import { AuxWindowView } from "@/components/AuxWindowView";
+import { auxWindowsApi } from "@/lib/api/auxWindows";
 
 // 根据平台添加 body class，便于平台特定样式
 try {
@@ -86,12 +88,15 @@ async function bootstrap() {
     console.error("拉取初始化错误失败", e);
   }
 
+  // 辅助窗口（MCP 管理、请求日志、用量统计）只渲染对应面板
+  const auxWindow = auxWindowsApi.current();
+
   ReactDOM.createRoot(document.getElementById("root")!).render(
     <React.StrictMode>
       <QueryClientProvider client={queryClient}>
         <ThemeProvider defaultTheme="system" storageKey="cc-switch-theme">
           <UpdateProvider>
-            <App />
+            {auxWindow ? <AuxWindowView kind={auxWindow} /> : <App />}
             <Toaster />
           </UpdateProvider>
         </ThemeProvider>
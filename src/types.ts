@@ -1,3 +1,5 @@
+import type { AppId } from "@/lib/api/types";
+
 export type ProviderCategory =
   | "official" // 官方
   | "cn_official" // 开源官方（原"国产官方"）
@@ -25,6 +27,8 @@ export interface Provider {
   iconColor?: string; // 图标颜色（Hex 格式，如 "#00A67E"）
   // 是否加入故障转移队列
   inFailoverQueue?: boolean;
+  // 受保护：解锁前不能删除，也不能修改密钥
+  locked?: boolean;
 }
 
 export interface AppConfig {
@@ -87,18 +91,85 @@ export interface UsageResult {
   error?: string;
 }
 
+// 中转站余额查询配置（one-api / new-api 等兼容接口）
+export interface BalanceQuery {
+  url: string; // 余额接口地址
+  token?: string; // 查询令牌，缺省使用供应商的 API Key
+}
+
+// 中转站余额
+export interface ProviderBalance {
+  remaining: number | null; // 剩余额度，不限额时为空
+  used: number | null;
+  total: number | null;
+  unit: string;
+  unlimited: boolean;
+  checkedAt: number; // Unix 时间戳（秒）
+}
+
 // 供应商元数据（字段名与后端一致，保持 snake_case）
 export interface ProviderMeta {
   // 自定义端点：以 URL 为键，值为端点信息
   custom_endpoints?: Record<string, CustomEndpoint>;
   // 用量查询脚本配置
   usage_script?: UsageScript;
+  // 中转站余额查询
+  balanceQuery?: BalanceQuery;
   // 请求地址管理：测速后自动选择最佳端点
   endpointAutoSelect?: boolean;
   // 是否为官方合作伙伴
   isPartner?: boolean;
   // 合作伙伴促销 key（用于后端识别 PackyCode 等）
   partnerPromotionKey?: string;
+  // 在快速切换面板中置顶
+  pinned?: boolean;
+}
+
+export type WebhookEvent = "providerSwitched" | "healthCheckFailed" | "failover";
+
+export type TrayMenuAction = "showMain" | "recent" | "pause" | "configDirs";
+
+// 托盘菜单自定义（「退出」始终显示）
+export interface TrayMenuConfig {
+  // 不在托盘菜单、提示文字与标题中出现的应用
+  hiddenApps?: AppId[];
+  hiddenActions?: TrayMenuAction[];
+  // 按应用隐藏的供应商 ID；当前供应商始终显示
+  hiddenProviders?: Partial<Record<AppId, string[]>>;
+}
+
+// 托盘自定义操作：运行脚本、打开配置目录、打开供应商网站（缺省为当前供应商）
+export type TrayQuickAction = { label: string } & (
+  | { type: "runScript"; command: string }
+  | { type: "openConfigDir"; app: AppId }
+  | { type: "openProviderDashboard"; app: AppId; providerId?: string }
+);
+
+export type NotificationEvent =
+  | "switchSucceeded"
+  | "switchFailed"
+  | "failover"
+  | "backup"
+  | "healthCheckFailed";
+
+export interface WebhookConfig {
+  url: string;
+  // generic：完整 JSON；slack / discord：只发送一句话摘要
+  format?: "generic" | "slack" | "discord";
+  // 订阅的事件，缺省为全部
+  events?: WebhookEvent[];
+  enabled?: boolean;
+}
+
+// 按速度自动选择供应商（单个应用）
+export interface AutoSelectConfig {
+  enabled?: boolean;
+  // 测速间隔（分钟），默认 30
+  intervalMinutes?: number;
+  // 候选供应商 ID，缺省为全部
+  candidates?: string[];
+  // 更快的供应商首字节耗时至少低出的百分比，默认 20
+  marginPercent?: number;
 }
 
 // 应用设置类型（用于设置对话框与 Tauri API）
@@ -109,16 +180,46 @@ export interface Settings {
   showInTray: boolean;
   // 点击关闭按钮时是否最小化到托盘而不是关闭应用
   minimizeToTrayOnClose: boolean;
+  // 在托盘图标旁（macOS 菜单栏、Linux）显示当前 Claude / Codex 供应商的简称，默认开启
+  showProviderInTrayTitle?: boolean;
+  // 托盘「最近使用」中每个应用显示的供应商数（0 表示不显示，默认 3）
+  trayRecentProviders?: number;
+  // 托盘菜单中隐藏的应用、操作与供应商
+  trayMenu?: TrayMenuConfig;
+  // 托盘菜单中的自定义操作（按顺序显示）
+  trayQuickActions?: TrayQuickAction[];
   // 启用 Claude 插件联动（写入 ~/.claude/config.json 的 primaryApiKey）
   enableClaudePluginIntegration?: boolean;
   // 跳过 Claude Code 初次安装确认（写入 ~/.claude.json 的 hasCompletedOnboarding）
   skipClaudeOnboarding?: boolean;
   // 是否开机自启
   launchOnStartup?: boolean;
+  // 启动时不显示主窗口，只驻留托盘
+  startMinimized?: boolean;
   // 首选语言（可选，默认中文）
   language?: "en" | "zh" | "ja";
+  // 以软链接方式写入 live 配置（指向 ~/.cc-switch/live/ 下的供应商文件）
+  liveConfigSymlink?: boolean;
+  // 切换供应商前备份 live 配置，支持撤销最近一次切换
+  switchSafetyBackup?: boolean;
+  // 复制密钥后自动清空剪贴板的秒数（0 表示不清空，默认 30）
+  clipboardClearSecs?: number;
+  // 查看/复制/导出 API Key 原文前要求系统身份验证（Touch ID / Windows Hello）
+  requireOsAuthForSecrets?: boolean;
+  // 只读模式：禁止切换、编辑、删除供应商（通过专门的命令加锁/解锁）
+  readOnlyMode?: boolean;
+  // 环境变量模式：切换时密钥只写入 ~/.cc-switch/env/<app>.sh|.ps1，不写入 live 配置
+  envOnlySwitching?: boolean;
+  // live 配置被外部改写（端点或密钥与当前供应商不一致）时：不处理 / 通知 / 自动重新应用
+  liveConfigGuard?: "off" | "notify" | "reapply";
+  // JSON 导出排除规则（.gitignore 风格的 JSON 路径模式，如 "*.apiKey"、"meta.notes"）
+  exportExcludeRules?: string[];
 
   // ===== 设备级目录覆盖 =====
+  // 是否启用下列目录覆盖（关闭后保留填写的目录，临时改用默认目录），默认开启
+  enableConfigDirOverrides?: boolean;
+  // 切换供应商时同时写入覆盖目录与默认目录（如 WSL 与 Windows 共用供应商）
+  syncProviderSwitchToBothConfigDirs?: boolean;
   // 覆盖 Claude Code 配置目录（可选）
   claudeConfigDir?: string;
   // 覆盖 Codex 配置目录（可选）
@@ -127,6 +228,78 @@ export interface Settings {
   geminiConfigDir?: string;
   // 覆盖 OpenCode 配置目录（可选）
   opencodeConfigDir?: string;
+  // 覆盖各应用 live 配置文件路径（文件名/相对路径基于对应配置目录，也可为绝对路径）
+  liveFileOverrides?: {
+    claudeSettings?: string;
+    codexAuth?: string;
+    codexConfig?: string;
+    geminiEnv?: string;
+    geminiSettings?: string;
+    opencodeConfig?: string;
+  };
+
+  // ===== 远程同步 =====
+  // WebDAV 数据库同步配置（可选）
+  webdavSync?: {
+    url: string;
+    username: string;
+    // 仅在修改密码时传入；保存后移入系统钥匙串，读取设置时不返回
+    password?: string;
+    remoteDir?: string;
+    // 同步时排除 API Key 等密钥（仅保存在本设备的系统钥匙串）
+    excludeSecrets?: boolean;
+    // 定时同步间隔（分钟），0 或未设置表示仅手动同步
+    autoSyncIntervalMinutes?: number;
+  };
+  // S3 兼容加密备份配置（访问密钥与口令保存在系统钥匙串）
+  s3Backup?: {
+    endpoint: string;
+    region?: string;
+    bucket: string;
+    prefix?: string;
+    pathStyle?: boolean;
+  };
+
+  // 定时自动备份（数据库 + live 配置，保存在 ~/.cc-switch/backups/auto）
+  autoBackup?: {
+    enabled: boolean;
+    intervalHours: number;
+    keepDaily: number;
+    keepWeekly: number;
+  };
+
+  // 本地自动化 HTTP API（仅监听 127.0.0.1，需 Bearer token；只能通过专门命令开关）
+  automationApi?: {
+    enabled: boolean;
+    port: number;
+  };
+
+  // 切换供应商、健康检查失败与故障转移时 POST 通知的 Webhook
+  webhooks?: WebhookConfig[];
+  // 按速度自动选择供应商，键为应用名（不适用于 OpenCode）
+  autoSelect?: Record<string, AutoSelectConfig>;
+  // 会话日志中限流 / 服务错误突增时告警（默认开启，5 分钟内 5 次）
+  errorBurst?: {
+    enabled: boolean;
+    threshold?: number;
+    windowMinutes?: number;
+  };
+  // 定期检查当前与故障转移队列中的供应商并记录可用性（默认关闭，5 分钟一次）
+  uptimeMonitor?: {
+    enabled: boolean;
+    intervalMinutes?: number;
+    retentionDays?: number;
+  };
+
+  // 系统通知：托盘 / 快速切换 / 自动化规则的切换结果、故障转移、自动备份与健康检查告警
+  notifications?: {
+    enabled: boolean;
+    // 发送通知的事件，缺省为全部
+    events?: NotificationEvent[];
+  };
+
+  // 当前的数据库配置档（为空表示默认配置档，通过 switchProfile 切换）
+  databaseProfile?: string;
 
   // ===== 当前供应商 ID（设备级）=====
   // 当前 Claude 供应商 ID（优先于数据库 is_current）
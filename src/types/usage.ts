@@ -105,6 +105,150 @@ export interface ProviderLimitStatus {
   monthlyExceeded: boolean;
 }
 
+// 会话日志用量（Claude Code / Codex 本地会话日志，按当时使用的供应商归属）
+export type SessionUsageRange = "today" | "week" | "month" | "all";
+
+export interface SessionUsageRow {
+  appType: string;
+  providerId: string;
+  date: string;
+  model: string;
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheCreationTokens: number;
+  messageCount: number;
+}
+
+export interface ProviderUsage {
+  providerId: string;
+  range: SessionUsageRange;
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheCreationTokens: number;
+  messageCount: number;
+  rows: SessionUsageRow[];
+}
+
+// 成本报告（按模型定价估算会话日志用量的成本）
+export type CostPeriod = "day" | "week" | "month";
+
+export interface PeriodCost {
+  period: string;
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheCreationTokens: number;
+  cost: string;
+}
+
+export interface ProviderCost {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  totalCost: string;
+  periods: PeriodCost[];
+}
+
+export interface CostReport {
+  range: SessionUsageRange;
+  period: CostPeriod;
+  totalCost: string;
+  providers: ProviderCost[];
+  unpricedModels: string[];
+}
+
+// 用量面板（会话日志用量与切换记录的聚合）
+export interface TokenTotals {
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheCreationTokens: number;
+  totalTokens: number;
+  messageCount: number;
+}
+
+export interface DailyAppTokens extends TokenTotals {
+  date: string;
+  appType: string;
+}
+
+export interface ProviderTokens extends TokenTotals {
+  appType: string;
+  providerId: string;
+  providerName: string;
+}
+
+export interface ModelTokens extends TokenTotals {
+  model: string;
+}
+
+export interface SwitchFrequency {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  count: number;
+}
+
+export interface UsageDashboard {
+  range: SessionUsageRange;
+  totals: TokenTotals;
+  daily: DailyAppTokens[];
+  providers: ProviderTokens[];
+  topModels: ModelTokens[];
+  switches: SwitchFrequency[];
+  generatedAt: number;
+}
+
+// 本地会话（会话日志浏览）
+export interface SessionProvider {
+  providerId: string;
+  providerName: string;
+  messageCount: number;
+}
+
+export interface SessionSummary {
+  appType: string;
+  sessionId: string;
+  title: string;
+  project: string | null;
+  path: string;
+  startedAt: number | null; // 毫秒时间戳
+  updatedAt: number | null;
+  inputTokens: number;
+  outputTokens: number;
+  cacheReadTokens: number;
+  cacheCreationTokens: number;
+  messageCount: number;
+  models: string[];
+  providers: SessionProvider[];
+}
+
+// 本地切换习惯统计
+export interface SwitchTransition {
+  appType: string;
+  fromProviderId: string;
+  fromProviderName: string;
+  toProviderId: string;
+  toProviderName: string;
+  count: number;
+}
+
+export interface SwitchStats {
+  totalSwitches: number;
+  firstSwitchAt: number | null; // 毫秒时间戳
+  lastSwitchAt: number | null;
+  perDay: number;
+  byHour: number[]; // 0-23 点
+  byWeekday: number[]; // 周一到周日
+  byApp: Record<string, number>;
+  transitions: SwitchTransition[];
+}
+
+// 用量报告导出（每月各供应商一行）
+export type UsageReportFormat = "csv" | "json";
+
 export type TimeRange = "1d" | "7d" | "30d";
 
 export interface StatsFilters {
@@ -153,7 +153,7 @@ export const handlers = [
   http.post(`${TAURI_ENDPOINT}/save_settings`, async ({ request }) => {
     const { settings } = await withJson<{ settings: Settings }>(request);
     setSettings(settings);
-    return success(true);
+    return success({ success: true, warnings: [] });
   }),
 
   http.post(